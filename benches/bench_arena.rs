@@ -0,0 +1,74 @@
+//! Benchmarks comparing concrete syntax trees allocated node by node with
+//! trees allocated in an arena.
+//!
+//! Run with `cargo bench --features bumpalo --bench bench_arena`.
+
+use bumpalo::Bump;
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, Criterion,
+};
+
+use wolfram_parser::{parse_cst_seq, parse_cst_seq_in, ParseOptions};
+
+fn large_input() -> String {
+    "f[x_, y_] := Module[{z = x + y}, If[z > 0, {z, -z}, <|\"a\" -> z^2|>]]\n"
+        .repeat(10_000)
+}
+
+fn benchmark(c: &mut Criterion) {
+    let input = large_input();
+    let opts = ParseOptions::default();
+
+    let mut group = c.benchmark_group("arena");
+    group.sample_size(20);
+
+    //------------------
+    // Parse, then drop
+    //------------------
+
+    group.bench_function("parse and drop CST", |b| {
+        b.iter(|| parse_cst_seq(black_box(&input), &opts).syntax.len())
+    });
+
+    let mut bump = Bump::new();
+
+    group.bench_function("parse and drop arena CST", |b| {
+        b.iter(|| {
+            // Reuse the memory of the previous tree.
+            bump.reset();
+
+            parse_cst_seq_in(black_box(&input), &opts, &bump)
+                .syntax
+                .len()
+        })
+    });
+
+    //------------
+    // Drop only
+    //------------
+
+    group.bench_function("drop CST", |b| {
+        b.iter_batched(
+            || parse_cst_seq(&input, &opts).syntax,
+            drop,
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("drop arena CST", |b| {
+        b.iter_batched(
+            || {
+                let bump = Bump::new();
+                parse_cst_seq_in(&input, &opts, &bump);
+                bump
+            },
+            drop,
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
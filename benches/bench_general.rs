@@ -20,6 +20,14 @@ fn parse_bytes(input: &[u8]) {
     wolfram_parser::parse_bytes_cst_seq(input, &ParseOptions::default());
 }
 
+fn parse_single(input: &str) {
+    wolfram_parser::parse_cst(input, &ParseOptions::default());
+}
+
+fn parse_simple(input: &str) {
+    wolfram_parser::parse_cst_simple(input, &ParseOptions::default());
+}
+
 fn benchmark(c: &mut Criterion) {
     c.bench_function("tokenize 2 + 2", |b| b.iter(|| tokenize("2 + 2")));
 
@@ -27,6 +35,24 @@ fn benchmark(c: &mut Criterion) {
     c.bench_function("tokenize Boxes.wl", |b| b.iter(|| tokenize(boxes_wl)));
     c.bench_function("parse CST of Boxes.wl", |b| b.iter(|| parse(boxes_wl)));
 
+    // A single expression with thousands of issues, to measure the cost of
+    // moving issues into the result.
+    let many_issues = format!("{{{}}}", "f[a,,b], 1.2.3, ".repeat(1_000));
+    c.bench_function("parse CST with many issues", |b| {
+        b.iter(|| parse_single(&many_issues))
+    });
+    c.bench_function("parse_cst_simple with many issues", |b| {
+        b.iter(|| parse_simple(&many_issues))
+    });
+
+    let encoded = wolfram_parser::paclet::encode_paclet(
+        &"f[x_, y_] := Module[{z = x + y}, z^2]\n".repeat(10_000),
+    )
+    .unwrap();
+    c.bench_function("decode paclet", |b| {
+        b.iter(|| wolfram_parser::paclet::decode_paclet(&encoded).unwrap())
+    });
+
     benchmark_large_files(c);
 }
 
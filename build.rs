@@ -0,0 +1,251 @@
+//! Generate the parselet tables from `src/generated/parselets.txt`.
+//!
+//! See that file for its format. The tables are written to
+//! `$OUT_DIR/prefix_parselets.rs` and `$OUT_DIR/infix_parselets.rs` as array
+//! expressions indexed by `TokenKind::id()`, and are included by
+//! `src/parse/token_parselets.rs`.
+
+use std::{collections::HashMap, env, fmt::Write, fs, path::Path};
+
+const TOKEN_KINDS: &str = "src/tokenize/token_kind.rs";
+const PARSELETS: &str = "src/generated/parselets.txt";
+
+/// The argument types of the parselets that take arguments, in order.
+///
+/// `Token` is not written in the data file: it is the token kind of the row,
+/// which `Group` and `Call` parselets need for finding their closer.
+const PARSELET_ARGS: &[(&str, &[&str])] = &[
+    ("PrefixOperator", &["Precedence", "PrefixOperator"]),
+    ("BinaryOperator", &["Precedence", "BinaryOperator"]),
+    ("InfixOperator", &["Precedence", "InfixOperator"]),
+    ("PostfixOperator", &["Precedence", "PostfixOperator"]),
+    ("Group", &["Token", "GroupOperator"]),
+    ("Call", &["Token", "GroupOperator"]),
+    ("Integral", &["PrefixBinaryOperator", "PrefixOperator"]),
+    ("Under", &["CompoundOperator", "CompoundOperator"]),
+    ("Equal", &[]),
+    ("ColonEqual", &[]),
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed={TOKEN_KINDS}");
+    println!("cargo:rerun-if-changed={PARSELETS}");
+
+    let token_kinds = read_token_kinds();
+
+    let text = fs::read_to_string(PARSELETS).expect("unable to read parselets");
+
+    let mut prefix = Table::new("prefix", token_kinds.len());
+    let mut infix = Table::new("infix", token_kinds.len());
+
+    for (index, line) in text.lines().enumerate() {
+        let line = match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        let [position, token, parselet, args @ ..] = fields.as_slice() else {
+            if !fields.is_empty() {
+                panic!(
+                    "{PARSELETS}:{}: expected a position, token kind, and \
+                     parselet",
+                    index + 1
+                );
+            }
+            continue;
+        };
+
+        let table = match *position {
+            "prefix" => &mut prefix,
+            "infix" => &mut infix,
+            _ => panic!(
+                "{PARSELETS}:{}: unknown position: {position}",
+                index + 1
+            ),
+        };
+
+        let expr = parselet_expr(token, parselet, args)
+            .unwrap_or_else(|err| panic!("{PARSELETS}:{}: {err}", index + 1));
+
+        let slot = if *token == "*" {
+            &mut table.default
+        } else {
+            let id = *token_kinds.get(*token).unwrap_or_else(|| {
+                panic!("{PARSELETS}:{}: unknown token kind: {token}", index + 1)
+            });
+            &mut table.parselets[id]
+        };
+
+        if slot.is_some() {
+            panic!(
+                "{PARSELETS}:{}: duplicate {position} parselet for {token}",
+                index + 1
+            );
+        }
+
+        *slot = Some(expr);
+    }
+
+    let mut names = vec![""; token_kinds.len()];
+    for (name, id) in &token_kinds {
+        names[*id] = name;
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+
+    for table in [prefix, infix] {
+        let path = Path::new(&out_dir)
+            .join(format!("{}_parselets.rs", table.position));
+
+        fs::write(path, table.generate(&names))
+            .expect("unable to write parselets");
+    }
+}
+
+/// The parselets of one position, indexed by token kind id.
+struct Table {
+    position: &'static str,
+    parselets: Vec<Option<String>>,
+    /// The parselet of token kinds without a row.
+    default: Option<String>,
+}
+
+impl Table {
+    fn new(position: &'static str, count: usize) -> Self {
+        Table {
+            position,
+            parselets: vec![None; count],
+            default: None,
+        }
+    }
+
+    fn generate(self, names: &[&str]) -> String {
+        let default = self.default.unwrap_or_else(|| {
+            panic!("{PARSELETS}: missing default {} parselet", self.position)
+        });
+
+        let mut out = String::from("[\n");
+
+        for (parselet, name) in self.parselets.iter().zip(names) {
+            let parselet = parselet.as_ref().unwrap_or(&default);
+
+            writeln!(out, "    // {name}\n    &{parselet},").unwrap();
+        }
+
+        out.push(']');
+        out
+    }
+}
+
+/// Read the token kind ids from the `token_kind!` invocation that defines
+/// `TokenKind`.
+fn read_token_kinds() -> HashMap<String, usize> {
+    let text =
+        fs::read_to_string(TOKEN_KINDS).expect("unable to read token kinds");
+
+    let start = text
+        .find("\ntoken_kind! {")
+        .expect("unable to find token_kind! invocation");
+
+    let mut token_kinds = HashMap::new();
+
+    for line in text[start..].lines().skip(2) {
+        let mut line = line.trim();
+
+        if line == "}" {
+            break;
+        }
+
+        if line.starts_with("//") || line.starts_with("#[") {
+            continue;
+        }
+
+        // Variants may be preceded by a /** .. */ doc comment on their line.
+        if line.starts_with("/**") {
+            line = match line.rfind("*/") {
+                Some(end) => &line[end + 2..],
+                None => continue,
+            };
+        }
+
+        if let Some((name, id)) = line.split_once('=') {
+            let id = id.split(',').next().unwrap().trim();
+            let id = id
+                .parse()
+                .unwrap_or_else(|_| panic!("invalid token kind id: {line}"));
+
+            token_kinds.insert(name.trim().to_owned(), id);
+        }
+    }
+
+    let mut ids: Vec<usize> = token_kinds.values().copied().collect();
+    ids.sort_unstable();
+    assert!(
+        ids.iter().copied().eq(0..ids.len()),
+        "token kind ids are not contiguous"
+    );
+
+    token_kinds
+}
+
+/// Get the Rust expression that constructs `parselet` for `token`.
+fn parselet_expr(
+    token: &str,
+    parselet: &str,
+    args: &[&str],
+) -> Result<String, String> {
+    let Some((_, types)) =
+        PARSELET_ARGS.iter().find(|(name, _)| *name == parselet)
+    else {
+        if !args.is_empty() {
+            return Err(format!("{parselet} takes no arguments"));
+        }
+
+        return Ok(format!("crate::parse::parselet::{parselet}Parselet {{}}"));
+    };
+
+    let mut args = args.iter();
+    let mut exprs = Vec::new();
+
+    for ty in *types {
+        let expr = match *ty {
+            "Token" => {
+                if token == "*" {
+                    return Err(format!("{parselet} needs a token kind"));
+                }
+                format!("crate::tokenize::TokenKind::{token}")
+            },
+            "Precedence" => {
+                let arg = args.next().ok_or("missing precedence")?;
+                format!("crate::precedence::Precedence::{arg}")
+            },
+            _ => {
+                let arg = args.next().ok_or_else(|| format!("missing {ty}"))?;
+                format!("crate::parse::operators::{ty}::{arg}")
+            },
+        };
+
+        exprs.push(expr);
+    }
+
+    if args.next().is_some() {
+        return Err(format!("too many arguments for {parselet}"));
+    }
+
+    let args = exprs.join(", ");
+
+    // A call parselet parses its arguments with the group parselet of its
+    // opener.
+    let expr = if parselet == "Call" {
+        format!(
+            "crate::parse::parselet::CallParselet::new(\
+             crate::parse::parselet::GroupParselet::new({args}))"
+        )
+    } else {
+        format!("crate::parse::parselet::{parselet}Parselet::new({args})")
+    };
+
+    Ok(expr)
+}
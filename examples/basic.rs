@@ -10,13 +10,13 @@ fn main() {
     // Parse to AST
     let ast_result = parse_ast(input, &opts);
     println!("AST:");
-    println!("{:#?}", ast_result.syntax);
+    print!("{}", ast_result.syntax.display_tree());
     println!();
     
     // Parse to CST
     let cst_result = parse_cst(input, &opts);
     println!("CST:");
-    println!("{:#?}", cst_result.syntax);
+    print!("{}", cst_result.syntax.display_tree());
     println!();
     
     // Tokenize
@@ -0,0 +1,36 @@
+//! Fuzz the paclet decoder with arbitrary input, and check that encoding and
+//! decoding round-trips.
+//!
+//! Run with `cargo fuzz run paclet`.
+
+#![no_main]
+
+use std::io::Read;
+
+use libfuzzer_sys::fuzz_target;
+use wolfram_parser::paclet::{self, DecodeReader};
+
+fuzz_target!(|data: &[u8]| {
+    // Decoding arbitrary input must not panic.
+    if let Ok(mut reader) = DecodeReader::new(data) {
+        let _ = reader.read_to_end(&mut Vec::new());
+    }
+
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let _ = paclet::maybe_decode_paclet(input);
+
+    // Decoding arbitrary input behind a valid header must not panic.
+    let _ = paclet::decode_paclet(&format!("(*!1N!*)mcm\n{input}"));
+
+    // Content that can be encoded must round-trip, with non-ASCII characters
+    // replaced by escapes.
+    if let Ok(encoded) = paclet::encode_paclet(input) {
+        assert_eq!(
+            paclet::decode_paclet(&encoded).unwrap(),
+            paclet::escape_non_ascii(input)
+        );
+    }
+});
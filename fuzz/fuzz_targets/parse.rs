@@ -0,0 +1,32 @@
+//! Fuzz the parser with arbitrary input, and check that parsing with
+//! `RecoveryMode::Recover` never panics.
+//!
+//! Run with `cargo fuzz run parse`.
+
+#![no_main]
+
+use std::sync::Once;
+
+use libfuzzer_sys::fuzz_target;
+use wolfram_parser::{
+    parse_bytes_cst, parse_bytes_cst_seq, parse_cst_simple, ParseOptions,
+    RecoveryMode,
+};
+
+static INIT: Once = Once::new();
+
+fuzz_target!(|data: &[u8]| {
+    // libfuzzer-sys aborts from its panic hook, before a panic can be
+    // recovered from. Panics that escape the parser are still reported as
+    // crashes when they unwind out of this function.
+    INIT.call_once(|| std::panic::set_hook(Box::new(|_| ())));
+
+    let opts = ParseOptions::default().recovery(RecoveryMode::Recover);
+
+    let _ = parse_bytes_cst_seq(data, &opts);
+    let _ = parse_bytes_cst(data, &opts);
+
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = parse_cst_simple(input, &opts);
+    }
+});
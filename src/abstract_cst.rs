@@ -1,13 +1,17 @@
 mod abstract_call_node;
+mod source_map;
 
 use std::fmt::Debug;
 
 use crate::{
     agg::{AggNodeSeq, LHS},
-    ast::{AbstractSyntaxError, Ast, AstCall, AstMetadata},
+    ast::{
+        AbstractSyntaxError, Ast, AstCall, AstMetadata, ComparisonChain,
+        ComparisonOperator, MissingCloser, UnhandledReason,
+    },
     cst::{
         BinaryNode, BoxKind, BoxNode, CallHead, CallNode, CodeNode,
-        CompoundNode, Cst, CstSeq, GroupMissingCloserNode,
+        CompoundNode, Cst, CstKind, CstSeq, GroupMissingCloserNode,
         GroupMissingOpenerNode, GroupNode, InfixNode, OperatorNode,
         PostfixNode, PrefixBinaryNode, PrefixNode, SyntaxErrorNode,
         TernaryNode,
@@ -20,12 +24,15 @@ use crate::{
             Operator, PostfixOperator, PrefixBinaryOperator, PrefixOperator,
             TernaryOperator,
         },
+        spec::OperatorPosition,
         SyntaxErrorKind,
     },
+    notation,
     quirks::{self, Quirk},
     symbol::Symbol,
     symbols as st,
     tokenize::{
+        token_kind::GroupOpenerToCloser,
         Token, TokenInput,
         TokenKind::{self, self as TK},
         TokenSource, TokenString,
@@ -34,6 +41,10 @@ use crate::{
     NodeSeq, QuirkSettings,
 };
 
+pub use self::source_map::{
+    abstract_cst_seq_with_map, abstract_cst_with_map, AbstractSourceMap,
+};
+
 //==========================================================
 // Aggregate
 //==========================================================
@@ -219,7 +230,7 @@ macro_rules! expect_children {
         let [$name1, _, $name2] = expect_children($children);
     };
 
-    ($children:ident, {$name:ident:_, LeafNode[$token_kind:ident, _, $data:ident:_]}) => {
+    ($children:ident, {$name:ident:_, LeafNode[$token_kind:ident, _, $data:ident:_]} else $unhandled:expr) => {
         let [$name, leaf] = expect_children($children);
 
         let $data = match leaf {
@@ -228,10 +239,10 @@ macro_rules! expect_children {
                 input: _,
                 src,
             }) => src,
-            _ => unhandled(),
+            _ => return $unhandled,
         };
     };
-    ($children:ident, {_, $name:ident:LeafNode[$token_kind:ident, _, _]}) => {
+    ($children:ident, {_, $name:ident:LeafNode[$token_kind:ident, _, _]} else $unhandled:expr) => {
         let [_, $name] = expect_children($children);
 
         if !matches!(
@@ -241,7 +252,7 @@ macro_rules! expect_children {
                 ..
             })
         ) {
-            unhandled()
+            return $unhandled;
         }
     };
 }
@@ -332,7 +343,7 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                 },
                 // CompoundNode[PatternOptionalDefault, {sym1_, LeafNode[Token`UnderDot, _, optionalDefaultData_]}, data_]
                 CompoundOperator::CodeParser_PatternOptionalDefault => {
-                    expect_children!(children, {sym1:_, LeafNode[UnderDot, _, optionalDefaultData:_]});
+                    expect_children!(children, {sym1:_, LeafNode[UnderDot, _, optionalDefaultData:_]} else unexpected_child(CstKind::Compound(op), data));
 
                     Ast::call(
                         st::Optional,
@@ -398,22 +409,22 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                                     data,
                                 )
                             },
-                            _ => unhandled(),
+                            _ => unexpected_child(CstKind::Compound(op), data),
                         },
-                        _ => unhandled(),
+                        _ => unexpected_child(CstKind::Compound(op), data),
                     }
                 },
 
                 // CompoundNode[SlotSequence, {_, arg:LeafNode[Integer, _, _]}, data_]
                 CompoundOperator::SlotSequence => {
-                    expect_children!(children, {_, arg:LeafNode[Integer, _, _]});
+                    expect_children!(children, {_, arg:LeafNode[Integer, _, _]} else unexpected_child(CstKind::Compound(op), data));
 
                     Ast::call(st::SlotSequence, vec![abstract_(arg)], data)
                 },
 
                 // CompoundNode[Out, {_, arg:LeafNode[Integer, _, _]}, data_]
                 CompoundOperator::Out => {
-                    expect_children!(children, {_, arg:LeafNode[Integer, _, _]});
+                    expect_children!(children, {_, arg:LeafNode[Integer, _, _]} else unexpected_child(CstKind::Compound(op), data));
 
                     Ast::call(st::Out, vec![abstract_(arg)], data)
                 },
@@ -424,6 +435,16 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
         // PrefixNode
         //============
         Cst::Prefix(PrefixNode(OperatorNode { op, children })) => match op {
+            // TID:261016/1: LegacyPrefixMinus quirk cases
+            //
+            // PrefixNode[Minus|Plus, {_, BinaryNode[Power, _, _]}, data_]
+            PrefixOperator::Minus | PrefixOperator::Plus
+                if quirks::is_quirk_enabled(Quirk::LegacyPrefixMinus)
+                    && isPowerNode(&children.0[1]) =>
+            {
+                abstractLegacyPrefixPower(op, children, data)
+            },
+
             // PrefixNode[Minus, {_, rand_}, data_]
             PrefixOperator::Minus => {
                 expect_children!(children, {_, rand:_});
@@ -469,7 +490,11 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                         vec![abstract_(rand.clone())],
                         data,
                     ),
-                    _ => unhandled(),
+                    _ => Ast::unhandled(
+                        CstKind::Prefix(op),
+                        UnhandledReason::UnexpectedChildCount,
+                        data,
+                    ),
                 }
             },
 
@@ -500,10 +525,23 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                         )],
                         data,
                     ),
-                    _ => unhandled(),
+                    _ => unexpected_child(CstKind::Prefix(op), data),
                 }
             },
 
+            // PrefixNode[PrefixNotation, {rator_, operand_}, data_]
+            PrefixOperator::CodeParser_PrefixNotation => {
+                let [rator, operand] = expect_children(children);
+
+                abstract_notation(
+                    OperatorPosition::Prefix,
+                    op,
+                    rator,
+                    vec![operand],
+                    data,
+                )
+            },
+
             // PrefixNode[op_, {_, operand_}, data_]
             op => {
                 let [_, operand] = expect_children(children);
@@ -533,26 +571,33 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                         // PostfixNode[Derivative, {rand_, LeafNode[Token`SingleQuote, _, _]}, _]
                         Cst::Token(Token {
                             tok: TK::SingleQuote,
-                            ..
+                            input: _,
+                            src,
                         }) => {
-                            let (order, abstractedBody) =
+                            let (order, abstractedBody, first_quote) =
                                 derivativeOrderAndAbstractedBody(operand);
 
+                            // Source of the complete run of quotes.
+                            let quotes = match first_quote {
+                                Some(first) => S::between(first, src),
+                                None => src,
+                            };
+
                             Ast::call2(
                                 Ast::call(
                                     st::Derivative,
                                     vec![Ast::usize(order + 1)],
-                                    AstMetadata::empty(),
+                                    quotes,
                                 ),
                                 vec![abstractedBody],
-                                AstMetadata::empty(),
+                                data,
                             )
                         },
                         // PostfixNode[Derivative, {rand_, LeafNode[Token`Boxes`MultiSingleQuote, quoteStr_, _]}, data_]
                         Cst::Token(Token {
                             tok: TK::Boxes_MultiSingleQuote,
                             input: quoteStr,
-                            ..
+                            src,
                         }) => {
                             let order = quoteStr.as_str().len();
 
@@ -560,13 +605,13 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                                 Ast::call(
                                     st::Derivative,
                                     vec![Ast::usize(order)],
-                                    AstMetadata::empty(),
+                                    src,
                                 ),
                                 vec![abstract_(operand)],
-                                AstMetadata::empty(),
+                                data,
                             )
                         },
-                        _ => unhandled(),
+                        _ => unexpected_child(CstKind::Postfix(op), data),
                     }
                 },
                 op => Ast::call(op.to_symbol(), vec![abstract_(operand)], data),
@@ -613,6 +658,15 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                     }
                 },
 
+                // BinaryNode[BinaryNotation, {left_, rator_, right_}, data_]
+                BinaryOperator::CodeParser_BinaryNotation => abstract_notation(
+                    OperatorPosition::Infix,
+                    op,
+                    middle,
+                    vec![left, right],
+                    data,
+                ),
+
                 BinaryOperator::CodeParser_BinarySlashSlash => Ast::call2(
                     // Make sure to reverse the arguments
                     abstract_(right),
@@ -627,7 +681,9 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                             ref input,
                             src: data1,
                         }) => (input.as_str(), data1),
-                        _ => unhandled(),
+                        _ => {
+                            return unexpected_child(CstKind::Binary(op), data)
+                        },
                     };
 
                     Ast::call(
@@ -654,13 +710,11 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                     if !matches!(
                         middle,
                         Cst::Token(Token { tok: TK::Equal, .. })
+                    ) || !matches!(
+                        right,
+                        Cst::Token(Token { tok: TK::Dot, .. })
                     ) {
-                        unhandled()
-                    }
-
-                    if !matches!(right, Cst::Token(Token { tok: TK::Dot, .. }))
-                    {
-                        unhandled()
+                        return unexpected_child(CstKind::Binary(op), data);
                     }
 
                     Ast::call(st::Unset, vec![abstract_(left)], data)
@@ -780,7 +834,10 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                                 tok: TK::Fake_ImplicitNull,
                                 input: _,
                                 src: data,
-                            }) => Ast::symbol_with_data(st::Null, data.clone()),
+                            }) => Ast::symbol_with_data(
+                                st::Null,
+                                AstMetadata::implicit(data.clone()),
+                            ),
                             node => abstract_(node),
                         })
                         .collect();
@@ -810,7 +867,11 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                 //   CallNode[ToNode[op], abstract /@ (processInfixBinaryAtQuirk[#, ToString[op]]& /@ children[[;;;;2]]), data]
                 _ => {
                     if !is_odd(children.len()) {
-                        unhandled()
+                        return Ast::unhandled(
+                            CstKind::Infix(op),
+                            UnhandledReason::UnexpectedChildCount,
+                            data,
+                        );
                     }
 
                     // TODO(robust): Add and use Operator::as_token_kind(), and
@@ -898,13 +959,11 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                     if !matches!(
                         middle_right,
                         Cst::Token(Token { tok: TK::Equal, .. })
+                    ) || !matches!(
+                        right,
+                        Cst::Token(Token { tok: TK::Dot, .. })
                     ) {
-                        unhandled()
-                    }
-
-                    if !matches!(right, Cst::Token(Token { tok: TK::Dot, .. }))
-                    {
-                        unhandled()
+                        return unexpected_child(CstKind::Ternary(op), data);
                     }
 
                     Ast::call(
@@ -1031,7 +1090,11 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                             vec![],
                             data,
                         ),
-                        _ => unhandled(),
+                        _ => Ast::unhandled(
+                            CstKind::Group(op),
+                            UnhandledReason::UnexpectedChildCount,
+                            data,
+                        ),
                     }
                 },
 
@@ -1069,7 +1132,11 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                             vec![],
                             data,
                         ),
-                        _ => unhandled(),
+                        _ => Ast::unhandled(
+                            CstKind::Group(op),
+                            UnhandledReason::UnexpectedChildCount,
+                            data,
+                        ),
                     }
                 },
 
@@ -1107,7 +1174,11 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                             vec![],
                             data,
                         ),
-                        _ => unhandled(),
+                        _ => Ast::unhandled(
+                            CstKind::Group(op),
+                            UnhandledReason::UnexpectedChildCount,
+                            data,
+                        ),
                     }
                 },
 
@@ -1123,12 +1194,13 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
         // GroupMissingCloserNode
         //==============================
         Cst::GroupMissingCloser(node) => {
-            let (op, abstracted_children, data) =
+            let (op, abstracted_children, closer, data) =
                 abstractGroupNode_GroupMissingCloserNode(node);
 
             Ast::GroupMissingCloser {
                 kind: op,
                 children: abstracted_children,
+                closer,
                 data,
             }
         },
@@ -1255,9 +1327,18 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
                         data,
                     )
                 },
-                _ => todo!(
-                "unhandled SyntaxErrorNode content: ({err:?}, {children:?})"
-            ),
+                // The tokens of unparsable input are not abstracted, since
+                // they may not form valid syntax.
+                (SyntaxErrorKind::Unparsable, _) => Ast::syntax_error(
+                    SyntaxErrorKind::Unparsable,
+                    vec![],
+                    data,
+                ),
+                _ => Ast::unhandled(
+                    CstKind::SyntaxError(err),
+                    UnhandledReason::UnsupportedSyntaxError,
+                    data,
+                ),
             }
         },
     };
@@ -1290,6 +1371,30 @@ fn abstract_<I: TokenInput + Debug, S: TokenSource + Debug>(
     ast_node
 }
 
+/// Abstract a user-defined notation node with operator token `rator`.
+///
+/// The head is the target symbol of the notation, or the `op` placeholder
+/// symbol if no notation for `rator` is active.
+fn abstract_notation<I: TokenInput + Debug, S: TokenSource + Debug>(
+    position: OperatorPosition,
+    op: impl Operator,
+    rator: Cst<I, S>,
+    operands: Vec<Cst<I, S>>,
+    data: S,
+) -> Ast {
+    let args = operands.into_iter().map(abstract_).collect();
+
+    let target = match rator {
+        Cst::Token(ref token) => notation::target_symbol(position, token),
+        _ => None,
+    };
+
+    match target {
+        Some(symbol) => Ast::call(symbol.as_symbol_ref(), args, data),
+        None => Ast::call(op.to_symbol(), args, data),
+    }
+}
+
 fn abstract_replace_token<I: TokenInput, S: TokenSource>(
     token: Token<I, S>,
 ) -> Ast {
@@ -1558,11 +1663,10 @@ Module[{replaced},
 //     }
 // }
 
-#[track_caller]
-fn unhandled() -> ! {
-    // TODO: Turn this into an Err(..) instead of a panic?
-    // Failure["Unhandled", ..]
-    todo!("unhandled case")
+/// Concrete node of kind `cst_kind` with a child that abstraction does not
+/// expect.
+fn unexpected_child<S: TokenSource>(cst_kind: CstKind, data: S) -> Ast {
+    Ast::unhandled(cst_kind, UnhandledReason::UnexpectedChild, data)
 }
 
 //--------------------------------------
@@ -1724,6 +1828,45 @@ impl<I: TokenInput + Debug, S: TokenSource + Debug> Negated<I, S> {
     }
 }
 
+fn isPowerNode<I, S>(node: &Cst<I, S>) -> bool {
+    matches!(
+        node,
+        Cst::Binary(BinaryNode(OperatorNode {
+            op: BinaryOperator::Power,
+            ..
+        }))
+    )
+}
+
+/// Abstract `-a^b` as `(-a)^b`, and `+a^b` as `(+a)^b`.
+///
+/// Used when the "LegacyPrefixMinus" quirk is enabled.
+fn abstractLegacyPrefixPower<I: TokenInput + Debug, S: TokenSource + Debug>(
+    op: PrefixOperator,
+    children: AggNodeSeq<I, S>,
+    data: S,
+) -> Ast {
+    let [rator, rand] = expect_children(children);
+
+    let Cst::Binary(BinaryNode(OperatorNode {
+        op: BinaryOperator::Power,
+        children: power_children,
+    })) = rand
+    else {
+        panic!("abstractLegacyPrefixPower: operand is not a Power: {rand:?}")
+    };
+
+    let [base, _, exponent] = expect_children(power_children);
+
+    // Apply the prefix operator to only the base of the power.
+    let base = Cst::Prefix(PrefixNode(OperatorNode {
+        op,
+        children: NodeSeq(vec![rator, base]),
+    }));
+
+    Ast::call(st::Power, vec![abstract_(base), abstract_(exponent)], data)
+}
+
 // concrete syntax does not have negated numbers
 // abstract syntax is allowed to have negated numbers
 //
@@ -1844,33 +1987,39 @@ impl<I: TokenInput + Debug, S: TokenSource + Debug> Reciprocate<I, S> {
 //======================================
 
 /// Collect all of the `'` in `f'''[x]`
+///
+/// Also returns the source of the first `'`, if there is one.
 fn derivativeOrderAndAbstractedBody<
     I: TokenInput + Debug,
     S: TokenSource + Debug,
 >(
     node: Cst<I, S>,
-) -> (usize, Ast) {
+) -> (usize, Ast, Option<S>) {
     match node {
         Cst::Postfix(PostfixNode(OperatorNode {
             op: PostfixOperator::Derivative,
             children,
         })) => {
-            let [rand, _] = expect_children(children);
+            let [rand, rator] = expect_children(children);
+
+            let (order, body, first_quote) =
+                derivativeOrderAndAbstractedBody(rand);
 
-            let (order, body) = derivativeOrderAndAbstractedBody(rand);
+            let first_quote = first_quote.unwrap_or_else(|| rator.source());
 
-            (order + 1, body)
+            (order + 1, body, Some(first_quote))
         },
-        _ => (0, abstract_(node)),
+        _ => (0, abstract_(node), None),
     }
 }
 
 //======================================
 
+/// Returns `None` if the first element of `pair` is not a `+` or `-` token.
 fn processPlusPair<I: TokenInput + Debug, S: TokenSource + Debug>(
     pair: [Cst<I, S>; 2],
-) -> Operand<I, S> {
-    match pair {
+) -> Option<Operand<I, S>> {
+    let operand = match pair {
         // {LeafNode[Token`Plus | Token`LongName`ImplicitPlus, _, _], rand_}
         [Cst::Token(Token {
             tok: TK::Plus | TK::LongName_ImplicitPlus,
@@ -1893,8 +2042,10 @@ fn processPlusPair<I: TokenInput + Debug, S: TokenSource + Debug>(
 
             Operand::Negated(negate(rand), source)
         },
-        _ => unhandled(),
-    }
+        _ => return None,
+    };
+
+    Some(operand)
 }
 
 // is it a quirk that  a + + b  is parsed as  a + b  ?
@@ -1937,7 +2088,11 @@ fn abstractPlus<I: TokenInput + Debug, S: TokenSource + Debug>(
         })
         .collect();
 
-    let processedPairs = pairs.into_iter().map(processPlusPair);
+    let Some(processedPairs): Option<Vec<_>> =
+        pairs.into_iter().map(processPlusPair).collect()
+    else {
+        return unexpected_child(CstKind::Infix(InfixOperator::Plus), data);
+    };
 
     let children = std::iter::once(Operand::Cst(children[0].clone()))
         .chain(processedPairs)
@@ -2246,6 +2401,8 @@ fn abstractMessageName<I: TokenInput + Debug, S: TokenSource + Debug>(
     let data = AstMetadata {
         source: data.into_general(),
         issues,
+        implicit: false,
+        comparisons: None,
     };
 
     let mut children = vec![abstract_(left)];
@@ -2297,6 +2454,20 @@ fn abstractInfixInequality<I: TokenInput + Debug, S: TokenSource + Debug>(
         })
         .collect();
 
+    let chain = ComparisonChain {
+        operands: std::iter::once(&children[0])
+            .chain(pairs.iter().map(|(_, rand)| rand))
+            .map(|rand| rand.source().into_general())
+            .collect(),
+        operators: pairs
+            .iter()
+            .map(|(rator, _)| ComparisonOperator {
+                kind: rator.tok,
+                source: rator.src.clone().into_general(),
+            })
+            .collect(),
+    };
+
     //
     // affinity is purposely not True nor False when starting
     //
@@ -2343,7 +2514,11 @@ fn abstractInfixInequality<I: TokenInput + Debug, S: TokenSource + Debug>(
         }
     }
 
-    simplifyInfixInequality(processed, affinity, data)
+    let mut ast = simplifyInfixInequality(processed, affinity, data);
+
+    ast.metadata_mut().comparisons = Some(Box::new(chain));
+
+    ast
 }
 
 fn simplifyInfixInequality<S: TokenSource>(
@@ -2788,9 +2963,18 @@ fn abstractGroupNode_GroupMissingCloserNode<
     O,
 >(
     group: GroupMissingCloserNode<I, S, O>,
-) -> (O, Vec<Ast>, AstMetadata) {
+) -> (O, Vec<Ast>, MissingCloser, AstMetadata) {
     let data = group.0.get_source();
 
+    let opener = group
+        .opener()
+        .expect("GroupMissingCloserNode does not start with an opener");
+
+    let closer = MissingCloser {
+        opener: opener.src.clone().into_general(),
+        expected: GroupOpenerToCloser(opener.tok).as_str(),
+    };
+
     let GroupMissingCloserNode(OperatorNode {
         op,
         children: NodeSeq(mut children),
@@ -2805,7 +2989,7 @@ fn abstractGroupNode_GroupMissingCloserNode<
         .flat_map(selectChildren)
         .collect();
 
-    (op, abstractedChildren, AstMetadata::from_src(data))
+    (op, abstractedChildren, closer, AstMetadata::from_src(data))
 }
 
 fn abstractGroupNode_GroupMissingOpenerNode<
@@ -2897,6 +3081,8 @@ fn abstractNot2<I: TokenInput + Debug, S: TokenSource + Debug>(
     let data = AstMetadata {
         source: data.into_general(),
         issues,
+        implicit: false,
+        comparisons: None,
     };
 
     Ast::call(
@@ -2915,23 +3101,24 @@ fn abstractNot2<I: TokenInput + Debug, S: TokenSource + Debug>(
 fn abstract_box_node<I: TokenInput + Debug, S: TokenSource + Debug>(
     box_node: BoxNode<I, S>,
 ) -> Ast {
-    // FIXME: Add test cases for and finish porting the todo!(..) cases below.
+    // FIXME: Add test cases for and finish porting the unhandled cases below.
     match box_node.kind {
         //
         // `a` is a List of boxes
         //
         // BoxNode[RowBox, {a_}, data_]
-        BoxKind::RowBox => {
-            todo!("RowBox[..] abstracting logic")
-            // BoxNode[RowBox, {abstract /@ a}, data]
-        },
+        //   => BoxNode[RowBox, {abstract /@ a}, data]
         //
         // `a` is a List of Lists
         //
         // BoxNode[GridBox, {a_, rest___}, data_]
-        BoxKind::GridBox => {
-            todo!("GridBox[..] abstracting logic");
-            // WL!(BoxNode[GridBox, {Map[abstract, a, {2}]} ~Join~ (abstract /@ {rest}), data])
+        //   => WL!(BoxNode[GridBox, {Map[abstract, a, {2}]} ~Join~ (abstract /@ {rest}), data])
+        BoxKind::RowBox | BoxKind::GridBox => {
+            return Ast::unhandled(
+                CstKind::Box(box_node.kind),
+                UnhandledReason::UnsupportedBox,
+                box_node.src,
+            );
         },
         _ => (),
     }
@@ -3190,7 +3377,7 @@ fn try_superscript_box_derivative_special_case<
 
     let (a, middle) = match children.0.as_slice() {
         [a, middle, ..] => (a.clone(), middle.clone()),
-        _ => todo!("Error?"),
+        _ => return Err(box_node),
     };
 
     let LHS!(BoxNode[
@@ -8,7 +8,7 @@ use crate::{
         CompoundNode, Cst, GroupNode, InfixNode, OperatorNode, PostfixNode,
         PrefixNode,
     },
-    issue::{Issue, IssueTag, Severity},
+    issue::{Annotation, Issue, IssueTag, Severity},
     parse::operators::{CallOperator, GroupOperator},
     symbols as st,
     tokenize::{
@@ -581,9 +581,12 @@ pub(super) fn abstract_call_node<
                     head.source().into_general(),
                     0.95,
                 )
-                .with_additional_descriptions(vec![
-                    "The head of ``::[]`` syntax is usually a string."
-                        .to_owned(),
+                .with_annotations(vec![
+                    Annotation::explanation(
+                        "The head of ``::[]`` syntax is usually a string."
+                            .to_owned(),
+                        head.source().into_general(),
+                    ),
                 ]),
             );
 
@@ -808,10 +811,10 @@ pub(super) fn abstract_call_node<
         ]) => {
             let head = abstract_(head);
 
-            let (_, children, _) =
+            let (_, children, closer, _) =
                 abstractGroupNode_GroupMissingCloserNode(part);
 
-            Ast::call_missing_closer(head, children, data)
+            Ast::call_missing_closer(head, children, closer, data)
         },
         LHS!(CallNode[
             head:_,
@@ -820,7 +823,7 @@ pub(super) fn abstract_call_node<
         ]) => {
             let head = abstract_(head);
 
-            let (_, children, _) =
+            let (_, children, closer, _) =
                 abstractGroupNode_GroupMissingCloserNode(part);
 
             /* TODO: Port this issue joining logic
@@ -838,6 +841,7 @@ pub(super) fn abstract_call_node<
             Ast::call_missing_closer(
                 Ast::call(st::TypeSpecifier, vec![head], AstMetadata::empty()),
                 children,
+                closer,
                 data,
             )
         },
@@ -848,7 +852,7 @@ pub(super) fn abstract_call_node<
         ]) => {
             let head = abstract_(head);
 
-            let (_, children, _) =
+            let (_, children, closer, _) =
                 abstractGroupNode_GroupMissingCloserNode(part);
 
             /* TODO: Port this issue joining logic
@@ -866,7 +870,12 @@ pub(super) fn abstract_call_node<
             // {head} ~Join~ part[[2]]
             let children = prepend(children, head);
 
-            Ast::call_missing_closer(Ast::symbol(st::Part), children, data)
+            Ast::call_missing_closer(
+                Ast::symbol(st::Part),
+                children,
+                closer,
+                data,
+            )
         },
     }
 }
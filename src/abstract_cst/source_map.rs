@@ -0,0 +1,168 @@
+//! Mapping abstract syntax back to the concrete syntax it was abstracted from.
+
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::{
+    ast::Ast,
+    cst::{Cst, CstSeq},
+    source::{Location, Source, Span},
+    tokenize::{TokenInput, TokenSource, TokenString},
+    NodeSeq, QuirkSettings,
+};
+
+use super::{abstract_cst, aggregate_cst, aggregate_cst_seq};
+
+/// The concrete syntax that an abstract syntax tree was abstracted from.
+///
+/// Returned by [`abstract_cst_with_map()`] and
+/// [`abstract_cst_seq_with_map()`]. Use [`AbstractSourceMap::cst()`] to get
+/// the concrete node an [`Ast`] node came from, including any comments and
+/// whitespace inside it:
+///
+/// ```
+/// use wolfram_parser::{
+///     abstract_cst::abstract_cst_seq_with_map, parse_cst_seq, ParseOptions,
+///     QuirkSettings,
+/// };
+///
+/// let input = "f[x, (* the second argument *) y + 1]";
+///
+/// let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+///
+/// let (ast, map) = abstract_cst_seq_with_map(cst, QuirkSettings::default());
+///
+/// let (_, [_, arg]) = ast.0[0].as_symbol_call().unwrap() else {
+///     panic!()
+/// };
+///
+/// assert_eq!(map.cst(arg).unwrap().to_source(), "y + 1");
+///
+/// // The call, including the comment in it.
+/// assert_eq!(map.cst(&ast.0[0]).unwrap().to_source(), input);
+/// ```
+///
+/// Abstract nodes that are not written in the input, like the `Plus` head of
+/// `y + 1`, have no concrete node. Only nodes with text spans are mapped, not
+/// nodes parsed from boxes.
+#[derive(Debug, Clone)]
+pub struct AbstractSourceMap<I = TokenString, S = Span> {
+    concrete: Vec<Cst<I, S>>,
+
+    /// The path from a node in `concrete` to the outermost node with each
+    /// span: the index of the top-level node, followed by child indices.
+    paths: HashMap<(Location, Location), Vec<usize>>,
+}
+
+//======================================
+// Abstraction
+//======================================
+
+/// Abstract `cst`, recording the concrete node each abstract node was
+/// created from.
+///
+/// Unlike [`abstract_cst()`], `cst` is concrete syntax as returned by
+/// [`parse_cst()`][crate::parse_cst], including trivia, and is aggregated
+/// before it is abstracted.
+///
+/// # Panics
+///
+/// Panics if `cst` is a trivia token, like whitespace or a comment, which has
+/// no abstract syntax.
+pub fn abstract_cst_with_map<I: TokenInput + Debug, S: TokenSource + Debug>(
+    cst: Cst<I, S>,
+    quirks: QuirkSettings,
+) -> (Ast, AbstractSourceMap<I, S>) {
+    let agg = aggregate_cst(cst.clone())
+        .expect("abstract_cst_with_map: cannot abstract a trivia token");
+
+    let ast = abstract_cst(agg, quirks);
+
+    (ast, AbstractSourceMap::new(vec![cst]))
+}
+
+/// Abstract the top-level nodes of `cst`, recording the concrete node each
+/// abstract node was created from.
+///
+/// `cst` is concrete syntax as returned by
+/// [`parse_cst_seq()`][crate::parse_cst_seq], including trivia. Top-level
+/// trivia is skipped, like it is by [`parse_ast_seq()`][crate::parse_ast_seq].
+pub fn abstract_cst_seq_with_map<
+    I: TokenInput + Debug,
+    S: TokenSource + Debug,
+>(
+    cst: CstSeq<I, S>,
+    quirks: QuirkSettings,
+) -> (NodeSeq<Ast>, AbstractSourceMap<I, S>) {
+    let NodeSeq(agg) = aggregate_cst_seq(cst.clone());
+
+    let ast = agg
+        .into_iter()
+        .map(|node| abstract_cst(node, quirks))
+        .collect();
+
+    let NodeSeq(concrete) = cst;
+
+    (NodeSeq(ast), AbstractSourceMap::new(concrete))
+}
+
+//======================================
+// Impls
+//======================================
+
+impl<I: TokenInput, S: TokenSource> AbstractSourceMap<I, S> {
+    fn new(concrete: Vec<Cst<I, S>>) -> Self {
+        let mut paths = HashMap::new();
+
+        for (index, node) in concrete.iter().enumerate() {
+            record_paths(node, &mut vec![index], &mut paths);
+        }
+
+        AbstractSourceMap { concrete, paths }
+    }
+
+    /// The concrete node that `ast` was abstracted from.
+    ///
+    /// If several nested concrete nodes have the same span as `ast`, the
+    /// outermost one is returned.
+    pub fn cst(&self, ast: &Ast) -> Option<&Cst<I, S>> {
+        let Source::Span(span) = ast.source() else {
+            return None;
+        };
+
+        let (first, rest) =
+            self.paths.get(&(span.start(), span.end()))?.split_first()?;
+
+        let mut node = &self.concrete[*first];
+
+        for index in rest {
+            node = node.children()[*index];
+        }
+
+        Some(node)
+    }
+
+    /// The concrete syntax that was abstracted, including trivia.
+    pub fn concrete(&self) -> &[Cst<I, S>] {
+        &self.concrete
+    }
+}
+
+/// Record the path to `node` and its descendants, unless a node with the
+/// same span has already been recorded.
+fn record_paths<I: TokenInput, S: TokenSource>(
+    node: &Cst<I, S>,
+    path: &mut Vec<usize>,
+    paths: &mut HashMap<(Location, Location), Vec<usize>>,
+) {
+    if let Source::Span(span) = node.get_source().into_general() {
+        paths
+            .entry((span.start(), span.end()))
+            .or_insert_with(|| path.clone());
+    }
+
+    for (index, child) in node.children().into_iter().enumerate() {
+        path.push(index);
+        record_paths(child, path, paths);
+        path.pop();
+    }
+}
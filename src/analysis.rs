@@ -0,0 +1,403 @@
+//! Data for editor features, computed in a single pass.
+//!
+//! A language server typically needs an outline, folding ranges, semantic
+//! highlighting, and diagnostics for a file after every edit.
+//! [`editor_data()`] computes all four in one traversal of the concrete
+//! syntax tree, instead of walking the same tree once per feature:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     analysis::{self, OutlineKind, SemanticTokenKind},
+//!     parse_cst_seq, ParseOptions,
+//! };
+//!
+//! let input = "square[x_] := x^2
+//!
+//! values = {
+//!     square[2],
+//!     square[3]
+//! }";
+//!
+//! let opts = ParseOptions::default();
+//! let cst = parse_cst_seq(input, &opts).syntax;
+//!
+//! let data = analysis::editor_data(&cst);
+//!
+//! let outline: Vec<_> = data
+//!     .outline
+//!     .iter()
+//!     .map(|item| (item.name.as_str(), item.kind))
+//!     .collect();
+//!
+//! assert_eq!(
+//!     outline,
+//!     [("square", OutlineKind::Function), ("values", OutlineKind::Variable)]
+//! );
+//!
+//! // The `{ .. }` list spans several lines.
+//! assert_eq!(data.folding_ranges.len(), 1);
+//!
+//! assert_eq!(data.semantic_tokens[0].kind, SemanticTokenKind::Function);
+//! assert_eq!(data.semantic_tokens[2].kind, SemanticTokenKind::Parameter);
+//!
+//! let cst = parse_cst_seq("f[a +", &opts).syntax;
+//!
+//! let data = analysis::editor_data(&cst);
+//!
+//! assert_eq!(data.diagnostics[0].msg, "Missing closer.");
+//! assert_eq!(data.diagnostics[1].msg, "Expected an operand.");
+//! ```
+//!
+//! Folding ranges are only computed for input parsed with
+//! [`SourceConvention::LineColumn`][crate::SourceConvention::LineColumn].
+//!
+//! The [`package`] module finds the structure of a package file, the
+//! [`scoping`] module finds the local variables in an abstract syntax tree,
+//! the [`control_flow`] module outlines the branches in each definition, and
+//! the [`whitespace`] module finds the whitespace that the parse depends on.
+
+pub mod control_flow;
+pub mod package;
+pub mod scoping;
+pub mod whitespace;
+
+use crate::{
+    cst::{
+        BinaryNode, CallHead, CallNode, CompoundNode, Cst, CstSeq,
+        GroupMissingCloserNode, GroupNode, InfixNode, OperatorNode,
+        TernaryNode,
+    },
+    issue::{
+        syntax_errors::{cst_error_message, error_issue},
+        Issue,
+    },
+    parse::operators::{
+        BinaryOperator, CompoundOperator, InfixOperator, TernaryOperator,
+    },
+    source::{Location, Source, Span},
+    tokenize::{Token, TokenInput, TokenKind},
+    NodeSeq,
+};
+
+/// The editor data for a file, as returned by [`editor_data()`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditorData {
+    /// Top-level definitions, in source order.
+    pub outline: Vec<OutlineItem>,
+
+    /// Multi-line groups and comments, in order of their start location.
+    pub folding_ranges: Vec<FoldingRange>,
+
+    /// Highlighting classes of the non-trivia tokens, in source order.
+    pub semantic_tokens: Vec<SemanticToken>,
+
+    /// Syntax errors, in source order.
+    ///
+    /// These are the same issues returned by
+    /// [`SyntaxErrors::syntax_errors()`][crate::issue::SyntaxErrors::syntax_errors].
+    pub diagnostics: Vec<Issue>,
+}
+
+/// A top-level definition, like `f[x_] := ..` or `x = ..`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineItem {
+    /// The name of the defined symbol, as it was written.
+    pub name: String,
+    pub kind: OutlineKind,
+
+    /// The span of the whole definition.
+    pub span: Span,
+
+    /// The span of the defined symbol.
+    pub name_span: Span,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutlineKind {
+    /// A definition with a left-hand side like `f[x_]`.
+    Function,
+    /// A definition with a left-hand side like `x`.
+    Variable,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FoldingRange {
+    pub span: Span,
+    pub kind: FoldingKind,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FoldingKind {
+    /// A group like `{ .. }` or `f[ .. ]`.
+    Region,
+    Comment,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SemanticToken {
+    pub span: Span,
+    pub kind: SemanticTokenKind,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+    Comment,
+    String,
+    /// An integer, real, or rational number.
+    Number,
+    /// A symbol not covered by a more specific kind.
+    Symbol,
+    /// The head symbol of a call, like `f` in `f[x]`.
+    Function,
+    /// The name of a pattern, like `x` in `x_` or `x : _`.
+    Parameter,
+    Operator,
+}
+
+/// How a token is used by its parent node.
+#[derive(Copy, Clone)]
+enum Role {
+    Other,
+    Head,
+    PatternName,
+}
+
+//======================================
+// Analysis
+//======================================
+
+/// Compute the outline, folding ranges, semantic tokens, and diagnostics of
+/// `cst` in a single traversal.
+///
+/// `cst` is a whole file, like the result of
+/// [`parse_cst_seq()`][crate::parse_cst_seq].
+pub fn editor_data<I: TokenInput>(cst: &CstSeq<I>) -> EditorData {
+    let NodeSeq(nodes) = cst;
+
+    let mut data = EditorData::default();
+
+    for node in nodes {
+        outline_statement(node, &mut data.outline);
+        data.node(node, Role::Other);
+    }
+
+    data
+}
+
+//======================================
+// Traversal
+//======================================
+
+impl EditorData {
+    fn node<I: TokenInput>(&mut self, node: &Cst<I>, role: Role) {
+        if let Some(msg) = cst_error_message(node) {
+            self.diagnostics
+                .push(error_issue(msg, Source::Span(node.get_source())));
+        }
+
+        match node {
+            Cst::Token(token) => self.token(token, role),
+            Cst::Call(CallNode { head, body }) => {
+                match head {
+                    CallHead::Concrete(NodeSeq(head)) => {
+                        for elem in head {
+                            self.node(elem, Role::Head);
+                        }
+                    },
+                    CallHead::Aggregate(head) => self.node(head, Role::Head),
+                }
+
+                let NodeSeq(children) = &body.as_op().children;
+
+                self.fold(body.get_source(), FoldingKind::Region);
+                self.children(children, None);
+            },
+            Cst::Compound(CompoundNode(OperatorNode { op, children })) => {
+                let first = match op {
+                    CompoundOperator::CodeParser_PatternBlank
+                    | CompoundOperator::CodeParser_PatternBlankSequence
+                    | CompoundOperator::CodeParser_PatternBlankNullSequence
+                    | CompoundOperator::CodeParser_PatternOptionalDefault => {
+                        Some(Role::PatternName)
+                    },
+                    _ => None,
+                };
+
+                self.children(&children.0, first);
+            },
+            Cst::Binary(BinaryNode(OperatorNode {
+                op: BinaryOperator::Pattern,
+                children,
+            })) => self.children(&children.0, Some(Role::PatternName)),
+            Cst::Group(GroupNode(op))
+            | Cst::GroupMissingCloser(GroupMissingCloserNode(op)) => {
+                self.fold(node.get_source(), FoldingKind::Region);
+                self.children(&op.children.0, None);
+            },
+            _ => {
+                for child in node.children() {
+                    self.node(child, Role::Other);
+                }
+            },
+        }
+    }
+
+    /// Visit `children`, using `first` as the role of the first child.
+    fn children<I: TokenInput>(
+        &mut self,
+        children: &[Cst<I>],
+        first: Option<Role>,
+    ) {
+        for (index, child) in children.iter().enumerate() {
+            let role = match first {
+                Some(role) if index == 0 => role,
+                _ => Role::Other,
+            };
+
+            self.node(child, role);
+        }
+    }
+
+    fn token<I>(&mut self, token: &Token<I>, role: Role) {
+        let kind = match token.tok {
+            TokenKind::Comment => {
+                self.fold(token.src, FoldingKind::Comment);
+                SemanticTokenKind::Comment
+            },
+            TokenKind::String => SemanticTokenKind::String,
+            TokenKind::Integer | TokenKind::Real | TokenKind::Rational => {
+                SemanticTokenKind::Number
+            },
+            TokenKind::Symbol => match role {
+                Role::Head => SemanticTokenKind::Function,
+                Role::PatternName => SemanticTokenKind::Parameter,
+                Role::Other => SemanticTokenKind::Symbol,
+            },
+            tok if tok.isTrivia() || tok.isEmpty() || tok.isError() => return,
+            _ => SemanticTokenKind::Operator,
+        };
+
+        self.semantic_tokens.push(SemanticToken {
+            span: token.src,
+            kind,
+        });
+    }
+
+    /// Add a folding range for `span` if it spans more than one line.
+    fn fold(&mut self, span: Span, kind: FoldingKind) {
+        let (Location::LineColumn(start), Location::LineColumn(end)) =
+            (span.start(), span.end())
+        else {
+            return;
+        };
+
+        if end.line() > start.line() {
+            self.folding_ranges.push(FoldingRange { span, kind });
+        }
+    }
+}
+
+//======================================
+// Outline
+//======================================
+
+/// Add an [`OutlineItem`] for each definition in the top-level `node`.
+fn outline_statement<I: TokenInput>(
+    node: &Cst<I>,
+    outline: &mut Vec<OutlineItem>,
+) {
+    let (lhs, kind) = match node {
+        // a; b; c
+        Cst::Infix(InfixNode(OperatorNode {
+            op: InfixOperator::CompoundExpression,
+            children,
+        })) => {
+            for child in &children.0 {
+                outline_statement(child, outline);
+            }
+            return;
+        },
+        // lhs = rhs, lhs := rhs
+        Cst::Binary(BinaryNode(OperatorNode {
+            op: BinaryOperator::Set | BinaryOperator::SetDelayed,
+            children,
+        })) => (&children.0[0], None),
+        // tag /: lhs = rhs
+        Cst::Ternary(TernaryNode(OperatorNode {
+            op: TernaryOperator::TagSet | TernaryOperator::TagSetDelayed,
+            children,
+        })) => (&children.0[0], Some(OutlineKind::Function)),
+        _ => return,
+    };
+
+    let Some((name, kind_of_lhs)) = defined_symbol(lhs) else {
+        return;
+    };
+
+    outline.push(OutlineItem {
+        name: name.input.as_str().to_owned(),
+        kind: kind.unwrap_or(kind_of_lhs),
+        span: node.get_source(),
+        name_span: name.src,
+    });
+}
+
+/// The symbol token defined by a definition with left-hand side `lhs`.
+fn defined_symbol<I: TokenInput>(
+    lhs: &Cst<I>,
+) -> Option<(&Token<I>, OutlineKind)> {
+    match lhs {
+        Cst::Token(token) if token.tok == TokenKind::Symbol => {
+            Some((token, OutlineKind::Variable))
+        },
+        // f[x_] /; cond := ..
+        Cst::Binary(BinaryNode(OperatorNode {
+            op: BinaryOperator::Condition,
+            children,
+        })) => defined_symbol(&children.0[0]),
+        Cst::Call(CallNode { head, body }) => {
+            let head = match head {
+                CallHead::Concrete(NodeSeq(head)) => head.first()?,
+                CallHead::Aggregate(head) => head,
+            };
+
+            let args = &body.as_op().children.0;
+
+            match head {
+                // HoldPattern[f[x_]] := ..
+                Cst::Token(token)
+                    if token.tok == TokenKind::Symbol
+                        && token.input.as_str() == "HoldPattern" =>
+                {
+                    args.iter()
+                        .find(|arg| !is_punctuation(arg))
+                        .and_then(defined_symbol)
+                },
+                Cst::Token(token) if token.tok == TokenKind::Symbol => {
+                    Some((token, OutlineKind::Function))
+                },
+                // f[a][b] := ..
+                Cst::Call(_) => {
+                    let (token, _) = defined_symbol(head)?;
+                    Some((token, OutlineKind::Function))
+                },
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Whether `node` is a bracket or trivia token in the body of a call.
+fn is_punctuation<I>(node: &Cst<I>) -> bool {
+    match node {
+        Cst::Token(token) => {
+            token.tok.isTrivia()
+                || matches!(
+                    token.tok,
+                    TokenKind::OpenSquare | TokenKind::CloseSquare
+                )
+        },
+        _ => false,
+    }
+}
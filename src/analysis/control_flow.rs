@@ -0,0 +1,342 @@
+//! A simplified outline of the control flow of each definition.
+//!
+//! [`control_flow()`] finds the branches (`If`, `Which`, and `Switch`) and
+//! early exits (`Return` and `Throw`) in the right-hand side of each
+//! top-level definition, without evaluating or otherwise analyzing the code:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     analysis::control_flow::{self, BranchKind, FlowNode},
+//!     parse_ast_seq, ParseOptions,
+//! };
+//!
+//! let input = "sign[x_] := Which[x > 0, 1, x < 0, -1, True, 0]
+//! abs[x_] := If[x < 0, Return[-x]; x, x]";
+//!
+//! let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+//!
+//! let defs = control_flow::control_flow(&ast);
+//!
+//! assert_eq!(defs[0].name.as_deref(), Some("sign"));
+//! assert_eq!(defs[0].complexity(), 4);
+//!
+//! let FlowNode::Branch(branch) = &defs[1].flow[0] else {
+//!     panic!()
+//! };
+//!
+//! assert_eq!(branch.kind, BranchKind::If);
+//! assert_eq!(branch.arms.len(), 2);
+//!
+//! // The `Return[-x]` in the first arm.
+//! assert!(matches!(branch.arms[0].flow[0], FlowNode::Exit(_)));
+//! ```
+//!
+//! The outline is lexical: calls to other functions are not followed, and an
+//! exit is reported wherever `Return` or `Throw` is written, even if it
+//! exits from something other than the definition, like a `Do` loop or an
+//! enclosing `Catch`.
+
+use crate::{
+    ast::Ast,
+    lint::{builtin_name, defined_symbol},
+    source::Source,
+    NodeSeq,
+};
+
+/// The control flow of a definition, as returned by [`control_flow()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefinitionFlow {
+    /// The symbol that is defined, like `f` in `f[x_] := ..` or `t` in
+    /// `t /: u[t] := ..`, if there is one.
+    pub name: Option<String>,
+
+    /// The source of the whole definition.
+    pub src: Source,
+
+    /// The branches and exits in the right-hand side, in source order.
+    pub flow: Vec<FlowNode>,
+}
+
+/// A branch or exit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowNode {
+    Branch(Branch),
+    Exit(Exit),
+}
+
+/// An `If`, `Which`, or `Switch` call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branch {
+    pub kind: BranchKind,
+
+    /// The source of the whole call.
+    pub src: Source,
+
+    /// The source of the expression a `Switch` matches against.
+    pub subject: Option<Source>,
+
+    /// The arms, in source order.
+    pub arms: Vec<Arm>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BranchKind {
+    /// `If[cond, t]`, `If[cond, t, f]`, or `If[cond, t, f, u]`
+    If,
+    /// `Which[cond1, v1, cond2, v2, ..]`
+    Which,
+    /// `Switch[expr, pattern1, v1, pattern2, v2, ..]`
+    Switch,
+}
+
+/// An arm of a [`Branch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arm {
+    /// When this arm is taken.
+    pub condition: ArmCondition,
+
+    /// The source of the value of this arm.
+    pub body: Source,
+
+    /// The branches and exits in the value of this arm, in source order.
+    pub flow: Vec<FlowNode>,
+}
+
+/// When an [`Arm`] is taken.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArmCondition {
+    /// The condition with this source is `True`: the first arm of an `If`,
+    /// or an arm of a `Which`.
+    True(Source),
+
+    /// The subject of a `Switch` matches the pattern with this source.
+    Matches(Source),
+
+    /// The condition of an `If` is `False`.
+    Else,
+
+    /// The condition of an `If` is neither `True` nor `False`.
+    Neither,
+}
+
+/// A `Return[..]` or `Throw[..]` call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exit {
+    pub kind: ExitKind,
+
+    /// The source of the whole call.
+    pub src: Source,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ExitKind {
+    Return,
+    Throw,
+}
+
+/// Find the control flow of each top-level definition in `ast`.
+///
+/// `ast` is a whole file, like the result of
+/// [`parse_ast_seq()`][crate::parse_ast_seq]. A definition is a `Set`,
+/// `SetDelayed`, `UpSet`, `UpSetDelayed`, `TagSet`, or `TagSetDelayed`
+/// statement, including those in a top-level `CompoundExpression`.
+///
+/// Branches and exits in a condition, or in the subject of a `Switch`, are
+/// listed before the branch they are in.
+pub fn control_flow(ast: &NodeSeq<Ast>) -> Vec<DefinitionFlow> {
+    let NodeSeq(nodes) = ast;
+
+    let mut defs = Vec::new();
+
+    for node in nodes {
+        definitions(node, &mut defs);
+    }
+
+    defs
+}
+
+//======================================
+// Impls
+//======================================
+
+impl DefinitionFlow {
+    /// The cyclomatic complexity of the definition: one more than the number
+    /// of arms with a condition or pattern.
+    ///
+    /// `If[c, t, f]` adds 1, and `Which` and `Switch` add 1 for each arm.
+    pub fn complexity(&self) -> usize {
+        1 + self.flow.iter().map(FlowNode::decisions).sum::<usize>()
+    }
+}
+
+impl FlowNode {
+    /// The number of arms with a condition or pattern in this node and the
+    /// nodes it contains.
+    fn decisions(&self) -> usize {
+        let FlowNode::Branch(branch) = self else {
+            return 0;
+        };
+
+        branch
+            .arms
+            .iter()
+            .map(|arm| {
+                let decision = match arm.condition {
+                    ArmCondition::True(_) | ArmCondition::Matches(_) => 1,
+                    ArmCondition::Else | ArmCondition::Neither => 0,
+                };
+
+                decision
+                    + arm.flow.iter().map(FlowNode::decisions).sum::<usize>()
+            })
+            .sum()
+    }
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// Add the definitions in the top-level statement `ast` to `defs`.
+fn definitions(ast: &Ast, defs: &mut Vec<DefinitionFlow>) {
+    let Some((head, args)) = ast.as_symbol_call() else {
+        return;
+    };
+
+    let (name, rhs) = match (builtin_name(head), args) {
+        (Some("CompoundExpression"), statements) => {
+            for statement in statements {
+                definitions(statement, defs);
+            }
+            return;
+        },
+        (Some("Set" | "SetDelayed" | "UpSet" | "UpSetDelayed"), [lhs, rhs]) => {
+            (defined_symbol(lhs), rhs)
+        },
+        (Some("TagSet" | "TagSetDelayed"), [tag, _, rhs]) => {
+            (tag.as_symbol(), rhs)
+        },
+        _ => return,
+    };
+
+    let mut flow = Vec::new();
+
+    visit(rhs, &mut flow);
+
+    defs.push(DefinitionFlow {
+        name: name.map(str::to_owned),
+        src: ast.source().clone(),
+        flow,
+    });
+}
+
+/// Add the branches and exits in `ast` to `flow`.
+fn visit(ast: &Ast, flow: &mut Vec<FlowNode>) {
+    if let Some((head, args)) = ast.as_symbol_call() {
+        match (builtin_name(head), args) {
+            (Some("If"), [cond, arms @ ..]) if !arms.is_empty() => {
+                visit(cond, flow);
+
+                let arms = arms
+                    .iter()
+                    .zip([
+                        ArmCondition::True(cond.source().clone()),
+                        ArmCondition::Else,
+                        ArmCondition::Neither,
+                    ])
+                    .map(|(body, condition)| arm(condition, body))
+                    .collect();
+
+                flow.push(branch(BranchKind::If, ast, None, arms));
+                return;
+            },
+            (Some("Which"), args) => {
+                let mut arms = Vec::new();
+
+                for pair in args.chunks(2) {
+                    let [cond, body] = pair else {
+                        // An odd number of arguments.
+                        visit(&pair[0], flow);
+                        continue;
+                    };
+
+                    visit(cond, flow);
+
+                    arms.push(arm(
+                        ArmCondition::True(cond.source().clone()),
+                        body,
+                    ));
+                }
+
+                flow.push(branch(BranchKind::Which, ast, None, arms));
+                return;
+            },
+            (Some("Switch"), [subject, cases @ ..]) => {
+                visit(subject, flow);
+
+                let mut arms = Vec::new();
+
+                for pair in cases.chunks(2) {
+                    let [pattern, body] = pair else {
+                        // An odd number of arguments.
+                        visit(&pair[0], flow);
+                        continue;
+                    };
+
+                    arms.push(arm(
+                        ArmCondition::Matches(pattern.source().clone()),
+                        body,
+                    ));
+                }
+
+                let subject = Some(subject.source().clone());
+
+                flow.push(branch(BranchKind::Switch, ast, subject, arms));
+                return;
+            },
+            (Some(name @ ("Return" | "Throw")), args) => {
+                args.iter().for_each(|arg| visit(arg, flow));
+
+                let kind = match name {
+                    "Return" => ExitKind::Return,
+                    _ => ExitKind::Throw,
+                };
+
+                flow.push(FlowNode::Exit(Exit {
+                    kind,
+                    src: ast.source().clone(),
+                }));
+                return;
+            },
+            _ => (),
+        }
+    }
+
+    ast.for_each_child(|child| visit(child, flow));
+}
+
+fn branch(
+    kind: BranchKind,
+    ast: &Ast,
+    subject: Option<Source>,
+    arms: Vec<Arm>,
+) -> FlowNode {
+    FlowNode::Branch(Branch {
+        kind,
+        src: ast.source().clone(),
+        subject,
+        arms,
+    })
+}
+
+fn arm(condition: ArmCondition, body: &Ast) -> Arm {
+    let mut flow = Vec::new();
+
+    visit(body, &mut flow);
+
+    Arm {
+        condition,
+        body: body.source().clone(),
+        flow,
+    }
+}
@@ -0,0 +1,270 @@
+//! The structure of a package file.
+//!
+//! [`package_outline()`] recognizes the `BeginPackage`, `Begin`, `End`, and
+//! `EndPackage` calls in a file, and the usage messages of the symbols the
+//! package exports:
+//!
+//! ```
+//! use wolfram_parser::{analysis::package, parse_ast_seq, ParseOptions};
+//!
+//! let input = r#"
+//! BeginPackage["Geometry`"]
+//!
+//! area::usage = "area[shape] gives the area of shape."
+//! perimeter
+//!
+//! Begin["`Private`"]
+//!
+//! area[Disk[_, r_]] := Pi r^2
+//!
+//! End[]
+//!
+//! EndPackage[]
+//! "#;
+//!
+//! let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+//!
+//! let outline = package::package_outline(&ast);
+//!
+//! assert_eq!(outline.context.as_deref(), Some("Geometry`"));
+//!
+//! let exports: Vec<_> = outline
+//!     .exported_symbols
+//!     .iter()
+//!     .map(|symbol| (symbol.name.as_str(), symbol.usage.as_deref()))
+//!     .collect();
+//!
+//! assert_eq!(
+//!     exports,
+//!     [
+//!         ("area", Some("area[shape] gives the area of shape.")),
+//!         ("perimeter", None),
+//!     ]
+//! );
+//!
+//! assert_eq!(outline.sections[0].context, "Geometry`Private`");
+//! assert!(outline.sections[0].end.is_some());
+//! ```
+
+use crate::{ast::Ast, lint::builtin_name, source::Source, NodeSeq};
+
+const GLOBAL_CONTEXT: &str = "Global`";
+
+/// The structure of a package file, as returned by [`package_outline()`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageOutline {
+    /// The context opened by the first ``BeginPackage["Ctx`"]``, if any.
+    pub context: Option<String>,
+
+    /// Symbols exported by the package, in source order.
+    ///
+    /// A symbol is exported if it is given a usage message, or written as a
+    /// statement by itself, between `BeginPackage[..]` and the first
+    /// `Begin[..]`.
+    pub exported_symbols: Vec<ExportedSymbol>,
+
+    /// `Begin[..]` sections, in source order.
+    pub sections: Vec<PackageSection>,
+}
+
+/// A symbol exported by a package.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedSymbol {
+    /// The symbol name, as written.
+    pub name: String,
+
+    /// The value of the usage message assigned with `name::usage = ".."`,
+    /// if any.
+    pub usage: Option<String>,
+
+    /// The source of the first statement that exports the symbol.
+    pub src: Source,
+}
+
+/// A section of a package between `Begin[..]` and `End[]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageSection {
+    /// The context of the section.
+    ///
+    /// A relative context, like ``"`Private`"``, is resolved against the
+    /// context that was current when the section began.
+    pub context: String,
+
+    /// The source of the `Begin[..]` call.
+    pub begin: Source,
+
+    /// The source of the matching `End[]` call, or `None` if the section is
+    /// never ended.
+    pub end: Option<Source>,
+}
+
+/// Find the package context, exported symbols, and sections of `ast`.
+///
+/// `ast` is a whole file, like the result of
+/// [`parse_ast_seq()`][crate::parse_ast_seq]. Only top-level statements,
+/// including those in a top-level `CompoundExpression`, are considered.
+/// Contexts must be string literals.
+pub fn package_outline(ast: &NodeSeq<Ast>) -> PackageOutline {
+    let NodeSeq(nodes) = ast;
+
+    let mut builder = OutlineBuilder {
+        outline: PackageOutline::default(),
+        context: GLOBAL_CONTEXT.to_owned(),
+        packages: Vec::new(),
+        open_sections: Vec::new(),
+    };
+
+    for node in nodes {
+        builder.statement(node);
+    }
+
+    builder.outline
+}
+
+//======================================
+// Helpers
+//======================================
+
+struct OutlineBuilder {
+    outline: PackageOutline,
+
+    /// The current context.
+    context: String,
+
+    /// The context that was current before each open `BeginPackage[..]`.
+    packages: Vec<String>,
+
+    /// The index of each open section, and the context that was current
+    /// before it began.
+    open_sections: Vec<(usize, String)>,
+}
+
+impl OutlineBuilder {
+    /// Whether statements are in the public part of a package.
+    fn is_public(&self) -> bool {
+        !self.packages.is_empty() && self.open_sections.is_empty()
+    }
+
+    fn statement(&mut self, ast: &Ast) {
+        if ast.is_implicit() {
+            return;
+        }
+
+        if let Some(name) = ast.as_symbol() {
+            if self.is_public() {
+                self.export(name, None, ast.source());
+            }
+            return;
+        }
+
+        let Some((head, args)) = ast.as_symbol_call() else {
+            return;
+        };
+
+        let first_string = args.first().and_then(Ast::as_string_value);
+
+        match (builtin_name(head), args, first_string) {
+            (Some("CompoundExpression"), statements, _) => {
+                for statement in statements {
+                    self.statement(statement);
+                }
+            },
+            (Some("BeginPackage"), _, Some(context)) => {
+                if self.outline.context.is_none() {
+                    self.outline.context = Some(context.clone());
+                }
+
+                let previous = std::mem::replace(&mut self.context, context);
+                self.packages.push(previous);
+            },
+            (Some("EndPackage"), [], _) => {
+                if let Some(previous) = self.packages.pop() {
+                    self.context = previous;
+                }
+            },
+            (Some("Begin"), [_], Some(context)) => {
+                let context = match context.strip_prefix('`') {
+                    Some(relative) => format!("{}{relative}", self.context),
+                    None => context,
+                };
+
+                self.outline.sections.push(PackageSection {
+                    context: context.clone(),
+                    begin: ast.source().clone(),
+                    end: None,
+                });
+
+                let previous = std::mem::replace(&mut self.context, context);
+                self.open_sections
+                    .push((self.outline.sections.len() - 1, previous));
+            },
+            (Some("End"), [], _) => {
+                if let Some((index, previous)) = self.open_sections.pop() {
+                    self.outline.sections[index].end =
+                        Some(ast.source().clone());
+                    self.context = previous;
+                }
+            },
+            // {f, g}
+            (Some("List"), elements, _) if self.is_public() => {
+                for element in elements {
+                    if let Some(name) = element.as_symbol() {
+                        self.export(name, None, element.source());
+                    }
+                }
+            },
+            // f::usage = ".."
+            (Some("Set" | "SetDelayed"), [lhs, rhs], _) => {
+                let Some(name) = usage_message_symbol(lhs) else {
+                    return;
+                };
+
+                let usage = rhs.as_joined_string_value();
+
+                if self.is_public() {
+                    self.export(name, usage, ast.source());
+                } else if let Some(symbol) = self.exported_mut(name) {
+                    // Usage messages are sometimes assigned in the private
+                    // part of a package.
+                    symbol.usage = symbol.usage.take().or(usage);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn export(&mut self, name: &str, usage: Option<String>, src: &Source) {
+        if let Some(symbol) = self.exported_mut(name) {
+            if usage.is_some() {
+                symbol.usage = usage;
+            }
+            return;
+        }
+
+        self.outline.exported_symbols.push(ExportedSymbol {
+            name: name.to_owned(),
+            usage,
+            src: src.clone(),
+        });
+    }
+
+    fn exported_mut(&mut self, name: &str) -> Option<&mut ExportedSymbol> {
+        self.outline
+            .exported_symbols
+            .iter_mut()
+            .find(|symbol| symbol.name == name)
+    }
+}
+
+/// The symbol `f` if `lhs` is `f::usage`.
+fn usage_message_symbol(lhs: &Ast) -> Option<&str> {
+    match lhs.as_symbol_call()? {
+        (head, [symbol, tag])
+            if builtin_name(head) == Some("MessageName")
+                && tag.as_string_value().as_deref() == Some("usage") =>
+        {
+            symbol.as_symbol()
+        },
+        _ => None,
+    }
+}
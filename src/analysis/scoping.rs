@@ -0,0 +1,400 @@
+//! Local variables and the scopes they are bound in.
+//!
+//! [`symbol_table()`] finds the variables bound by scoping constructs, and
+//! resolves each symbol that refers to one of them:
+//!
+//! * `Module`, `Block`, and `With`, like `Module[{x, y = 1}, ..]`
+//! * `Function` with named parameters, like `Function[{x, y}, ..]` or
+//!   `x |-> ..`
+//! * pattern variables, like `x` in `f[x_] := ..` or `{x_, y_} :> ..`, which
+//!   are bound in definitions (`Set`, `SetDelayed`, `UpSet`,
+//!   `UpSetDelayed`, `TagSet`, and `TagSetDelayed`) and rules (`Rule` and
+//!   `RuleDelayed`)
+//!
+//! ```
+//! use wolfram_parser::{analysis::scoping, parse_ast, ParseOptions};
+//!
+//! let input = "f[x_] := Module[{y = x, z}, y + 1]";
+//!
+//! let ast = parse_ast(input, &ParseOptions::default()).syntax;
+//!
+//! let table = scoping::symbol_table(&ast);
+//!
+//! let uses: Vec<_> = table
+//!     .variables
+//!     .iter()
+//!     .map(|var| (var.name.as_str(), var.usages.len()))
+//!     .collect();
+//!
+//! assert_eq!(uses, [("x", 1), ("y", 1), ("z", 0)]);
+//!
+//! let unused: Vec<_> = table.unused().map(|var| var.name.as_str()).collect();
+//!
+//! assert_eq!(unused, ["z"]);
+//! ```
+//!
+//! A symbol refers to the variable with the same name, as written, in the
+//! innermost enclosing scope. Initial values, like `x` in
+//! `Module[{y = x}, ..]`, are outside of the scope they initialize. The
+//! analysis is lexical: dynamic scoping by `Block`, and scoping constructs
+//! that aren't listed above, like the iterators of `Table`, are not
+//! modeled.
+
+use crate::{ast::Ast, lint::builtin_name, source::Source, NodeSeq};
+
+/// The scopes and local variables of some input, as returned by
+/// [`symbol_table()`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolTable {
+    /// Every scope, in the order they begin in the input.
+    pub scopes: Vec<Scope>,
+
+    /// Every local variable, in the order they are bound.
+    pub variables: Vec<Variable>,
+}
+
+/// A scoping construct that binds one or more variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    pub kind: ScopeKind,
+
+    /// The source of the whole construct, like `Module[..]` or
+    /// `f[x_] := ..`.
+    pub src: Source,
+
+    /// The index in [`SymbolTable::scopes`] of the innermost scope that
+    /// encloses this one.
+    pub parent: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ScopeKind {
+    /// `Module[{..}, ..]`
+    Module,
+    /// `Block[{..}, ..]`
+    Block,
+    /// `With[{..}, ..]`
+    With,
+    /// `Function[x, ..]`, `Function[{..}, ..]`, or `x |-> ..`
+    Function,
+    /// A definition or rule with pattern variables, like `f[x_] := ..` or
+    /// `x_ :> ..`.
+    Pattern,
+}
+
+/// A local variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable {
+    /// The symbol name, as written.
+    pub name: String,
+
+    /// The index in [`SymbolTable::scopes`] of the scope that binds this
+    /// variable.
+    pub scope: usize,
+
+    /// The source of the symbol that binds this variable: an element of a
+    /// variable list, a `Function` parameter, or the name of the first
+    /// pattern with this name.
+    pub definition: Source,
+
+    /// The source of every other occurrence of this variable, in source
+    /// order, including repeated patterns like the second `x_` in
+    /// `f[x_, x_]`.
+    pub usages: Vec<Source>,
+}
+
+/// Find the scopes and local variables in `ast`.
+pub fn symbol_table(ast: &Ast) -> SymbolTable {
+    let mut resolver = Resolver::default();
+
+    resolver.visit(ast);
+
+    resolver.table
+}
+
+/// Find the scopes and local variables in each of the expressions in `ast`.
+pub fn symbol_table_seq(ast: &NodeSeq<Ast>) -> SymbolTable {
+    let NodeSeq(nodes) = ast;
+
+    let mut resolver = Resolver::default();
+
+    for node in nodes {
+        resolver.visit(node);
+    }
+
+    resolver.table
+}
+
+//======================================
+// Impls
+//======================================
+
+impl SymbolTable {
+    /// The variable that the symbol with source `src` binds or refers to.
+    ///
+    /// Renaming a variable means replacing its
+    /// [`definition`][Variable::definition] and every one of its
+    /// [`usages`][Variable::usages].
+    pub fn variable_at(&self, src: &Source) -> Option<&Variable> {
+        self.variables
+            .iter()
+            .find(|var| var.definition == *src || var.usages.contains(src))
+    }
+
+    /// The variables bound by the scope at index `scope`.
+    pub fn variables_in(
+        &self,
+        scope: usize,
+    ) -> impl Iterator<Item = &Variable> + '_ {
+        self.variables.iter().filter(move |var| var.scope == scope)
+    }
+
+    /// The variables that are bound but never used.
+    pub fn unused(&self) -> impl Iterator<Item = &Variable> + '_ {
+        self.variables.iter().filter(|var| var.usages.is_empty())
+    }
+}
+
+//======================================
+// Resolution
+//======================================
+
+#[derive(Default)]
+struct Resolver {
+    table: SymbolTable,
+
+    /// The index of the innermost enclosing scope.
+    scope: Option<usize>,
+
+    /// The variables that are in scope, innermost last.
+    visible: Vec<usize>,
+}
+
+impl Resolver {
+    fn visit(&mut self, ast: &Ast) {
+        if let Some(name) = ast.as_symbol() {
+            if let Some(var) = self.lookup(name) {
+                self.table.variables[var].usages.push(ast.source().clone());
+            }
+            return;
+        }
+
+        if let Some((head, args)) = ast.as_symbol_call() {
+            match (builtin_name(head), args) {
+                (
+                    Some(name @ ("Module" | "Block" | "With")),
+                    [vars, body @ ..],
+                ) => {
+                    if let Some(vars) = list_elements(vars) {
+                        let kind = match name {
+                            "Module" => ScopeKind::Module,
+                            "Block" => ScopeKind::Block,
+                            _ => ScopeKind::With,
+                        };
+
+                        self.local_scope(kind, ast, vars, body);
+                        return;
+                    }
+                },
+                // Function[body] has slots, not named parameters.
+                (Some("Function"), [params, body @ ..]) if !body.is_empty() => {
+                    if let Some(params) = function_params(params) {
+                        self.function_scope(ast, &params, body);
+                        return;
+                    }
+                },
+                (
+                    Some(
+                        "Set" | "SetDelayed" | "UpSet" | "UpSetDelayed"
+                        | "Rule" | "RuleDelayed",
+                    ),
+                    [lhs, _],
+                )
+                | (Some("TagSet" | "TagSetDelayed"), [_, lhs, _]) => {
+                    self.pattern_scope(ast, lhs, args);
+                    return;
+                },
+                // A pattern, which is either the first pattern with this
+                // name, which binds the variable, or a repeated pattern.
+                (Some("Pattern"), [name, pattern]) => {
+                    self.pattern(name);
+                    self.visit(pattern);
+                    return;
+                },
+                _ => (),
+            }
+        }
+
+        ast.for_each_child(|child| self.visit(child));
+    }
+
+    /// The name of a pattern, like `x` in `x_`.
+    fn pattern(&mut self, name: &Ast) {
+        let Some(var) = name.as_symbol().and_then(|name| self.lookup(name))
+        else {
+            return;
+        };
+
+        let var = &mut self.table.variables[var];
+
+        if var.definition != *name.source() {
+            var.usages.push(name.source().clone());
+        }
+    }
+
+    /// `Module[{x, y = init}, body]`, and similarly for `Block` and `With`.
+    fn local_scope(
+        &mut self,
+        kind: ScopeKind,
+        ast: &Ast,
+        vars: &[Ast],
+        body: &[Ast],
+    ) {
+        let mut names = Vec::new();
+
+        for var in vars {
+            if var.as_symbol().is_some() {
+                names.push(var);
+                continue;
+            }
+
+            match var.as_symbol_call() {
+                Some((head, [name, init]))
+                    if matches!(
+                        builtin_name(head),
+                        Some("Set" | "SetDelayed")
+                    ) && name.as_symbol().is_some() =>
+                {
+                    // Initial values are evaluated outside of the scope.
+                    self.visit(init);
+                    names.push(name);
+                },
+                _ => self.visit(var),
+            }
+        }
+
+        self.with_scope(kind, ast, &names, |resolver| {
+            body.iter().for_each(|node| resolver.visit(node))
+        });
+    }
+
+    /// `Function[x, body]` or `Function[{x, y}, body]`.
+    fn function_scope(&mut self, ast: &Ast, params: &[&Ast], body: &[Ast]) {
+        self.with_scope(ScopeKind::Function, ast, params, |resolver| {
+            body.iter().for_each(|node| resolver.visit(node))
+        });
+    }
+
+    /// A definition or rule whose left-hand side is `lhs`.
+    ///
+    /// The pattern variables in `lhs` are bound in all of `args`, including
+    /// `lhs` itself, where a repeated pattern refers to the first one.
+    fn pattern_scope(&mut self, ast: &Ast, lhs: &Ast, args: &[Ast]) {
+        let mut names = Vec::new();
+
+        pattern_names(lhs, &mut names);
+
+        if names.is_empty() {
+            args.iter().for_each(|node| self.visit(node));
+            return;
+        }
+
+        self.with_scope(ScopeKind::Pattern, ast, &names, |resolver| {
+            args.iter().for_each(|node| resolver.visit(node))
+        });
+    }
+
+    /// Bind each of `names` in a new scope, and call `body` in that scope.
+    fn with_scope(
+        &mut self,
+        kind: ScopeKind,
+        ast: &Ast,
+        names: &[&Ast],
+        body: impl FnOnce(&mut Self),
+    ) {
+        let scope = self.table.scopes.len();
+
+        self.table.scopes.push(Scope {
+            kind,
+            src: ast.source().clone(),
+            parent: self.scope,
+        });
+
+        let parent = self.scope.replace(scope);
+        let visible = self.visible.len();
+
+        for name in names {
+            let Some(symbol) = name.as_symbol() else {
+                continue;
+            };
+
+            self.visible.push(self.table.variables.len());
+
+            self.table.variables.push(Variable {
+                name: symbol.to_owned(),
+                scope,
+                definition: name.source().clone(),
+                usages: Vec::new(),
+            });
+        }
+
+        body(self);
+
+        self.visible.truncate(visible);
+        self.scope = parent;
+    }
+
+    /// The variable that `name` refers to.
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.visible
+            .iter()
+            .rev()
+            .copied()
+            .find(|var| self.table.variables[*var].name == name)
+    }
+}
+
+/// The elements of `ast` if it is a list, like `{x, y}`.
+pub(crate) fn list_elements(ast: &Ast) -> Option<&[Ast]> {
+    match ast.as_symbol_call()? {
+        (head, elements) if builtin_name(head) == Some("List") => {
+            Some(elements)
+        },
+        _ => None,
+    }
+}
+
+/// The named parameters of a `Function`, if it has any.
+fn function_params(params: &Ast) -> Option<Vec<&Ast>> {
+    match params.as_symbol() {
+        // Function[Null, body, attrs] has slots, not named parameters.
+        Some(name) if builtin_name(name) == Some("Null") => None,
+        Some(_) => Some(vec![params]),
+        None => {
+            let params = list_elements(params)?;
+
+            Some(
+                params
+                    .iter()
+                    .filter(|param| param.as_symbol().is_some())
+                    .collect(),
+            )
+        },
+    }
+}
+
+/// Add the name of the first pattern with each name in `lhs` to `names`.
+pub(crate) fn pattern_names<'a>(lhs: &'a Ast, names: &mut Vec<&'a Ast>) {
+    if let Some((head, [name, _])) = lhs.as_symbol_call() {
+        if builtin_name(head) == Some("Pattern") {
+            if let Some(symbol) = name.as_symbol() {
+                if !names.iter().any(|other| other.as_symbol() == Some(symbol))
+                {
+                    names.push(name);
+                }
+            }
+        }
+    }
+
+    lhs.for_each_child(|child| pattern_names(child, names));
+}
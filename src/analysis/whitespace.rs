@@ -0,0 +1,426 @@
+//! Places where whitespace changes, or seems to change, how input is parsed.
+//!
+//! [`significant_whitespace()`] finds whitespace that the meaning of the
+//! input depends on, and spacing that suggests a different parse than the
+//! one the parser makes, with an explanation of each:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     analysis::whitespace::{self, WhitespaceEffect},
+//!     parse_cst_seq, ParseOptions,
+//! };
+//!
+//! let input = "area = w h
+//! {1 -2}
+//! x = .";
+//!
+//! let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+//!
+//! let effects: Vec<_> = whitespace::significant_whitespace(&cst)
+//!     .iter()
+//!     .map(|found| found.effect)
+//!     .collect();
+//!
+//! assert_eq!(
+//!     effects,
+//!     [
+//!         // `w h` is `w*h`, not the symbol `wh`.
+//!         WhitespaceEffect::ImplicitTimes,
+//!         // `{1 -2}` is `{1 - 2}`, not `{1, -2}`.
+//!         WhitespaceEffect::PrefixSpacing,
+//!         // `x = .` is `x =.`, which clears `x`.
+//!         WhitespaceEffect::UnsetSpacing,
+//!     ]
+//! );
+//! ```
+//!
+//! This is intended as a teaching and code review aid: none of these are
+//! errors, and most are written on purpose.
+
+use crate::{
+    cst::{
+        visit::{walk_cst, CstVisitor},
+        BinaryNode, Cst, CstSeq, InfixNode, OperatorNode, TernaryNode,
+    },
+    parse::operators::{BinaryOperator, InfixOperator, TernaryOperator},
+    source::Span,
+    tokenize::{Token, TokenInput, TokenKind},
+    NodeSeq, ParseOptions,
+};
+
+/// Whitespace that changes or seems to change the parse, as returned by
+/// [`significant_whitespace()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignificantWhitespace {
+    pub effect: WhitespaceEffect,
+
+    /// The span of the whitespace, or of the operator whose spacing is
+    /// misleading.
+    pub span: Span,
+
+    /// What the whitespace does, in terms of the input.
+    pub explanation: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WhitespaceEffect {
+    /// Whitespace between two operands that would be read as one token
+    /// without it, so the operands are multiplied, like `a b`, which is
+    /// `a*b` and not the symbol `ab`.
+    ///
+    /// Also a line break inside of brackets between two operands, like in
+    /// `{a` newline `(b)}`, which is `{a*b}`.
+    ImplicitTimes,
+
+    /// Whitespace between two tokens that would be read as one token
+    /// without it, like `- -` in `x - -y`, which would be `--` in `x--y`.
+    Separation,
+
+    /// A top-level newline before a line that starts with `+` or `-`, which
+    /// makes the line a separate expression. On one line, `a -b` is
+    /// `a - b`.
+    NewlineBreak,
+
+    /// A binary `+` or `-` with whitespace before but not after it, which
+    /// looks like a sign. `{1 -2}` is `{1 - 2}`, not `{1, -2}`.
+    PrefixSpacing,
+
+    /// Whitespace between the `=` and `.` of `=.`, like `x = .`, which is
+    /// still `Unset` and not an assignment.
+    UnsetSpacing,
+}
+
+/// Find the whitespace in `cst` that changes or seems to change the parse.
+///
+/// `cst` is a whole file, like the result of
+/// [`parse_cst_seq()`][crate::parse_cst_seq]. The results are sorted by
+/// their start location.
+pub fn significant_whitespace<I: TokenInput>(
+    cst: &CstSeq<I>,
+) -> Vec<SignificantWhitespace> {
+    let NodeSeq(nodes) = cst;
+
+    let mut finder = Finder { found: Vec::new() };
+
+    let mut tokens = Vec::new();
+
+    let mut previous: Option<&Cst<I>> = None;
+    let mut newline = false;
+
+    for node in nodes {
+        walk_cst(&mut finder, node);
+        collect_tokens(node, &mut tokens);
+
+        if let Cst::Token(token) = node {
+            if token.tok == TokenKind::ToplevelNewline {
+                newline = true;
+            }
+            if token.tok.isTrivia() {
+                continue;
+            }
+        }
+
+        if let Some(previous) = previous.filter(|_| newline) {
+            finder.newline_break(previous, node);
+        }
+
+        previous = Some(node);
+        newline = false;
+    }
+
+    finder.separations(&tokens);
+
+    let Finder { mut found } = finder;
+
+    found.sort_by(|a, b| {
+        a.span
+            .start()
+            .partial_cmp(&b.span.start())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    found
+}
+
+//======================================
+// Helpers
+//======================================
+
+struct Finder {
+    found: Vec<SignificantWhitespace>,
+}
+
+/// The trivia between two tokens.
+#[derive(Default)]
+struct Gap {
+    trivia: bool,
+    line_break: bool,
+    toplevel_newline: bool,
+
+    /// Whether the tokens are the operands of an implicit multiplication.
+    times: bool,
+}
+
+impl Finder {
+    fn push(
+        &mut self,
+        effect: WhitespaceEffect,
+        span: Span,
+        explanation: String,
+    ) {
+        self.found.push(SignificantWhitespace {
+            effect,
+            span,
+            explanation,
+        });
+    }
+
+    /// Find the whitespace between two of `tokens` that makes an implicit
+    /// multiplication or separates tokens.
+    fn separations<I: TokenInput>(&mut self, tokens: &[&Token<I>]) {
+        let mut left: Option<&Token<I>> = None;
+        let mut gap = Gap::default();
+
+        for &token in tokens {
+            match token.tok {
+                TokenKind::Fake_ImplicitTimes => gap.times = true,
+                // Handled by newline_break().
+                TokenKind::ToplevelNewline => gap.toplevel_newline = true,
+                TokenKind::InternalNewline => {
+                    gap.trivia = true;
+                    gap.line_break = true;
+                },
+                tok if tok.isTrivia() => gap.trivia = true,
+                // Other implicit tokens, like the `Null` after `a;`.
+                _ if token.input.as_bytes().is_empty() => (),
+                _ => {
+                    if let Some(left) = left {
+                        if gap.trivia && !gap.toplevel_newline {
+                            self.separation(left, token, &gap);
+                        }
+                    }
+
+                    left = Some(token);
+                    gap = Gap::default();
+                },
+            }
+        }
+    }
+
+    /// The trivia `gap` between `left` and `right`.
+    fn separation<I: TokenInput>(
+        &mut self,
+        left: &Token<I>,
+        right: &Token<I>,
+        gap: &Gap,
+    ) {
+        let (left_text, right_text) =
+            (left.input.as_str(), right.input.as_str());
+
+        let joined = format!("{left_text}{right_text}");
+
+        let NodeSeq(tokens) =
+            crate::tokenize(&joined, &ParseOptions::builtin_default());
+
+        let merged = tokens
+            .first()
+            .filter(|first| first.input.as_bytes().len() > left_text.len())
+            .map(|first| first.input.as_str());
+
+        let span = Span::new(left.src.end(), right.src.start());
+
+        match merged {
+            Some(merged) if gap.times => self.push(
+                WhitespaceEffect::ImplicitTimes,
+                span,
+                format!(
+                    "`{left_text}` and `{right_text}` are multiplied. \
+                     Without whitespace between them, `{merged}` would be \
+                     read as one token."
+                ),
+            ),
+            None if gap.times && gap.line_break => self.push(
+                WhitespaceEffect::ImplicitTimes,
+                span,
+                format!(
+                    "The expressions that end with `{left_text}` and start \
+                     with `{right_text}` are multiplied, across a line \
+                     break."
+                ),
+            ),
+            Some(merged) => self.push(
+                WhitespaceEffect::Separation,
+                span,
+                format!(
+                    "Whitespace separates `{left_text}` and `{right_text}`. \
+                     Without it, `{merged}` would be read as one token."
+                ),
+            ),
+            None => (),
+        }
+    }
+
+    /// The top-level expression `node` follows `previous` on a new line.
+    fn newline_break<I: TokenInput>(
+        &mut self,
+        previous: &Cst<I>,
+        node: &Cst<I>,
+    ) {
+        let Some(op) = first_token(node) else {
+            return;
+        };
+
+        if !is_sign(op.tok) {
+            return;
+        }
+
+        // `a;` followed by `-b` on the same line is `a; -b`.
+        if last_token(previous).is_some_and(|last| last.tok == TokenKind::Semi)
+        {
+            return;
+        }
+
+        let op = op.input.as_str();
+
+        self.push(
+            WhitespaceEffect::NewlineBreak,
+            Span::new(previous.get_source().end(), node.get_source().start()),
+            format!(
+                "The newline ends the previous expression, so this `{op}` \
+                 starts a new expression. On the same line, `{op}` would \
+                 continue the previous expression."
+            ),
+        );
+    }
+}
+
+impl<I: TokenInput> CstVisitor<I> for Finder {
+    fn visit_infix(&mut self, node: &InfixNode<I>) {
+        let InfixNode(OperatorNode {
+            op: InfixOperator::Plus,
+            children: NodeSeq(children),
+        }) = node
+        else {
+            return;
+        };
+
+        for window in children.windows(3) {
+            let [before, Cst::Token(op), after] = window else {
+                continue;
+            };
+
+            if is_sign(op.tok) && is_trivia(before) && !is_trivia(after) {
+                let op = op.input.as_str();
+
+                self.push(
+                    WhitespaceEffect::PrefixSpacing,
+                    window[1].get_source(),
+                    format!(
+                        "This `{op}` is a binary operator applied to the \
+                         expressions before and after it, even though the \
+                         spacing makes it look like a sign."
+                    ),
+                );
+            }
+        }
+    }
+
+    fn visit_binary(&mut self, node: &BinaryNode<I>) {
+        if let BinaryNode(OperatorNode {
+            op: BinaryOperator::Unset,
+            children: NodeSeq(children),
+        }) = node
+        {
+            self.unset(children);
+        }
+    }
+
+    fn visit_ternary(&mut self, node: &TernaryNode<I>) {
+        if let TernaryNode(OperatorNode {
+            op: TernaryOperator::TagUnset,
+            children: NodeSeq(children),
+        }) = node
+        {
+            self.unset(children);
+        }
+    }
+}
+
+impl Finder {
+    /// The children of an `Unset` or `TagUnset` node.
+    fn unset<I: TokenInput>(&mut self, children: &[Cst<I>]) {
+        let Some(equal) = children.iter().rposition(|child| {
+            matches!(child, Cst::Token(token) if token.tok == TokenKind::Equal)
+        }) else {
+            return;
+        };
+
+        let Some(dot) = children.last() else {
+            return;
+        };
+
+        if !children[equal + 1..].iter().any(is_trivia) {
+            return;
+        }
+
+        self.push(
+            WhitespaceEffect::UnsetSpacing,
+            Span::new(
+                children[equal].get_source().start(),
+                dot.get_source().end(),
+            ),
+            "`= .` is read as `=.`, so this clears the value of the \
+             left-hand side instead of assigning to it."
+                .to_owned(),
+        );
+    }
+}
+
+/// Whether `tok` can be either a sign or a binary operator.
+fn is_sign(tok: TokenKind) -> bool {
+    matches!(
+        tok,
+        TokenKind::Plus
+            | TokenKind::Minus
+            | TokenKind::LongName_PlusMinus
+            | TokenKind::LongName_MinusPlus
+    )
+}
+
+fn is_trivia<I>(node: &Cst<I>) -> bool {
+    matches!(node, Cst::Token(token) if token.tok.isTrivia())
+}
+
+/// Add every token in `node` to `tokens`, in source order.
+fn collect_tokens<'a, I>(node: &'a Cst<I>, tokens: &mut Vec<&'a Token<I>>) {
+    match node {
+        Cst::Token(token) => tokens.push(token),
+        _ => {
+            for child in node.children() {
+                collect_tokens(child, tokens);
+            }
+        },
+    }
+}
+
+/// The first token of `node` that is written in the input.
+fn first_token<I: TokenInput>(node: &Cst<I>) -> Option<&Token<I>> {
+    match node {
+        Cst::Token(token) => {
+            let written = !token.input.as_bytes().is_empty();
+            (written && !token.tok.isTrivia()).then_some(token)
+        },
+        _ => node.children().into_iter().find_map(first_token),
+    }
+}
+
+/// The last token of `node` that is written in the input.
+fn last_token<I: TokenInput>(node: &Cst<I>) -> Option<&Token<I>> {
+    match node {
+        Cst::Token(token) => {
+            let written = !token.input.as_bytes().is_empty();
+            (written && !token.tok.isTrivia()).then_some(token)
+        },
+        _ => node.children().into_iter().rev().find_map(last_token),
+    }
+}
@@ -1,9 +1,16 @@
 //! Abstract syntax trees.
 
-use std::fmt::Debug;
+pub mod expr;
+pub mod fold;
+pub mod visit;
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Debug,
+};
 
 use crate::{
-    cst::{BoxKind, CodeNode},
+    cst::{BoxKind, CodeNode, CstKind},
     issue::Issue,
     parse::{operators::GroupOperator, SyntaxErrorKind},
     source::{BoxPosition, LineColumnSpan, Source, Span},
@@ -12,6 +19,7 @@ use crate::{
 
 /// An abstract syntax tree (AST) node.
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Ast {
     /// `LeafNode[...]`
     Leaf {
@@ -35,6 +43,7 @@ pub enum Ast {
     CallMissingCloser {
         head: Box<Ast>,
         args: Vec<Ast>,
+        closer: MissingCloser,
         data: AstMetadata,
     },
     /// `SyntaxErrorNode[...]`
@@ -49,6 +58,21 @@ pub enum Ast {
         args: Vec<Ast>,
         data: AstMetadata,
     },
+    /// `Failure["Unhandled", ..]`
+    ///
+    /// Concrete syntax that abstraction does not support.
+    ///
+    /// Unlike [`Ast::Error`], [`Ast::SyntaxError`], and
+    /// [`Ast::AbstractSyntaxError`], which describe errors in the input, this
+    /// is a limitation of abstraction, and is not reported by
+    /// [`SyntaxErrors`][crate::issue::SyntaxErrors]. The concrete node is not
+    /// abstracted further, so this node has no children.
+    Unhandled {
+        /// The kind of the concrete node that could not be abstracted.
+        cst_kind: CstKind,
+        reason: UnhandledReason,
+        data: AstMetadata,
+    },
     Box {
         kind: BoxKind,
         args: Vec<Ast>,
@@ -56,7 +80,15 @@ pub enum Ast {
     },
     /// `CodeNode[_, _, _]`
     Code {
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_impls::expr")
+        )]
         first: Expr,
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_impls::expr")
+        )]
         second: Expr,
         data: AstMetadata,
     },
@@ -74,6 +106,7 @@ pub enum Ast {
     GroupMissingCloser {
         kind: GroupOperator,
         children: Vec<Ast>,
+        closer: MissingCloser,
         data: AstMetadata,
     },
     GroupMissingOpener {
@@ -92,18 +125,139 @@ pub enum Ast {
     /// `PrefixNode[PrefixLinearSyntaxBang, {operator_, operand_}, data_]`
     #[allow(non_camel_case_types)]
     PrefixNode_PrefixLinearSyntaxBang(Box<[Ast; 2]>, AstMetadata),
+    /// Placeholder for consecutive sibling subtrees removed by
+    /// [`Ast::truncate()`].
+    ///
+    /// The source of this node spans the removed subtrees.
+    Elided {
+        /// Number of sibling subtrees that were removed.
+        subtrees: usize,
+        /// Total number of nodes in the removed subtrees.
+        nodes: usize,
+        data: AstMetadata,
+    },
+}
+
+/// Recovery information for a group that is missing its closer, like `{1, 2`
+/// or `f[x`.
+//
+// Deserialize is implemented in serde_impls.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MissingCloser {
+    /// Source of the group opener, e.g. the `{` in `{1, 2`.
+    pub opener: Source,
+    /// The closer that was expected, e.g. `"}"`.
+    pub expected: &'static str,
+}
+
+/// A chain of calls and parts, like `f[x][y][[2]]`, as returned by
+/// [`Ast::call_chain()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallChain<'a> {
+    /// The expression the chain is applied to, e.g. `f` in `f[x][y][[2]]`.
+    pub root: &'a Ast,
+    /// Each application in the chain, innermost (leftmost) first.
+    pub links: Vec<ChainLink<'a>>,
+}
+
+/// One application in a [`CallChain`], like `[y]` or `[[2]]` in
+/// `f[x][y][[2]]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainLink<'a> {
+    pub kind: ChainLinkKind,
+    /// Arguments of a call, or indices of a part.
+    pub args: &'a [Ast],
+    /// Source of the chain up to and including this link, e.g. `f[x][y]` for
+    /// the `[y]` link of `f[x][y][[2]]`.
+    pub src: &'a Source,
+    /// Whether this link is missing its closer, like in `f[x`.
+    pub missing_closer: bool,
+}
+
+/// The components of a symbol name, like `` A`B`c ``, as returned by
+/// [`Ast::symbol_parts()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolParts<'a> {
+    /// Whether the context is relative to the current context, like in
+    /// `` `B`c ``.
+    pub relative: bool,
+    /// Each segment of the context, e.g. `["A", "B"]` for `` A`B`c ``.
+    ///
+    /// This is empty if the symbol has no context, like `c`.
+    pub context: Vec<&'a str>,
+    /// The name of the symbol without its context, e.g. `c` in `` A`B`c ``.
+    pub name: &'a str,
+}
+
+/// A derivative written with quotes, like `f''`, as returned by
+/// [`Ast::derivative()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Derivative<'a> {
+    /// Number of quotes, e.g. `2` for `f''`.
+    pub order: usize,
+    /// The expression being differentiated, e.g. `f` in `f''`.
+    pub body: &'a Ast,
+    /// Source of the run of quotes, e.g. the `''` in `f''`.
+    pub quotes: &'a Source,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ChainLinkKind {
+    /// `f[x]`
+    Call,
+    /// `f[[x]]`
+    Part,
 }
 
 // TODO(cleanup): Combine this with `Metadata`?
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AstMetadata {
     pub source: Source,
     pub issues: Vec<Issue>,
+    /// Whether this node was inserted by abstraction and has no text of its
+    /// own in the input, like the trailing `Null` in
+    /// `CompoundExpression[a, b, Null]` from `a; b;`.
+    ///
+    /// The [`source`][AstMetadata::source] of an implicit node is the empty
+    /// span where it was inserted.
+    pub implicit: bool,
+    /// The operators and operands of a chain of comparisons, like `a < b > c`,
+    /// if this node is the result of abstracting one.
+    ///
+    /// Abstraction simplifies chains, e.g. `a < b < c` becomes
+    /// `Less[a, b, c]`, so the original operators are not otherwise
+    /// available.
+    pub comparisons: Option<Box<ComparisonChain>>,
+}
+
+/// The operators and operands of a chain of comparisons, as recorded in
+/// [`AstMetadata::comparisons`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComparisonChain {
+    /// Source of each operand, in order.
+    pub operands: Vec<Source>,
+    /// Each comparison operator, in order.
+    ///
+    /// `operators[i]` compares `operands[i]` and `operands[i + 1]`.
+    pub operators: Vec<ComparisonOperator>,
+}
+
+/// A comparison operator token in a [`ComparisonChain`], like the `>` in
+/// `a < b > c`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComparisonOperator {
+    pub kind: TokenKind,
+    pub source: Source,
 }
 
 // TODO(cleanup): Add `Kind` suffix to this name? Or remove `Kind` suffix from
 //                other enums like this?
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AbstractSyntaxError {
     CommaTopLevel,
     OpenParen,
@@ -115,6 +269,25 @@ pub enum AbstractSyntaxError {
     ExpectedTilde,
 }
 
+/// Why a concrete node could not be abstracted, as recorded in an
+/// [`Ast::Unhandled`] node.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum UnhandledReason {
+    /// A child of the node is not of a kind that abstraction expects, like
+    /// an operand of `<<` that is not a string.
+    UnexpectedChild,
+    /// The node does not have a number of children that abstraction
+    /// expects.
+    UnexpectedChildCount,
+    /// Abstraction of this kind of [`Cst::SyntaxError`][crate::cst::Cst::SyntaxError]
+    /// node, or of these children of it, is not supported.
+    UnsupportedSyntaxError,
+    /// Abstraction of this kind of box is not supported.
+    UnsupportedBox,
+}
+
 //======================================
 
 pub(crate) struct AstCall {
@@ -130,7 +303,10 @@ pub(crate) struct AstCall {
 impl Ast {
     pub(crate) fn into_children_and_source(self) -> (Vec<Ast>, Source) {
         match self {
-            Ast::Leaf { .. } | Ast::Error { .. } => panic!(
+            Ast::Leaf { .. }
+            | Ast::Error { .. }
+            | Ast::Unhandled { .. }
+            | Ast::Elided { .. } => panic!(
                 "Ast::into_children_and_source(): Ast variant has no children: {self:?}"
             ),
             Ast::Call {
@@ -141,6 +317,7 @@ impl Ast {
             Ast::CallMissingCloser {
                 head: _,
                 args,
+                closer: _,
                 data,
             } => (args, data.source),
             Ast::SyntaxError {
@@ -160,8 +337,17 @@ impl Ast {
             } => (args, data.source),
             Ast::Code { .. } => todo!(),
             Ast::Group { .. } => todo!(),
-            Ast::GroupMissingCloser { .. } => todo!(),
-            Ast::GroupMissingOpener { .. } => todo!(),
+            Ast::GroupMissingCloser {
+                kind: _,
+                children,
+                closer: _,
+                data,
+            } => (children, data.source),
+            Ast::GroupMissingOpener {
+                kind: _,
+                children,
+                data,
+            } => (children, data.source),
             Ast::TagBox_GroupParen { .. } => todo!(),
             Ast::PrefixNode_PrefixLinearSyntaxBang(children, data) => {
                 (Vec::from(*children), data.source)
@@ -181,6 +367,49 @@ impl Ast {
         }
     }
 
+    /// Get the [`Source`] of this node.
+    ///
+    /// Unlike [`Ast::span()`], this does not panic for nodes that were
+    /// synthesized during abstraction and have no source location.
+    pub fn source(&self) -> &Source {
+        &self.metadata().source
+    }
+
+    /// Whether this node was inserted by abstraction, and does not correspond
+    /// to any text of the input.
+    ///
+    /// See [`AstMetadata::implicit`].
+    pub fn is_implicit(&self) -> bool {
+        self.metadata().implicit
+    }
+
+    /// Whether this node is an error in the input, like a missing closer or
+    /// operand.
+    ///
+    /// This is `false` for [`Ast::Unhandled`] nodes, which are syntax that
+    /// abstraction does not support, not errors in the input. Only this node
+    /// is checked, not its children.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_ast, ParseOptions};
+    ///
+    /// let opts = ParseOptions::default();
+    ///
+    /// assert!(parse_ast("f[x", &opts).syntax.is_syntax_error());
+    /// assert!(!parse_ast("f[x]", &opts).syntax.is_syntax_error());
+    /// ```
+    pub fn is_syntax_error(&self) -> bool {
+        matches!(
+            self,
+            Ast::Error { .. }
+                | Ast::CallMissingCloser { .. }
+                | Ast::SyntaxError { .. }
+                | Ast::AbstractSyntaxError { .. }
+                | Ast::GroupMissingCloser { .. }
+                | Ast::GroupMissingOpener { .. }
+        )
+    }
+
     pub(crate) fn metadata(&self) -> &AstMetadata {
         match self {
             Ast::Leaf { data, .. } | Ast::Error { data, .. } => data,
@@ -188,6 +417,26 @@ impl Ast {
             Ast::CallMissingCloser { data, .. } => data,
             Ast::SyntaxError { data, .. } => data,
             Ast::AbstractSyntaxError { data, .. } => data,
+            Ast::Unhandled { data, .. } => data,
+            Ast::Box { data, .. } => data,
+            Ast::Code { data, .. } => data,
+            Ast::Group { data, .. } => data,
+            Ast::GroupMissingCloser { data, .. } => data,
+            Ast::GroupMissingOpener { data, .. } => data,
+            Ast::TagBox_GroupParen { data, .. } => data,
+            Ast::PrefixNode_PrefixLinearSyntaxBang(_, data) => data,
+            Ast::Elided { data, .. } => data,
+        }
+    }
+
+    pub(crate) fn metadata_mut(&mut self) -> &mut AstMetadata {
+        match self {
+            Ast::Leaf { data, .. } | Ast::Error { data, .. } => data,
+            Ast::Call { data, .. } => data,
+            Ast::CallMissingCloser { data, .. } => data,
+            Ast::SyntaxError { data, .. } => data,
+            Ast::AbstractSyntaxError { data, .. } => data,
+            Ast::Unhandled { data, .. } => data,
             Ast::Box { data, .. } => data,
             Ast::Code { data, .. } => data,
             Ast::Group { data, .. } => data,
@@ -195,9 +444,520 @@ impl Ast {
             Ast::GroupMissingOpener { data, .. } => data,
             Ast::TagBox_GroupParen { data, .. } => data,
             Ast::PrefixNode_PrefixLinearSyntaxBang(_, data) => data,
+            Ast::Elided { data, .. } => data,
         }
     }
 
+    //==================================
+    // Accessors
+    //==================================
+
+    /// If this is a [`TokenKind::Symbol`] leaf, get the symbol name as it
+    /// was written (including any context prefix).
+    pub fn as_symbol(&self) -> Option<&str> {
+        match self {
+            Ast::Leaf {
+                kind: TokenKind::Symbol,
+                input,
+                data: _,
+            } => Some(input.to_str()),
+            _ => None,
+        }
+    }
+
+    /// If this is a [`TokenKind::Symbol`] leaf, get the components of the
+    /// symbol name.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_ast, ParseOptions};
+    ///
+    /// let ast = parse_ast("System`Plus", &ParseOptions::default()).syntax;
+    ///
+    /// let parts = ast.symbol_parts().unwrap();
+    ///
+    /// assert_eq!(parts.context, ["System"]);
+    /// assert_eq!(parts.name, "Plus");
+    /// ```
+    pub fn symbol_parts(&self) -> Option<SymbolParts<'_>> {
+        self.as_symbol().map(SymbolParts::parse)
+    }
+
+    /// If this is a [`TokenKind::String`] leaf, get the value of the string
+    /// literal, with the surrounding quotes removed and escape sequences
+    /// interpreted.
+    ///
+    /// Returns `None` if this is not a string leaf, or if the string contains
+    /// an escape sequence that cannot be interpreted.
+    pub fn as_string_value(&self) -> Option<String> {
+        match self {
+            Ast::Leaf {
+                kind: TokenKind::String,
+                input,
+                data: _,
+            } => unescape_string_literal(input.to_str()),
+            _ => None,
+        }
+    }
+
+    /// If this is a string literal, or a `<>` chain of string literals, get
+    /// the value of the joined string.
+    ///
+    /// See also [`Ast::string_join_parts()`].
+    ///
+    /// Chains nested with parentheses, like `("a" <> "b") <> "c"`, are
+    /// joined recursively. Returns `None` if any part of the chain is not a
+    /// string literal, or if a literal contains an escape sequence that
+    /// cannot be interpreted.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_ast, ParseOptions};
+    ///
+    /// let joined = |input: &str| {
+    ///     parse_ast(input, &ParseOptions::default())
+    ///         .syntax
+    ///         .as_joined_string_value()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     joined(r#""Kernel/" <> "Utils" <> ".wl""#).as_deref(),
+    ///     Some("Kernel/Utils.wl")
+    /// );
+    /// assert_eq!(joined(r#""Kernel/" <> name"#), None);
+    /// ```
+    pub fn as_joined_string_value(&self) -> Option<String> {
+        let Some(parts) = self.string_join_parts() else {
+            return self.as_string_value();
+        };
+
+        let mut joined = String::new();
+
+        for part in parts {
+            joined.push_str(&part.as_joined_string_value()?);
+        }
+
+        Some(joined)
+    }
+
+    /// If this is a `<>` chain, like `a <> b <> c`, or the equivalent
+    /// `StringJoin[a, b, c]`, get the joined parts.
+    pub fn string_join_parts(&self) -> Option<&[Ast]> {
+        match self.as_symbol_call() {
+            Some(("StringJoin", parts)) => Some(parts),
+            _ => None,
+        }
+    }
+
+    /// If this is a call whose head is a symbol leaf, get the head symbol
+    /// name and the arguments.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_ast, ParseOptions};
+    ///
+    /// let ast = parse_ast("f[x, y]", &ParseOptions::default()).syntax;
+    ///
+    /// let (head, args) = ast.as_symbol_call().unwrap();
+    ///
+    /// assert_eq!(head, "f");
+    /// assert_eq!(args.len(), 2);
+    /// ```
+    pub fn as_symbol_call(&self) -> Option<(&str, &[Ast])> {
+        match self {
+            Ast::Call {
+                head,
+                args,
+                data: _,
+            } => Some((head.as_symbol()?, args.as_slice())),
+            _ => None,
+        }
+    }
+
+    /// If this is a group or call that is missing its closer, get the opener
+    /// of the group and the closer that was expected.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_ast, ParseOptions};
+    ///
+    /// let ast = parse_ast("f[x, y", &ParseOptions::default()).syntax;
+    ///
+    /// let closer = ast.missing_closer().unwrap();
+    ///
+    /// assert_eq!(closer.expected, "]");
+    /// ```
+    pub fn missing_closer(&self) -> Option<&MissingCloser> {
+        match self {
+            Ast::CallMissingCloser { closer, .. }
+            | Ast::GroupMissingCloser { closer, .. } => Some(closer),
+            _ => None,
+        }
+    }
+
+    /// Decompose nested calls and parts, like `f[x][y][[2]]`, into the
+    /// expression they are applied to and an ordered sequence of
+    /// applications.
+    ///
+    /// ```
+    /// use wolfram_parser::{ast::ChainLinkKind, parse_ast, ParseOptions};
+    ///
+    /// let ast = parse_ast("f[x][y][[2]]", &ParseOptions::default()).syntax;
+    ///
+    /// let chain = ast.call_chain();
+    ///
+    /// assert_eq!(chain.root.as_symbol(), Some("f"));
+    ///
+    /// let kinds: Vec<_> = chain.links.iter().map(|link| link.kind).collect();
+    ///
+    /// assert_eq!(
+    ///     kinds,
+    ///     [ChainLinkKind::Call, ChainLinkKind::Call, ChainLinkKind::Part]
+    /// );
+    /// ```
+    ///
+    /// Only `[[...]]` syntax is considered a part; an explicit call like
+    /// `Part[x, 2]` is a [`ChainLinkKind::Call`] with head `Part`.
+    ///
+    /// If this is not a call written with `[..]` or `[[..]]`, the chain has no
+    /// links and its root is this node.
+    pub fn call_chain(&self) -> CallChain<'_> {
+        let mut links = Vec::new();
+        let mut node = self;
+
+        loop {
+            let (head, args, data, missing_closer) = match node {
+                Ast::Call { head, args, data } => (head, args, data, false),
+                Ast::CallMissingCloser {
+                    head,
+                    args,
+                    closer: _,
+                    data,
+                } => (head, args, data, true),
+                _ => break,
+            };
+
+            // `x[[i]]` is abstracted as `Part[x, i]`, with a `Part` head that
+            // has no source.
+            let is_part = head.as_symbol() == Some("Part")
+                && head.source().is_unknown()
+                && !args.is_empty();
+
+            let (kind, args, inner) = if is_part {
+                (ChainLinkKind::Part, &args[1..], &args[0])
+            } else if head.source().is_unknown() {
+                // Calls inserted by abstraction, like the `Plus` in `a + b`,
+                // are not written as calls.
+                break;
+            } else {
+                (ChainLinkKind::Call, args.as_slice(), &**head)
+            };
+
+            links.push(ChainLink {
+                kind,
+                args,
+                src: &data.source,
+                missing_closer,
+            });
+
+            node = inner;
+        }
+
+        links.reverse();
+
+        CallChain { root: node, links }
+    }
+
+    /// If this is a derivative written with quotes, like `f''`, get its order
+    /// and the source of the quotes.
+    ///
+    /// `f''` is abstracted as `Derivative[2][f]`. Explicit calls written like
+    /// `Derivative[2][f]` are not considered quote derivatives.
+    pub fn derivative(&self) -> Option<Derivative<'_>> {
+        let Ast::Call { head, args, .. } = self else {
+            return None;
+        };
+
+        let [body] = args.as_slice() else {
+            return None;
+        };
+
+        let Ast::Call {
+            head: derivative,
+            args: order,
+            data,
+        } = &**head
+        else {
+            return None;
+        };
+
+        // The `Derivative` head of a quote derivative has no source.
+        if derivative.as_symbol() != Some("Derivative")
+            || !derivative.source().is_unknown()
+        {
+            return None;
+        }
+
+        let [Ast::Leaf {
+            kind: TokenKind::Integer,
+            input,
+            data: _,
+        }] = order.as_slice()
+        else {
+            return None;
+        };
+
+        Some(Derivative {
+            order: input.to_str().parse().ok()?,
+            body,
+            quotes: &data.source,
+        })
+    }
+
+    /// Call `func` on each immediate child of this node, in source order.
+    pub(crate) fn for_each_child<'a>(&'a self, mut func: impl FnMut(&'a Ast)) {
+        match self {
+            Ast::Leaf { .. }
+            | Ast::Error { .. }
+            | Ast::Unhandled { .. }
+            | Ast::Code { .. }
+            | Ast::Elided { .. } => (),
+            Ast::Call { head, args, .. }
+            | Ast::CallMissingCloser { head, args, .. } => {
+                func(head);
+                args.iter().for_each(func);
+            },
+            Ast::SyntaxError { children, .. }
+            | Ast::GroupMissingCloser { children, .. }
+            | Ast::GroupMissingOpener { children, .. } => {
+                children.iter().for_each(func)
+            },
+            Ast::AbstractSyntaxError { args, .. } | Ast::Box { args, .. } => {
+                args.iter().for_each(func)
+            },
+            Ast::Group { children, .. } => {
+                let (opener, body, closer) = &**children;
+                func(opener);
+                func(body);
+                func(closer);
+            },
+            Ast::TagBox_GroupParen { group, .. } => {
+                let (opener, body, closer, _) = &**group;
+                func(opener);
+                func(body);
+                func(closer);
+            },
+            Ast::PrefixNode_PrefixLinearSyntaxBang(children, _) => {
+                children.iter().for_each(func)
+            },
+        }
+    }
+
+    //==================================
+    // Truncation
+    //==================================
+
+    /// Get a summary of this tree that contains at most `max_nodes` of its
+    /// nodes, for display of very large expressions.
+    ///
+    /// Nodes are kept in breadth-first order, so the shallowest parts of the
+    /// tree are kept, and deep or large subtrees are elided. Each run of
+    /// consecutive removed siblings is replaced by a single [`Ast::Elided`]
+    /// placeholder, which records how many subtrees and nodes it replaces and
+    /// spans their source. Placeholders are not counted in `max_nodes`.
+    ///
+    /// The root node is always kept.
+    ///
+    /// ```
+    /// use wolfram_parser::{ast::Ast, parse_ast, ParseOptions};
+    ///
+    /// let ast = parse_ast("{1, 2, 3, 4, 5}", &ParseOptions::default()).syntax;
+    ///
+    /// let Ast::Call { args, .. } = ast.truncate(4) else { panic!() };
+    ///
+    /// assert_eq!(args.len(), 3);
+    /// assert!(matches!(args[2], Ast::Elided { subtrees: 3, nodes: 3, .. }));
+    /// ```
+    pub fn truncate(&self, max_nodes: usize) -> Ast {
+        let max_nodes = max_nodes.max(1);
+
+        let mut kept: HashSet<*const Ast> = HashSet::new();
+        let mut queue: VecDeque<&Ast> = VecDeque::from([self]);
+
+        while let Some(node) = queue.pop_front() {
+            kept.insert(node);
+
+            node.for_each_child(|child| {
+                // Children past the limit will never be kept.
+                if kept.len() + queue.len() < max_nodes {
+                    queue.push_back(child);
+                }
+            });
+        }
+
+        self.truncated(&kept)
+    }
+
+    fn truncated(&self, kept: &HashSet<*const Ast>) -> Ast {
+        let one = |child: &Ast| -> Ast {
+            if kept.contains(&(child as *const Ast)) {
+                child.truncated(kept)
+            } else {
+                Ast::elided(std::slice::from_ref(child))
+            }
+        };
+
+        let seq = |children: &[Ast]| -> Vec<Ast> {
+            let mut result = Vec::new();
+            let mut removed = 0..0;
+
+            for (index, child) in children.iter().enumerate() {
+                if kept.contains(&(child as *const Ast)) {
+                    if !removed.is_empty() {
+                        result.push(Ast::elided(&children[removed.clone()]));
+                    }
+                    result.push(child.truncated(kept));
+                    removed = index + 1..index + 1;
+                } else {
+                    removed.end = index + 1;
+                }
+            }
+
+            if !removed.is_empty() {
+                result.push(Ast::elided(&children[removed]));
+            }
+
+            result
+        };
+
+        match self {
+            Ast::Leaf { .. }
+            | Ast::Error { .. }
+            | Ast::Unhandled { .. }
+            | Ast::Code { .. }
+            | Ast::Elided { .. } => self.clone(),
+            Ast::Call { head, args, data } => Ast::Call {
+                head: Box::new(one(head)),
+                args: seq(args),
+                data: data.clone(),
+            },
+            Ast::CallMissingCloser {
+                head,
+                args,
+                closer,
+                data,
+            } => Ast::CallMissingCloser {
+                head: Box::new(one(head)),
+                args: seq(args),
+                closer: closer.clone(),
+                data: data.clone(),
+            },
+            Ast::SyntaxError {
+                kind,
+                children,
+                data,
+            } => Ast::SyntaxError {
+                kind: *kind,
+                children: seq(children),
+                data: data.clone(),
+            },
+            Ast::AbstractSyntaxError { kind, args, data } => {
+                Ast::AbstractSyntaxError {
+                    kind: kind.clone(),
+                    args: seq(args),
+                    data: data.clone(),
+                }
+            },
+            Ast::Box { kind, args, data } => Ast::Box {
+                kind: kind.clone(),
+                args: seq(args),
+                data: data.clone(),
+            },
+            Ast::Group {
+                kind,
+                children,
+                data,
+            } => {
+                let (opener, body, closer) = &**children;
+
+                Ast::Group {
+                    kind: *kind,
+                    children: Box::new((one(opener), one(body), one(closer))),
+                    data: data.clone(),
+                }
+            },
+            Ast::GroupMissingCloser {
+                kind,
+                children,
+                closer,
+                data,
+            } => Ast::GroupMissingCloser {
+                kind: *kind,
+                children: seq(children),
+                closer: closer.clone(),
+                data: data.clone(),
+            },
+            Ast::GroupMissingOpener {
+                kind,
+                children,
+                data,
+            } => Ast::GroupMissingOpener {
+                kind: *kind,
+                children: seq(children),
+                data: data.clone(),
+            },
+            Ast::TagBox_GroupParen { group, tag, data } => {
+                let (opener, body, closer, src) = &**group;
+
+                Ast::TagBox_GroupParen {
+                    group: Box::new((
+                        one(opener),
+                        one(body),
+                        one(closer),
+                        src.clone(),
+                    )),
+                    tag: tag.clone(),
+                    data: data.clone(),
+                }
+            },
+            Ast::PrefixNode_PrefixLinearSyntaxBang(children, data) => {
+                let [operator, operand] = &**children;
+
+                Ast::PrefixNode_PrefixLinearSyntaxBang(
+                    Box::new([one(operator), one(operand)]),
+                    data.clone(),
+                )
+            },
+        }
+    }
+
+    /// Placeholder for the removed sibling subtrees `removed`.
+    fn elided(removed: &[Ast]) -> Ast {
+        let mut sources = removed
+            .iter()
+            .map(Ast::source)
+            .filter(|source| !source.is_unknown());
+
+        let source = match (sources.next(), sources.last()) {
+            (Some(first), Some(last)) => {
+                Source::between(first.clone(), last.clone())
+            },
+            (Some(only), None) => only.clone(),
+            (None, _) => Source::unknown(),
+        };
+
+        Ast::Elided {
+            subtrees: removed.len(),
+            nodes: removed.iter().map(Ast::subtree_size).sum(),
+            data: AstMetadata::from_src(source),
+        }
+    }
+
+    /// Number of nodes in the tree rooted at this node, including itself.
+    fn subtree_size(&self) -> usize {
+        let mut size = 1;
+
+        self.for_each_child(|child| size += child.subtree_size());
+
+        size
+    }
+
     //==================================
     // Convenience constructor methods
     //==================================
@@ -289,11 +1049,13 @@ impl Ast {
     pub(crate) fn call_missing_closer(
         head: Ast,
         args: Vec<Ast>,
+        closer: MissingCloser,
         data: impl Into<AstMetadata>,
     ) -> Self {
         Ast::CallMissingCloser {
             head: Box::new(head),
             args,
+            closer,
             data: data.into(),
         }
     }
@@ -321,6 +1083,65 @@ impl Ast {
             data: data.into(),
         }
     }
+
+    pub(crate) fn unhandled(
+        cst_kind: CstKind,
+        reason: UnhandledReason,
+        data: impl Into<AstMetadata>,
+    ) -> Self {
+        Ast::Unhandled {
+            cst_kind,
+            reason,
+            data: data.into(),
+        }
+    }
+}
+
+impl<'a> SymbolParts<'a> {
+    /// Split a symbol name, like `` A`B`c ``, into its components.
+    pub fn parse(symbol: &'a str) -> Self {
+        let (relative, symbol) = match symbol.strip_prefix('`') {
+            Some(rest) => (true, rest),
+            None => (false, symbol),
+        };
+
+        let (context, name) = match symbol.rsplit_once('`') {
+            Some((context, name)) => (context.split('`').collect(), name),
+            None => (Vec::new(), symbol),
+        };
+
+        SymbolParts {
+            relative,
+            context,
+            name,
+        }
+    }
+
+    /// Whether the symbol name includes a context.
+    pub fn is_qualified(&self) -> bool {
+        self.relative || !self.context.is_empty()
+    }
+
+    /// Get the context of the symbol as it was written, like `` "A`B`" `` or
+    /// `` "`B`" ``, or `None` if the symbol has no context.
+    pub fn context_str(&self) -> Option<String> {
+        if !self.is_qualified() {
+            return None;
+        }
+
+        let mut context = String::new();
+
+        if self.relative {
+            context.push('`');
+        }
+
+        for segment in &self.context {
+            context.push_str(segment);
+            context.push('`');
+        }
+
+        Some(context)
+    }
 }
 
 impl AstMetadata {
@@ -330,6 +1151,16 @@ impl AstMetadata {
         AstMetadata {
             source: src,
             issues: Vec::new(),
+            implicit: false,
+            comparisons: None,
+        }
+    }
+
+    /// Metadata of a node inserted by abstraction at `src`.
+    pub fn implicit<S: TokenSource>(src: S) -> Self {
+        AstMetadata {
+            implicit: true,
+            ..AstMetadata::from_src(src)
         }
     }
 
@@ -338,8 +1169,73 @@ impl AstMetadata {
         AstMetadata {
             source: Source::unknown(),
             issues: Vec::new(),
+            implicit: false,
+            comparisons: None,
+        }
+    }
+}
+
+//======================================
+// Utilities
+//======================================
+
+/// Interpret the contents of a string literal token, including the
+/// surrounding quotes.
+pub(crate) fn unescape_string_literal(input: &str) -> Option<String> {
+    let inner = input.strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut out = String::with_capacity(inner.len());
+
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
         }
+
+        let c = match chars.next()? {
+            '\\' => '\\',
+            '"' => '"',
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            '[' => {
+                let name: String =
+                    chars.by_ref().take_while(|c| *c != ']').collect();
+
+                crate::long_names::longname_to_codepoint(&name)?.as_char()?
+            },
+            ':' => hex_escape(&mut chars, 4)?,
+            '.' => hex_escape(&mut chars, 2)?,
+            '|' => hex_escape(&mut chars, 6)?,
+            // Line continuation
+            '\n' => continue,
+            d @ '0'..='7' => {
+                let rest: String = chars.by_ref().take(2).collect();
+                let digits = format!("{d}{rest}");
+
+                char::from_u32(u32::from_str_radix(&digits, 8).ok()?)?
+            },
+            _ => return None,
+        };
+
+        out.push(c);
+    }
+
+    Some(out)
+}
+
+fn hex_escape(chars: &mut std::str::Chars, len: usize) -> Option<char> {
+    let digits: String = chars.by_ref().take(len).collect();
+
+    if digits.len() != len {
+        return None;
     }
+
+    char::from_u32(u32::from_str_radix(&digits, 16).ok()?)
 }
 
 //======================================
@@ -363,6 +1259,8 @@ impl<S: TokenSource> From<S> for AstMetadata {
         AstMetadata {
             source: source.into_general(),
             issues: Vec::new(),
+            implicit: false,
+            comparisons: None,
         }
     }
 }
@@ -372,6 +1270,8 @@ impl From<LineColumnSpan> for AstMetadata {
         AstMetadata {
             source: Source::Span(Span::from(value)),
             issues: Vec::new(),
+            implicit: false,
+            comparisons: None,
         }
     }
 }
@@ -381,6 +1281,8 @@ impl From<BoxPosition> for AstMetadata {
         AstMetadata {
             source: Source::Box(value),
             issues: Vec::new(),
+            implicit: false,
+            comparisons: None,
         }
     }
 }
@@ -432,10 +1334,16 @@ impl Debug for Ast {
                 .field("args", args)
                 .field("data", data)
                 .finish(),
-            Self::CallMissingCloser { head, args, data } => f
+            Self::CallMissingCloser {
+                head,
+                args,
+                closer,
+                data,
+            } => f
                 .debug_struct("CallMissingCloser")
                 .field("head", head)
                 .field("args", args)
+                .field("closer", closer)
                 .field("data", data)
                 .finish(),
             Self::SyntaxError {
@@ -454,6 +1362,16 @@ impl Debug for Ast {
                 .field("args", args)
                 .field("data", data)
                 .finish(),
+            Self::Unhandled {
+                cst_kind,
+                reason,
+                data,
+            } => f
+                .debug_struct("Unhandled")
+                .field("cst_kind", cst_kind)
+                .field("reason", reason)
+                .field("data", data)
+                .finish(),
             Self::Box { kind, args, data } => f
                 .debug_struct("Box")
                 .field("kind", kind)
@@ -483,11 +1401,13 @@ impl Debug for Ast {
             Self::GroupMissingCloser {
                 kind,
                 children,
+                closer,
                 data,
             } => f
                 .debug_struct("GroupMissingCloser")
                 .field("kind", kind)
                 .field("children", children)
+                .field("closer", closer)
                 .field("data", data)
                 .finish(),
             Self::GroupMissingOpener {
@@ -511,15 +1431,30 @@ impl Debug for Ast {
                 .field(arg0)
                 .field(arg1)
                 .finish(),
+            Self::Elided {
+                subtrees,
+                nodes,
+                data,
+            } => f
+                .debug_struct("Elided")
+                .field("subtrees", subtrees)
+                .field("nodes", nodes)
+                .field("data", data)
+                .finish(),
         }
     }
 }
 
 impl Debug for AstMetadata {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let AstMetadata { source, issues } = self;
+        let AstMetadata {
+            source,
+            issues,
+            implicit,
+            comparisons,
+        } = self;
 
-        if issues.is_empty() {
+        if issues.is_empty() && !implicit && comparisons.is_none() {
             if source.is_unknown() {
                 return write!(f, "<||>");
             } else {
@@ -530,6 +1465,8 @@ impl Debug for AstMetadata {
         f.debug_struct("AstMetadata")
             .field("source", source)
             .field("issues", issues)
+            .field("implicit", implicit)
+            .field("comparisons", comparisons)
             .finish()
     }
 }
@@ -0,0 +1,316 @@
+//! Convert abstract syntax trees to and from [`Expr`] values.
+//!
+//! [`Ast::to_expr()`] lowers an abstract syntax tree to the expression the
+//! Wolfram Language kernel would read from the same input:
+//!
+//! ```
+//! use wolfram_expr::{Expr, Symbol};
+//! use wolfram_parser::{parse_ast, ParseOptions};
+//!
+//! let ast = parse_ast("f[x, 1 + 2]", &ParseOptions::default()).syntax;
+//!
+//! let plus = Expr::normal(Symbol::new("System`Plus"), vec![
+//!     Expr::from(1_i64),
+//!     Expr::from(2_i64),
+//! ]);
+//!
+//! assert_eq!(
+//!     ast.to_expr(),
+//!     Ok(Expr::normal(Symbol::new("Global`f"), vec![
+//!         Expr::from(Symbol::new("Global`x")),
+//!         plus,
+//!     ]))
+//! );
+//! ```
+//!
+//! [`Ast::from_expr()`] goes the other way, producing an abstract syntax tree
+//! with no source locations.
+//!
+//! # Symbol contexts
+//!
+//! [`Expr`] symbols are always fully qualified, but symbols in the input
+//! usually aren't. [`Ast::to_expr()`] puts a symbol written without a
+//! context in `` System` `` if it is one of the built-in symbols that
+//! abstraction produces, like `Plus` or `List`, and in `` Global` ``
+//! otherwise. Use [`Ast::to_expr_with()`] to resolve symbols differently,
+//! for example to use the contexts of a package being analyzed.
+//!
+//! # Limitations
+//!
+//! Only abstract syntax that has an expression equivalent can be converted:
+//!
+//! * syntax errors, and groups missing their opener or closer, return
+//!   [`ToExprError::SyntaxError`]
+//! * boxes, [`Ast::Unhandled`] syntax, and [`Ast::Elided`] placeholders
+//!   return [`ToExprError::Unsupported`]
+//! * integers that don't fit in an `i64`, reals written in a base other than
+//!   10, and strings with escapes that can't be interpreted return
+//!   [`ToExprError::UnsupportedLiteral`]
+//!
+//! The precision and accuracy of reals, like in `` 1.5`20 ``, are ignored.
+
+use thiserror::Error;
+use wolfram_expr::{Expr, ExprKind, Symbol};
+
+use crate::{
+    ast::{unescape_string_literal, Ast, AstMetadata},
+    data::{parse_integer, parse_real},
+    source::Source,
+    symbols::is_system_symbol,
+    tokenize::{TokenKind, TokenString},
+};
+
+/// Error returned by [`Ast::to_expr()`] for syntax that has no expression
+/// equivalent.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ToExprError {
+    #[error("syntax error at {0}")]
+    SyntaxError(Source),
+
+    #[error("unsupported literal `{input}` at {src}")]
+    UnsupportedLiteral { input: String, src: Source },
+
+    #[error("symbol `{input}` could not be resolved at {src}")]
+    UnresolvedSymbol { input: String, src: Source },
+
+    #[error("syntax with no expression equivalent at {0}")]
+    Unsupported(Source),
+}
+
+impl Ast {
+    /// Convert this abstract syntax tree to an [`Expr`].
+    ///
+    /// Symbols without a context are resolved as described in the
+    /// [module documentation][crate::ast::expr#symbol-contexts].
+    pub fn to_expr(&self) -> Result<Expr, ToExprError> {
+        self.to_expr_with(&|name: &str| {
+            Symbol::try_new(&default_symbol_name(name))
+        })
+    }
+
+    /// Convert this abstract syntax tree to an [`Expr`], using `resolve` to
+    /// get the [`Symbol`] for each symbol name as it was written, like `x`,
+    /// `` `x `` or `` A`x ``.
+    ///
+    /// If `resolve` returns `None`, the conversion fails with
+    /// [`ToExprError::UnresolvedSymbol`].
+    ///
+    /// ```
+    /// use wolfram_expr::{Expr, Symbol};
+    /// use wolfram_parser::{parse_ast, ParseOptions};
+    ///
+    /// let ast = parse_ast("x", &ParseOptions::default()).syntax;
+    ///
+    /// let expr = ast.to_expr_with(&|name: &str| {
+    ///     Symbol::try_new(&format!("MyPackage`{name}"))
+    /// });
+    ///
+    /// assert_eq!(expr, Ok(Expr::from(Symbol::new("MyPackage`x"))));
+    /// ```
+    pub fn to_expr_with(
+        &self,
+        resolve: &dyn Fn(&str) -> Option<Symbol>,
+    ) -> Result<Expr, ToExprError> {
+        match self {
+            Ast::Leaf { kind, input, data } => {
+                leaf_to_expr(*kind, input.to_str(), &data.source, resolve)
+            },
+            Ast::Call {
+                head,
+                args,
+                data: _,
+            } => {
+                let head = head.to_expr_with(resolve)?;
+
+                let args = args
+                    .iter()
+                    .map(|arg| arg.to_expr_with(resolve))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(Expr::normal(head, args))
+            },
+            Ast::Error { data, .. }
+            | Ast::CallMissingCloser { data, .. }
+            | Ast::SyntaxError { data, .. }
+            | Ast::AbstractSyntaxError { data, .. }
+            | Ast::GroupMissingCloser { data, .. }
+            | Ast::GroupMissingOpener { data, .. }
+            | Ast::PrefixNode_PrefixLinearSyntaxBang(_, data) => {
+                Err(ToExprError::SyntaxError(data.source.clone()))
+            },
+            Ast::Box { data, .. }
+            | Ast::Unhandled { data, .. }
+            | Ast::Code { data, .. }
+            | Ast::Group { data, .. }
+            | Ast::TagBox_GroupParen { data, .. }
+            | Ast::Elided { data, .. } => {
+                Err(ToExprError::Unsupported(data.source.clone()))
+            },
+        }
+    }
+
+    /// Convert an [`Expr`] to an abstract syntax tree.
+    ///
+    /// The nodes of the returned tree have [unknown][Source::unknown]
+    /// sources. Returns `None` if `expr` contains a real that is infinite or
+    /// NaN, which have no literal syntax.
+    ///
+    /// ```
+    /// use wolfram_expr::{Expr, Symbol};
+    /// use wolfram_parser::ast::Ast;
+    ///
+    /// let expr = Expr::normal(Symbol::new("System`List"), vec![
+    ///     Expr::from(1_i64),
+    ///     Expr::string("two"),
+    /// ]);
+    ///
+    /// let ast = Ast::from_expr(&expr).unwrap();
+    ///
+    /// assert_eq!(ast.as_symbol_call().unwrap().0, "List");
+    /// assert_eq!(ast.to_expr(), Ok(expr));
+    /// ```
+    pub fn from_expr(expr: &Expr) -> Option<Ast> {
+        let ast = match expr.kind() {
+            ExprKind::Integer(int) => leaf(TokenKind::Integer, int.to_string()),
+            ExprKind::Real(real) => {
+                leaf(TokenKind::Real, real_literal(real.into_inner())?)
+            },
+            ExprKind::String(string) => {
+                leaf(TokenKind::String, string_literal(string))
+            },
+            ExprKind::Symbol(symbol) => {
+                leaf(TokenKind::Symbol, symbol_literal(symbol))
+            },
+            ExprKind::Normal(normal) => Ast::Call {
+                head: Box::new(Ast::from_expr(normal.head())?),
+                args: normal
+                    .elements()
+                    .iter()
+                    .map(Ast::from_expr)
+                    .collect::<Option<Vec<_>>>()?,
+                data: AstMetadata::empty(),
+            },
+        };
+
+        Some(ast)
+    }
+}
+
+fn leaf_to_expr(
+    kind: TokenKind,
+    input: &str,
+    src: &Source,
+    resolve: &dyn Fn(&str) -> Option<Symbol>,
+) -> Result<Expr, ToExprError> {
+    let unsupported = || ToExprError::UnsupportedLiteral {
+        input: input.to_owned(),
+        src: src.clone(),
+    };
+
+    match kind {
+        TokenKind::Symbol => {
+            // Symbols can contain long names like `\[Alpha]`, which are
+            // interpreted the same way as in strings.
+            let name = unescape_string_literal(&format!("\"{input}\""))
+                .ok_or_else(unsupported)?;
+
+            match resolve(&name) {
+                Some(symbol) => Ok(Expr::from(symbol)),
+                None => Err(ToExprError::UnresolvedSymbol {
+                    input: input.to_owned(),
+                    src: src.clone(),
+                }),
+            }
+        },
+        TokenKind::String => unescape_string_literal(input)
+            .map(Expr::string)
+            .ok_or_else(unsupported),
+        TokenKind::Integer => {
+            parse_integer(input).map(Expr::from).ok_or_else(unsupported)
+        },
+        TokenKind::Real => match parse_real(input) {
+            Some(real) if real.is_finite() => Ok(Expr::real(real)),
+            _ => Err(unsupported()),
+        },
+        _ => Err(unsupported()),
+    }
+}
+
+/// Fully qualify `name` the way [`Ast::to_expr()`] does.
+fn default_symbol_name(name: &str) -> String {
+    if let Some(name) = name.strip_prefix('`') {
+        return format!("Global`{name}");
+    }
+
+    if name.contains('`') {
+        name.to_owned()
+    } else if is_system_symbol(name) {
+        format!("System`{name}")
+    } else {
+        format!("Global`{name}")
+    }
+}
+
+fn leaf(kind: TokenKind, input: String) -> Ast {
+    Ast::Leaf {
+        kind,
+        input: TokenString::from_string(input),
+        data: AstMetadata::empty(),
+    }
+}
+
+/// Write `symbol` without its context if [`Ast::to_expr()`] would resolve
+/// it back to the same symbol.
+fn symbol_literal(symbol: &Symbol) -> String {
+    let name = symbol.symbol_name();
+
+    if symbol.context().as_str() == "System`" && is_system_symbol(name.as_str())
+    {
+        name.as_str().to_owned()
+    } else {
+        symbol.as_str().to_owned()
+    }
+}
+
+fn string_literal(string: &str) -> String {
+    let mut literal = String::with_capacity(string.len() + 2);
+
+    literal.push('"');
+
+    for c in string.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\t' => literal.push_str("\\t"),
+            '\r' => literal.push_str("\\r"),
+            _ => literal.push(c),
+        }
+    }
+
+    literal.push('"');
+
+    literal
+}
+
+/// Write `real` in Wolfram Language real syntax, like `1.5` or `1.*^300`.
+fn real_literal(real: f64) -> Option<String> {
+    if !real.is_finite() {
+        return None;
+    }
+
+    // The `Debug` format of a finite `f64` is the shortest representation
+    // that round trips, like `1.5`, `1e300`, or `1.5e-7`.
+    let debug = format!("{real:?}");
+
+    let literal = match debug.split_once('e') {
+        Some((mantissa, exponent)) if mantissa.contains('.') => {
+            format!("{mantissa}*^{exponent}")
+        },
+        Some((mantissa, exponent)) => format!("{mantissa}.*^{exponent}"),
+        None => debug,
+    };
+
+    Some(literal)
+}
@@ -0,0 +1,234 @@
+//! Fold trait for rewriting [`Ast`] trees.
+//!
+//! Implement [`AstFold`] and override [`fold_leaf()`][AstFold::fold_leaf] or
+//! [`fold_call()`][AstFold::fold_call] to replace nodes. Trees are folded
+//! bottom-up, so the arguments passed to `fold_call()` have already been
+//! folded. The [`AstMetadata`] of each node is passed to the callbacks, and
+//! is kept on every node that isn't replaced.
+//!
+//! ```
+//! use wolfram_parser::{
+//!     ast::{fold::AstFold, Ast, AstMetadata},
+//!     parse_ast,
+//!     tokenize::{TokenKind, TokenString},
+//!     ParseOptions,
+//! };
+//!
+//! /// Replace `1 + 2` with `3`.
+//! struct AddIntegers;
+//!
+//! impl AstFold for AddIntegers {
+//!     fn fold_call(
+//!         &mut self,
+//!         head: Ast,
+//!         args: Vec<Ast>,
+//!         data: AstMetadata,
+//!     ) -> Ast {
+//!         let ints: Option<Vec<i64>> = args
+//!             .iter()
+//!             .map(|arg| match arg {
+//!                 Ast::Leaf { kind: TokenKind::Integer, input, .. } => {
+//!                     input.to_str().parse().ok()
+//!                 },
+//!                 _ => None,
+//!             })
+//!             .collect();
+//!
+//!         match (head.as_symbol(), ints) {
+//!             (Some("Plus"), Some(ints)) => Ast::Leaf {
+//!                 kind: TokenKind::Integer,
+//!                 input: TokenString::from_string(
+//!                     ints.iter().sum::<i64>().to_string(),
+//!                 ),
+//!                 data,
+//!             },
+//!             _ => Ast::Call { head: Box::new(head), args, data },
+//!         }
+//!     }
+//! }
+//!
+//! let ast = parse_ast("f[1 + 2 + 3]", &ParseOptions::default()).syntax;
+//!
+//! let ast = AddIntegers.fold_ast(ast);
+//!
+//! let (_, args) = ast.as_symbol_call().unwrap();
+//!
+//! let Ast::Leaf { input, .. } = &args[0] else { panic!() };
+//!
+//! assert_eq!(input.to_str(), "6");
+//! ```
+
+use crate::{
+    ast::{Ast, AstMetadata},
+    tokenize::{TokenKind, TokenString},
+};
+
+/// Callbacks for rewriting an [`Ast`] tree, called by
+/// [`fold_ast()`][AstFold::fold_ast].
+pub trait AstFold {
+    /// Fold `node` and all of its children.
+    ///
+    /// By default, this folds the children of `node` with [`fold_children()`]
+    /// and then calls [`fold_leaf()`][AstFold::fold_leaf] or
+    /// [`fold_call()`][AstFold::fold_call] if `node` is a leaf or call.
+    fn fold_ast(&mut self, node: Ast) -> Ast {
+        match fold_children(self, node) {
+            Ast::Leaf { kind, input, data } => {
+                self.fold_leaf(kind, input, data)
+            },
+            Ast::Call { head, args, data } => self.fold_call(*head, args, data),
+            node => node,
+        }
+    }
+
+    /// Fold an [`Ast::Leaf`] node.
+    ///
+    /// By default, this returns the leaf unchanged.
+    fn fold_leaf(
+        &mut self,
+        kind: TokenKind,
+        input: TokenString,
+        data: AstMetadata,
+    ) -> Ast {
+        Ast::Leaf { kind, input, data }
+    }
+
+    /// Fold an [`Ast::Call`] node whose head and arguments have already been
+    /// folded.
+    ///
+    /// By default, this returns the call unchanged.
+    fn fold_call(
+        &mut self,
+        head: Ast,
+        args: Vec<Ast>,
+        data: AstMetadata,
+    ) -> Ast {
+        Ast::Call {
+            head: Box::new(head),
+            args,
+            data,
+        }
+    }
+}
+
+/// Fold each immediate child of `node` with
+/// [`fold_ast()`][AstFold::fold_ast], keeping the kind and metadata of
+/// `node` itself.
+pub fn fold_children<F: AstFold + ?Sized>(folder: &mut F, node: Ast) -> Ast {
+    match node {
+        Ast::Leaf { .. }
+        | Ast::Error { .. }
+        | Ast::Unhandled { .. }
+        | Ast::Code { .. }
+        | Ast::Elided { .. } => node,
+        Ast::Call { head, args, data } => {
+            let head = Box::new(folder.fold_ast(*head));
+            let args = fold_vec(folder, args);
+
+            Ast::Call { head, args, data }
+        },
+        Ast::CallMissingCloser {
+            head,
+            args,
+            closer,
+            data,
+        } => {
+            let head = Box::new(folder.fold_ast(*head));
+            let args = fold_vec(folder, args);
+
+            Ast::CallMissingCloser {
+                head,
+                args,
+                closer,
+                data,
+            }
+        },
+        Ast::SyntaxError {
+            kind,
+            children,
+            data,
+        } => Ast::SyntaxError {
+            kind,
+            children: fold_vec(folder, children),
+            data,
+        },
+        Ast::AbstractSyntaxError { kind, args, data } => {
+            Ast::AbstractSyntaxError {
+                kind,
+                args: fold_vec(folder, args),
+                data,
+            }
+        },
+        Ast::Box { kind, args, data } => Ast::Box {
+            kind,
+            args: fold_vec(folder, args),
+            data,
+        },
+        Ast::Group {
+            kind,
+            children,
+            data,
+        } => {
+            let (opener, body, closer) = *children;
+
+            Ast::Group {
+                kind,
+                children: Box::new((
+                    folder.fold_ast(opener),
+                    folder.fold_ast(body),
+                    folder.fold_ast(closer),
+                )),
+                data,
+            }
+        },
+        Ast::GroupMissingCloser {
+            kind,
+            children,
+            closer,
+            data,
+        } => Ast::GroupMissingCloser {
+            kind,
+            children: fold_vec(folder, children),
+            closer,
+            data,
+        },
+        Ast::GroupMissingOpener {
+            kind,
+            children,
+            data,
+        } => Ast::GroupMissingOpener {
+            kind,
+            children: fold_vec(folder, children),
+            data,
+        },
+        Ast::TagBox_GroupParen { group, tag, data } => {
+            let (opener, body, closer, src) = *group;
+
+            Ast::TagBox_GroupParen {
+                group: Box::new((
+                    folder.fold_ast(opener),
+                    folder.fold_ast(body),
+                    folder.fold_ast(closer),
+                    src,
+                )),
+                tag,
+                data,
+            }
+        },
+        Ast::PrefixNode_PrefixLinearSyntaxBang(children, data) => {
+            let [op, operand] = *children;
+
+            Ast::PrefixNode_PrefixLinearSyntaxBang(
+                Box::new([folder.fold_ast(op), folder.fold_ast(operand)]),
+                data,
+            )
+        },
+    }
+}
+
+fn fold_vec<F: AstFold + ?Sized>(folder: &mut F, nodes: Vec<Ast>) -> Vec<Ast> {
+    nodes
+        .into_iter()
+        .map(|node| folder.fold_ast(node))
+        .collect()
+}
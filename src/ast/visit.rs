@@ -0,0 +1,124 @@
+//! Visitor trait for [`Ast`] traversal.
+//!
+//! Implement [`AstVisitor`] and override the callbacks for the kinds of nodes
+//! of interest, then walk a tree with [`walk_ast()`]:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     ast::{
+//!         visit::{walk_ast, AstVisitor},
+//!         Ast,
+//!     },
+//!     parse_ast, ParseOptions,
+//! };
+//!
+//! #[derive(Default)]
+//! struct Symbols(Vec<String>);
+//!
+//! impl AstVisitor for Symbols {
+//!     fn visit_leaf(&mut self, node: &Ast) {
+//!         if let Some(name) = node.as_symbol() {
+//!             self.0.push(name.to_owned());
+//!         }
+//!     }
+//! }
+//!
+//! let ast = parse_ast("f[x, g[y]]", &ParseOptions::default()).syntax;
+//!
+//! let mut symbols = Symbols::default();
+//! walk_ast(&mut symbols, &ast);
+//!
+//! assert_eq!(symbols.0, ["f", "x", "g", "y"]);
+//! ```
+//!
+//! To rewrite a tree, see [`AstFold`][crate::ast::fold::AstFold].
+
+use crate::ast::Ast;
+
+/// Callbacks for each kind of [`Ast`] node, called by [`walk_ast()`].
+///
+/// Every callback is passed a node of the corresponding [`Ast`] variant, and
+/// does nothing by default. Callbacks are called before the children of a
+/// node are walked.
+pub trait AstVisitor {
+    /// Called for every [`Ast::Leaf`] node, like `x`, `1`, or `"str"`.
+    fn visit_leaf(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::Error`] node.
+    fn visit_error(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::Call`] node, like `f[x]` or `a + b`.
+    fn visit_call(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::CallMissingCloser`] node, like `f[x`.
+    fn visit_call_missing_closer(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::SyntaxError`] node.
+    fn visit_syntax_error(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::AbstractSyntaxError`] node.
+    fn visit_abstract_syntax_error(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::Unhandled`] node.
+    fn visit_unhandled(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::Box`] node.
+    fn visit_box(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::Code`] node.
+    fn visit_code(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::Group`] node.
+    fn visit_group(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::GroupMissingCloser`] node, like `{x`.
+    fn visit_group_missing_closer(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::GroupMissingOpener`] node.
+    fn visit_group_missing_opener(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::TagBox_GroupParen`] node.
+    fn visit_tag_box_group_paren(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::PrefixNode_PrefixLinearSyntaxBang`] node.
+    fn visit_prefix_linear_syntax_bang(&mut self, _node: &Ast) {}
+
+    /// Called for every [`Ast::Elided`] node.
+    fn visit_elided(&mut self, _node: &Ast) {}
+}
+
+/// Call the [`AstVisitor`] callback for `node`, and then walk each of its
+/// children, recursively and in source order.
+pub fn walk_ast<V: AstVisitor + ?Sized>(visitor: &mut V, node: &Ast) {
+    match node {
+        Ast::Leaf { .. } => visitor.visit_leaf(node),
+        Ast::Error { .. } => visitor.visit_error(node),
+        Ast::Call { .. } => visitor.visit_call(node),
+        Ast::CallMissingCloser { .. } => {
+            visitor.visit_call_missing_closer(node)
+        },
+        Ast::SyntaxError { .. } => visitor.visit_syntax_error(node),
+        Ast::AbstractSyntaxError { .. } => {
+            visitor.visit_abstract_syntax_error(node)
+        },
+        Ast::Unhandled { .. } => visitor.visit_unhandled(node),
+        Ast::Box { .. } => visitor.visit_box(node),
+        Ast::Code { .. } => visitor.visit_code(node),
+        Ast::Group { .. } => visitor.visit_group(node),
+        Ast::GroupMissingCloser { .. } => {
+            visitor.visit_group_missing_closer(node)
+        },
+        Ast::GroupMissingOpener { .. } => {
+            visitor.visit_group_missing_opener(node)
+        },
+        Ast::TagBox_GroupParen { .. } => {
+            visitor.visit_tag_box_group_paren(node)
+        },
+        Ast::PrefixNode_PrefixLinearSyntaxBang(..) => {
+            visitor.visit_prefix_linear_syntax_bang(node)
+        },
+        Ast::Elided { .. } => visitor.visit_elided(node),
+    }
+
+    node.for_each_child(|child| walk_ast(visitor, child));
+}
@@ -0,0 +1,280 @@
+//! Measure parser throughput over a corpus of source files.
+//!
+//! [`run()`] tokenizes, parses, and abstracts every source file in a
+//! directory, and reports the time and number of allocations taken by each
+//! phase. This makes it possible for embedders to measure the effect of
+//! [`ParseOptions`] and crate features on their own code:
+//!
+//! ```no_run
+//! use wolfram_parser::{benchmarks, ParseOptions};
+//!
+//! let report = benchmarks::run("path/to/corpus")?;
+//!
+//! println!("{report}");
+//! println!("parse: {:.1} MB/s", report.parse.mb_per_sec());
+//!
+//! let quick = benchmarks::run_with_options(
+//!     "path/to/corpus",
+//!     &ParseOptions::default().reparse_unterminated(None),
+//! )?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! # Allocation counts
+//!
+//! Allocations can only be counted if [`CountingAllocator`] is installed as
+//! the global allocator of the program running the benchmarks:
+//!
+//! ```no_run
+//! use wolfram_parser::benchmarks::CountingAllocator;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//! ```
+//!
+//! Otherwise, [`Phase::allocations`] is `None`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    abstract_cst::{abstract_cst, aggregate_cst_seq},
+    parse_bytes_cst_seq, testing, tokenize_bytes, NodeSeq, ParseOptions,
+};
+
+/// The results of [`run()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// Paths of every file in the corpus, in sorted order.
+    pub files: Vec<PathBuf>,
+    /// Total size of the files in the corpus, in bytes.
+    pub bytes: u64,
+
+    /// Tokenizing every file.
+    pub tokenize: Phase,
+    /// Parsing every file into concrete syntax.
+    pub parse: Phase,
+    /// Aggregating and abstracting the concrete syntax of every file.
+    pub abstract_: Phase,
+}
+
+/// Measurements of a single phase of a [`Report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    /// Number of input bytes processed.
+    pub bytes: u64,
+    /// Total time taken.
+    pub elapsed: Duration,
+    /// Allocations made, if [`CountingAllocator`] is installed.
+    pub allocations: Option<Allocations>,
+}
+
+/// Allocations counted by [`CountingAllocator`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Allocations {
+    /// Number of calls to allocate or reallocate memory.
+    pub count: u64,
+    /// Total number of bytes requested by those calls.
+    pub bytes: u64,
+}
+
+/// A global allocator that counts allocations, for use by [`run()`].
+///
+/// Memory is allocated by the [`System`] allocator.
+pub struct CountingAllocator;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATION_COUNT: AtomicU64 = AtomicU64::new(0);
+static ALLOCATION_BYTES: AtomicU64 = AtomicU64::new(0);
+
+//======================================
+// Impls
+//======================================
+
+impl Phase {
+    /// Throughput of this phase, in megabytes (10⁶ bytes) per second.
+    pub fn mb_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+
+        if secs == 0.0 {
+            return f64::INFINITY;
+        }
+
+        self.bytes as f64 / 1e6 / secs
+    }
+}
+
+impl Allocations {
+    fn now() -> Option<Self> {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        Some(Allocations {
+            count: ALLOCATION_COUNT.load(Ordering::Relaxed),
+            bytes: ALLOCATION_BYTES.load(Ordering::Relaxed),
+        })
+    }
+
+    fn since(start: Option<Self>) -> Option<Self> {
+        let (start, end) = (start?, Allocations::now()?);
+
+        Some(Allocations {
+            count: end.count - start.count,
+            bytes: end.bytes - start.bytes,
+        })
+    }
+}
+
+impl CountingAllocator {
+    fn count(size: usize) {
+        INSTALLED.store(true, Ordering::Relaxed);
+        ALLOCATION_COUNT.fetch_add(1, Ordering::Relaxed);
+        ALLOCATION_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        CountingAllocator::count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        CountingAllocator::count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        CountingAllocator::count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} files, {} bytes", self.files.len(), self.bytes)?;
+
+        for (name, phase) in [
+            ("tokenize", &self.tokenize),
+            ("parse", &self.parse),
+            ("abstract", &self.abstract_),
+        ] {
+            write!(
+                f,
+                "{name:<10} {:>10.3?} {:>10.1} MB/s",
+                phase.elapsed,
+                phase.mb_per_sec()
+            )?;
+
+            if let Some(Allocations { count, bytes }) = phase.allocations {
+                write!(f, " {count:>12} allocs {bytes:>14} bytes")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+//======================================
+// Running
+//======================================
+
+/// Benchmark every source file in `corpus_dir` and its subdirectories, using
+/// the default [`ParseOptions`].
+///
+/// Files are recognized by their extension, see
+/// [`SOURCE_EXTENSIONS`][testing::SOURCE_EXTENSIONS].
+pub fn run<P: AsRef<Path>>(corpus_dir: P) -> io::Result<Report> {
+    run_with_options(corpus_dir, &ParseOptions::default())
+}
+
+/// Benchmark every source file in `corpus_dir` and its subdirectories, using
+/// `opts`.
+///
+/// Files are read into memory before any phase is timed, so file system
+/// access is not included in the measurements. Each phase processes every
+/// file once.
+pub fn run_with_options<P: AsRef<Path>>(
+    corpus_dir: P,
+    opts: &ParseOptions,
+) -> io::Result<Report> {
+    let mut files = Vec::new();
+
+    testing::collect_source_files(corpus_dir.as_ref(), &mut files)?;
+
+    let inputs = files
+        .iter()
+        .map(std::fs::read)
+        .collect::<io::Result<Vec<Vec<u8>>>>()?;
+
+    let bytes = inputs.iter().map(|input| input.len() as u64).sum();
+
+    let tokenize = measure(bytes, || {
+        for input in &inputs {
+            // Inputs with an unsafe character encoding are still counted.
+            let _ = tokenize_bytes(input, opts);
+        }
+    });
+
+    let parse = measure(bytes, || {
+        for input in &inputs {
+            let _ = parse_bytes_cst_seq(input, opts);
+        }
+    });
+
+    // Parse outside of the measurement, so only abstraction is timed.
+    let csts: Vec<_> = inputs
+        .iter()
+        .map(|input| parse_bytes_cst_seq(input, opts).syntax)
+        .collect();
+
+    let abstract_ = measure(bytes, || {
+        for cst in csts {
+            let NodeSeq(nodes) = aggregate_cst_seq(cst);
+
+            for node in nodes {
+                let _ = abstract_cst(node, opts.quirk_settings);
+            }
+        }
+    });
+
+    Ok(Report {
+        files,
+        bytes,
+        tokenize,
+        parse,
+        abstract_,
+    })
+}
+
+fn measure<F: FnOnce()>(bytes: u64, phase: F) -> Phase {
+    let allocations = Allocations::now();
+    let start = Instant::now();
+
+    phase();
+
+    let elapsed = start.elapsed();
+
+    Phase {
+        bytes,
+        elapsed,
+        allocations: Allocations::since(allocations),
+    }
+}
@@ -1,7 +1,19 @@
-use std::io::{self, Write};
+use std::{
+    fmt::Display,
+    io::{self, IsTerminal, Write},
+    time::Instant,
+};
 
 use wolfram_parser::{
-    fmt_as_expr::FmtAsExpr, ParseOptions, QuirkSettings, StringifyMode,
+    cli::{Envelope, FileResult},
+    display_tree::DisplayTree,
+    fmt_as_expr::FmtAsExpr,
+    format::{self, FormatOptions},
+    issue::{self, Issue, Severity},
+    lint::{self, LintConfig},
+    report::{self, Theme},
+    source::{LineMap, Source, Span},
+    ParseOptions, QuirkSettings, StringifyMode,
 };
 
 
@@ -17,6 +29,10 @@ enum ApiMode {
     Tokenize,
     Leaf,
     SafeString,
+    /// Print the input formatted with the default [`FormatOptions`].
+    Format,
+    /// Report syntax issues and [`lint::lint()`] issues in the input.
+    Lint,
 }
 
 #[derive(Copy, Clone)]
@@ -28,10 +44,41 @@ enum OutputMode {
     SyntaxQ,
 }
 
+/// Format of everything printed, selected with `--format`.
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Text,
+    /// Print a [`cli::Envelope`][wolfram_parser::cli::Envelope] as JSON.
+    Json,
+}
+
+#[derive(Copy, Clone)]
+struct Settings {
+    mode: ApiMode,
+    output_mode: OutputMode,
+    format: Format,
+    range: Option<Range>,
+    quirks: QuirkSettings,
+}
+
+/// A range of a document, given as `L1:C1-L2:C2`, for the `format` and
+/// `lint` commands to handle instead of the whole document.
+///
+/// Lines and columns start at 1, columns count characters, and the end
+/// position is exclusive.
+#[derive(Copy, Clone)]
+struct Range {
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
 fn main() {
     let mut file_input = None;
+    let mut explain_input = None;
+    let mut range = None;
     let mut api_mode = ApiMode::CstExpr;
     let mut output_mode = OutputMode::Print;
+    let mut format = Format::Text;
     let mut quirks = QuirkSettings::default();
 
     let args: Vec<String> = std::env::args().skip(1).collect();
@@ -49,6 +96,31 @@ fn main() {
                 i += 1;
                 file_input = Some(args[i].clone());
             },
+            "--range" => {
+                i += 1;
+                range = Some(Range::parse(&args[i]).unwrap_or_else(|| {
+                    fail(format!(
+                        "invalid range: {} (expected L1:C1-L2:C2)",
+                        args[i]
+                    ))
+                }));
+            },
+            "--format" => {
+                i += 1;
+                format = match &*args[i] {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    other => fail(format!(
+                        "invalid format: {other} (expected text or json)"
+                    )),
+                };
+            },
+            "explain" => {
+                i += 1;
+                explain_input = Some(args[i].clone());
+            },
+            "format" => api_mode = ApiMode::Format,
+            "lint" => api_mode = ApiMode::Lint,
             "-tokenize" => api_mode = ApiMode::Tokenize,
             "-leaf" => api_mode = ApiMode::Leaf,
             "-safestring" => api_mode = ApiMode::SafeString,
@@ -61,32 +133,76 @@ fn main() {
             "--flatten-times" => {
                 quirks.flatten_times = true;
             },
-            _ => panic!("unrecognized argument: {arg}"),
+            "--legacy-prefix-minus" => {
+                quirks.legacy_prefix_minus = true;
+            },
+            _ => fail(format!("unrecognized argument: {arg}")),
         }
 
         i += 1;
     }
 
-    let result = match file_input {
-        Some(file_input) => {
-            read_file(&file_input, api_mode, output_mode, quirks)
+    if range.is_some() && !matches!(api_mode, ApiMode::Format | ApiMode::Lint) {
+        fail("--range is only supported by the format and lint commands");
+    }
+
+    let settings = Settings {
+        mode: api_mode,
+        output_mode,
+        format,
+        range,
+        quirks,
+    };
+
+    if let Some(input) = explain_input {
+        let start = Instant::now();
+
+        let mut opts = ParseOptions::default();
+        opts.quirk_settings = quirks;
+
+        let explanation = wolfram_parser::explain::explain(&input, &opts);
+
+        match format {
+            Format::Text => print!("{explanation}"),
+            Format::Json => {
+                let mut result =
+                    FileResult::new(None, Some(explanation.to_string()));
+                result.elapsed = start.elapsed();
+
+                let mut envelope = settings.envelope("explain");
+                envelope.results.push(result);
+                envelope.elapsed = start.elapsed();
+
+                println!("{}", envelope.to_json());
+            },
+        }
+
+        return;
+    }
+
+    let result = match (file_input, range) {
+        (Some(file_input), None) => read_file(&file_input, settings),
+        (None, None) => read_std_in(settings),
+        (file_input, Some(range)) => {
+            read_range(file_input.as_deref(), range, settings)
         },
-        None => read_std_in(api_mode, output_mode, quirks),
     };
 
     return result;
 }
 
-fn read_std_in(mode: ApiMode, output_mode: OutputMode, quirks: QuirkSettings) {
+fn read_std_in(settings: Settings) {
     loop {
         let mut input = String::new();
 
-        print!(">>> ");
-        io::stdout().flush().unwrap();
+        if settings.format == Format::Text {
+            print!(">>> ");
+            io::stdout().flush().unwrap();
+        }
 
         io::stdin().read_line(&mut input).unwrap();
 
-        handle(input.trim_end().as_bytes(), mode, output_mode, quirks)
+        report(None, input.trim_end().as_bytes(), settings)
     }
 
     // #if DIAGNOSTICS
@@ -94,34 +210,134 @@ fn read_std_in(mode: ApiMode, output_mode: OutputMode, quirks: QuirkSettings) {
     // #endif // DIAGNOSTICS
 }
 
-fn read_file(
-    file: &str,
-    mode: ApiMode,
-    output_mode: OutputMode,
-    quirks: QuirkSettings,
-) {
-    let fb: Vec<u8> = std::fs::read(file).expect("error reading file");
+fn read_file(file: &str, settings: Settings) {
+    let fb: Vec<u8> = std::fs::read(file)
+        .unwrap_or_else(|err| fail(format!("{file}: {err}")));
 
-    handle(fb.as_slice(), mode, output_mode, quirks)
+    report(Some(file), fb.as_slice(), settings)
 
     // #if DIAGNOSTICS
     //     DiagnosticsPrint();
     // #endif // DIAGNOSTICS
 }
 
+/// Handle only the part of a document inside `range`, reading the complete
+/// document from `file`, or from stdin if `file` is `None`.
+///
+/// The sources of issues are spans of the complete document.
+fn read_range(file: Option<&str>, range: Range, settings: Settings) {
+    let document = match file {
+        Some(file) => std::fs::read_to_string(file)
+            .unwrap_or_else(|err| fail(format!("{file}: {err}"))),
+        None => io::read_to_string(io::stdin())
+            .unwrap_or_else(|err| fail(format!("error reading stdin: {err}"))),
+    };
+
+    let Some(offsets) = range.offsets(&document) else {
+        fail(format!("range is outside of the input: {range}"));
+    };
+
+    let start = Instant::now();
+
+    let input = &document[offsets.clone()];
+
+    let (text, mut issues) = handle(
+        input.as_bytes(),
+        settings.mode,
+        settings.quirks,
+        settings.ansi(),
+    );
+
+    let mut opts = ParseOptions::default();
+    opts.quirk_settings = settings.quirks;
+
+    let spans = RangeSpans {
+        offset: offsets.start,
+        range: LineMap::new(input, &opts),
+        document: LineMap::new(&document, &opts),
+    };
+
+    for issue in &mut issues {
+        spans.issue(issue);
+    }
+
+    print_result(file, document.as_bytes(), text, issues, start, settings)
+}
+
+/// Handle `input`, and print the result in the selected [`Format`].
+fn report(file: Option<&str>, input: &[u8], settings: Settings) {
+    let start = Instant::now();
+
+    let (text, issues) =
+        handle(input, settings.mode, settings.quirks, settings.ansi());
+
+    print_result(file, input, text, issues, start, settings)
+}
+
+/// Print the text output and issues of `input`, which was handled since
+/// `start`, in the selected [`Format`].
+fn print_result(
+    file: Option<&str>,
+    input: &[u8],
+    text: String,
+    issues: Vec<Issue>,
+    start: Instant,
+    settings: Settings,
+) {
+    match settings.format {
+        Format::Text => {
+            if !text.is_empty() {
+                output(settings.output_mode, text.trim_end_matches('\n'));
+            }
+
+            if !issues.is_empty() {
+                let input = String::from_utf8_lossy(input);
+
+                let mut opts = ParseOptions::default();
+                opts.quirk_settings = settings.quirks;
+
+                let report =
+                    report::render(&input, &issues, &opts, &Theme::default());
+
+                output(settings.output_mode, report.trim_end());
+                output(settings.output_mode, issue::summarize(&issues));
+            }
+        },
+        Format::Json => {
+            let text = match (settings.output_mode, settings.mode) {
+                (OutputMode::SyntaxQ, _) | (_, ApiMode::Lint) => None,
+                _ => Some(text),
+            };
+
+            let mut result = FileResult::new(file.map(str::to_owned), text);
+            result.success =
+                !issues.iter().any(|issue| issue.sev == Severity::Fatal);
+            result.issues = issues;
+            result.elapsed = start.elapsed();
+
+            let mut envelope = settings.envelope(settings.mode.name());
+            envelope.results.push(result);
+            envelope.elapsed = start.elapsed();
+
+            output(settings.output_mode, envelope.to_json());
+        },
+    }
+}
+
+/// Returns the text output for `input` and any issues found in it.
 fn handle(
     input: &[u8],
     mode: ApiMode,
-    output_mode: OutputMode,
     quirks: QuirkSettings,
-) {
+    ansi: bool,
+) -> (String, Vec<Issue>) {
     let mut opts = ParseOptions::default();
     opts.quirk_settings = quirks;
 
     match mode {
         ApiMode::Tokenize => {
             let result = wolfram_parser::tokenize_bytes(input, &opts).unwrap();
-            output(output_mode, FmtAsExpr(&result));
+            (FmtAsExpr(&result).to_string(), Vec::new())
         },
         ApiMode::Leaf => {
             let result = wolfram_parser::parse_to_token(
@@ -129,28 +345,61 @@ fn handle(
                 &opts,
                 StringifyMode::Normal,
             );
-            output(output_mode, FmtAsExpr(&result.syntax));
+            let issues = result.issues().cloned().collect();
+            (FmtAsExpr(&result.syntax).to_string(), issues)
         },
         ApiMode::SafeString => {
             let result = wolfram_parser::safe_string(input, &opts).unwrap();
-            output(output_mode, result);
+            (result.to_owned(), Vec::new())
         },
         ApiMode::CstExpr => {
             let result = wolfram_parser::parse_bytes_cst_seq(input, &opts);
-            output(output_mode, FmtAsExpr(&result.syntax));
+            let issues = result.issues().cloned().collect();
+            (FmtAsExpr(&result.syntax).to_string(), issues)
         },
         ApiMode::Cst => {
             let result = wolfram_parser::parse_bytes_cst_seq(input, &opts);
-            output(output_mode, format!("{:#?}", result.syntax));
+            let issues = result.issues().cloned().collect();
+            (display_tree(result.syntax.display_tree(), ansi), issues)
         },
         ApiMode::Ast => {
             let result = wolfram_parser::parse_bytes_ast_seq(input, &opts);
-            output(output_mode, format!("{:#?}", result.syntax));
+            let issues = result.issues().cloned().collect();
+            (display_tree(result.syntax.display_tree(), ansi), issues)
+        },
+        ApiMode::Format => {
+            let result = wolfram_parser::parse_bytes_cst_seq(input, &opts);
+            let issues = result.issues().cloned().collect();
+
+            let mut text = format::format_cst_seq(
+                &result.syntax,
+                &FormatOptions::default(),
+            );
+
+            // Keep whether the input ends with a newline, so that the output
+            // can replace a range that ends inside of a line.
+            if !input.ends_with(b"\n") {
+                text.truncate(text.trim_end_matches('\n').len());
+            }
+
+            (text, issues)
+        },
+        ApiMode::Lint => {
+            let result = wolfram_parser::parse_bytes_ast_seq(input, &opts);
+            let mut issues: Vec<Issue> = result.issues().cloned().collect();
+            issues.extend(lint::lint(&result.syntax, &LintConfig::default()));
+            (String::new(), issues)
         },
     }
 }
 
-fn output<T: std::fmt::Display>(mode: OutputMode, value: T) {
+fn display_tree(tree: DisplayTree, ansi: bool) -> String {
+    let tree = if ansi { tree.ansi() } else { tree };
+
+    tree.to_string().trim_end().to_owned()
+}
+
+fn output<T: Display>(mode: OutputMode, value: T) {
     match mode {
         OutputMode::Print => {
             println!("{value}");
@@ -163,3 +412,167 @@ fn output<T: std::fmt::Display>(mode: OutputMode, value: T) {
         OutputMode::None | OutputMode::SyntaxQ => {},
     }
 }
+
+/// Print `message` as an error and exit with a non-zero status.
+fn fail(message: impl Display) -> ! {
+    eprintln!("Error: {message}");
+    std::process::exit(1)
+}
+
+//======================================
+// Settings
+//======================================
+
+impl ApiMode {
+    fn name(self) -> &'static str {
+        match self {
+            ApiMode::CstExpr => "cst-expr",
+            ApiMode::Cst => "cst",
+            ApiMode::Ast => "ast",
+            ApiMode::Tokenize => "tokenize",
+            ApiMode::Leaf => "leaf",
+            ApiMode::SafeString => "safe-string",
+            ApiMode::Format => "format",
+            ApiMode::Lint => "lint",
+        }
+    }
+}
+
+impl Settings {
+    /// Whether trees are colored, which they are only when printed as text
+    /// to a terminal.
+    fn ansi(&self) -> bool {
+        self.format == Format::Text && io::stdout().is_terminal()
+    }
+
+    fn envelope(&self, command: &str) -> Envelope {
+        let Settings {
+            mode: _,
+            output_mode,
+            format: _,
+            range,
+            quirks,
+        } = *self;
+
+        let mut envelope = Envelope::new("wolfram-parser", command);
+
+        envelope.option("check", matches!(output_mode, OutputMode::SyntaxQ));
+        envelope.option("range", range.map(|range| range.to_string()));
+        envelope.option("flattenTimes", quirks.flatten_times);
+        envelope.option("legacyPrefixMinus", quirks.legacy_prefix_minus);
+
+        envelope
+    }
+}
+
+//======================================
+// Range
+//======================================
+
+impl Range {
+    fn parse(string: &str) -> Option<Range> {
+        let (start, end) = string.split_once('-')?;
+
+        Some(Range {
+            start: parse_position(start)?,
+            end: parse_position(end)?,
+        })
+    }
+
+    /// Byte offsets of this range in `document`.
+    fn offsets(self, document: &str) -> Option<std::ops::Range<usize>> {
+        let start = byte_offset(document, self.start)?;
+        let end = byte_offset(document, self.end)?;
+
+        (start <= end).then_some(start..end)
+    }
+}
+
+/// Converts spans of the text of a [`Range`], which was handled alone, to
+/// spans of the complete document.
+struct RangeSpans {
+    /// Byte offset of the start of the range in the document.
+    offset: usize,
+    range: LineMap,
+    document: LineMap,
+}
+
+impl RangeSpans {
+    fn span(&self, span: Span) -> Span {
+        let Some(offsets) = self.range.span_offsets(span) else {
+            return span;
+        };
+
+        self.document
+            .span(
+                offsets.start + self.offset..offsets.end + self.offset,
+                span.convention(),
+            )
+            .unwrap_or(span)
+    }
+
+    fn source(&self, source: &mut Source) {
+        if let Source::Span(span) = source {
+            *span = self.span(*span);
+        }
+    }
+
+    fn issue(&self, issue: &mut Issue) {
+        self.source(&mut issue.src);
+
+        for action in &mut issue.actions {
+            action.src = self.span(action.src);
+        }
+
+        for annotation in &mut issue.annotations {
+            self.source(&mut annotation.src);
+        }
+
+        for source in &mut issue.additional_sources {
+            self.source(source);
+        }
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let Range {
+            start: (line1, column1),
+            end: (line2, column2),
+        } = *self;
+
+        write!(f, "{line1}:{column1}-{line2}:{column2}")
+    }
+}
+
+fn parse_position(string: &str) -> Option<(usize, usize)> {
+    let (line, column) = string.split_once(':')?;
+
+    let line: usize = line.parse().ok()?;
+    let column: usize = column.parse().ok()?;
+
+    (line >= 1 && column >= 1).then_some((line, column))
+}
+
+/// Byte offset of the character at `(line, column)` in `document`.
+///
+/// The position just past the last character of a line is allowed.
+fn byte_offset(
+    document: &str,
+    (line, column): (usize, usize),
+) -> Option<usize> {
+    let line_start = match line {
+        1 => 0,
+        _ => document.match_indices('\n').nth(line - 2)?.0 + 1,
+    };
+
+    let line_text = &document[line_start..];
+    let line_end = line_text.find('\n').unwrap_or(line_text.len());
+
+    line_text[..line_end]
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([line_end])
+        .nth(column - 1)
+        .map(|index| line_start + index)
+}
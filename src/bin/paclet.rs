@@ -1,13 +1,31 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgMatches, Command};
+use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use wolfram_parser::cli::{Envelope, FileResult};
 use wolfram_parser::paclet;
 
+type Log = Vec<String>;
+
+/// Extensions of the Wolfram Language source files in a paclet layout
+const SOURCE_EXTENSIONS: &[&str] = &["m", "wl"];
+
 fn main() {
     let matches = Command::new("paclet")
         .version("1.0.0")
         .author("Wolfram Parser Rust")
         .about("Encode and decode Wolfram Language paclet files")
+        .arg(
+            Arg::new("format")
+                .help("Output format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .global(true)
+        )
         .subcommand(
             Command::new("decode")
                 .about("Decode a paclet file to readable Wolfram Language")
@@ -42,6 +60,50 @@ fn main() {
                         .value_name("FILE")
                 )
         )
+        .subcommand(
+            Command::new("decode-dir")
+                .about("Decode every paclet file in a paclet layout, copying all other files")
+                .arg(
+                    Arg::new("input")
+                        .help("Input paclet directory")
+                        .required(true)
+                        .index(1)
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("Output directory (optional, defaults to input with _decoded suffix)")
+                        .short('o')
+                        .long("output")
+                        .value_name("DIR")
+                )
+        )
+        .subcommand(
+            Command::new("encode-dir")
+                .about("Encode every Wolfram Language file in a paclet layout, copying all other files")
+                .arg(
+                    Arg::new("input")
+                        .help("Input paclet directory")
+                        .required(true)
+                        .index(1)
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("Output directory (optional, defaults to input with _encoded suffix)")
+                        .short('o')
+                        .long("output")
+                        .value_name("DIR")
+                )
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Check that decoding and re-encoding a paclet file reproduces it exactly")
+                .arg(
+                    Arg::new("input")
+                        .help("Input paclet file, or a directory to verify every paclet file in")
+                        .required(true)
+                        .index(1)
+                )
+        )
         .subcommand(
             Command::new("check")
                 .about("Check if a file is a paclet and show header information")
@@ -54,67 +116,249 @@ fn main() {
         )
         .get_matches();
 
-    match matches.subcommand() {
-        Some(("decode", sub_matches)) => {
-            let input_path = sub_matches.get_one::<String>("input").unwrap();
-            let output_path = sub_matches
-                .get_one::<String>("output")
-                .map(|s| s.as_str())
-                .unwrap_or_else(|| {
-                    // Default output: input_decoded.m
-                    let path = Path::new(input_path);
-                    let stem = path.file_stem().unwrap().to_str().unwrap();
-                    let extension = path.extension().unwrap_or_default().to_str().unwrap();
-                    Box::leak(format!("{}_decoded.{}", stem, extension).into_boxed_str())
-                });
-
-            if let Err(e) = decode_file(input_path, output_path) {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
+    let json = matches.get_one::<String>("format").map(String::as_str) == Some("json");
+
+    let Some((command, sub_matches)) = matches.subcommand() else {
+        eprintln!("No subcommand provided. Use --help for usage information.");
+        std::process::exit(1);
+    };
+
+    let input_path = sub_matches.get_one::<String>("input").unwrap();
+    let output_path = output_path(command, sub_matches);
+
+    let start = Instant::now();
+
+    let processed = match command {
+        "decode" => Ok(vec![process(input_path, |log| decode_file(input_path, output_path.as_deref().unwrap(), log))]),
+        "encode" => Ok(vec![process(input_path, |log| encode_file(input_path, output_path.as_deref().unwrap(), log))]),
+        "check" => Ok(vec![process(input_path, |log| check_file(input_path, log))]),
+        "decode-dir" => map_dir(input_path, output_path.as_deref().unwrap(), is_paclet_file, decode_file),
+        "encode-dir" => map_dir(input_path, output_path.as_deref().unwrap(), |path| !is_paclet_file(path), encode_file),
+        "verify" => verify_path(input_path),
+        _ => unreachable!("unknown subcommand: {command}"),
+    };
+
+    let processed = match processed {
+        Ok(processed) => processed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let failures = processed.iter().filter(|p| p.result.is_err()).count();
+
+    if json {
+        let mut envelope = Envelope::new("paclet", command);
+        envelope.option("output", output_path);
+
+        for p in processed {
+            let mut file_result = match p.result {
+                Ok(()) => FileResult::new(Some(p.file), None),
+                Err(e) => FileResult::error(Some(p.file), e.to_string()),
+            };
+            file_result.output = Some(p.log.join("\n"));
+            file_result.elapsed = p.elapsed;
+
+            envelope.results.push(file_result);
         }
-        Some(("encode", sub_matches)) => {
-            let input_path = sub_matches.get_one::<String>("input").unwrap();
-            let output_path = sub_matches
-                .get_one::<String>("output")
-                .map(|s| s.as_str())
-                .unwrap_or_else(|| {
-                    // Default output: input_encoded.m
-                    let path = Path::new(input_path);
-                    let stem = path.file_stem().unwrap().to_str().unwrap();
-                    let extension = path.extension().unwrap_or_default().to_str().unwrap();
-                    Box::leak(format!("{}_encoded.{}", stem, extension).into_boxed_str())
-                });
-
-            if let Err(e) = encode_file(input_path, output_path) {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+
+        envelope.elapsed = start.elapsed();
+
+        println!("{}", envelope.to_json());
+    } else {
+        let count = processed.len();
+
+        for p in &processed {
+            for line in &p.log {
+                println!("{}", line);
             }
+
+            if let Err(e) = &p.result {
+                eprintln!("Error: {}: {}", p.file, e);
+            }
+        }
+
+        if command.ends_with("-dir") || command == "verify" {
+            println!("{} file(s) processed, {} failed", count, failures);
         }
-        Some(("check", sub_matches)) => {
-            let input_path = sub_matches.get_one::<String>("input").unwrap();
-            
-            if let Err(e) = check_file(input_path) {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+    }
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// The output of processing a single file
+struct Processed {
+    file: String,
+    log: Log,
+    result: Result<(), Box<dyn Error>>,
+    elapsed: Duration,
+}
+
+/// Run `f` on `file`, collecting the messages it logs and the time taken
+fn process<F>(file: &str, f: F) -> Processed
+where
+    F: FnOnce(&mut Log) -> Result<(), Box<dyn Error>>,
+{
+    let start = Instant::now();
+    let mut log = Log::new();
+
+    let result = f(&mut log);
+
+    Processed {
+        file: file.to_string(),
+        log,
+        result,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// The output path given with `--output`, or the default output path for
+/// `command`, if it writes output.
+fn output_path(command: &str, sub_matches: &ArgMatches) -> Option<String> {
+    let suffix = match command {
+        "decode" | "decode-dir" => "decoded",
+        "encode" | "encode-dir" => "encoded",
+        _ => return None,
+    };
+
+    if let Some(output) = sub_matches.get_one::<String>("output") {
+        return Some(output.clone());
+    }
+
+    let input_path = sub_matches.get_one::<String>("input").unwrap();
+    let path = Path::new(input_path);
+
+    if command.ends_with("-dir") {
+        // Default output: a sibling directory named input_decoded or input_encoded
+        let mut dir = path.as_os_str().to_owned();
+        dir.push(format!("_{}", suffix));
+        return Some(dir.to_string_lossy().into_owned());
+    }
+
+    // Default output: input_decoded.m or input_encoded.m
+    let stem = path.file_stem().unwrap().to_str().unwrap();
+    let extension = path.extension().unwrap_or_default().to_str().unwrap();
+    Some(format!("{}_{}.{}", stem, suffix, extension))
+}
+
+//======================================
+// Directories
+//======================================
+
+/// Apply `f` to every Wolfram Language source file in the `input_dir` paclet
+/// layout for which `select` returns true, writing the results to the same
+/// relative paths in `output_dir`.
+///
+/// All other files are copied unchanged, so that `output_dir` is a complete
+/// copy of the layout.
+fn map_dir(
+    input_dir: &str,
+    output_dir: &str,
+    select: fn(&Path) -> bool,
+    f: fn(&str, &str, &mut Log) -> Result<(), Box<dyn Error>>,
+) -> Result<Vec<Processed>, Box<dyn Error>> {
+    let input_dir = Path::new(input_dir);
+    let output_dir = Path::new(output_dir);
+
+    if output_dir.starts_with(input_dir) {
+        return Err("Output directory must not be inside the input directory".into());
+    }
+
+    let mut processed = Vec::new();
+
+    for path in collect_files(input_dir)? {
+        let output_path = output_dir.join(path.strip_prefix(input_dir)?);
+        let input = path.to_string_lossy().into_owned();
+        let output = output_path.to_string_lossy().into_owned();
+
+        processed.push(process(&input, |log| {
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
             }
+
+            if is_source_file(&path) && select(&path) {
+                f(&input, &output, log)
+            } else {
+                fs::copy(&path, &output_path)?;
+                log.push(format!("Copied: {} -> {}", input, output));
+                Ok(())
+            }
+        }));
+    }
+
+    Ok(processed)
+}
+
+/// Verify `path`, or every encoded source file in `path` if it is a directory.
+fn verify_path(path: &str) -> Result<Vec<Processed>, Box<dyn Error>> {
+    if !Path::new(path).is_dir() {
+        return Ok(vec![process(path, |log| verify_file(path, log))]);
+    }
+
+    let mut processed = Vec::new();
+
+    for file in collect_files(Path::new(path))? {
+        if !is_source_file(&file) || !is_paclet_file(&file) {
+            continue;
         }
-        _ => {
-            eprintln!("No subcommand provided. Use --help for usage information.");
-            std::process::exit(1);
+
+        let file = file.to_string_lossy().into_owned();
+
+        processed.push(process(&file, |log| verify_file(&file, log)));
+    }
+
+    Ok(processed)
+}
+
+/// Paths of all files in `dir` and its subdirectories, in sorted order
+fn collect_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+
+    entries.sort();
+
+    let mut files = Vec::new();
+
+    for path in entries {
+        if path.is_dir() {
+            files.extend(collect_files(&path)?);
+        } else {
+            files.push(path);
         }
     }
+
+    Ok(files)
+}
+
+fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
 }
 
-fn decode_file(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Decoding paclet file: {} -> {}", input_path, output_path);
+fn is_paclet_file(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|content| paclet::detect_paclet_header(&content).is_some())
+        .unwrap_or(false)
+}
+
+//======================================
+// Files
+//======================================
+
+fn decode_file(input_path: &str, output_path: &str, log: &mut Log) -> Result<(), Box<dyn Error>> {
+    log.push(format!("Decoding paclet file: {} -> {}", input_path, output_path));
     
     // Read input file
     let content = fs::read_to_string(input_path)?;
     
     // Check if it's a paclet
     if let Some(header) = paclet::detect_paclet_header(&content) {
-        println!("Detected paclet header: version {}, variant {}", header.version, header.variant);
+        log.push(format!("Detected paclet header: version {}, variant {}", header.version, header.variant));
         
         if !header.is_supported() {
             return Err(format!("Unsupported paclet version {}{}", header.version, header.variant).into());
@@ -126,9 +370,9 @@ fn decode_file(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::e
         // Write decoded content
         fs::write(output_path, decoded)?;
         
-        println!("✅ Successfully decoded paclet file");
-        println!("   Input size: {} bytes", content.len());
-        println!("   Output size: {} bytes", fs::metadata(output_path)?.len());
+        log.push("✅ Successfully decoded paclet file".to_string());
+        log.push(format!("   Input size: {} bytes", content.len()));
+        log.push(format!("   Output size: {} bytes", fs::metadata(output_path)?.len()));
         
     } else {
         return Err("Input file is not a paclet (no valid paclet header found)".into());
@@ -137,71 +381,79 @@ fn decode_file(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::e
     Ok(())
 }
 
-fn encode_file(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Encoding file as paclet: {} -> {}", input_path, output_path);
-    
-    // For now, we'll use the external encoder from the paclet-decode directory
-    // In the future, we could integrate the Rust encoder directly
-    
-    let paclet_dir = "/Users/David.Girardo/Documents/wolfram-parser-rust/paclet-decode";
-    let encoder_path = format!("{}/encoder", paclet_dir);
-    
-    // Check if encoder exists
-    if !Path::new(&encoder_path).exists() {
-        // Try to compile it
-        println!("Compiling Rust encoder...");
-        let compile_result = std::process::Command::new("rustc")
-            .args(&[
-                &format!("{}/encoder.rs", paclet_dir),
-                "-o", &encoder_path
-            ])
-            .current_dir(paclet_dir)
-            .output()?;
-            
-        if !compile_result.status.success() {
-            return Err(format!("Failed to compile encoder: {}", 
-                String::from_utf8_lossy(&compile_result.stderr)).into());
-        }
+fn encode_file(input_path: &str, output_path: &str, log: &mut Log) -> Result<(), Box<dyn Error>> {
+    log.push(format!("Encoding file as paclet: {} -> {}", input_path, output_path));
+    
+    let content = fs::read_to_string(input_path)?;
+    
+    if paclet::detect_paclet_header(&content).is_some() {
+        return Err("Input file is already a paclet".into());
     }
     
-    // Run the encoder
-    println!("Running encoder...");
-    let result = std::process::Command::new(&encoder_path)
-        .args(&[input_path, output_path])
-        .output()?;
+    let encoded = paclet::encode_paclet(&content)?;
+    
+    fs::write(output_path, encoded)?;
+    
+    log.push("✅ Successfully encoded file as paclet".to_string());
+    log.push(format!("   Input size: {} bytes", content.len()));
+    log.push(format!("   Output size: {} bytes", fs::metadata(output_path)?.len()));
+    
+    Ok(())
+}
+
+/// Decode a paclet file, re-encode it, and check that the result is
+/// byte-for-byte identical to the original file.
+fn verify_file(input_path: &str, log: &mut Log) -> Result<(), Box<dyn Error>> {
+    log.push(format!("Verifying paclet file: {}", input_path));
+    
+    let content = fs::read_to_string(input_path)?;
+    
+    let decoded = paclet::decode_paclet(&content)?;
+    let reencoded = paclet::encode_paclet(&decoded)?;
+    
+    if reencoded != content {
+        let offset = content
+            .bytes()
+            .zip(reencoded.bytes())
+            .position(|(a, b)| a != b)
+            .unwrap_or(content.len().min(reencoded.len()));
+        
+        // Distinguish a different layout of the encoded body from a decoding error
+        let decodes_equal = paclet::decode_paclet(&reencoded)? == decoded;
         
-    if !result.status.success() {
-        return Err(format!("Encoder failed: {}", 
-            String::from_utf8_lossy(&result.stderr)).into());
+        return Err(format!(
+            "Re-encoded file differs from the original at byte {} ({})",
+            offset,
+            if decodes_equal { "decoded content is identical" } else { "decoded content differs" }
+        ).into());
     }
     
-    println!("✅ Successfully encoded file as paclet");
-    println!("   Input size: {} bytes", fs::metadata(input_path)?.len());
-    println!("   Output size: {} bytes", fs::metadata(output_path)?.len());
+    log.push("✅ Re-encoded file is identical".to_string());
+    log.push(format!("   Decoded size: {} bytes", decoded.len()));
     
     Ok(())
 }
 
-fn check_file(input_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Checking file: {}", input_path);
+fn check_file(input_path: &str, log: &mut Log) -> Result<(), Box<dyn Error>> {
+    log.push(format!("Checking file: {}", input_path));
     
     let content = fs::read_to_string(input_path)?;
     let file_size = content.len();
     
-    println!("File size: {} bytes", file_size);
+    log.push(format!("File size: {} bytes", file_size));
     
     if let Some(header) = paclet::detect_paclet_header(&content) {
-        println!("🎯 This is a paclet file!");
-        println!("   Header version: {}", header.version);
-        println!("   Header variant: {}", header.variant);
-        println!("   Supported: {}", if header.is_supported() { "✅ Yes" } else { "❌ No" });
+        log.push("🎯 This is a paclet file!".to_string());
+        log.push(format!("   Header version: {}", header.version));
+        log.push(format!("   Header variant: {}", header.variant));
+        log.push(format!("   Supported: {}", if header.is_supported() { "✅ Yes" } else { "❌ No" }));
         
         if header.is_supported() {
             // Try to decode and show stats
             match paclet::decode_paclet(&content) {
                 Ok(decoded) => {
-                    println!("   Decoded size: {} bytes", decoded.len());
-                    println!("   Compression ratio: {:.1}x", file_size as f64 / decoded.len() as f64);
+                    log.push(format!("   Decoded size: {} bytes", decoded.len()));
+                    log.push(format!("   Compression ratio: {:.1}x", file_size as f64 / decoded.len() as f64));
                     
                     // Show a preview of the decoded content
                     let preview = if decoded.len() > 200 {
@@ -209,15 +461,15 @@ fn check_file(input_path: &str) -> Result<(), Box<dyn std::error::Error>> {
                     } else {
                         decoded
                     };
-                    println!("   Preview: {}", preview.replace('\n', "\\n"));
+                    log.push(format!("   Preview: {}", preview.replace('\n', "\\n")));
                 }
                 Err(e) => {
-                    println!("   ❌ Failed to decode: {}", e);
+                    log.push(format!("   ❌ Failed to decode: {}", e));
                 }
             }
         }
     } else {
-        println!("📄 This is a regular Wolfram Language file (not a paclet)");
+        log.push("📄 This is a regular Wolfram Language file (not a paclet)".to_string());
         
         // Show a preview of the content
         let preview = if content.len() > 200 {
@@ -225,7 +477,7 @@ fn check_file(input_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         } else {
             content
         };
-        println!("   Preview: {}", preview.replace('\n', "\\n"));
+        log.push(format!("   Preview: {}", preview.replace('\n', "\\n")));
     }
     
     Ok(())
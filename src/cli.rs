@@ -0,0 +1,331 @@
+//! Machine-readable output of the command line tools of this crate.
+//!
+//! Every subcommand of the `wolfram-parser` and `paclet` binaries accepts
+//! `--format json`, which prints a single [`Envelope`] as JSON instead of the
+//! human-readable output:
+//!
+//! ```json
+//! {
+//!   "tool": "paclet",
+//!   "version": "0.1.0",
+//!   "command": "decode",
+//!   "options": {"output": "out.m"},
+//!   "success": true,
+//!   "results": [
+//!     {
+//!       "file": "in.m",
+//!       "success": true,
+//!       "output": "...",
+//!       "issues": [],
+//!       "timeMs": 1.25
+//!     }
+//!   ],
+//!   "summary": {"total": 0, "bySeverity": [], "byTag": [], "byRegion": []},
+//!   "timeMs": 1.5
+//! }
+//! ```
+//!
+//! Each issue is an object with `tag`, `severity`, `message`, `source`, and
+//! `annotations` fields, where `source` is
+//! `{"start": [line, column], "end": [line, column]}` for issues with a source
+//! span, and `annotations` is a list of `{"kind", "text", "source"}` objects
+//! with additional information about the issue.
+//!
+//! `summary` counts the issues of every result, as computed by
+//! [`issue::summarize()`][crate::issue::summarize]. Its lists are ranked,
+//! most frequent first, and contain objects like
+//! `{"severity": "Warning", "count": 2}`, `{"tag": "SyntaxError", "count": 1}`,
+//! and `{"startLine": 1, "endLine": 100, "count": 3}`.
+//!
+//! ```
+//! use wolfram_parser::cli::{Envelope, FileResult};
+//!
+//! let mut envelope = Envelope::new("wolfram-parser", "cst");
+//! envelope.option("flattenTimes", false);
+//! envelope.results.push(FileResult::new(None, Some("1 + 2".into())));
+//!
+//! assert!(envelope.to_json().starts_with(r#"{"tool":"wolfram-parser""#));
+//! ```
+
+use std::time::Duration;
+
+use crate::{
+    issue::{self, Annotation, Issue, IssueSummary},
+    json::Json,
+    source::{BoxPosition, Source},
+};
+
+/// The JSON output of a single command line tool invocation.
+#[derive(Debug, Clone)]
+pub struct Envelope {
+    /// Name of the binary, e.g. `"paclet"`.
+    pub tool: String,
+    /// The subcommand or mode that was run.
+    pub command: String,
+    /// The options the command was run with, in the order they were given.
+    pub options: Vec<(String, OptionValue)>,
+    /// One result for each input processed by the command.
+    pub results: Vec<FileResult>,
+    /// Total time taken by the command.
+    pub elapsed: Duration,
+}
+
+/// The result of processing a single input.
+#[derive(Debug, Clone)]
+pub struct FileResult {
+    /// Path of the input, or `None` if it was read from stdin or given on the
+    /// command line.
+    pub file: Option<String>,
+    /// Whether processing this input succeeded.
+    pub success: bool,
+    /// The output the command would have printed for this input in text mode.
+    pub output: Option<String>,
+    /// Error message, if processing this input failed.
+    pub error: Option<String>,
+    pub issues: Vec<Issue>,
+    pub elapsed: Duration,
+}
+
+/// The value of a command line option recorded in an [`Envelope`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionValue {
+    Null,
+    Bool(bool),
+    String(String),
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Envelope {
+    pub fn new(tool: &str, command: &str) -> Self {
+        Envelope {
+            tool: tool.to_owned(),
+            command: command.to_owned(),
+            options: Vec::new(),
+            results: Vec::new(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Record the value of the command line option `name`.
+    pub fn option<V: Into<OptionValue>>(&mut self, name: &str, value: V) {
+        self.options.push((name.to_owned(), value.into()));
+    }
+
+    /// Summarize the issues of every result.
+    pub fn summary(&self) -> IssueSummary {
+        let issues: Vec<Issue> = self
+            .results
+            .iter()
+            .flat_map(|result| result.issues.iter().cloned())
+            .collect();
+
+        issue::summarize(&issues)
+    }
+
+    /// Returns true if every result succeeded.
+    pub fn success(&self) -> bool {
+        self.results.iter().all(|result| result.success)
+    }
+
+    /// Serialize this envelope as compact JSON.
+    pub fn to_json(&self) -> String {
+        let Envelope {
+            tool,
+            command,
+            options,
+            results,
+            elapsed,
+        } = self;
+
+        let options = options
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    OptionValue::Null => Json::Null,
+                    OptionValue::Bool(value) => Json::from(*value),
+                    OptionValue::String(value) => Json::from(value.as_str()),
+                };
+
+                (name.clone(), value)
+            })
+            .collect();
+
+        Json::object([
+            ("tool", Json::from(tool.as_str())),
+            ("version", Json::from(env!("CARGO_PKG_VERSION"))),
+            ("command", Json::from(command.as_str())),
+            ("options", Json::Object(options)),
+            ("success", Json::from(self.success())),
+            (
+                "results",
+                Json::Array(results.iter().map(result_to_json).collect()),
+            ),
+            ("summary", summary_to_json(&self.summary())),
+            ("timeMs", duration_to_json(*elapsed)),
+        ])
+        .to_string()
+    }
+}
+
+impl FileResult {
+    /// A successful result with no issues.
+    pub fn new(file: Option<String>, output: Option<String>) -> Self {
+        FileResult {
+            file,
+            success: true,
+            output,
+            error: None,
+            issues: Vec::new(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// A failed result.
+    pub fn error(file: Option<String>, error: String) -> Self {
+        FileResult {
+            file,
+            success: false,
+            output: None,
+            error: Some(error),
+            issues: Vec::new(),
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl From<bool> for OptionValue {
+    fn from(value: bool) -> Self {
+        OptionValue::Bool(value)
+    }
+}
+
+impl From<&str> for OptionValue {
+    fn from(value: &str) -> Self {
+        OptionValue::String(value.to_owned())
+    }
+}
+
+impl From<String> for OptionValue {
+    fn from(value: String) -> Self {
+        OptionValue::String(value)
+    }
+}
+
+impl<T: Into<OptionValue>> From<Option<T>> for OptionValue {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => OptionValue::Null,
+        }
+    }
+}
+
+//======================================
+// Helpers
+//======================================
+
+fn result_to_json(result: &FileResult) -> Json {
+    let FileResult {
+        file,
+        success,
+        output,
+        error,
+        issues,
+        elapsed,
+    } = result;
+
+    let mut fields = vec![
+        ("file", Json::from(file.clone())),
+        ("success", Json::from(*success)),
+        ("output", Json::from(output.clone())),
+    ];
+
+    if let Some(error) = error {
+        fields.push(("error", Json::from(error.as_str())));
+    }
+
+    fields.push((
+        "issues",
+        Json::Array(issues.iter().map(issue_to_json).collect()),
+    ));
+    fields.push(("timeMs", duration_to_json(*elapsed)));
+
+    Json::object(fields)
+}
+
+fn issue_to_json(issue: &Issue) -> Json {
+    Json::object([
+        ("tag", Json::from(issue.tag.as_str())),
+        ("severity", Json::from(issue.sev.as_str())),
+        ("message", Json::from(issue.msg.as_str())),
+        ("source", source_to_json(&issue.src)),
+        (
+            "annotations",
+            Json::Array(
+                issue.annotations.iter().map(annotation_to_json).collect(),
+            ),
+        ),
+    ])
+}
+
+fn annotation_to_json(annotation: &Annotation) -> Json {
+    Json::object([
+        ("kind", Json::from(annotation.kind.as_str())),
+        ("text", Json::from(annotation.text.as_str())),
+        ("source", source_to_json(&annotation.src)),
+    ])
+}
+
+fn source_to_json(source: &Source) -> Json {
+    match source {
+        Source::Unknown => Json::Null,
+        Source::Span(span) => Json::from(*span),
+        Source::Box(BoxPosition::At(index)) => Json::from(index.clone()),
+        Source::Box(position) => Json::from(position.to_string()),
+    }
+}
+
+fn summary_to_json(summary: &IssueSummary) -> Json {
+    let IssueSummary {
+        total,
+        by_tag,
+        by_severity,
+        by_region,
+    } = summary;
+
+    let by_severity = by_severity.iter().map(|(sev, count)| {
+        Json::object([
+            ("severity", Json::from(sev.as_str())),
+            ("count", Json::from(*count)),
+        ])
+    });
+
+    let by_tag = by_tag.iter().map(|(tag, count)| {
+        Json::object([
+            ("tag", Json::from(tag.as_str())),
+            ("count", Json::from(*count)),
+        ])
+    });
+
+    let by_region = by_region.iter().map(|(region, count)| {
+        Json::object([
+            ("startLine", Json::from(region.start_line)),
+            ("endLine", Json::from(region.end_line)),
+            ("count", Json::from(*count)),
+        ])
+    });
+
+    Json::object([
+        ("total", Json::from(*total)),
+        ("bySeverity", Json::Array(by_severity.collect())),
+        ("byTag", Json::Array(by_tag.collect())),
+        ("byRegion", Json::Array(by_region.collect())),
+    ])
+}
+
+fn duration_to_json(duration: Duration) -> Json {
+    Json::Float(duration.as_nanos() as f64 / 1_000_000.0)
+}
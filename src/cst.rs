@@ -2,7 +2,11 @@
 //!
 //! [`Cst`] — root and element type in a concrete syntax tree.
 
-mod visit;
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+pub mod render;
+pub mod spans;
+pub mod visit;
 
 use std::fmt::Debug;
 
@@ -19,7 +23,10 @@ use crate::{
         SyntaxErrorKind,
     },
     source::{Source, Span},
-    tokenize::{Token, TokenInput, TokenKind, TokenSource, TokenString},
+    tokenize::{
+        token_kind::{GroupOpenerToCloser, TryGroupOpenerToCloser},
+        Token, TokenInput, TokenKind, TokenSource, TokenString,
+    },
     NodeSeq,
 };
 
@@ -48,6 +55,7 @@ pub struct TriviaSeq<I>(pub SmallVec<[Token<I>; 4]>);
 /// A typical [`Cst`] is made up of further child syntax trees. A [`Cst`] tree
 /// terminates at "leaf" variants such as [`Cst::Token`].
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cst<I = TokenString, S = Span> {
     Token(Token<I, S>),
     Call(CallNode<I, S>),
@@ -68,15 +76,47 @@ pub enum Cst<I = TokenString, S = Span> {
     Code(CodeNode<S>),
 }
 
+/// The kind of a [`Cst`] node, without its children, as returned by
+/// [`Cst::kind()`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CstKind {
+    Token(TokenKind),
+    Call,
+    SyntaxError(SyntaxErrorKind),
+    Prefix(PrefixOperator),
+    Infix(InfixOperator),
+    Postfix(PostfixOperator),
+    Binary(BinaryOperator),
+    Ternary(TernaryOperator),
+    PrefixBinary(PrefixBinaryOperator),
+    Compound(CompoundOperator),
+    Group(GroupOperator),
+    GroupMissingCloser(GroupOperator),
+    GroupMissingOpener(GroupOperator),
+    Box(BoxKind),
+    Code,
+}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CodeNode<S = Span> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::expr"))]
     pub first: Expr,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::expr"))]
     pub second: Expr,
     pub src: S,
 }
 
+/// A box, like `SuperscriptBox[x, 2]`, as parsed by
+/// [`parse_box_cst()`][crate::parse_box_cst].
+///
+/// Every kind of box is a `BoxNode`, with the parsed arguments of the box as
+/// its `children`. Script boxes are not separate node kinds: the base is the
+/// first child, followed by the scripts in the order of the arguments of the
+/// box. Use [`BoxNode::as_script()`] to get the base and scripts by role.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoxNode<I = TokenString, S = Span> {
     pub kind: BoxKind,
     pub children: CstSeq<I, S>,
@@ -84,6 +124,7 @@ pub struct BoxNode<I = TokenString, S = Span> {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BoxKind {
     RowBox,
     GridBox,
@@ -96,17 +137,40 @@ pub enum BoxKind {
     NamespaceBox,
     OverscriptBox,
     SubsuperscriptBox,
+    UnderscriptBox,
+    UnderoverscriptBox,
     GraphicsBox,
     // TODO(cleanup):
     //   Make this unnecessary? Try to represent _every_ box kind
     //   as a variant? Or represent all box kinds as a Symbol
     //   field?
     /// Must be a `` System` `` symbol that ends in "Box".
-    Other(wolfram_expr::Symbol),
+    Other(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_impls::symbol")
+        )]
+        wolfram_expr::Symbol,
+    ),
+}
+
+/// The base and scripts of a script box, as returned by
+/// [`BoxNode::as_script()`].
+///
+/// Each script is `Some` if the kind of box has it, like the
+/// [`superscript`][ScriptBox::superscript] of a `SuperscriptBox[base, sup]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptBox<'n, I = TokenString, S = Span> {
+    pub base: &'n Cst<I, S>,
+    pub subscript: Option<&'n Cst<I, S>>,
+    pub superscript: Option<&'n Cst<I, S>>,
+    pub underscript: Option<&'n Cst<I, S>>,
+    pub overscript: Option<&'n Cst<I, S>>,
 }
 
 /// Any kind of prefix, postfix, binary, or infix operator
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OperatorNode<I = TokenString, S = Span, O = InfixOperator> {
     pub op: O,
     pub children: CstSeq<I, S>,
@@ -114,46 +178,54 @@ pub struct OperatorNode<I = TokenString, S = Span, O = InfixOperator> {
 
 /// `-a`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrefixNode<I = TokenString, S = Span>(
     pub OperatorNode<I, S, PrefixOperator>,
 );
 
 /// `a @ b`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BinaryNode<I = TokenString, S = Span>(
     pub OperatorNode<I, S, BinaryOperator>,
 );
 
 /// `a + b + c`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InfixNode<I = TokenString, S = Span>(pub OperatorNode<I, S>);
 
 /// `a /: b = c`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TernaryNode<I = TokenString, S = Span>(
     pub OperatorNode<I, S, TernaryOperator>,
 );
 
 /// `a!`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PostfixNode<I = TokenString, S = Span>(
     pub OperatorNode<I, S, PostfixOperator>,
 );
 
 /// `\[Integral] f \[DifferentialD] x`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrefixBinaryNode<I = TokenString, S = Span>(
     pub OperatorNode<I, S, PrefixBinaryOperator>,
 );
 
 /// `f[x]`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CallNode<I = TokenString, S = Span> {
     pub head: CallHead<I, S>,
     pub body: CallBody<I, S>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CallHead<I, S> {
     /// Concrete Call nodes can have more than one element in `head`, and
     /// serialize as `CallNode[{__}, ..]`
@@ -169,6 +241,7 @@ pub enum CallHead<I, S> {
 
 /// Subset of [`Cst`] variants that are allowed as the body of a [`CallNode`].
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CallBody<I = TokenString, S = Span> {
     Group(GroupNode<I, S, CallOperator>),
     GroupMissingCloser(GroupMissingCloserNode<I, S, CallOperator>),
@@ -176,6 +249,7 @@ pub enum CallBody<I = TokenString, S = Span> {
 
 /// `{x}`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupNode<I = TokenString, S = Span, O = GroupOperator>(
     pub OperatorNode<I, S, O>,
 );
@@ -190,12 +264,14 @@ pub struct GroupNode<I = TokenString, S = Span, O = GroupOperator>(
 /// * `##2`
 /// * `%2`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompoundNode<I = TokenString, S = Span>(
     pub OperatorNode<I, S, CompoundOperator>,
 );
 
 /// A syntax error that contains structure.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SyntaxErrorNode<I = TokenString, S = Span> {
     pub err: SyntaxErrorKind,
     pub children: CstSeq<I, S>,
@@ -203,12 +279,14 @@ pub struct SyntaxErrorNode<I = TokenString, S = Span> {
 
 /// `{]`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupMissingCloserNode<I = TokenString, S = Span, O = GroupOperator>(
     pub OperatorNode<I, S, O>,
 );
 
 /// Only possible with boxes
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupMissingOpenerNode<I = TokenString, S = Span>(
     pub OperatorNode<I, S, GroupOperator>,
 );
@@ -225,6 +303,34 @@ pub struct LeafNode {
     pub src: Source,
 }
 
+/// Operands of a [`TernaryNode`], as returned by [`TernaryNode::operands()`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TernaryOperands<'n, I = TokenString, S = Span> {
+    /// `a` in `a /: b = c` or `a ~f~ b`
+    pub lhs: &'n Cst<I, S>,
+    /// `b` in `a /: b = c`, or `f` in `a ~f~ b`
+    pub mid: &'n Cst<I, S>,
+    /// `c` in `a /: b = c`, or `b` in `a ~f~ b`
+    ///
+    /// This is `None` for `a /: b =.`, which has no right-hand side.
+    pub rhs: Option<&'n Cst<I, S>>,
+}
+
+/// Operands of a `;;` span expression, as returned by
+/// [`Cst::span_operands()`].
+///
+/// Operands that are omitted, like in `;;b` or `a;;`, are
+/// [`TokenKind::Fake_ImplicitOne`] or [`TokenKind::Fake_ImplicitAll`] tokens.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SpanOperands<'n, I = TokenString, S = Span> {
+    /// `a` in `a;;b;;c`
+    pub lhs: &'n Cst<I, S>,
+    /// `b` in `a;;b;;c`
+    pub rhs: &'n Cst<I, S>,
+    /// `c` in `a;;b;;c`, or `None` for `a;;b`
+    pub step: Option<&'n Cst<I, S>>,
+}
+
 //======================================
 // Node convertions
 //======================================
@@ -348,13 +454,22 @@ impl<I, S: TokenSource> CstSeq<I, S> {
 }
 
 impl<I: TokenInput, S> CstSeq<I, S> {
-    pub(crate) fn into_owned_input(self) -> CstSeq<TokenString, S> {
+    /// Copy the input of every token in these trees into an owned
+    /// [`TokenString`].
+    ///
+    /// See [`Cst::into_owned()`].
+    pub fn into_owned(self) -> CstSeq<TokenString, S> {
         let NodeSeq(nodes) = self;
 
-        let nodes = nodes.into_iter().map(Cst::into_owned_input).collect();
+        let nodes = nodes.into_iter().map(Cst::into_owned).collect();
 
         NodeSeq(nodes)
     }
+
+    #[deprecated(note = "renamed to `into_owned()`")]
+    pub fn into_owned_input(self) -> CstSeq<TokenString, S> {
+        self.into_owned()
+    }
 }
 
 //==========================================================
@@ -362,59 +477,76 @@ impl<I: TokenInput, S> CstSeq<I, S> {
 //==========================================================
 
 impl<I: TokenInput, S> Cst<I, S> {
-    pub fn into_owned_input(self) -> Cst<TokenString, S> {
+    /// Copy the input of every token in this tree into an owned
+    /// [`TokenString`].
+    ///
+    /// The result no longer borrows the input it was parsed from, so it can
+    /// be stored, e.g. in a cache, or sent to another thread.
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     cst::Cst, parse_cst, tokenize::TokenString, ParseOptions,
+    /// };
+    ///
+    /// fn parse_owned(input: &str) -> Cst<TokenString> {
+    ///     parse_cst(input, &ParseOptions::default()).syntax.into_owned()
+    /// }
+    ///
+    /// let input = String::from("f[x] + 1");
+    /// let cst = parse_owned(&input);
+    ///
+    /// // The tree outlives the input, and can be sent to another thread.
+    /// drop(input);
+    ///
+    /// let cst = std::thread::spawn(move || cst).join().unwrap();
+    ///
+    /// assert!(matches!(cst, Cst::Infix(_)));
+    /// ```
+    pub fn into_owned(self) -> Cst<TokenString, S> {
         match self {
-            Cst::Token(token) => Cst::Token(token.into_owned_input()),
+            Cst::Token(token) => Cst::Token(token.into_owned()),
             Cst::Call(CallNode { head, body }) => Cst::Call(CallNode {
                 head: match head {
                     CallHead::Concrete(head) => {
-                        CallHead::Concrete(head.into_owned_input())
+                        CallHead::Concrete(head.into_owned())
+                    },
+                    CallHead::Aggregate(head) => {
+                        CallHead::Aggregate(Box::new((*head).into_owned()))
                     },
-                    CallHead::Aggregate(head) => CallHead::Aggregate(Box::new(
-                        (*head).into_owned_input(),
-                    )),
                 },
-                body: body.map_op(|body_op| body_op.into_owned_input()),
+                body: body.map_op(|body_op| body_op.into_owned()),
             }),
             Cst::SyntaxError(SyntaxErrorNode { err, children }) => {
                 Cst::SyntaxError(SyntaxErrorNode {
                     err,
-                    children: children.into_owned_input(),
+                    children: children.into_owned(),
                 })
             },
             Cst::Prefix(PrefixNode(op)) => {
-                Cst::Prefix(PrefixNode(op.into_owned_input()))
-            },
-            Cst::Infix(InfixNode(op)) => {
-                Cst::Infix(InfixNode(op.into_owned_input()))
+                Cst::Prefix(PrefixNode(op.into_owned()))
             },
+            Cst::Infix(InfixNode(op)) => Cst::Infix(InfixNode(op.into_owned())),
             Cst::Postfix(PostfixNode(op)) => {
-                Cst::Postfix(PostfixNode(op.into_owned_input()))
+                Cst::Postfix(PostfixNode(op.into_owned()))
             },
             Cst::Binary(BinaryNode(op)) => {
-                Cst::Binary(BinaryNode(op.into_owned_input()))
+                Cst::Binary(BinaryNode(op.into_owned()))
             },
             Cst::Ternary(TernaryNode(op)) => {
-                Cst::Ternary(TernaryNode(op.into_owned_input()))
+                Cst::Ternary(TernaryNode(op.into_owned()))
             },
             Cst::PrefixBinary(PrefixBinaryNode(op)) => {
-                Cst::PrefixBinary(PrefixBinaryNode(op.into_owned_input()))
+                Cst::PrefixBinary(PrefixBinaryNode(op.into_owned()))
             },
             Cst::Compound(CompoundNode(op)) => {
-                Cst::Compound(CompoundNode(op.into_owned_input()))
-            },
-            Cst::Group(GroupNode(op)) => {
-                Cst::Group(GroupNode(op.into_owned_input()))
+                Cst::Compound(CompoundNode(op.into_owned()))
             },
+            Cst::Group(GroupNode(op)) => Cst::Group(GroupNode(op.into_owned())),
             Cst::GroupMissingCloser(GroupMissingCloserNode(op)) => {
-                Cst::GroupMissingCloser(GroupMissingCloserNode(
-                    op.into_owned_input(),
-                ))
+                Cst::GroupMissingCloser(GroupMissingCloserNode(op.into_owned()))
             },
             Cst::GroupMissingOpener(GroupMissingOpenerNode(op)) => {
-                Cst::GroupMissingOpener(GroupMissingOpenerNode(
-                    op.into_owned_input(),
-                ))
+                Cst::GroupMissingOpener(GroupMissingOpenerNode(op.into_owned()))
             },
             Cst::Box(BoxNode {
                 kind,
@@ -422,12 +554,17 @@ impl<I: TokenInput, S> Cst<I, S> {
                 src,
             }) => Cst::Box(BoxNode {
                 kind,
-                children: children.into_owned_input(),
+                children: children.into_owned(),
                 src,
             }),
             Cst::Code(node) => Cst::Code(node),
         }
     }
+
+    #[deprecated(note = "renamed to `into_owned()`")]
+    pub fn into_owned_input(self) -> Cst<TokenString, S> {
+        self.into_owned()
+    }
 }
 
 
@@ -462,6 +599,125 @@ impl<I, S: TokenSource> Cst<I, S> {
     }
 }
 
+impl<I, S> Cst<I, S> {
+    /// Get the kind of this node, like the token kind of a token, or the
+    /// operator of an operator node.
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     cst::CstKind, parse::operators::InfixOperator, parse_cst,
+    ///     ParseOptions,
+    /// };
+    ///
+    /// let cst = parse_cst("a + b", &ParseOptions::default()).syntax;
+    ///
+    /// assert_eq!(cst.kind(), CstKind::Infix(InfixOperator::Plus));
+    /// ```
+    pub fn kind(&self) -> CstKind {
+        match self {
+            Cst::Token(token) => CstKind::Token(token.tok),
+            Cst::Call(_) => CstKind::Call,
+            Cst::SyntaxError(SyntaxErrorNode { err, children: _ }) => {
+                CstKind::SyntaxError(*err)
+            },
+            Cst::Prefix(PrefixNode(op)) => CstKind::Prefix(op.op),
+            Cst::Infix(InfixNode(op)) => CstKind::Infix(op.op),
+            Cst::Postfix(PostfixNode(op)) => CstKind::Postfix(op.op),
+            Cst::Binary(BinaryNode(op)) => CstKind::Binary(op.op),
+            Cst::Ternary(TernaryNode(op)) => CstKind::Ternary(op.op),
+            Cst::PrefixBinary(PrefixBinaryNode(op)) => {
+                CstKind::PrefixBinary(op.op)
+            },
+            Cst::Compound(CompoundNode(op)) => CstKind::Compound(op.op),
+            Cst::Group(GroupNode(op)) => CstKind::Group(op.op),
+            Cst::GroupMissingCloser(GroupMissingCloserNode(op)) => {
+                CstKind::GroupMissingCloser(op.op)
+            },
+            Cst::GroupMissingOpener(GroupMissingOpenerNode(op)) => {
+                CstKind::GroupMissingOpener(op.op)
+            },
+            Cst::Box(BoxNode { kind, .. }) => CstKind::Box(kind.clone()),
+            Cst::Code(_) => CstKind::Code,
+        }
+    }
+
+    /// If this is a `;;` span expression, like `a;;b` or `a;;b;;c`, get its
+    /// operands.
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     cst::Cst, parse_cst, tokenize::TokenKind, ParseOptions,
+    /// };
+    ///
+    /// let cst = parse_cst(";;b", &ParseOptions::default()).syntax;
+    ///
+    /// let span = cst.span_operands().unwrap();
+    ///
+    /// assert!(matches!(
+    ///     span.lhs,
+    ///     Cst::Token(tok) if tok.tok == TokenKind::Fake_ImplicitOne
+    /// ));
+    /// assert!(span.step.is_none());
+    /// ```
+    pub fn span_operands(&self) -> Option<SpanOperands<'_, I, S>> {
+        match self {
+            Cst::Binary(BinaryNode(OperatorNode {
+                op: BinaryOperator::Span,
+                children,
+            })) => {
+                let [lhs, _, rhs] = non_trivia_children(children);
+
+                Some(SpanOperands {
+                    lhs,
+                    rhs,
+                    step: None,
+                })
+            },
+            Cst::Ternary(TernaryNode(OperatorNode {
+                op: TernaryOperator::Span,
+                children,
+            })) => {
+                let [lhs, _, rhs, _, step] = non_trivia_children(children);
+
+                Some(SpanOperands {
+                    lhs,
+                    rhs,
+                    step: Some(step),
+                })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Get the `N` children in `children` that are not trivia tokens.
+///
+/// Panics if there are not exactly `N` non-trivia children.
+fn non_trivia_children<I, S, const N: usize>(
+    children: &CstSeq<I, S>,
+) -> [&Cst<I, S>; N] {
+    let non_trivia: Vec<&Cst<I, S>> = non_trivia(children).collect();
+
+    match <[&Cst<I, S>; N]>::try_from(non_trivia) {
+        Ok(array) => array,
+        Err(non_trivia) => {
+            panic!("expected {N} non-trivia children, got {}", non_trivia.len())
+        },
+    }
+}
+
+/// Get the children in `children` that are not trivia tokens.
+fn non_trivia<I, S>(
+    children: &CstSeq<I, S>,
+) -> impl Iterator<Item = &Cst<I, S>> {
+    let NodeSeq(children) = children;
+
+    children.iter().filter(|child| match child {
+        Cst::Token(token) => !token.tok.isTrivia(),
+        _ => true,
+    })
+}
+
 //======================================
 // LeafNode
 //======================================
@@ -500,12 +756,12 @@ impl<I, S: TokenSource, O> OperatorNode<I, S, O> {
 }
 
 impl<I: TokenInput, S, O> OperatorNode<I, S, O> {
-    fn into_owned_input(self) -> OperatorNode<TokenString, S, O> {
+    fn into_owned(self) -> OperatorNode<TokenString, S, O> {
         let OperatorNode { op, children } = self;
 
         OperatorNode {
             op,
-            children: children.into_owned_input(),
+            children: children.into_owned(),
         }
     }
 }
@@ -567,6 +823,65 @@ impl<I, S: TokenSource> InfixNode<I, S> {
     }
 }
 
+impl<I, S> InfixNode<I, S> {
+    /// Get the operands of this node, skipping operator tokens and trivia.
+    ///
+    /// ```
+    /// use wolfram_parser::{cst::Cst, parse_cst, ParseOptions};
+    ///
+    /// let cst = parse_cst("a + b - c", &ParseOptions::default()).syntax;
+    ///
+    /// let Cst::Infix(node) = &cst else { panic!() };
+    ///
+    /// assert_eq!(node.operands().count(), 3);
+    /// ```
+    pub fn operands(&self) -> impl Iterator<Item = &Cst<I, S>> {
+        let InfixNode(OperatorNode { op: _, children }) = self;
+
+        non_trivia(children).step_by(2)
+    }
+
+    /// Get the operator tokens of this node, each paired with the operand
+    /// that follows it, skipping trivia.
+    ///
+    /// The first operand has no preceding operator, and is only returned by
+    /// [`operands()`][Self::operands]. Implicit operators, like the
+    /// multiplication in `a b`, are tokens with empty input, like
+    /// [`TokenKind::Fake_ImplicitTimes`].
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     cst::Cst, parse_cst, tokenize::TokenKind, ParseOptions,
+    /// };
+    ///
+    /// let cst = parse_cst("a + b - c", &ParseOptions::default()).syntax;
+    ///
+    /// let Cst::Infix(node) = &cst else { panic!() };
+    ///
+    /// let operators: Vec<TokenKind> =
+    ///     node.operator_tokens().map(|(token, _)| token.tok).collect();
+    ///
+    /// assert_eq!(operators, [TokenKind::Plus, TokenKind::Minus]);
+    /// ```
+    pub fn operator_tokens(
+        &self,
+    ) -> impl Iterator<Item = (&Token<I, S>, &Cst<I, S>)> {
+        let InfixNode(OperatorNode { op: _, children }) = self;
+
+        let mut rest = non_trivia(children).skip(1);
+
+        std::iter::from_fn(move || {
+            let operator = rest.next()?;
+            let operand = rest.next()?;
+
+            match operator {
+                Cst::Token(token) => Some((token, operand)),
+                _ => panic!("InfixNode operator is not a token"),
+            }
+        })
+    }
+}
+
 //======================================
 // TernaryNode
 //======================================
@@ -587,6 +902,41 @@ impl<I, S: TokenSource> TernaryNode<I, S> {
     }
 }
 
+impl<I, S> TernaryNode<I, S> {
+    /// Get the operands of this node, skipping operator tokens and trivia.
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     cst::Cst, parse_cst, tokenize::TokenKind, ParseOptions,
+    /// };
+    ///
+    /// let cst = parse_cst("a /: b =.", &ParseOptions::default()).syntax;
+    ///
+    /// let Cst::Ternary(node) = &cst else { panic!() };
+    ///
+    /// let operands = node.operands();
+    ///
+    /// assert!(matches!(
+    ///     operands.mid,
+    ///     Cst::Token(tok) if tok.tok == TokenKind::Symbol
+    /// ));
+    /// assert!(operands.rhs.is_none());
+    /// ```
+    pub fn operands(&self) -> TernaryOperands<'_, I, S> {
+        let TernaryNode(OperatorNode { op, children }) = self;
+
+        let [lhs, _, mid, _, rhs] = non_trivia_children(children);
+
+        let rhs = match op {
+            // `a /: b =.` -- the last "operand" is the `.` of `=.`.
+            TernaryOperator::TagUnset => None,
+            _ => Some(rhs),
+        };
+
+        TernaryOperands { lhs, mid, rhs }
+    }
+}
+
 //======================================
 // PostfixNode
 //======================================
@@ -667,6 +1017,41 @@ impl<I, S: TokenSource, O> GroupMissingCloserNode<I, S, O> {
     }
 }
 
+impl<I, S, O> GroupMissingCloserNode<I, S, O> {
+    /// Get the opener of this group, e.g. the `{` in `{1, 2`.
+    pub fn opener(&self) -> Option<&Token<I, S>> {
+        let GroupMissingCloserNode(OperatorNode { op: _, children }) = self;
+
+        match children.0.first() {
+            Some(Cst::Token(opener))
+                if TryGroupOpenerToCloser(opener.tok).is_some() =>
+            {
+                Some(opener)
+            },
+            _ => None,
+        }
+    }
+
+    /// Get the closer this group is missing, e.g. `"}"` for `{1, 2`.
+    ///
+    /// ```
+    /// use wolfram_parser::{cst::Cst, parse_cst, ParseOptions};
+    ///
+    /// let result = parse_cst("<|a -> 1", &ParseOptions::default());
+    ///
+    /// let Cst::GroupMissingCloser(group) = &result.syntax else {
+    ///     panic!()
+    /// };
+    ///
+    /// assert_eq!(group.expected_closer(), Some("|>"));
+    /// ```
+    pub fn expected_closer(&self) -> Option<&'static str> {
+        let opener = self.opener()?;
+
+        Some(GroupOpenerToCloser(opener.tok).as_str())
+    }
+}
+
 //======================================
 // GroupMissingOpenerNode
 //======================================
@@ -820,6 +1205,110 @@ impl<I, S: TokenSource> SyntaxErrorNode<I, S> {
     }
 }
 
+//======================================
+// BoxNode
+//======================================
+
+impl<I, S> BoxNode<I, S> {
+    /// If this is a `SubscriptBox`, `SuperscriptBox`, `SubsuperscriptBox`,
+    /// `UnderscriptBox`, `OverscriptBox`, or `UnderoverscriptBox`, get its
+    /// base and scripts.
+    ///
+    /// Returns `None` if this is another kind of box, or is missing a script.
+    ///
+    /// ```
+    /// use wolfram_expr::{Expr, Symbol};
+    /// use wolfram_parser::{
+    ///     cst::Cst, parse_box_cst, ContainerBody, NodeSeq, ParseOptions,
+    /// };
+    ///
+    /// // x_i^2
+    /// let boxes = Expr::normal(
+    ///     Symbol::new("System`SubsuperscriptBox"),
+    ///     vec![Expr::string("x"), Expr::string("i"), Expr::string("2")],
+    /// );
+    ///
+    /// let container = parse_box_cst(&boxes, &ParseOptions::default());
+    ///
+    /// let ContainerBody::Nodes(NodeSeq(nodes)) = container.body else {
+    ///     panic!()
+    /// };
+    ///
+    /// let Cst::Box(node) = &nodes[0] else { panic!() };
+    ///
+    /// let script = node.as_script().unwrap();
+    ///
+    /// assert!(matches!(script.base, Cst::Token(_)));
+    /// assert!(script.subscript.is_some());
+    /// assert!(script.superscript.is_some());
+    /// assert!(script.overscript.is_none());
+    /// ```
+    pub fn as_script(&self) -> Option<ScriptBox<'_, I, S>> {
+        let NodeSeq(children) = &self.children;
+
+        let (base, scripts) = children.split_first()?;
+
+        let mut script = ScriptBox {
+            base,
+            subscript: None,
+            superscript: None,
+            underscript: None,
+            overscript: None,
+        };
+
+        // Arguments after the scripts are options.
+        match (&self.kind, scripts) {
+            (BoxKind::SubscriptBox, [sub, ..]) => script.subscript = Some(sub),
+            (BoxKind::SuperscriptBox, [sup, ..]) => {
+                script.superscript = Some(sup)
+            },
+            (BoxKind::SubsuperscriptBox, [sub, sup, ..]) => {
+                script.subscript = Some(sub);
+                script.superscript = Some(sup);
+            },
+            (BoxKind::UnderscriptBox, [under, ..]) => {
+                script.underscript = Some(under)
+            },
+            (BoxKind::OverscriptBox, [over, ..]) => {
+                script.overscript = Some(over)
+            },
+            (BoxKind::UnderoverscriptBox, [under, over, ..]) => {
+                script.underscript = Some(under);
+                script.overscript = Some(over);
+            },
+            _ => return None,
+        }
+
+        Some(script)
+    }
+}
+
+impl<'n, I, S> ScriptBox<'n, I, S> {
+    /// If this is a derivative typeset as a superscript, like
+    /// `SuperscriptBox[f, TagBox[RowBox[{"(", "2", ")"}], Derivative]]` for
+    /// `f^(2)`, get the parenthesized order of the derivative.
+    pub fn derivative_order(&self) -> Option<&'n Cst<I, S>> {
+        let Cst::Box(BoxNode {
+            kind: BoxKind::TagBox,
+            children: NodeSeq(children),
+            src: _,
+        }) = self.superscript?
+        else {
+            return None;
+        };
+
+        match children.as_slice() {
+            [order, Cst::Code(CodeNode { second, .. }), ..]
+                if second.try_as_symbol().map(|tag| tag.as_str())
+                    == Some("System`Derivative") =>
+            {
+                Some(order)
+            },
+            _ => None,
+        }
+    }
+}
+
 //======================================
 
 impl SyntaxErrorKind {
@@ -831,6 +1320,7 @@ impl SyntaxErrorKind {
             SyntaxErrorKind::ExpectedSymbol => sym::SyntaxError_ExpectedSymbol,
             SyntaxErrorKind::ExpectedSet => sym::SyntaxError_ExpectedSet,
             SyntaxErrorKind::ExpectedTilde => sym::SyntaxError_ExpectedTilde,
+            SyntaxErrorKind::Unparsable => sym::SyntaxError_Unparsable,
         }
     }
 
@@ -840,6 +1330,7 @@ impl SyntaxErrorKind {
             "ExpectedSymbol" => SyntaxErrorKind::ExpectedSymbol,
             "ExpectedSet" => SyntaxErrorKind::ExpectedSet,
             "ExpectedTilde" => SyntaxErrorKind::ExpectedTilde,
+            "Unparsable" => SyntaxErrorKind::Unparsable,
             _ => return None,
         };
 
@@ -861,6 +1352,8 @@ impl BoxKind {
             BoxKind::NamespaceBox => "NamespaceBox",
             BoxKind::OverscriptBox => "OverscriptBox",
             BoxKind::SubsuperscriptBox => "SubsuperscriptBox",
+            BoxKind::UnderscriptBox => "UnderscriptBox",
+            BoxKind::UnderoverscriptBox => "UnderoverscriptBox",
             BoxKind::GraphicsBox => "GraphicsBox",
             // NOTE: When adding a case here, also update from_str().
             BoxKind::Other(name) => {
@@ -886,6 +1379,8 @@ impl BoxKind {
             "NamespaceBox" => BoxKind::NamespaceBox,
             "OverscriptBox" => BoxKind::OverscriptBox,
             "SubsuperscriptBox" => BoxKind::SubsuperscriptBox,
+            "UnderscriptBox" => BoxKind::UnderscriptBox,
+            "UnderoverscriptBox" => BoxKind::UnderoverscriptBox,
             "GraphicsBox" => BoxKind::GraphicsBox,
             _ => return None,
         };
@@ -0,0 +1,332 @@
+//! Concrete syntax trees allocated in a [`Bump`] arena.
+//!
+//! [`parse_cst_seq_in()`][crate::parse_cst_seq_in] parses input into
+//! [`ArenaCst`] nodes whose children are slices in an arena, instead of
+//! [`Vec`]s of [`Cst`] nodes. Every node of the tree is then freed at once
+//! when the arena is dropped or reset, instead of one node at a time:
+//!
+//! ```
+//! use bumpalo::Bump;
+//! use wolfram_parser::{
+//!     cst::arena::{ArenaCst, ArenaNodeKind},
+//!     parse::operators::InfixOperator,
+//!     parse_cst_seq, parse_cst_seq_in, ParseOptions,
+//! };
+//!
+//! let bump = Bump::new();
+//!
+//! let result = parse_cst_seq_in("a + b", &ParseOptions::default(), &bump);
+//!
+//! let [ArenaCst::Node(node)] = result.syntax else {
+//!     panic!()
+//! };
+//!
+//! assert_eq!(node.kind, ArenaNodeKind::Infix(InfixOperator::Plus));
+//! assert_eq!(node.children.len(), 5);
+//!
+//! // The same tree as the one allocated by parse_cst_seq().
+//! assert_eq!(
+//!     node.to_cst(),
+//!     parse_cst_seq("a + b", &ParseOptions::default()).syntax[0]
+//! );
+//! ```
+//!
+//! This module is only available with the `bumpalo` feature enabled.
+
+use bumpalo::Bump;
+
+use crate::{
+    cst::{
+        BinaryNode, CallBody, CallHead, CallNode, CompoundNode, Cst, CstSeq,
+        GroupMissingCloserNode, GroupMissingOpenerNode, GroupNode, InfixNode,
+        OperatorNode, PostfixNode, PrefixBinaryNode, PrefixNode,
+        SyntaxErrorNode, TernaryNode,
+    },
+    parse::{
+        operators::{
+            BinaryOperator, CallOperator, CompoundOperator, GroupOperator,
+            InfixOperator, PostfixOperator, PrefixBinaryOperator,
+            PrefixOperator, TernaryOperator,
+        },
+        SyntaxErrorKind,
+    },
+    source::Span,
+    tokenize::{Token, TokenSource, TokenStr},
+    NodeSeq,
+};
+
+/// A concrete syntax tree node whose children are allocated in an arena.
+///
+/// This is the arena-allocated equivalent of [`Cst`], with the same tokens
+/// and structure. Use [`to_cst()`][ArenaCst::to_cst] to copy it into a
+/// [`Cst`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ArenaCst<'a> {
+    Token(Token<TokenStr<'a>>),
+    Node(&'a ArenaNode<'a>),
+}
+
+/// A node that is not a token, like an operator or a group.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ArenaNode<'a> {
+    pub kind: ArenaNodeKind,
+    pub children: &'a [ArenaCst<'a>],
+}
+
+/// The kind of an [`ArenaNode`], corresponding to a [`Cst`] variant.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ArenaNodeKind {
+    /// A call, like `f[x]`.
+    ///
+    /// The children are the nodes of the head, followed by the body, which
+    /// is a [`CallBody`][ArenaNodeKind::CallBody] or
+    /// [`CallBodyMissingCloser`][ArenaNodeKind::CallBodyMissingCloser]
+    /// node.
+    Call,
+    /// The `[x]` of `f[x]`.
+    CallBody(CallOperator),
+    /// The `[x` of `f[x`.
+    CallBodyMissingCloser(CallOperator),
+    SyntaxError(SyntaxErrorKind),
+    Prefix(PrefixOperator),
+    Infix(InfixOperator),
+    Postfix(PostfixOperator),
+    Binary(BinaryOperator),
+    Ternary(TernaryOperator),
+    PrefixBinary(PrefixBinaryOperator),
+    Compound(CompoundOperator),
+    Group(GroupOperator),
+    GroupMissingCloser(GroupOperator),
+    GroupMissingOpener(GroupOperator),
+}
+
+//======================================
+// Impls
+//======================================
+
+impl<'a> ArenaCst<'a> {
+    /// Copy `cst` into `bump`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cst` contains [`Cst::Box`] or [`Cst::Code`] nodes, which are
+    /// never constructed during concrete parsing.
+    pub(crate) fn from_cst(bump: &'a Bump, cst: &Cst<TokenStr<'a>>) -> Self {
+        let (kind, children): (_, &[Cst<_>]) = match cst {
+            Cst::Token(token) => return ArenaCst::Token(*token),
+            Cst::Call(CallNode { head, body }) => {
+                let head = match head {
+                    CallHead::Concrete(NodeSeq(head)) => head,
+                    CallHead::Aggregate(_) => {
+                        panic!("expected concrete call head: {cst:?}")
+                    },
+                };
+
+                let body = match body {
+                    CallBody::Group(GroupNode(OperatorNode {
+                        op,
+                        children,
+                    })) => ArenaNodeKind::CallBody(*op).alloc(bump, children),
+                    CallBody::GroupMissingCloser(GroupMissingCloserNode(
+                        OperatorNode { op, children },
+                    )) => ArenaNodeKind::CallBodyMissingCloser(*op)
+                        .alloc(bump, children),
+                };
+
+                let mut children: Vec<_> = head
+                    .iter()
+                    .map(|child| ArenaCst::from_cst(bump, child))
+                    .collect();
+
+                children.push(body);
+
+                return ArenaCst::Node(bump.alloc(ArenaNode {
+                    kind: ArenaNodeKind::Call,
+                    children: bump.alloc_slice_copy(&children),
+                }));
+            },
+            Cst::SyntaxError(SyntaxErrorNode { err, children }) => {
+                (ArenaNodeKind::SyntaxError(*err), children)
+            },
+            Cst::Prefix(PrefixNode(OperatorNode { op, children })) => {
+                (ArenaNodeKind::Prefix(*op), children)
+            },
+            Cst::Infix(InfixNode(OperatorNode { op, children })) => {
+                (ArenaNodeKind::Infix(*op), children)
+            },
+            Cst::Postfix(PostfixNode(OperatorNode { op, children })) => {
+                (ArenaNodeKind::Postfix(*op), children)
+            },
+            Cst::Binary(BinaryNode(OperatorNode { op, children })) => {
+                (ArenaNodeKind::Binary(*op), children)
+            },
+            Cst::Ternary(TernaryNode(OperatorNode { op, children })) => {
+                (ArenaNodeKind::Ternary(*op), children)
+            },
+            Cst::PrefixBinary(PrefixBinaryNode(OperatorNode {
+                op,
+                children,
+            })) => (ArenaNodeKind::PrefixBinary(*op), children),
+            Cst::Compound(CompoundNode(OperatorNode { op, children })) => {
+                (ArenaNodeKind::Compound(*op), children)
+            },
+            Cst::Group(GroupNode(OperatorNode { op, children })) => {
+                (ArenaNodeKind::Group(*op), children)
+            },
+            Cst::GroupMissingCloser(GroupMissingCloserNode(OperatorNode {
+                op,
+                children,
+            })) => (ArenaNodeKind::GroupMissingCloser(*op), children),
+            Cst::GroupMissingOpener(GroupMissingOpenerNode(OperatorNode {
+                op,
+                children,
+            })) => (ArenaNodeKind::GroupMissingOpener(*op), children),
+            Cst::Box(_) | Cst::Code(_) => {
+                panic!(
+                    "unexpected box or code node in concrete syntax: {cst:?}"
+                )
+            },
+        };
+
+        kind.alloc(bump, children)
+    }
+
+    /// Copy this node and its children into a [`Cst`].
+    ///
+    /// Token inputs still refer to the parsed input, which is not copied.
+    pub fn to_cst(&self) -> Cst<TokenStr<'a>> {
+        match self {
+            ArenaCst::Token(token) => Cst::Token(*token),
+            ArenaCst::Node(node) => node.to_cst(),
+        }
+    }
+
+    pub fn get_source(&self) -> Span {
+        match self {
+            ArenaCst::Token(token) => token.src,
+            ArenaCst::Node(node) => node.get_source(),
+        }
+    }
+
+    /// The children of this node, which are empty for a token.
+    pub fn children(&self) -> &'a [ArenaCst<'a>] {
+        match self {
+            ArenaCst::Token(_) => &[],
+            ArenaCst::Node(node) => node.children,
+        }
+    }
+}
+
+impl<'a> ArenaNode<'a> {
+    /// Copy this node and its children into a [`Cst`].
+    ///
+    /// See [`ArenaCst::to_cst()`].
+    pub fn to_cst(&self) -> Cst<TokenStr<'a>> {
+        let ArenaNode { kind, children } = *self;
+
+        match kind {
+            ArenaNodeKind::Call => {
+                let Some((ArenaCst::Node(body), head)) = children.split_last()
+                else {
+                    panic!("expected call node to end with a body: {self:?}")
+                };
+
+                let body = match body.kind {
+                    ArenaNodeKind::CallBody(op) => {
+                        CallBody::Group(GroupNode(OperatorNode {
+                            op,
+                            children: to_cst_seq(body.children),
+                        }))
+                    },
+                    ArenaNodeKind::CallBodyMissingCloser(op) => {
+                        CallBody::GroupMissingCloser(GroupMissingCloserNode(
+                            OperatorNode {
+                                op,
+                                children: to_cst_seq(body.children),
+                            },
+                        ))
+                    },
+                    _ => panic!("expected call body node: {body:?}"),
+                };
+
+                Cst::Call(CallNode::concrete(to_cst_seq(head), body))
+            },
+            ArenaNodeKind::CallBody(_)
+            | ArenaNodeKind::CallBodyMissingCloser(_) => {
+                panic!("unexpected call body outside of a call: {self:?}")
+            },
+            ArenaNodeKind::SyntaxError(err) => Cst::SyntaxError(
+                SyntaxErrorNode::new(err, to_cst_seq(children)),
+            ),
+            ArenaNodeKind::Prefix(op) => {
+                Cst::Prefix(PrefixNode::new(op, to_cst_seq(children)))
+            },
+            ArenaNodeKind::Infix(op) => {
+                Cst::Infix(InfixNode::new(op, to_cst_seq(children)))
+            },
+            ArenaNodeKind::Postfix(op) => {
+                Cst::Postfix(PostfixNode::new(op, to_cst_seq(children)))
+            },
+            ArenaNodeKind::Binary(op) => {
+                Cst::Binary(BinaryNode::new(op, to_cst_seq(children)))
+            },
+            ArenaNodeKind::Ternary(op) => {
+                Cst::Ternary(TernaryNode::new(op, to_cst_seq(children)))
+            },
+            ArenaNodeKind::PrefixBinary(op) => Cst::PrefixBinary(
+                PrefixBinaryNode::new(op, to_cst_seq(children)),
+            ),
+            ArenaNodeKind::Compound(op) => {
+                Cst::Compound(CompoundNode(OperatorNode {
+                    op,
+                    children: to_cst_seq(children),
+                }))
+            },
+            ArenaNodeKind::Group(op) => {
+                Cst::Group(GroupNode::new(op, to_cst_seq(children)))
+            },
+            ArenaNodeKind::GroupMissingCloser(op) => Cst::GroupMissingCloser(
+                GroupMissingCloserNode::new(op, to_cst_seq(children)),
+            ),
+            ArenaNodeKind::GroupMissingOpener(op) => {
+                Cst::GroupMissingOpener(GroupMissingOpenerNode(OperatorNode {
+                    op,
+                    children: to_cst_seq(children),
+                }))
+            },
+        }
+    }
+
+    pub fn get_source(&self) -> Span {
+        let (Some(first), Some(last)) =
+            (self.children.first(), self.children.last())
+        else {
+            panic!("ArenaNode::get_source(): node has no children")
+        };
+
+        Span::between(first.get_source(), last.get_source())
+    }
+}
+
+impl ArenaNodeKind {
+    /// Allocate a node of this kind in `bump`, with a copy of `children`.
+    fn alloc<'a>(
+        self,
+        bump: &'a Bump,
+        children: &[Cst<TokenStr<'a>>],
+    ) -> ArenaCst<'a> {
+        let children = bump.alloc_slice_fill_iter(
+            children.iter().map(|child| ArenaCst::from_cst(bump, child)),
+        );
+
+        ArenaCst::Node(bump.alloc(ArenaNode {
+            kind: self,
+            children,
+        }))
+    }
+}
+
+/// Copy each of `nodes` into a [`Cst`].
+pub fn to_cst_seq<'a>(nodes: &[ArenaCst<'a>]) -> CstSeq<TokenStr<'a>> {
+    NodeSeq(nodes.iter().map(ArenaCst::to_cst).collect())
+}
@@ -0,0 +1,87 @@
+//! Render concrete syntax trees back to source text.
+//!
+//! A concrete syntax tree keeps every token of its input, including
+//! whitespace, comments, and line continuations, so [`Cst::to_source()`]
+//! reproduces the input it was parsed from exactly:
+//!
+//! ```
+//! use wolfram_parser::{parse_cst_seq, ParseOptions};
+//!
+//! let input = "f[x_] := (* add one *) x +\\\n  1\n";
+//!
+//! let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+//!
+//! assert_eq!(cst.to_source(), input);
+//! ```
+//!
+//! This makes the concrete syntax tree a suitable basis for refactoring tools:
+//! edit the tree, then render it to get the new source.
+//!
+//! Trees that have lost tokens cannot be rendered exactly. This includes
+//! aggregated trees, which have no trivia, and input skipped by the
+//! tokenizer, like the `#!` line of a script parsed with
+//! [`FirstLineBehavior::Script`][crate::FirstLineBehavior::Script]. A line
+//! continuation at the very end of the input, with any whitespace after it,
+//! is also lost, as are the lines after an unterminated group or token that
+//! [`ParseOptions::reparse_unterminated()`][crate::ParseOptions::reparse_unterminated]
+//! leaves out of the tree. Nodes produced from boxes, [`Cst::Box`] and
+//! [`Cst::Code`], have no input form text of their own, and render as the
+//! concatenation of their tokens.
+
+use crate::{
+    cst::{Cst, CstSeq},
+    tokenize::{Token, TokenInput},
+};
+
+impl<I: TokenInput, S> Cst<I, S> {
+    /// Reconstruct the source text this tree was parsed from.
+    ///
+    /// Input that is not valid UTF-8 is replaced with
+    /// [`U+FFFD`][char::REPLACEMENT_CHARACTER]; use
+    /// [`Cst::to_source_bytes()`] to get the exact input bytes.
+    pub fn to_source(&self) -> String {
+        into_string(self.to_source_bytes())
+    }
+
+    /// Reconstruct the bytes of the source this tree was parsed from.
+    pub fn to_source_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        self.visit(&mut |node| write_token(node, &mut out));
+
+        out
+    }
+}
+
+impl<I: TokenInput, S> CstSeq<I, S> {
+    /// Reconstruct the source text this sequence was parsed from.
+    ///
+    /// See [`Cst::to_source()`].
+    pub fn to_source(&self) -> String {
+        into_string(self.to_source_bytes())
+    }
+
+    /// Reconstruct the bytes of the source this sequence was parsed from.
+    pub fn to_source_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        self.visit(&mut |node| write_token(node, &mut out));
+
+        out
+    }
+}
+
+fn write_token<I: TokenInput, S>(node: &Cst<I, S>, out: &mut Vec<u8>) {
+    // Every byte of input belongs to exactly one token, and visiting is in
+    // source order, so only tokens have to be written.
+    if let Cst::Token(Token { input, .. }) = node {
+        out.extend_from_slice(input.as_bytes());
+    }
+}
+
+fn into_string(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(string) => string,
+        Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+    }
+}
@@ -0,0 +1,273 @@
+//! Checking the source spans of a concrete syntax tree.
+//!
+//! The spans of a well-formed [`Cst`] are strictly nested: every child is
+//! contained in its parent, and the children of a node follow each other in
+//! source order without overlapping. When trivia is included, the tokens of
+//! a parsed [`CstSeq`] also cover every character of the input.
+//!
+//! Tools that construct or rewrite trees can check that they preserve these
+//! invariants with [`Cst::validate_spans()`] and
+//! [`CstSeq::validate_coverage()`]:
+//!
+//! ```
+//! use wolfram_parser::{parse_cst_seq, ParseOptions};
+//!
+//! let input = "f[x, y] + (* comment *) 2";
+//! let opts = ParseOptions::default();
+//!
+//! let cst = parse_cst_seq(input, &opts).syntax;
+//!
+//! assert_eq!(cst.validate_spans(), Ok(()));
+//! assert_eq!(cst.validate_coverage(input, &opts), Ok(()));
+//! ```
+
+use std::cmp::Ordering;
+
+use thiserror::Error;
+
+use crate::{
+    cst::{Cst, CstSeq},
+    source::{LineMap, Location, Span},
+    NodeSeq, ParseOptions,
+};
+
+/// A violation of the span invariants of a concrete syntax tree.
+///
+/// Returned by [`Cst::validate_spans()`] and [`CstSeq::validate_coverage()`].
+#[derive(Error, Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SpanError {
+    /// A child node extends outside of the span of its parent
+    /// [`Cst::Box`] node.
+    #[error("child span {child} is not contained in parent span {parent}")]
+    NotContained { parent: Span, child: Span },
+
+    /// Two adjacent nodes overlap, or are not in source order.
+    #[error("span {second} does not follow span {first}")]
+    OutOfOrder { first: Span, second: Span },
+
+    /// No token covers the input between `end` and `start`.
+    #[error("no token covers the input between {end} and {start}")]
+    Gap { end: Location, start: Location },
+
+    /// A node that has no children, and so has no span.
+    #[error("node has no children")]
+    Empty,
+
+    /// Two spans use different [`SourceConvention`][crate::SourceConvention]s,
+    /// and cannot be compared.
+    #[error("spans {first} and {second} cannot be compared")]
+    MismatchedKinds { first: Span, second: Span },
+}
+
+//======================================
+// Impls
+//======================================
+
+impl<I> Cst<I, Span> {
+    /// Check that the spans of this node and all of its descendants are
+    /// strictly nested.
+    ///
+    /// Adjacent children must be in source order without overlapping, which
+    /// keeps them within the span of their parent. The children of a
+    /// [`Cst::Box`] must also be contained in its span. Zero-width tokens,
+    /// like the implicit `Times` operator between `a b`, may share a location
+    /// with their neighbors.
+    ///
+    /// Unlike [`Cst::get_source()`], this does not panic if the tree is
+    /// malformed.
+    pub fn validate_spans(&self) -> Result<(), SpanError> {
+        check_node(self).map(|_| ())
+    }
+}
+
+impl<I> CstSeq<I, Span> {
+    /// Check the spans of every node in this sequence, and that the nodes
+    /// are in source order.
+    ///
+    /// See [`Cst::validate_spans()`].
+    pub fn validate_spans(&self) -> Result<(), SpanError> {
+        let NodeSeq(nodes) = self;
+
+        check_seq(nodes.iter()).map(|_| ())
+    }
+
+    /// Check that the tokens in this sequence cover all of `input`, which
+    /// this sequence was parsed from using `opts`.
+    ///
+    /// Line continuations between tokens are not part of any token, and are
+    /// allowed in gaps.
+    ///
+    /// This only holds for sequences parsed with trivia, like the result of
+    /// [`parse_cst_seq()`][crate::parse_cst_seq]. The heuristics used for
+    /// [`ParseOptions::reparse_unterminated()`] can shorten unterminated
+    /// tokens, so disable them when checking input that may contain
+    /// unterminated strings or comments.
+    pub fn validate_coverage(
+        &self,
+        input: &str,
+        opts: &ParseOptions,
+    ) -> Result<(), SpanError> {
+        self.validate_spans()?;
+
+        let map = LineMap::new(input, opts);
+
+        let location = |offset| {
+            map.location(offset, opts.src_convention)
+                .expect("offset is a character boundary of the input")
+        };
+
+        let NodeSeq(nodes) = self;
+
+        let mut leaves = Vec::new();
+
+        for node in nodes {
+            collect_leaves(node, &mut leaves);
+        }
+
+        let start = location(0);
+        let end = location(input.len());
+
+        let mut prev_end = start;
+
+        for span in leaves.into_iter().chain([Span::at(end)]) {
+            if span.start() == prev_end {
+                prev_end = span.end();
+                continue;
+            }
+
+            let gap = map
+                .offset(prev_end)
+                .zip(map.offset(span.start()))
+                .and_then(|(from, to)| input.get(from..to));
+
+            if !gap.is_some_and(is_line_continuations) {
+                return Err(SpanError::Gap {
+                    end: prev_end,
+                    start: span.start(),
+                });
+            }
+
+            prev_end = span.end();
+        }
+
+        Ok(())
+    }
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// The spans of `node`, if it is a leaf, or of every leaf under it, in
+/// source order.
+fn collect_leaves<I>(node: &Cst<I, Span>, leaves: &mut Vec<Span>) {
+    match node {
+        Cst::Token(_) | Cst::Box(_) | Cst::Code(_) => {
+            leaves.push(node.get_source())
+        },
+        _ => {
+            for child in node.children() {
+                collect_leaves(child, leaves);
+            }
+        },
+    }
+}
+
+/// Check the spans of `node` and its descendants, returning the span of
+/// `node`.
+fn check_node<I>(node: &Cst<I, Span>) -> Result<Span, SpanError> {
+    let span = match node {
+        Cst::Token(token) => return Ok(token.src),
+        Cst::Code(node) => return Ok(node.src),
+        Cst::Box(node) => {
+            let NodeSeq(children) = &node.children;
+
+            let children = check_seq(children.iter())?;
+
+            for child in children {
+                check_contained(node.src, child)?;
+            }
+
+            return Ok(node.src);
+        },
+        _ => {
+            let children = check_seq(node.children().into_iter())?;
+
+            match (children.first(), children.last()) {
+                (Some(first), Some(last)) => {
+                    Span::new(first.start(), last.end())
+                },
+                _ => return Err(SpanError::Empty),
+            }
+        },
+    };
+
+    Ok(span)
+}
+
+/// Check each of `nodes`, and that each one ends before the next one starts,
+/// returning their spans.
+fn check_seq<'n, I: 'n>(
+    nodes: impl Iterator<Item = &'n Cst<I, Span>>,
+) -> Result<Vec<Span>, SpanError> {
+    let spans = nodes.map(check_node).collect::<Result<Vec<_>, _>>()?;
+
+    for pair in spans.windows(2) {
+        let [first, second] = [pair[0], pair[1]];
+
+        if !is_ordered(first, second, first.end(), second.start())? {
+            return Err(SpanError::OutOfOrder { first, second });
+        }
+    }
+
+    Ok(spans)
+}
+
+fn check_contained(parent: Span, child: Span) -> Result<(), SpanError> {
+    let contained = is_ordered(parent, child, parent.start(), child.start())?
+        && is_ordered(parent, child, child.end(), parent.end())?;
+
+    if !contained {
+        return Err(SpanError::NotContained { parent, child });
+    }
+
+    Ok(())
+}
+
+/// Whether `a <= b`, where `a` and `b` are locations in `first` and `second`.
+///
+/// Returns an error if the locations use different conventions.
+fn is_ordered(
+    first: Span,
+    second: Span,
+    a: Location,
+    b: Location,
+) -> Result<bool, SpanError> {
+    match a.partial_cmp(&b) {
+        Some(ordering) => Ok(ordering != Ordering::Greater),
+        None => Err(SpanError::MismatchedKinds { first, second }),
+    }
+}
+
+/// Whether `text` consists only of line continuations, like `\` followed by
+/// a newline.
+fn is_line_continuations(text: &str) -> bool {
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        rest = match rest.strip_prefix('\\') {
+            Some(after) => match after
+                .strip_prefix("\r\n")
+                .or_else(|| after.strip_prefix('\n'))
+                .or_else(|| after.strip_prefix('\r'))
+            {
+                Some(after) => after,
+                None => return false,
+            },
+            None => return false,
+        };
+    }
+
+    true
+}
@@ -1,15 +1,357 @@
 //! Visitor methods for [`Cst`] and related types.
+//!
+//! For simple traversals, [`Cst::visit()`] calls a closure on every node. For
+//! analyses that handle several kinds of nodes differently, implement
+//! [`CstVisitor`] and override the callbacks for the node kinds of interest:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     cst::{
+//!         visit::{walk_cst_seq, CstVisitor},
+//!         CallNode, InfixNode,
+//!     },
+//!     parse_cst_seq, ParseOptions,
+//! };
+//!
+//! #[derive(Default)]
+//! struct Counts {
+//!     calls: usize,
+//!     infix: usize,
+//! }
+//!
+//! impl<I, S> CstVisitor<I, S> for Counts {
+//!     fn visit_call(&mut self, _node: &CallNode<I, S>) {
+//!         self.calls += 1;
+//!     }
+//!
+//!     fn visit_infix(&mut self, _node: &InfixNode<I, S>) {
+//!         self.infix += 1;
+//!     }
+//! }
+//!
+//! let cst = parse_cst_seq("f[a + b, g[c]]", &ParseOptions::default()).syntax;
+//!
+//! let mut counts = Counts::default();
+//! walk_cst_seq(&mut counts, &cst);
+//!
+//! assert_eq!(counts.calls, 2);
+//! // `a + b`, and the `,` sequence of arguments to `f`.
+//! assert_eq!(counts.infix, 2);
+//! ```
+//!
+//! [`CstVisitorMut`] is the equivalent for modifying a tree in place.
 
 use crate::{
     cst::{
-        BinaryNode, BoxNode, CallHead, CallNode, CompoundNode, Cst, CstSeq,
-        GroupMissingCloserNode, GroupMissingOpenerNode, GroupNode, InfixNode,
-        OperatorNode, PostfixNode, PrefixBinaryNode, PrefixNode,
+        BinaryNode, BoxNode, CallHead, CallNode, CodeNode, CompoundNode, Cst,
+        CstSeq, GroupMissingCloserNode, GroupMissingOpenerNode, GroupNode,
+        InfixNode, OperatorNode, PostfixNode, PrefixBinaryNode, PrefixNode,
         SyntaxErrorNode, TernaryNode,
     },
+    source::Span,
+    tokenize::{Token, TokenString},
     NodeSeq,
 };
 
+//======================================
+// CstVisitor
+//======================================
+
+/// Callbacks for each kind of [`Cst`] node, called by [`walk_cst()`].
+///
+/// Every callback does nothing by default. Callbacks are called before the
+/// children of a node are walked.
+pub trait CstVisitor<I = TokenString, S = Span> {
+    /// Called for every [`Cst::Token`] node, like `a`, `+`, or a comment.
+    fn visit_token(&mut self, _token: &Token<I, S>) {}
+
+    /// Called for every [`Cst::Call`] node, like `f[x]`.
+    fn visit_call(&mut self, _node: &CallNode<I, S>) {}
+
+    /// Called for every [`Cst::SyntaxError`] node.
+    fn visit_syntax_error(&mut self, _node: &SyntaxErrorNode<I, S>) {}
+
+    /// Called for every [`Cst::Prefix`] node, like `-a`.
+    fn visit_prefix(&mut self, _node: &PrefixNode<I, S>) {}
+
+    /// Called for every [`Cst::Infix`] node, like `a + b + c`.
+    fn visit_infix(&mut self, _node: &InfixNode<I, S>) {}
+
+    /// Called for every [`Cst::Postfix`] node, like `a!`.
+    fn visit_postfix(&mut self, _node: &PostfixNode<I, S>) {}
+
+    /// Called for every [`Cst::Binary`] node, like `a @ b`.
+    fn visit_binary(&mut self, _node: &BinaryNode<I, S>) {}
+
+    /// Called for every [`Cst::Ternary`] node, like `a /: b = c`.
+    fn visit_ternary(&mut self, _node: &TernaryNode<I, S>) {}
+
+    /// Called for every [`Cst::PrefixBinary`] node, like
+    /// `\[Integral] f \[DifferentialD] x`.
+    fn visit_prefix_binary(&mut self, _node: &PrefixBinaryNode<I, S>) {}
+
+    /// Called for every [`Cst::Compound`] node, like `a_`, `#1`, `%2`, ....
+    fn visit_compound(&mut self, _node: &CompoundNode<I, S>) {}
+
+    /// Called for every [`Cst::Group`] node, like `{x}`.
+    fn visit_group(&mut self, _node: &GroupNode<I, S>) {}
+
+    /// Called for every [`Cst::GroupMissingCloser`] node, like `{x`.
+    fn visit_group_missing_closer(
+        &mut self,
+        _node: &GroupMissingCloserNode<I, S>,
+    ) {
+    }
+
+    /// Called for every [`Cst::GroupMissingOpener`] node.
+    fn visit_group_missing_opener(
+        &mut self,
+        _node: &GroupMissingOpenerNode<I, S>,
+    ) {
+    }
+
+    /// Called for every [`Cst::Box`] node.
+    fn visit_box(&mut self, _node: &BoxNode<I, S>) {}
+
+    /// Called for every [`Cst::Code`] node.
+    fn visit_code(&mut self, _node: &CodeNode<S>) {}
+}
+
+/// Call the [`CstVisitor`] callback for `node`, and then walk each of its
+/// children, recursively.
+pub fn walk_cst<I, S, V>(visitor: &mut V, node: &Cst<I, S>)
+where
+    V: CstVisitor<I, S> + ?Sized,
+{
+    match node {
+        Cst::Token(token) => visitor.visit_token(token),
+        Cst::Call(node) => {
+            visitor.visit_call(node);
+
+            let CallNode { head, body } = node;
+
+            match head {
+                CallHead::Concrete(head) => walk_cst_seq(visitor, head),
+                CallHead::Aggregate(head) => walk_cst(visitor, head),
+            }
+
+            walk_cst_seq(visitor, &body.as_op().children);
+        },
+        Cst::SyntaxError(node) => {
+            visitor.visit_syntax_error(node);
+            walk_cst_seq(visitor, &node.children);
+        },
+        Cst::Prefix(node) => {
+            visitor.visit_prefix(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::Infix(node) => {
+            visitor.visit_infix(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::Postfix(node) => {
+            visitor.visit_postfix(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::Binary(node) => {
+            visitor.visit_binary(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::Ternary(node) => {
+            visitor.visit_ternary(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::PrefixBinary(node) => {
+            visitor.visit_prefix_binary(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::Compound(node) => {
+            visitor.visit_compound(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::Group(node) => {
+            visitor.visit_group(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::GroupMissingCloser(node) => {
+            visitor.visit_group_missing_closer(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::GroupMissingOpener(node) => {
+            visitor.visit_group_missing_opener(node);
+            walk_cst_seq(visitor, &node.0.children);
+        },
+        Cst::Box(node) => {
+            visitor.visit_box(node);
+            walk_cst_seq(visitor, &node.children);
+        },
+        // These node types have no child nodes.
+        Cst::Code(node) => visitor.visit_code(node),
+    }
+}
+
+/// Walk every node in `seq` with [`walk_cst()`].
+pub fn walk_cst_seq<I, S, V>(visitor: &mut V, seq: &CstSeq<I, S>)
+where
+    V: CstVisitor<I, S> + ?Sized,
+{
+    let NodeSeq(elements) = seq;
+
+    for elem in elements {
+        walk_cst(visitor, elem);
+    }
+}
+
+//======================================
+// CstVisitorMut
+//======================================
+
+/// Callbacks for each kind of [`Cst`] node, called by [`walk_cst_mut()`].
+///
+/// Every callback does nothing by default. Callbacks are called before the
+/// children of a node are walked, so children modified or added by a
+/// callback are walked afterwards.
+pub trait CstVisitorMut<I = TokenString, S = Span> {
+    /// Called for every [`Cst::Token`] node, like `a`, `+`, or a comment.
+    fn visit_token_mut(&mut self, _token: &mut Token<I, S>) {}
+
+    /// Called for every [`Cst::Call`] node, like `f[x]`.
+    fn visit_call_mut(&mut self, _node: &mut CallNode<I, S>) {}
+
+    /// Called for every [`Cst::SyntaxError`] node.
+    fn visit_syntax_error_mut(&mut self, _node: &mut SyntaxErrorNode<I, S>) {}
+
+    /// Called for every [`Cst::Prefix`] node, like `-a`.
+    fn visit_prefix_mut(&mut self, _node: &mut PrefixNode<I, S>) {}
+
+    /// Called for every [`Cst::Infix`] node, like `a + b + c`.
+    fn visit_infix_mut(&mut self, _node: &mut InfixNode<I, S>) {}
+
+    /// Called for every [`Cst::Postfix`] node, like `a!`.
+    fn visit_postfix_mut(&mut self, _node: &mut PostfixNode<I, S>) {}
+
+    /// Called for every [`Cst::Binary`] node, like `a @ b`.
+    fn visit_binary_mut(&mut self, _node: &mut BinaryNode<I, S>) {}
+
+    /// Called for every [`Cst::Ternary`] node, like `a /: b = c`.
+    fn visit_ternary_mut(&mut self, _node: &mut TernaryNode<I, S>) {}
+
+    /// Called for every [`Cst::PrefixBinary`] node, like
+    /// `\[Integral] f \[DifferentialD] x`.
+    fn visit_prefix_binary_mut(&mut self, _node: &mut PrefixBinaryNode<I, S>) {}
+
+    /// Called for every [`Cst::Compound`] node, like `a_`, `#1`, `%2`, ....
+    fn visit_compound_mut(&mut self, _node: &mut CompoundNode<I, S>) {}
+
+    /// Called for every [`Cst::Group`] node, like `{x}`.
+    fn visit_group_mut(&mut self, _node: &mut GroupNode<I, S>) {}
+
+    /// Called for every [`Cst::GroupMissingCloser`] node, like `{x`.
+    fn visit_group_missing_closer_mut(
+        &mut self,
+        _node: &mut GroupMissingCloserNode<I, S>,
+    ) {
+    }
+
+    /// Called for every [`Cst::GroupMissingOpener`] node.
+    fn visit_group_missing_opener_mut(
+        &mut self,
+        _node: &mut GroupMissingOpenerNode<I, S>,
+    ) {
+    }
+
+    /// Called for every [`Cst::Box`] node.
+    fn visit_box_mut(&mut self, _node: &mut BoxNode<I, S>) {}
+
+    /// Called for every [`Cst::Code`] node.
+    fn visit_code_mut(&mut self, _node: &mut CodeNode<S>) {}
+}
+
+/// Call the [`CstVisitorMut`] callback for `node`, and then walk each of its
+/// children, recursively.
+pub fn walk_cst_mut<I, S, V>(visitor: &mut V, node: &mut Cst<I, S>)
+where
+    V: CstVisitorMut<I, S> + ?Sized,
+{
+    match node {
+        Cst::Token(token) => visitor.visit_token_mut(token),
+        Cst::Call(node) => {
+            visitor.visit_call_mut(node);
+
+            let CallNode { head, body } = node;
+
+            match head {
+                CallHead::Concrete(head) => walk_cst_seq_mut(visitor, head),
+                CallHead::Aggregate(head) => walk_cst_mut(visitor, head),
+            }
+
+            walk_cst_seq_mut(visitor, &mut body.as_op_mut().children);
+        },
+        Cst::SyntaxError(node) => {
+            visitor.visit_syntax_error_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.children);
+        },
+        Cst::Prefix(node) => {
+            visitor.visit_prefix_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::Infix(node) => {
+            visitor.visit_infix_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::Postfix(node) => {
+            visitor.visit_postfix_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::Binary(node) => {
+            visitor.visit_binary_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::Ternary(node) => {
+            visitor.visit_ternary_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::PrefixBinary(node) => {
+            visitor.visit_prefix_binary_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::Compound(node) => {
+            visitor.visit_compound_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::Group(node) => {
+            visitor.visit_group_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::GroupMissingCloser(node) => {
+            visitor.visit_group_missing_closer_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::GroupMissingOpener(node) => {
+            visitor.visit_group_missing_opener_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.0.children);
+        },
+        Cst::Box(node) => {
+            visitor.visit_box_mut(node);
+            walk_cst_seq_mut(visitor, &mut node.children);
+        },
+        // These node types have no child nodes.
+        Cst::Code(node) => visitor.visit_code_mut(node),
+    }
+}
+
+/// Walk every node in `seq` with [`walk_cst_mut()`].
+pub fn walk_cst_seq_mut<I, S, V>(visitor: &mut V, seq: &mut CstSeq<I, S>)
+where
+    V: CstVisitorMut<I, S> + ?Sized,
+{
+    let NodeSeq(elements) = seq;
+
+    for elem in elements {
+        walk_cst_mut(visitor, elem);
+    }
+}
+
 //======================================
 // CstSeq
 //======================================
@@ -230,6 +572,123 @@ impl<I, S> Cst<I, S> {
     }
 }
 
+//======================================
+// Metrics
+//======================================
+
+impl<I, S> CstSeq<I, S> {
+    /// Total number of nodes in every tree in this sequence.
+    ///
+    /// See [`Cst::node_count()`].
+    pub fn node_count(&self) -> usize {
+        let NodeSeq(elements) = self;
+
+        elements.iter().map(Cst::node_count).sum()
+    }
+
+    /// Greatest [depth][Cst::max_depth] of any tree in this sequence, or 0 if
+    /// the sequence is empty.
+    pub fn max_depth(&self) -> usize {
+        let NodeSeq(elements) = self;
+
+        elements.iter().map(Cst::max_depth).max().unwrap_or(0)
+    }
+
+    /// Total number of tokens in every tree in this sequence.
+    ///
+    /// See [`Cst::token_count()`].
+    pub fn token_count(&self) -> usize {
+        let NodeSeq(elements) = self;
+
+        elements.iter().map(Cst::token_count).sum()
+    }
+}
+
+impl<I, S> Cst<I, S> {
+    /// Number of nodes in the tree rooted at this node, including itself.
+    ///
+    /// This is the number of nodes visited by [`Cst::visit()`].
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_cst, ParseOptions};
+    ///
+    /// let cst = parse_cst("f[a + b]", &ParseOptions::default()).syntax;
+    ///
+    /// // f[...], f, [, a + b, a, whitespace, +, whitespace, b, ]
+    /// assert_eq!(cst.node_count(), 10);
+    /// assert_eq!(cst.token_count(), 8);
+    /// assert_eq!(cst.max_depth(), 3);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+
+        self.visit(&mut |_| count += 1);
+
+        count
+    }
+
+    /// Number of nodes on the longest path from this node to a leaf,
+    /// including both ends.
+    ///
+    /// A token has depth 1.
+    pub fn max_depth(&self) -> usize {
+        let children = self.children().into_iter().map(Cst::max_depth);
+
+        1 + children.max().unwrap_or(0)
+    }
+
+    /// Number of [`Cst::Token`] nodes in the tree rooted at this node,
+    /// including trivia like whitespace and comments.
+    pub fn token_count(&self) -> usize {
+        let mut count = 0;
+
+        self.visit(&mut |node| {
+            if let Cst::Token(_) = node {
+                count += 1;
+            }
+        });
+
+        count
+    }
+
+    /// The direct children of this node, in source order.
+    ///
+    /// The children of a [`Cst::Call`] are the elements of its head, followed
+    /// by the children of its body.
+    pub(crate) fn children(&self) -> Vec<&Cst<I, S>> {
+        let seq = match self {
+            Cst::Call(CallNode { head, body }) => {
+                let head = match head {
+                    CallHead::Concrete(NodeSeq(head)) => head.iter().collect(),
+                    CallHead::Aggregate(head) => vec![&**head],
+                };
+
+                let NodeSeq(body) = &body.as_op().children;
+
+                return head.into_iter().chain(body).collect();
+            },
+            Cst::SyntaxError(node) => &node.children,
+            Cst::Prefix(node) => &node.0.children,
+            Cst::Infix(node) => &node.0.children,
+            Cst::Postfix(node) => &node.0.children,
+            Cst::Binary(node) => &node.0.children,
+            Cst::Ternary(node) => &node.0.children,
+            Cst::PrefixBinary(node) => &node.0.children,
+            Cst::Compound(node) => &node.0.children,
+            Cst::Group(node) => &node.0.children,
+            Cst::GroupMissingCloser(node) => &node.0.children,
+            Cst::GroupMissingOpener(node) => &node.0.children,
+            Cst::Box(node) => &node.children,
+            // These node types have no child nodes.
+            Cst::Token(_) | Cst::Code(_) => return Vec::new(),
+        };
+
+        let NodeSeq(children) = seq;
+
+        children.iter().collect()
+    }
+}
+
 //======================================
 // OperatorNode
 //======================================
@@ -0,0 +1,363 @@
+//! Reading Wolfram Language data files into typed Rust values.
+//!
+//! This module parses "data-only" Wolfram Language input — literals, lists,
+//! associations, and rules, as accepted by [`GrammarProfile::Data`] — into
+//! [`LiteralValue`]s, without needing to walk the generic [`Ast`].
+//!
+//! ```
+//! use wolfram_parser::data::{parse_association, LiteralValue};
+//!
+//! let config = parse_association(r#"<|
+//!     "name" -> "server",
+//!     "port" -> 8080,
+//!     "ratio" -> 0.75,
+//!     "debug" -> False,
+//!     "tags" -> {"a", "b"}
+//! |>"#).unwrap();
+//!
+//! assert_eq!(config["port"], LiteralValue::Integer(8080));
+//! assert_eq!(config["debug"], LiteralValue::Boolean(false));
+//! assert_eq!(config["name"].as_str(), Some("server"));
+//! ```
+//!
+//! Any syntax outside of [`GrammarProfile::Data`], such as function calls, is
+//! rejected with an error instead of being returned.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::{
+    ast::Ast,
+    issue::{Issue, SyntaxErrors},
+    source::Source,
+    tokenize::TokenKind,
+    GrammarProfile, ParseOptions,
+};
+
+/// A literal Wolfram Language data value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue {
+    /// `Null`, or an implicit `Null` like in `{1, , 2}`.
+    Null,
+    /// `True` or `False`
+    Boolean(bool),
+    Integer(i64),
+    Real(f64),
+    String(String),
+    /// Any symbol other than `Null`, `True`, or `False`.
+    Symbol(String),
+    List(Vec<LiteralValue>),
+    Association(BTreeMap<String, LiteralValue>),
+}
+
+/// Error returned when input is not a valid literal data value.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum LiteralError {
+    /// The input contains syntax errors, or syntax not allowed by
+    /// [`GrammarProfile::Data`].
+    #[error("{}", syntax_error_message(.0))]
+    Syntax(Vec<Issue>),
+
+    #[error("expected a single expression, found {0}")]
+    ExpectedSingleExpression(usize),
+
+    #[error("expected an association at {0}")]
+    ExpectedAssociation(Source),
+
+    #[error("association key is not a string at {0}")]
+    NonStringKey(Source),
+
+    #[error("unsupported literal `{input}` at {src}")]
+    Unsupported { input: String, src: Source },
+}
+
+//======================================
+// API
+//======================================
+
+/// Parse input containing a single association with string keys, like
+/// `<|"key" -> value, ...|>`.
+///
+/// If an association contains a key more than once, the last value is used.
+pub fn parse_association(
+    input: &str,
+) -> Result<BTreeMap<String, LiteralValue>, LiteralError> {
+    let ast = parse_data(input)?;
+
+    match LiteralValue::from_ast(&ast)? {
+        LiteralValue::Association(map) => Ok(map),
+        _ => Err(LiteralError::ExpectedAssociation(ast.source().clone())),
+    }
+}
+
+/// Parse input containing a single literal data value.
+pub fn parse_literal(input: &str) -> Result<LiteralValue, LiteralError> {
+    let ast = parse_data(input)?;
+
+    LiteralValue::from_ast(&ast)
+}
+
+fn parse_data(input: &str) -> Result<Ast, LiteralError> {
+    let opts =
+        ParseOptions::builtin_default().grammar_profile(GrammarProfile::Data);
+
+    let result = crate::parse_ast_seq(input, &opts);
+
+    if !result.fatal_issues.is_empty() {
+        return Err(LiteralError::Syntax(result.fatal_issues));
+    }
+
+    let syntax_errors = result.syntax.syntax_errors();
+
+    if !syntax_errors.is_empty() {
+        return Err(LiteralError::Syntax(syntax_errors));
+    }
+
+    let crate::NodeSeq(mut exprs) = result.syntax;
+
+    match exprs.len() {
+        1 => Ok(exprs.remove(0)),
+        len => Err(LiteralError::ExpectedSingleExpression(len)),
+    }
+}
+
+//======================================
+// Impls
+//======================================
+
+impl LiteralValue {
+    /// Convert an abstract syntax tree into a literal value.
+    pub fn from_ast(ast: &Ast) -> Result<Self, LiteralError> {
+        match ast {
+            Ast::Leaf { kind, input, .. } => {
+                let input = input.to_str();
+
+                let value = match kind {
+                    TokenKind::Symbol => match input {
+                        "Null" => LiteralValue::Null,
+                        "True" => LiteralValue::Boolean(true),
+                        "False" => LiteralValue::Boolean(false),
+                        _ => LiteralValue::Symbol(input.to_owned()),
+                    },
+                    TokenKind::String => LiteralValue::String(
+                        ast.as_string_value()
+                            .ok_or_else(|| unsupported(ast))?,
+                    ),
+                    TokenKind::Integer => parse_integer(input)
+                        .map(LiteralValue::Integer)
+                        .ok_or_else(|| unsupported(ast))?,
+                    TokenKind::Real => parse_real(input)
+                        .map(LiteralValue::Real)
+                        .ok_or_else(|| unsupported(ast))?,
+                    _ => return Err(unsupported(ast)),
+                };
+
+                Ok(value)
+            },
+            Ast::Call { .. } => {
+                let Some((head, args)) = ast.as_symbol_call() else {
+                    return Err(unsupported(ast));
+                };
+
+                match (head, args) {
+                    ("List", elements) => Ok(LiteralValue::List(
+                        elements
+                            .iter()
+                            .map(LiteralValue::from_ast)
+                            .collect::<Result<_, _>>()?,
+                    )),
+                    ("Association", rules) => {
+                        let map = rules
+                            .iter()
+                            .map(rule_from_ast)
+                            .collect::<Result<_, _>>()?;
+
+                        Ok(LiteralValue::Association(map))
+                    },
+                    // `+1`
+                    ("Plus", [number]) => {
+                        match LiteralValue::from_ast(number)? {
+                            value @ (LiteralValue::Integer(_)
+                            | LiteralValue::Real(_)) => Ok(value),
+                            _ => Err(unsupported(ast)),
+                        }
+                    },
+                    _ => Err(unsupported(ast)),
+                }
+            },
+            _ => Err(unsupported(ast)),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            LiteralValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            LiteralValue::Integer(int) => Some(int),
+            _ => None,
+        }
+    }
+
+    /// Get this value as a float, converting integers if necessary.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            LiteralValue::Integer(int) => Some(int as f64),
+            LiteralValue::Real(real) => Some(real),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            LiteralValue::Boolean(bool) => Some(bool),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[LiteralValue]> {
+        match self {
+            LiteralValue::List(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_association(&self) -> Option<&BTreeMap<String, LiteralValue>> {
+        match self {
+            LiteralValue::Association(map) => Some(map),
+            _ => None,
+        }
+    }
+}
+
+/// Convert a `"key" -> value` association element.
+fn rule_from_ast(rule: &Ast) -> Result<(String, LiteralValue), LiteralError> {
+    let Some(("Rule" | "RuleDelayed", [key, value])) = rule.as_symbol_call()
+    else {
+        return Err(unsupported(rule));
+    };
+
+    let key = key
+        .as_string_value()
+        .ok_or_else(|| LiteralError::NonStringKey(key.source().clone()))?;
+
+    Ok((key, LiteralValue::from_ast(value)?))
+}
+
+fn unsupported(ast: &Ast) -> LiteralError {
+    let input = match ast {
+        Ast::Leaf { input, .. } | Ast::Error { input, .. } => {
+            input.to_str().to_owned()
+        },
+        _ => match ast.as_symbol_call() {
+            Some((head, _)) => format!("{head}[...]"),
+            None => "expression".to_owned(),
+        },
+    };
+
+    LiteralError::Unsupported {
+        input,
+        src: ast.source().clone(),
+    }
+}
+
+//======================================
+// Numbers
+//======================================
+
+/// Parse integer literal syntax like `123`, `-123`, `16^^FF`, or `12*^3`.
+///
+/// The exponent of `*^` is in the base of the digits, so `16^^FF*^2` is
+/// `255 * 16^2`.
+///
+/// Returns `None` if the value does not fit in an `i64`.
+pub(crate) fn parse_integer(input: &str) -> Option<i64> {
+    let (negative, digits) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+
+    let (digits, exponent) = match digits.split_once("*^") {
+        Some((digits, exponent)) => (digits, exponent.parse::<u32>().ok()?),
+        None => (digits, 0),
+    };
+
+    let (base, value) = match digits.split_once("^^") {
+        Some((base, digits)) => {
+            let base = base.parse::<u32>().ok()?;
+
+            if !(2..=36).contains(&base) {
+                return None;
+            }
+
+            (base, i64::from_str_radix(digits, base).ok()?)
+        },
+        None => (10, digits.parse::<i64>().ok()?),
+    };
+
+    let value = value.checked_mul(i64::from(base).checked_pow(exponent)?)?;
+
+    Some(if negative { -value } else { value })
+}
+
+/// Parse real literal syntax like `1.5`, `-1.5`, `1.5``20`, or `1.5*^-3`.
+///
+/// Precision and accuracy marks are ignored. Reals written in a base other
+/// than 10 are not supported.
+pub(crate) fn parse_real(input: &str) -> Option<f64> {
+    if input.contains("^^") {
+        return None;
+    }
+
+    let (mantissa, exponent) = match input.split_once("*^") {
+        Some((mantissa, exponent)) => (mantissa, exponent),
+        None => (input, "0"),
+    };
+
+    // Strip precision or accuracy
+    let mantissa = match mantissa.split_once('`') {
+        Some((mantissa, _)) => mantissa,
+        None => mantissa,
+    };
+
+    format!("{mantissa}e{exponent}").parse::<f64>().ok()
+}
+
+pub(crate) fn syntax_error_message(issues: &[Issue]) -> String {
+    match issues.first() {
+        Some(issue) => format!("syntax error at {}: {}", issue.src, issue.msg),
+        None => "syntax error".to_owned(),
+    }
+}
+
+//======================================
+// Tests
+//======================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numbers() {
+        assert_eq!(parse_integer("123"), Some(123));
+        assert_eq!(parse_integer("-123"), Some(-123));
+        assert_eq!(parse_integer("16^^FF"), Some(255));
+        assert_eq!(parse_integer("12*^3"), Some(12000));
+        assert_eq!(parse_integer("99999999999999999999"), None);
+
+        assert_eq!(parse_real("1.5"), Some(1.5));
+        assert_eq!(parse_real("-1.5"), Some(-1.5));
+        assert_eq!(parse_real("1."), Some(1.0));
+        assert_eq!(parse_real("1.5`"), Some(1.5));
+        assert_eq!(parse_real("1.5``20"), Some(1.5));
+        assert_eq!(parse_real("1.5`20*^-3"), Some(0.0015));
+        assert_eq!(parse_real("2^^1.1"), None);
+    }
+}
@@ -0,0 +1,262 @@
+//! Counters of the work done while reading, tokenizing, and parsing input.
+//!
+//! [`collect()`] counts events like decoded escape sequences, tokens of
+//! common kinds, and created nodes while running a closure, and returns the
+//! counts along with the result:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     diagnostics::{self, Counter},
+//!     parse_cst, ParseOptions,
+//! };
+//!
+//! let (_, counts) = diagnostics::collect(|| {
+//!     parse_cst("f[a + b]", &ParseOptions::default())
+//! });
+//!
+//! assert_eq!(counts.get(Counter::Node_CallNodeCount), 1);
+//! assert_eq!(counts.get(Counter::Node_InfixNodeCount), 1);
+//!
+//! assert!(counts.get(Counter::Tokenizer_OpenSquareCount) > 0);
+//! ```
+//!
+//! The tokenizer and decoder counters measure work, not the contents of the
+//! input: input that is read more than once, like a token that is peeked
+//! before it is consumed, is counted each time it is read. Use
+//! [`tokenize::count()`][crate::tokenize::count] to count the tokens in some
+//! input.
+//!
+//! Counts are kept separately for each thread, so several threads can
+//! collect counts at the same time without seeing each other's work. Work
+//! done on other threads while the closure runs is not counted.
+//!
+//! Counting has no effect on parsing. When no thread is collecting counts,
+//! each counted event costs a single relaxed atomic load.
+
+use std::{
+    cell::RefCell,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+thread_local! {
+    /// The counts of the innermost [`collect()`] call on this thread, if any.
+    static COUNTS: RefCell<Option<Diagnostics>> = const { RefCell::new(None) };
+}
+
+/// The number of threads currently in a [`collect()`] call.
+///
+/// Checked before accessing [`COUNTS`], so that counting is cheap when it is
+/// not being used.
+static COLLECTING: AtomicUsize = AtomicUsize::new(0);
+
+macro_rules! counters {
+    ($($name:ident,)*) => {
+        /// An event counted by [`collect()`].
+        ///
+        /// Counter names are prefixed with the component that counts them.
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum Counter {
+            $($name,)*
+        }
+
+        impl Counter {
+            /// Every counter, in declaration order.
+            pub const ALL: &'static [Counter] = &[$(Counter::$name,)*];
+
+            /// The name of this counter, e.g. `"Tokenizer_SymbolCount"`.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Counter::$name => stringify!($name),)*
+                }
+            }
+        }
+    };
+}
+
+counters! {
+    ByteDecoder_PrintableCount,
+    ByteDecoder_LineFeedCount,
+    ByteDecoder_TabCount,
+    ByteDecoder_CarriageReturnCount,
+    ByteDecoder_1ByteCount,
+    ByteDecoder_2ByteCount,
+    ByteDecoder_3ByteCount,
+    ByteDecoder_4ByteCount,
+    ByteDecoder_FFCount,
+    ByteDecoder_Incomplete1ByteCount,
+
+    CharacterDecoder_UnescapedCount,
+    CharacterDecoder_LongNameCount,
+    CharacterDecoder_2HexCount,
+    CharacterDecoder_4HexCount,
+    CharacterDecoder_6HexCount,
+    CharacterDecoder_OctalCount,
+    CharacterDecoder_UnhandledCount,
+    CharacterDecoder_StringMetaDoubleQuoteCount,
+    CharacterDecoder_StringMetaOpenCount,
+    CharacterDecoder_StringMetaCloseCount,
+    CharacterDecoder_StringMetaBackslashCount,
+    CharacterDecoder_StringMetaBackspaceCount,
+    CharacterDecoder_StringMetaFormFeedCount,
+    CharacterDecoder_StringMetaLineFeedCount,
+    CharacterDecoder_StringMetaCarriageReturnCount,
+    CharacterDecoder_StringMetaTabCount,
+    CharacterDecoder_LinearSyntaxBangCount,
+    CharacterDecoder_LinearSyntaxPercentCount,
+    CharacterDecoder_LinearSyntaxAmpCount,
+    CharacterDecoder_LinearSyntaxOpenParenCount,
+    CharacterDecoder_LinearSyntaxCloseParenCount,
+    CharacterDecoder_LinearSyntaxStarCount,
+    CharacterDecoder_LinearSyntaxPlusCount,
+    CharacterDecoder_LinearSyntaxSlashCount,
+    CharacterDecoder_LinearSyntaxAtCount,
+    CharacterDecoder_LinearSyntaxCaretCount,
+    CharacterDecoder_LinearSyntaxUnderscoreCount,
+    CharacterDecoder_LinearSyntaxBacktickCount,
+    CharacterDecoder_LinearSyntaxSpaceCount,
+
+    Tokenizer_SymbolCount,
+    Tokenizer_NumberCount,
+    Tokenizer_StringFastCount,
+    Tokenizer_StringSlowCount,
+    Tokenizer_CommentCount,
+    Tokenizer_WhitespaceCount,
+    Tokenizer_NewlineCount,
+    Tokenizer_LineContinuationCount,
+    Tokenizer_CommaCount,
+    Tokenizer_OpenParenCount,
+    Tokenizer_CloseParenCount,
+    Tokenizer_OpenSquareCount,
+    Tokenizer_CloseSquareCount,
+    Tokenizer_OpenCurlyCount,
+    Tokenizer_CloseCurlyCount,
+    Tokenizer_PlusCount,
+    Tokenizer_MinusCount,
+    Tokenizer_MinusGreaterCount,
+    Tokenizer_ColonGreaterCount,
+    Tokenizer_HashCount,
+    Tokenizer_AmpCount,
+
+    Node_PrefixNodeCount,
+    Node_BinaryNodeCount,
+    Node_InfixNodeCount,
+    Node_TernaryNodeCount,
+    Node_PostfixNodeCount,
+    Node_PrefixBinaryNodeCount,
+    Node_CallNodeCount,
+    Node_GroupNodeCount,
+    Node_GroupMissingCloserNodeCount,
+    Node_SyntaxErrorNodeCount,
+}
+
+/// The counts of each [`Counter`], as returned by [`collect()`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Indexed by the position of each counter in [`Counter::ALL`].
+    counts: Box<[u64]>,
+}
+
+/// Run `f`, counting the events that occur on this thread while it runs.
+///
+/// Each call starts from zero. Calls can be nested: the counts of an inner
+/// call are also included in the counts of the outer call.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Diagnostics) {
+    /// Restores the counts of the outer call, even if `f` panics.
+    struct Guard {
+        outer: Option<Diagnostics>,
+    }
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            COLLECTING.fetch_sub(1, Ordering::Relaxed);
+
+            let inner = COUNTS.replace(self.outer.take());
+
+            COUNTS.with_borrow_mut(|outer| {
+                if let (Some(outer), Some(inner)) = (outer, inner) {
+                    *outer += &inner;
+                }
+            });
+        }
+    }
+
+    let guard = Guard {
+        outer: COUNTS.replace(Some(Diagnostics::default())),
+    };
+    COLLECTING.fetch_add(1, Ordering::Relaxed);
+
+    let value = f();
+
+    let counts =
+        COUNTS.with_borrow(|counts| counts.clone().unwrap_or_default());
+
+    drop(guard);
+
+    (value, counts)
+}
+
+/// Count one occurrence of `counter`, if this thread is collecting counts.
+#[inline]
+pub(crate) fn increment(counter: Counter) {
+    if COLLECTING.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    COUNTS.with_borrow_mut(|counts| {
+        if let Some(counts) = counts {
+            counts.counts[counter as usize] += 1;
+        }
+    });
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Diagnostics {
+    /// Number of times `counter` was counted.
+    pub fn get(&self, counter: Counter) -> u64 {
+        self.counts[counter as usize]
+    }
+
+    /// Iterate over every counter that was counted at least once, with its
+    /// count, in [`Counter::ALL`] order.
+    pub fn iter(&self) -> impl Iterator<Item = (Counter, u64)> + '_ {
+        Counter::ALL
+            .iter()
+            .zip(self.counts.iter())
+            .filter(|(_, count)| **count > 0)
+            .map(|(counter, count)| (*counter, *count))
+    }
+}
+
+/// Nothing counted.
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Diagnostics {
+            counts: vec![0; Counter::ALL.len()].into_boxed_slice(),
+        }
+    }
+}
+
+/// Add the counts of `other`, e.g. to total the counts of several files.
+impl std::ops::AddAssign<&Diagnostics> for Diagnostics {
+    fn add_assign(&mut self, other: &Diagnostics) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other;
+        }
+    }
+}
+
+impl fmt::Debug for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.iter().map(|(counter, count)| (counter.name(), count)),
+            )
+            .finish()
+    }
+}
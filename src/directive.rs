@@ -0,0 +1,287 @@
+//! Structured directives and annotations embedded in comments.
+//!
+//! Two forms of comment are recognized:
+//!
+//! * **Settings**, written as `(* wolfram-parse: key=value, flag *)`. Each
+//!   `key=value` or `key` entry becomes one [`DirectiveKind::Setting`].
+//!   Entries are separated by commas or whitespace.
+//! * **Annotations**, written as `(*@ text @*)`. The text between the `@`
+//!   markers becomes a [`DirectiveKind::Annotation`].
+//!
+//! Other comments are ignored. Directives found while parsing are available
+//! as [`ParseResult::directives`][crate::ParseResult::directives]:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     directive::DirectiveKind, parse_cst_seq, ParseOptions,
+//! };
+//!
+//! let result = parse_cst_seq(
+//!     "(* wolfram-parse: max-line-length=100 *)\nf[x] (*@ deprecated @*)",
+//!     &ParseOptions::default(),
+//! );
+//!
+//! let kinds: Vec<_> = result.directives.iter().map(|d| &d.kind).collect();
+//!
+//! assert_eq!(
+//!     kinds,
+//!     [
+//!         &DirectiveKind::Setting {
+//!             key: "max-line-length".to_owned(),
+//!             value: Some("100".to_owned()),
+//!         },
+//!         &DirectiveKind::Annotation("deprecated".to_owned()),
+//!     ]
+//! );
+//! ```
+
+use crate::source::{Location, Span};
+
+/// Prefix of the comment text of a settings directive.
+const SETTINGS_PREFIX: &str = "wolfram-parse:";
+
+/// Keys of the setting that declares the tab width of a file.
+///
+/// `tab_width` is the [EditorConfig](https://editorconfig.org) spelling.
+const TAB_WIDTH_KEYS: &[&str] = &["tab-width", "tab_width"];
+
+/// A directive found in a comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Directive {
+    pub kind: DirectiveKind,
+    /// Source of the comment containing this directive.
+    pub src: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DirectiveKind {
+    /// A `key=value` or `key` entry in a `(* wolfram-parse: ... *)` comment.
+    Setting { key: String, value: Option<String> },
+    /// The text of a `(*@ ... @*)` comment, with surrounding whitespace
+    /// removed.
+    Annotation(String),
+}
+
+/// A `wolfram-parse:` setting entry that could not be parsed, like `=100`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InvalidSetting {
+    pub entry: String,
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Directive {
+    /// If this is a [`DirectiveKind::Setting`], get its key and value.
+    pub fn as_setting(&self) -> Option<(&str, Option<&str>)> {
+        match &self.kind {
+            DirectiveKind::Setting { key, value } => {
+                Some((key.as_str(), value.as_deref()))
+            },
+            DirectiveKind::Annotation(_) => None,
+        }
+    }
+
+    /// If this is a [`DirectiveKind::Annotation`], get its text.
+    pub fn as_annotation(&self) -> Option<&str> {
+        match &self.kind {
+            DirectiveKind::Annotation(text) => Some(text),
+            DirectiveKind::Setting { .. } => None,
+        }
+    }
+}
+
+/// Get the tab width declared by a `(* wolfram-parse: tab-width=N *)` setting
+/// at the start of `input`.
+///
+/// The directive must be the first thing on the first line of `input`, or on
+/// the second line if the first line is a `#!` script line. The
+/// [EditorConfig](https://editorconfig.org) key `tab_width` is also accepted.
+///
+/// ```
+/// use wolfram_parser::directive::detect_tab_width;
+///
+/// let script = "#!/usr/bin/env wolframscript\n(* wolfram-parse: tab_width=2 *)";
+///
+/// assert_eq!(detect_tab_width("(* wolfram-parse: tab-width=8 *)"), Some(8));
+/// assert_eq!(detect_tab_width(script), Some(2));
+///
+/// assert_eq!(detect_tab_width("f (* wolfram-parse: tab-width=8 *)"), None);
+/// assert_eq!(detect_tab_width("(* wolfram-parse: tab-width=0 *)"), None);
+/// ```
+pub fn detect_tab_width(input: &str) -> Option<u32> {
+    let mut lines = input.lines();
+
+    let mut line = lines.next()?;
+
+    if line.starts_with("#!") {
+        line = lines.next()?;
+    }
+
+    let line = line.trim_start();
+
+    if !line.starts_with("(*") {
+        return None;
+    }
+
+    let end = line.find("*)")? + 2;
+
+    let mut directives = Vec::new();
+
+    let src = Span::new(Location::start(), Location::start());
+
+    let _ = parse_comment(&line[..end], src, &mut directives);
+
+    directives
+        .iter()
+        .filter_map(Directive::as_setting)
+        .filter(|(key, _)| TAB_WIDTH_KEYS.contains(key))
+        .find_map(|(_, value)| value?.parse::<u32>().ok())
+        .filter(|tab_width| *tab_width > 0)
+}
+
+/// Returns true if `comment`, the complete input of a comment token, is an
+/// annotation or settings directive.
+pub(crate) fn is_directive(comment: &str) -> bool {
+    let Some(body) = comment
+        .strip_prefix("(*")
+        .and_then(|rest| rest.strip_suffix("*)"))
+    else {
+        return false;
+    };
+
+    (body.len() >= 2 && body.starts_with('@') && body.ends_with('@'))
+        || body.trim_start().starts_with(SETTINGS_PREFIX)
+}
+
+/// Parse the directives in `comment`, the complete input of a
+/// [`TokenKind::Comment`][crate::tokenize::TokenKind::Comment] token.
+///
+/// Valid entries are appended to `directives`, and any invalid setting
+/// entries are returned.
+pub(crate) fn parse_comment(
+    comment: &str,
+    src: Span,
+    directives: &mut Vec<Directive>,
+) -> Vec<InvalidSetting> {
+    let Some(body) = comment
+        .strip_prefix("(*")
+        .and_then(|rest| rest.strip_suffix("*)"))
+    else {
+        return vec![];
+    };
+
+    // (*@ ... @*)
+    if let Some(text) = body
+        .strip_prefix('@')
+        .and_then(|rest| rest.strip_suffix('@'))
+    {
+        directives.push(Directive {
+            kind: DirectiveKind::Annotation(text.trim().to_owned()),
+            src,
+        });
+
+        return vec![];
+    }
+
+    // (* wolfram-parse: ... *)
+    let Some(settings) = body.trim_start().strip_prefix(SETTINGS_PREFIX) else {
+        return vec![];
+    };
+
+    let mut invalid = Vec::new();
+
+    for entry in settings
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+    {
+        let (key, value) = match entry.split_once('=') {
+            Some((key, value)) => (key, Some(value.to_owned())),
+            None => (entry, None),
+        };
+
+        if key.is_empty() {
+            invalid.push(InvalidSetting {
+                entry: entry.to_owned(),
+            });
+            continue;
+        }
+
+        directives.push(Directive {
+            kind: DirectiveKind::Setting {
+                key: key.to_owned(),
+                value,
+            },
+            src,
+        });
+    }
+
+    invalid
+}
+
+//======================================
+// Tests
+//======================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::macros::src;
+
+    fn parse(comment: &str) -> (Vec<DirectiveKind>, Vec<InvalidSetting>) {
+        let mut directives = Vec::new();
+
+        let invalid =
+            parse_comment(comment, src!(1:1-1:2).into(), &mut directives);
+
+        (directives.into_iter().map(|d| d.kind).collect(), invalid)
+    }
+
+    fn setting(key: &str, value: Option<&str>) -> DirectiveKind {
+        DirectiveKind::Setting {
+            key: key.to_owned(),
+            value: value.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn test_parse_comment() {
+        assert_eq!(parse("(* plain comment *)"), (vec![], vec![]));
+        assert_eq!(parse("(**)"), (vec![], vec![]));
+
+        assert_eq!(
+            parse("(* wolfram-parse: a=1, b c=x=y *)"),
+            (
+                vec![
+                    setting("a", Some("1")),
+                    setting("b", None),
+                    setting("c", Some("x=y")),
+                ],
+                vec![]
+            )
+        );
+
+        assert_eq!(
+            parse("(*wolfram-parse: =1 a= *)"),
+            (
+                vec![setting("a", Some(""))],
+                vec![InvalidSetting {
+                    entry: "=1".to_owned()
+                }]
+            )
+        );
+
+        assert_eq!(
+            parse("(*@ tag: value @*)"),
+            (
+                vec![DirectiveKind::Annotation("tag: value".to_owned())],
+                vec![]
+            )
+        );
+
+        // Not an annotation
+        assert_eq!(parse("(*@ tag *)"), (vec![], vec![]));
+    }
+}
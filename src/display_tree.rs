@@ -0,0 +1,426 @@
+//! Render syntax trees as indented text, for people to read.
+//!
+//! [`Cst::display_tree()`] and [`Ast::display_tree()`] draw one line per
+//! node, with the kind of the node, the text of tokens, and the source span
+//! of every node:
+//!
+//! ```
+//! use wolfram_parser::{parse_cst, ParseOptions};
+//!
+//! let cst = parse_cst("f[x] + 1", &ParseOptions::default()).syntax;
+//!
+//! assert_eq!(
+//!     cst.display_tree().to_string(),
+//!     "\
+//! Infix Plus 1:1-9
+//! ├── Call 1:1-5
+//! │   ├── Token Symbol \"f\" 1:1-2
+//! │   └── Group CodeParser_GroupSquare 1:2-5
+//! │       ├── Token OpenSquare \"[\" 1:2-3
+//! │       ├── Token Symbol \"x\" 1:3-4
+//! │       └── Token CloseSquare \"]\" 1:4-5
+//! ├── Token Whitespace \" \" 1:5-6
+//! ├── Token Plus \"+\" 1:6-7
+//! ├── Token Whitespace \" \" 1:7-8
+//! └── Token Integer \"1\" 1:8-9
+//! "
+//! );
+//! ```
+//!
+//! Use [`DisplayTree::ansi()`] to color the output for a terminal.
+
+use std::fmt::{self, Display};
+
+use crate::{
+    ast::Ast,
+    cst::{
+        BinaryNode, BoxNode, CallBody, CallHead, CallNode, CompoundNode, Cst,
+        CstSeq, GroupMissingCloserNode, GroupMissingOpenerNode, GroupNode,
+        InfixNode, OperatorNode, PostfixNode, PrefixBinaryNode, PrefixNode,
+        SyntaxErrorNode, TernaryNode,
+    },
+    source::Source,
+    tokenize::{Token, TokenInput, TokenSource},
+    NodeSeq,
+};
+
+/// An indented tree of syntax nodes, as returned by [`Cst::display_tree()`]
+/// and [`Ast::display_tree()`].
+///
+/// The tree is drawn by the [`Display`] implementation of this type.
+#[derive(Debug, Clone)]
+pub struct DisplayTree {
+    roots: Vec<TreeNode>,
+    ansi: bool,
+}
+
+#[derive(Debug, Clone)]
+struct TreeNode {
+    /// Like `Infix` or `Token`.
+    kind: &'static str,
+    /// Like the operator of an `Infix` node, or the kind of a `Token`.
+    detail: Option<String>,
+    /// The input text of a token.
+    text: Option<String>,
+    source: Source,
+    /// Whether this node is a syntax error.
+    error: bool,
+    children: Vec<TreeNode>,
+}
+
+/// ANSI escape codes used by [`DisplayTree::ansi()`].
+const KIND_STYLE: &str = "1;34";
+const ERROR_STYLE: &str = "1;31";
+const DETAIL_STYLE: &str = "36";
+const TEXT_STYLE: &str = "32";
+const SOURCE_STYLE: &str = "2";
+
+//======================================
+// Impls
+//======================================
+
+impl DisplayTree {
+    /// Color the tree using ANSI terminal escape codes.
+    pub fn ansi(self) -> Self {
+        DisplayTree { ansi: true, ..self }
+    }
+
+    fn new(roots: Vec<TreeNode>) -> Self {
+        DisplayTree { roots, ansi: false }
+    }
+
+    fn paint(
+        &self,
+        f: &mut fmt::Formatter,
+        code: &str,
+        text: &str,
+    ) -> fmt::Result {
+        if self.ansi {
+            write!(f, "\x1b[{code}m{text}\x1b[0m")
+        } else {
+            write!(f, "{text}")
+        }
+    }
+
+    fn write_node(
+        &self,
+        f: &mut fmt::Formatter,
+        node: &TreeNode,
+        prefix: &mut String,
+    ) -> fmt::Result {
+        let TreeNode {
+            kind,
+            detail,
+            text,
+            source,
+            error,
+            children,
+        } = node;
+
+        let kind_style = if *error { ERROR_STYLE } else { KIND_STYLE };
+
+        self.paint(f, kind_style, kind)?;
+
+        if let Some(detail) = detail {
+            write!(f, " ")?;
+            self.paint(f, DETAIL_STYLE, detail)?;
+        }
+
+        if let Some(text) = text {
+            write!(f, " ")?;
+            self.paint(f, TEXT_STYLE, &format!("{text:?}"))?;
+        }
+
+        write!(f, " ")?;
+        self.paint(f, SOURCE_STYLE, &source.to_string())?;
+        writeln!(f)?;
+
+        for (index, child) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+
+            let (rule, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            write!(f, "{prefix}")?;
+            self.paint(f, SOURCE_STYLE, rule)?;
+
+            let len = prefix.len();
+            prefix.push_str(indent);
+            self.write_node(f, child, prefix)?;
+            prefix.truncate(len);
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DisplayTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for root in &self.roots {
+            self.write_node(f, root, &mut String::new())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TreeNode {
+    fn new(kind: &'static str, source: Source) -> Self {
+        TreeNode {
+            kind,
+            detail: None,
+            text: None,
+            source,
+            error: false,
+            children: Vec::new(),
+        }
+    }
+
+    fn detail(self, detail: impl Display) -> Self {
+        TreeNode {
+            detail: Some(detail.to_string()),
+            ..self
+        }
+    }
+
+    fn error(self) -> Self {
+        TreeNode {
+            error: true,
+            ..self
+        }
+    }
+
+    fn children(self, children: Vec<TreeNode>) -> Self {
+        TreeNode { children, ..self }
+    }
+}
+
+//======================================
+// Cst
+//======================================
+
+impl<I: TokenInput, S: TokenSource> Cst<I, S> {
+    /// Draw this tree as indented text, with the kind, token text, and
+    /// source of every node.
+    ///
+    /// See the [module documentation][crate::display_tree].
+    pub fn display_tree(&self) -> DisplayTree {
+        DisplayTree::new(vec![cst_tree(self)])
+    }
+}
+
+impl<I: TokenInput, S: TokenSource> CstSeq<I, S> {
+    /// Draw every tree in this sequence, one after the other.
+    pub fn display_tree(&self) -> DisplayTree {
+        let NodeSeq(nodes) = self;
+
+        DisplayTree::new(nodes.iter().map(cst_tree).collect())
+    }
+}
+
+fn cst_tree<I: TokenInput, S: TokenSource>(cst: &Cst<I, S>) -> TreeNode {
+    let source = cst.get_source().into_general();
+
+    match cst {
+        Cst::Token(token) => token_tree(token),
+        Cst::Call(CallNode { head, body }) => {
+            let mut children = match head {
+                CallHead::Concrete(NodeSeq(head)) => {
+                    head.iter().map(cst_tree).collect()
+                },
+                CallHead::Aggregate(head) => vec![cst_tree(head)],
+            };
+
+            children.push(match body {
+                CallBody::Group(GroupNode(op)) => {
+                    operator_tree("Group", op, op.get_source())
+                },
+                CallBody::GroupMissingCloser(GroupMissingCloserNode(op)) => {
+                    operator_tree("GroupMissingCloser", op, op.get_source())
+                        .error()
+                },
+            });
+
+            TreeNode::new("Call", source).children(children)
+        },
+        Cst::SyntaxError(SyntaxErrorNode { err, children }) => {
+            TreeNode::new("SyntaxError", source)
+                .detail(format!("{err:?}"))
+                .error()
+                .children(seq_tree(children))
+        },
+        Cst::Prefix(PrefixNode(op)) => operator_tree("Prefix", op, source),
+        Cst::Infix(InfixNode(op)) => operator_tree("Infix", op, source),
+        Cst::Postfix(PostfixNode(op)) => operator_tree("Postfix", op, source),
+        Cst::Binary(BinaryNode(op)) => operator_tree("Binary", op, source),
+        Cst::Ternary(TernaryNode(op)) => operator_tree("Ternary", op, source),
+        Cst::PrefixBinary(PrefixBinaryNode(op)) => {
+            operator_tree("PrefixBinary", op, source)
+        },
+        Cst::Compound(CompoundNode(op)) => {
+            operator_tree("Compound", op, source)
+        },
+        Cst::Group(GroupNode(op)) => operator_tree("Group", op, source),
+        Cst::GroupMissingCloser(GroupMissingCloserNode(op)) => {
+            operator_tree("GroupMissingCloser", op, source).error()
+        },
+        Cst::GroupMissingOpener(GroupMissingOpenerNode(op)) => {
+            operator_tree("GroupMissingOpener", op, source).error()
+        },
+        Cst::Box(BoxNode { kind, children, .. }) => {
+            TreeNode::new("Box", source)
+                .detail(kind.as_str())
+                .children(seq_tree(children))
+        },
+        Cst::Code(_) => TreeNode::new("Code", source),
+    }
+}
+
+fn token_tree<I: TokenInput, S: TokenSource>(token: &Token<I, S>) -> TreeNode {
+    let Token { tok, input, src } = token;
+
+    let node = TreeNode {
+        text: Some(input.as_str().to_owned()),
+        ..TreeNode::new("Token", src.clone().into_general()).detail(tok.name())
+    };
+
+    if tok.isError() {
+        node.error()
+    } else {
+        node
+    }
+}
+
+fn operator_tree<I: TokenInput, S: TokenSource, O: fmt::Debug>(
+    kind: &'static str,
+    node: &OperatorNode<I, S, O>,
+    source: impl TokenSource,
+) -> TreeNode {
+    let OperatorNode { op, children } = node;
+
+    TreeNode::new(kind, source.into_general())
+        .detail(format!("{op:?}"))
+        .children(seq_tree(children))
+}
+
+fn seq_tree<I: TokenInput, S: TokenSource>(
+    children: &CstSeq<I, S>,
+) -> Vec<TreeNode> {
+    let NodeSeq(children) = children;
+
+    children.iter().map(cst_tree).collect()
+}
+
+//======================================
+// Ast
+//======================================
+
+impl Ast {
+    /// Draw this tree as indented text, with the kind, token text, and
+    /// source of every node.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_ast, ParseOptions};
+    ///
+    /// let ast = parse_ast("f[x]", &ParseOptions::default()).syntax;
+    ///
+    /// assert_eq!(
+    ///     ast.display_tree().to_string(),
+    ///     "\
+    /// Call 1:1-5
+    /// ├── Leaf Symbol \"f\" 1:1-2
+    /// └── Leaf Symbol \"x\" 1:3-4
+    /// "
+    /// );
+    /// ```
+    pub fn display_tree(&self) -> DisplayTree {
+        DisplayTree::new(vec![ast_tree(self)])
+    }
+}
+
+impl NodeSeq<Ast> {
+    /// Draw every tree in this sequence, one after the other.
+    pub fn display_tree(&self) -> DisplayTree {
+        let NodeSeq(nodes) = self;
+
+        DisplayTree::new(nodes.iter().map(ast_tree).collect())
+    }
+}
+
+fn ast_tree(ast: &Ast) -> TreeNode {
+    let source = ast.source().clone();
+
+    let node = match ast {
+        Ast::Leaf { kind, input, .. } => TreeNode {
+            text: Some(input.as_str().to_owned()),
+            ..TreeNode::new("Leaf", source).detail(kind.name())
+        },
+        Ast::Error { kind, input, .. } => TreeNode {
+            text: Some(input.as_str().to_owned()),
+            ..TreeNode::new("Error", source).detail(kind.name()).error()
+        },
+        Ast::Call { .. } => TreeNode::new("Call", source),
+        Ast::CallMissingCloser { .. } => {
+            TreeNode::new("CallMissingCloser", source).error()
+        },
+        Ast::SyntaxError { kind, .. } => TreeNode::new("SyntaxError", source)
+            .detail(format!("{kind:?}"))
+            .error(),
+        Ast::AbstractSyntaxError { kind, .. } => {
+            TreeNode::new("AbstractSyntaxError", source)
+                .detail(format!("{kind:?}"))
+                .error()
+        },
+        Ast::Unhandled {
+            cst_kind, reason, ..
+        } => TreeNode::new("Unhandled", source)
+            .detail(format!("{cst_kind:?}, {reason:?}")),
+        Ast::Box { kind, .. } => {
+            TreeNode::new("Box", source).detail(kind.as_str())
+        },
+        Ast::Code { .. } => TreeNode::new("Code", source),
+        Ast::Group { kind, .. } => {
+            TreeNode::new("Group", source).detail(format!("{kind:?}"))
+        },
+        Ast::GroupMissingCloser { kind, .. } => {
+            TreeNode::new("GroupMissingCloser", source)
+                .detail(format!("{kind:?}"))
+                .error()
+        },
+        Ast::GroupMissingOpener { kind, .. } => {
+            TreeNode::new("GroupMissingOpener", source)
+                .detail(format!("{kind:?}"))
+                .error()
+        },
+        Ast::TagBox_GroupParen { .. } => {
+            TreeNode::new("TagBox_GroupParen", source)
+        },
+        Ast::PrefixNode_PrefixLinearSyntaxBang(..) => {
+            TreeNode::new("PrefixNode_PrefixLinearSyntaxBang", source)
+        },
+        Ast::Elided {
+            subtrees, nodes, ..
+        } => TreeNode::new("Elided", source).detail(format!(
+            "{}, {}",
+            plural(*subtrees, "subtree"),
+            plural(*nodes, "node")
+        )),
+    };
+
+    let mut children = Vec::new();
+
+    ast.for_each_child(|child| children.push(ast_tree(child)));
+
+    node.children(children)
+}
+
+/// Like `1 node` or `2 nodes`.
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("{count} {noun}"),
+        _ => format!("{count} {noun}s"),
+    }
+}
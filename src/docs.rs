@@ -0,0 +1,370 @@
+//! Documentation comments for top-level definitions.
+//!
+//! [`doc_comments()`] associates each top-level definition in a file with the
+//! block of comments immediately above it. A block is a run of comments that
+//! are not separated from each other, or from the definition, by a blank
+//! line:
+//!
+//! ```
+//! use wolfram_parser::{docs, parse_cst_seq, ParseOptions};
+//!
+//! let input = r#"
+//! (* Square a number. *)
+//! (* Threads over lists. *)
+//! square[x_] := x^2
+//!
+//! (* Not documentation: followed by a blank line. *)
+//!
+//! cube[x_] := x^3
+//! "#;
+//!
+//! let opts = ParseOptions::default();
+//! let cst = parse_cst_seq(input, &opts).syntax;
+//!
+//! let docs = docs::doc_comments(&cst, &opts);
+//!
+//! assert_eq!(docs[0].symbol, "square");
+//! assert_eq!(docs[0].text(), "Square a number.\nThreads over lists.");
+//!
+//! assert_eq!(docs[1].symbol, "cube");
+//! assert!(docs[1].comments.is_empty());
+//! ```
+//!
+//! # Section markers
+//!
+//! Package files saved from a notebook mark the style of each cell with a
+//! comment like `(* ::Section:: *)`, followed by a comment containing the
+//! contents of the cell. Markers are reported as [`Comment`]s with a
+//! [`style`][Comment::style], and markers for heading styles like `Section`
+//! and `Subsection` open a [`Section`] that contains the definitions after
+//! it:
+//!
+//! ```
+//! use wolfram_parser::{docs, parse_cst_seq, ParseOptions};
+//!
+//! let input = r#"
+//! (* ::Section:: *)
+//! (*Arithmetic*)
+//!
+//! (* ::Subsection:: *)
+//! (*Powers*)
+//!
+//! square[x_] := x^2
+//! "#;
+//!
+//! let opts = ParseOptions::default();
+//! let cst = parse_cst_seq(input, &opts).syntax;
+//!
+//! let [doc] = docs::doc_comments(&cst, &opts).try_into().unwrap();
+//!
+//! let titles: Vec<_> =
+//!     doc.sections.iter().map(|s| s.title.as_deref().unwrap()).collect();
+//!
+//! assert_eq!(titles, ["Arithmetic", "Powers"]);
+//! ```
+
+use std::fmt::Debug;
+
+use crate::{
+    abstract_cst::{abstract_cst, aggregate_cst},
+    ast::Ast,
+    cst::{Cst, CstSeq},
+    notation,
+    source::Span,
+    tokenize::{Token, TokenInput, TokenKind},
+    NodeSeq, ParseOptions,
+};
+
+/// A top-level definition and the comments documenting it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocComment {
+    /// The symbol being defined, as it was written (including any context
+    /// prefix).
+    pub symbol: String,
+
+    /// The span of the top-level expression containing the definition.
+    pub span: Span,
+
+    /// The comments immediately above the definition, in source order.
+    pub comments: Vec<Comment>,
+
+    /// The sections containing the definition, outermost first.
+    pub sections: Vec<Section>,
+}
+
+/// A single comment in a [`DocComment`] block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// The contents of the comment, without the `(*` and `*)` delimiters and
+    /// surrounding whitespace.
+    pub text: String,
+
+    pub span: Span,
+
+    /// If this comment is a section marker like `(* ::Section:: *)`, the
+    /// name of the cell style, e.g. `"Section"`.
+    pub style: Option<String>,
+}
+
+/// A heading opened by a section marker comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// The name of the heading style, e.g. `"Subsection"`.
+    pub style: String,
+
+    /// The contents of the comment following the marker, if any.
+    pub title: Option<String>,
+
+    /// The span of the marker comment.
+    pub span: Span,
+}
+
+/// Heading styles, from outermost to innermost.
+const HEADING_STYLES: &[&str] = &[
+    "Title",
+    "Subtitle",
+    "Chapter",
+    "Section",
+    "Subsection",
+    "Subsubsection",
+    "Subsubsubsection",
+];
+
+//======================================
+// Impls
+//======================================
+
+impl DocComment {
+    /// The text of the comments in this block that are not section markers,
+    /// joined by newlines.
+    pub fn text(&self) -> String {
+        let texts: Vec<&str> = self
+            .comments
+            .iter()
+            .filter(|comment| comment.style.is_none())
+            .map(|comment| comment.text.as_str())
+            .collect();
+
+        texts.join("\n")
+    }
+}
+
+impl Comment {
+    fn new(input: &str, span: Span) -> Self {
+        let text = input
+            .strip_prefix("(*")
+            .and_then(|text| text.strip_suffix("*)"))
+            .unwrap_or(input)
+            .trim();
+
+        let style = text
+            .strip_prefix("::")
+            .and_then(|style| style.strip_suffix("::"))
+            .filter(|style| {
+                !style.is_empty()
+                    && style.chars().all(|c| c.is_ascii_alphanumeric())
+            })
+            .map(str::to_owned);
+
+        Comment {
+            text: text.to_owned(),
+            span,
+            style,
+        }
+    }
+}
+
+//======================================
+// Analysis
+//======================================
+
+/// Find the documentation comments of each top-level definition in `cst`.
+///
+/// `cst` is a whole file parsed with trivia, like the result of
+/// [`parse_cst_seq()`][crate::parse_cst_seq], and `opts` are the options it
+/// was parsed with.
+///
+/// Definitions are top-level `Set`, `SetDelayed`, `UpSet`, `UpSetDelayed`,
+/// `TagSet`, and `TagSetDelayed` expressions, including those in a top-level
+/// `CompoundExpression`. A statement like `f::usage = ".."` or
+/// `Options[f] = {..}` is a definition of `f`. Every definition is returned,
+/// in source order, even if it has no comments.
+pub fn doc_comments<I: TokenInput + Debug>(
+    cst: &CstSeq<I>,
+    opts: &ParseOptions,
+) -> Vec<DocComment> {
+    let NodeSeq(nodes) = cst;
+
+    let mut docs = Vec::new();
+
+    let mut block: Vec<Comment> = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
+
+    // Newlines since the last comment or expression.
+    let mut newlines = 0;
+
+    // Whether the last comment was a heading marker, and the next comment
+    // is its title.
+    let mut expect_title = false;
+
+    for node in nodes {
+        let is_blank_line = newlines >= 2;
+
+        match node {
+            Cst::Token(Token {
+                tok: TokenKind::ToplevelNewline | TokenKind::InternalNewline,
+                ..
+            }) => {
+                newlines += 1;
+                continue;
+            },
+            Cst::Token(Token {
+                tok: TokenKind::Comment,
+                input,
+                src,
+            }) => {
+                let comment = Comment::new(input.as_str(), *src);
+
+                if is_blank_line {
+                    block.clear();
+                }
+
+                match &comment.style {
+                    Some(style) => {
+                        expect_title =
+                            open_section(&mut sections, style, comment.span)
+                    },
+                    None if expect_title && !is_blank_line => {
+                        let section =
+                            sections.last_mut().expect("open section");
+                        section.title = Some(comment.text.clone());
+                        expect_title = false;
+                    },
+                    None => expect_title = false,
+                }
+
+                block.push(comment);
+                newlines = 0;
+                continue;
+            },
+            Cst::Token(token) if token.tok.isTrivia() => continue,
+            _ => (),
+        }
+
+        if is_blank_line {
+            block.clear();
+        }
+
+        let span = node.get_source();
+
+        for symbol in defined_symbols(node, opts) {
+            docs.push(DocComment {
+                symbol,
+                span,
+                comments: block.clone(),
+                sections: sections.clone(),
+            });
+        }
+
+        block.clear();
+        newlines = 0;
+        expect_title = false;
+    }
+
+    docs
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// Update `sections` for a marker comment with the given style, returning
+/// whether the style is a heading.
+fn open_section(sections: &mut Vec<Section>, style: &str, span: Span) -> bool {
+    let Some(level) = heading_level(style) else {
+        return false;
+    };
+
+    sections.retain(|section| {
+        heading_level(&section.style).is_some_and(|outer| outer < level)
+    });
+
+    sections.push(Section {
+        style: style.to_owned(),
+        title: None,
+        span,
+    });
+
+    true
+}
+
+fn heading_level(style: &str) -> Option<usize> {
+    HEADING_STYLES.iter().position(|heading| *heading == style)
+}
+
+/// The symbols defined by the top-level expression `node`.
+fn defined_symbols<I: TokenInput + Debug>(
+    node: &Cst<I>,
+    opts: &ParseOptions,
+) -> Vec<String> {
+    let Some(node) = aggregate_cst(node.clone()) else {
+        return Vec::new();
+    };
+
+    let ast = notation::with_notations(opts.notations.as_ref(), || {
+        abstract_cst(node, opts.quirk_settings)
+    });
+
+    let mut symbols = Vec::new();
+
+    collect_definitions(&ast, &mut symbols);
+
+    symbols
+}
+
+fn collect_definitions(ast: &Ast, symbols: &mut Vec<String>) {
+    let Some((head, args)) = ast.as_symbol_call() else {
+        return;
+    };
+
+    let lhs = match (head, args) {
+        ("CompoundExpression", statements) => {
+            for statement in statements {
+                collect_definitions(statement, symbols);
+            }
+            return;
+        },
+        ("Set" | "SetDelayed" | "UpSet" | "UpSetDelayed", [lhs, _]) => lhs,
+        ("TagSet" | "TagSetDelayed", [tag, _, _]) => tag,
+        _ => return,
+    };
+
+    if let Some(symbol) = defined_symbol(lhs) {
+        symbols.push(symbol.to_owned());
+    }
+}
+
+/// The symbol defined by a definition with left-hand side `lhs`.
+fn defined_symbol(lhs: &Ast) -> Option<&str> {
+    if let Some(symbol) = lhs.as_symbol() {
+        return Some(symbol);
+    }
+
+    match lhs.as_symbol_call() {
+        // HoldPattern[f[x_]] := .., f[x_] /; cond := ..
+        Some(("HoldPattern", [inner]) | ("Condition", [inner, _])) => {
+            defined_symbol(inner)
+        },
+        // f::usage = .., Options[f] = .., Attributes[f] = ..
+        Some((
+            "MessageName" | "Options" | "Attributes" | "Format" | "Default"
+            | "DownValues" | "OwnValues" | "UpValues" | "SubValues",
+            [symbol, ..],
+        )) if symbol.as_symbol().is_some() => symbol.as_symbol(),
+        _ => match lhs {
+            // f[x_] := .., f[a][b] := ..
+            Ast::Call { head, .. } => defined_symbol(head),
+            _ => None,
+        },
+    }
+}
@@ -0,0 +1,67 @@
+//! Edits to Wolfram Language source text.
+//!
+//! Transforms that rewrite source, like
+//! [`normalize_whitespace()`][crate::whitespace::normalize_whitespace], return
+//! a list of [`TextEdit`]s instead of the rewritten source, so that callers
+//! like editors can apply them incrementally. Use [`apply_edits()`] to get the
+//! rewritten source.
+//!
+//! ```
+//! use wolfram_parser::{edit, whitespace, ParseOptions};
+//!
+//! let input = "f[x]  \ng[y]";
+//!
+//! let edits = whitespace::normalize_whitespace(input, &ParseOptions::default());
+//!
+//! assert_eq!(edit::apply_edits(input, &edits), "f[x]\ng[y]\n");
+//! ```
+
+use std::ops::Range;
+
+use crate::source::Span;
+
+/// A replacement of a range of the input by new text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    /// The byte range of the input that is replaced.
+    ///
+    /// This is empty for insertions.
+    pub range: Range<usize>,
+    /// The source span of [`range`][TextEdit::range].
+    pub span: Span,
+    /// The text that replaces [`range`][TextEdit::range].
+    ///
+    /// This is empty for deletions.
+    pub new_text: String,
+}
+
+/// Apply `edits` to `input`, returning the edited input.
+///
+/// # Panics
+///
+/// Panics if `edits` are not sorted by range, if any edits overlap, or if any
+/// range is not on a character boundary of `input`.
+pub fn apply_edits(input: &str, edits: &[TextEdit]) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    let mut offset = 0;
+
+    for TextEdit {
+        range, new_text, ..
+    } in edits
+    {
+        assert!(
+            offset <= range.start && range.start <= range.end,
+            "apply_edits: edit at {range:?} overlaps or is out of order"
+        );
+
+        output.push_str(&input[offset..range.start]);
+        output.push_str(new_text);
+
+        offset = range.end;
+    }
+
+    output.push_str(&input[offset..]);
+
+    output
+}
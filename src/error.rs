@@ -5,18 +5,19 @@ use crate::{
     cst::{Cst, GroupMissingCloserNode, OperatorNode},
     parse::operators::GroupOperator,
     source::{
-        BufferAndLength, CharacterSpan, LineColumn, LineColumnSpan, Location,
+        BufferAndLength, CharIndexSpan, LineColumn, LineColumnSpan, Location,
         Span, SpanKind,
     },
     tokenize::{Token, TokenKind, TokenStr},
     utils::{non_zero_u32_add, non_zero_u32_incr},
-    NodeSeq,
+    NodeSeq, ReparseHeuristics,
 };
 
 pub(crate) fn reparse_unterminated<'i>(
     mut nodes: AggNodeSeq<TokenStr<'i>>,
     input: &'i str,
     tab_width: usize,
+    heuristics: ReparseHeuristics,
 ) -> AggNodeSeq<TokenStr<'i>> {
     // TODO(cleanup): Change function parameter to take tab width as u32.
     let tab_width = u32::try_from(tab_width).unwrap();
@@ -25,7 +26,9 @@ pub(crate) fn reparse_unterminated<'i>(
         let Cst::Token(token) = node else { return };
 
         if token.tok.isError() && token.tok.isUnterminated() {
-            reparse_unterminated_token_error_node(token, input, tab_width);
+            reparse_unterminated_token_error_node(
+                token, input, tab_width, heuristics,
+            );
         }
     });
 
@@ -37,6 +40,7 @@ pub(crate) fn reparse_unterminated_tokens<'i>(
     tokens: NodeSeq<Token<TokenStr<'i>>>,
     input: &'i str,
     tab_width: usize,
+    heuristics: ReparseHeuristics,
 ) -> NodeSeq<Token<TokenStr<'i>>> {
     // TODO(cleanup): Change function parameter to take tab width as u32.
     let tab_width = u32::try_from(tab_width).unwrap();
@@ -45,7 +49,9 @@ pub(crate) fn reparse_unterminated_tokens<'i>(
 
     for token in &mut tokens {
         if token.tok.isError() && token.tok.isUnterminated() {
-            reparse_unterminated_token_error_node(token, input, tab_width);
+            reparse_unterminated_token_error_node(
+                token, input, tab_width, heuristics,
+            );
         }
     }
 
@@ -65,14 +71,21 @@ pub(crate) fn reparse_unterminated_group_node<'i>(
     (tag, children): (GroupOperator, NodeSeq<Cst<TokenStr<'i>>>),
     str: &'i str,
     tab_width: usize,
+    heuristics: ReparseHeuristics,
 ) -> GroupMissingCloserNode<TokenStr<'i>> {
     let src = children.get_source();
 
+    let Some(Cst::Token(opener)) = children.0.first() else {
+        panic!("unterminated group does not start with an opener: {children:?}")
+    };
+
+    let start = offset_in(str, &opener.input);
+
     // TODO(cleanup): Change function parameter to take tab width as u32.
     let tab_width = u32::try_from(tab_width).unwrap();
 
     let (_, _, better_src) =
-        first_chunk_and_last_good_line(str, tab_width, src);
+        first_chunk_and_last_good_line(str, start, tab_width, src, heuristics);
 
     // Flatten out children, because there may be parsing errors from missing bracket, and
     // we do not want to propagate
@@ -130,18 +143,23 @@ fn reparse_unterminated_token_error_node<'i>(
     error: &mut Token<TokenStr<'i>>,
     str: &'i str,
     tab_width: u32,
+    heuristics: ReparseHeuristics,
 ) {
     debug_assert!(error.tok.isError() && error.tok.isUnterminated());
 
     // TODO: Use `input` here to optimize the process_lines() calculation?
-    let Token {
-        tok: _,
-        input: _,
-        src,
-    } = error;
+    let Token { tok: _, input, src } = error;
+
+    let start = offset_in(str, input);
 
     let (first_chunk, last_good_line_index, better_src) =
-        first_chunk_and_last_good_line(str, tab_width, src.clone());
+        first_chunk_and_last_good_line(
+            str,
+            start,
+            tab_width,
+            src.clone(),
+            heuristics,
+        );
 
     // Use original src Start, but readjust src End to be the EndOfLine of the
     // last good line of the chunk
@@ -182,8 +200,23 @@ fn reparse_unterminated_token_error_node<'i>(
 
             make_better_input(better_str2)
         },
-        SpanKind::CharacterSpan(better_src) => {
-            let better_str: &str = StringTake(str, better_src);
+        SpanKind::CharIndexSpan(better_src) => {
+            let SpanKind::CharIndexSpan(CharIndexSpan(src_start, _)) =
+                src.kind()
+            else {
+                unreachable!("better source has a different kind than source")
+            };
+
+            // Character index of the start of `str`, which is not the start of
+            // the complete input if only the end of it was kept.
+            let base = src_start - 1 - u32::try_from(start).unwrap();
+
+            let CharIndexSpan(better_start, better_end) = better_src;
+
+            let better_str: &str = StringTake(
+                str,
+                CharIndexSpan(better_start - base, better_end - base),
+            );
 
             make_better_input(better_str)
         },
@@ -206,24 +239,35 @@ fn make_better_input<'i>(better: &'i str) -> TokenStr<'i> {
 // Helpers
 //==========================================================
 
+/// `start` is the byte offset in `input` of the token or group at `src`.
+///
+/// Only the lines of `input` from the line containing `start` are used, so
+/// `input` may be just the end of the complete input, as long as it starts
+/// at the beginning of a line.
 fn first_chunk_and_last_good_line(
     input: &str,
+    start: usize,
     tab_width: u32,
     src: Span,
+    heuristics: ReparseHeuristics,
 ) -> (Vec<Line>, usize, Span) {
-    let lines = to_lines_and_expand_tabs(input, tab_width);
+    let line_start = input[..start].rfind(['\n', '\r']).map_or(0, |i| i + 1);
+
+    let lines = to_lines_and_expand_tabs(&input[line_start..], tab_width);
 
     //------------------------------------------------------
     // Filter `lines` into the lines that overlap with `src`
     //------------------------------------------------------
 
-    let (lines, char_ranges_of_lines): (Vec<Line>, Option<Vec<CharacterSpan>>) =
+    let (lines, char_ranges_of_lines): (Vec<Line>, Option<Vec<CharIndexSpan>>) =
         match src.kind() {
             SpanKind::LineColumnSpan(src) => {
                 // (*
                 // lines of the node
                 // *)
                 // lines = lines[[src[[1, 1]];;src[[2, 1]]]];
+                //
+                // `lines` starts at the line `src` starts on.
 
                 let start_line = src.start.line();
                 let end_line = src.end.line();
@@ -231,19 +275,27 @@ fn first_chunk_and_last_good_line(
                 (
                     retain_range(
                         lines,
-                        to_zero_index(start_line)..to_zero_index(end_line) + 1,
+                        0..to_zero_index(end_line) - to_zero_index(start_line)
+                            + 1,
                     ),
                     None,
                 )
             },
-            SpanKind::CharacterSpan(src) => {
-                let specs_of_lines = lines_start_and_end_char_indexes(lines);
+            SpanKind::CharIndexSpan(src) => {
+                let CharIndexSpan(src_start, _) = src;
+
+                // Character index of the start of `lines`.
+                let base = src_start - 1
+                    - u32::try_from(start - line_start).unwrap();
+
+                let specs_of_lines =
+                    lines_start_and_end_char_indexes(lines, base);
 
                 let CollectMultiple(lines, specs_of_lines): CollectMultiple<
                     Line,
-                    CharacterSpan,
+                    CharIndexSpan,
                 > = specs_of_lines
-                    .flat_map(|(line, pos): (Line, CharacterSpan)| {
+                    .flat_map(|(line, pos): (Line, CharIndexSpan)| {
                         // Only returns lines that intersect with the source character span.
                         if pos.intersects(src) {
                             Some((line, pos))
@@ -278,11 +330,22 @@ fn first_chunk_and_last_good_line(
             },
         };
 
+    let mut lines = lines;
+
+    if let Some(max_lookahead_lines) = heuristics.max_lookahead_lines {
+        // Always keep the line the token or group starts on.
+        lines.truncate(max_lookahead_lines.max(1) as usize);
+    }
+
     //--------------------------
     // Find first "useful" chunk
     //--------------------------
 
-    let chunks = split_into_chunks(&lines);
+    let chunks = if heuristics.split_at_toplevel_newlines {
+        split_into_chunks(&lines)
+    } else {
+        vec![lines.as_slice()]
+    };
 
     let first_chunk: &[Line] = chunks
         .iter()
@@ -320,11 +383,11 @@ fn first_chunk_and_last_good_line(
                 ),
             })
         },
-        SpanKind::CharacterSpan(src) => {
+        SpanKind::CharIndexSpan(src) => {
             // This WILL include newline at the end
             // FIXME?
 
-            let CharacterSpan(original_start, _) = src;
+            let CharIndexSpan(original_start, _) = src;
 
             let char_ranges_of_lines = char_ranges_of_lines.unwrap();
 
@@ -540,7 +603,7 @@ fn StringLength(s: &str) -> usize {
     s.len()
 }
 
-fn StringTake(s: &str, range: CharacterSpan) -> &str {
+fn StringTake(s: &str, range: CharIndexSpan) -> &str {
     // FIXME: This treats WL characters as bytes.
     &s[range.to_rust_range()]
 }
@@ -554,9 +617,10 @@ fn lines_start_and_end_char_indexes(
     // TODO(optimization): Make this take an `impl Iterator<..>` instead of
     //                     an allocated Vec.
     lines: Vec<Line>,
-) -> impl Iterator<Item = (Line, CharacterSpan)> {
+    start_index: u32,
+) -> impl Iterator<Item = (Line, CharIndexSpan)> {
     // Cumulative character index.
-    let mut current_character_index: u32 = 0;
+    let mut current_character_index: u32 = start_index;
 
     let fold_list = lines.into_iter().map(move |line: Line| {
         let Line { content, newline } = line;
@@ -575,12 +639,22 @@ fn lines_start_and_end_char_indexes(
 
         let end_index = current_character_index;
 
-        (line, CharacterSpan(start_index, end_index))
+        (line, CharIndexSpan(start_index, end_index))
     });
 
     fold_list
 }
 
+/// Byte offset of `token` in `input`, which it must be a slice of.
+fn offset_in(input: &str, token: &TokenStr) -> usize {
+    let offset =
+        token.buf.as_bytes().as_ptr() as usize - input.as_ptr() as usize;
+
+    debug_assert!(offset <= input.len());
+
+    offset
+}
+
 fn retain_range<T>(mut vec: Vec<T>, range: Range<usize>) -> Vec<T> {
     let Range { start, end } = range;
 
@@ -2,9 +2,42 @@
 //!
 //! This module provides comprehensive error handling with zero-cost abstractions
 //! for safe parsing operations.
+//!
+//! [`ParseError`] is the root of the error hierarchy. Every error returned by a
+//! public entry point either is a `ParseError` or converts into one with `?`.
+//!
+//! The parsing, tokenizing, and file and reader entry points, and
+//! [`ParseResult::into_result()`][crate::ParseResult::into_result], return
+//! `ParseError` itself. A few functions return a narrower error, because every
+//! way they can fail is specific to them, and a caller matching on the error
+//! should not have to handle variants that cannot occur:
+//!
+//! * [`parse_from_tokens()`][crate::parse_from_tokens] returns a
+//!   [`TokenStreamError`] describing the inconsistent token.
+//! * [`NodeSeq::try_single()`][crate::NodeSeq::try_single] returns a
+//!   [`NodeSeqError`].
+//! * The functions of [`data`][crate::data] return a [`LiteralError`].
+//! * [`Ast::to_expr()`][crate::ast::Ast::to_expr] returns a [`ToExprError`].
+//! * [`Notations::register()`][crate::notation::Notations::register] returns a
+//!   [`NotationError`].
+//! * [`Cst::validate_spans()`][crate::cst::Cst::validate_spans] returns a
+//!   [`SpanError`].
+//! * Span conversions return a [`SpanConversionError`].
+//! * The format stability checks of [`testing`][crate::testing] return a
+//!   [`StabilityError`].
 
 use thiserror::Error;
-use crate::source::Span;
+use crate::{
+    ast::expr::ToExprError,
+    cst::spans::SpanError,
+    data::{syntax_error_message, LiteralError},
+    issue::Issue,
+    notation::NotationError,
+    source::{Span, SpanConversionError},
+    testing::StabilityError,
+    tokenize::TokenStreamError,
+    NodeSeqError, UnsafeCharacterEncoding,
+};
 
 /// Main error type for the parser
 #[derive(Error, Debug)]
@@ -23,6 +56,56 @@ pub enum ParseError {
     
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    
+    #[error("Unsafe character encoding: {0}")]
+    UnsafeCharacterEncoding(#[from] UnsafeCharacterEncoding),
+    
+    #[error("{0}")]
+    NodeSeq(#[from] NodeSeqError),
+    
+    #[error("Token stream error: {0}")]
+    TokenStream(#[from] TokenStreamError),
+    
+    #[error("Literal error: {0}")]
+    Literal(#[from] LiteralError),
+
+    #[error("Expression conversion error: {0}")]
+    ToExpr(#[from] ToExprError),
+
+    #[error("Notation error: {0}")]
+    Notation(#[from] NotationError),
+
+    #[error("Span error: {0}")]
+    Span(#[from] SpanError),
+
+    #[error("Span conversion error: {0}")]
+    SpanConversion(#[from] SpanConversionError),
+
+    #[error("Format stability error: {0}")]
+    Stability(#[from] StabilityError),
+    
+    /// Parsing completed, but reported fatal issues.
+    ///
+    /// Returned by [`ParseResult::into_result()`][crate::ParseResult::into_result]
+    /// and [`ParseResult::try_syntax()`][crate::ParseResult::try_syntax].
+    #[error("{}", syntax_error_message(.0))]
+    Issues(Vec<Issue>),
+}
+
+impl ParseError {
+    /// The issues attached to this error, if any.
+    pub fn issues(&self) -> &[Issue] {
+        match self {
+            ParseError::Issues(issues) | ParseError::Literal(LiteralError::Syntax(issues)) => issues,
+            _ => &[],
+        }
+    }
+}
+
+impl From<Vec<Issue>> for ParseError {
+    fn from(issues: Vec<Issue>) -> Self {
+        ParseError::Issues(issues)
+    }
 }
 
 /// Tokenizer-specific errors
@@ -100,166 +183,3 @@ pub enum EncodingError {
     #[error("Character decode error: {details}")]
     DecodeError { details: String },
 }
-
-
-/// Safe error handling macros
-/// 
-/// These macros provide consistent error handling behavior throughout the parser.
-
-/// Safe unwrap that returns an error instead of panicking
-#[macro_export]
-macro_rules! safe_unwrap {
-    ($expr:expr, $err:expr) => {
-        $expr.ok_or($err)?
-    };
-}
-
-/// Safe expect that provides better error messages
-#[macro_export]
-macro_rules! safe_expect {
-    ($expr:expr, $msg:literal) => {
-        match $expr {
-            Some(val) => val,
-            None => {
-                $crate::error_handling::perf_monitor::increment_error();
-                panic!("Assertion failed: {}", $msg)
-            }
-        }
-    };
-}
-
-/// Safe index access with bounds checking
-#[macro_export]
-macro_rules! safe_index {
-    ($slice:expr, $index:expr, $context:literal) => {
-        if $index >= $slice.len() {
-            return Err($crate::error_handling::InternalError::IndexOutOfBounds {
-                index: $index,
-                len: $slice.len(),
-                context: $context.to_string(),
-            }.into());
-        }
-        &$slice[$index]
-    };
-}
-
-/// Safe numeric conversion
-#[macro_export]
-macro_rules! safe_convert {
-    ($value:expr, $target_type:ty, $context:literal) => {
-        match <$target_type>::try_from($value) {
-            Ok(val) => val,
-            Err(e) => {
-                $crate::error_handling::perf_monitor::increment_error();
-                panic!("Numeric overflow during {}: converting {} to {}: {}", 
-                    $context, stringify!($value), stringify!($target_type), e)
-            }
-        }
-    };
-}
-
-/// Context for parser operations
-pub trait ErrorContext<T> {
-    fn with_context(self, msg: &str) -> Result<T, ParseError>;
-    fn with_span(self, span: Span) -> Result<T, ParseError>;
-}
-
-impl<T, E> ErrorContext<T> for Result<T, E>
-where
-    E: Into<ParseError>,
-{
-    fn with_context(self, msg: &str) -> Result<T, ParseError> {
-        self.map_err(|e| {
-            ParseError::Internal(InternalError::InvalidState(
-                format!("{}: {}", msg, Into::<ParseError>::into(e))
-            ))
-        })
-    }
-    
-    fn with_span(self, _span: Span) -> Result<T, ParseError> {
-        // TODO: Enhance error with span information
-        self.map_err(|e| e.into())
-    }
-}
-
-/// Safe utilities for common operations
-pub mod safe_utils {
-    use super::InternalError;
-
-    /// Safe usize to u32 conversion
-    pub fn safe_usize_to_u32(value: usize) -> Result<u32, InternalError> {
-        u32::try_from(value)
-            .map_err(|_| InternalError::NumericOverflow {
-                context: "usize_to_u32".to_string(),
-                details: format!("value {} exceeds u32::MAX", value),
-            })
-    }
-    
-    /// Safe u32 to usize conversion  
-    pub fn safe_u32_to_usize(value: u32) -> Result<usize, InternalError> {
-        usize::try_from(value)
-            .map_err(|_| InternalError::NumericOverflow {
-                context: "u32_to_usize".to_string(),
-                details: format!("value {} cannot fit in usize", value),
-            })
-    }
-}
-
-/// Performance monitoring for error handling
-pub mod perf_monitor {
-    use std::sync::atomic::{AtomicUsize, Ordering};
-    
-    static ERROR_CHECK_COUNT: AtomicUsize = AtomicUsize::new(0);
-    static ERROR_OCCURRED_COUNT: AtomicUsize = AtomicUsize::new(0);
-    
-    pub fn increment_check() {
-        ERROR_CHECK_COUNT.fetch_add(1, Ordering::Relaxed);
-    }
-    
-    pub fn increment_error() {
-        ERROR_OCCURRED_COUNT.fetch_add(1, Ordering::Relaxed);
-    }
-    
-    pub fn get_stats() -> (usize, usize) {
-        (
-            ERROR_CHECK_COUNT.load(Ordering::Relaxed),
-            ERROR_OCCURRED_COUNT.load(Ordering::Relaxed),
-        )
-    }
-    
-    pub fn reset_stats() {
-        ERROR_CHECK_COUNT.store(0, Ordering::Relaxed);
-        ERROR_OCCURRED_COUNT.store(0, Ordering::Relaxed);
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[test]
-    fn test_safe_conversion() {
-        let result = safe_utils::safe_usize_to_u32(42);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 42);
-    }
-    
-    #[test]
-    fn test_safe_conversion_overflow() {
-        let large_value = u64::MAX as usize;
-        let result = safe_utils::safe_usize_to_u32(large_value);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), InternalError::NumericOverflow { .. }));
-    }
-    
-    #[test]
-    fn test_performance_monitoring() {
-        perf_monitor::reset_stats();
-        perf_monitor::increment_check();
-        perf_monitor::increment_error();
-        
-        let (checks, errors) = perf_monitor::get_stats();
-        assert_eq!(checks, 1);
-        assert_eq!(errors, 1);
-    }
-}
\ No newline at end of file
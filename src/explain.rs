@@ -0,0 +1,294 @@
+//! Explain how operator precedence determined the grouping of some input.
+//!
+//! Useful for diagnosing surprising parses, like why `a && b || c ~f~ d`
+//! groups as `((a && b) || (c ~ f ~ d))`.
+//!
+//! ```
+//! use wolfram_parser::{explain, ParseOptions};
+//!
+//! let explanation = explain::explain("a && b || c", &ParseOptions::default());
+//!
+//! assert_eq!(explanation.grouping, "((a && b) || c)");
+//!
+//! // `||` first yields to the enclosing `&&`, then binds at the top level.
+//! let binds: Vec<(&str, bool)> = explanation
+//!     .steps
+//!     .iter()
+//!     .map(|step| (step.text.as_str(), step.binds))
+//!     .collect();
+//!
+//! assert_eq!(binds, [("&&", true), ("||", false), ("||", true)]);
+//! ```
+
+use std::fmt::{self, Display};
+
+use crate::{
+    cst::{
+        CallBody, CallHead, CallNode, Cst, CstSeq, GroupMissingCloserNode,
+        GroupNode,
+    },
+    parse::{self, operators::InfixOperator},
+    parse_cst::ParseCst,
+    precedence::Precedence,
+    source::Span,
+    tokenize::{TokenInput, TokenKind, TokenRef},
+    ParseOptions,
+};
+
+/// How some input was grouped by operator precedence, as computed by
+/// [`explain()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The input with every operator application wrapped in parentheses and
+    /// trivia removed. Top-level expressions are separated by newlines.
+    pub grouping: String,
+    /// Every precedence decision made by the parser, in the order it was made.
+    pub steps: Vec<PrecedenceStep>,
+}
+
+/// A single decision about whether an infix or postfix operator takes the
+/// expression that precedes it as its left operand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrecedenceStep {
+    /// The operator token being considered.
+    pub tok: TokenKind,
+    /// The source text of the operator token.
+    ///
+    /// This is empty for implicit operators, like the implicit `Times` in
+    /// `a b`.
+    pub text: String,
+    pub src: Span,
+    /// Precedence of the operator being considered.
+    pub precedence: OperatorPrecedence,
+    /// Precedence of the innermost enclosing operator, or `None` if there is
+    /// none, like at the top level or directly inside a group.
+    pub context: Option<OperatorPrecedence>,
+    /// Whether the operator took the preceding expression as its left
+    /// operand.
+    ///
+    /// If `false`, the enclosing operator binds tighter, so the preceding
+    /// expression becomes an operand of the enclosing operator instead. The
+    /// same operator is then considered again at an outer level.
+    pub binds: bool,
+}
+
+/// The precedence of an operator.
+///
+/// Operators with a higher [`level`][OperatorPrecedence::level] bind tighter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OperatorPrecedence {
+    pub level: u8,
+    pub associativity: Associativity,
+}
+
+/// How a sequence of operators with the same precedence is grouped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a ~ b ~ c` groups as `(a ~ b) ~ c`, or is flattened.
+    NonRight,
+    /// `a ~ b ~ c` groups as `a ~ (b ~ c)`.
+    Right,
+}
+
+/// Parse `input` and explain how operator precedence determined its grouping.
+pub fn explain(input: &str, opts: &ParseOptions) -> Explanation {
+    let (result, steps) = parse::parse_with_precedence_trace(
+        input.as_bytes(),
+        ParseCst::new_builder(),
+        opts,
+    );
+
+    let grouping: Vec<String> = result
+        .syntax
+        .0
+        .iter()
+        .filter(|node| !is_trivia(node))
+        .map(grouping)
+        .collect();
+
+    Explanation {
+        grouping: grouping.join("\n"),
+        steps,
+    }
+}
+
+//======================================
+// Impls
+//======================================
+
+impl PrecedenceStep {
+    pub(crate) fn new(
+        token: TokenRef,
+        precedence: Precedence,
+        context: Option<Precedence>,
+        binds: bool,
+    ) -> Self {
+        PrecedenceStep {
+            tok: token.tok,
+            text: String::from_utf8_lossy(token.input.as_bytes()).into_owned(),
+            src: token.src,
+            precedence: OperatorPrecedence::from(precedence),
+            context: context.map(OperatorPrecedence::from),
+            binds,
+        }
+    }
+}
+
+impl From<Precedence> for OperatorPrecedence {
+    fn from(prec: Precedence) -> Self {
+        let associativity = if prec.is_right_associative() {
+            Associativity::Right
+        } else {
+            Associativity::NonRight
+        };
+
+        OperatorPrecedence {
+            level: prec.level(),
+            associativity,
+        }
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Explanation { grouping, steps } = self;
+
+        writeln!(f, "{grouping}")?;
+
+        if !steps.is_empty() {
+            writeln!(f)?;
+        }
+
+        for step in steps {
+            writeln!(f, "{step}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for PrecedenceStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let PrecedenceStep {
+            tok,
+            ref text,
+            src,
+            precedence,
+            context,
+            binds,
+        } = *self;
+
+        if text.is_empty() {
+            write!(f, "{src}: implicit {tok:?}")?;
+        } else {
+            write!(f, "{src}: `{text}`")?;
+        }
+
+        write!(f, " (precedence {}) ", precedence.level)?;
+
+        match (binds, context) {
+            (true, None) => write!(f, "binds: no enclosing operator"),
+            (true, Some(context)) if context.level == precedence.level => {
+                write!(
+                    f,
+                    "binds: same precedence as enclosing operator, groups right"
+                )
+            },
+            (true, Some(context)) => write!(
+                f,
+                "binds: enclosing operator has lower precedence {}",
+                context.level
+            ),
+            (false, Some(context)) if context.level == precedence.level => {
+                write!(
+                    f,
+                    "yields: same precedence as enclosing operator, groups left"
+                )
+            },
+            (false, Some(context)) => write!(
+                f,
+                "yields: enclosing operator has higher precedence {}",
+                context.level
+            ),
+            (false, None) => {
+                panic!("operator yielded without an enclosing operator")
+            },
+        }
+    }
+}
+
+//======================================
+// Grouping
+//======================================
+
+fn grouping<I: TokenInput, S>(node: &Cst<I, S>) -> String {
+    match node {
+        Cst::Token(token) => {
+            String::from_utf8_lossy(token.input.as_bytes()).into_owned()
+        },
+        Cst::Call(CallNode { head, body }) => {
+            let head = match head {
+                CallHead::Concrete(head) => join(head, ""),
+                CallHead::Aggregate(head) => grouping(head),
+            };
+
+            let body = match body {
+                CallBody::Group(GroupNode(body))
+                | CallBody::GroupMissingCloser(GroupMissingCloserNode(body)) => {
+                    join(&body.children, "")
+                },
+            };
+
+            format!("{head}{body}")
+        },
+        Cst::Infix(node) if node.0.op == InfixOperator::CodeParser_Comma => {
+            let parts: Vec<String> = node
+                .0
+                .children
+                .0
+                .iter()
+                .filter(|child| !is_trivia(child))
+                .map(|child| match child {
+                    Cst::Token(token) if token.tok == TokenKind::Comma => {
+                        ", ".to_owned()
+                    },
+                    _ => grouping(child),
+                })
+                .collect();
+
+            parts.concat()
+        },
+        Cst::Prefix(node) => format!("({})", join(&node.0.children, "")),
+        Cst::Postfix(node) => format!("({})", join(&node.0.children, "")),
+        Cst::Infix(node) => format!("({})", join(&node.0.children, " ")),
+        Cst::Binary(node) => format!("({})", join(&node.0.children, " ")),
+        Cst::Ternary(node) => format!("({})", join(&node.0.children, " ")),
+        Cst::PrefixBinary(node) => {
+            format!("({})", join(&node.0.children, " "))
+        },
+        Cst::Compound(node) => join(&node.0.children, ""),
+        Cst::Group(node) => join(&node.0.children, ""),
+        Cst::GroupMissingCloser(node) => join(&node.0.children, ""),
+        Cst::GroupMissingOpener(node) => join(&node.0.children, ""),
+        Cst::SyntaxError(node) => join(&node.children, " "),
+        Cst::Box(node) => join(&node.children, ""),
+        Cst::Code(_) => String::new(),
+    }
+}
+
+fn join<I: TokenInput, S>(children: &CstSeq<I, S>, sep: &str) -> String {
+    let parts: Vec<String> = children
+        .0
+        .iter()
+        .filter(|child| !is_trivia(child))
+        .map(grouping)
+        // Skip implicit tokens, like the implicit `Times` in `a b`.
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    parts.join(sep)
+}
+
+fn is_trivia<I, S>(node: &Cst<I, S>) -> bool {
+    matches!(node, Cst::Token(token) if token.tok.isTrivia())
+}
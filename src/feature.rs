@@ -33,7 +33,152 @@
 //!
 //! This makes code controlled by feature flags easier to keep up-to-date as
 //! refactoring occurs.
+//!
+//! The features are reported to users of this library by
+//! [`capabilities()`][crate::capabilities].
 
 pub(crate) const CHECK_ABORT: bool = cfg!(feature = "CHECK_ABORT");
 
 pub(crate) const FAST_STRING_SCAN: bool = cfg!(feature = "FAST_STRING_SCAN");
+
+pub(crate) const USE_EXPR_LIB: bool = cfg!(feature = "USE_EXPR_LIB");
+
+pub(crate) const USE_MATHLINK: bool = cfg!(feature = "USE_MATHLINK");
+
+pub(crate) const STRING_INTERNING: bool = cfg!(feature = "string-interning");
+
+pub(crate) const INCREMENTAL: bool = cfg!(feature = "incremental");
+
+pub(crate) const BENCH: bool = cfg!(feature = "bench");
+
+pub(crate) const SERDE: bool = cfg!(feature = "serde");
+
+pub(crate) const BUMPALO: bool = cfg!(feature = "bumpalo");
+
+/// The compile-time features this library was built with.
+///
+/// Returned by [`capabilities()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Symbol names and other repeated strings are interned
+    /// (`string-interning`).
+    pub string_interning: bool,
+
+    /// Tokens and nodes record their source location.
+    ///
+    /// This is always `true`. Unlike the `COMPUTE_SOURCE` option of the C++
+    /// parser this library is derived from, source tracking can't be turned
+    /// off.
+    pub compute_source: bool,
+
+    /// Long-running operations check whether they have been aborted
+    /// (`CHECK_ABORT`).
+    ///
+    /// Aborts are only requested through LibraryLink, so this has no effect
+    /// unless [`use_mathlink`][Capabilities::use_mathlink] is also enabled.
+    pub check_abort: bool,
+
+    /// Built to be loaded into the Wolfram kernel with LibraryLink
+    /// (`USE_MATHLINK`).
+    pub use_mathlink: bool,
+
+    /// Strings are tokenized with a faster scan of plain characters
+    /// (`FAST_STRING_SCAN`).
+    pub fast_string_scan: bool,
+
+    /// Character decoding can produce `wolfram_expr` expressions
+    /// (`USE_EXPR_LIB`).
+    pub use_expr_lib: bool,
+
+    /// The `query` module for incremental reparsing is available
+    /// (`incremental`).
+    pub incremental: bool,
+
+    /// Syntax trees implement `serde::Serialize` and `serde::Deserialize`
+    /// (`serde`).
+    pub serde: bool,
+
+    /// The `benchmarks` module is available (`bench`).
+    pub bench: bool,
+
+    /// Syntax trees can be allocated in an arena with
+    /// [`parse_cst_in()`][crate::parse_cst_in] and related functions
+    /// (`bumpalo`).
+    pub bumpalo: bool,
+}
+
+/// Get the compile-time features this library was built with.
+///
+/// Host applications can use this to adapt their behavior, or explain why
+/// something is unavailable, instead of relying on differences in behavior:
+///
+/// ```
+/// let caps = wolfram_parser::capabilities();
+///
+/// if !caps.can_abort() {
+///     println!("parsing can't be interrupted in this build");
+/// }
+///
+/// assert!(caps.compute_source);
+/// ```
+pub const fn capabilities() -> Capabilities {
+    Capabilities {
+        string_interning: STRING_INTERNING,
+        compute_source: true,
+        check_abort: CHECK_ABORT,
+        use_mathlink: USE_MATHLINK,
+        fast_string_scan: FAST_STRING_SCAN,
+        use_expr_lib: USE_EXPR_LIB,
+        incremental: INCREMENTAL,
+        serde: SERDE,
+        bench: BENCH,
+        bumpalo: BUMPALO,
+    }
+}
+
+impl Capabilities {
+    /// Whether parsing can be aborted by the Wolfram kernel.
+    ///
+    /// This requires both the `CHECK_ABORT` and `USE_MATHLINK` features.
+    pub const fn can_abort(&self) -> bool {
+        self.check_abort && self.use_mathlink
+    }
+
+    /// The names of the enabled Cargo features, as written in `Cargo.toml`.
+    ///
+    /// ```
+    /// let features = wolfram_parser::capabilities().enabled_features();
+    ///
+    /// println!("wolfram-parser features: {}", features.join(", "));
+    /// ```
+    pub fn enabled_features(&self) -> Vec<&'static str> {
+        let Capabilities {
+            string_interning,
+            compute_source: _,
+            check_abort,
+            use_mathlink,
+            fast_string_scan,
+            use_expr_lib,
+            incremental,
+            serde,
+            bench,
+            bumpalo,
+        } = *self;
+
+        [
+            ("FAST_STRING_SCAN", fast_string_scan),
+            ("USE_EXPR_LIB", use_expr_lib),
+            ("CHECK_ABORT", check_abort),
+            ("USE_MATHLINK", use_mathlink),
+            ("string-interning", string_interning),
+            ("incremental", incremental),
+            ("bench", bench),
+            ("serde", serde),
+            ("bumpalo", bumpalo),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect()
+    }
+}
@@ -0,0 +1,245 @@
+//! Files and contexts read and written by Wolfram Language code.
+//!
+//! [`file_operations()`] finds every call to `Get`, `Put`, `PutAppend`,
+//! `Needs`, `Import`, and `Export` whose target is a literal string, in any
+//! of the forms the target can be written in:
+//!
+//! * a string literal, like `Get["utils.wl"]`
+//! * a `<>` chain of string literals, like `Import["data/" <> "x.csv"]`
+//! * a `File[..]` wrapper, like `Import[File["x.csv"]]`
+//! * a file name after `<<`, `>>`, or `>>>`, which may be written without
+//!   quotes, like `<< Utils.wl` or `x >> out.txt`
+//!
+//! This can be used to build a dependency graph of the files in a project,
+//! or to find code that accesses the file system.
+//!
+//! ```
+//! use wolfram_parser::{
+//!     file_ops::{self, FileOperationKind, OperationSyntax, Target},
+//!     parse_ast_seq, ParseOptions,
+//! };
+//!
+//! let input = r#"
+//! Needs["Utils`"]
+//! << init.wl
+//! data = Import["data/" <> "points.csv"];
+//! data >> cache.m
+//! "#;
+//!
+//! let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+//!
+//! let ops: Vec<_> = file_ops::file_operations(&ast)
+//!     .into_iter()
+//!     .map(|op| (op.kind, op.target, op.syntax))
+//!     .collect();
+//!
+//! assert_eq!(
+//!     ops,
+//!     [
+//!         (
+//!             FileOperationKind::Needs,
+//!             Target::Context("Utils`".to_owned()),
+//!             OperationSyntax::Call,
+//!         ),
+//!         (
+//!             FileOperationKind::Get,
+//!             Target::Path("init.wl".to_owned()),
+//!             OperationSyntax::Operator,
+//!         ),
+//!         (
+//!             FileOperationKind::Import,
+//!             Target::Path("data/points.csv".to_owned()),
+//!             OperationSyntax::Call,
+//!         ),
+//!         (
+//!             FileOperationKind::Put,
+//!             Target::Path("cache.m".to_owned()),
+//!             OperationSyntax::Operator,
+//!         ),
+//!     ]
+//! );
+//! ```
+
+use crate::{ast::Ast, lint::builtin_name, source::Source, NodeSeq};
+
+/// A call that reads or writes a literal file or context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileOperation {
+    pub kind: FileOperationKind,
+    pub target: Target,
+    pub syntax: OperationSyntax,
+
+    /// The source of the whole call.
+    pub src: Source,
+
+    /// The source of the argument naming the target.
+    pub target_src: Source,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FileOperationKind {
+    /// `Get[..]` or `<< ..`
+    Get,
+    /// `Put[..]` or `.. >> ..`
+    Put,
+    /// `PutAppend[..]` or `.. >>> ..`
+    PutAppend,
+    /// `Needs[..]`
+    Needs,
+    /// `Import[..]`
+    Import,
+    /// `Export[..]`
+    Export,
+}
+
+/// The file or context named by a [`FileOperation`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// A file path, as written.
+    Path(String),
+    /// A context name, like ``"Utils`"``, that is resolved to a file by the
+    /// kernel.
+    Context(String),
+}
+
+/// How a [`FileOperation`] was written.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OperationSyntax {
+    /// A call, like `Get["file"]`.
+    Call,
+    /// An operator, like `<< file` or `x >> file`.
+    Operator,
+}
+
+//======================================
+// Impls
+//======================================
+
+impl FileOperationKind {
+    fn from_name(name: &str) -> Option<Self> {
+        let kind = match name {
+            "Get" => FileOperationKind::Get,
+            "Put" => FileOperationKind::Put,
+            "PutAppend" => FileOperationKind::PutAppend,
+            "Needs" => FileOperationKind::Needs,
+            "Import" => FileOperationKind::Import,
+            "Export" => FileOperationKind::Export,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    /// Whether this operation writes to its target.
+    pub fn is_write(self) -> bool {
+        matches!(
+            self,
+            FileOperationKind::Put
+                | FileOperationKind::PutAppend
+                | FileOperationKind::Export
+        )
+    }
+}
+
+impl Target {
+    /// The path or context name.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Target::Path(path) => path,
+            Target::Context(context) => context,
+        }
+    }
+
+    fn new(name: String) -> Self {
+        if name.ends_with('`') {
+            Target::Context(name)
+        } else {
+            Target::Path(name)
+        }
+    }
+}
+
+//======================================
+// Analysis
+//======================================
+
+/// Find the file operations with literal targets in `ast`, in source order.
+///
+/// Every expression is searched, not only top-level statements. Calls whose
+/// target is not a literal, like `Get[file]`, are skipped.
+///
+/// ``Needs["Ctx`", "file"]`` is reported as two operations: one for the
+/// context and one for the file.
+pub fn file_operations(ast: &NodeSeq<Ast>) -> Vec<FileOperation> {
+    let NodeSeq(nodes) = ast;
+
+    let mut ops = Vec::new();
+
+    for node in nodes {
+        collect(node, &mut ops);
+    }
+
+    ops
+}
+
+fn collect(ast: &Ast, ops: &mut Vec<FileOperation>) {
+    if let Ast::Call { head, args, .. } = ast {
+        let kind = head
+            .as_symbol()
+            .and_then(builtin_name)
+            .and_then(FileOperationKind::from_name);
+
+        if let Some(kind) = kind {
+            // Operator forms like `<< file` are abstracted with a head that
+            // has no source.
+            let syntax = if head.source().is_unknown() {
+                OperationSyntax::Operator
+            } else {
+                OperationSyntax::Call
+            };
+
+            for arg in target_args(kind, args) {
+                let Some(name) = literal_target(arg) else {
+                    continue;
+                };
+
+                ops.push(FileOperation {
+                    kind,
+                    target: Target::new(name),
+                    syntax,
+                    src: ast.source().clone(),
+                    target_src: arg.source().clone(),
+                });
+            }
+        }
+    }
+
+    ast.for_each_child(|child| collect(child, ops));
+}
+
+/// The arguments of a call to `kind` that name its targets.
+fn target_args(kind: FileOperationKind, args: &[Ast]) -> &[Ast] {
+    match kind {
+        // Get[file], Import[file, ..], Export[file, expr, ..]
+        FileOperationKind::Get
+        | FileOperationKind::Import
+        | FileOperationKind::Export => &args[..args.len().min(1)],
+        // Put[expr1, expr2, .., file]
+        FileOperationKind::Put | FileOperationKind::PutAppend => {
+            &args[args.len().saturating_sub(1)..]
+        },
+        // Needs[context], Needs[context, file]
+        FileOperationKind::Needs => &args[..args.len().min(2)],
+    }
+}
+
+/// The value of `arg` if it is a literal string, `<>` chain, or
+/// `File[..]` of one.
+pub(crate) fn literal_target(arg: &Ast) -> Option<String> {
+    match arg.as_symbol_call() {
+        Some((head, [file])) if builtin_name(head) == Some("File") => {
+            file.as_joined_string_value()
+        },
+        _ => arg.as_joined_string_value(),
+    }
+}
@@ -344,7 +344,7 @@ impl Display for FmtAsExpr<Span> {
                     FmtAsExpr(&Location::from(end))
                 )
             },
-            SpanKind::CharacterSpan(_) => todo!(),
+            SpanKind::CharIndexSpan(_) => todo!(),
         }
     }
 }
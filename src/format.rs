@@ -0,0 +1,1167 @@
+//! Format Wolfram Language source in a canonical style.
+//!
+//! [`format_cst_seq()`] lays out concrete syntax trees with consistent
+//! spacing and indentation:
+//!
+//! * operators are spaced according to [`FormatOptions::operator_spacing`]
+//! * brackets whose contents don't fit within
+//!   [`FormatOptions::max_line_length`] are broken over several lines, with
+//!   one element per line, indented by [`FormatOptions::indent_width`]
+//! * comments are kept, and runs of blank lines between top-level
+//!   expressions are collapsed to a single blank line
+//!
+//! Some formatting is optional, and off by default:
+//!
+//! * [`FormatOptions::align_rules`] lines up the `->` of the rules in lists
+//!   and associations that are broken over several lines
+//! * [`FormatOptions::align_definitions`] lines up the `=` and `:=` of
+//!   consecutive top-level definitions
+//! * [`FormatOptions::reflow_comments`] rewraps the text of top-level
+//!   comments that are longer than
+//!   [`max_line_length`][FormatOptions::max_line_length]
+//!
+//! ```
+//! use wolfram_parser::{format::{self, FormatOptions}, ParseOptions};
+//!
+//! let input = "f[x_]:=x+1\n\n\n\n(* done *)";
+//!
+//! let formatted = format::format(
+//!     input,
+//!     &ParseOptions::default(),
+//!     &FormatOptions::default(),
+//! );
+//!
+//! assert_eq!(formatted, "f[x_] := x + 1\n\n(* done *)\n");
+//! ```
+//!
+//! Formatting never changes the syntax of the code it formats. The grouping
+//! of operands is given by the tree, so parentheses never have to be added,
+//! and whitespace is kept wherever removing it would change how the code is
+//! tokenized or parsed, like between the operands of `a b`. Lines are only
+//! broken inside brackets, where newlines can't end an expression, so lines
+//! of top-level code without brackets can be longer than
+//! [`max_line_length`][FormatOptions::max_line_length].
+//!
+//! Some code is kept exactly as written:
+//!
+//! * expressions that contain syntax errors
+//! * operators whose operands are read as strings, like `a::b`, `<<file` and
+//!   `a >> file`
+//! * compound tokens like `x_`, `#1` and `%%`
+
+mod comments;
+
+use std::collections::HashMap;
+
+use crate::{
+    cst::{
+        BinaryNode, BoxNode, CallBody, CallHead, CallNode, CompoundNode, Cst,
+        CstSeq, GroupMissingCloserNode, GroupMissingOpenerNode, GroupNode,
+        InfixNode, OperatorNode, PostfixNode, PrefixBinaryNode, PrefixNode,
+        SyntaxErrorNode, TernaryNode,
+    },
+    parse::operators::{
+        BinaryOperator, GroupOperator, InfixOperator, PostfixOperator,
+        PrefixOperator, TernaryOperator,
+    },
+    parse_cst_seq,
+    tokenize::{Token, TokenInput, TokenKind},
+    NodeSeq, ParseOptions,
+};
+
+/// Options that control how code is formatted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    /// Number of spaces the contents of broken brackets are indented by.
+    pub indent_width: usize,
+    /// Column that lines are kept within, where possible.
+    pub max_line_length: usize,
+    /// How operators are spaced.
+    pub operator_spacing: OperatorSpacing,
+    /// Line up the `->` and `:>` of the rules in a list or association that
+    /// is broken over several lines:
+    ///
+    /// ```text
+    /// <|
+    ///     "name"    -> "server",
+    ///     "port"    -> 8080,
+    ///     "timeout" :> $Timeout
+    /// |>
+    /// ```
+    ///
+    /// Rules are only aligned if every element is a rule, and the left-hand
+    /// side of each rule fits on one line.
+    pub align_rules: bool,
+    /// Line up the `=`, `:=`, `^=` and `^:=` of consecutive top-level
+    /// definitions:
+    ///
+    /// ```text
+    /// f[x_]     := x^2
+    /// g[x_, y_] := x + y
+    /// ```
+    ///
+    /// Definitions separated by a blank line or a comment are aligned
+    /// separately.
+    pub align_definitions: bool,
+    /// Rewrap the text of top-level comments that have lines longer than
+    /// [`max_line_length`][FormatOptions::max_line_length].
+    ///
+    /// Only comments that start a line are rewrapped. Directives, package
+    /// metadata like `(* :Title: ... *)`, comments that contain other
+    /// comments, and lines indented more than the rest of the comment, like
+    /// code examples, are kept as written.
+    pub reflow_comments: bool,
+}
+
+/// How operators are spaced by the formatter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OperatorSpacing {
+    /// Put spaces around infix and binary operators, like `a + b` and
+    /// `x -> y`, and after commas and semicolons.
+    ///
+    /// Operators that conventionally bind their operands tightly are not
+    /// spaced: `^`, `?`, `;;`, pattern and optional `:`, and prefix and
+    /// postfix operators other than `&`.
+    Standard,
+    /// Only put spaces where they are needed, like `f[x,y]+1`.
+    Compact,
+    /// Keep the spacing of the input: a single space is put around an
+    /// operator wherever the input had whitespace.
+    Preserve,
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Default for FormatOptions {
+    /// Indent by 4 spaces, keep lines within 80 columns, and use
+    /// [`OperatorSpacing::Standard`], without any of the optional formatting.
+    fn default() -> Self {
+        FormatOptions {
+            indent_width: 4,
+            max_line_length: 80,
+            operator_spacing: OperatorSpacing::Standard,
+            align_rules: false,
+            align_definitions: false,
+            reflow_comments: false,
+        }
+    }
+}
+
+//======================================
+// API
+//======================================
+
+/// Parse and format `input`.
+///
+/// See [`format_cst_seq()`].
+pub fn format(
+    input: &str,
+    parse_opts: &ParseOptions,
+    opts: &FormatOptions,
+) -> String {
+    format_cst_seq(&parse_cst_seq(input, parse_opts).syntax, opts)
+}
+
+/// Format a sequence of top-level concrete syntax trees.
+///
+/// The result ends with a newline, unless it is empty.
+pub fn format_cst_seq<I: TokenInput, S>(
+    nodes: &CstSeq<I, S>,
+    opts: &FormatOptions,
+) -> String {
+    let NodeSeq(nodes) = nodes;
+
+    let mut formatter = Formatter::new(opts);
+
+    let mut statements = Vec::new();
+    let mut newlines = 0;
+    let mut spaced = false;
+
+    for node in nodes {
+        match node {
+            Cst::Token(token) if token.tok == TokenKind::Comment => (),
+            Cst::Token(token) if token.tok.isTrivia() => {
+                if matches!(
+                    token.tok,
+                    TokenKind::ToplevelNewline | TokenKind::InternalNewline
+                ) {
+                    newlines += 1;
+                }
+                spaced = true;
+                continue;
+            },
+            Cst::Token(token) if token.input.as_bytes().is_empty() => continue,
+            _ => (),
+        }
+
+        let separator = match newlines {
+            0 if spaced => " ",
+            0 => "",
+            1 => "\n",
+            _ => "\n\n",
+        };
+
+        statements.push((separator, node));
+
+        newlines = 0;
+        spaced = false;
+    }
+
+    if opts.align_definitions {
+        formatter.align_definitions(&statements);
+    }
+
+    let mut docs = Vec::new();
+
+    for (index, (separator, node)) in statements.into_iter().enumerate() {
+        if index > 0 {
+            docs.push(Doc::Text(separator.to_owned()));
+        }
+
+        let starts_line = index == 0 || separator.contains('\n');
+
+        let reflowed = match node {
+            Cst::Token(token)
+                if token.tok == TokenKind::Comment
+                    && opts.reflow_comments
+                    && starts_line =>
+            {
+                comments::reflow(&input(token), opts.max_line_length)
+            },
+            _ => None,
+        };
+
+        docs.push(match reflowed {
+            Some(comment) => Doc::Text(comment),
+            None => formatter.toplevel(node),
+        });
+    }
+
+    let mut out = layout(&Doc::Concat(docs), opts);
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Format a single concrete syntax tree.
+///
+/// Unlike [`format_cst_seq()`], the result does not end with a newline.
+pub fn format_cst<I: TokenInput, S>(
+    node: &Cst<I, S>,
+    opts: &FormatOptions,
+) -> String {
+    let doc = Formatter::new(opts).toplevel(node);
+
+    layout(&doc, opts)
+}
+
+//======================================
+// Formatter
+//======================================
+
+struct Formatter<'o> {
+    opts: &'o FormatOptions,
+    /// Used to check if tokens can be joined without changing how they are
+    /// tokenized.
+    parse_opts: ParseOptions,
+    /// Number of brackets enclosing the node being formatted.
+    depth: usize,
+    /// Padding put after the left-hand sides of aligned rules and
+    /// definitions, keyed by the address of the rule or definition.
+    aligned: HashMap<*const (), Padding>,
+}
+
+/// Padding put after the left-hand side of a rule or definition, so that the
+/// operators of a run of them line up.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Padding {
+    /// Pad the left-hand side to the given width.
+    Always(usize),
+    /// Pad the left-hand side to the given width, if the enclosing brackets
+    /// are broken over several lines.
+    IfBroken(usize),
+}
+
+/// Whitespace put between two parts of a node.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Gap {
+    None,
+    Space,
+    /// A line break, or the given text if the enclosing group fits on one
+    /// line.
+    Line(&'static str),
+}
+
+/// A non-trivia child of a node, with the trivia that preceded it.
+struct Part<'n, I, S> {
+    node: &'n Cst<I, S>,
+    gap: Gap,
+    /// Whether the input had trivia between this part and the previous one.
+    spaced: bool,
+    /// Whether this part is the right operand of an implicit `Times`.
+    implicit_times: bool,
+    comments: Vec<&'n Token<I, S>>,
+}
+
+impl<'o> Formatter<'o> {
+    fn new(opts: &'o FormatOptions) -> Self {
+        Formatter {
+            opts,
+            parse_opts: ParseOptions::builtin_default(),
+            depth: 0,
+            aligned: HashMap::new(),
+        }
+    }
+
+    fn toplevel<I: TokenInput, S>(&mut self, node: &Cst<I, S>) -> Doc {
+        if is_verbatim(node) {
+            return verbatim(node);
+        }
+
+        self.node(node)
+    }
+
+    fn node<I: TokenInput, S>(&mut self, node: &Cst<I, S>) -> Doc {
+        match node {
+            Cst::Token(token) => text(token),
+            Cst::Call(CallNode { head, body }) => {
+                let mut docs = match head {
+                    CallHead::Concrete(NodeSeq(head)) => {
+                        self.operator(head, |_| Gap::Space)
+                    },
+                    CallHead::Aggregate(head) => vec![self.node(head)],
+                };
+
+                docs.push(self.group(&body.as_op().children.0));
+
+                Doc::Concat(docs)
+            },
+            Cst::Prefix(PrefixNode(OperatorNode { op, children })) => {
+                match op {
+                    PrefixOperator::Get
+                    | PrefixOperator::Information
+                    | PrefixOperator::CodeParser_PrefixLinearSyntaxBang => {
+                        verbatim(node)
+                    },
+                    _ => Doc::Concat(self.operator(&children.0, |_| Gap::None)),
+                }
+            },
+            Cst::Postfix(PostfixNode(OperatorNode { op, children })) => {
+                let gap = match op {
+                    PostfixOperator::Function => Gap::Space,
+                    _ => Gap::None,
+                };
+
+                Doc::Concat(self.operator(&children.0, |_| gap))
+            },
+            Cst::Binary(BinaryNode(OperatorNode { op, children })) => {
+                let gap = match op {
+                    BinaryOperator::Put | BinaryOperator::PutAppend => {
+                        return verbatim(node)
+                    },
+                    BinaryOperator::Pattern
+                    | BinaryOperator::Optional
+                    | BinaryOperator::Power
+                    | BinaryOperator::PatternTest
+                    | BinaryOperator::Span => Gap::None,
+                    _ => Gap::Space,
+                };
+
+                let mut docs = self.operator(&children.0, |_| gap);
+
+                if let Some(padding) = self.aligned.get(&key(node)) {
+                    self.pad(&mut docs, *padding);
+                }
+
+                Doc::Concat(docs)
+            },
+            Cst::Ternary(TernaryNode(OperatorNode { op, children })) => {
+                let gap = match op {
+                    TernaryOperator::Span
+                    | TernaryOperator::CodeParser_TernaryOptionalPattern => {
+                        Gap::None
+                    },
+                    _ => Gap::Space,
+                };
+
+                Doc::Concat(self.operator(&children.0, |_| gap))
+            },
+            Cst::Infix(InfixNode(OperatorNode { op, children })) => {
+                self.infix(node, *op, &children.0)
+            },
+            Cst::PrefixBinary(PrefixBinaryNode(OperatorNode {
+                op: _,
+                children,
+            })) => Doc::Concat(self.operator(&children.0, |_| Gap::Space)),
+            Cst::Group(GroupNode(OperatorNode { op, children })) => {
+                if self.opts.align_rules
+                    && matches!(
+                        op,
+                        GroupOperator::List | GroupOperator::Association
+                    )
+                {
+                    self.align_rules(&children.0);
+                }
+
+                self.group(&children.0)
+            },
+            Cst::SyntaxError(_)
+            | Cst::Compound(_)
+            | Cst::GroupMissingCloser(_)
+            | Cst::GroupMissingOpener(_)
+            | Cst::Box(_)
+            | Cst::Code(_) => verbatim(node),
+        }
+    }
+
+    fn infix<I: TokenInput, S>(
+        &mut self,
+        node: &Cst<I, S>,
+        op: InfixOperator,
+        children: &[Cst<I, S>],
+    ) -> Doc {
+        // Operators at odd indices, operands at even indices.
+        let gaps = match op {
+            InfixOperator::MessageName => return verbatim(node),
+            InfixOperator::CodeParser_Comma => {
+                // Commas only appear directly inside brackets, so these lines
+                // are broken together with the enclosing brackets.
+                return Doc::Concat(self.operator(children, |index| {
+                    if index % 2 == 1 {
+                        Gap::None
+                    } else {
+                        Gap::Line(" ")
+                    }
+                }));
+            },
+            InfixOperator::CompoundExpression => (Gap::None, Gap::Space),
+            _ => (Gap::Space, Gap::Space),
+        };
+
+        // Line breaks are only safe inside brackets.
+        let nested = self.depth > 0;
+
+        let (before, after) = match gaps {
+            (before, Gap::Space) if nested => (before, Gap::Line(" ")),
+            gaps => gaps,
+        };
+
+        let mut docs = self.operator(children, |index| {
+            if index % 2 == 1 {
+                before
+            } else {
+                after
+            }
+        });
+
+        if !nested || docs.is_empty() {
+            return Doc::Concat(docs);
+        }
+
+        // Break after the operators, indenting every operand but the first.
+        let rest = docs.split_off(1);
+
+        Doc::Group(vec![Doc::Concat(docs), Doc::Nest(rest)])
+    }
+
+    /// Format a node made up of `[opener, contents..., closer]`.
+    fn group<I: TokenInput, S>(&mut self, children: &[Cst<I, S>]) -> Doc {
+        let (opener, contents, closer) = match children {
+            // `a[[b]]` is parsed as a call whose only argument is `[b]`. Keep
+            // the brackets of each side together.
+            [Cst::Token(open), Cst::Group(GroupNode(OperatorNode {
+                op: GroupOperator::CodeParser_GroupSquare,
+                children: NodeSeq(inner),
+            })), Cst::Token(close)]
+                if open.tok == TokenKind::OpenSquare
+                    && close.tok == TokenKind::CloseSquare
+                    && inner.len() >= 2 =>
+            {
+                ("[[".to_owned(), &inner[1..inner.len() - 1], "]]".to_owned())
+            },
+            [Cst::Token(open), contents @ .., Cst::Token(close)] => {
+                (input(open), contents, input(close))
+            },
+            _ => return Doc::Concat(self.operator(children, |_| Gap::None)),
+        };
+
+        self.depth += 1;
+        let contents = self.operator(contents, |_| Gap::Space);
+        self.depth -= 1;
+
+        if contents.is_empty() {
+            return Doc::Text(opener + &closer);
+        }
+
+        Doc::Group(vec![
+            Doc::Text(opener),
+            Doc::Nest(vec![Doc::Line(""), Doc::Concat(contents)]),
+            Doc::Line(""),
+            Doc::Text(closer),
+        ])
+    }
+
+    /// Format the children of a node, putting `gap(index)` between the
+    /// non-trivia children at `index - 1` and `index`.
+    fn operator<'n, I: TokenInput, S>(
+        &mut self,
+        children: &'n [Cst<I, S>],
+        gap: impl Fn(usize) -> Gap,
+    ) -> Vec<Doc> {
+        let (mut parts, mut trailing) = parts(children);
+
+        for (index, part) in parts.iter_mut().enumerate() {
+            part.gap = gap(index);
+        }
+
+        let parts = drop_empty(parts, &mut trailing);
+
+        let mut docs = Vec::new();
+        let mut prev: Option<&Cst<I, S>> = None;
+
+        for part in parts {
+            match prev {
+                Some(prev) => self.gap(&mut docs, &part, prev),
+                None => {
+                    for comment in &part.comments {
+                        docs.push(text(comment));
+                        docs.push(space());
+                    }
+                },
+            }
+
+            docs.push(self.node(part.node));
+
+            prev = Some(part.node);
+        }
+
+        for comment in trailing {
+            if !docs.is_empty() {
+                docs.push(space());
+            }
+
+            docs.push(text(comment));
+        }
+
+        docs
+    }
+
+    fn gap<I: TokenInput, S>(
+        &self,
+        docs: &mut Vec<Doc>,
+        part: &Part<'_, I, S>,
+        prev: &Cst<I, S>,
+    ) {
+        let mut gap = match self.opts.operator_spacing {
+            OperatorSpacing::Preserve if part.implicit_times => {
+                if part.spaced {
+                    Gap::Space
+                } else {
+                    Gap::None
+                }
+            },
+            _ if part.implicit_times => Gap::Space,
+            OperatorSpacing::Standard => part.gap,
+            OperatorSpacing::Compact => match part.gap {
+                Gap::Line(_) => Gap::Line(""),
+                _ => Gap::None,
+            },
+            OperatorSpacing::Preserve => match (part.gap, part.spaced) {
+                (Gap::Line(_), true) => Gap::Line(" "),
+                (Gap::Line(_), false) => Gap::Line(""),
+                (_, true) => Gap::Space,
+                (_, false) => Gap::None,
+            },
+        };
+
+        // Comments stay with the part before them. A comment can be followed
+        // by any token, so no further checks are needed.
+        if !part.comments.is_empty() {
+            for comment in &part.comments {
+                docs.push(space());
+                docs.push(text(comment));
+            }
+        } else if part.spaced && !self.can_join(prev, part.node) {
+            gap = match gap {
+                Gap::None => Gap::Space,
+                Gap::Line(_) => Gap::Line(" "),
+                Gap::Space => Gap::Space,
+            };
+        }
+
+        match gap {
+            Gap::None => (),
+            Gap::Space => docs.push(space()),
+            Gap::Line(flat) => docs.push(Doc::Line(flat)),
+        }
+    }
+
+    /// Record the padding of each run of two or more definitions in
+    /// `statements` that are on consecutive lines.
+    fn align_definitions<I: TokenInput, S>(
+        &mut self,
+        statements: &[(&str, &Cst<I, S>)],
+    ) {
+        let mut run = Vec::new();
+
+        for (separator, node) in statements {
+            if *separator != "\n" {
+                self.align(std::mem::take(&mut run), Padding::Always);
+            }
+
+            let definition = match definition(node) {
+                Some(definition) if !is_verbatim(node) => definition,
+                _ => {
+                    self.align(std::mem::take(&mut run), Padding::Always);
+                    continue;
+                },
+            };
+
+            match self.lhs_width(definition) {
+                Some(width) => run.push((definition, width)),
+                None => self.align(std::mem::take(&mut run), Padding::Always),
+            }
+        }
+
+        self.align(run, Padding::Always);
+    }
+
+    /// Record the padding of the rules in the list or association made up of
+    /// `children`, if every element is a rule.
+    fn align_rules<I: TokenInput, S>(&mut self, children: &[Cst<I, S>]) {
+        let Some(rules) = rules(children) else {
+            return;
+        };
+
+        // The rules are formatted inside the brackets.
+        self.depth += 1;
+        let widths: Option<Vec<usize>> =
+            rules.iter().map(|rule| self.lhs_width(rule)).collect();
+        self.depth -= 1;
+
+        if let Some(widths) = widths {
+            self.align(
+                rules.into_iter().zip(widths).collect(),
+                Padding::IfBroken,
+            );
+        }
+    }
+
+    fn align<I, S>(
+        &mut self,
+        run: Vec<(&Cst<I, S>, usize)>,
+        padding: fn(usize) -> Padding,
+    ) {
+        if run.len() < 2 {
+            return;
+        }
+
+        let target = run.iter().map(|(_, width)| *width).max().unwrap_or(0);
+
+        for (node, _) in run {
+            self.aligned.insert(key(node), padding(target));
+        }
+    }
+
+    /// Width of the formatted left-hand side of the rule or definition
+    /// `node`, or `None` if it doesn't fit on one line.
+    fn lhs_width<I: TokenInput, S>(
+        &mut self,
+        node: &Cst<I, S>,
+    ) -> Option<usize> {
+        let lhs = children(node).into_iter().next()?;
+
+        let lhs = layout(&self.node(lhs), self.opts);
+
+        (!lhs.contains('\n')).then(|| width(&lhs))
+    }
+
+    /// Pad the left-hand side of an aligned rule or definition, the first of
+    /// its formatted `docs`.
+    fn pad(&self, docs: &mut Vec<Doc>, padding: Padding) {
+        let Some(lhs) = docs.first() else {
+            return;
+        };
+
+        // Lay out the left-hand side as it was measured.
+        let lhs = layout(lhs, self.opts);
+
+        let (target, if_broken) = match padding {
+            Padding::Always(target) => (target, false),
+            Padding::IfBroken(target) => (target, true),
+        };
+
+        let mut spaces = " ".repeat(target.saturating_sub(width(&lhs)));
+
+        // Aligned operators are always spaced, so that the padding doesn't
+        // change the spacing kept by `OperatorSpacing::Preserve`.
+        if !matches!(docs.get(1), Some(Doc::Text(text)) if text == " ") {
+            spaces.push(' ');
+        }
+
+        docs[0] = Doc::Text(lhs);
+
+        docs.insert(
+            1,
+            if if_broken {
+                Doc::IfBroken(spaces)
+            } else {
+                Doc::Text(spaces)
+            },
+        );
+    }
+
+    /// Returns true if `left` and `right`, which were separated by whitespace
+    /// in the input, can be written next to each other without changing the
+    /// syntax.
+    fn can_join<I: TokenInput, S>(
+        &self,
+        left: &Cst<I, S>,
+        right: &Cst<I, S>,
+    ) -> bool {
+        let (Some(left), Some(right)) = (last_token(left), first_token(right))
+        else {
+            return true;
+        };
+
+        // `a _`, `# 1`, and `% 2` are products, but `a_`, `#1`, and `%2` are
+        // single expressions.
+        if forms_compound(left.tok, right.tok) {
+            return false;
+        }
+
+        let (left, right) = (left.input.as_bytes(), right.input.as_bytes());
+
+        let joined = [left, right].concat();
+
+        match crate::tokenize_bytes(&joined, &self.parse_opts) {
+            Ok(NodeSeq(tokens)) => match tokens.as_slice() {
+                [a, b] => {
+                    a.input.as_bytes() == left && b.input.as_bytes() == right
+                },
+                _ => false,
+            },
+            Err(_) => false,
+        }
+    }
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// Split `children` into its non-trivia children and the trivia before them.
+///
+/// Also returns the comments after the last non-trivia child.
+#[allow(clippy::type_complexity)]
+fn parts<'n, I: TokenInput, S>(
+    children: &'n [Cst<I, S>],
+) -> (Vec<Part<'n, I, S>>, Vec<&'n Token<I, S>>) {
+    let mut parts = Vec::new();
+    let mut spaced = false;
+    let mut comments = Vec::new();
+
+    for child in children {
+        match child {
+            Cst::Token(token) if token.tok == TokenKind::Comment => {
+                spaced = true;
+                comments.push(token);
+            },
+            Cst::Token(token) if token.tok.isTrivia() => spaced = true,
+            _ => parts.push(Part {
+                node: child,
+                gap: Gap::None,
+                spaced: std::mem::take(&mut spaced),
+                implicit_times: false,
+                comments: std::mem::take(&mut comments),
+            }),
+        }
+    }
+
+    (parts, comments)
+}
+
+/// Remove parts that have no input, like the implicit `Null` in `a;`,
+/// moving the trivia before them to the next part.
+fn drop_empty<'n, I: TokenInput, S>(
+    parts: Vec<Part<'n, I, S>>,
+    trailing: &mut Vec<&'n Token<I, S>>,
+) -> Vec<Part<'n, I, S>> {
+    let mut kept = Vec::with_capacity(parts.len());
+
+    let mut spaced = false;
+    let mut implicit_times = false;
+    let mut comments = Vec::new();
+
+    for mut part in parts {
+        spaced |= part.spaced;
+        comments.append(&mut part.comments);
+
+        match part.node {
+            Cst::Token(token) if token.input.as_bytes().is_empty() => {
+                implicit_times |= token.tok == TokenKind::Fake_ImplicitTimes;
+            },
+            _ => {
+                part.spaced = std::mem::take(&mut spaced);
+                part.implicit_times = std::mem::take(&mut implicit_times);
+                part.comments = std::mem::take(&mut comments);
+
+                kept.push(part);
+            },
+        }
+    }
+
+    comments.append(trailing);
+    *trailing = comments;
+
+    kept
+}
+
+/// If `node` is a definition like `f[x_] := x^2`, or a definition ended by a
+/// semicolon, get the definition.
+fn definition<I: TokenInput, S>(node: &Cst<I, S>) -> Option<&Cst<I, S>> {
+    let is_definition = |node: &Cst<I, S>| {
+        matches!(
+            node,
+            Cst::Binary(BinaryNode(OperatorNode {
+                op: BinaryOperator::Set
+                    | BinaryOperator::SetDelayed
+                    | BinaryOperator::UpSet
+                    | BinaryOperator::UpSetDelayed,
+                ..
+            }))
+        )
+    };
+
+    match node {
+        _ if is_definition(node) => Some(node),
+        Cst::Infix(InfixNode(OperatorNode {
+            op: InfixOperator::CompoundExpression,
+            children: NodeSeq(children),
+        })) => {
+            let (first, rest) = children.split_first()?;
+
+            let ends_with_semi = rest.iter().all(|child| match child {
+                Cst::Token(token) => {
+                    token.tok == TokenKind::Semi
+                        || token.tok.isTrivia()
+                        || token.input.as_bytes().is_empty()
+                },
+                _ => false,
+            });
+
+            (ends_with_semi && is_definition(first)).then_some(first)
+        },
+        _ => None,
+    }
+}
+
+/// If the list or association made up of `children` contains two or more
+/// elements, all of which are rules, get the rules.
+fn rules<I: TokenInput, S>(children: &[Cst<I, S>]) -> Option<Vec<&Cst<I, S>>> {
+    let [_, contents @ .., _] = children else {
+        return None;
+    };
+
+    let contents: Vec<_> =
+        contents.iter().filter(|node| !is_trivia(node)).collect();
+
+    let [Cst::Infix(InfixNode(OperatorNode {
+        op: InfixOperator::CodeParser_Comma,
+        children: NodeSeq(elements),
+    }))] = contents.as_slice()
+    else {
+        return None;
+    };
+
+    // Elements at even indices, commas at odd indices.
+    let rules: Vec<_> = elements
+        .iter()
+        .filter(|node| !is_trivia(node))
+        .step_by(2)
+        .collect();
+
+    let all_rules = rules.iter().all(|node| {
+        matches!(
+            node,
+            Cst::Binary(BinaryNode(OperatorNode {
+                op: BinaryOperator::Rule | BinaryOperator::RuleDelayed,
+                ..
+            }))
+        )
+    });
+
+    (rules.len() >= 2 && all_rules).then_some(rules)
+}
+
+fn is_trivia<I, S>(node: &Cst<I, S>) -> bool {
+    matches!(node, Cst::Token(token) if token.tok.isTrivia())
+}
+
+/// Identifies `node` in [`Formatter::aligned`].
+fn key<I, S>(node: &Cst<I, S>) -> *const () {
+    node as *const Cst<I, S> as *const ()
+}
+
+/// Returns true if `node` should be written exactly as it was parsed.
+fn is_verbatim<I, S>(node: &Cst<I, S>) -> bool {
+    match node {
+        Cst::Token(token) => token.tok.isError() || token.tok.isUnterminated(),
+        Cst::SyntaxError(_)
+        | Cst::GroupMissingCloser(_)
+        | Cst::GroupMissingOpener(_)
+        | Cst::Box(_)
+        | Cst::Code(_)
+        | Cst::Call(CallNode {
+            head: _,
+            body: CallBody::GroupMissingCloser(_),
+        }) => true,
+        _ => children(node).into_iter().any(is_verbatim),
+    }
+}
+
+fn forms_compound(left: TokenKind, right: TokenKind) -> bool {
+    let is_operand = matches!(
+        right,
+        TokenKind::Symbol | TokenKind::Integer | TokenKind::String
+    );
+
+    match left {
+        TokenKind::Under
+        | TokenKind::UnderUnder
+        | TokenKind::UnderUnderUnder
+        | TokenKind::Hash
+        | TokenKind::HashHash
+        | TokenKind::Percent => is_operand,
+        TokenKind::Symbol => matches!(
+            right,
+            TokenKind::Under
+                | TokenKind::UnderUnder
+                | TokenKind::UnderUnderUnder
+                | TokenKind::UnderDot
+        ),
+        _ => false,
+    }
+}
+
+fn first_token<I: TokenInput, S>(node: &Cst<I, S>) -> Option<&Token<I, S>> {
+    match node {
+        Cst::Token(token) => {
+            (!token.input.as_bytes().is_empty()).then_some(token)
+        },
+        _ => children(node).into_iter().find_map(first_token),
+    }
+}
+
+fn last_token<I: TokenInput, S>(node: &Cst<I, S>) -> Option<&Token<I, S>> {
+    match node {
+        Cst::Token(token) => {
+            (!token.input.as_bytes().is_empty()).then_some(token)
+        },
+        _ => children(node).into_iter().rev().find_map(last_token),
+    }
+}
+
+fn children<I, S>(node: &Cst<I, S>) -> Vec<&Cst<I, S>> {
+    match node {
+        Cst::Token(_) | Cst::Code(_) => Vec::new(),
+        Cst::Call(CallNode { head, body }) => {
+            let mut children: Vec<_> = match head {
+                CallHead::Concrete(NodeSeq(head)) => head.iter().collect(),
+                CallHead::Aggregate(head) => vec![&**head],
+            };
+
+            children.extend(&body.as_op().children.0);
+
+            children
+        },
+        Cst::SyntaxError(SyntaxErrorNode { err: _, children })
+        | Cst::Box(BoxNode { children, .. })
+        | Cst::Prefix(PrefixNode(OperatorNode { children, .. }))
+        | Cst::Infix(InfixNode(OperatorNode { children, .. }))
+        | Cst::Postfix(PostfixNode(OperatorNode { children, .. }))
+        | Cst::Binary(BinaryNode(OperatorNode { children, .. }))
+        | Cst::Ternary(TernaryNode(OperatorNode { children, .. }))
+        | Cst::PrefixBinary(PrefixBinaryNode(OperatorNode {
+            children, ..
+        }))
+        | Cst::Compound(CompoundNode(OperatorNode { children, .. }))
+        | Cst::Group(GroupNode(OperatorNode { children, .. }))
+        | Cst::GroupMissingCloser(GroupMissingCloserNode(OperatorNode {
+            children,
+            ..
+        }))
+        | Cst::GroupMissingOpener(GroupMissingOpenerNode(OperatorNode {
+            children,
+            ..
+        })) => children.0.iter().collect(),
+    }
+}
+
+fn input<I: TokenInput, S>(token: &Token<I, S>) -> String {
+    String::from_utf8_lossy(token.input.as_bytes()).into_owned()
+}
+
+fn text<I: TokenInput, S>(token: &Token<I, S>) -> Doc {
+    Doc::Text(input(token))
+}
+
+fn verbatim<I: TokenInput, S>(node: &Cst<I, S>) -> Doc {
+    Doc::Text(node.to_source())
+}
+
+fn space() -> Doc {
+    Doc::Text(" ".to_owned())
+}
+
+//======================================
+// Layout
+//======================================
+
+/// The layout of some formatted code, before line breaks are chosen.
+///
+/// This follows Wadler's "A prettier printer": every [`Doc::Group`] is put on
+/// a single line if it fits, otherwise each [`Doc::Line`] directly in it is
+/// broken.
+#[derive(Debug)]
+enum Doc {
+    Text(String),
+    /// A line break, or the given text if the enclosing group is not broken.
+    Line(&'static str),
+    /// The given text if the enclosing group is broken, otherwise nothing.
+    IfBroken(String),
+    /// Indent the line breaks in these documents by one more level.
+    Nest(Vec<Doc>),
+    Group(Vec<Doc>),
+    Concat(Vec<Doc>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+fn layout(doc: &Doc, opts: &FormatOptions) -> String {
+    let mut out = String::new();
+    let mut column = 0;
+
+    let mut stack: Vec<(usize, Mode, &Doc)> = vec![(0, Mode::Break, doc)];
+
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Text(text) => {
+                out.push_str(text);
+
+                column = match text.rfind('\n') {
+                    Some(index) => width(&text[index + 1..]),
+                    None => column + width(text),
+                };
+            },
+            Doc::Line(flat) => match mode {
+                Mode::Flat => {
+                    out.push_str(flat);
+                    column += width(flat);
+                },
+                Mode::Break => {
+                    let len = out.trim_end_matches(' ').len();
+                    out.truncate(len);
+
+                    out.push('\n');
+                    out.extend(std::iter::repeat(' ').take(indent));
+                    column = indent;
+                },
+            },
+            Doc::IfBroken(text) => {
+                if mode == Mode::Break {
+                    out.push_str(text);
+                    column += width(text);
+                }
+            },
+            Doc::Nest(docs) => {
+                let indent = indent + opts.indent_width;
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            },
+            Doc::Group(docs) => {
+                let remaining = opts.max_line_length as isize - column as isize;
+
+                let mode = match mode {
+                    Mode::Break if !fits(docs, &stack, remaining) => {
+                        Mode::Break
+                    },
+                    _ => Mode::Flat,
+                };
+
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            },
+            Doc::Concat(docs) => {
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            },
+        }
+    }
+
+    out
+}
+
+/// Returns true if `docs` fit in `remaining` columns when laid out on one
+/// line, together with the rest of the line after them.
+fn fits(
+    docs: &[Doc],
+    rest: &[(usize, Mode, &Doc)],
+    mut remaining: isize,
+) -> bool {
+    let mut stack: Vec<(Mode, &Doc)> =
+        docs.iter().rev().map(|doc| (Mode::Flat, doc)).collect();
+
+    let mut rest = rest.iter().rev();
+    let mut in_rest = false;
+
+    loop {
+        let (mode, doc) = match stack.pop() {
+            Some(next) => next,
+            None => match rest.next() {
+                Some((_, mode, doc)) => {
+                    in_rest = true;
+                    (*mode, *doc)
+                },
+                None => return true,
+            },
+        };
+
+        match doc {
+            Doc::Text(text) => match text.find('\n') {
+                // Text that spans several lines can't be put on one line.
+                Some(_) if !in_rest => return false,
+                Some(index) => {
+                    return (width(&text[..index]) as isize) <= remaining
+                },
+                None => remaining -= width(text) as isize,
+            },
+            Doc::Line(flat) => match mode {
+                Mode::Flat => remaining -= width(flat) as isize,
+                Mode::Break => return remaining >= 0,
+            },
+            Doc::IfBroken(text) => {
+                if mode == Mode::Break {
+                    remaining -= width(text) as isize;
+                }
+            },
+            Doc::Nest(docs) | Doc::Group(docs) | Doc::Concat(docs) => {
+                stack.extend(docs.iter().rev().map(|doc| (mode, doc)));
+            },
+        }
+
+        if remaining < 0 {
+            return false;
+        }
+    }
+}
+
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
@@ -0,0 +1,248 @@
+//! Rewrapping of comments, for [`FormatOptions::reflow_comments`].
+//!
+//! [`FormatOptions::reflow_comments`]: super::FormatOptions::reflow_comments
+
+use crate::directive;
+
+use super::width;
+
+/// A line of the text of a comment.
+enum Line<'c> {
+    Blank,
+    /// A line of a paragraph, without its indentation.
+    Text(&'c str),
+    /// A line indented more than the paragraphs of the comment, like a line
+    /// of a code example, with its indentation.
+    Verbatim(&'c str),
+}
+
+/// Rewrap the paragraphs of `comment`, the input of a comment token that
+/// starts at column 0, that have lines longer than `max_line_length` columns.
+///
+/// Returns `None` if the comment should be kept as written.
+pub(super) fn reflow(comment: &str, max_line_length: usize) -> Option<String> {
+    let body = comment.strip_prefix("(*")?.strip_suffix("*)")?;
+
+    if body.contains("(*")
+        || body.contains("*)")
+        || body.contains('\t')
+        || body.trim_start().starts_with(':')
+        || directive::is_directive(comment)
+    {
+        return None;
+    }
+
+    let text = body.trim();
+
+    if text.is_empty() {
+        return None;
+    }
+
+    // Whitespace between the opener and the text, and between the text and
+    // the closer.
+    let head = &body[..body.len() - body.trim_start().len()];
+    let tail = &body[body.trim_end().len()..];
+
+    // Column of the first line of text.
+    let first_column = match head.rfind('\n') {
+        Some(index) => width(&head[index + 1..]),
+        None => 2 + width(head),
+    };
+
+    // Text that starts on the same line as the opener is part of the first
+    // paragraph, whatever the indentation of the lines after it.
+    let same_line = !head.contains('\n');
+
+    // The first line of text has no indentation, since `text` is trimmed.
+    let raw: Vec<(usize, &str)> = text
+        .split('\n')
+        .map(str::trim_end)
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => (first_column, line),
+            _ => (width(line) - width(line.trim_start()), line),
+        })
+        .collect();
+
+    let paragraph_indent = raw
+        .iter()
+        .skip(usize::from(same_line))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(indent, _)| *indent)
+        .min()
+        .unwrap_or(first_column);
+
+    let lines: Vec<Line> = raw
+        .iter()
+        .enumerate()
+        .map(|(index, (indent, line))| {
+            if line.is_empty() {
+                Line::Blank
+            } else if (index == 0 && same_line) || *indent == paragraph_indent {
+                Line::Text(line.trim_start())
+            } else {
+                Line::Verbatim(line)
+            }
+        })
+        .collect();
+
+    let mut out = format!("(*{head}");
+
+    // Columns taken by the closer, if it is on the last line of text.
+    let closer = if tail.contains('\n') {
+        0
+    } else {
+        width(tail) + 2
+    };
+
+    let mut index = 0;
+    let mut reflowed = false;
+
+    while index < lines.len() {
+        if index > 0 {
+            out.push('\n');
+        }
+
+        let paragraph: Vec<&str> = match lines[index] {
+            Line::Blank => {
+                index += 1;
+                continue;
+            },
+            Line::Verbatim(line) => {
+                out.push_str(line);
+                index += 1;
+                continue;
+            },
+            Line::Text(_) => {
+                let start = index;
+                let mut paragraph = Vec::new();
+
+                while let Some(Line::Text(line)) = lines.get(index) {
+                    if index > start && is_list_item(line) {
+                        break;
+                    }
+
+                    paragraph.push(*line);
+                    index += 1;
+                }
+
+                paragraph
+            },
+        };
+
+        let column = if out.ends_with('\n') {
+            paragraph_indent
+        } else {
+            first_column
+        };
+
+        let is_last = index == lines.len();
+
+        let fits = paragraph.iter().enumerate().all(|(line_index, line)| {
+            let start = if line_index == 0 {
+                column
+            } else {
+                paragraph_indent
+            };
+
+            let end = if is_last && line_index == paragraph.len() - 1 {
+                closer
+            } else {
+                0
+            };
+
+            start + width(line) + end <= max_line_length
+        });
+
+        let lines = if fits {
+            paragraph.into_iter().map(str::to_owned).collect()
+        } else {
+            reflowed = true;
+
+            let words: Vec<&str> = paragraph
+                .iter()
+                .flat_map(|line| line.split_whitespace())
+                .collect();
+
+            wrap(
+                &words,
+                max_line_length.saturating_sub(column),
+                max_line_length.saturating_sub(paragraph_indent),
+                if is_last { closer } else { 0 },
+            )
+        };
+
+        for (line_index, line) in lines.iter().enumerate() {
+            if line_index > 0 {
+                out.push('\n');
+            }
+
+            if line_index > 0 || out.ends_with('\n') {
+                out.extend(std::iter::repeat(' ').take(paragraph_indent));
+            }
+
+            out.push_str(line);
+        }
+    }
+
+    if !reflowed {
+        return None;
+    }
+
+    out.push_str(tail);
+    out.push_str("*)");
+
+    Some(out)
+}
+
+/// Put `words` on as few lines as possible, with at most `first` columns on
+/// the first line and `rest` columns on the others, leaving `end` columns
+/// free after the last line.
+///
+/// Words longer than a line are put on a line of their own.
+fn wrap(words: &[&str], first: usize, rest: usize, end: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+
+    for word in words {
+        let max = if lines.len() == 1 { first } else { rest };
+
+        let line = lines.last_mut().expect("lines is never empty");
+
+        if line.is_empty() {
+            line.push_str(word);
+        } else if width(line) + 1 + width(word) <= max {
+            line.push(' ');
+            line.push_str(word);
+        } else {
+            lines.push((*word).to_owned());
+        }
+    }
+
+    let max = if lines.len() == 1 { first } else { rest };
+
+    // Move the last word down if there is no room for the text after it.
+    if let Some(last) = lines.last_mut() {
+        if width(last) + end > max {
+            if let Some((line, word)) = last.rsplit_once(' ') {
+                let word = word.to_owned();
+                last.truncate(line.len());
+                lines.push(word);
+            }
+        }
+    }
+
+    lines
+}
+
+/// Returns true if `line` starts an item of a list, like `- item` or
+/// `1. item`, which starts a new paragraph.
+fn is_list_item(line: &str) -> bool {
+    let marker = line.split_whitespace().next().unwrap_or("");
+
+    matches!(marker, "-" | "*" | "+" | "\u{2022}")
+        || (marker.len() > 1
+            && marker.ends_with(['.', ')'])
+            && marker[..marker.len() - 1]
+                .bytes()
+                .all(|byte| byte.is_ascii_digit()))
+}
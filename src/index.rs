@@ -0,0 +1,890 @@
+//! Project-wide symbol index.
+//!
+//! A [`SymbolIndex`] maps each fully qualified symbol name to the sites in a
+//! [`ProjectParse`] where that symbol is defined and used, along with the
+//! package context that exports it (if any).
+//!
+//! ```
+//! use wolfram_parser::{index::SymbolIndex, project::ProjectParse, ParseOptions};
+//!
+//! let project = ProjectParse::from_sources(
+//!     vec![
+//!         (
+//!             "MyPackage.wl".into(),
+//!             r#"
+//! BeginPackage["MyPackage`"]
+//! square::usage = "square[x] squares x"
+//! Begin["`Private`"]
+//! square[x_] := x^2
+//! End[]
+//! EndPackage[]
+//! "#.to_owned(),
+//!         ),
+//!         (
+//!             "Main.wl".into(),
+//!             "Needs[\"MyPackage`\"]\nsquare[3]".to_owned(),
+//!         ),
+//!     ],
+//!     &ParseOptions::default(),
+//! );
+//!
+//! let index = SymbolIndex::build(&project);
+//!
+//! let entry = index.get("MyPackage`square").unwrap();
+//!
+//! assert_eq!(entry.exporting_context.as_deref(), Some("MyPackage`"));
+//! assert_eq!(entry.definitions.len(), 2);
+//! assert_eq!(entry.uses.len(), 1);
+//! ```
+//!
+//! # Incremental updates
+//!
+//! Collecting the symbol occurrences in a file is done once per file, and the
+//! results are cached in the index. When a single file is reparsed, use
+//! [`SymbolIndex::update_file()`] to recollect the occurrences in only that
+//! file. Unqualified symbol names are resolved against the exports of every
+//! file in the project, so name resolution is redone for all files after an
+//! update.
+//!
+//! # Limitations
+//!
+//! Contexts are tracked by recognizing `BeginPackage`, `Begin`, `End`,
+//! `EndPackage`, `Needs`, and `Get` calls that appear as top-level
+//! statements. Symbols from `System` and other contexts not defined in the
+//! project are resolved to the current context at the point they are used.
+//!
+//! # Persistence
+//!
+//! A [`SymbolIndex`] can be written to disk with [`SymbolIndex::save()`] and
+//! reloaded with [`SymbolIndex::load()`]. The per-file occurrence tables are
+//! stored along with a [`content_hash()`][crate::project::content_hash] of
+//! each file, so that after reloading only files whose contents changed need
+//! to be reparsed:
+//!
+//! ```no_run
+//! # fn sources() -> Vec<(std::path::PathBuf, String)> { vec![] }
+//! use std::fs::File;
+//! use wolfram_parser::{index::SymbolIndex, project::ProjectParse, ParseOptions};
+//!
+//! let mut index = SymbolIndex::load(File::open("project.wlix")?)?;
+//! let mut project = ProjectParse::new();
+//!
+//! for (path, source) in sources() {
+//!     if index.is_stale(&path, &source) {
+//!         let file = project.reparse_file(path, &source, &ParseOptions::default());
+//!         index.update_file(file);
+//!     }
+//! }
+//!
+//! index.save(File::create("project.wlix")?)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+mod persist;
+
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    ast::Ast,
+    project::{self, ProjectFile, ProjectParse},
+    source::{LineColumn, Location, Source, Span},
+    utils,
+};
+
+/// Index of symbol definitions and uses across a [`ProjectParse`].
+#[derive(Debug)]
+pub struct SymbolIndex {
+    files: HashMap<PathBuf, FileSymbols>,
+
+    /// Merged view of `files`, keyed by fully qualified symbol name.
+    symbols: HashMap<String, SymbolEntry>,
+
+    /// The occurrences in each file and the fully qualified name each
+    /// resolves to, ordered by start location.
+    locations: HashMap<PathBuf, Vec<(Occurrence, String)>>,
+}
+
+/// The definition and use sites of a single symbol.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolEntry {
+    pub definitions: Vec<Occurrence>,
+    pub uses: Vec<Occurrence>,
+
+    /// The package context that exports this symbol, if it was declared
+    /// between `BeginPackage[..]` and `Begin[..]` in some file.
+    pub exporting_context: Option<String>,
+}
+
+/// A single occurrence of a symbol in a project file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub path: PathBuf,
+    pub span: Span,
+    pub kind: OccurrenceKind,
+}
+
+/// A replacement of the name of a single symbol occurrence, as returned by
+/// [`SymbolIndex::rename()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenameEdit {
+    pub path: PathBuf,
+    /// The span of the symbol name, not including any context it was written
+    /// with.
+    pub span: Span,
+    pub new_text: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OccurrenceKind {
+    /// The symbol is being assigned a value or definition, e.g. the `f` in
+    /// `f[x_] := ..` or in `f::usage = ..`.
+    Definition,
+    /// Any other reference to the symbol.
+    Use,
+}
+
+/// The symbol occurrences collected from a single file, before unqualified
+/// names are resolved against the rest of the project.
+#[derive(Debug)]
+struct FileSymbols {
+    occurrences: Vec<RawOccurrence>,
+
+    /// [`content_hash()`][crate::project::content_hash] of the file source.
+    content_hash: u64,
+}
+
+#[derive(Debug)]
+struct RawOccurrence {
+    /// The symbol name without any context.
+    name: String,
+    span: Span,
+    kind: OccurrenceKind,
+    resolution: Resolution,
+    /// Whether this occurrence is in the public section of a package (between
+    /// `BeginPackage[..]` and `Begin[..]`) and names a symbol in the package
+    /// context, making it a candidate export of that package.
+    public: bool,
+}
+
+#[derive(Debug)]
+enum Resolution {
+    /// The symbol was written with an explicit context.
+    Qualified(String),
+    /// The symbol was written without a context, and resolves to the first
+    /// context in `context_path` other than `context` that exports it, or to
+    /// `context` otherwise.
+    Unqualified {
+        context: String,
+        context_path: Arc<[String]>,
+    },
+}
+
+//======================================
+// Impls
+//======================================
+
+impl SymbolIndex {
+    /// Build an index of every file in `project`.
+    ///
+    /// Files are processed in parallel.
+    pub fn build(project: &ProjectParse) -> Self {
+        let files: Vec<&ProjectFile> = project.files().collect();
+
+        let tables = utils::par_map(&files, |file| FileSymbols::collect(file));
+
+        let files = files
+            .iter()
+            .map(|file| file.path.clone())
+            .zip(tables)
+            .collect();
+
+        SymbolIndex::from_files(files)
+    }
+
+    fn from_files(files: HashMap<PathBuf, FileSymbols>) -> Self {
+        let mut index = SymbolIndex {
+            files,
+            symbols: HashMap::new(),
+            locations: HashMap::new(),
+        };
+
+        index.merge();
+
+        index
+    }
+
+    /// Update the index after `file` has been reparsed.
+    ///
+    /// Only the occurrences in `file` are recollected.
+    pub fn update_file(&mut self, file: &ProjectFile) {
+        self.files
+            .insert(file.path.clone(), FileSymbols::collect(file));
+
+        self.merge();
+    }
+
+    /// Remove the occurrences in the file at `path` from the index.
+    pub fn remove_file(&mut self, path: &Path) {
+        if self.files.remove(path).is_some() {
+            self.merge();
+        }
+    }
+
+    /// Check whether the occurrences indexed for the file at `path` may be out
+    /// of date with `source`, or the file has not been indexed.
+    pub fn is_stale(&self, path: &Path, source: &str) -> bool {
+        match self.files.get(path) {
+            Some(file) => file.content_hash != project::content_hash(source),
+            None => true,
+        }
+    }
+
+    /// Iterate over the paths of the indexed files.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// Get the entry for a fully qualified symbol name, e.g.
+    /// ``"MyPackage`square"``.
+    pub fn get(&self, qualified_name: &str) -> Option<&SymbolEntry> {
+        self.symbols.get(qualified_name)
+    }
+
+    /// Iterate over every indexed symbol and its entry.
+    pub fn symbols(&self) -> impl Iterator<Item = (&str, &SymbolEntry)> {
+        self.symbols
+            .iter()
+            .map(|(name, entry)| (name.as_str(), entry))
+    }
+
+    /// Find the fully qualified name of the symbol occurring at `span` in the
+    /// file at `path`.
+    pub fn qualified_name_at(&self, path: &Path, span: Span) -> Option<&str> {
+        self.locations
+            .get(path)?
+            .iter()
+            .find(|(occ, _)| occ.span == span)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Find the symbol occurrence whose span contains `location` in the file
+    /// at `path`.
+    pub fn occurrence_at(
+        &self,
+        path: &Path,
+        location: Location,
+    ) -> Option<&Occurrence> {
+        self.locations
+            .get(path)?
+            .iter()
+            .find(|(occ, _)| occ.span.contains(location))
+            .map(|(occ, _)| occ)
+    }
+
+    /// Get the definition sites of the symbol at `occurrence`.
+    ///
+    /// Unqualified symbols are resolved through the `BeginPackage[..]`,
+    /// `Begin[..]`, `Needs[..]`, and `Get[..]` calls in effect at the
+    /// occurrence, so a use of an exported package symbol in another file
+    /// resolves to the definitions in the package.
+    ///
+    /// If `occurrence` is itself a definition, every definition of the symbol
+    /// is returned, including `occurrence`.
+    ///
+    /// ```
+    /// use std::path::{Path, PathBuf};
+    /// use wolfram_parser::{
+    ///     index::SymbolIndex, macros::src, project::ProjectParse, ParseOptions,
+    /// };
+    ///
+    /// let project = ProjectParse::from_sources(
+    ///     vec![
+    ///         (
+    ///             "Pkg.wl".into(),
+    ///             [
+    ///                 "BeginPackage[\"Pkg`\"]",
+    ///                 "f",
+    ///                 "Begin[\"`Private`\"]",
+    ///                 "f[] := 1",
+    ///                 "End[]",
+    ///                 "EndPackage[]",
+    ///             ]
+    ///             .join("\n"),
+    ///         ),
+    ///         ("Main.wl".into(), "Needs[\"Pkg`\"]\nf[]".to_owned()),
+    ///     ],
+    ///     &ParseOptions::default(),
+    /// );
+    ///
+    /// let index = SymbolIndex::build(&project);
+    ///
+    /// let occ = index
+    ///     .occurrence_at(Path::new("Main.wl"), src!(2:1).into())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     index.definition_of(occ),
+    ///     vec![(PathBuf::from("Pkg.wl"), src!(4:1-4:2).into())]
+    /// );
+    /// ```
+    pub fn definition_of(
+        &self,
+        occurrence: &Occurrence,
+    ) -> Vec<(PathBuf, Span)> {
+        let Some(name) =
+            self.qualified_name_at(&occurrence.path, occurrence.span)
+        else {
+            return Vec::new();
+        };
+
+        let Some(entry) = self.symbols.get(name) else {
+            return Vec::new();
+        };
+
+        entry
+            .definitions
+            .iter()
+            .map(|def| (def.path.clone(), def.span))
+            .collect()
+    }
+
+    /// Compute the edits that rename the symbol with fully qualified name
+    /// `qualified_name` to `new_name` in every indexed file.
+    ///
+    /// Every occurrence that resolves to the symbol is renamed, whether it
+    /// was written without a context, with its full context like
+    /// ``Pkg`f``, or with a context relative to the current context like
+    /// `` `Private`g ``. Only the name is replaced, so each occurrence keeps
+    /// the context it was written with.
+    ///
+    /// Edits are sorted by path, and then by location.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_name` includes a context.
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     index::SymbolIndex, macros::src, project::ProjectParse, source::Span,
+    ///     ParseOptions,
+    /// };
+    ///
+    /// let project = ProjectParse::from_sources(
+    ///     vec![(
+    ///         "Main.wl".into(),
+    ///         ["BeginPackage[\"Pkg`\"]", "f", "EndPackage[]", "Pkg`f[1] + f[2]"]
+    ///             .join("\n"),
+    ///     )],
+    ///     &ParseOptions::default(),
+    /// );
+    ///
+    /// let index = SymbolIndex::build(&project);
+    ///
+    /// let spans: Vec<_> = index
+    ///     .rename("Pkg`f", "g")
+    ///     .into_iter()
+    ///     .map(|edit| edit.span)
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     spans,
+    ///     [
+    ///         Span::from(src!(2:1-2:2)),
+    ///         Span::from(src!(4:5-4:6)),
+    ///         Span::from(src!(4:12-4:13)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn rename(
+        &self,
+        qualified_name: &str,
+        new_name: &str,
+    ) -> Vec<RenameEdit> {
+        assert!(
+            !new_name.contains('`'),
+            "SymbolIndex::rename: new name must not include a context: {new_name}"
+        );
+
+        // The name of the symbol, as it is written in each occurrence.
+        let name = match qualified_name.rsplit_once('`') {
+            Some((_, name)) => name,
+            None => qualified_name,
+        };
+        let len = name.chars().count() as u32;
+
+        let mut paths: Vec<&PathBuf> = self.locations.keys().collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .flat_map(|path| {
+                self.locations[path]
+                    .iter()
+                    .filter(|(_, qualified)| qualified == qualified_name)
+                    .map(move |(occ, _)| RenameEdit {
+                        path: path.clone(),
+                        span: name_span(occ.span, len),
+                        new_text: new_name.to_owned(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Rebuild `symbols` from the cached per-file tables.
+    fn merge(&mut self) {
+        let occurrences: Vec<(&PathBuf, &RawOccurrence)> = self
+            .files
+            .iter()
+            .flat_map(|(path, file)| {
+                file.occurrences.iter().map(move |occ| (path, occ))
+            })
+            .collect();
+
+        // A symbol mentioned in the public section of a package is exported
+        // by that package, unless it resolves to a symbol exported by one of
+        // the packages it needs.
+        let candidates: HashSet<(&str, &str)> = occurrences
+            .iter()
+            .filter(|(_, occ)| occ.public)
+            .map(|(_, occ)| (occ.resolution.context(), occ.name.as_str()))
+            .collect();
+
+        let resolved: Vec<&str> = occurrences
+            .iter()
+            .map(|(_, occ)| occ.resolve(&candidates))
+            .collect();
+
+        let exports: HashSet<(&str, &str)> = occurrences
+            .iter()
+            .zip(&resolved)
+            .filter(|((_, occ), context)| {
+                occ.public && **context == occ.resolution.context()
+            })
+            .map(|((_, occ), context)| (*context, occ.name.as_str()))
+            .collect();
+
+        let mut symbols: HashMap<String, SymbolEntry> = HashMap::new();
+        let mut locations: HashMap<PathBuf, Vec<(Occurrence, String)>> =
+            HashMap::new();
+
+        for ((path, occ), context) in occurrences.iter().zip(&resolved) {
+            let qualified_name = format!("{context}{}", occ.name);
+
+            let entry = symbols.entry(qualified_name.clone()).or_default();
+
+            if exports.contains(&(*context, occ.name.as_str())) {
+                entry.exporting_context = Some(context.to_string());
+            }
+
+            let occurrence = Occurrence {
+                path: PathBuf::clone(path),
+                span: occ.span,
+                kind: occ.kind,
+            };
+
+            locations
+                .entry(PathBuf::clone(path))
+                .or_default()
+                .push((occurrence.clone(), qualified_name));
+
+            match occ.kind {
+                OccurrenceKind::Definition => {
+                    entry.definitions.push(occurrence)
+                },
+                OccurrenceKind::Use => entry.uses.push(occurrence),
+            }
+        }
+
+        // Keep occurrence order deterministic regardless of the order files
+        // were stored in.
+        for entry in symbols.values_mut() {
+            entry.definitions.sort_by(|a, b| compare_occurrences(a, b));
+            entry.uses.sort_by(|a, b| compare_occurrences(a, b));
+        }
+        for occurrences in locations.values_mut() {
+            occurrences.sort_by(|(a, _), (b, _)| compare_occurrences(a, b));
+        }
+
+        self.symbols = symbols;
+        self.locations = locations;
+    }
+}
+
+impl RawOccurrence {
+    /// Get the context this occurrence resolves to, given the candidate
+    /// exports of every package in the project.
+    fn resolve<'s>(&'s self, candidates: &HashSet<(&str, &str)>) -> &'s str {
+        match &self.resolution {
+            Resolution::Qualified(context) => context.as_str(),
+            Resolution::Unqualified {
+                context,
+                context_path,
+            } => context_path
+                .iter()
+                .map(String::as_str)
+                .filter(|ctx| *ctx != context.as_str())
+                .find(|ctx| candidates.contains(&(*ctx, self.name.as_str())))
+                .unwrap_or(context.as_str()),
+        }
+    }
+}
+
+/// Get the span of the last `len` characters of the symbol at `span`, which
+/// is the name of the symbol without any context.
+fn name_span(span: Span, len: u32) -> Span {
+    let start = match span.end() {
+        Location::LineColumn(LineColumn(line, column)) => {
+            let column = NonZeroU32::new(column.get() - len).unwrap();
+
+            Location::LineColumn(LineColumn(line, column))
+        },
+        Location::CharacterIndex(index) => {
+            Location::CharacterIndex(index - len)
+        },
+    };
+
+    Span::new(start, span.end())
+}
+
+fn compare_occurrences(a: &Occurrence, b: &Occurrence) -> std::cmp::Ordering {
+    a.path.cmp(&b.path).then_with(|| {
+        a.span
+            .start()
+            .partial_cmp(&b.span.start())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })
+}
+
+//======================================
+// Per-file collection
+//======================================
+
+const GLOBAL_CONTEXT: &str = "Global`";
+const SYSTEM_CONTEXT: &str = "System`";
+
+/// State saved by `BeginPackage[..]` and `Begin[..]`, and restored by the
+/// matching `EndPackage[]` and `End[]`.
+struct SavedContext {
+    context: String,
+    context_path: Arc<[String]>,
+    /// The package context begun by `BeginPackage[..]`, or `None` for
+    /// `Begin[..]`.
+    package: Option<String>,
+}
+
+struct Collector {
+    occurrences: Vec<RawOccurrence>,
+
+    context: String,
+    context_path: Arc<[String]>,
+    saved: Vec<SavedContext>,
+    /// The innermost package context begun by `BeginPackage[..]`.
+    package: Option<String>,
+}
+
+impl FileSymbols {
+    fn collect(file: &ProjectFile) -> Self {
+        let mut collector = Collector {
+            occurrences: Vec::new(),
+            context: GLOBAL_CONTEXT.to_owned(),
+            context_path: Arc::from(vec![
+                GLOBAL_CONTEXT.to_owned(),
+                SYSTEM_CONTEXT.to_owned(),
+            ]),
+            saved: Vec::new(),
+            package: None,
+        };
+
+        for expr in file.exprs() {
+            collector.statement(expr);
+        }
+
+        FileSymbols {
+            occurrences: collector.occurrences,
+            content_hash: file.content_hash,
+        }
+    }
+}
+
+impl Collector {
+    /// Process a top-level statement, updating the current context if it is
+    /// a context manipulation function call.
+    fn statement(&mut self, ast: &Ast) {
+        if let Some(("CompoundExpression", args)) = ast.as_symbol_call() {
+            for arg in args {
+                self.statement(arg);
+            }
+            return;
+        }
+
+        self.visit(ast);
+
+        let Some((head, args)) = ast.as_symbol_call() else {
+            return;
+        };
+
+        let first_string = args.first().and_then(Ast::as_string_value);
+
+        match (head, first_string) {
+            ("BeginPackage", Some(package)) => {
+                let mut context_path = vec![package.clone()];
+                context_path
+                    .extend(args.get(1).map(string_list).unwrap_or_default());
+                context_path.push(SYSTEM_CONTEXT.to_owned());
+
+                self.saved.push(SavedContext {
+                    context: std::mem::replace(
+                        &mut self.context,
+                        package.clone(),
+                    ),
+                    context_path: std::mem::replace(
+                        &mut self.context_path,
+                        Arc::from(context_path),
+                    ),
+                    package: Some(package.clone()),
+                });
+                self.package = Some(package);
+            },
+            ("Begin", Some(context)) => {
+                let context = self.absolute_context(&context);
+
+                self.saved.push(SavedContext {
+                    context: std::mem::replace(&mut self.context, context),
+                    context_path: self.context_path.clone(),
+                    package: None,
+                });
+            },
+            ("End", None) if args.is_empty() => {
+                if let Some(saved) = self.saved.pop() {
+                    self.context = saved.context;
+                }
+            },
+            ("EndPackage", None) if args.is_empty() => {
+                if let Some(saved) = self.saved.pop() {
+                    self.context = saved.context;
+
+                    let mut context_path = saved.context_path.to_vec();
+                    if let Some(package) = saved.package {
+                        context_path.insert(0, package);
+                    }
+                    self.context_path = Arc::from(context_path);
+                }
+
+                self.package = self
+                    .saved
+                    .iter()
+                    .rev()
+                    .find_map(|saved| saved.package.clone());
+            },
+            ("Needs" | "Get", Some(context)) if context.ends_with('`') => {
+                let mut context_path = self.context_path.to_vec();
+                context_path.insert(0, context);
+                self.context_path = Arc::from(context_path);
+            },
+            _ => (),
+        }
+    }
+
+    fn visit(&mut self, ast: &Ast) {
+        if let Some((head, args)) = ast.as_symbol_call() {
+            match (head, args) {
+                (
+                    "Set" | "SetDelayed" | "UpSet" | "UpSetDelayed",
+                    [lhs, rhs],
+                ) => {
+                    self.visit_head(ast);
+                    if head.starts_with("Up") {
+                        self.up_lhs(lhs);
+                    } else {
+                        self.lhs(lhs);
+                    }
+                    self.visit(rhs);
+                    return;
+                },
+                ("TagSet" | "TagSetDelayed", [tag, lhs, rhs]) => {
+                    self.visit_head(ast);
+                    self.symbol(tag, OccurrenceKind::Definition);
+                    self.visit(lhs);
+                    self.visit(rhs);
+                    return;
+                },
+                // The name of a pattern is local to the enclosing rule or
+                // definition.
+                ("Pattern", [_name, pattern]) => {
+                    self.visit_head(ast);
+                    self.visit(pattern);
+                    return;
+                },
+                _ => (),
+            }
+        }
+
+        if ast.as_symbol().is_some() {
+            self.symbol(ast, OccurrenceKind::Use);
+            return;
+        }
+
+        ast.for_each_child(|child| self.visit(child));
+    }
+
+    fn visit_head(&mut self, call: &Ast) {
+        if let Ast::Call { head, .. } = call {
+            self.visit(head);
+        }
+    }
+
+    /// Visit the left-hand side of a `Set` or `SetDelayed`.
+    fn lhs(&mut self, lhs: &Ast) {
+        // x = ..
+        if lhs.as_symbol().is_some() {
+            self.symbol(lhs, OccurrenceKind::Definition);
+            return;
+        }
+
+        let Some((head, args)) = lhs.as_symbol_call() else {
+            // f[a][b] := ..
+            if let Ast::Call { head, args, .. } = lhs {
+                self.lhs(head);
+                args.iter().for_each(|arg| self.visit(arg));
+            } else {
+                self.visit(lhs);
+            }
+            return;
+        };
+
+        match (head, args) {
+            // {a, b} = ..
+            ("List", _) => {
+                self.visit_head(lhs);
+                args.iter().for_each(|arg| self.lhs(arg));
+            },
+            // HoldPattern[f[x_]] := .., f[x_] /; cond := ..
+            ("HoldPattern", [inner]) | ("Condition", [inner, _]) => {
+                self.visit_head(lhs);
+                self.lhs(inner);
+                args[1..].iter().for_each(|arg| self.visit(arg));
+            },
+            // f::usage = .., Options[f] = .., Attributes[f] = ..
+            (
+                "MessageName" | "Options" | "Attributes" | "Format" | "Default"
+                | "DownValues" | "OwnValues" | "UpValues" | "SubValues",
+                [symbol, rest @ ..],
+            ) if symbol.as_symbol().is_some() => {
+                self.visit_head(lhs);
+                self.symbol(symbol, OccurrenceKind::Definition);
+                rest.iter().for_each(|arg| self.visit(arg));
+            },
+            _ => {
+                self.visit_head_as_lhs(lhs);
+                args.iter().for_each(|arg| self.visit(arg));
+            },
+        }
+    }
+
+    /// Visit the left-hand side of an `UpSet` or `UpSetDelayed`, which
+    /// defines values for the heads of the arguments.
+    fn up_lhs(&mut self, lhs: &Ast) {
+        let Ast::Call { head, args, .. } = lhs else {
+            self.visit(lhs);
+            return;
+        };
+
+        self.visit(head);
+
+        for arg in args {
+            match arg {
+                _ if arg.as_symbol().is_some() => {
+                    self.symbol(arg, OccurrenceKind::Definition)
+                },
+                Ast::Call { head, args, .. } if head.as_symbol().is_some() => {
+                    self.symbol(head, OccurrenceKind::Definition);
+                    args.iter().for_each(|arg| self.visit(arg));
+                },
+                _ => self.visit(arg),
+            }
+        }
+    }
+
+    fn visit_head_as_lhs(&mut self, call: &Ast) {
+        if let Ast::Call { head, .. } = call {
+            self.symbol(head, OccurrenceKind::Definition);
+        }
+    }
+
+    /// Record an occurrence of the symbol leaf `ast`.
+    ///
+    /// Symbols without a source span (e.g. heads synthesized during
+    /// abstraction) are not recorded.
+    fn symbol(&mut self, ast: &Ast, kind: OccurrenceKind) {
+        let (Some(written), Source::Span(span)) =
+            (ast.as_symbol(), ast.source())
+        else {
+            return;
+        };
+
+        let (name, resolution) = match written.rfind('`') {
+            Some(index) => {
+                let (context, name) = written.split_at(index + 1);
+
+                let context = self.absolute_context(context);
+
+                (name, Resolution::Qualified(context))
+            },
+            None => (
+                written,
+                Resolution::Unqualified {
+                    context: self.context.clone(),
+                    context_path: self.context_path.clone(),
+                },
+            ),
+        };
+
+        let public = match &self.package {
+            Some(package) => {
+                self.context == *package && resolution.context() == package
+            },
+            None => false,
+        };
+
+        self.occurrences.push(RawOccurrence {
+            name: name.to_owned(),
+            span: *span,
+            kind,
+            resolution,
+            public,
+        });
+    }
+
+    /// Resolve a context relative to the current context, e.g. ``"`Private`"``.
+    fn absolute_context(&self, context: &str) -> String {
+        match context.strip_prefix('`') {
+            Some(relative) => format!("{}{relative}", self.context),
+            None => context.to_owned(),
+        }
+    }
+}
+
+impl Resolution {
+    /// The context this symbol is in if it is not found on the context path.
+    fn context(&self) -> &str {
+        match self {
+            Resolution::Qualified(context) => context,
+            Resolution::Unqualified { context, .. } => context,
+        }
+    }
+}
+
+/// Get the string elements of a list, or of a single string.
+fn string_list(ast: &Ast) -> Vec<String> {
+    match ast.as_symbol_call() {
+        Some(("List", elements)) => {
+            elements.iter().filter_map(Ast::as_string_value).collect()
+        },
+        _ => ast.as_string_value().into_iter().collect(),
+    }
+}
@@ -0,0 +1,377 @@
+//! Compact binary on-disk format for [`SymbolIndex`].
+//!
+//! # Format
+//!
+//! All integers are unsigned LEB128 varints unless noted otherwise.
+//!
+//! ```text
+//! index       := MAGIC version:u32-le strings files
+//! strings     := count (len utf8-bytes)*
+//! files       := count file*
+//! file        := path:str hash:u64-le count occurrence*
+//! occurrence  := name:str span kind:u8 public:u8 resolution
+//! span        := location location
+//! location    := 0 line column | 1 index
+//! resolution  := 0 context:str | 1 context:str count context:str*
+//! str         := index into `strings`
+//! ```
+//!
+//! Symbol names and contexts are heavily repeated across a project, so every
+//! string is stored once in the leading string table and referred to by
+//! index.
+//!
+//! Only the symbol index is persisted. Parse results are not: tooling that
+//! loads a saved index reparses a file when it is opened or when
+//! [`SymbolIndex::is_stale()`] reports that it changed, using
+//! [`ProjectParse::reparse_file()`][crate::project::ProjectParse::reparse_file].
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    io::{self, Read, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use crate::{
+    source::{Location, Span},
+    utils,
+};
+
+use super::{
+    FileSymbols, OccurrenceKind, RawOccurrence, Resolution, SymbolIndex,
+};
+
+const MAGIC: &[u8; 4] = b"WLIX";
+const VERSION: u32 = 1;
+
+impl SymbolIndex {
+    /// Write this index to `writer` in a compact binary format.
+    ///
+    /// See [`SymbolIndex::load()`].
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut strings = StringTable::default();
+
+        // Sort files by path so the same index always produces the same
+        // bytes.
+        let mut files: Vec<(&PathBuf, &FileSymbols)> =
+            self.files.iter().collect();
+        files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        // Encode the file tables first to collect the strings they use.
+        let mut body = Vec::new();
+
+        utils::write_varint(&mut body, files.len() as u64)?;
+
+        for (path, file) in files {
+            let path = path.to_str().ok_or_else(|| {
+                invalid_data(format!("path is not valid UTF-8: {path:?}"))
+            })?;
+
+            utils::write_varint(&mut body, strings.intern(path))?;
+            body.write_all(&file.content_hash.to_le_bytes())?;
+
+            utils::write_varint(&mut body, file.occurrences.len() as u64)?;
+
+            for occ in &file.occurrences {
+                write_occurrence(&mut body, &mut strings, occ)?;
+            }
+        }
+
+        let mut writer = io::BufWriter::new(writer);
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+
+        utils::write_varint(&mut writer, strings.strings.len() as u64)?;
+        for string in &strings.strings {
+            utils::write_varint(&mut writer, string.len() as u64)?;
+            writer.write_all(string.as_bytes())?;
+        }
+
+        writer.write_all(&body)?;
+
+        writer.flush()
+    }
+
+    /// Read an index previously written by [`SymbolIndex::save()`].
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if `reader`
+    /// does not contain a valid index, or if it was written by an
+    /// incompatible version of this crate.
+    pub fn load<R: Read>(reader: R) -> io::Result<Self> {
+        let mut reader = io::BufReader::new(reader);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+
+        if magic != *MAGIC {
+            return Err(invalid_data("not a symbol index file".to_owned()));
+        }
+
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported symbol index version: {version}"
+            )));
+        }
+
+        let count = read_len(&mut reader)?;
+        let mut strings = Vec::with_capacity(count.min(1 << 16));
+
+        for _ in 0..count {
+            let len = read_len(&mut reader)?;
+
+            // Don't trust `len` to size the buffer: a corrupt length must not
+            // allocate more than the input actually contains.
+            let mut bytes = Vec::new();
+            (&mut reader).take(len as u64).read_to_end(&mut bytes)?;
+
+            if bytes.len() != len {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "string extends past the end of the index",
+                ));
+            }
+
+            let string = String::from_utf8(bytes)
+                .map_err(|err| invalid_data(err.to_string()))?;
+
+            strings.push(string);
+        }
+
+        let mut decoder = Decoder {
+            reader,
+            strings,
+            context_paths: HashMap::new(),
+        };
+
+        let count = decoder.len()?;
+        let mut files = HashMap::with_capacity(count.min(1 << 16));
+
+        for _ in 0..count {
+            let path = PathBuf::from(decoder.string()?);
+
+            let mut hash = [0; 8];
+            decoder.reader.read_exact(&mut hash)?;
+            let content_hash = u64::from_le_bytes(hash);
+
+            let count = decoder.len()?;
+            let mut occurrences = Vec::with_capacity(count.min(1 << 16));
+
+            for _ in 0..count {
+                occurrences.push(decoder.occurrence()?);
+            }
+
+            files.insert(
+                path,
+                FileSymbols {
+                    occurrences,
+                    content_hash,
+                },
+            );
+        }
+
+        Ok(SymbolIndex::from_files(files))
+    }
+}
+
+//======================================
+// Encoding
+//======================================
+
+#[derive(Default)]
+struct StringTable {
+    strings: Vec<String>,
+    indices: HashMap<String, u64>,
+}
+
+impl StringTable {
+    fn intern(&mut self, string: &str) -> u64 {
+        if let Some(index) = self.indices.get(string) {
+            return *index;
+        }
+
+        let index = self.strings.len() as u64;
+        self.strings.push(string.to_owned());
+        self.indices.insert(string.to_owned(), index);
+
+        index
+    }
+}
+
+fn write_occurrence(
+    out: &mut Vec<u8>,
+    strings: &mut StringTable,
+    occ: &RawOccurrence,
+) -> io::Result<()> {
+    let RawOccurrence {
+        name,
+        span,
+        kind,
+        resolution,
+        public,
+    } = occ;
+
+    utils::write_varint(out, strings.intern(name))?;
+
+    span.start().write_binary(out)?;
+    span.end().write_binary(out)?;
+
+    out.push(match kind {
+        OccurrenceKind::Definition => 0,
+        OccurrenceKind::Use => 1,
+    });
+    out.push(u8::from(*public));
+
+    match resolution {
+        Resolution::Qualified(context) => {
+            out.push(0);
+            utils::write_varint(out, strings.intern(context))?;
+        },
+        Resolution::Unqualified {
+            context,
+            context_path,
+        } => {
+            out.push(1);
+            utils::write_varint(out, strings.intern(context))?;
+            utils::write_varint(out, context_path.len() as u64)?;
+            for context in context_path.iter() {
+                utils::write_varint(out, strings.intern(context))?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+//======================================
+// Decoding
+//======================================
+
+struct Decoder<R> {
+    reader: R,
+    strings: Vec<String>,
+
+    /// Context paths are shared between consecutive occurrences in memory, so
+    /// share identical paths again when loading.
+    context_paths: HashMap<Vec<u64>, Arc<[String]>>,
+}
+
+impl<R: Read> Decoder<R> {
+    fn occurrence(&mut self) -> io::Result<RawOccurrence> {
+        let name = self.string()?;
+
+        let start = Location::read_binary(&mut self.reader)?;
+        let end = Location::read_binary(&mut self.reader)?;
+
+        if !matches!(
+            start.partial_cmp(&end),
+            Some(Ordering::Less | Ordering::Equal)
+        ) {
+            return Err(invalid_data("span start is after end".to_owned()));
+        }
+
+        let kind = match self.byte()? {
+            0 => OccurrenceKind::Definition,
+            1 => OccurrenceKind::Use,
+            other => {
+                return Err(invalid_data(format!(
+                    "invalid occurrence kind: {other}"
+                )))
+            },
+        };
+
+        let public = self.byte()? != 0;
+
+        let resolution = match self.byte()? {
+            0 => Resolution::Qualified(self.string()?),
+            1 => {
+                let context = self.string()?;
+
+                let count = self.len()?;
+                let mut indices = Vec::with_capacity(count.min(64));
+                for _ in 0..count {
+                    indices.push(self.uint()?);
+                }
+
+                let context_path =
+                    match self.context_paths.get(&indices).cloned() {
+                        Some(context_path) => context_path,
+                        None => {
+                            let context_path: Arc<[String]> = indices
+                                .iter()
+                                .map(|index| self.lookup(*index))
+                                .collect::<io::Result<Vec<String>>>()?
+                                .into();
+
+                            self.context_paths
+                                .insert(indices, context_path.clone());
+
+                            context_path
+                        },
+                    };
+
+                Resolution::Unqualified {
+                    context,
+                    context_path,
+                }
+            },
+            other => {
+                return Err(invalid_data(format!(
+                    "invalid symbol resolution kind: {other}"
+                )))
+            },
+        };
+
+        Ok(RawOccurrence {
+            name,
+            span: Span::new(start, end),
+            kind,
+            resolution,
+            public,
+        })
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        let index = self.uint()?;
+
+        self.lookup(index)
+    }
+
+    fn lookup(&self, index: u64) -> io::Result<String> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.strings.get(index))
+            .cloned()
+            .ok_or_else(|| {
+                invalid_data(format!("string index out of range: {index}"))
+            })
+    }
+
+    fn len(&mut self) -> io::Result<usize> {
+        read_len(&mut self.reader)
+    }
+
+    fn uint(&mut self) -> io::Result<u64> {
+        utils::read_varint(&mut self.reader)
+    }
+
+    fn byte(&mut self) -> io::Result<u8> {
+        let mut byte = [0];
+        self.reader.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+}
+
+fn read_len<R: Read>(reader: &mut R) -> io::Result<usize> {
+    usize::try_from(utils::read_varint(reader)?)
+        .map_err(|err| invalid_data(err.to_string()))
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -1,3 +1,7 @@
+mod dedup;
+mod summary;
+pub(crate) mod syntax_errors;
+
 use std::cmp::Ordering;
 
 use crate::{
@@ -7,10 +11,19 @@ use crate::{
 };
 
 
-type AdditionalDescriptionVector = Vec<String>;
+pub use self::{
+    dedup::deduplicate,
+    summary::{summarize, IssueSummary, Region, REGION_LINES},
+    syntax_errors::SyntaxErrors,
+};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Issue {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_impls::symbol_ref")
+    )]
     pub make_sym: Symbol,
     pub tag: IssueTag,
     pub msg: String,
@@ -18,11 +31,12 @@ pub struct Issue {
     pub src: Source,
     pub val: f64,
     pub actions: Vec<CodeAction>,
-    pub additional_descriptions: AdditionalDescriptionVector,
+    pub annotations: Vec<Annotation>,
     pub additional_sources: Vec<Source>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IssueTag {
     Ambiguous,
     UnhandledCharacter,
@@ -47,9 +61,92 @@ pub enum IssueTag {
     PrefixNotNot,
     StrangeCall,
     StrangeCallSlotSequence,
+    /// Syntax that is not allowed by the [`GrammarProfile`][crate::GrammarProfile]
+    /// used for parsing.
+    DisallowedSyntax,
+    /// A group closer that does not match the innermost open group.
+    UnexpectedCloser,
+    /// A syntax error node in the parsed syntax.
+    ///
+    /// See [`SyntaxErrors`].
+    SyntaxError,
+    /// A malformed [`Directive`][crate::directive::Directive] in a comment.
+    InvalidDirective,
+    /// A comma followed by an implicit `Null` argument at the end of a
+    /// sequence, like in `f[a, ]`.
+    TrailingComma,
+    /// An implicit `Null` argument before a comma, like in `{1,,2}`.
+    EmptyArgument,
+    /// Input that the parser failed on, returned as a
+    /// [`SyntaxErrorKind::Unparsable`][crate::parse::SyntaxErrorKind::Unparsable]
+    /// node.
+    ///
+    /// See [`RecoveryMode::Recover`][crate::RecoveryMode::Recover].
+    ParserFailure,
+    //
+    // Style Issues
+    //
+    /// A line longer than
+    /// [`ParseOptions::max_line_length()`][crate::ParseOptions::max_line_length].
+    LineTooLong,
+    /// A line indented differently from the rest of the file.
+    ///
+    /// See
+    /// [`ParseOptions::check_mixed_indentation()`][crate::ParseOptions::check_mixed_indentation].
+    MixedIndentation,
+    //
+    // Lint Issues
+    //
+    /// A top-level statement of a package that is evaluated when the package
+    /// is loaded.
+    ///
+    /// See [`top_level_side_effects()`][crate::lint::top_level_side_effects].
+    TopLevelSideEffect,
+    /// `ToExpression[..]` of input that is not a literal string.
+    ///
+    /// See [`dynamic_code_execution()`][crate::lint::dynamic_code_execution].
+    DynamicToExpression,
+    /// `Symbol[..]` of a name that is not a literal string.
+    ///
+    /// See [`dynamic_code_execution()`][crate::lint::dynamic_code_execution].
+    DynamicSymbol,
+    /// `Get[..]` of a path that is not a literal string.
+    ///
+    /// See [`dynamic_code_execution()`][crate::lint::dynamic_code_execution].
+    DynamicGet,
+    /// A top-level `Set` of a pattern whose right-hand side uses one of the
+    /// pattern variables, like `f[x_] = x^2`.
+    ///
+    /// See [`lint()`][crate::lint::lint].
+    SuspiciousSet,
+    /// An assignment to a protected `System` symbol.
+    ///
+    /// See [`lint()`][crate::lint::lint].
+    ProtectedAssignment,
+    /// An implicit multiplication between operands on different lines.
+    ///
+    /// See [`lint()`][crate::lint::lint].
+    ImplicitTimesAcrossLines,
+    /// A `Module`, `Block`, or `With` with an empty variable list.
+    ///
+    /// See [`lint()`][crate::lint::lint].
+    EmptyScopeVariables,
+    /// An `If` or `Which` condition that is always `True` or always
+    /// `False`.
+    ///
+    /// See [`lint()`][crate::lint::lint].
+    ConstantCondition,
+    /// A comparison of literals, like `1 == 1`.
+    ///
+    /// See [`lint()`][crate::lint::lint].
+    ConstantComparison,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// How severe an [`Issue`] is.
+///
+/// Severities are ordered from least to most severe.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Severity {
     Formatting,
     Remark,
@@ -59,6 +156,7 @@ pub enum Severity {
 }
 
 #[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CodeAction {
     pub label: String,
     pub kind: CodeActionKind,
@@ -66,12 +164,39 @@ pub struct CodeAction {
 }
 
 #[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CodeActionKind {
     ReplaceText { replacement_text: String },
     InsertText { insertion_text: String },
     DeleteText,
 }
 
+/// Additional information attached to an [`Issue`], beyond its message.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Annotation {
+    pub kind: AnnotationKind,
+    pub text: String,
+    /// The code this annotation is about, which may differ from the source
+    /// of the issue.
+    pub src: Source,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum AnnotationKind {
+    /// Why the issue was reported, e.g. what the code was expected to look
+    /// like.
+    Explanation,
+    /// How the issue could be fixed, when there is no automatic
+    /// [`CodeAction`].
+    Suggestion,
+    /// Another issue reported for the same problem, which was collapsed into
+    /// this one by [`deduplicate()`].
+    Note,
+}
+
 
 //=======================================
 // Impls
@@ -106,6 +231,25 @@ impl IssueTag {
             IssueTag::PrefixNotNot => "PrefixNotNot",
             IssueTag::StrangeCall => "StrangeCall",
             IssueTag::StrangeCallSlotSequence => "StrangeCallSlotSequence",
+            IssueTag::DisallowedSyntax => "DisallowedSyntax",
+            IssueTag::UnexpectedCloser => "UnexpectedCloser",
+            IssueTag::SyntaxError => "SyntaxError",
+            IssueTag::InvalidDirective => "InvalidDirective",
+            IssueTag::TrailingComma => "TrailingComma",
+            IssueTag::EmptyArgument => "EmptyArgument",
+            IssueTag::ParserFailure => "ParserFailure",
+            IssueTag::LineTooLong => "LineTooLong",
+            IssueTag::MixedIndentation => "MixedIndentation",
+            IssueTag::TopLevelSideEffect => "TopLevelSideEffect",
+            IssueTag::DynamicToExpression => "DynamicToExpression",
+            IssueTag::DynamicSymbol => "DynamicSymbol",
+            IssueTag::DynamicGet => "DynamicGet",
+            IssueTag::SuspiciousSet => "SuspiciousSet",
+            IssueTag::ProtectedAssignment => "ProtectedAssignment",
+            IssueTag::ImplicitTimesAcrossLines => "ImplicitTimesAcrossLines",
+            IssueTag::EmptyScopeVariables => "EmptyScopeVariables",
+            IssueTag::ConstantCondition => "ConstantCondition",
+            IssueTag::ConstantComparison => "ConstantComparison",
             // NOTE: When adding a case here, also update from_str().
         }
     }
@@ -139,6 +283,25 @@ impl IssueTag {
             "PrefixNotNot" => IssueTag::PrefixNotNot,
             "StrangeCall" => IssueTag::StrangeCall,
             "StrangeCallSlotSequence" => IssueTag::StrangeCallSlotSequence,
+            "DisallowedSyntax" => IssueTag::DisallowedSyntax,
+            "UnexpectedCloser" => IssueTag::UnexpectedCloser,
+            "SyntaxError" => IssueTag::SyntaxError,
+            "InvalidDirective" => IssueTag::InvalidDirective,
+            "TrailingComma" => IssueTag::TrailingComma,
+            "EmptyArgument" => IssueTag::EmptyArgument,
+            "ParserFailure" => IssueTag::ParserFailure,
+            "LineTooLong" => IssueTag::LineTooLong,
+            "MixedIndentation" => IssueTag::MixedIndentation,
+            "TopLevelSideEffect" => IssueTag::TopLevelSideEffect,
+            "DynamicToExpression" => IssueTag::DynamicToExpression,
+            "DynamicSymbol" => IssueTag::DynamicSymbol,
+            "DynamicGet" => IssueTag::DynamicGet,
+            "SuspiciousSet" => IssueTag::SuspiciousSet,
+            "ProtectedAssignment" => IssueTag::ProtectedAssignment,
+            "ImplicitTimesAcrossLines" => IssueTag::ImplicitTimesAcrossLines,
+            "EmptyScopeVariables" => IssueTag::EmptyScopeVariables,
+            "ConstantCondition" => IssueTag::ConstantCondition,
+            "ConstantComparison" => IssueTag::ConstantComparison,
             _ => return None,
         };
 
@@ -173,12 +336,48 @@ impl Severity {
     }
 }
 
+impl AnnotationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnnotationKind::Explanation => "Explanation",
+            AnnotationKind::Suggestion => "Suggestion",
+            AnnotationKind::Note => "Note",
+        }
+    }
+}
+
 
 //==========================================================
 // Code Actions
 //==========================================================
 
 
+impl Annotation {
+    pub fn explanation(text: String, src: Source) -> Self {
+        Annotation {
+            kind: AnnotationKind::Explanation,
+            text,
+            src,
+        }
+    }
+
+    pub fn suggestion(text: String, src: Source) -> Self {
+        Annotation {
+            kind: AnnotationKind::Suggestion,
+            text,
+            src,
+        }
+    }
+
+    pub fn note(text: String, src: Source) -> Self {
+        Annotation {
+            kind: AnnotationKind::Note,
+            text,
+            src,
+        }
+    }
+}
+
 impl CodeAction {
     pub fn replace_text(
         label: String,
@@ -257,7 +456,7 @@ impl PartialEq for Issue {
             && self.src == other.src
             && self.val == other.val
             && self.actions == other.actions
-            && self.additional_descriptions == other.additional_descriptions
+            && self.annotations == other.annotations
     }
 }
 
@@ -272,7 +471,7 @@ impl Issue {
         src: Span,
         val: std::os::raw::c_double,
         actions: Vec<CodeAction>,
-        additional_descriptions: AdditionalDescriptionVector,
+        annotations: Vec<Annotation>,
     ) -> Issue {
         Issue {
             make_sym,
@@ -282,7 +481,7 @@ impl Issue {
             src: Source::Span(src),
             val,
             actions,
-            additional_descriptions,
+            annotations,
             additional_sources: Vec::new(),
         }
     }
@@ -302,7 +501,7 @@ impl Issue {
             src,
             val,
             actions: Vec::new(),
-            additional_descriptions: Vec::new(),
+            annotations: Vec::new(),
             additional_sources: Vec::new(),
         }
     }
@@ -319,14 +518,11 @@ impl Issue {
         }
     }
 
-    pub fn with_additional_descriptions(
-        self,
-        additional_descriptions: Vec<String>,
-    ) -> Self {
-        debug_assert!(self.additional_descriptions.is_empty());
+    pub fn with_annotations(self, annotations: Vec<Annotation>) -> Self {
+        debug_assert!(self.annotations.is_empty());
 
         Issue {
-            additional_descriptions,
+            annotations,
             ..self
         }
     }
@@ -373,7 +569,7 @@ pub(crate) fn SyntaxIssue(
     src: Span,
     val: std::os::raw::c_double,
     actions: Vec<CodeAction>,
-    additional_descriptions: AdditionalDescriptionVector,
+    annotations: Vec<Annotation>,
 ) -> Issue {
     Issue::new(
         sym::CodeParser_SyntaxIssue,
@@ -383,7 +579,7 @@ pub(crate) fn SyntaxIssue(
         src,
         val,
         actions,
-        additional_descriptions,
+        annotations,
     )
 }
 
@@ -394,7 +590,7 @@ pub(crate) fn FormatIssue(
     src: Span,
     val: std::os::raw::c_double,
     actions: Vec<CodeAction>,
-    additional_descriptions: AdditionalDescriptionVector,
+    annotations: Vec<Annotation>,
 ) -> Issue {
     Issue::new(
         sym::CodeParser_FormatIssue,
@@ -404,7 +600,7 @@ pub(crate) fn FormatIssue(
         src,
         val,
         actions,
-        additional_descriptions,
+        annotations,
     )
 }
 
@@ -415,7 +611,7 @@ pub(crate) fn EncodingIssue(
     src: Span,
     val: std::os::raw::c_double,
     actions: Vec<CodeAction>,
-    additional_descriptions: AdditionalDescriptionVector,
+    annotations: Vec<Annotation>,
 ) -> Issue {
     Issue::new(
         sym::CodeParser_EncodingIssue,
@@ -425,7 +621,7 @@ pub(crate) fn EncodingIssue(
         src,
         val,
         actions,
-        additional_descriptions,
+        annotations,
     )
 }
 
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use crate::source::Source;
+
+use super::{Annotation, Issue, IssueTag, Severity};
+
+/// Collapse the issues in `issues` that describe the same problem into a
+/// single primary issue.
+///
+/// The tokenizer, the parser, and the abstraction of parsed syntax can each
+/// report the same problem. For example, with
+/// [`RecoveryMode::Recover`][crate::RecoveryMode::Recover], empty input is
+/// reported both as a parser failure and as the syntax error node returned in
+/// its place. Issues describe the same problem if they have the same source,
+/// and are either both errors ([`Severity::Error`] or [`Severity::Fatal`]) or
+/// have the same tag.
+///
+/// Of the issues describing the same problem, the most severe is kept, then
+/// the most confident, then the first. Each of the others is added to the
+/// kept issue as an [`AnnotationKind::Note`][super::AnnotationKind::Note]
+/// annotation, unless it has the same message, and its code actions and
+/// annotations are added to those of the kept issue.
+///
+/// The kept issues are returned in the order of the first issue describing
+/// each problem. Issues with an unknown source are never collapsed.
+///
+/// ```
+/// use wolfram_parser::{
+///     issue::{self, AnnotationKind, SyntaxErrors},
+///     parse_cst, ParseOptions, RecoveryMode,
+/// };
+///
+/// let result =
+///     parse_cst("", &ParseOptions::default().recovery(RecoveryMode::Recover));
+///
+/// let all: Vec<_> = result
+///     .issues()
+///     .cloned()
+///     .chain(result.syntax.syntax_errors())
+///     .collect();
+///
+/// assert!(all.len() > 1);
+///
+/// let deduplicated = issue::deduplicate(all);
+///
+/// assert_eq!(deduplicated.len(), 1);
+/// assert_eq!(deduplicated[0].msg, "Expected an expression.");
+/// assert_eq!(deduplicated[0].annotations[0].kind, AnnotationKind::Note);
+/// ```
+pub fn deduplicate(issues: impl IntoIterator<Item = Issue>) -> Vec<Issue> {
+    let mut problems: Vec<Vec<Issue>> = Vec::new();
+    let mut index: HashMap<(Source, Problem), usize> = HashMap::new();
+
+    for issue in issues {
+        if issue.src.is_unknown() {
+            problems.push(vec![issue]);
+            continue;
+        }
+
+        let key = (issue.src.clone(), Problem::of(&issue));
+
+        match index.get(&key) {
+            Some(&problem) => problems[problem].push(issue),
+            None => {
+                index.insert(key, problems.len());
+                problems.push(vec![issue]);
+            },
+        }
+    }
+
+    problems.into_iter().map(collapse).collect()
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// What issues with the same source must have in common to describe the same
+/// problem.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Problem {
+    Error,
+    Tag(IssueTag),
+}
+
+impl Problem {
+    fn of(issue: &Issue) -> Self {
+        match issue.sev {
+            Severity::Error | Severity::Fatal => Problem::Error,
+            _ => Problem::Tag(issue.tag),
+        }
+    }
+}
+
+/// Collapse `issues`, which describe the same problem, into one issue.
+fn collapse(mut issues: Vec<Issue>) -> Issue {
+    // max_by() returns the last of equal elements, so search in reverse to
+    // find the first.
+    let primary = (0..issues.len())
+        .rev()
+        .max_by(|&a, &b| {
+            let (a, b) = (&issues[a], &issues[b]);
+
+            a.sev.cmp(&b.sev).then(a.val.total_cmp(&b.val))
+        })
+        .expect("problem has at least one issue");
+
+    let mut primary = issues.remove(primary);
+
+    for issue in issues {
+        let Issue {
+            make_sym: _,
+            tag: _,
+            msg,
+            sev: _,
+            src,
+            val: _,
+            actions,
+            annotations,
+            additional_sources,
+        } = issue;
+
+        if msg != primary.msg {
+            primary.annotations.push(Annotation::note(msg, src));
+        }
+
+        extend_unique(&mut primary.actions, actions);
+        extend_unique(&mut primary.annotations, annotations);
+        extend_unique(&mut primary.additional_sources, additional_sources);
+    }
+
+    primary
+}
+
+fn extend_unique<T: PartialEq>(values: &mut Vec<T>, new: Vec<T>) {
+    for value in new {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+}
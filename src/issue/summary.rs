@@ -0,0 +1,152 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    hash::Hash,
+};
+
+use crate::source::{LineColumn, Location, Source};
+
+use super::{Issue, IssueTag, Severity};
+
+/// Number of lines in each region counted by [`IssueSummary::by_region`].
+pub const REGION_LINES: u32 = 100;
+
+/// Counts of issues, as computed by [`summarize()`].
+///
+/// Every list of counts is ranked: the most frequent entry comes first, and
+/// entries with the same count are ordered by their key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueSummary {
+    pub total: usize,
+    pub by_tag: Vec<(IssueTag, usize)>,
+    pub by_severity: Vec<(Severity, usize)>,
+    /// Number of issues that start in each region of [`REGION_LINES`] lines.
+    ///
+    /// Issues whose source is not a line and column span are not counted.
+    pub by_region: Vec<(Region, usize)>,
+}
+
+/// A range of lines of a file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Region {
+    /// The first line in this region.
+    pub start_line: u32,
+    /// The last line in this region, inclusive.
+    pub end_line: u32,
+}
+
+/// Count `issues` by tag, severity, and region of the file they start in.
+///
+/// ```
+/// use wolfram_parser::{
+///     issue::{self, IssueTag, Region},
+///     parse_cst_seq, ParseOptions,
+/// };
+///
+/// let result = parse_cst_seq("1.2.3\n4.5.6", &ParseOptions::default());
+///
+/// let issues: Vec<_> = result.issues().cloned().collect();
+///
+/// let summary = issue::summarize(&issues);
+///
+/// assert_eq!(summary.by_tag[0], (IssueTag::UnexpectedImplicitTimes, 2));
+/// assert_eq!(
+///     summary.by_region,
+///     [(Region { start_line: 1, end_line: 100 }, summary.total)]
+/// );
+/// ```
+pub fn summarize(issues: &[Issue]) -> IssueSummary {
+    let regions = issues.iter().filter_map(|issue| {
+        let Source::Span(span) = &issue.src else {
+            return None;
+        };
+
+        let Location::LineColumn(LineColumn(line, _)) = span.start() else {
+            return None;
+        };
+
+        let start_line = (line.get() - 1) / REGION_LINES * REGION_LINES + 1;
+
+        Some(Region {
+            start_line,
+            end_line: start_line + REGION_LINES - 1,
+        })
+    });
+
+    IssueSummary {
+        total: issues.len(),
+        by_tag: ranked(issues.iter().map(|issue| issue.tag)),
+        by_severity: ranked(issues.iter().map(|issue| issue.sev)),
+        by_region: ranked(regions),
+    }
+}
+
+impl IssueSummary {
+    /// Get the number of issues with severity `sev`.
+    pub fn count(&self, sev: Severity) -> usize {
+        self.by_severity
+            .iter()
+            .find(|(key, _)| *key == sev)
+            .map_or(0, |(_, count)| *count)
+    }
+}
+
+/// Formats a one-line summary, like
+/// `3 issues (2 Warning, 1 Remark); most common: UnexpectedImplicitTimes (2)`.
+impl Display for IssueSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let IssueSummary {
+            total,
+            ref by_tag,
+            ref by_severity,
+            by_region: _,
+        } = *self;
+
+        match total {
+            1 => write!(f, "1 issue")?,
+            _ => write!(f, "{total} issues")?,
+        }
+
+        if total == 0 {
+            return Ok(());
+        }
+
+        // Most severe first.
+        let mut by_severity = by_severity.clone();
+        by_severity.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let severities: Vec<String> = by_severity
+            .iter()
+            .map(|(sev, count)| format!("{count} {}", sev.as_str()))
+            .collect();
+
+        write!(f, " ({})", severities.join(", "))?;
+
+        let tags: Vec<String> = by_tag
+            .iter()
+            .take(3)
+            .map(|(tag, count)| format!("{} ({count})", tag.as_str()))
+            .collect();
+
+        write!(f, "; most common: {}", tags.join(", "))
+    }
+}
+
+/// Count each distinct key, most frequent first.
+fn ranked<K: Copy + Eq + Hash + Ord>(
+    keys: impl Iterator<Item = K>,
+) -> Vec<(K, usize)> {
+    let mut counts: HashMap<K, usize> = HashMap::new();
+
+    for key in keys {
+        *counts.entry(key).or_default() += 1;
+    }
+
+    let mut counts: Vec<(K, usize)> = counts.into_iter().collect();
+
+    counts.sort_by(|(a_key, a_count), (b_key, b_count)| {
+        b_count.cmp(a_count).then(a_key.cmp(b_key))
+    });
+
+    counts
+}
@@ -0,0 +1,209 @@
+//! Issues describing the syntax error nodes in parsed syntax.
+//!
+//! Syntax errors like a missing operand or closer are represented as nodes in
+//! the returned syntax, not as entries in
+//! [`ParseResult::fatal_issues`][crate::ParseResult::fatal_issues]. The
+//! [`SyntaxErrors`] trait converts these nodes into [`Issue`]s, so that they
+//! can be reported alongside other issues.
+
+use crate::{
+    ast::Ast,
+    cst::{CallBody, CallNode, Cst, SyntaxErrorNode},
+    issue::{Issue, IssueTag, Severity},
+    parse::SyntaxErrorKind,
+    source::Source,
+    tokenize::{Token, TokenKind, TokenSource},
+    NodeSeq,
+};
+
+/// Syntax that can contain syntax error nodes.
+///
+/// This is implemented for the syntax types returned by the parsing functions
+/// in this crate.
+pub trait SyntaxErrors {
+    /// Get a [`Severity::Fatal`] issue describing each syntax error node in
+    /// this syntax, in source order.
+    ///
+    /// ```
+    /// use wolfram_parser::{issue::SyntaxErrors, parse_cst, ParseOptions};
+    ///
+    /// let result = parse_cst("f[a +", &ParseOptions::default());
+    ///
+    /// let errors = result.syntax.syntax_errors();
+    ///
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].msg, "Missing closer.");
+    /// assert_eq!(errors[1].msg, "Expected an operand.");
+    /// ```
+    fn syntax_errors(&self) -> Vec<Issue>;
+}
+
+//======================================
+// Impls
+//======================================
+
+impl<I, S: TokenSource> SyntaxErrors for Cst<I, S> {
+    fn syntax_errors(&self) -> Vec<Issue> {
+        let mut errors = Vec::new();
+
+        self.visit(&mut |node| {
+            if let Some(msg) = cst_error_message(node) {
+                errors.push(error_issue(msg, node.get_source().into_general()));
+            }
+        });
+
+        errors
+    }
+}
+
+impl SyntaxErrors for Ast {
+    fn syntax_errors(&self) -> Vec<Issue> {
+        let mut errors = Vec::new();
+
+        ast_syntax_errors(self, &mut errors);
+
+        errors
+    }
+}
+
+impl<N: SyntaxErrors> SyntaxErrors for NodeSeq<N> {
+    fn syntax_errors(&self) -> Vec<Issue> {
+        let NodeSeq(nodes) = self;
+
+        nodes.iter().flat_map(N::syntax_errors).collect()
+    }
+}
+
+impl<I, S: TokenSource> SyntaxErrors for Token<I, S> {
+    fn syntax_errors(&self) -> Vec<Issue> {
+        match token_error_message(self.tok) {
+            Some(msg) => {
+                vec![error_issue(msg, self.src.clone().into_general())]
+            },
+            None => vec![],
+        }
+    }
+}
+
+impl<N: SyntaxErrors> SyntaxErrors for Option<N> {
+    fn syntax_errors(&self) -> Vec<Issue> {
+        match self {
+            Some(node) => node.syntax_errors(),
+            None => vec![],
+        }
+    }
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// Get a description of `node` if it is a syntax error node.
+///
+/// Only `node` itself is checked, not its children.
+pub(crate) fn cst_error_message<I, S>(
+    node: &Cst<I, S>,
+) -> Option<&'static str> {
+    let msg = match node {
+        Cst::Token(Token { tok, .. }) => return token_error_message(*tok),
+        Cst::SyntaxError(SyntaxErrorNode { err, children: _ }) => {
+            syntax_error_message(*err)
+        },
+        Cst::Call(CallNode {
+            head: _,
+            body: CallBody::GroupMissingCloser(_),
+        })
+        | Cst::GroupMissingCloser(_) => "Missing closer.",
+        Cst::GroupMissingOpener(_) => "Missing opener.",
+        _ => return None,
+    };
+
+    Some(msg)
+}
+
+fn ast_syntax_errors(ast: &Ast, errors: &mut Vec<Issue>) {
+    let msg = match ast {
+        Ast::Error { kind, .. } => token_error_message(*kind),
+        Ast::SyntaxError { kind, .. } => Some(syntax_error_message(*kind)),
+        Ast::AbstractSyntaxError { .. } => Some("Invalid syntax."),
+        Ast::CallMissingCloser { .. } | Ast::GroupMissingCloser { .. } => {
+            Some("Missing closer.")
+        },
+        Ast::GroupMissingOpener { .. } => Some("Missing opener."),
+        _ => None,
+    };
+
+    if let Some(msg) = msg {
+        errors.push(error_issue(msg, ast.source().clone()));
+    }
+
+    // Errors found while abstracting this node.
+    errors.extend(
+        ast.metadata()
+            .issues
+            .iter()
+            .filter(|issue| {
+                matches!(issue.sev, Severity::Error | Severity::Fatal)
+            })
+            .cloned(),
+    );
+
+    ast.for_each_child(|child| ast_syntax_errors(child, errors));
+}
+
+pub(crate) fn error_issue(msg: &str, src: Source) -> Issue {
+    Issue::syntax(
+        IssueTag::SyntaxError,
+        msg.to_owned(),
+        Severity::Fatal,
+        src,
+        1.0,
+    )
+}
+
+/// Get a description of `tok` if it is a syntax error token.
+///
+/// Implicit `Null` tokens, like in `f[1,]`, are not considered errors.
+fn token_error_message(tok: TokenKind) -> Option<&'static str> {
+    let msg = match tok {
+        TokenKind::Error_ExpectedEqual => "Expected `=`.",
+        TokenKind::Error_Number => "Invalid number.",
+        TokenKind::Error_UnhandledCharacter => "Unhandled character.",
+        TokenKind::Error_ExpectedLetterlike => {
+            "Expected a letterlike character."
+        },
+        TokenKind::Error_Aborted => "Aborted.",
+        TokenKind::Error_ExpectedOperand => "Expected an operand.",
+        TokenKind::Error_ExpectedTag => "Expected a tag.",
+        TokenKind::Error_ExpectedFile => "Expected a file.",
+        TokenKind::Error_UnexpectedCloser => "Unexpected closer.",
+        TokenKind::Error_UnsafeCharacterEncoding => {
+            "Unsafe character encoding."
+        },
+        TokenKind::Error_UnterminatedComment => "Unterminated comment.",
+        TokenKind::Error_UnterminatedString => "Unterminated string.",
+        TokenKind::Error_UnterminatedFileString => "Unterminated file string.",
+        TokenKind::Error_UnterminatedLinearSyntaxBlob => {
+            "Unterminated linear syntax."
+        },
+        TokenKind::Error_UnsupportedToken => "Unsupported token.",
+        TokenKind::Error_UnexpectedCommentCloser => {
+            "Unexpected comment closer."
+        },
+        TokenKind::Error_PrefixImplicitNull
+        | TokenKind::Error_InfixImplicitNull => return None,
+        tok if tok.isError() => "Syntax error.",
+        _ => return None,
+    };
+
+    Some(msg)
+}
+
+fn syntax_error_message(kind: SyntaxErrorKind) -> &'static str {
+    match kind {
+        SyntaxErrorKind::ExpectedSymbol => "Expected a symbol.",
+        SyntaxErrorKind::ExpectedSet => "Expected `=`, `:=`, or `=.`.",
+        SyntaxErrorKind::ExpectedTilde => "Expected `~`.",
+        SyntaxErrorKind::Unparsable => "Unparsable input.",
+    }
+}
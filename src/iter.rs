@@ -52,12 +52,28 @@
 //! assert_eq!(chars.next(), Some(Token::new(TokenKind::Symbol, "\\[Pi]", src!(1:3-8))));
 //! assert_eq!(chars.next(), None);
 //! ```
+//!
+//! ## Top-level Expressions
+//!
+//! Parse the top-level expressions in an input one at a time using
+//! [`parse_cst_expr_iter()`][crate::parse_cst_expr_iter], which returns a
+//! [`CstExprs`] iterator.
+//!
+//! Parse the top-level expressions read from a stream one at a time, without
+//! reading all of it first, using
+//! [`parse_reader_expr_iter()`][crate::parse_reader_expr_iter], which returns
+//! a [`ReaderExprs`] iterator.
+
+use std::io::BufRead;
 
 use crate::{
-    read::{code_point::CodePoint, Reader, WLCharacter},
+    cst::{Cst, CstSeq},
+    parse,
+    read::{code_point::CodePoint, InputMark, Reader, WLCharacter},
+    scan,
     source::{SourceCharacter, TOPLEVEL},
-    tokenize::{Token, TokenKind, TokenStr, Tokenizer},
-    ParseOptions,
+    tokenize::{Token, TokenKind, TokenStr, TokenString, Tokenizer},
+    NodeSeq, ParseError, ParseOptions, ParseResult,
 };
 
 //======================================
@@ -117,6 +133,156 @@ pub struct Tokens<'i> {
     tokenizer: Tokenizer<'i>,
 }
 
+/// Iterator over the top-level expressions in a Wolfram Language input,
+/// parsed one at a time.
+///
+/// Returned by [`parse_cst_expr_iter()`][crate::parse_cst_expr_iter].
+pub struct CstExprs<'i> {
+    input: &'i [u8],
+    /// Where to resume parsing, or `None` if nothing has been parsed yet.
+    mark: Option<InputMark>,
+    opts: ParseOptions,
+    done: bool,
+}
+
+impl<'i> CstExprs<'i> {
+    pub(crate) fn new(input: &'i [u8], opts: &ParseOptions) -> Self {
+        CstExprs {
+            input,
+            mark: None,
+            opts: opts.clone(),
+            done: false,
+        }
+    }
+}
+
+/// Iterator over the top-level expressions read from a stream, parsed one at
+/// a time.
+///
+/// Returned by [`parse_reader_expr_iter()`][crate::parse_reader_expr_iter].
+pub struct ReaderExprs<R> {
+    reader: R,
+    /// Input that has been read but not parsed yet, preceded by the start of
+    /// the line it begins on.
+    buffer: Vec<u8>,
+    /// Where to resume parsing in `buffer`, or `None` if nothing has been
+    /// parsed yet.
+    mark: Option<InputMark>,
+    opts: ParseOptions,
+    /// Progress scanning `buffer` for the end of the next expression.
+    scanned: scan::ExprScan,
+    /// Whether all of the input has been read.
+    eof: bool,
+    done: bool,
+}
+
+impl<R: BufRead> ReaderExprs<R> {
+    pub(crate) fn new(reader: R, opts: &ParseOptions) -> Self {
+        ReaderExprs {
+            reader,
+            buffer: Vec::new(),
+            mark: None,
+            opts: opts.clone(),
+            scanned: scan::ExprScan::default(),
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Parse the remaining top-level expressions into a single result.
+    pub(crate) fn parse_rest(
+        mut self,
+    ) -> Result<ParseResult<CstSeq<TokenString>>, ParseError> {
+        let mut result =
+            self.parse_next()?.map(|cst| NodeSeq(Vec::from_iter(cst)));
+
+        if result.syntax.is_empty() {
+            return Ok(result);
+        }
+
+        loop {
+            let next = self.parse_next()?;
+
+            match append(&mut result, next) {
+                Some(cst) => result.syntax.push(cst),
+                None => return Ok(result),
+            }
+        }
+    }
+
+    /// Parse the next top-level expression, reading only as much input as
+    /// is needed.
+    ///
+    /// The syntax is `None` once only top-level trivia is left.
+    fn parse_next(
+        &mut self,
+    ) -> Result<ParseResult<Option<Cst<TokenString>>>, ParseError> {
+        // Results of parsing input that only contained trivia, like a `#!`
+        // line, before more input was read.
+        let mut skipped: Option<ParseResult<Option<Cst<TokenString>>>> = None;
+
+        loop {
+            self.fill()?;
+
+            let (result, end) =
+                parse::parse_cst_first(&self.buffer, self.mark, &self.opts);
+
+            let result = result.map(|cst| cst.map(Cst::into_owned));
+
+            // Drop the parsed input, keeping source locations relative to the
+            // start of the complete input. The line the next expression
+            // starts on is kept whole, because reparsing an unterminated
+            // group or token looks at complete lines.
+            let line_start = self.buffer[..end.offset()]
+                .iter()
+                .rposition(|&byte| byte == b'\n' || byte == b'\r')
+                .map_or(0, |index| index + 1);
+
+            self.buffer.drain(..line_start);
+            self.mark = Some(end.without_prefix(line_start));
+            self.scanned = scan::ExprScan::default();
+
+            let result = match skipped.take() {
+                Some(mut earlier) => {
+                    earlier.syntax = append(&mut earlier, result);
+                    earlier
+                },
+                None => result,
+            };
+
+            if result.syntax.is_some() || self.eof {
+                return Ok(result);
+            }
+
+            skipped = Some(result);
+        }
+    }
+
+    /// Read lines into `buffer` until it starts with a complete top-level
+    /// expression, or the end of the input is reached.
+    fn fill(&mut self) -> Result<(), ParseError> {
+        let start = self.mark.map_or(0, |mark| mark.offset());
+
+        while !self.eof {
+            if self.buffer.len() > start
+                && scan::starts_with_expression(
+                    &self.buffer[start..],
+                    &self.opts,
+                    &mut self.scanned,
+                )
+            {
+                return Ok(());
+            }
+
+            if self.reader.read_until(b'\n', &mut self.buffer)? == 0 {
+                self.eof = true;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 //=======================================
 // Iterator Impls
 //=======================================
@@ -168,3 +334,101 @@ impl<'i> Iterator for Tokens<'i> {
         }
     }
 }
+
+impl<'i> Iterator for CstExprs<'i> {
+    type Item = ParseResult<Cst<TokenStr<'i>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let CstExprs {
+            input,
+            mark,
+            opts,
+            done,
+        } = self;
+
+        if *done {
+            return None;
+        }
+
+        let (result, end) = parse::parse_cst_first(*input, *mark, opts);
+
+        *mark = Some(end);
+
+        // Only trivia was left in the input.
+        if result.syntax.is_none() {
+            *done = true;
+            return None;
+        }
+
+        Some(result.map(Option::unwrap))
+    }
+}
+
+impl<'i> std::iter::FusedIterator for CstExprs<'i> {}
+
+impl<R: BufRead> Iterator for ReaderExprs<R> {
+    type Item = Result<ParseResult<Cst<TokenString>>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = match self.parse_next() {
+            Ok(result) => result,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            },
+        };
+
+        // Only trivia was left in the input.
+        if result.syntax.is_none() {
+            self.done = true;
+            return None;
+        }
+
+        Some(Ok(result.map(Option::unwrap)))
+    }
+}
+
+impl<R: BufRead> std::iter::FusedIterator for ReaderExprs<R> {}
+
+//=======================================
+// Helpers
+//=======================================
+
+/// Append the issues, directives, and tracked source locations of `later`
+/// to `result`, returning the syntax of `later`.
+fn append<T, U>(result: &mut ParseResult<T>, later: ParseResult<U>) -> U {
+    let ParseResult {
+        syntax,
+        unsafe_character_encoding,
+        fatal_issues,
+        non_fatal_issues,
+        directives,
+        tracked,
+    } = later;
+
+    result.unsafe_character_encoding = result
+        .unsafe_character_encoding
+        .or(unsafe_character_encoding);
+    result.fatal_issues.extend(fatal_issues);
+    result.non_fatal_issues.extend(non_fatal_issues);
+    result.directives.extend(directives);
+
+    let tracked_result = &mut result.tracked;
+
+    tracked_result
+        .simple_line_continuations
+        .extend(tracked.simple_line_continuations);
+    tracked_result
+        .complex_line_continuations
+        .extend(tracked.complex_line_continuations);
+    tracked_result
+        .embedded_newlines
+        .extend(tracked.embedded_newlines);
+    tracked_result.embedded_tabs.extend(tracked.embedded_tabs);
+
+    syntax
+}
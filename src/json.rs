@@ -0,0 +1,196 @@
+//! Minimal JSON values, used for the `--format json` output of the command
+//! line tools.
+//!
+//! Only writing JSON is supported.
+
+use std::fmt::{self, Display, Write};
+
+use crate::source::{LineColumn, Location, Span};
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Object fields, in insertion order.
+    Object(Vec<(String, Json)>),
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Json {
+    pub fn object<K: Into<String>>(
+        fields: impl IntoIterator<Item = (K, Json)>,
+    ) -> Json {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<i64> for Json {
+    fn from(value: i64) -> Self {
+        Json::Int(value)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(value: u32) -> Self {
+        Json::Int(i64::from(value))
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+/// Formats as `[line, column]` or as a character index.
+impl From<Location> for Json {
+    fn from(location: Location) -> Self {
+        match location {
+            Location::LineColumn(LineColumn(line, column)) => {
+                Json::from(vec![line.get(), column.get()])
+            },
+            Location::CharacterIndex(index) => Json::from(index),
+        }
+    }
+}
+
+/// Formats as `{"start": ..., "end": ...}`.
+impl From<Span> for Json {
+    fn from(span: Span) -> Self {
+        Json::object([
+            ("start", Json::from(span.start())),
+            ("end", Json::from(span.end())),
+        ])
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Json::Null,
+        }
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(value: Vec<T>) -> Self {
+        Json::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Formats as compact JSON.
+impl Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Int(value) => write!(f, "{value}"),
+            Json::Float(value) if value.is_finite() => write!(f, "{value}"),
+            // JSON has no representation for NaN or infinity.
+            Json::Float(_) => f.write_str("null"),
+            Json::String(value) => write_string(f, value),
+            Json::Array(elements) => {
+                f.write_char('[')?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                f.write_char(']')
+            },
+            Json::Object(fields) => {
+                f.write_char('{')?;
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            },
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+    f.write_char('"')?;
+
+    for c in string.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if u32::from(c) < 0x20 => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+//======================================
+// Tests
+//======================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let value = Json::object([
+            ("a", Json::Int(-12)),
+            ("b", Json::from("x\"y\\z\n\u{1}é")),
+            (
+                "c",
+                Json::Array(vec![
+                    Json::Null,
+                    Json::Bool(true),
+                    Json::Float(1.5),
+                ]),
+            ),
+            ("d", Json::Object(vec![])),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"a":-12,"b":"x\"y\\z\n\u0001é","c":[null,true,1.5],"d":{}}"#
+        );
+    }
+}
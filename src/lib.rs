@@ -45,9 +45,11 @@
 //!
 //! # File Parsing
 //!
-//! File-level functions automatically handle both regular and paclet-encoded files:
+//! File-level functions read a source file and prepare it for parsing in one
+//! call, removing byte order marks, skipping `#!` shebang lines, and decoding
+//! paclet-encoded files:
 //!
-//! ```rust
+//! ```no_run
 //! use wolfram_parser::{parse_file_cst, ParseOptions};
 //!
 //! let opts = ParseOptions::default();
@@ -55,9 +57,17 @@
 //! // Automatically detects and decodes paclets
 //! let result = parse_file_cst("QuantityUnits.m", &opts)?;
 //! println!("Parsed {} expressions", result.syntax.0.len());
-//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! # Ok::<(), wolfram_parser::ParseError>(())
 //! ```
 //!
+//! # Arena Allocation
+//!
+//! With the `bumpalo` feature enabled, `parse_cst_in()` and
+//! `parse_cst_seq_in()` parse into concrete syntax trees whose nodes are
+//! allocated in a [`bumpalo`](https://docs.rs/bumpalo) arena. The whole tree
+//! is freed at once when the arena is dropped or reset, which avoids freeing
+//! every node separately when parsing large files.
+//!
 //! # Paclet Support
 //!
 //! Paclets are compressed Wolfram Language files using Huffman + base-95 encoding.
@@ -69,12 +79,12 @@
 //! The file-level functions ([`parse_file_cst()`], [`parse_file_ast()`], [`tokenize_file()`])
 //! automatically detect and decode paclet files transparently:
 //!
-//! ```rust
+//! ```no_run
 //! use wolfram_parser::{parse_file_cst, ParseOptions};
 //!
 //! // Works with both regular .m files and paclet files
 //! let regular_result = parse_file_cst("Regular.m", &ParseOptions::default())?;
-//! let paclet_result = parse_file_cst("Paclet.m", &ParseOptions::default()))?;
+//! let paclet_result = parse_file_cst("Paclet.m", &ParseOptions::default())?;
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 //!
@@ -82,7 +92,7 @@
 //!
 //! For direct paclet manipulation, use the [`paclet`] module:
 //!
-//! ```rust
+//! ```no_run
 //! use wolfram_parser::paclet;
 //!
 //! let content = std::fs::read_to_string("file.m")?;
@@ -98,6 +108,10 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 //!
+//! Spans reported for decoded source, like the spans of issues, can be mapped
+//! back to the lines of the encoded file they were decoded from using
+//! [`paclet::decode_paclet_with_source_map()`].
+//!
 //! ## Supported Packlet Versions
 //!
 //! - **Version 1N**: Standard paclet encoding (Huffman + base-95)
@@ -128,10 +142,7 @@
 
 macro_rules! incr_diagnostic {
     ($name:ident) => {
-        #[cfg(feature = "DIAGNOSTICS")]
-        {
-            $name += 1;
-        }
+        crate::diagnostics::increment(crate::diagnostics::Counter::$name)
     };
 }
 
@@ -165,6 +176,7 @@ macro_rules! Print {
 
 mod utils;
 mod error_handling;
+mod json;
 pub mod paclet;
 pub mod newtypes;
 
@@ -182,6 +194,9 @@ pub mod symbol;
 pub mod read;
 pub mod tokenize;
 pub mod parse;
+pub mod scan;
+pub mod explain;
+pub mod notation;
 
 pub mod iter;
 
@@ -193,6 +208,30 @@ pub mod cst;
 
 pub mod abstract_cst;
 
+pub mod project;
+pub mod index;
+pub mod data;
+pub mod directive;
+pub mod testing;
+pub mod cli;
+pub mod edit;
+pub mod whitespace;
+pub mod format;
+pub mod report;
+pub mod display_tree;
+pub mod lint;
+pub mod docs;
+pub mod diagnostics;
+pub mod lsp;
+pub mod analysis;
+pub mod file_ops;
+#[cfg(feature = "incremental")]
+pub mod query;
+#[cfg(feature = "bench")]
+pub mod benchmarks;
+#[cfg(feature = "serde")]
+mod serde_impls;
+
 #[doc(hidden)]
 pub mod fmt_as_expr;
 
@@ -213,12 +252,17 @@ mod tests;
 pub mod macros;
 
 mod parse_cst;
+#[cfg(feature = "bumpalo")]
+mod parse_cst_arena;
 
 //==========================================================
 // API
 //==========================================================
 
-use std::fmt::{self, Debug};
+use std::{
+    fmt::{self, Debug},
+    sync::{Arc, OnceLock},
+};
 
 use wolfram_expr::{Expr, Number};
 
@@ -227,16 +271,23 @@ use crate::{
     ast::Ast,
     cst::Cst,
     cst::CstSeq,
-    issue::{CodeAction, Issue},
-    parse_cst::ParseCst,
+    directive::Directive,
+    issue::{
+        Annotation, CodeAction, Issue, IssueTag, Severity, SyntaxErrors,
+    },
+    notation::Notations,
+    read::InputMark,
     source::TOPLEVEL,
-    source::{Source, SourceConvention, DEFAULT_TAB_WIDTH},
+    source::{
+        BoxPosition, Location, Source, SourceConvention, DEFAULT_TAB_WIDTH,
+    },
     tokenize::{
         tokenizer::{
             Tokenizer_nextToken_stringifyAsFile,
             Tokenizer_nextToken_stringifyAsTag, TrackedSourceLocations,
         },
-        Token, TokenKind, TokenStr, Tokenizer,
+        Token, TokenInput, TokenKind, TokenStr, TokenStreamError, TokenString,
+        Tokenizer,
     },
 };
 
@@ -250,6 +301,12 @@ pub use crate::quirks::QuirkSettings;
 
 pub use crate::tokenize::tokenizer::UnsafeCharacterEncoding;
 
+pub use crate::feature::{capabilities, Capabilities};
+
+pub use crate::error_handling::{
+    EncodingError, InternalError, ParseError, ParserError, TokenizerError,
+};
+
 //======================================
 // Types
 //======================================
@@ -283,6 +340,8 @@ pub enum ContainerMissingReason {
 
 /// A sequence of Nodes
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct NodeSeq<N>(pub Vec<N>);
 
 #[derive(Debug)]
@@ -291,7 +350,7 @@ pub struct Metadata {
     pub syntax_issues: Option<Vec<Issue>>,
     pub confidence_level: Option<Number>,
     pub code_actions: Option<Vec<CodeAction>>,
-    pub additional_descriptions: Option<Vec<String>>,
+    pub annotations: Option<Vec<Annotation>>,
     // TODO: Change this to Option<String>?
     pub file_name: Option<Expr>,
     pub embedded_tabs: Option<Expr>,
@@ -319,6 +378,159 @@ pub enum FirstLineBehavior {
     Script = 2,
 }
 
+/// The subset of Wolfram Language syntax accepted by the parser.
+///
+/// Restricted profiles are useful for safely reading untrusted input, such as
+/// configuration or data files, that should not contain general code.
+///
+/// Syntax outside of the profile is still parsed, but each outermost
+/// disallowed expression is reported as a fatal
+/// [`DisallowedSyntax`][crate::issue::IssueTag::DisallowedSyntax] issue.
+///
+/// # Examples
+///
+/// ```
+/// use wolfram_parser::{parse_ast, GrammarProfile, ParseOptions};
+///
+/// let opts = ParseOptions::default().grammar_profile(GrammarProfile::Data);
+///
+/// assert!(parse_ast(r#"<|"a" -> {1, -2.5, "x"}, b :> None|>"#, &opts).is_ok());
+///
+/// assert!(!parse_ast("DeleteFile[\"a.txt\"]", &opts).is_ok());
+/// assert!(!parse_ast("{1 + 2}", &opts).is_ok());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum GrammarProfile {
+    /// All Wolfram Language syntax.
+    #[default]
+    Full,
+
+    /// "Data-only" syntax: literals, symbols, lists, associations, and rules.
+    ///
+    /// Numbers may have a leading `-` or `+` sign. Function calls and all
+    /// other operators are disallowed.
+    Data,
+}
+
+/// Heuristics used to give unterminated tokens and groups a useful source.
+///
+/// An unterminated string, comment, or group, like `"abc` or `f[x`, extends
+/// to the end of the input. Instead of reporting the rest of the input as a
+/// single error, the lines it covers are reparsed, and it is ended at the
+/// last line before one that looks like the start of a new top-level
+/// statement, like `f[x_] := ..` or `End[]`.
+///
+/// Reparsing examines every line covered by an unterminated token, so can be
+/// expensive on large inputs with many unterminated strings. See
+/// [`ParseOptions::reparse_unterminated()`].
+///
+/// ```
+/// use wolfram_parser::{macros::src, tokenize, ParseOptions, ReparseHeuristics};
+///
+/// let input = "x = \"abc\ny = 1\nz = 2";
+///
+/// let opts = ParseOptions::default();
+/// assert_eq!(tokenize(input, &opts).0[4].src, src!(1:5-1:9).into());
+///
+/// let opts = ParseOptions::default().reparse_unterminated(None);
+/// assert_eq!(tokenize(input, &opts).0[4].src, src!(1:5-3:6).into());
+///
+/// let opts = ParseOptions::default().reparse_unterminated(Some(ReparseHeuristics {
+///     max_lookahead_lines: Some(2),
+///     split_at_toplevel_newlines: false,
+/// }));
+/// assert_eq!(tokenize(input, &opts).0[4].src, src!(1:5-2:6).into());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ReparseHeuristics {
+    /// Maximum number of lines, including the first, that an unterminated
+    /// token or group can extend over. `None` for no limit.
+    pub max_lookahead_lines: Option<u32>,
+
+    /// Whether to end an unterminated token or group at the first line that
+    /// looks like a new top-level statement.
+    ///
+    /// If `false`, only [`max_lookahead_lines`][Self::max_lookahead_lines]
+    /// limits its source.
+    pub split_at_toplevel_newlines: bool,
+}
+
+/// No lookahead limit, splitting at top-level newlines.
+impl Default for ReparseHeuristics {
+    fn default() -> Self {
+        ReparseHeuristics {
+            max_lookahead_lines: None,
+            split_at_toplevel_newlines: true,
+        }
+    }
+}
+
+/// How the parser handles input that it fails to parse.
+///
+/// The parser represents syntax errors in its output instead of failing, so
+/// this only matters for input that triggers a bug in the parser. See
+/// [`ParseOptions::recovery()`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RecoveryMode {
+    /// Panic if an internal assertion in the parser fails.
+    #[default]
+    Panic,
+
+    /// Never panic on any input.
+    ///
+    /// If parsing fails, the input is parsed again one top-level expression at
+    /// a time. Each expression that still fails is returned as a
+    /// [`SyntaxErrorKind::Unparsable`][crate::parse::SyntaxErrorKind::Unparsable]
+    /// node containing its tokens, and a fatal
+    /// [`ParserFailure`][crate::issue::IssueTag::ParserFailure] issue is
+    /// reported for it.
+    ///
+    /// Failures are recovered from by catching the panic of the failed
+    /// assertion with [`std::panic::catch_unwind()`], which has some limits:
+    ///
+    /// * An expression that could not be parsed is assumed to end at the
+    ///   next newline outside of any brackets, so an `Unparsable` node may
+    ///   cover only part of an expression that continues onto later lines.
+    /// * If every expression parses on its own, the whole input becomes a
+    ///   single `Unparsable` node.
+    /// * Nothing is recovered from in a program built with
+    ///   `panic = "abort"`, which aborts on the first failure.
+    /// * The panic hook still runs before the panic is caught, so the
+    ///   default hook prints the panic message to stderr. Use
+    ///   [`std::panic::set_hook()`] to silence it.
+    Recover,
+}
+
+/// When a newline outside of any brackets ends a top-level expression.
+///
+/// A top-level newline never ends an expression that is incomplete because
+/// of an open bracket or an operator that is missing its operand, like
+/// `f[a,` or `a +`. The modes differ in how they treat the operators whose
+/// last operand is optional, `;` and `;;`. See
+/// [`ParseOptions::toplevel_newlines()`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ToplevelNewlineMode {
+    /// End the expression at a top-level newline if it is complete.
+    ///
+    /// A trailing `;` or `;;` has an implicit operand, so `a;` followed by a
+    /// newline is the complete expression `CompoundExpression[a, Null]`.
+    /// This is how files are read.
+    #[default]
+    EndWhenComplete,
+
+    /// End the expression at a top-level newline if it is complete and no
+    /// operator is pending.
+    ///
+    /// A trailing `;` or `;;` is pending, so `a;` followed by a newline and
+    /// `b` is `CompoundExpression[a, b]`. If only trivia follows, the
+    /// operator gets its implicit operand, like in
+    /// [`EndWhenComplete`][Self::EndWhenComplete]. This matches front ends
+    /// that read input one line at a time, and ask for another line while an
+    /// operator is pending.
+    ContinueIfOperatorPending,
+}
+
 /// Different encoding modes
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum EncodingMode {
@@ -376,36 +588,160 @@ pub struct ParseResult<T> {
     #[doc(hidden)]
     pub non_fatal_issues: Vec<Issue>,
 
+    /// Directives found in comments, in source order.
+    ///
+    /// See the [`directive`] module.
+    pub directives: Vec<Directive>,
+
     #[doc(hidden)]
     pub tracked: TrackedSourceLocations,
 }
 
+/// The part of an input that has not been parsed yet.
+///
+/// Returned by [`parse_cst_first()`], to parse the top-level expressions in
+/// an input one at a time.
+#[derive(Debug, Copy, Clone)]
+pub struct RemainingInput<'i> {
+    input: &'i [u8],
+    mark: InputMark,
+}
+
 //-------------
 // ParseOptions
 //-------------
 
+#[derive(Clone)]
 pub struct ParseOptions {
     first_line_behavior: FirstLineBehavior,
     src_convention: SourceConvention,
     encoding_mode: EncodingMode,
     tab_width: u32,
+    tab_width_auto: bool,
     check_issues: bool,
     compute_oob: bool,
+    implicit_times: bool,
+    toplevel_newlines: ToplevelNewlineMode,
+    grammar_profile: GrammarProfile,
+    denied_issues: Vec<IssueTag>,
+    max_line_length: Option<u32>,
+    check_indentation: bool,
+    reparse: Option<ReparseHeuristics>,
+    recovery: RecoveryMode,
+    deduplicate_issues: bool,
+    notations: Option<Arc<Notations>>,
+    debug_sink: Option<Arc<DebugSink>>,
     pub quirk_settings: QuirkSettings,
 }
 
+/// Receives a snapshot of the result of each [`DebugPhase`] of parsing.
+///
+/// See [`ParseOptions::debug_sink()`].
+pub type DebugSink = dyn Fn(DebugPhase, &dyn Debug) + Send + Sync;
+
+/// A phase of parsing whose result is reported to a [`DebugSink`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DebugPhase {
+    /// The tokens produced by [`tokenize()`] or [`tokenize_bytes()`].
+    Tokens,
+    /// The concrete syntax trees produced by the parser.
+    Cst,
+    /// The concrete syntax trees after aggregation, which removes trivia.
+    Aggregate,
+    /// The abstract syntax trees.
+    Ast,
+}
+
+/// Set by [`ParseOptions::set_global_default()`].
+static GLOBAL_DEFAULT: OnceLock<ParseOptions> = OnceLock::new();
+
+/// Returns the [global default][ParseOptions::set_global_default()] options,
+/// or the [built-in defaults][ParseOptions::builtin_default()] if none have
+/// been set.
 impl Default for ParseOptions {
     fn default() -> ParseOptions {
+        match GLOBAL_DEFAULT.get() {
+            Some(opts) => opts.clone(),
+            None => ParseOptions::builtin_default(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// The options used by [`ParseOptions::default()`] if no
+    /// [global default][Self::set_global_default()] has been set.
+    pub fn builtin_default() -> ParseOptions {
         ParseOptions {
             first_line_behavior: FirstLineBehavior::NotScript,
             src_convention: SourceConvention::LineColumn,
             encoding_mode: EncodingMode::Normal,
             tab_width: DEFAULT_TAB_WIDTH,
+            tab_width_auto: false,
             check_issues: true,
             compute_oob: true,
-            quirk_settings: QuirkSettings::default(),
+            implicit_times: true,
+            toplevel_newlines: ToplevelNewlineMode::EndWhenComplete,
+            grammar_profile: GrammarProfile::Full,
+            denied_issues: Vec::new(),
+            max_line_length: None,
+            check_indentation: false,
+            reparse: Some(ReparseHeuristics::default()),
+            recovery: RecoveryMode::Panic,
+            deduplicate_issues: true,
+            notations: None,
+            debug_sink: None,
+            quirk_settings: QuirkSettings::const_default(),
         }
     }
+
+    /// Set the options returned by [`ParseOptions::default()`] for the rest
+    /// of the process.
+    ///
+    /// This is intended to be called once at startup by applications that
+    /// parse from many places, so that options like
+    /// [`quirk_settings`][Self::quirk_settings] don't have to be passed
+    /// through every layer. [`QuirkSettings::default()`] also returns the
+    /// quirk settings of the global default.
+    ///
+    /// The global default only affects options obtained from
+    /// [`ParseOptions::default()`] (or [`QuirkSettings::default()`]), whether
+    /// by the application or by a library it calls. Functions that take a
+    /// `&ParseOptions` argument use the options they are given, and parses
+    /// that this crate does internally, like tokenizing a fragment of input
+    /// to normalize whitespace or reading a literal with
+    /// [`parse_literal()`][crate::data::parse_literal], always use the
+    /// [built-in defaults][Self::builtin_default()].
+    ///
+    /// The global default can only be set once. If it has already been set,
+    /// `opts` is returned as an error.
+    ///
+    /// ```
+    /// use wolfram_parser::{ParseOptions, QuirkSettings};
+    ///
+    /// let quirks = QuirkSettings::default().flatten_times(true);
+    ///
+    /// let mut opts = ParseOptions::default().tab_width(8);
+    /// opts.quirk_settings = quirks;
+    ///
+    /// assert!(ParseOptions::set_global_default(opts).is_ok());
+    ///
+    /// assert_eq!(ParseOptions::default().quirk_settings, quirks);
+    /// assert_eq!(QuirkSettings::default(), quirks);
+    ///
+    /// // The global default can't be changed once it is set.
+    /// assert!(ParseOptions::set_global_default(ParseOptions::default())
+    ///     .is_err());
+    /// ```
+    pub fn set_global_default(opts: ParseOptions) -> Result<(), ParseOptions> {
+        GLOBAL_DEFAULT.set(opts)
+    }
+
+    /// The options set by [`set_global_default()`][Self::set_global_default],
+    /// if any.
+    pub(crate) fn global_default() -> Option<&'static ParseOptions> {
+        GLOBAL_DEFAULT.get()
+    }
 }
 
 impl ParseOptions {
@@ -423,8 +759,20 @@ impl ParseOptions {
             src_convention,
             encoding_mode,
             tab_width,
+            tab_width_auto: false,
             check_issues: true,
             compute_oob: true,
+            implicit_times: true,
+            toplevel_newlines: ToplevelNewlineMode::EndWhenComplete,
+            grammar_profile: GrammarProfile::Full,
+            denied_issues: Vec::new(),
+            max_line_length: None,
+            check_indentation: false,
+            reparse: Some(ReparseHeuristics::default()),
+            recovery: RecoveryMode::Panic,
+            deduplicate_issues: true,
+            notations: None,
+            debug_sink: None,
             quirk_settings,
         }
     }
@@ -433,6 +781,50 @@ impl ParseOptions {
         ParseOptions { tab_width, ..self }
     }
 
+    /// Use the tab width declared by the input, if it declares one.
+    ///
+    /// The tab width is declared by a `tab-width` setting directive on the
+    /// first line of the input, or on the second line if the first line is a
+    /// `#!` script line. The [EditorConfig](https://editorconfig.org) spelling
+    /// `tab_width` is also accepted. If the input does not declare a tab width,
+    /// [`tab_width()`][Self::tab_width] is used.
+    ///
+    /// See [`directive::detect_tab_width()`].
+    ///
+    /// ```
+    /// use wolfram_parser::{macros::src, tokenize, ParseOptions};
+    ///
+    /// let input = "(* wolfram-parse: tab-width=8 *)\n\tx";
+    ///
+    /// let tokens = tokenize(input, &ParseOptions::default().tab_width_auto());
+    ///
+    /// assert_eq!(tokens.0.last().unwrap().src, src!(2:9-2:10).into());
+    /// ```
+    pub fn tab_width_auto(self) -> Self {
+        ParseOptions {
+            tab_width_auto: true,
+            ..self
+        }
+    }
+
+    /// The tab width used to compute source locations in `input`.
+    pub(crate) fn tab_width_for(&self, input: &[u8]) -> u32 {
+        if !self.tab_width_auto {
+            return self.tab_width;
+        }
+
+        // The directive must be on one of the first two lines.
+        let head = input
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'\n')
+            .nth(1)
+            .map_or(input, |(index, _)| &input[..index]);
+
+        directive::detect_tab_width(&String::from_utf8_lossy(head))
+            .unwrap_or(self.tab_width)
+    }
+
     pub fn source_convention(self, src_convention: SourceConvention) -> Self {
         ParseOptions {
             src_convention,
@@ -440,6 +832,297 @@ impl ParseOptions {
         }
     }
 
+    /// Enable or disable implicit multiplication.
+    ///
+    /// Implicit multiplication is enabled by default, so `a b` is parsed as
+    /// `Times[a, b]`.
+    ///
+    /// If disabled, `a b` is still parsed as `Times[a, b]`, but each implicit
+    /// multiplication is reported as a fatal
+    /// [`UnexpectedImplicitTimes`][crate::issue::IssueTag::UnexpectedImplicitTimes]
+    /// issue. This is useful for teaching environments and configuration
+    /// files, where adjacent expressions are more likely to be a mistake (e.g.
+    /// a missing comma) than a product.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_ast, ParseOptions};
+    ///
+    /// let opts = ParseOptions::default().implicit_times(false);
+    ///
+    /// assert!(parse_ast("a * b", &opts).is_ok());
+    /// assert!(!parse_ast("a b", &opts).is_ok());
+    /// ```
+    pub fn implicit_times(self, enabled: bool) -> Self {
+        ParseOptions {
+            implicit_times: enabled,
+            ..self
+        }
+    }
+
+    /// Set when a top-level newline ends an expression.
+    ///
+    /// By default, a top-level newline ends an expression if it is complete.
+    /// Interactive front ends can use
+    /// [`ToplevelNewlineMode::ContinueIfOperatorPending`] to also continue an
+    /// expression after a trailing `;` or `;;`, like a kernel prompt does:
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_ast_seq, ParseOptions, ToplevelNewlineMode};
+    ///
+    /// let input = "x = 1;\nx + 1";
+    ///
+    /// let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+    ///
+    /// assert_eq!(ast.0.len(), 2);
+    ///
+    /// let opts = ParseOptions::default()
+    ///     .toplevel_newlines(ToplevelNewlineMode::ContinueIfOperatorPending);
+    ///
+    /// let ast = parse_ast_seq(input, &opts).syntax;
+    ///
+    /// assert_eq!(ast.0.len(), 1);
+    /// ```
+    pub fn toplevel_newlines(self, mode: ToplevelNewlineMode) -> Self {
+        ParseOptions {
+            toplevel_newlines: mode,
+            ..self
+        }
+    }
+
+    /// Restrict the syntax that is accepted to the subset allowed by
+    /// `grammar_profile`.
+    ///
+    /// See [`GrammarProfile`].
+    pub fn grammar_profile(self, grammar_profile: GrammarProfile) -> Self {
+        ParseOptions {
+            grammar_profile,
+            ..self
+        }
+    }
+
+    /// Report issues with any of the tags in `tags` as fatal.
+    ///
+    /// This is useful for enforcing stricter rules than the parser does by
+    /// default, without having to filter the issues of every parse result.
+    /// Calling this more than once denies the tags from every call.
+    ///
+    /// ```
+    /// use wolfram_parser::{issue::IssueTag, parse_ast, ParseOptions};
+    ///
+    /// // `1.2.3` parses as `1.2 * .3`, with a non-fatal issue.
+    /// assert!(parse_ast("1.2.3", &ParseOptions::default()).is_ok());
+    ///
+    /// let opts = ParseOptions::default()
+    ///     .deny(&[IssueTag::UnexpectedImplicitTimes]);
+    ///
+    /// assert!(!parse_ast("1.2.3", &opts).is_ok());
+    /// ```
+    pub fn deny(self, tags: &[IssueTag]) -> Self {
+        let mut denied_issues = self.denied_issues;
+
+        for tag in tags {
+            if !denied_issues.contains(tag) {
+                denied_issues.push(*tag);
+            }
+        }
+
+        ParseOptions {
+            denied_issues,
+            ..self
+        }
+    }
+
+    /// Report a [`LineTooLong`][crate::issue::IssueTag::LineTooLong] issue
+    /// for every line longer than `max_line_length` columns.
+    ///
+    /// Line length is measured in the same columns as source spans, so tabs
+    /// advance to the next multiple of [`tab_width()`][Self::tab_width].
+    /// This check is only performed when using
+    /// [`SourceConvention::LineColumn`].
+    ///
+    /// ```
+    /// use wolfram_parser::{issue::IssueTag, parse_cst_seq, ParseOptions};
+    ///
+    /// let opts = ParseOptions::default().max_line_length(Some(10));
+    ///
+    /// let result = parse_cst_seq("f[x]\ng[xxxxxxxxxx]", &opts);
+    ///
+    /// let tags: Vec<IssueTag> =
+    ///     result.issues().map(|issue| issue.tag).collect();
+    ///
+    /// assert_eq!(tags, [IssueTag::LineTooLong]);
+    /// ```
+    pub fn max_line_length(self, max_line_length: Option<u32>) -> Self {
+        ParseOptions {
+            max_line_length,
+            ..self
+        }
+    }
+
+    /// Report a
+    /// [`MixedIndentation`][crate::issue::IssueTag::MixedIndentation] issue
+    /// for every line whose indentation mixes tabs and spaces, or uses a
+    /// different character than the first indented line of the input.
+    ///
+    /// Lines containing only whitespace are ignored.
+    pub fn check_mixed_indentation(self, enabled: bool) -> Self {
+        ParseOptions {
+            check_indentation: enabled,
+            ..self
+        }
+    }
+
+    /// Set the heuristics used to give unterminated tokens and groups a useful
+    /// source, or disable reparsing them with `None`.
+    ///
+    /// Without reparsing, unterminated tokens and groups extend to the end of
+    /// the input. See [`ReparseHeuristics`].
+    pub fn reparse_unterminated(
+        self,
+        reparse: Option<ReparseHeuristics>,
+    ) -> Self {
+        ParseOptions { reparse, ..self }
+    }
+
+    /// Set how the parser handles input that it fails to parse.
+    ///
+    /// With [`RecoveryMode::Recover`], [`parse_cst()`], [`parse_ast()`], and
+    /// the other syntax parsing functions return a syntax tree for every
+    /// input, and never panic.
+    ///
+    /// Input that does not contain exactly one expression is also returned as
+    /// an [`Unparsable`][crate::parse::SyntaxErrorKind::Unparsable] node,
+    /// instead of panicking:
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     cst::{Cst, SyntaxErrorNode},
+    ///     issue::IssueTag,
+    ///     parse::SyntaxErrorKind,
+    ///     parse_cst, ParseOptions, RecoveryMode,
+    /// };
+    ///
+    /// let opts = ParseOptions::default().recovery(RecoveryMode::Recover);
+    ///
+    /// let result = parse_cst("", &opts);
+    ///
+    /// assert!(matches!(
+    ///     result.syntax,
+    ///     Cst::SyntaxError(SyntaxErrorNode {
+    ///         err: SyntaxErrorKind::Unparsable,
+    ///         ..
+    ///     })
+    /// ));
+    ///
+    /// let tags: Vec<IssueTag> =
+    ///     result.issues().map(|issue| issue.tag).collect();
+    ///
+    /// assert_eq!(tags, [IssueTag::ParserFailure]);
+    /// ```
+    pub fn recovery(self, recovery: RecoveryMode) -> Self {
+        ParseOptions { recovery, ..self }
+    }
+
+    /// Set whether [`ParseResult::diagnostics()`] collapses issues that
+    /// describe the same problem into a single issue.
+    ///
+    /// A single problem, like a missing closer, can be reported by the
+    /// tokenizer, by the parser, and by the abstraction of the parsed syntax.
+    /// When enabled, which is the default, these are collapsed into one
+    /// primary issue, with the others attached to it as
+    /// [`Note`][crate::issue::AnnotationKind::Note] annotations. See
+    /// [`issue::deduplicate()`].
+    pub fn deduplicate_issues(self, enabled: bool) -> Self {
+        ParseOptions {
+            deduplicate_issues: enabled,
+            ..self
+        }
+    }
+
+    /// Parse using the user-defined operator notations in `notations`.
+    ///
+    /// This API is experimental. See the [`notation`] module.
+    pub fn notations(self, notations: Notations) -> Self {
+        let notations = if notations.is_empty() {
+            None
+        } else {
+            Some(Arc::new(notations))
+        };
+
+        ParseOptions { notations, ..self }
+    }
+
+    /// Call `sink` with a snapshot of the result of each phase of parsing.
+    ///
+    /// This is intended for debugging the parser: the snapshots can be
+    /// logged, written to files, or compared between runs. Each snapshot is
+    /// only formatted if `sink` formats it.
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use wolfram_parser::{parse_ast, DebugPhase, ParseOptions};
+    ///
+    /// let phases = Arc::new(Mutex::new(Vec::new()));
+    ///
+    /// let opts = ParseOptions::default().debug_sink({
+    ///     let phases = Arc::clone(&phases);
+    ///     move |phase, snapshot| {
+    ///         phases.lock().unwrap().push((phase, format!("{snapshot:#?}")))
+    ///     }
+    /// });
+    ///
+    /// parse_ast("f[x]", &opts);
+    ///
+    /// let phases: Vec<DebugPhase> =
+    ///     phases.lock().unwrap().iter().map(|(phase, _)| *phase).collect();
+    ///
+    /// assert_eq!(
+    ///     phases,
+    ///     [DebugPhase::Cst, DebugPhase::Aggregate, DebugPhase::Ast]
+    /// );
+    /// ```
+    pub fn debug_sink<F>(self, sink: F) -> Self
+    where
+        F: Fn(DebugPhase, &dyn Debug) + Send + Sync + 'static,
+    {
+        ParseOptions {
+            debug_sink: Some(Arc::new(sink)),
+            ..self
+        }
+    }
+
+    /// Report `snapshot` to the [`debug_sink()`][Self::debug_sink], if any.
+    pub(crate) fn debug_snapshot(
+        &self,
+        phase: DebugPhase,
+        snapshot: &dyn Debug,
+    ) {
+        if let Some(sink) = &self.debug_sink {
+            sink(phase, snapshot)
+        }
+    }
+
+    /// The options to use for parsing the file at `path`.
+    ///
+    /// See [`parse_file_cst()`].
+    fn for_file(&self, path: &std::path::Path) -> ParseOptions {
+        let first_line_behavior = match self.first_line_behavior {
+            FirstLineBehavior::NotScript => {
+                match path.extension().and_then(|ext| ext.to_str()) {
+                    Some("wls") => FirstLineBehavior::Script,
+                    _ => FirstLineBehavior::Check,
+                }
+            },
+            behavior => behavior,
+        };
+
+        ParseOptions {
+            first_line_behavior,
+            ..self.clone()
+        }
+    }
+
     #[doc(hidden)]
     pub fn first_line_behavior(
         self,
@@ -487,10 +1170,15 @@ pub fn tokenize<'i>(
 }
 
 /// Parse bytes containing Wolfram Language input into a sequence of tokens.
+///
+/// # Errors
+///
+/// Returns [`ParseError::UnsafeCharacterEncoding`] if `input` contains
+/// invalid UTF-8, an encoded surrogate, or a byte order mark.
 pub fn tokenize_bytes<'i>(
     input: &'i [u8],
     opts: &ParseOptions,
-) -> Result<NodeSeq<Token<TokenStr<'i>>>, UnsafeCharacterEncoding> {
+) -> Result<NodeSeq<Token<TokenStr<'i>>>, ParseError> {
     let mut tokenizer = Tokenizer::new(input, opts);
 
     let mut tokens = Vec::new();
@@ -512,101 +1200,476 @@ pub fn tokenize_bytes<'i>(
     } // while (true)
 
     if let Some(flag) = tokenizer.unsafe_character_encoding_flag {
-        return Err(flag);
+        return Err(flag.into());
     }
 
-    if let Ok(input) = std::str::from_utf8(tokenizer.input) {
+    if let (Ok(input), Some(heuristics)) =
+        (std::str::from_utf8(tokenizer.input), opts.reparse)
+    {
         NodeSeq(tokens) = crate::error::reparse_unterminated_tokens(
             NodeSeq(tokens),
             input,
-            crate::safe_convert!(tokenizer.tab_width, usize, "tab_width conversion"),
+            usize::try_from(tokenizer.tab_width).unwrap(),
+            heuristics,
         );
     }
 
+    opts.debug_snapshot(DebugPhase::Tokens, &tokens);
+
     return Ok(NodeSeq(tokens));
 }
 
-//======================================
-// Parse CST
-//======================================
-
+//======================================
+// Parse CST
+//======================================
+
+//--------------------------------------
+// Scalar Cst
+//--------------------------------------
+
+/// Parse a string containing Wolfram Language input into a concrete syntax tree.
+///
+/// # Examples
+///
+/// Parse `2 + 2`:
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use wolfram_parser::{
+///     parse_cst, ParseOptions, NodeSeq,
+///     cst::{Cst, InfixNode, OperatorNode},
+///     parse::operators::InfixOperator,
+///     macros::{token, src},
+/// };
+///
+/// let result = parse_cst("2 + 2", &ParseOptions::default());
+///
+/// assert_eq!(
+///     result.syntax,
+///     Cst::Infix(InfixNode(OperatorNode {
+///         op: InfixOperator::Plus,
+///         children: NodeSeq(vec![
+///             Cst::Token(token!(Integer, "2", 1:1-2)),
+///             Cst::Token(token!(Whitespace, " ", 1:2-3)),
+///             Cst::Token(token!(Plus, "+", 1:3-4)),
+///             Cst::Token(token!(Whitespace, " ", 1:4-5)),
+///             Cst::Token(token!(Integer, "2", 1:5-6))
+///         ]),
+///     }))
+/// );
+/// ```
+pub fn parse_cst<'i>(
+    input: &'i str,
+    opts: &ParseOptions,
+) -> ParseResult<Cst<TokenStr<'i>>> {
+    let result = parse_cst_seq(input, opts);
+
+    match opts.recovery {
+        RecoveryMode::Panic => expect_single_item(result, "parse_cst", "Cst"),
+        RecoveryMode::Recover => {
+            recover_single_item(input.as_bytes(), result, opts)
+        },
+    }
+}
+
+/// Parse bytes containing Wolfram Language input into a concrete syntax tree.
+pub fn parse_bytes_cst<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+) -> ParseResult<Cst<TokenStr<'i>>> {
+    let result = parse_bytes_cst_seq(input, opts);
+
+    match opts.recovery {
+        RecoveryMode::Panic => {
+            expect_single_item(result, "parse_bytes_cst", "Cst")
+        },
+        RecoveryMode::Recover => recover_single_item(input, result, opts),
+    }
+}
+
+/// Parse the first top-level expression in a string containing Wolfram
+/// Language input into a concrete syntax tree.
+///
+/// Unlike [`parse_cst()`], the input may contain any number of expressions.
+/// The returned [`RemainingInput`] can be used to parse the expressions after
+/// the first one, one at a time. This is useful for REPL-style consumers that
+/// evaluate each expression before parsing the next.
+///
+/// Top-level trivia before the expression, like whitespace, newlines, and
+/// comments, is skipped. If there are no more expressions in the input,
+/// [`syntax`][ParseResult::syntax] is `None`.
+///
+/// Source locations are relative to the start of the complete input, and the
+/// issues in each [`ParseResult`] are those found while parsing that
+/// expression.
+///
+/// ```
+/// use wolfram_parser::{
+///     macros::src, parse_cst_first, source::Span, ParseOptions,
+/// };
+///
+/// let opts = ParseOptions::default();
+///
+/// let (first, rest) = parse_cst_first("a + b\n\nf[x] (* done *)\n", &opts);
+///
+/// assert_eq!(first.syntax.unwrap().get_source(), Span::from(src!(1:1-6)));
+///
+/// let (second, rest) = rest.parse_cst_next(&opts);
+///
+/// assert_eq!(second.syntax.unwrap().get_source(), Span::from(src!(3:1-5)));
+///
+/// let (end, rest) = rest.parse_cst_next(&opts);
+///
+/// assert!(end.syntax.is_none());
+/// assert!(rest.is_empty());
+/// ```
+pub fn parse_cst_first<'i>(
+    input: &'i str,
+    opts: &ParseOptions,
+) -> (ParseResult<Option<Cst<TokenStr<'i>>>>, RemainingInput<'i>) {
+    parse_bytes_cst_first(input.as_bytes(), opts)
+}
+
+/// Parse the first top-level expression in bytes containing Wolfram Language
+/// input into a concrete syntax tree.
+///
+/// See [`parse_cst_first()`].
+pub fn parse_bytes_cst_first<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+) -> (ParseResult<Option<Cst<TokenStr<'i>>>>, RemainingInput<'i>) {
+    let (result, mark) = parse::parse_cst_first(input, None, opts);
+
+    (result, RemainingInput { input, mark })
+}
+
+impl<'i> RemainingInput<'i> {
+    /// Parse the next top-level expression, resuming where the previous call
+    /// to [`parse_cst_first()`] or [`parse_cst_next()`][Self::parse_cst_next]
+    /// left off.
+    ///
+    /// `opts` should be the same options used to parse the previous
+    /// expressions.
+    pub fn parse_cst_next(
+        self,
+        opts: &ParseOptions,
+    ) -> (ParseResult<Option<Cst<TokenStr<'i>>>>, RemainingInput<'i>) {
+        let RemainingInput { input, mark } = self;
+
+        let (result, mark) = parse::parse_cst_first(input, Some(mark), opts);
+
+        (result, RemainingInput { input, mark })
+    }
+
+    /// The input that has not been parsed yet.
+    pub fn as_bytes(&self) -> &'i [u8] {
+        &self.input[self.mark.offset()..]
+    }
+
+    /// Returns true if all of the input has been parsed.
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+
+    /// The location of the start of the input that has not been parsed yet.
+    pub fn location(&self) -> Location {
+        self.mark.src_loc
+    }
+}
+
+/// Get an iterator that parses the top-level expressions in a string
+/// containing Wolfram Language input one at a time.
+///
+/// Each expression is parsed only when the iterator is advanced, so a
+/// consumer can start evaluating the first expression of a large file before
+/// the rest of it is parsed. Each item is the result of
+/// [`parse_cst_first()`] or [`RemainingInput::parse_cst_next()`], and the
+/// iterator ends when only top-level trivia is left.
+///
+/// ```
+/// use wolfram_parser::{parse_cst_expr_iter, ParseOptions};
+///
+/// let input = "x = 1\ny = x + 1;\nPrint[y] (* 2 *)\n";
+///
+/// let exprs: Vec<String> =
+///     parse_cst_expr_iter(input, &ParseOptions::default())
+///         .map(|result| result.syntax.to_source())
+///         .collect();
+///
+/// assert_eq!(exprs, ["x = 1", "y = x + 1;", "Print[y]"]);
+/// ```
+pub fn parse_cst_expr_iter<'i>(
+    input: &'i str,
+    opts: &ParseOptions,
+) -> iter::CstExprs<'i> {
+    iter::CstExprs::new(input.as_bytes(), opts)
+}
+
+/// Parse a string containing Wolfram Language input into a concrete syntax
+/// tree, returning only the tree and any issues.
+///
+/// This is equivalent to taking the syntax and the
+/// [`issues()`][ParseResult::issues] of the result of [`parse_cst()`], for
+/// callers that don't need the other [`ParseResult`] fields. Fatal issues come
+/// before non-fatal issues in the returned list.
+///
+/// ```
+/// use wolfram_parser::{parse_cst_simple, ParseOptions};
+///
+/// let input = "1+\\[Alpha+2";
+///
+/// let (_cst, issues) = parse_cst_simple(input, &ParseOptions::default());
+///
+/// assert!(!issues.is_empty());
+/// ```
+pub fn parse_cst_simple<'i>(
+    input: &'i str,
+    opts: &ParseOptions,
+) -> (Cst<TokenStr<'i>>, Vec<Issue>) {
+    let (NodeSeq(syntax), issues) =
+        parse::parse_cst_seq_simple(input.as_bytes(), opts);
+
+    if opts.recovery == RecoveryMode::Recover {
+        let (cst, issue) = parse::recovery::single_cst(
+            input.as_bytes(),
+            opts,
+            NodeSeq(syntax),
+        );
+
+        return (cst, issue.into_iter().chain(issues).collect());
+    }
+
+    (expect_single(syntax, "parse_cst_simple", "Cst"), issues)
+}
+
+//--------------------------------------
+// Sequence of Cst
+//--------------------------------------
+
+pub fn parse_cst_seq<'i>(
+    input: &'i str,
+    opts: &ParseOptions,
+) -> ParseResult<CstSeq<TokenStr<'i>>> {
+    parse_bytes_cst_seq(input.as_bytes(), opts)
+}
+
+pub fn parse_bytes_cst_seq<'i>(
+    bytes: &'i [u8],
+    opts: &ParseOptions,
+) -> ParseResult<CstSeq<TokenStr<'i>>> {
+    parse::parse_cst_seq(bytes, opts)
+}
+
+//--------------------------------------
+// Arena-allocated Cst
+//--------------------------------------
+
+/// Parse a string containing Wolfram Language input into a concrete syntax
+/// tree allocated in `bump`.
+///
+/// This is the arena-allocated equivalent of [`parse_cst()`]. Instead of
+/// allocating each node separately, the children of every node are allocated
+/// in `bump`, so the whole tree is freed at once when `bump` is dropped or
+/// reset. See [`cst::arena`].
+///
+/// Only available with the `bumpalo` feature enabled.
+#[cfg(feature = "bumpalo")]
+pub fn parse_cst_in<'a>(
+    input: &'a str,
+    opts: &ParseOptions,
+    bump: &'a bumpalo::Bump,
+) -> ParseResult<cst::arena::ArenaCst<'a>> {
+    let result = parse_cst_seq_in(input, opts, bump);
+
+    match opts.recovery {
+        RecoveryMode::Panic => result.map(|nodes| match nodes {
+            [node] => *node,
+            _ => panic!(
+                "parse_cst_in: expected one Cst in input, found {}: {nodes:?}",
+                nodes.len()
+            ),
+        }),
+        RecoveryMode::Recover => {
+            let mut issue = None;
+
+            let mut result = result.map(|nodes| {
+                let (node, fatal) = parse::recovery::single_cst_in(
+                    input.as_bytes(),
+                    opts,
+                    bump,
+                    nodes,
+                );
+
+                issue = fatal;
+
+                node
+            });
+
+            result.fatal_issues.extend(issue);
+
+            result
+        },
+    }
+}
+
+/// Parse a string containing Wolfram Language input into a sequence of
+/// concrete syntax trees allocated in `bump`.
+///
+/// This is the arena-allocated equivalent of [`parse_cst_seq()`], and
+/// reports the same issues. See [`cst::arena`].
+///
+/// ```
+/// use bumpalo::Bump;
+/// use wolfram_parser::{parse_cst_seq, parse_cst_seq_in, ParseOptions};
+///
+/// let input = "f[x_] := x + 1\ng[{1,,2}]";
+/// let opts = ParseOptions::default();
+///
+/// let mut bump = Bump::new();
+///
+/// let result = parse_cst_seq_in(input, &opts, &bump);
+///
+/// let expected = parse_cst_seq(input, &opts);
+///
+/// assert_eq!(result.syntax.len(), expected.syntax.len());
+/// assert!(result.issues().eq(expected.issues()));
+///
+/// // Free every node at once, keeping the memory for the next parse.
+/// bump.reset();
+/// ```
+///
+/// Only available with the `bumpalo` feature enabled.
+#[cfg(feature = "bumpalo")]
+pub fn parse_cst_seq_in<'a>(
+    input: &'a str,
+    opts: &ParseOptions,
+    bump: &'a bumpalo::Bump,
+) -> ParseResult<&'a [cst::arena::ArenaCst<'a>]> {
+    parse::parse_cst_seq_in(input.as_bytes(), opts, bump)
+}
+
 //--------------------------------------
-// Scalar Cst
+// Box input
 //--------------------------------------
 
-/// Parse a string containing Wolfram Language input into a concrete syntax tree.
+/// Parse a box expression, like the contents of a notebook cell, into
+/// concrete syntax trees.
 ///
-/// # Examples
+/// This is the equivalent of `CodeConcreteParseBox` in the CodeParser
+/// paclet. The strings in each `RowBox[{..}]` are parsed as Wolfram Language
+/// input using [`EncodingMode::Box`], and the sources of the resulting nodes
+/// are [`BoxPosition`]s within `boxes`. Boxes like `SuperscriptBox[..]` are
+/// returned as [`Cst::Box`] nodes, and arguments of a box that are not boxes,
+/// like options, as [`Cst::Code`] nodes.
 ///
-/// Parse `2 + 2`:
+/// The issues found while parsing are stored in the
+/// [`syntax_issues`][Metadata::syntax_issues] of the returned container.
 ///
 /// ```
-/// # use pretty_assertions::assert_eq;
+/// use wolfram_expr::{Expr, Symbol};
 /// use wolfram_parser::{
-///     parse_cst, ParseOptions, NodeSeq,
-///     cst::{Cst, InfixNode, OperatorNode},
-///     parse::operators::InfixOperator,
-///     macros::{token, src},
+///     cst::Cst, macros::src, parse_box_cst, source::Source, ContainerBody,
+///     ContainerKind, NodeSeq, ParseOptions,
 /// };
 ///
-/// let result = parse_cst("2 + 2", &ParseOptions::default());
+/// let call = |head: &str, args: Vec<Expr>| {
+///     Expr::normal(Symbol::new(&format!("System`{head}")), args)
+/// };
 ///
-/// assert_eq!(
-///     result.syntax,
-///     Cst::Infix(InfixNode(OperatorNode {
-///         op: InfixOperator::Plus,
-///         children: NodeSeq(vec![
-///             Cst::Token(token!(Integer, "2", 1:1-2)),
-///             Cst::Token(token!(Whitespace, " ", 1:2-3)),
-///             Cst::Token(token!(Plus, "+", 1:3-4)),
-///             Cst::Token(token!(Whitespace, " ", 1:4-5)),
-///             Cst::Token(token!(Integer, "2", 1:5-6))
-///         ]),
-///     }))
-/// );
+/// // a + x^2
+/// let boxes = call("RowBox", vec![call("List", vec![
+///     Expr::string("a"),
+///     Expr::string("+"),
+///     call("SuperscriptBox", vec![Expr::string("x"), Expr::string("2")]),
+/// ])]);
+///
+/// let container = parse_box_cst(&boxes, &ParseOptions::default());
+///
+/// assert_eq!(container.kind, ContainerKind::Box);
+///
+/// let ContainerBody::Nodes(NodeSeq(nodes)) = container.body else {
+///     panic!()
+/// };
+///
+/// let [Cst::Infix(plus)] = nodes.as_slice() else { panic!() };
+///
+/// let NodeSeq(children) = &plus.0.children;
+///
+/// assert_eq!(children[0].get_source(), Source::from(src!({1, 1})));
+/// assert!(matches!(children[2], Cst::Box(_)));
 /// ```
-pub fn parse_cst<'i>(
-    input: &'i str,
+pub fn parse_box_cst(
+    boxes: &Expr,
     opts: &ParseOptions,
-) -> ParseResult<Cst<TokenStr<'i>>> {
-    let result = parse_cst_seq(input, opts);
-
-    let result = expect_single_item(result, "parse_cst", "Cst");
-
-    #[cfg(test)]
-    {
-        crate::utils::copy_to_clipboard(&format!("{:#?}", result.syntax));
+) -> Container<Cst<TokenString, Source>> {
+    let (nodes, issues) = parse::boxes::parse_box_cst_seq(boxes, opts);
+
+    Container {
+        kind: ContainerKind::Box,
+        body: ContainerBody::Nodes(nodes),
+        metadata: Metadata {
+            source: Source::Box(BoxPosition::At(vec![])),
+            syntax_issues: (!issues.is_empty()).then_some(issues),
+            confidence_level: None,
+            code_actions: None,
+            annotations: None,
+            file_name: None,
+            embedded_tabs: None,
+            embedded_newlines: None,
+            simple_line_continuations: None,
+            complex_line_continuations: None,
+        },
     }
-
-    result
-}
-
-/// Parse bytes containing Wolfram Language input into a concrete syntax tree.
-pub fn parse_bytes_cst<'i>(
-    input: &'i [u8],
-    opts: &ParseOptions,
-) -> ParseResult<Cst<TokenStr<'i>>> {
-    let result = parse_bytes_cst_seq(input, opts);
-
-    expect_single_item(result, "parse_bytes_cst", "Cst")
 }
 
 //--------------------------------------
-// Sequence of Cst
+// Pre-tokenized input
 //--------------------------------------
 
-pub fn parse_cst_seq<'i>(
-    input: &'i str,
+/// Parse a sequence of tokens previously produced by [`tokenize()`] into
+/// concrete syntax trees.
+///
+/// This is intended for use with token streams that have been stored or
+/// transferred using [`NodeSeq::to_bytes()`], or `NodeSeq::to_json()` with
+/// the `serde` feature enabled.
+///
+/// `opts` must be the same options the tokens were produced with. The tokens
+/// are parsed without lexing their input again, and the resulting syntax
+/// trees are the same as those produced by parsing the original source with
+/// [`parse_cst_seq()`].
+///
+/// See [`parse::parse_tokens()`] for details and the errors this can return.
+///
+/// # Examples
+///
+/// ```
+/// use wolfram_parser::{
+///     parse_cst_seq, parse_from_tokens, tokenize, NodeSeq, ParseOptions,
+///     tokenize::{Token, TokenString},
+/// };
+///
+/// let opts = ParseOptions::default();
+///
+/// let tokens: NodeSeq<Token<TokenString>> =
+///     tokenize("f[x] + 1", &opts).into_owned();
+///
+/// let bytes = tokens.to_bytes();
+///
+/// let tokens = NodeSeq::from_bytes(&bytes).unwrap();
+/// let result = parse_from_tokens(&tokens, &opts).unwrap();
+///
+/// assert_eq!(
+///     result.syntax,
+///     parse_cst_seq("f[x] + 1", &opts).syntax.into_owned()
+/// );
+/// ```
+pub fn parse_from_tokens(
+    tokens: &NodeSeq<Token<TokenString>>,
     opts: &ParseOptions,
-) -> ParseResult<CstSeq<TokenStr<'i>>> {
-    parse_bytes_cst_seq(input.as_bytes(), opts)
-}
+) -> Result<ParseResult<CstSeq<TokenString>>, TokenStreamError> {
+    let NodeSeq(tokens) = tokens;
 
-pub fn parse_bytes_cst_seq<'i>(
-    bytes: &'i [u8],
-    opts: &ParseOptions,
-) -> ParseResult<CstSeq<TokenStr<'i>>> {
-    parse::parse::<ParseCst>(bytes, opts)
+    parse::parse_tokens(tokens.as_slice(), opts)
 }
 
 //======================================
@@ -660,11 +1723,16 @@ pub fn parse_bytes_cst_seq<'i>(
 /// );
 /// ```
 pub fn parse_ast<'i>(input: &'i str, opts: &ParseOptions) -> ParseResult<Ast> {
-    expect_single_item(
-        parse_bytes_ast_seq(input.as_bytes(), opts),
-        "parse_ast",
-        "Ast",
-    )
+    match opts.recovery {
+        RecoveryMode::Panic => expect_single_item(
+            parse_bytes_ast_seq(input.as_bytes(), opts),
+            "parse_ast",
+            "Ast",
+        ),
+        RecoveryMode::Recover => {
+            recover_single_ast(input.as_bytes(), opts, "parse_ast")
+        },
+    }
 }
 
 /// Parse bytes containing Wolfram Language input into an abstract syntax tree.
@@ -672,11 +1740,34 @@ pub fn parse_bytes_ast<'i>(
     bytes: &'i [u8],
     opts: &ParseOptions,
 ) -> ParseResult<Ast> {
-    expect_single_item(
-        parse_bytes_ast_seq(bytes, opts),
-        "parse_bytes_ast",
-        "Ast",
-    )
+    match opts.recovery {
+        RecoveryMode::Panic => expect_single_item(
+            parse_bytes_ast_seq(bytes, opts),
+            "parse_bytes_ast",
+            "Ast",
+        ),
+        RecoveryMode::Recover => {
+            recover_single_ast(bytes, opts, "parse_bytes_ast")
+        },
+    }
+}
+
+/// Like [`recover_single_item()`], for the functions that parse a single
+/// [`Ast`].
+///
+/// The concrete syntax is aggregated before checking that there is exactly
+/// one node, so top-level trivia is not counted.
+fn recover_single_ast(
+    bytes: &[u8],
+    opts: &ParseOptions,
+    func: &'static str,
+) -> ParseResult<Ast> {
+    let result = parse::parse_cst_seq(bytes, opts)
+        .map(|NodeSeq(nodes)| NodeSeq(aggregate_cst_nodes(nodes, opts)));
+
+    recover_single_item(bytes, result, opts).map(|node| {
+        expect_single(abstract_cst_nodes(vec![node], opts), func, "Ast")
+    })
 }
 
 //--------------------------------------
@@ -694,73 +1785,252 @@ pub fn parse_bytes_ast_seq<'i>(
     bytes: &'i [u8],
     opts: &ParseOptions,
 ) -> ParseResult<NodeSeq<Ast>> {
-    let result = parse::parse::<ParseCst>(bytes, opts);
+    abstract_cst_seq_result(parse::parse_cst_seq(bytes, opts), opts)
+}
 
-    let ParseResult {
-        syntax: nodes,
-        unsafe_character_encoding,
-        fatal_issues,
-        non_fatal_issues,
-        tracked,
-    } = result;
+/// Aggregate and abstract the concrete syntax trees in `result`.
+pub(crate) fn abstract_cst_seq_result<I: TokenInput + Debug>(
+    result: ParseResult<CstSeq<I>>,
+    opts: &ParseOptions,
+) -> ParseResult<NodeSeq<Ast>> {
+    result.map(|NodeSeq(nodes)| {
+        let nodes = aggregate_cst_nodes(nodes, opts);
 
-    let NodeSeq(nodes) = aggregate_cst_seq(nodes);
+        NodeSeq(abstract_cst_nodes(nodes, opts))
+    })
+}
 
-    let nodes = nodes
-        .into_iter()
-        .map(|cst| abstract_cst(cst, opts.quirk_settings))
-        .collect();
+/// Aggregate `nodes`, reporting the result to the debug sink.
+fn aggregate_cst_nodes<I: TokenInput + Debug>(
+    nodes: Vec<Cst<I>>,
+    opts: &ParseOptions,
+) -> Vec<Cst<I>> {
+    let NodeSeq(nodes) = aggregate_cst_seq(NodeSeq(nodes));
 
-    ParseResult {
-        syntax: NodeSeq(nodes),
-        unsafe_character_encoding,
-        fatal_issues,
-        non_fatal_issues,
-        tracked,
-    }
+    opts.debug_snapshot(DebugPhase::Aggregate, &nodes);
+
+    nodes
+}
+
+/// Abstract the aggregated `nodes`, reporting the result to the debug sink.
+fn abstract_cst_nodes<I: TokenInput + Debug>(
+    nodes: Vec<Cst<I>>,
+    opts: &ParseOptions,
+) -> Vec<Ast> {
+    let nodes: Vec<Ast> =
+        notation::with_notations(opts.notations.as_ref(), || {
+            nodes
+                .into_iter()
+                .map(|cst| abstract_cst(cst, opts.quirk_settings))
+                .collect()
+        });
+
+    opts.debug_snapshot(DebugPhase::Ast, &nodes);
+
+    nodes
 }
 
 //==========================================================
 // File-level parsing with automatic paclet decoding
 //==========================================================
 
-/// Parse a file containing Wolfram Language input into a concrete syntax tree.
-/// 
-/// This function automatically detects and decodes paclet-encoded files before parsing.
+/// Parse a Wolfram Language source file into concrete syntax trees.
+///
+/// The contents of the file are prepared for parsing in the same way as by
+/// the front end:
+///
+/// * a UTF-8 byte order mark is removed, and UTF-16 input with a byte order
+///   mark is converted to UTF-8
+/// * paclet-encoded files are decoded
+/// * a `#!` shebang on the first line of a script is skipped
+///
+/// Unless `opts` already sets a [`FirstLineBehavior`] other than
+/// [`FirstLineBehavior::NotScript`], `.wls` files are parsed as scripts, and
+/// the first line of any other file is checked for a shebang.
+///
+/// The tokens of the returned trees own their input. Spans are relative to
+/// the decoded contents of the file. Use
+/// [`paclet::decode_paclet_with_source_map()`] to map spans in a decoded
+/// paclet back to the encoded file.
+///
+/// ```no_run
+/// use wolfram_parser::{parse_file_cst, ParseOptions};
+///
+/// let result = parse_file_cst("Kernel/MyPackage.wl", &ParseOptions::default())?;
+///
+/// println!("{} top-level expressions", result.syntax.len());
+/// # Ok::<(), wolfram_parser::ParseError>(())
+/// ```
 pub fn parse_file_cst(
-    file_path: &str,
+    path: impl AsRef<std::path::Path>,
     opts: &ParseOptions,
-) -> Result<ParseResult<CstSeq<TokenStr<'static>>>, Box<dyn std::error::Error>> {
-    let content = std::fs::read_to_string(file_path)?;
-    let final_content = crate::paclet::maybe_decode_paclet(&content)?;
-    let leaked_content = Box::leak(final_content.into_boxed_str());
-    Ok(parse_cst_seq(leaked_content, opts))
+) -> Result<ParseResult<CstSeq<TokenString>>, ParseError> {
+    let path = path.as_ref();
+    let bytes = read_source_file(path)?;
+
+    Ok(parse_bytes_cst_seq(&bytes, &opts.for_file(path))
+        .map(CstSeq::into_owned))
 }
 
-/// Parse a file containing Wolfram Language input into an abstract syntax tree.
-/// 
-/// This function automatically detects and decodes paclet-encoded files before parsing.
+/// Parse a Wolfram Language source file into abstract syntax trees.
+///
+/// See [`parse_file_cst()`].
 pub fn parse_file_ast(
-    file_path: &str,
+    path: impl AsRef<std::path::Path>,
     opts: &ParseOptions,
-) -> Result<ParseResult<NodeSeq<Ast>>, Box<dyn std::error::Error>> {
-    let content = std::fs::read_to_string(file_path)?;
-    let final_content = crate::paclet::maybe_decode_paclet(&content)?;
-    let leaked_content = Box::leak(final_content.into_boxed_str());
-    Ok(parse_ast_seq(leaked_content, opts))
+) -> Result<ParseResult<NodeSeq<Ast>>, ParseError> {
+    let path = path.as_ref();
+    let bytes = read_source_file(path)?;
+
+    Ok(parse_bytes_ast_seq(&bytes, &opts.for_file(path)))
 }
 
-/// Tokenize a file containing Wolfram Language input.
-/// 
-/// This function automatically detects and decodes paclet-encoded files before tokenizing.
+/// Tokenize a Wolfram Language source file.
+///
+/// See [`parse_file_cst()`].
 pub fn tokenize_file(
-    file_path: &str,
+    path: impl AsRef<std::path::Path>,
+    opts: &ParseOptions,
+) -> Result<NodeSeq<Token<TokenString>>, ParseError> {
+    let path = path.as_ref();
+    let bytes = read_source_file(path)?;
+
+    Ok(tokenize_bytes(&bytes, &opts.for_file(path))?.into_owned())
+}
+
+/// Read the file at `path`, and decode its contents for parsing.
+///
+/// See [`parse_file_cst()`].
+pub(crate) fn read_source_file(
+    path: &std::path::Path,
+) -> Result<Vec<u8>, ParseError> {
+    let bytes = std::fs::read(path)?;
+
+    let bytes = match bytes.as_slice() {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => rest.to_vec(),
+        [0xFF, 0xFE, rest @ ..] => {
+            decode_utf16(rest, u16::from_le_bytes)?.into_bytes()
+        },
+        [0xFE, 0xFF, rest @ ..] => {
+            decode_utf16(rest, u16::from_be_bytes)?.into_bytes()
+        },
+        _ => bytes,
+    };
+
+    // Input that is not valid UTF-8 can't be a paclet, and is left for the
+    // tokenizer to report.
+    match std::str::from_utf8(&bytes) {
+        Ok(text) if paclet::detect_paclet_header(text).is_some() => {
+            Ok(paclet::maybe_decode_paclet(text)?.into_bytes())
+        },
+        _ => Ok(bytes),
+    }
+}
+
+fn decode_utf16(
+    bytes: &[u8],
+    from_bytes: fn([u8; 2]) -> u16,
+) -> Result<String, ParseError> {
+    let invalid = |details: &str| {
+        ParseError::Encoding(EncodingError::DecodeError {
+            details: format!("invalid UTF-16 input: {details}"),
+        })
+    };
+
+    if bytes.len() % 2 != 0 {
+        return Err(invalid("odd number of bytes"));
+    }
+
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+
+    String::from_utf16(&units).map_err(|_| invalid("unpaired surrogate"))
+}
+
+//==========================================================
+// Parsing from readers
+//==========================================================
+
+/// Parse Wolfram Language input read from `reader` into concrete syntax
+/// trees.
+///
+/// `reader` is buffered internally and read one line at a time. Each
+/// top-level expression is parsed as soon as all of its lines have been read,
+/// and the input it was parsed from is then dropped, so the complete input
+/// is never held in memory at once. Use [`parse_reader_expr_iter()`] to also
+/// process each expression before the next one is read.
+///
+/// Like [`parse_cst_expr_iter()`], the result contains only the top-level
+/// expressions, and not the top-level trivia between them. Source locations
+/// are relative to the start of the complete input.
+///
+/// Input that is not valid UTF-8 is parsed the same way as by
+/// [`parse_bytes_cst_seq()`]. Unlike [`parse_file_cst()`], paclet-encoded
+/// input is not decoded.
+///
+/// ```
+/// use wolfram_parser::{parse_reader_cst, ParseOptions};
+///
+/// let input: &[u8] = b"f[x]\ng[y]";
+///
+/// let result = parse_reader_cst(input, &ParseOptions::default())?;
+///
+/// assert_eq!(result.syntax.len(), 2);
+/// # Ok::<(), wolfram_parser::ParseError>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParseError::Io`] if reading from `reader` fails.
+pub fn parse_reader_cst<R: std::io::Read>(
+    reader: R,
+    opts: &ParseOptions,
+) -> Result<ParseResult<CstSeq<TokenString>>, ParseError> {
+    parse_reader_expr_iter(reader, opts).parse_rest()
+}
+
+/// Parse Wolfram Language input read from `reader` into abstract syntax
+/// trees.
+///
+/// See [`parse_reader_cst()`].
+pub fn parse_reader_ast<R: std::io::Read>(
+    reader: R,
+    opts: &ParseOptions,
+) -> Result<ParseResult<NodeSeq<Ast>>, ParseError> {
+    let result = parse_reader_cst(reader, opts)?;
+
+    Ok(abstract_cst_seq_result(result, opts))
+}
+
+/// Get an iterator that parses the top-level expressions read from `reader`
+/// one at a time.
+///
+/// Each call to [`next()`][Iterator::next] reads only as many lines from
+/// `reader` as are needed to parse the next expression, so a consumer can
+/// process the expressions of an input that is larger than memory, or that
+/// is still being written. See [`parse_reader_cst()`].
+///
+/// ```
+/// use wolfram_parser::{parse_reader_expr_iter, ParseOptions};
+///
+/// let input: &[u8] = b"x = 1\ny = x +\n    1;\nPrint[y] (* 2 *)\n";
+///
+/// let exprs = parse_reader_expr_iter(input, &ParseOptions::default())
+///     .map(|result| Ok(result?.syntax.to_source()))
+///     .collect::<Result<Vec<String>, wolfram_parser::ParseError>>()?;
+///
+/// assert_eq!(exprs, ["x = 1", "y = x +\n    1;", "Print[y]"]);
+/// # Ok::<(), wolfram_parser::ParseError>(())
+/// ```
+///
+/// The iterator ends after the first error reading from `reader`.
+pub fn parse_reader_expr_iter<R: std::io::Read>(
+    reader: R,
     opts: &ParseOptions,
-) -> Result<NodeSeq<Token<TokenStr<'static>>>, Box<dyn std::error::Error>> {
-    let content = std::fs::read_to_string(file_path)?;
-    let final_content = crate::paclet::maybe_decode_paclet(&content)?;
-    let leaked_content = Box::leak(final_content.into_boxed_str());
-    Ok(tokenize(leaked_content, opts))
+) -> iter::ReaderExprs<std::io::BufReader<R>> {
+    iter::ReaderExprs::new(std::io::BufReader::new(reader), opts)
 }
 
 //==========================================================
@@ -793,7 +2063,7 @@ pub fn parse_to_token<'i>(
 
     exprs.push(token);
 
-    return create_parse_result(&tokenizer, exprs);
+    return tokenizer.into_parse_result(exprs);
 }
 
 // TODO(cleanup): What is this used for? Perhaps ultimately this is just
@@ -936,36 +2206,69 @@ fn expect_single_item<N: Debug>(
         unsafe_character_encoding,
         fatal_issues,
         non_fatal_issues,
+        directives,
         tracked,
     } = result;
 
-    // FIXME: Make the "error" case hold a type for resuming parsing where this
-    //        one left off. ParseResult is a bad name anyway because it sounds
-    //        like a type alias for Result<T, ParseError> or something similar.
-    //        Maybe ParseData and ResumableParseData? Or ParseData<I, Resume = ()>?
-    let [item]: [_; 1] = syntax.try_into().unwrap_or_else(|syntax| {
-        panic!("{func}: more than one {ty} in input: {syntax:?}")
-    });
+    // FIXME: ParseResult is a bad name because it sounds like a type alias
+    //        for Result<T, ParseError> or something similar. Maybe ParseData?
+    let item = expect_single(syntax, func, ty);
 
     ParseResult {
         syntax: item,
         unsafe_character_encoding,
         fatal_issues,
         non_fatal_issues,
+        directives,
         tracked,
     }
 }
 
-fn create_parse_result<N>(tokenizer: &Tokenizer, nodes: N) -> ParseResult<N> {
-    let result = ParseResult {
-        syntax: nodes,
-        unsafe_character_encoding: tokenizer.unsafe_character_encoding_flag,
-        fatal_issues: tokenizer.fatal_issues.clone(),
-        non_fatal_issues: tokenizer.non_fatal_issues.clone(),
-        tracked: tokenizer.tracked.clone(),
-    };
+fn expect_single<N: Debug>(
+    items: Vec<N>,
+    func: &'static str,
+    ty: &'static str,
+) -> N {
+    // NOTE: Use parse_cst_first() to parse inputs with more than one item.
+    let [item]: [_; 1] = items.try_into().unwrap_or_else(|items: Vec<N>| {
+        panic!(
+            "{func}: expected one {ty} in input, found {}: {items:?}",
+            items.len()
+        )
+    });
+
+    item
+}
+
+/// Like [`expect_single_item()`], but returning the input as an
+/// [`Unparsable`][crate::parse::SyntaxErrorKind::Unparsable] node if it does
+/// not contain exactly one node.
+fn recover_single_item<'i>(
+    input: &'i [u8],
+    result: ParseResult<CstSeq<TokenStr<'i>>>,
+    opts: &ParseOptions,
+) -> ParseResult<Cst<TokenStr<'i>>> {
+    let ParseResult {
+        syntax,
+        unsafe_character_encoding,
+        mut fatal_issues,
+        non_fatal_issues,
+        directives,
+        tracked,
+    } = result;
+
+    let (item, issue) = parse::recovery::single_cst(input, opts, syntax);
+
+    fatal_issues.extend(issue);
 
-    result
+    ParseResult {
+        syntax: item,
+        unsafe_character_encoding,
+        fatal_issues,
+        non_fatal_issues,
+        directives,
+        tracked,
+    }
 }
 
 //======================================
@@ -1086,8 +2389,20 @@ impl fmt::Debug for ParseOptions {
             .field("src_convention", &self.src_convention)
             .field("encoding_mode", &self.encoding_mode)
             .field("tab_width", &self.tab_width)
+            .field("tab_width_auto", &self.tab_width_auto)
             .field("check_issues", &self.check_issues)
             .field("compute_oob", &self.compute_oob)
+            .field("implicit_times", &self.implicit_times)
+            .field("toplevel_newlines", &self.toplevel_newlines)
+            .field("grammar_profile", &self.grammar_profile)
+            .field("denied_issues", &self.denied_issues)
+            .field("max_line_length", &self.max_line_length)
+            .field("check_indentation", &self.check_indentation)
+            .field("reparse", &self.reparse)
+            .field("recovery", &self.recovery)
+            .field("deduplicate_issues", &self.deduplicate_issues)
+            .field("notations", &self.notations)
+            .field("debug_sink", &self.debug_sink.is_some())
             .field("quirk_settings", &self.quirk_settings)
             .finish()
     }
@@ -1232,11 +2547,49 @@ impl<T> ParseResult<T> {
     }
     
     /// Convert to Result, failing if there are fatal issues.
-    pub fn into_result(self) -> Result<T, Vec<Issue>> {
+    ///
+    /// The error is a [`ParseError::Issues`] containing the fatal issues.
+    pub fn into_result(self) -> Result<T, ParseError> {
         if self.fatal_issues.is_empty() {
             Ok(self.syntax)
         } else {
-            Err(self.fatal_issues)
+            Err(ParseError::Issues(self.fatal_issues))
+        }
+    }
+    
+    /// Convert to Option, discarding the syntax tree if there are fatal
+    /// issues.
+    pub fn ok(self) -> Option<T> {
+        self.into_result().ok()
+    }
+
+    /// Convert to Result, failing if any issue is more severe than
+    /// `max_severity`.
+    ///
+    /// Unlike [`into_result()`][ParseResult::into_result], both fatal and
+    /// non-fatal issues are considered. The error is a [`ParseError::Issues`]
+    /// containing only the issues that exceeded `max_severity`, in the order
+    /// returned by [`issues()`][ParseResult::issues].
+    ///
+    /// `try_syntax(Severity::Fatal)` always succeeds.
+    pub fn try_syntax(self, max_severity: Severity) -> Result<T, ParseError> {
+        let ParseResult {
+            syntax,
+            fatal_issues,
+            non_fatal_issues,
+            ..
+        } = self;
+
+        let exceeded: Vec<Issue> = fatal_issues
+            .into_iter()
+            .chain(non_fatal_issues)
+            .filter(|issue| issue.sev > max_severity)
+            .collect();
+
+        if exceeded.is_empty() {
+            Ok(syntax)
+        } else {
+            Err(ParseError::Issues(exceeded))
         }
     }
     
@@ -1260,11 +2613,156 @@ impl<T> ParseResult<T> {
             unsafe_character_encoding: self.unsafe_character_encoding,
             fatal_issues: self.fatal_issues,
             non_fatal_issues: self.non_fatal_issues,
+            directives: self.directives,
             tracked: self.tracked,
         }
     }
 }
 
+impl<T: SyntaxErrors> ParseResult<T> {
+    /// Get the issues found by every stage of parsing.
+    ///
+    /// These are the [`issues()`][ParseResult::issues] found while tokenizing
+    /// and parsing, followed by the
+    /// [`syntax_errors()`][SyntaxErrors::syntax_errors] of
+    /// [`syntax`][ParseResult::syntax]. Unless disabled with
+    /// [`ParseOptions::deduplicate_issues()`], issues that describe the same
+    /// problem are collapsed into one.
+    ///
+    /// `opts` must be the options the input was parsed with.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_cst, ParseOptions, RecoveryMode};
+    ///
+    /// let opts = ParseOptions::default().recovery(RecoveryMode::Recover);
+    ///
+    /// let result = parse_cst("", &opts);
+    ///
+    /// // The missing expression is reported once.
+    /// let diagnostics = result.diagnostics(&opts);
+    ///
+    /// assert_eq!(diagnostics.len(), 1);
+    /// assert_eq!(diagnostics[0].msg, "Expected an expression.");
+    ///
+    /// // By both the parser and the syntax error node.
+    /// let opts = opts.deduplicate_issues(false);
+    ///
+    /// assert!(result.diagnostics(&opts).len() > 1);
+    /// ```
+    pub fn diagnostics(&self, opts: &ParseOptions) -> Vec<Issue> {
+        let issues =
+            self.issues().cloned().chain(self.syntax.syntax_errors());
+
+        if opts.deduplicate_issues {
+            issue::deduplicate(issues)
+        } else {
+            issues.collect()
+        }
+    }
+
+    /// Get the single most relevant error, for UIs that only have room to show
+    /// one.
+    ///
+    /// Both [`issues()`][ParseResult::issues] and the syntax error nodes in
+    /// [`syntax`][ParseResult::syntax] are considered, but only issues with
+    /// [`Severity::Fatal`] or [`Severity::Error`]. Fatal errors are preferred,
+    /// then errors with higher confidence, then errors that appear earlier in
+    /// the input.
+    pub fn primary_error(&self) -> Option<Issue> {
+        fn rank(issue: &Issue) -> Option<u8> {
+            match issue.sev {
+                Severity::Fatal => Some(1),
+                Severity::Error => Some(0),
+                _ => None,
+            }
+        }
+
+        fn start(issue: &Issue) -> Option<Location> {
+            match &issue.src {
+                Source::Span(span) => Some(span.start()),
+                Source::Box(_) | Source::Unknown => None,
+            }
+        }
+
+        let syntax_errors = self.syntax.syntax_errors();
+
+        let mut primary: Option<(u8, &Issue)> = None;
+
+        for issue in self.issues().chain(&syntax_errors) {
+            let Some(rank) = rank(issue) else {
+                continue;
+            };
+
+            let is_better = match primary {
+                None => true,
+                Some((best_rank, best)) => {
+                    rank > best_rank
+                        || (rank == best_rank && issue.val > best.val)
+                        || (rank == best_rank
+                            && issue.val == best.val
+                            && is_before(start(issue), start(best)))
+                },
+            };
+
+            if is_better {
+                primary = Some((rank, issue));
+            }
+        }
+
+        primary.map(|(_, issue)| issue.clone())
+    }
+
+    /// Get the source location of the
+    /// [`primary_error()`][ParseResult::primary_error].
+    ///
+    /// This is the span an editor or notebook should highlight.
+    pub fn primary_error_span(&self) -> Option<Source> {
+        self.primary_error().map(|issue| issue.src)
+    }
+
+    /// Get a one-line description of the
+    /// [`primary_error()`][ParseResult::primary_error], suitable for a status
+    /// bar.
+    ///
+    /// ```
+    /// use wolfram_parser::{parse_cst, ParseOptions};
+    ///
+    /// let result = parse_cst("f[x, y +]", &ParseOptions::default());
+    ///
+    /// assert_eq!(
+    ///     result.error_summary().unwrap(),
+    ///     "1:9: Expected an operand."
+    /// );
+    /// ```
+    pub fn error_summary(&self) -> Option<String> {
+        let issue = self.primary_error()?;
+
+        let msg = issue.msg.lines().next().unwrap_or_default().trim();
+
+        let summary = match &issue.src {
+            Source::Span(span) => format!("{}: {msg}", span.start()),
+            Source::Box(_) | Source::Unknown => msg.to_owned(),
+        };
+
+        Some(summary)
+    }
+}
+
+/// Whether `a` is a location strictly before `b`.
+///
+/// Locations using different conventions, or unknown locations, are not
+/// ordered.
+fn is_before(a: Option<Location>, b: Option<Location>) -> bool {
+    match (a, b) {
+        (Some(Location::LineColumn(a)), Some(Location::LineColumn(b))) => a < b,
+        (
+            Some(Location::CharacterIndex(a)),
+            Some(Location::CharacterIndex(b)),
+        ) => a < b,
+        _ => false,
+    }
+}
+
 //======================================
 // Tests for ergonomic features
 //======================================
@@ -1357,6 +2855,7 @@ mod ergonomic_tests {
             unsafe_character_encoding: None,
             fatal_issues: vec![],
             non_fatal_issues: vec![],
+            directives: vec![],
             tracked: TrackedSourceLocations {
                 simple_line_continuations: HashSet::new(),
                 complex_line_continuations: HashSet::new(),
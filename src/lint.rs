@@ -0,0 +1,1547 @@
+//! Lints over abstract syntax trees.
+//!
+//! # Top-level side effects
+//!
+//! Loading a package file should only define symbols. Code at the top level
+//! of a package that does anything else runs every time the package is
+//! loaded, which is usually a mistake.
+//!
+//! [`top_level_side_effects()`] reports every top-level statement that is not
+//! one of:
+//!
+//! * a definition, like `f[x_] := x` or `f::usage = "..."`
+//! * a declaration, like `SetAttributes[f, HoldAll]` or `Protect[f]`
+//! * context management, like `BeginPackage[...]`, `End[]`, or `Needs[...]`
+//! * an atom, like a bare symbol `f`
+//! * a call to a function in [`SideEffectOptions::allow()`]
+//!
+//! ```
+//! use wolfram_parser::{lint::{self, SideEffectOptions}, parse_ast_seq, ParseOptions};
+//!
+//! let input = r#"
+//! BeginPackage["Pkg`"]
+//! f[x_] := x + 1
+//! Print["loading..."]
+//! EndPackage[]
+//! "#;
+//!
+//! let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+//!
+//! let issues = lint::top_level_side_effects(&ast, &SideEffectOptions::default());
+//! assert_eq!(issues.len(), 1);
+//!
+//! let opts = SideEffectOptions::default().allow("Print");
+//! assert!(lint::top_level_side_effects(&ast, &opts).is_empty());
+//! ```
+//!
+//! # Dynamic code execution
+//!
+//! Code that evaluates or loads code chosen at run time is hard to audit,
+//! and can run arbitrary code if the input comes from a user.
+//!
+//! [`dynamic_code_execution()`] reports, anywhere in a file:
+//!
+//! * `ToExpression[..]` of anything but a literal string
+//!   ([`DynamicCodeRule::ToExpression`])
+//! * `Symbol[..]` of anything but a literal string
+//!   ([`DynamicCodeRule::Symbol`])
+//! * `Get[..]` of a path that is not a literal string
+//!   ([`DynamicCodeRule::Get`])
+//!
+//! The severity of each rule can be configured, or the rule disabled:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     issue::Severity,
+//!     lint::{self, DynamicCodeOptions, DynamicCodeRule},
+//!     parse_ast_seq, ParseOptions,
+//! };
+//!
+//! let input = r#"
+//! f[s_String] := ToExpression[s]
+//! g[name_] := Symbol["Pkg`" <> name]
+//! Get[FileNameJoin[{dir, "init.wl"}]]
+//! ToExpression["1 + 1"]
+//! "#;
+//!
+//! let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+//!
+//! let issues = lint::dynamic_code_execution(&ast, &DynamicCodeOptions::default());
+//! assert_eq!(issues.len(), 3);
+//! assert_eq!(issues[0].sev, Severity::Error);
+//!
+//! let opts = DynamicCodeOptions::default()
+//!     .severity(DynamicCodeRule::ToExpression, Severity::Warning)
+//!     .disable(DynamicCodeRule::Symbol);
+//!
+//! let issues = lint::dynamic_code_execution(&ast, &opts);
+//! assert_eq!(issues.len(), 2);
+//! assert_eq!(issues[0].sev, Severity::Warning);
+//! ```
+//!
+//! # Common mistakes
+//!
+//! [`lint()`] checks a file for code that is valid but probably not what was
+//! meant:
+//!
+//! * a top-level `=` that should probably be `:=`, like `f[x_] = x^2`
+//!   ([`LintRule::SuspiciousSet`])
+//! * an assignment to a protected symbol, like `N = 10`
+//!   ([`LintRule::ProtectedAssignment`])
+//! * an implicit multiplication between operands on different lines, which
+//!   is usually a missing comma ([`LintRule::ImplicitTimesAcrossLines`])
+//! * a scoping construct with no variables, like `Module[{}, ..]`
+//!   ([`LintRule::EmptyScopeVariables`])
+//! * an `If` or `Which` condition that is always `True` or always `False`,
+//!   like `If[True, a, b]` ([`LintRule::ConstantCondition`])
+//! * a comparison of literals, like `1 == 1` or `"a" === "b"`
+//!   ([`LintRule::ConstantComparison`])
+//!
+//! Conditions are evaluated when they only use literals, `Not`, `And`, `Or`,
+//! and comparisons, like `!(1 < 2)`.
+//!
+//! Either abstract or concrete syntax can be checked. Concrete syntax is
+//! needed to find implicit multiplications, and to offer [`CodeAction`]s:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     issue::{CodeActionKind, IssueTag},
+//!     lint::{self, LintConfig, LintRule},
+//!     parse_cst_seq, ParseOptions,
+//! };
+//!
+//! let input = "square[x_] = x^2
+//! N = 10
+//! values = {
+//!     a
+//!     b
+//! }";
+//!
+//! let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+//!
+//! let issues = lint::lint(&cst, &LintConfig::default());
+//!
+//! let tags: Vec<_> = issues.iter().map(|issue| issue.tag).collect();
+//!
+//! assert_eq!(
+//!     tags,
+//!     [
+//!         IssueTag::SuspiciousSet,
+//!         IssueTag::ProtectedAssignment,
+//!         IssueTag::ImplicitTimesAcrossLines,
+//!     ]
+//! );
+//!
+//! assert_eq!(
+//!     issues[0].actions[0].kind,
+//!     CodeActionKind::ReplaceText {
+//!         replacement_text: ":=".to_owned()
+//!     }
+//! );
+//!
+//! let config = LintConfig::default().disable(LintRule::ProtectedAssignment);
+//!
+//! assert_eq!(lint::lint(&cst, &config).len(), 2);
+//! ```
+
+use std::{cmp::Ordering, fmt::Debug};
+
+use crate::{
+    abstract_cst::{abstract_cst_seq_with_map, AbstractSourceMap},
+    analysis::scoping::{list_elements, pattern_names},
+    ast::Ast,
+    cst::{BinaryNode, Cst, CstSeq, InfixNode, OperatorNode},
+    data::LiteralValue,
+    file_ops::literal_target,
+    issue::{Annotation, CodeAction, Issue, IssueTag, Severity},
+    parse::{
+        operators::{BinaryOperator, InfixOperator},
+        spec::{operator_spec, OperatorPosition},
+    },
+    source::{Source, Span},
+    symbols as sym,
+    tokenize::{TokenInput, TokenKind, TokenString},
+    NodeSeq, QuirkSettings,
+};
+
+/// Options for [`top_level_side_effects()`].
+#[derive(Debug, Clone)]
+pub struct SideEffectOptions {
+    allowed: Vec<String>,
+    severity: Severity,
+}
+
+/// Options for [`dynamic_code_execution()`].
+#[derive(Debug, Clone)]
+pub struct DynamicCodeOptions {
+    to_expression: Option<Severity>,
+    symbol: Option<Severity>,
+    get: Option<Severity>,
+}
+
+/// A rule checked by [`dynamic_code_execution()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DynamicCodeRule {
+    /// `ToExpression[..]` of input that is not a literal string.
+    ToExpression,
+    /// `Symbol[..]` of a name that is not a literal string.
+    Symbol,
+    /// `Get[..]` of a path that is not a literal string.
+    Get,
+}
+
+/// Configuration of the rules checked by [`lint()`].
+#[derive(Debug, Clone)]
+pub struct LintConfig {
+    suspicious_set: Option<Severity>,
+    protected_assignment: Option<Severity>,
+    implicit_times_across_lines: Option<Severity>,
+    empty_scope_variables: Option<Severity>,
+    constant_condition: Option<Severity>,
+    constant_comparison: Option<Severity>,
+    protected: Vec<String>,
+}
+
+/// A rule checked by [`lint()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// A top-level `Set` of a pattern whose right-hand side uses one of the
+    /// pattern variables, like `f[x_] = x^2`.
+    ///
+    /// The right-hand side is evaluated once, when the definition is made,
+    /// instead of each time the definition is used.
+    SuspiciousSet,
+    /// An assignment to a protected `System` symbol, like `N = 10` or
+    /// `Sin[x_] := ..`, that is not preceded by an `Unprotect` of it.
+    ProtectedAssignment,
+    /// An implicit multiplication between operands on different lines,
+    /// like `{a` and `b}` on separate lines.
+    ///
+    /// Only checked in concrete syntax.
+    ImplicitTimesAcrossLines,
+    /// A `Module`, `Block`, or `With` with an empty variable list.
+    EmptyScopeVariables,
+    /// An `If` whose condition is always `True` or always `False`, a
+    /// `Which` arm whose condition is always `False`, or a `Which` arm whose
+    /// condition is always `True` that is followed by other arms.
+    ///
+    /// The branches that are never evaluated can be removed.
+    ConstantCondition,
+    /// A comparison of literals, like `1 == 1`, that is not a
+    /// [`ConstantCondition`][LintRule::ConstantCondition].
+    ConstantComparison,
+}
+
+/// Syntax that can be checked by [`lint()`].
+///
+/// This is implemented for the sequences returned by
+/// [`parse_ast_seq()`][crate::parse_ast_seq] and
+/// [`parse_cst_seq()`][crate::parse_cst_seq].
+pub trait Lint {
+    /// Add the issues found in this syntax to `issues`.
+    #[doc(hidden)]
+    fn lint_into(&self, config: &LintConfig, issues: &mut Vec<Issue>);
+}
+
+/// `System` symbols checked by [`LintRule::ProtectedAssignment`] by default.
+///
+/// These are the protected symbols that are most often assigned to by
+/// mistake, typically because their names are short or common words.
+const PROTECTED: &[&str] = &[
+    "Abs",
+    "And",
+    "Apply",
+    "C",
+    "Catalan",
+    "Cos",
+    "D",
+    "Degree",
+    "Do",
+    "E",
+    "EulerGamma",
+    "Exp",
+    "False",
+    "For",
+    "Function",
+    "Gamma",
+    "GoldenRatio",
+    "I",
+    "If",
+    "Indeterminate",
+    "Infinity",
+    "Integer",
+    "Integrate",
+    "Length",
+    "List",
+    "Log",
+    "Map",
+    "Max",
+    "Min",
+    "Module",
+    "N",
+    "Not",
+    "Null",
+    "O",
+    "Or",
+    "Pi",
+    "Plus",
+    "Power",
+    "Print",
+    "Range",
+    "Real",
+    "Sin",
+    "Solve",
+    "Sqrt",
+    "String",
+    "Sum",
+    "Symbol",
+    "Table",
+    "Tan",
+    "Times",
+    "True",
+    "Which",
+    "While",
+];
+
+/// Comparisons checked by [`LintRule::ConstantComparison`].
+const COMPARISONS: &[&str] = &[
+    "Equal",
+    "Unequal",
+    "SameQ",
+    "UnsameQ",
+    "Less",
+    "LessEqual",
+    "Greater",
+    "GreaterEqual",
+];
+
+/// Functions whose top-level calls define or declare symbols.
+const DECLARATIONS: &[&str] = &[
+    "Set",
+    "SetDelayed",
+    "UpSet",
+    "UpSetDelayed",
+    "TagSet",
+    "TagSetDelayed",
+    "Unset",
+    "TagUnset",
+    "SetAttributes",
+    "ClearAttributes",
+    "Protect",
+    "Unprotect",
+    "Clear",
+    "ClearAll",
+    "Remove",
+    "PackageExport",
+    "PackageScope",
+    "PackageImport",
+    "Package",
+];
+
+/// Functions whose top-level calls manage the current context.
+const CONTEXT_MANAGEMENT: &[&str] =
+    &["BeginPackage", "EndPackage", "Begin", "End", "Needs", "Get"];
+
+//======================================
+// Impls
+//======================================
+
+impl SideEffectOptions {
+    /// Also allow top-level calls to `head`.
+    ///
+    /// `head` is compared against the head of calls as it was written, so
+    /// e.g. `` "Pkg`init" `` and `"init"` are distinct.
+    pub fn allow(mut self, head: impl Into<String>) -> Self {
+        self.allowed.push(head.into());
+        self
+    }
+
+    /// Severity of reported issues. The default is [`Severity::Warning`].
+    pub fn severity(self, severity: Severity) -> Self {
+        SideEffectOptions { severity, ..self }
+    }
+
+    fn is_allowed(&self, head: &str) -> bool {
+        self.allowed.iter().any(|allowed| allowed == head)
+    }
+}
+
+impl Default for SideEffectOptions {
+    fn default() -> Self {
+        SideEffectOptions {
+            allowed: Vec::new(),
+            severity: Severity::Warning,
+        }
+    }
+}
+
+impl DynamicCodeOptions {
+    /// Severity of issues reported by `rule`.
+    ///
+    /// The default is [`Severity::Error`] for
+    /// [`DynamicCodeRule::ToExpression`], and [`Severity::Warning`] for the
+    /// other rules.
+    pub fn severity(
+        mut self,
+        rule: DynamicCodeRule,
+        severity: Severity,
+    ) -> Self {
+        *self.rule_mut(rule) = Some(severity);
+        self
+    }
+
+    /// Do not report issues for `rule`.
+    pub fn disable(mut self, rule: DynamicCodeRule) -> Self {
+        *self.rule_mut(rule) = None;
+        self
+    }
+
+    fn rule_mut(&mut self, rule: DynamicCodeRule) -> &mut Option<Severity> {
+        match rule {
+            DynamicCodeRule::ToExpression => &mut self.to_expression,
+            DynamicCodeRule::Symbol => &mut self.symbol,
+            DynamicCodeRule::Get => &mut self.get,
+        }
+    }
+
+    fn rule_severity(&self, rule: DynamicCodeRule) -> Option<Severity> {
+        match rule {
+            DynamicCodeRule::ToExpression => self.to_expression,
+            DynamicCodeRule::Symbol => self.symbol,
+            DynamicCodeRule::Get => self.get,
+        }
+    }
+}
+
+impl Default for DynamicCodeOptions {
+    fn default() -> Self {
+        DynamicCodeOptions {
+            to_expression: Some(Severity::Error),
+            symbol: Some(Severity::Warning),
+            get: Some(Severity::Warning),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Severity of issues reported by `rule`.
+    ///
+    /// The default is [`Severity::Error`] for
+    /// [`LintRule::ProtectedAssignment`], [`Severity::Remark`] for
+    /// [`LintRule::EmptyScopeVariables`] and
+    /// [`LintRule::ConstantComparison`], and [`Severity::Warning`] for the
+    /// other rules.
+    pub fn severity(mut self, rule: LintRule, severity: Severity) -> Self {
+        *self.rule_mut(rule) = Some(severity);
+        self
+    }
+
+    /// Do not report issues for `rule`.
+    pub fn disable(mut self, rule: LintRule) -> Self {
+        *self.rule_mut(rule) = None;
+        self
+    }
+
+    /// Also report assignments to `name` as
+    /// [`LintRule::ProtectedAssignment`]s.
+    ///
+    /// `name` is compared against symbols as they are written, after
+    /// removing a ``System` `` context.
+    pub fn protect(mut self, name: impl Into<String>) -> Self {
+        self.protected.push(name.into());
+        self
+    }
+
+    fn rule_mut(&mut self, rule: LintRule) -> &mut Option<Severity> {
+        match rule {
+            LintRule::SuspiciousSet => &mut self.suspicious_set,
+            LintRule::ProtectedAssignment => &mut self.protected_assignment,
+            LintRule::ImplicitTimesAcrossLines => {
+                &mut self.implicit_times_across_lines
+            },
+            LintRule::EmptyScopeVariables => &mut self.empty_scope_variables,
+            LintRule::ConstantCondition => &mut self.constant_condition,
+            LintRule::ConstantComparison => &mut self.constant_comparison,
+        }
+    }
+
+    fn rule_severity(&self, rule: LintRule) -> Option<Severity> {
+        match rule {
+            LintRule::SuspiciousSet => self.suspicious_set,
+            LintRule::ProtectedAssignment => self.protected_assignment,
+            LintRule::ImplicitTimesAcrossLines => {
+                self.implicit_times_across_lines
+            },
+            LintRule::EmptyScopeVariables => self.empty_scope_variables,
+            LintRule::ConstantCondition => self.constant_condition,
+            LintRule::ConstantComparison => self.constant_comparison,
+        }
+    }
+
+    fn is_protected(&self, name: &str) -> bool {
+        let Some(name) = builtin_name(name) else {
+            return false;
+        };
+
+        PROTECTED.contains(&name)
+            || self.protected.iter().any(|protected| protected == name)
+    }
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            suspicious_set: Some(Severity::Warning),
+            protected_assignment: Some(Severity::Error),
+            implicit_times_across_lines: Some(Severity::Warning),
+            empty_scope_variables: Some(Severity::Remark),
+            constant_condition: Some(Severity::Warning),
+            constant_comparison: Some(Severity::Remark),
+            protected: Vec::new(),
+        }
+    }
+}
+
+impl LintRule {
+    fn tag(self) -> IssueTag {
+        match self {
+            LintRule::SuspiciousSet => IssueTag::SuspiciousSet,
+            LintRule::ProtectedAssignment => IssueTag::ProtectedAssignment,
+            LintRule::ImplicitTimesAcrossLines => {
+                IssueTag::ImplicitTimesAcrossLines
+            },
+            LintRule::EmptyScopeVariables => IssueTag::EmptyScopeVariables,
+            LintRule::ConstantCondition => IssueTag::ConstantCondition,
+            LintRule::ConstantComparison => IssueTag::ConstantComparison,
+        }
+    }
+}
+
+impl Lint for NodeSeq<Ast> {
+    fn lint_into(&self, config: &LintConfig, issues: &mut Vec<Issue>) {
+        Linter::<TokenString>::new(config, None).file(self, issues);
+    }
+}
+
+/// Implicit multiplications are only found, and [`CodeAction`]s only
+/// offered, for concrete syntax. The syntax is abstracted with the default
+/// [`QuirkSettings`].
+impl<I: TokenInput + Debug> Lint for CstSeq<I> {
+    fn lint_into(&self, config: &LintConfig, issues: &mut Vec<Issue>) {
+        if let Some(sev) =
+            config.rule_severity(LintRule::ImplicitTimesAcrossLines)
+        {
+            self.visit(&mut |node| check_implicit_times(node, sev, issues));
+        }
+
+        let (ast, map) =
+            abstract_cst_seq_with_map(self.clone(), QuirkSettings::default());
+
+        Linter::new(config, Some(&map)).file(&ast, issues);
+    }
+}
+
+impl DynamicCodeRule {
+    fn from_name(name: &str) -> Option<Self> {
+        let rule = match name {
+            "ToExpression" => DynamicCodeRule::ToExpression,
+            "Symbol" => DynamicCodeRule::Symbol,
+            "Get" => DynamicCodeRule::Get,
+            _ => return None,
+        };
+
+        Some(rule)
+    }
+
+    fn tag(self) -> IssueTag {
+        match self {
+            DynamicCodeRule::ToExpression => IssueTag::DynamicToExpression,
+            DynamicCodeRule::Symbol => IssueTag::DynamicSymbol,
+            DynamicCodeRule::Get => IssueTag::DynamicGet,
+        }
+    }
+}
+
+//======================================
+// Lints
+//======================================
+
+/// Report the top-level statements of `ast` that have an effect when the
+/// file is loaded.
+///
+/// `ast` is a whole file, like the result of
+/// [`parse_ast_seq()`][crate::parse_ast_seq]. Statements of a top-level
+/// `CompoundExpression` are checked individually. Syntax errors are not
+/// reported, as they are already reported by the parser.
+pub fn top_level_side_effects(
+    ast: &NodeSeq<Ast>,
+    opts: &SideEffectOptions,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for statement in &ast.0 {
+        check_statement(statement, opts, &mut issues);
+    }
+
+    issues
+}
+
+fn check_statement(
+    statement: &Ast,
+    opts: &SideEffectOptions,
+    issues: &mut Vec<Issue>,
+) {
+    let head = match statement {
+        Ast::Leaf { .. } => return,
+        Ast::Call { head, .. } | Ast::CallMissingCloser { head, .. } => head,
+        // Syntax errors are reported by the parser.
+        Ast::Error { .. }
+        | Ast::SyntaxError { .. }
+        | Ast::AbstractSyntaxError { .. }
+        | Ast::GroupMissingCloser { .. }
+        | Ast::GroupMissingOpener { .. } => return,
+        // The effects of syntax that could not be abstracted are unknown.
+        Ast::Unhandled { .. } => return,
+        _ => {
+            issues.push(side_effect_issue(statement, None, opts));
+            return;
+        },
+    };
+
+    let Some(head) = head.as_symbol() else {
+        issues.push(side_effect_issue(statement, None, opts));
+        return;
+    };
+
+    if opts.is_allowed(head) {
+        return;
+    }
+
+    let name = builtin_name(head);
+
+    if name == Some("CompoundExpression") {
+        if let Some((_, args)) = statement.as_symbol_call() {
+            for arg in args {
+                check_statement(arg, opts, issues);
+            }
+        }
+        return;
+    }
+
+    let is_declaration = name.map_or(false, |name| {
+        DECLARATIONS.contains(&name) || CONTEXT_MANAGEMENT.contains(&name)
+    });
+
+    if !is_declaration {
+        issues.push(side_effect_issue(statement, Some(head), opts));
+    }
+}
+
+/// Report code in `syntax` that is valid but probably not what was meant.
+///
+/// `syntax` is a whole file, like the result of
+/// [`parse_ast_seq()`][crate::parse_ast_seq] or
+/// [`parse_cst_seq()`][crate::parse_cst_seq]. Issues are returned in source
+/// order. See the [module documentation](self#common-mistakes) for the rules
+/// that are checked.
+pub fn lint<T: Lint + ?Sized>(syntax: &T, config: &LintConfig) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    syntax.lint_into(config, &mut issues);
+
+    issues.sort_by(|a, b| a.src.partial_cmp(&b.src).unwrap_or(Ordering::Equal));
+
+    issues
+}
+
+/// Report calls in `ast` that evaluate or load code chosen at run time.
+///
+/// Every expression is searched, not only top-level statements. An argument
+/// is considered literal if it is a string, a `<>` chain of strings, or (for
+/// `Get`) a `File[..]` of one. `<< file` always has a literal path.
+pub fn dynamic_code_execution(
+    ast: &NodeSeq<Ast>,
+    opts: &DynamicCodeOptions,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for node in &ast.0 {
+        check_dynamic_code(node, opts, &mut issues);
+    }
+
+    issues
+}
+
+fn check_dynamic_code(
+    ast: &Ast,
+    opts: &DynamicCodeOptions,
+    issues: &mut Vec<Issue>,
+) {
+    if let Ast::Call { head, args, .. } = ast {
+        let rule = head
+            .as_symbol()
+            .and_then(builtin_name)
+            .and_then(DynamicCodeRule::from_name);
+
+        if let (Some(rule), Some(arg)) = (rule, args.first()) {
+            let is_literal = match rule {
+                DynamicCodeRule::Get => literal_target(arg).is_some(),
+                DynamicCodeRule::ToExpression | DynamicCodeRule::Symbol => {
+                    arg.as_joined_string_value().is_some()
+                },
+            };
+
+            if let (false, Some(sev)) = (is_literal, opts.rule_severity(rule)) {
+                issues.push(dynamic_code_issue(ast, arg, rule, sev));
+            }
+        }
+    }
+
+    ast.for_each_child(|child| check_dynamic_code(child, opts, issues));
+}
+
+struct Linter<'c, I> {
+    config: &'c LintConfig,
+
+    /// The concrete syntax the checked abstract syntax was abstracted from,
+    /// if any.
+    map: Option<&'c AbstractSourceMap<I>>,
+
+    /// The symbols that have been unprotected by `Unprotect[..]`.
+    unprotected: Vec<String>,
+}
+
+impl<'c, I: TokenInput> Linter<'c, I> {
+    fn new(
+        config: &'c LintConfig,
+        map: Option<&'c AbstractSourceMap<I>>,
+    ) -> Self {
+        Linter {
+            config,
+            map,
+            unprotected: Vec::new(),
+        }
+    }
+
+    fn file(mut self, ast: &NodeSeq<Ast>, issues: &mut Vec<Issue>) {
+        let NodeSeq(nodes) = ast;
+
+        for node in nodes {
+            self.statement(node, issues);
+            self.visit(node, issues);
+        }
+    }
+
+    /// Check the top-level statement `ast`.
+    fn statement(&self, ast: &Ast, issues: &mut Vec<Issue>) {
+        let Some((head, args)) = ast.as_symbol_call() else {
+            return;
+        };
+
+        match (builtin_name(head), args) {
+            (Some("CompoundExpression"), statements) => {
+                for statement in statements {
+                    self.statement(statement, issues);
+                }
+            },
+            (Some("Set"), [lhs, rhs]) => {
+                let Some(sev) =
+                    self.config.rule_severity(LintRule::SuspiciousSet)
+                else {
+                    return;
+                };
+
+                let mut names = Vec::new();
+
+                pattern_names(lhs, &mut names);
+
+                let names: Vec<&str> =
+                    names.iter().filter_map(|name| name.as_symbol()).collect();
+
+                if mentions_any(rhs, &names) {
+                    issues.push(self.suspicious_set_issue(ast, sev));
+                }
+            },
+            _ => (),
+        }
+    }
+
+    /// Check `ast` and every expression in it.
+    fn visit(&mut self, ast: &Ast, issues: &mut Vec<Issue>) {
+        if let Some((head, args)) = ast.as_symbol_call() {
+            match (builtin_name(head), args) {
+                (Some("Unprotect"), names) => {
+                    let names = names.iter().filter_map(|name| {
+                        name.as_symbol()
+                            .map(str::to_owned)
+                            .or_else(|| name.as_string_value())
+                    });
+
+                    self.unprotected.extend(names);
+                },
+                (Some("Set" | "SetDelayed"), [lhs, _]) => {
+                    self.check_assignment(defined_symbol(lhs), ast, issues);
+                },
+                (Some("TagSet" | "TagSetDelayed"), [tag, _, _]) => {
+                    self.check_assignment(tag.as_symbol(), ast, issues);
+                },
+                (
+                    Some(name @ ("Module" | "Block" | "With")),
+                    [vars, body @ ..],
+                ) => {
+                    if let Some(vars) = list_elements(vars) {
+                        self.scope(name, ast, vars, body, issues);
+                        return;
+                    }
+                },
+                (Some("If"), [cond, arms @ ..]) if !arms.is_empty() => {
+                    if let Some(value) = constant_condition(cond) {
+                        self.check_constant_if(ast, cond, value, arms, issues);
+
+                        for arm in arms {
+                            self.visit(arm, issues);
+                        }
+                        return;
+                    }
+                },
+                (Some("Which"), args) => {
+                    self.which(ast, args, issues);
+                    return;
+                },
+                (Some(name), [lhs, rhs]) if COMPARISONS.contains(&name) => {
+                    if let Some(value) = compare(name, lhs, rhs) {
+                        self.check_constant_comparison(ast, value, issues);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        ast.for_each_child(|child| self.visit(child, issues));
+    }
+
+    /// `Module[{x, y = init}, body]`, and similarly for `Block` and `With`.
+    fn scope(
+        &mut self,
+        name: &str,
+        ast: &Ast,
+        vars: &[Ast],
+        body: &[Ast],
+        issues: &mut Vec<Issue>,
+    ) {
+        if vars.is_empty() {
+            self.check_empty_scope(name, ast, body, issues);
+        }
+
+        for var in vars {
+            match var.as_symbol_call() {
+                // Assignments in the variable list are initial values of
+                // local variables, not definitions.
+                Some((head, [_, init]))
+                    if matches!(
+                        builtin_name(head),
+                        Some("Set" | "SetDelayed")
+                    ) =>
+                {
+                    self.visit(init, issues)
+                },
+                _ => self.visit(var, issues),
+            }
+        }
+
+        for node in body {
+            self.visit(node, issues);
+        }
+    }
+
+    /// `Which[cond1, value1, cond2, value2, ..]`
+    fn which(&mut self, ast: &Ast, args: &[Ast], issues: &mut Vec<Issue>) {
+        for (index, pair) in args.chunks(2).enumerate() {
+            let cond = &pair[0];
+
+            match constant_condition(cond) {
+                Some(value) if pair.len() == 2 => {
+                    self.check_constant_which_arm(
+                        ast,
+                        args,
+                        index * 2,
+                        value,
+                        issues,
+                    );
+                },
+                _ => self.visit(cond, issues),
+            }
+
+            if let Some(value) = pair.get(1) {
+                self.visit(value, issues);
+            }
+        }
+    }
+
+    fn check_assignment(
+        &self,
+        name: Option<&str>,
+        ast: &Ast,
+        issues: &mut Vec<Issue>,
+    ) {
+        let Some(sev) =
+            self.config.rule_severity(LintRule::ProtectedAssignment)
+        else {
+            return;
+        };
+
+        let Some(name) = name.filter(|name| self.config.is_protected(name))
+        else {
+            return;
+        };
+
+        let is_unprotected = self
+            .unprotected
+            .iter()
+            .any(|unprotected| builtin_name(unprotected) == builtin_name(name));
+
+        if !is_unprotected {
+            issues.push(lint_issue(
+                LintRule::ProtectedAssignment,
+                format!("Assignment to protected symbol ``{name}``."),
+                sev,
+                ast.source().clone(),
+                Vec::new(),
+                Annotation::suggestion(
+                    format!(
+                        "Use a different name, or call ``Unprotect[{name}]`` \
+                         first."
+                    ),
+                    ast.source().clone(),
+                ),
+            ));
+        }
+    }
+
+    fn check_empty_scope(
+        &self,
+        name: &str,
+        ast: &Ast,
+        body: &[Ast],
+        issues: &mut Vec<Issue>,
+    ) {
+        let Some(sev) =
+            self.config.rule_severity(LintRule::EmptyScopeVariables)
+        else {
+            return;
+        };
+
+        // Module[{}, body] -> body
+        let action = match body {
+            [body] => {
+                self.source_text(body).zip(span(ast)).map(|(text, src)| {
+                    CodeAction::replace_text(
+                        format!("Replace ``{name}`` with its body"),
+                        src,
+                        text,
+                    )
+                })
+            },
+            _ => None,
+        };
+
+        issues.push(lint_issue(
+            LintRule::EmptyScopeVariables,
+            format!("``{name}`` has no variables."),
+            sev,
+            ast.source().clone(),
+            action.into_iter().collect(),
+            Annotation::explanation(
+                format!("``{name}`` with no variables has no effect."),
+                ast.source().clone(),
+            ),
+        ));
+    }
+
+    /// `If[cond, t, f]`, where `cond` is always `value`.
+    fn check_constant_if(
+        &self,
+        ast: &Ast,
+        cond: &Ast,
+        value: bool,
+        arms: &[Ast],
+        issues: &mut Vec<Issue>,
+    ) {
+        let Some(sev) = self.config.rule_severity(LintRule::ConstantCondition)
+        else {
+            return;
+        };
+
+        // If[True, t, f] -> t, If[False, t, f] -> f, If[False, t] -> Null
+        let taken = if value { arms.first() } else { arms.get(1) };
+
+        let action = match taken {
+            Some(taken) => self.replacement_text(ast, taken),
+            None => self.map.map(|_| "Null".to_owned()),
+        }
+        .zip(span(ast))
+        .map(|(text, src)| {
+            CodeAction::replace_text(
+                format!(
+                    "Replace ``If`` with its ``{}`` branch",
+                    bool_str(value)
+                ),
+                src,
+                text,
+            )
+        });
+
+        let explanation = if value {
+            "Only the ``True`` branch is ever evaluated."
+        } else {
+            "The ``True`` branch is never evaluated."
+        };
+
+        issues.push(lint_issue(
+            LintRule::ConstantCondition,
+            format!("Condition of ``If`` is always ``{}``.", bool_str(value)),
+            sev,
+            cond.source().clone(),
+            action.into_iter().collect(),
+            Annotation::explanation(
+                explanation.to_owned(),
+                ast.source().clone(),
+            ),
+        ));
+    }
+
+    /// The arm of `Which[args]` whose condition is `args[index]`, where the
+    /// condition is always `value`.
+    fn check_constant_which_arm(
+        &self,
+        ast: &Ast,
+        args: &[Ast],
+        index: usize,
+        value: bool,
+        issues: &mut Vec<Issue>,
+    ) {
+        let Some(sev) = self.config.rule_severity(LintRule::ConstantCondition)
+        else {
+            return;
+        };
+
+        let cond = &args[index];
+        let is_last = index + 2 >= args.len();
+
+        // A last arm with condition `True` is the usual way to write a
+        // default value.
+        if value && is_last {
+            return;
+        }
+
+        let action = if value {
+            // Which[c1, v1, True, v2, c3, v3] -> Which[c1, v1, True, v2]
+            span_between(&args[index + 1], args.last().unwrap(), false).map(
+                |src| {
+                    CodeAction::delete_text(
+                        "Remove the arms after this arm".to_owned(),
+                        src,
+                    )
+                },
+            )
+        } else if args.len() == 2 {
+            // Which[False, v] -> Null
+            span(ast).map(|src| {
+                CodeAction::replace_text(
+                    "Replace ``Which`` with ``Null``".to_owned(),
+                    src,
+                    "Null".to_owned(),
+                )
+            })
+        } else {
+            let src = if is_last {
+                // Which[c1, v1, False, v2] -> Which[c1, v1]
+                span_between(&args[index - 1], &args[index + 1], false)
+            } else {
+                // Which[False, v1, c2, v2] -> Which[c2, v2]
+                span_between(cond, &args[index + 2], true)
+            };
+
+            src.map(|src| {
+                CodeAction::delete_text("Remove this arm".to_owned(), src)
+            })
+        };
+
+        let explanation = if value {
+            "The arms after this arm are never evaluated."
+        } else {
+            "The value of this arm is never evaluated."
+        };
+
+        issues.push(lint_issue(
+            LintRule::ConstantCondition,
+            format!(
+                "Condition of ``Which`` arm is always ``{}``.",
+                bool_str(value)
+            ),
+            sev,
+            cond.source().clone(),
+            self.map.and(action).into_iter().collect(),
+            Annotation::explanation(
+                explanation.to_owned(),
+                ast.source().clone(),
+            ),
+        ));
+    }
+
+    /// A comparison of literals whose result is always `value`.
+    fn check_constant_comparison(
+        &self,
+        ast: &Ast,
+        value: bool,
+        issues: &mut Vec<Issue>,
+    ) {
+        let Some(sev) = self.config.rule_severity(LintRule::ConstantComparison)
+        else {
+            return;
+        };
+
+        let action = self.map.and(span(ast)).map(|src| {
+            CodeAction::replace_text(
+                format!("Replace with ``{}``", bool_str(value)),
+                src,
+                bool_str(value).to_owned(),
+            )
+        });
+
+        issues.push(lint_issue(
+            LintRule::ConstantComparison,
+            format!("Comparison is always ``{}``.", bool_str(value)),
+            sev,
+            ast.source().clone(),
+            action.into_iter().collect(),
+            Annotation::explanation(
+                "Both sides of the comparison are literals.".to_owned(),
+                ast.source().clone(),
+            ),
+        ));
+    }
+
+    /// The source text of `ast`, if the concrete syntax is known.
+    fn source_text(&self, ast: &Ast) -> Option<String> {
+        Some(self.map?.cst(ast)?.to_source())
+    }
+
+    /// The source text of `replacement`, parenthesized if needed to replace
+    /// `ast`.
+    fn replacement_text(&self, ast: &Ast, replacement: &Ast) -> Option<String> {
+        let map = self.map?;
+        let node = map.cst(replacement)?;
+        let text = node.to_source();
+
+        let parent =
+            map.cst(ast).and_then(|ast| parent_of(map.concrete(), ast));
+
+        match parent {
+            Some(parent) if needs_parentheses(node, parent) => {
+                Some(format!("({text})"))
+            },
+            _ => Some(text),
+        }
+    }
+
+    fn suspicious_set_issue(&self, ast: &Ast, sev: Severity) -> Issue {
+        // The `=` token of the concrete `lhs = rhs` node.
+        let operator = self.map.and_then(|map| match map.cst(ast)? {
+            Cst::Binary(BinaryNode(OperatorNode {
+                op: BinaryOperator::Set,
+                children,
+            })) => children.0.iter().find_map(|child| match child {
+                Cst::Token(token) if token.tok == TokenKind::Equal => {
+                    Some(token.src)
+                },
+                _ => None,
+            }),
+            _ => None,
+        });
+
+        let actions = operator
+            .map(|src| {
+                CodeAction::replace_text(
+                    "Replace ``=`` with ``:=``".to_owned(),
+                    src,
+                    ":=".to_owned(),
+                )
+            })
+            .into_iter()
+            .collect();
+
+        lint_issue(
+            LintRule::SuspiciousSet,
+            "``Set`` is used to define a pattern that is used on the \
+             right-hand side."
+                .to_owned(),
+            sev,
+            ast.source().clone(),
+            actions,
+            Annotation::explanation(
+                "The right-hand side is evaluated once, when the definition \
+                 is made. Use ``SetDelayed`` (``:=``) to evaluate it each \
+                 time the definition is used."
+                    .to_owned(),
+                ast.source().clone(),
+            ),
+        )
+    }
+}
+
+/// Report each implicit multiplication in `node` whose operands are on
+/// different lines.
+fn check_implicit_times<I>(
+    node: &Cst<I>,
+    sev: Severity,
+    issues: &mut Vec<Issue>,
+) {
+    let Cst::Infix(InfixNode(OperatorNode {
+        op: InfixOperator::Times,
+        children,
+    })) = node
+    else {
+        return;
+    };
+
+    let mut newline = false;
+
+    for (index, child) in children.0.iter().enumerate() {
+        let Cst::Token(token) = child else {
+            newline = false;
+            continue;
+        };
+
+        match token.tok {
+            TokenKind::InternalNewline | TokenKind::ToplevelNewline => {
+                newline = true;
+            },
+            TokenKind::Fake_ImplicitTimes if newline => {
+                // The operand that follows the implicit `*`.
+                let following = &children.0[index + 1..];
+
+                let Some(operand) = following.iter().find(|child| {
+                    !matches!(child, Cst::Token(token) if token.tok.isTrivia())
+                }) else {
+                    continue;
+                };
+
+                let src = Source::Span(operand.get_source());
+
+                issues.push(lint_issue(
+                    LintRule::ImplicitTimesAcrossLines,
+                    "Implicit ``Times`` between operands on different lines."
+                        .to_owned(),
+                    sev,
+                    src.clone(),
+                    vec![CodeAction::insert_text(
+                        "Insert ``*``".to_owned(),
+                        token.src,
+                        "*".to_owned(),
+                    )],
+                    Annotation::suggestion(
+                        "Insert a ``,`` if these are separate expressions."
+                            .to_owned(),
+                        src,
+                    ),
+                ));
+            },
+            tok if tok.isTrivia() => (),
+            _ => newline = false,
+        }
+    }
+}
+
+/// The symbol defined by a definition with left-hand side `lhs`, like `f` in
+/// `f[x_] /; x > 0 := ..` or `HoldPattern[f[x_]] := ..`.
+pub(crate) fn defined_symbol(lhs: &Ast) -> Option<&str> {
+    match lhs.as_symbol_call() {
+        Some((head, [lhs, ..]))
+            if matches!(
+                builtin_name(head),
+                Some("Condition" | "HoldPattern")
+            ) =>
+        {
+            defined_symbol(lhs)
+        },
+        _ => lhs.call_chain().root.as_symbol(),
+    }
+}
+
+/// The value of `cond`, if it is always `True` or always `False`.
+fn constant_condition(cond: &Ast) -> Option<bool> {
+    if let Some(LiteralValue::Boolean(value)) = literal(cond) {
+        return Some(value);
+    }
+
+    let (head, args) = cond.as_symbol_call()?;
+
+    match (builtin_name(head)?, args) {
+        ("Not", [arg]) => constant_condition(arg).map(|value| !value),
+        ("And", args) if !args.is_empty() => args
+            .iter()
+            .map(constant_condition)
+            .collect::<Option<Vec<bool>>>()
+            .map(|values| values.iter().all(|value| *value)),
+        ("Or", args) if !args.is_empty() => args
+            .iter()
+            .map(constant_condition)
+            .collect::<Option<Vec<bool>>>()
+            .map(|values| values.iter().any(|value| *value)),
+        (name, [lhs, rhs]) if COMPARISONS.contains(&name) => {
+            compare(name, lhs, rhs)
+        },
+        _ => None,
+    }
+}
+
+/// The result of the comparison `name[lhs, rhs]`, if both sides are literals
+/// that can be compared.
+fn compare(name: &str, lhs: &Ast, rhs: &Ast) -> Option<bool> {
+    let (lhs, rhs) = (literal(lhs)?, literal(rhs)?);
+
+    let numbers = lhs.as_f64().zip(rhs.as_f64());
+
+    let equal = match (&lhs, &rhs) {
+        (LiteralValue::String(lhs), LiteralValue::String(rhs)) => {
+            Some(lhs == rhs)
+        },
+        (LiteralValue::Boolean(lhs), LiteralValue::Boolean(rhs)) => {
+            Some(lhs == rhs)
+        },
+        _ => numbers.map(|(lhs, rhs)| lhs == rhs),
+    };
+
+    match name {
+        "SameQ" => Some(lhs == rhs),
+        "UnsameQ" => Some(lhs != rhs),
+        "Equal" => equal,
+        "Unequal" => equal.map(|equal| !equal),
+        "Less" => numbers.map(|(lhs, rhs)| lhs < rhs),
+        "LessEqual" => numbers.map(|(lhs, rhs)| lhs <= rhs),
+        "Greater" => numbers.map(|(lhs, rhs)| lhs > rhs),
+        "GreaterEqual" => numbers.map(|(lhs, rhs)| lhs >= rhs),
+        _ => None,
+    }
+}
+
+/// The value of `ast` if it is a literal, like `1`, `"a"`, `True`, or
+/// `{1, 2}`.
+fn literal(ast: &Ast) -> Option<LiteralValue> {
+    fn is_constant(value: &LiteralValue) -> bool {
+        match value {
+            LiteralValue::Symbol(_) => false,
+            LiteralValue::List(elements) => elements.iter().all(is_constant),
+            LiteralValue::Association(map) => map.values().all(is_constant),
+            _ => true,
+        }
+    }
+
+    LiteralValue::from_ast(ast).ok().filter(is_constant)
+}
+
+fn bool_str(value: bool) -> &'static str {
+    if value {
+        "True"
+    } else {
+        "False"
+    }
+}
+
+fn span(ast: &Ast) -> Option<Span> {
+    match ast.source() {
+        Source::Span(span) => Some(*span),
+        _ => None,
+    }
+}
+
+/// The span from the end of `from` to the end of `to`, or from the start of
+/// `from` to the start of `to` if `starts` is true.
+fn span_between(from: &Ast, to: &Ast, starts: bool) -> Option<Span> {
+    let (from, to) = (span(from)?, span(to)?);
+
+    Some(if starts {
+        Span::from_locations(from.start(), to.start())
+    } else {
+        Span::from_locations(from.end(), to.end())
+    })
+}
+
+/// The node in `nodes`, or in their descendants, that `node` is a direct
+/// child of.
+fn parent_of<'n, I, S>(
+    nodes: &'n [Cst<I, S>],
+    node: &Cst<I, S>,
+) -> Option<&'n Cst<I, S>> {
+    fn search<'n, I, S>(
+        parent: &'n Cst<I, S>,
+        node: &Cst<I, S>,
+    ) -> Option<&'n Cst<I, S>> {
+        let children = parent.children();
+
+        if children.iter().any(|child| std::ptr::eq(*child, node)) {
+            return Some(parent);
+        }
+
+        children.into_iter().find_map(|child| search(child, node))
+    }
+
+    nodes.iter().find_map(|parent| search(parent, node))
+}
+
+/// Returns true if `node` must be parenthesized to be an operand of
+/// `context`.
+///
+/// Operators with the same precedence as `context` are parenthesized too,
+/// since e.g. `b - c` can't be the right operand of `a - x` without them.
+fn needs_parentheses<I, S>(node: &Cst<I, S>, context: &Cst<I, S>) -> bool {
+    if !is_operator(node) || !is_operator(context) {
+        return false;
+    }
+
+    match (operator_level(node), operator_level(context)) {
+        (Some(node), Some(context)) => node <= context,
+        // Unknown precedence, e.g. of an implicit operator.
+        _ => true,
+    }
+}
+
+fn is_operator<I, S>(node: &Cst<I, S>) -> bool {
+    matches!(
+        node,
+        Cst::Prefix(_)
+            | Cst::Infix(_)
+            | Cst::Postfix(_)
+            | Cst::Binary(_)
+            | Cst::Ternary(_)
+            | Cst::PrefixBinary(_)
+    )
+}
+
+/// The precedence level of the first operator token of the operator node
+/// `node`.
+fn operator_level<I, S>(node: &Cst<I, S>) -> Option<u8> {
+    let (position, skip) = match node {
+        Cst::Prefix(_) | Cst::PrefixBinary(_) => (OperatorPosition::Prefix, 0),
+        // The first child is the left operand.
+        _ => (OperatorPosition::Infix, 1),
+    };
+
+    let operator = node.children().into_iter().skip(skip).find_map(
+        |child| match child {
+            Cst::Token(token) if !token.tok.isTrivia() => Some(token.tok),
+            _ => None,
+        },
+    )?;
+
+    Some(operator_spec(operator, position)?.precedence?.level)
+}
+
+/// Whether `ast` contains a symbol named one of `names`.
+fn mentions_any(ast: &Ast, names: &[&str]) -> bool {
+    if let Some(name) = ast.as_symbol() {
+        return names.contains(&name);
+    }
+
+    let mut found = false;
+
+    ast.for_each_child(|child| found = found || mentions_any(child, names));
+
+    found
+}
+
+/// The name of `head` if it refers to a `System` symbol, either unqualified
+/// or written as ``System`name``.
+pub(crate) fn builtin_name(head: &str) -> Option<&str> {
+    match head.strip_prefix("System`") {
+        Some(name) => Some(name),
+        None if !head.contains('`') => Some(head),
+        None => None,
+    }
+}
+
+fn side_effect_issue(
+    statement: &Ast,
+    head: Option<&str>,
+    opts: &SideEffectOptions,
+) -> Issue {
+    let msg = match head {
+        Some(head) => format!("``{head}`` is called when the file is loaded."),
+        None => "Expression is evaluated when the file is loaded.".to_owned(),
+    };
+
+    Issue {
+        make_sym: sym::CodeParser_LintIssue,
+        tag: IssueTag::TopLevelSideEffect,
+        msg,
+        sev: opts.severity,
+        src: statement.source().clone(),
+        val: 0.95,
+        actions: Vec::new(),
+        annotations: vec![Annotation::explanation(
+            "Top-level code in a package should only define symbols."
+                .to_owned(),
+            statement.source().clone(),
+        )],
+        additional_sources: Vec::new(),
+    }
+}
+
+fn dynamic_code_issue(
+    call: &Ast,
+    arg: &Ast,
+    rule: DynamicCodeRule,
+    sev: Severity,
+) -> Issue {
+    let (msg, explanation) = match rule {
+        DynamicCodeRule::ToExpression => (
+            "``ToExpression`` is called on input that is not a literal string.",
+            "The input is evaluated as code.",
+        ),
+        DynamicCodeRule::Symbol => (
+            "``Symbol`` is called on a name that is not a literal string.",
+            "The symbol may refer to any function, in any context.",
+        ),
+        DynamicCodeRule::Get => (
+            "``Get`` is called on a path that is not a literal string.",
+            "The file is loaded and evaluated as code.",
+        ),
+    };
+
+    Issue {
+        make_sym: sym::CodeParser_LintIssue,
+        tag: rule.tag(),
+        msg: msg.to_owned(),
+        sev,
+        src: call.source().clone(),
+        val: 0.95,
+        actions: Vec::new(),
+        annotations: vec![Annotation::explanation(
+            explanation.to_owned(),
+            arg.source().clone(),
+        )],
+        additional_sources: Vec::new(),
+    }
+}
+
+fn lint_issue(
+    rule: LintRule,
+    msg: String,
+    sev: Severity,
+    src: Source,
+    actions: Vec<CodeAction>,
+    annotation: Annotation,
+) -> Issue {
+    Issue {
+        make_sym: sym::CodeParser_LintIssue,
+        tag: rule.tag(),
+        msg,
+        sev,
+        src,
+        val: 0.9,
+        actions,
+        annotations: vec![annotation],
+        additional_sources: Vec::new(),
+    }
+}
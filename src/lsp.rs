@@ -0,0 +1,311 @@
+//! Issues as [Language Server Protocol][LSP] diagnostics.
+//!
+//! [`diagnostics()`] converts the [`Issue`]s reported for some input into
+//! [`Diagnostic`]s, whose positions are zero-based lines and columns counted
+//! in UTF-16 code units, as required by the Language Server Protocol:
+//!
+//! ```
+//! use wolfram_parser::{
+//!     lsp::{self, DiagnosticSeverity, Position},
+//!     parse_cst_seq, ParseOptions,
+//! };
+//!
+//! let input = "{\"\u{1F600}\" b}";
+//! let opts = ParseOptions::default().implicit_times(false);
+//!
+//! let result = parse_cst_seq(input, &opts);
+//!
+//! let diagnostics = lsp::diagnostics(input, result.issues(), &opts);
+//!
+//! assert_eq!(diagnostics[0].code, "UnexpectedImplicitTimes");
+//! assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+//!
+//! // The emoji before the `b` is two UTF-16 code units wide.
+//! let position = Position {
+//!     line: 0,
+//!     character: 6,
+//! };
+//!
+//! assert_eq!(diagnostics[0].range.start, position);
+//! assert_eq!(diagnostics[0].fixes[0].new_text, "*");
+//! ```
+//!
+//! With the `serde` feature enabled, these types serialize to the JSON of
+//! the corresponding LSP types, with severities as numbers. The
+//! [`Fix`]es of a diagnostic are `TextEdit`s with a title, which a server
+//! can offer as quick fix code actions.
+//!
+//! Use [`Position`] and [`Range`] directly to convert between LSP positions
+//! and byte offsets or [`Location`]s, e.g. for the positions in requests
+//! from a client.
+//!
+//! [LSP]: https://microsoft.github.io/language-server-protocol/
+
+use std::num::NonZeroU32;
+
+use crate::{
+    issue::{CodeAction, CodeActionKind, Issue, Severity},
+    source::{ColumnUnit, LineColumn, LineMap, Location, Source, Span},
+    ParseOptions, SourceConvention,
+};
+
+/// A zero-based line and column in the input, with the column counted in
+/// UTF-16 code units.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// The input between two [`Position`]s.
+///
+/// The end position is exclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// The severity of a [`Diagnostic`].
+///
+/// Serialized as the number used by the Language Server Protocol.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
+pub enum DiagnosticSeverity {
+    Error = 1,
+    Warning = 2,
+    Information = 3,
+    Hint = 4,
+}
+
+/// An [`Issue`], as a Language Server Protocol diagnostic.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+
+    /// The [tag][crate::issue::IssueTag] of the issue, like
+    /// `"UnexpectedImplicitTimes"`.
+    pub code: String,
+
+    /// The [actions][Issue::actions] of the issue that could be converted,
+    /// in the same order.
+    pub fixes: Vec<Fix>,
+}
+
+/// A [`CodeAction`] of an issue, as a titled text edit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Fix {
+    pub title: String,
+
+    /// The input that is replaced by [`new_text`][Fix::new_text].
+    ///
+    /// This is empty for insertions.
+    pub range: Range,
+
+    /// The text that replaces [`range`][Fix::range].
+    ///
+    /// This is empty for deletions.
+    pub new_text: String,
+}
+
+/// Convert `issues` of `input` to diagnostics.
+///
+/// `opts` must be the options `input` was parsed with, so that source spans
+/// are converted with the same tab width. Issues whose source is not a span
+/// of `input`, like issues in boxes, are skipped.
+pub fn diagnostics<'a>(
+    input: &str,
+    issues: impl IntoIterator<Item = &'a Issue>,
+    opts: &ParseOptions,
+) -> Vec<Diagnostic> {
+    let map = LineMap::new(input, opts);
+
+    issues
+        .into_iter()
+        .filter_map(|issue| Diagnostic::from_issue(&map, issue))
+        .collect()
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Position {
+    /// The position of byte offset `offset`.
+    ///
+    /// Returns `None` if `offset` is outside of the input, or is not on a
+    /// character boundary.
+    pub fn from_offset(map: &LineMap, offset: usize) -> Option<Self> {
+        let LineColumn(line, column) =
+            map.line_column(offset, ColumnUnit::Utf16)?;
+
+        Some(Position {
+            line: line.get() - 1,
+            character: column.get() - 1,
+        })
+    }
+
+    /// The position of `loc`, which uses either [`SourceConvention`].
+    ///
+    /// Returns `None` if `loc` is outside of the input.
+    pub fn from_location(map: &LineMap, loc: Location) -> Option<Self> {
+        Position::from_offset(map, map.offset(loc)?)
+    }
+
+    /// The byte offset of this position.
+    ///
+    /// Returns `None` if this position is outside of the input, or is
+    /// inside of a character.
+    pub fn to_offset(self, map: &LineMap) -> Option<usize> {
+        let Position { line, character } = self;
+
+        let pos = LineColumn(
+            NonZeroU32::new(line.checked_add(1)?)?,
+            NonZeroU32::new(character.checked_add(1)?)?,
+        );
+
+        map.offset_at(pos, ColumnUnit::Utf16)
+    }
+
+    /// The [`Location`] of this position, using `convention`.
+    pub fn to_location(
+        self,
+        map: &LineMap,
+        convention: SourceConvention,
+    ) -> Option<Location> {
+        map.location(self.to_offset(map)?, convention)
+    }
+}
+
+impl Range {
+    /// The range of `span`, which uses either [`SourceConvention`].
+    ///
+    /// Returns `None` if `span` is outside of the input.
+    pub fn from_span(map: &LineMap, span: Span) -> Option<Self> {
+        let (start, end) = span.start_end();
+
+        Some(Range {
+            start: Position::from_location(map, start)?,
+            end: Position::from_location(map, end)?,
+        })
+    }
+
+    /// The byte offsets of this range.
+    pub fn to_offsets(self, map: &LineMap) -> Option<std::ops::Range<usize>> {
+        Some(self.start.to_offset(map)?..self.end.to_offset(map)?)
+    }
+
+    /// The [`Span`] of this range, using `convention`.
+    pub fn to_span(
+        self,
+        map: &LineMap,
+        convention: SourceConvention,
+    ) -> Option<Span> {
+        Some(Span::new(
+            self.start.to_location(map, convention)?,
+            self.end.to_location(map, convention)?,
+        ))
+    }
+}
+
+impl Diagnostic {
+    /// Convert `issue` to a diagnostic.
+    ///
+    /// Returns `None` if the source of `issue` is not a span of the input of
+    /// `map`.
+    pub fn from_issue(map: &LineMap, issue: &Issue) -> Option<Self> {
+        let Source::Span(span) = issue.src else {
+            return None;
+        };
+
+        let fixes = issue
+            .actions
+            .iter()
+            .filter_map(|action| Fix::from_code_action(map, action))
+            .collect();
+
+        Some(Diagnostic {
+            range: Range::from_span(map, span)?,
+            severity: DiagnosticSeverity::from(issue.sev),
+            message: issue.msg.clone(),
+            code: issue.tag.as_str().to_owned(),
+            fixes,
+        })
+    }
+}
+
+impl Fix {
+    /// Convert `action` to a fix.
+    ///
+    /// Returns `None` if the span of `action` is outside of the input of
+    /// `map`.
+    pub fn from_code_action(
+        map: &LineMap,
+        action: &CodeAction,
+    ) -> Option<Self> {
+        let range = Range::from_span(map, action.src)?;
+
+        let (range, new_text) = match &action.kind {
+            CodeActionKind::ReplaceText { replacement_text } => {
+                (range, replacement_text.clone())
+            },
+            CodeActionKind::InsertText { insertion_text } => {
+                let start = range.start;
+
+                (Range { start, end: start }, insertion_text.clone())
+            },
+            CodeActionKind::DeleteText => (range, String::new()),
+        };
+
+        Some(Fix {
+            title: action.label.clone(),
+            range,
+            new_text,
+        })
+    }
+}
+
+/// Fatal issues and errors are errors, and formatting issues are hints.
+impl From<Severity> for DiagnosticSeverity {
+    fn from(sev: Severity) -> Self {
+        match sev {
+            Severity::Fatal | Severity::Error => DiagnosticSeverity::Error,
+            Severity::Warning => DiagnosticSeverity::Warning,
+            Severity::Remark => DiagnosticSeverity::Information,
+            Severity::Formatting => DiagnosticSeverity::Hint,
+        }
+    }
+}
+
+impl From<DiagnosticSeverity> for u8 {
+    fn from(sev: DiagnosticSeverity) -> u8 {
+        sev as u8
+    }
+}
+
+impl TryFrom<u8> for DiagnosticSeverity {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, String> {
+        let sev = match value {
+            1 => DiagnosticSeverity::Error,
+            2 => DiagnosticSeverity::Warning,
+            3 => DiagnosticSeverity::Information,
+            4 => DiagnosticSeverity::Hint,
+            _ => return Err(format!("invalid diagnostic severity: {value}")),
+        };
+
+        Ok(sev)
+    }
+}
@@ -1,3 +1,5 @@
+use crate::tokenize::TokenKind;
+
 /// Convenience constructor for one of the crate source location types.
 ///
 /// # Examples
@@ -21,12 +23,23 @@
 /// let span: LineColumnSpan = src!(1:3-8);
 /// ```
 ///
-/// Construct a [`CharacterSpan`][crate::source::CharacterSpan] span:
+/// Construct a [`CharIndexSpan`][crate::source::CharIndexSpan] span:
 ///
 /// ```
-/// # use wolfram_parser::{macros::src, source::CharacterSpan};
+/// # use wolfram_parser::{macros::src, source::CharIndexSpan};
 /// // Characters 1 through 4
-/// let span: CharacterSpan = src!(1-4);
+/// let span: CharIndexSpan = src!(1-4);
+/// ```
+///
+/// Construct a [`ByteSpan`][crate::source::ByteSpan] from 0-based byte
+/// offsets, like those returned by [`str::find()`]:
+///
+/// ```
+/// # use wolfram_parser::{macros::src, source::ByteSpan};
+/// // Bytes 0 through 3 (exclusive)
+/// let span: ByteSpan = src!(byte 0..3);
+///
+/// assert_eq!(span, ByteSpan { start: 0, end: 3 });
 /// ```
 ///
 /// ## Boxes
@@ -88,10 +101,15 @@ macro_rules! __src {
     // TODO: Pick only one of these syntaxes to use
     // a-b  OR  a..b
     ($start:literal - $end:literal) => {
-        $crate::source::CharacterSpan($start, $end)
+        $crate::source::CharIndexSpan($start, $end)
     };
     ($start:literal .. $end:literal) => {
-        $crate::source::CharacterSpan($start, $end)
+        $crate::source::CharIndexSpan($start, $end)
+    };
+
+    // byte a..b
+    (byte $start:literal .. $end:literal) => {
+        $crate::source::ByteSpan { start: $start, end: $end }
     };
 
     //==================================
@@ -116,6 +134,11 @@ macro_rules! __src {
     (Before[{$($value:literal),* }]) => {
         $crate::source::BoxPosition::Before(vec![$($value),*])
     };
+
+    // After[{a, b, c, ...}]
+    (After[{$($value:literal),* }]) => {
+        $crate::source::BoxPosition::After(vec![$($value),*])
+    };
 }
 
 /// Convenience constructor for [`Token`][crate::tokenize::Token]s.
@@ -132,6 +155,23 @@ macro_rules! __src {
 /// * `...` — input content
 /// * `***` — [`Source`][crate::source::Source] of the token; supports [`src!`] syntax
 ///
+/// A token with a `byte a..b` source has a
+/// [`ByteSpan`][crate::source::ByteSpan] instead of a
+/// [`Span`][crate::source::Span]. Use
+/// [`LineMap::byte_span()`][crate::source::LineMap::byte_span] to compare it
+/// with tokens produced by the tokenizer.
+///
+/// Long name operators can be written as `LongName[Name]`, in which case the
+/// input content is the `\[Name]` spelling:
+///
+/// ```
+/// # use wolfram_parser::macros::{src, token};
+/// assert_eq!(
+///     token!(LongName[Integral], 1:1-1:12),
+///     token!(LongName_Integral, "\\[Integral]", 1:1-1:12),
+/// );
+/// ```
+///
 /// # Example
 ///
 /// ```
@@ -151,40 +191,110 @@ macro_rules! __src {
 ///     token!(Integer, b"1", 1:5-1:6),
 /// ]);
 /// ```
+///
+/// # Validation
+///
+/// Literal input content is checked at compile time against the token kind,
+/// so a fixture can't contain a token the tokenizer would never produce:
+///
+/// ```compile_fail
+/// # use wolfram_parser::macros::{src, token};
+/// let token = token!(Plus, "-", 1:1-1:2);
+/// ```
+///
+/// ```compile_fail
+/// # use wolfram_parser::macros::{src, token};
+/// let token = token!(LongName[NotALongName], 1:1-1:17);
+/// ```
+///
+/// The check is conservative: operators must have their usual spelling,
+/// implicit tokens must be empty, integers must start with a digit, and
+/// comments must start with `(*`. Input containing escapes or non-ASCII
+/// characters is only checked for long names.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __token {
-    // token!(Kind, "...", 1:1-3:2)
-    ($kind:ident, $input:tt, $l1:literal : $c1:literal  -  $l2:literal : $c2:literal) => {
-        $crate::macros::token!($kind, $input, $crate::macros::src!($l1:$c1-$l2:$c2))
+    // token!(@kind TokenKind::Plus, "...", ...)
+    (@kind $tok:expr, $input:tt, $l1:literal : $c1:literal  -  $l2:literal : $c2:literal) => {
+        $crate::macros::token!(@kind $tok, $input, $crate::macros::src!($l1:$c1-$l2:$c2))
     };
 
-    // token!(Kind, "...", 1:1-2)
-    ($kind:ident, $input:tt, $l1:literal : $c1:literal  -  $c2:literal) => {
-        $crate::macros::token!($kind, $input, $crate::macros::src!($l1:$c1-$c2))
+    (@kind $tok:expr, $input:tt, $l1:literal : $c1:literal  -  $c2:literal) => {
+        $crate::macros::token!(@kind $tok, $input, $crate::macros::src!($l1:$c1-$c2))
     };
 
-    // token!(Kind, "...", 1..3)
-    ($kind:ident, $input:tt, $start:literal .. $end:literal) => {
-        $crate::macros::token!($kind, $input, $crate::macros::src!($start .. $end))
+    (@kind $tok:expr, $input:tt, $start:literal .. $end:literal) => {
+        $crate::macros::token!(@kind $tok, $input, $crate::macros::src!($start .. $end))
     };
 
-    // token!(Kind, "...", {1, 2, 3})
-    ($kind:ident, $input:tt, {$($value:literal),*}) => {
+    (@kind $tok:expr, $input:tt, byte $start:literal .. $end:literal) => {
         $crate::tokenize::Token {
-            tok: $crate::tokenize::TokenKind::$kind,
+            tok: $tok,
+            src: $crate::macros::src!(byte $start .. $end),
+            input: $crate::tokenize::TokenStr::new($input.as_ref()),
+        }
+    };
+
+    (@kind $tok:expr, $input:tt, {$($value:literal),*}) => {
+        $crate::tokenize::Token {
+            tok: $tok,
             src: $crate::source::Source::from($crate::macros::src!({$($value),*})),
             input: $crate::tokenize::TokenStr::new($input.as_ref()),
         }
     };
 
-    ($kind:ident, $input:tt, $src:expr) => {
+    (@kind $tok:expr, $input:tt, $src:expr) => {
         $crate::tokenize::Token {
-            tok: $crate::tokenize::TokenKind::$kind,
+            tok: $tok,
             src: $crate::source::Span::from($src),
             input: $crate::tokenize::TokenStr::new($input.as_ref()),
         }
     };
+
+    // token!(LongName[Integral], 1:1-1:12)
+    (LongName[$name:ident], $($src:tt)+) => {
+        $crate::macros::token!(
+            @kind {
+                const KIND: $crate::tokenize::TokenKind =
+                    $crate::macros::long_name_kind(stringify!($name));
+                KIND
+            },
+            (concat!("\\[", stringify!($name), "]")),
+            $($src)+
+        )
+    };
+
+    // token!(Kind, "...", 1:1-3:2)
+    // token!(Kind, "...", 1:1-2)
+    // token!(Kind, "...", 1..3)
+    // token!(Kind, "...", byte 0..2)
+    // token!(Kind, "...", {1, 2, 3})
+    ($kind:ident, $input:tt, $($src:tt)+) => {{
+        $crate::__check_token_text!($kind, $input);
+
+        $crate::macros::token!(
+            @kind $crate::tokenize::TokenKind::$kind,
+            $input,
+            $($src)+
+        )
+    }};
+}
+
+/// Fail to compile if `$input` is a literal that is not valid text for a
+/// `$kind` token.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_token_text {
+    ($kind:ident, $input:literal) => {
+        const _: () = assert!(
+            $crate::macros::TokenText($input)
+                .is_valid($crate::tokenize::TokenKind::$kind),
+            concat!("invalid `", stringify!($kind), "` token text"),
+        );
+    };
+
+    // Non-literal input, like `[0xff]`, is not checked.
+    ($kind:ident, $input:tt) => {};
 }
 
 
@@ -251,7 +361,134 @@ macro_rules! __leaf {
     };
 }
 
+//======================================
+// Compile-time checks
+//======================================
+
+/// Token input content checked by [`token!`].
+#[doc(hidden)]
+pub struct TokenText<T>(pub T);
+
+impl TokenText<&str> {
+    pub const fn is_valid(&self, kind: TokenKind) -> bool {
+        is_valid_token_text(kind, self.0.as_bytes())
+    }
+}
+
+impl<const N: usize> TokenText<&[u8; N]> {
+    pub const fn is_valid(&self, kind: TokenKind) -> bool {
+        is_valid_token_text(kind, self.0)
+    }
+}
+
+/// Get the `LongName_*` token kind for the long name `name`.
+///
+/// # Panics
+///
+/// Panics if there is no such token kind. Used in constants by [`token!`],
+/// where this is a compile error.
+#[doc(hidden)]
+pub const fn long_name_kind(name: &str) -> TokenKind {
+    let mut index = 0;
+
+    while index < TokenKind::VARIANTS.len() {
+        let kind = TokenKind::VARIANTS[index];
+
+        if let Some(suffix) = long_name_suffix(kind) {
+            if suffix.len() == name.len()
+                && bytes_eq(suffix, 0, name.as_bytes())
+            {
+                return kind;
+            }
+        }
+
+        index += 1;
+    }
+
+    panic!("no LongName_* token kind with that name")
+}
+
+/// Whether the tokenizer could produce a `kind` token with input `text`.
+///
+/// This only rejects input that is certainly wrong, and accepts input
+/// containing escapes or non-ASCII characters, which can spell many tokens.
+const fn is_valid_token_text(kind: TokenKind, text: &[u8]) -> bool {
+    // \[Name]
+    if let Some(name) = long_name_suffix(kind) {
+        if text.len() >= 2 && text[0] == b'\\' && text[1] == b'[' {
+            return text.len() == name.len() + 3
+                && bytes_eq(text, 2, name)
+                && text[text.len() - 1] == b']';
+        }
+    }
+
+    if matches!(
+        kind,
+        TokenKind::Fake_ImplicitTimes
+            | TokenKind::Fake_ImplicitNull
+            | TokenKind::Fake_ImplicitOne
+            | TokenKind::Fake_ImplicitAll
+    ) {
+        return text.is_empty();
+    }
+
+    let mut index = 0;
+
+    while index < text.len() {
+        if text[index] == b'\\' || !text[index].is_ascii() {
+            return true;
+        }
+
+        index += 1;
+    }
+
+    if let Some(spelling) = kind.spelling() {
+        return text.len() == spelling.len()
+            && bytes_eq(text, 0, spelling.as_bytes());
+    }
+
+    match kind {
+        TokenKind::Integer => !text.is_empty() && text[0].is_ascii_digit(),
+        TokenKind::Comment => {
+            text.len() >= 2 && text[0] == b'(' && text[1] == b'*'
+        },
+        _ => true,
+    }
+}
+
+/// The `Name` in a `LongName_Name` token kind.
+const fn long_name_suffix(kind: TokenKind) -> Option<&'static [u8]> {
+    const PREFIX: &[u8] = b"LongName_";
 
+    let name = kind.name().as_bytes();
+
+    if name.len() <= PREFIX.len() || !bytes_eq(name, 0, PREFIX) {
+        return None;
+    }
+
+    let (_, suffix) = name.split_at(PREFIX.len());
+
+    Some(suffix)
+}
+
+/// Whether `text` contains `expected` starting at `offset`.
+const fn bytes_eq(text: &[u8], offset: usize, expected: &[u8]) -> bool {
+    if text.len() < offset + expected.len() {
+        return false;
+    }
+
+    let mut index = 0;
+
+    while index < expected.len() {
+        if text[offset + index] != expected[index] {
+            return false;
+        }
+
+        index += 1;
+    }
+
+    true
+}
 
 // Publicly export these macros from `wolfram_parser::macros` *without* also
 // publicly exporting them from the root `wolfram_parser` module.
@@ -0,0 +1,285 @@
+//! User-defined operator notations (experimental).
+//!
+//! Packages that define notation, like domain-specific languages, can extend
+//! the grammar understood by the kernel at runtime. [`Notations`] describes
+//! such extensions to the parser, so that code using them can be parsed and
+//! analyzed without the package being loaded.
+//!
+//! A notation gives an existing operator or symbol token a new meaning as a
+//! prefix or infix operator, with a precedence and a target symbol. Notations
+//! are used when parsing with [`ParseOptions::notations()`], and take priority
+//! over the built-in meaning of their token.
+//!
+//! ```
+//! use wolfram_parser::{
+//!     explain::{Associativity, OperatorPrecedence},
+//!     notation::Notations,
+//!     parse::spec::OperatorPosition,
+//!     parse_ast, ParseOptions,
+//! };
+//!
+//! let mut notations = Notations::new();
+//!
+//! notations
+//!     .register(
+//!         "\\[Wolf]",
+//!         OperatorPosition::Infix,
+//!         OperatorPrecedence {
+//!             level: 20,
+//!             associativity: Associativity::NonRight,
+//!         },
+//!         "Pack`Howl",
+//!     )
+//!     .unwrap();
+//!
+//! let opts = ParseOptions::default().notations(notations);
+//!
+//! // Parsed as  Pack`Howl[a, Plus[b, c]]
+//! let ast = parse_ast("a \\[Wolf] b + c", &opts).syntax;
+//!
+//! let (head, args) = ast.as_symbol_call().unwrap();
+//!
+//! assert_eq!(head, "Pack`Howl");
+//! assert_eq!(args[0].as_symbol(), Some("a"));
+//! assert_eq!(args[1].as_symbol_call().unwrap().0, "Plus");
+//! ```
+//!
+//! This API is experimental: notations are currently limited to a single
+//! token, and may change in future versions.
+
+use std::{cell::RefCell, sync::Arc};
+
+use thiserror::Error;
+use wolfram_expr::Symbol;
+
+use crate::{
+    explain::{Associativity, OperatorPrecedence},
+    parse::{
+        infix_parselet_class, prefix_parselet_class, spec::OperatorPosition,
+        ParseletClass,
+    },
+    precedence::Precedence,
+    tokenize::{Token, TokenInput, TokenKind},
+    NodeSeq, ParseOptions,
+};
+
+thread_local! {
+    // TODO(cleanup): Pass these to abstraction as a normal argument, like
+    //                quirk settings should be.
+    static ACTIVE_NOTATIONS: RefCell<Option<Arc<Notations>>> =
+        RefCell::new(None);
+}
+
+/// A set of user-defined operator notations.
+///
+/// See the [module documentation][self].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Notations {
+    notations: Vec<Notation>,
+}
+
+/// A user-defined prefix or infix operator.
+///
+/// See [`Notations::register()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notation {
+    /// The source text of the operator token.
+    pub text: String,
+    /// Whether the operator is prefix, like `-` in `-a`, or infix, like `+`
+    /// in `a + b`.
+    pub position: OperatorPosition,
+    pub precedence: OperatorPrecedence,
+    /// The head of the abstract syntax produced by the operator.
+    pub symbol: Symbol,
+
+    kind: TokenKind,
+}
+
+/// Error returned when registering an invalid [`Notation`].
+#[derive(Error, Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum NotationError {
+    /// The operator text is not a single symbol or operator token.
+    #[error("`{0}` is not a single symbol or operator token")]
+    InvalidToken(String),
+
+    /// The precedence level is outside of the range `1..=127`.
+    #[error("invalid precedence level {0}: must be between 1 and 127")]
+    InvalidPrecedence(u8),
+
+    /// The target symbol is not a fully qualified symbol name, like
+    /// ``Pack`Op``.
+    #[error("`{0}` is not a fully qualified symbol")]
+    InvalidSymbol(String),
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Notations {
+    pub fn new() -> Self {
+        Notations::default()
+    }
+
+    /// Register `text` as a `position` operator with `precedence`, which is
+    /// abstracted to a call to `symbol`.
+    ///
+    /// `text` must be a single symbol token, like `dot` or `\[Wolf]`, or a
+    /// single operator token, like `**` or `\[CirclePlus]`. Operator tokens
+    /// are matched by kind, so any spelling of the operator is accepted;
+    /// symbols are matched by their exact text. Tokens with special syntax,
+    /// like groups, `=`, `;`, or `_`, cannot be used.
+    ///
+    /// Registering a notation for a token and position that already has one
+    /// replaces it.
+    ///
+    /// Prefix notations produce a
+    /// [`PrefixNode`][crate::cst::PrefixNode] with operator
+    /// [`CodeParser_PrefixNotation`][crate::parse::operators::PrefixOperator::CodeParser_PrefixNotation],
+    /// and infix notations produce a [`BinaryNode`][crate::cst::BinaryNode]
+    /// with operator
+    /// [`CodeParser_BinaryNotation`][crate::parse::operators::BinaryOperator::CodeParser_BinaryNotation].
+    pub fn register(
+        &mut self,
+        text: &str,
+        position: OperatorPosition,
+        precedence: OperatorPrecedence,
+        symbol: &str,
+    ) -> Result<(), NotationError> {
+        let kind = notation_token_kind(text)
+            .ok_or_else(|| NotationError::InvalidToken(text.to_owned()))?;
+
+        if !(1..=127).contains(&precedence.level) {
+            return Err(NotationError::InvalidPrecedence(precedence.level));
+        }
+
+        let symbol = Symbol::try_new(symbol)
+            .ok_or_else(|| NotationError::InvalidSymbol(symbol.to_owned()))?;
+
+        let notation = Notation {
+            text: text.to_owned(),
+            position,
+            precedence,
+            symbol,
+            kind,
+        };
+
+        self.notations.retain(|other| {
+            !(other.position == position && other.matches_notation(&notation))
+        });
+
+        self.notations.push(notation);
+
+        Ok(())
+    }
+
+    /// Iterate over the registered notations, in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &Notation> {
+        self.notations.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notations.is_empty()
+    }
+
+    /// Find the notation for `token` when it appears in `position`.
+    pub(crate) fn find<I: TokenInput, S>(
+        &self,
+        position: OperatorPosition,
+        token: &Token<I, S>,
+    ) -> Option<&Notation> {
+        self.notations.iter().find(|notation| {
+            notation.position == position
+                && notation.matches(token.tok, token.input.as_bytes())
+        })
+    }
+}
+
+impl Notation {
+    pub(crate) fn parser_precedence(&self) -> Precedence {
+        let OperatorPrecedence {
+            level,
+            associativity,
+        } = self.precedence;
+
+        let right = match associativity {
+            Associativity::NonRight => 0,
+            Associativity::Right => 1,
+        };
+
+        Precedence::new(level << 1 | right)
+    }
+
+    fn matches(&self, kind: TokenKind, text: &[u8]) -> bool {
+        self.kind == kind
+            && (kind != TokenKind::Symbol || self.text.as_bytes() == text)
+    }
+
+    fn matches_notation(&self, other: &Notation) -> bool {
+        self.matches(other.kind, other.text.as_bytes())
+    }
+}
+
+//======================================
+// Abstraction
+//======================================
+
+/// Call `f` with `notations` used to abstract notation nodes.
+pub(crate) fn with_notations<T>(
+    notations: Option<&Arc<Notations>>,
+    f: impl FnOnce() -> T,
+) -> T {
+    let previous = ACTIVE_NOTATIONS.replace(notations.cloned());
+
+    let value = f();
+
+    ACTIVE_NOTATIONS.set(previous);
+
+    value
+}
+
+/// Get the target symbol of the notation for `token` in `position`, from the
+/// notations set by [`with_notations()`].
+pub(crate) fn target_symbol<I: TokenInput, S>(
+    position: OperatorPosition,
+    token: &Token<I, S>,
+) -> Option<Symbol> {
+    ACTIVE_NOTATIONS.with_borrow(|notations| {
+        let notation = notations.as_ref()?.find(position, token)?;
+
+        Some(notation.symbol.clone())
+    })
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// The kind of the single token in `text`, if it can be used as a notation.
+fn notation_token_kind(text: &str) -> Option<TokenKind> {
+    let NodeSeq(tokens) =
+        crate::tokenize(text, &ParseOptions::builtin_default());
+
+    let [token] = tokens.as_slice() else {
+        return None;
+    };
+
+    let prefix_ok = matches!(
+        prefix_parselet_class(token.tok),
+        ParseletClass::Symbol
+            | ParseletClass::PrefixOperator
+            | ParseletClass::PrefixUnhandled
+    );
+
+    let infix_ok = matches!(
+        infix_parselet_class(token.tok),
+        ParseletClass::InfixImplicitTimes
+            | ParseletClass::BinaryOperator
+            | ParseletClass::InfixOperator
+            | ParseletClass::PostfixOperator
+            | ParseletClass::Times
+    );
+
+    (prefix_ok && infix_ok).then_some(token.tok)
+}
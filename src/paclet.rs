@@ -1,9 +1,20 @@
 //! Packlet decoding functionality for handling encoded Wolfram Language files.
 //!
-//! This module provides functionality to detect and decode paclet-encoded files,
+//! This module provides functionality to detect, decode, and encode paclet-encoded files,
 //! which use a Huffman + base-95 encoding scheme to compress Wolfram Language source code.
 
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+
 use crate::error_handling::{ParseError, EncodingError};
+use crate::long_names;
+use crate::read::code_point::CodePoint;
+use crate::source::{LineMap, Location, Span};
+use crate::{ParseOptions, SourceConvention};
 
 /// Huffman table for paclet decoding - maps ASCII char codes (0-127) to bit strings
 const HUFFMAN_TABLE: &[&str] = &[
@@ -29,53 +40,149 @@ const HUFFMAN_TABLE: &[&str] = &[
     "0111111110", "1111111", "0111111100101", "000000", "111111010110011", "111111010110010000000"
 ];
 
-/// Huffman tree node for decoding
-#[derive(Debug)]
-struct HuffmanNode {
-    left: Option<Box<HuffmanNode>>,
-    right: Option<Box<HuffmanNode>>,
-    value: Option<char>,
+/// Header written by [`encode_paclet`]
+const ENCODED_HEADER: &str = "(*!1N!*)mcm\n";
+
+/// Length of the lines of the encoded body written by [`encode_paclet`]
+const ENCODED_LINE_LENGTH: usize = 70;
+
+/// Number of bits looked up at once in [`HuffmanDecoder::lut`]
+const LUT_BITS: usize = 12;
+
+/// Length of the longest code in [`HUFFMAN_TABLE`]
+const MAX_CODE_LENGTH: usize = 22;
+
+/// Table-driven decoder for [`HUFFMAN_TABLE`]
+static HUFFMAN_DECODER: Lazy<HuffmanDecoder> = Lazy::new(HuffmanDecoder::new);
+
+/// Huffman decoder that looks up the next [`LUT_BITS`] bits at once
+struct HuffmanDecoder {
+    /// Indexed by the next `LUT_BITS` bits: the decoded character and its code
+    /// length, or a code length of 0 if the code is longer than `LUT_BITS`
+    lut: Vec<(u8, u8)>,
+    /// Codes longer than `LUT_BITS`: maps (code length, code) to the character
+    long_codes: HashMap<(usize, u32), u8>,
 }
 
-impl HuffmanNode {
-    fn new() -> Self {
-        HuffmanNode {
-            left: None,
-            right: None,
-            value: None,
-        }
-    }
+/// Buffer of bits that have not been decoded yet
+///
+/// The `len` low bits of `bits` are valid, and the first bit in the stream is
+/// the most significant of them.
+#[derive(Default)]
+struct BitBuffer {
+    bits: u64,
+    len: usize,
 }
 
-/// Build the Huffman tree for decoding
-fn build_huffman_tree() -> HuffmanNode {
-    let mut root = HuffmanNode::new();
-    
-    for (ascii_code, bit_string) in HUFFMAN_TABLE.iter().enumerate() {
-        let mut current = &mut root;
+/// The result of [`HuffmanDecoder::decode`]
+enum Decoded {
+    Char(u8),
+    /// More bits are needed to decode the next character
+    NeedBits,
+    /// The EOT character, or bits that are not the prefix of any code (padding)
+    End,
+}
+
+impl HuffmanDecoder {
+    fn new() -> Self {
+        let mut lut = vec![(0u8, 0u8); 1 << LUT_BITS];
+        let mut long_codes = HashMap::new();
         
-        for bit in bit_string.chars() {
-            match bit {
-                '0' => {
-                    if current.left.is_none() {
-                        current.left = Some(Box::new(HuffmanNode::new()));
-                    }
-                    current = current.left.as_mut().unwrap();
-                }
-                '1' => {
-                    if current.right.is_none() {
-                        current.right = Some(Box::new(HuffmanNode::new()));
-                    }
-                    current = current.right.as_mut().unwrap();
+        for (ascii_code, bit_string) in HUFFMAN_TABLE.iter().enumerate() {
+            let len = bit_string.len();
+            let code = u32::from_str_radix(bit_string, 2).unwrap();
+            
+            if len <= LUT_BITS {
+                // Every entry that starts with this code decodes to it
+                let first = (code as usize) << (LUT_BITS - len);
+                for entry in &mut lut[first..first + (1 << (LUT_BITS - len))] {
+                    *entry = (ascii_code as u8, len as u8);
                 }
-                _ => return root, // Invalid bit character, return partial tree
+            } else {
+                long_codes.insert((len, code), ascii_code as u8);
             }
         }
         
-        current.value = Some(ascii_code as u8 as char);
+        HuffmanDecoder { lut, long_codes }
+    }
+    
+    /// Decode the next character from `buffer`
+    ///
+    /// If `at_end` is true, no more bits will be added to `buffer`.
+    fn decode(&self, buffer: &mut BitBuffer, at_end: bool) -> Decoded {
+        let BitBuffer { bits, len } = *buffer;
+        
+        let code_len = if len >= LUT_BITS {
+            self.lut[(bits >> (len - LUT_BITS)) as usize & ((1 << LUT_BITS) - 1)].1 as usize
+        } else {
+            // Pad with zeros; a code longer than `len` is rejected below
+            self.lut[(bits << (LUT_BITS - len)) as usize & ((1 << LUT_BITS) - 1)].1 as usize
+        };
+        
+        let found = if code_len != 0 {
+            (code_len <= len).then_some(code_len)
+        } else {
+            (LUT_BITS + 1..=len.min(MAX_CODE_LENGTH)).find(|&code_len| {
+                let code = (bits >> (len - code_len)) as u32 & ((1 << code_len) - 1);
+                self.long_codes.contains_key(&(code_len, code))
+            })
+        };
+        
+        let Some(code_len) = found else {
+            return if at_end || len >= MAX_CODE_LENGTH {
+                Decoded::End
+            } else {
+                Decoded::NeedBits
+            };
+        };
+        
+        let code = (bits >> (len - code_len)) as u32 & ((1 << code_len) - 1);
+        
+        let ch = if code_len <= LUT_BITS {
+            self.lut[(code as usize) << (LUT_BITS - code_len)].0
+        } else {
+            self.long_codes[&(code_len, code)]
+        };
+        
+        buffer.len -= code_len;
+        buffer.bits &= (1 << buffer.len) - 1;
+        
+        // Check for EOT character (ASCII 4) - end of transmission
+        if ch == 4 {
+            Decoded::End
+        } else {
+            Decoded::Char(ch)
+        }
+    }
+}
+
+impl BitBuffer {
+    /// Add the 13 bits of a base-95 value, least significant bit first (as per
+    /// encoding algorithm)
+    fn push_value(&mut self, value: u32) {
+        let reversed = value.reverse_bits() >> (32 - 13);
+        
+        self.bits = (self.bits << 13) | u64::from(reversed);
+        self.len += 13;
     }
+}
+
+/// Decode a pair of characters of the paclet body to a 13 bit value
+fn base95_value(c1: u8, c2: u8) -> Result<u32, EncodingError> {
+    let base = 95u32;
+    let offset = 32u32;
     
-    root
+    match (u32::from(c1).checked_sub(offset), u32::from(c2).checked_sub(offset)) {
+        (Some(a), Some(b)) if a < base && b < base && a * base + b < (1 << 13) => {
+            Ok(a * base + b)
+        }
+        _ => Err(EncodingError::DecodeError {
+            details: format!(
+                "Invalid character values in paclet body: {} {}",
+                c1 as char, c2 as char
+            ),
+        }),
+    }
 }
 
 /// Represents the version and table variant of a paclet header
@@ -102,18 +209,15 @@ pub fn detect_paclet_header(input: &str) -> Option<PackletHeader> {
     }
     
     // Check for the basic pattern
-    if !input.starts_with("(*!") || !input[5..].starts_with("!*)") {
+    if !input.starts_with("(*!") || !input.get(5..).is_some_and(|rest| rest.starts_with("!*)")) {
         return None;
     }
     
     // Extract version and variant characters
-    let chars: Vec<char> = input.chars().collect();
-    if chars.len() < 8 {
-        return None;
-    }
+    let mut chars = input[3..5].chars();
     
-    let version = chars[3];
-    let variant = chars[4];
+    let version = chars.next()?;
+    let variant = chars.next()?;
     
     Some(PackletHeader { version, variant })
 }
@@ -122,6 +226,123 @@ pub fn detect_paclet_header(input: &str) -> Option<PackletHeader> {
 /// 
 /// This function assumes the input has already been verified to have a valid paclet header
 pub fn decode_paclet(content: &str) -> Result<String, ParseError> {
+    let body_start = paclet_body_start(content)?;
+    
+    decode_body(&content[body_start..], |_| ())
+}
+
+/// Decode a paclet-encoded string, keeping track of where each decoded character came from
+///
+/// The returned [`PacletSourceMap`] maps spans of the decoded source, like the
+/// spans of the issues found when parsing it, back to the lines of the encoded
+/// body they were decoded from. `opts` must be the options the decoded source is
+/// parsed with.
+///
+/// ```
+/// use wolfram_parser::{macros::src, paclet, source::Span, ParseOptions};
+///
+/// let source = "f[x_] := x + 1\n".repeat(10) + "g[y_] := y +";
+/// let encoded = paclet::encode_paclet(&source).unwrap();
+///
+/// let opts = ParseOptions::default();
+/// let (decoded, source_map) = paclet::decode_paclet_with_source_map(&encoded, &opts).unwrap();
+///
+/// assert_eq!(decoded, source);
+///
+/// // The `+` at the end of the decoded source was decoded from two pairs near
+/// // the end of the third line of the encoded file, counting the header.
+/// let span = source_map.encoded_span(Span::from(src!(11:12-11:13))).unwrap();
+///
+/// assert_eq!(span, Span::from(src!(3:65-3:69)));
+/// ```
+pub fn decode_paclet_with_source_map(
+    content: &str,
+    opts: &ParseOptions,
+) -> Result<(String, PacletSourceMap), ParseError> {
+    let body_start = paclet_body_start(content)?;
+    
+    // Offset in `content` of each character of the body, skipping newlines
+    let body_offsets: Vec<usize> = content
+        .bytes()
+        .enumerate()
+        .skip(body_start)
+        .filter(|&(_, c)| c != b'\n' && c != b'\r')
+        .map(|(offset, _)| offset)
+        .collect();
+    
+    let mut origins = Vec::new();
+    
+    let decoded = decode_body(&content[body_start..], |pairs| {
+        origins.push(body_offsets[2 * pairs.start]..body_offsets[2 * pairs.end - 1] + 1);
+    })?;
+    
+    let end = origins.last().map_or(body_start, |origin: &Range<usize>| origin.end);
+    
+    let source_map = PacletSourceMap {
+        origins,
+        end,
+        decoded: LineMap::new(&decoded, opts),
+        encoded: LineMap::new(content, opts),
+    };
+    
+    Ok((decoded, source_map))
+}
+
+/// Map from positions in a decoded paclet to positions in the encoded content,
+/// as returned by [`decode_paclet_with_source_map`]
+#[derive(Debug, Clone)]
+pub struct PacletSourceMap {
+    /// For each byte of the decoded source, the bytes of the encoded content
+    /// holding its bits
+    origins: Vec<Range<usize>>,
+    /// Offset in the encoded content of the end of the decoded source
+    end: usize,
+    decoded: LineMap,
+    encoded: LineMap,
+}
+
+impl PacletSourceMap {
+    /// Get the bytes of the encoded content that the decoded bytes `offsets`
+    /// were decoded from
+    ///
+    /// Each pair of characters in the encoded body holds 13 bits, so a pair can
+    /// hold parts of several decoded characters, and a decoded character can be
+    /// split over two pairs. The result covers every pair that holds a bit of
+    /// `offsets`. An empty range maps to an empty range.
+    ///
+    /// Returns `None` if `offsets` is outside of the decoded source.
+    pub fn encoded_range(&self, offsets: Range<usize>) -> Option<Range<usize>> {
+        if offsets.start > offsets.end || offsets.end > self.origins.len() {
+            return None;
+        }
+        
+        let start = self.origins.get(offsets.start).map_or(self.end, |origin| origin.start);
+        
+        if offsets.is_empty() {
+            return Some(start..start);
+        }
+        
+        Some(start..self.origins[offsets.end - 1].end)
+    }
+    
+    /// Map `span` of the decoded source to the span of the encoded content it
+    /// was decoded from, using the same [`SourceConvention`] as `span`
+    ///
+    /// Returns `None` if `span` is outside of the decoded source.
+    pub fn encoded_span(&self, span: Span) -> Option<Span> {
+        let convention = match span.start() {
+            Location::LineColumn(_) => SourceConvention::LineColumn,
+            Location::CharacterIndex(_) => SourceConvention::CharacterIndex,
+        };
+        
+        let offsets = self.encoded_range(self.decoded.span_offsets(span)?)?;
+        
+        self.encoded.span(offsets, convention)
+    }
+}
+
+/// Validate the header of a paclet, and get the offset of its body
+fn paclet_body_start(content: &str) -> Result<usize, ParseError> {
     // Validate minimum length
     if content.len() < 11 {
         return Err(ParseError::Encoding(EncodingError::DecodeError {
@@ -130,7 +351,7 @@ pub fn decode_paclet(content: &str) -> Result<String, ParseError> {
     }
     
     // Extract and validate header
-    let header = &content[0..8];
+    let header = content.get(..8).unwrap_or("");
     let paclet_header = detect_paclet_header(content).ok_or_else(|| {
         ParseError::Encoding(EncodingError::DecodeError {
             details: format!("Invalid paclet header: {}", header),
@@ -150,12 +371,17 @@ pub fn decode_paclet(content: &str) -> Result<String, ParseError> {
         }));
     }
     
-    // Extract body (skip header + "mcm" + newline)
-    let body_start = if content.chars().nth(11) == Some('\n') { 12 } else { 11 };
-    let body = &content[body_start..];
-    
+    // Skip header + "mcm" + newline
+    Ok(if content.chars().nth(11) == Some('\n') { 12 } else { 11 })
+}
+
+/// Decode the body of a paclet
+///
+/// `on_char` is called with the indices of the pairs of the body that hold the
+/// bits of each decoded character.
+fn decode_body(body: &str, mut on_char: impl FnMut(Range<usize>)) -> Result<String, ParseError> {
     // Remove newlines from body 
-    let clean_body: String = body.chars().filter(|&c| c != '\n' && c != '\r').collect();
+    let clean_body: Vec<u8> = body.bytes().filter(|&c| c != b'\n' && c != b'\r').collect();
     
     // Validate body length
     if clean_body.len() % 2 != 0 {
@@ -164,74 +390,255 @@ pub fn decode_paclet(content: &str) -> Result<String, ParseError> {
         }));
     }
     
-    // Convert body to bitstream
+    // Decode the body pair by pair, keeping at most a few undecoded bits
+    let decoder = &*HUFFMAN_DECODER;
+    let mut buffer = BitBuffer::default();
+    let mut output = String::with_capacity(clean_body.len() * 2);
+    
+    let mut pairs = clean_body.chunks(2);
+    let mut pairs_read = 0;
+    
+    loop {
+        let at_end = pairs.len() == 0;
+        
+        // Index in the body of the first bit that has not been decoded yet
+        let start_bit = 13 * pairs_read - buffer.len;
+        
+        match decoder.decode(&mut buffer, at_end) {
+            Decoded::Char(ch) => {
+                let end_bit = 13 * pairs_read - buffer.len;
+                
+                on_char(start_bit / 13..(end_bit - 1) / 13 + 1);
+                output.push(char::from(ch));
+            }
+            Decoded::End => break,
+            Decoded::NeedBits => {
+                // `NeedBits` is only returned if there are more pairs
+                let pair = pairs.next().unwrap();
+                buffer.push_value(base95_value(pair[0], pair[1])?);
+                pairs_read += 1;
+            }
+        }
+    }
+    
+    Ok(output)
+}
+
+/// Encode Wolfram Language source as a paclet
+///
+/// This is the inverse of [`decode_paclet`] for ASCII content. The paclet
+/// format can only store ASCII, so non-ASCII characters are first replaced by
+/// escapes that denote the same character, using [`escape_non_ascii`]. Decoding
+/// the result returns the escaped source, which parses to the same syntax.
+///
+/// Content containing the EOT character (ASCII 4) cannot be encoded.
+///
+/// ```
+/// use wolfram_parser::paclet;
+///
+/// let encoded = paclet::encode_paclet("\u{03b1} + \u{0416}").unwrap();
+///
+/// assert_eq!(paclet::decode_paclet(&encoded).unwrap(), "\\[Alpha] + \\:0416");
+/// ```
+pub fn encode_paclet(content: &str) -> Result<String, ParseError> {
+    let content = escape_non_ascii(content);
+    
+    // Huffman encode, followed by the EOT character (ASCII 4)
     let mut bitstream = String::new();
+    
+    for ch in content.chars() {
+        // The EOT character marks the end of the content, so it cannot be encoded
+        if ch == '\u{4}' {
+            return Err(ParseError::Encoding(EncodingError::UnsupportedEncoding {
+                encoding: "EOT character (ASCII 4) in paclet content".to_string(),
+            }));
+        }
+        
+        bitstream.push_str(HUFFMAN_TABLE[ch as usize]);
+    }
+    
+    bitstream.push_str(HUFFMAN_TABLE[4]);
+    
+    // Pad with zeros to a multiple of 13 bits
+    while bitstream.len() % 13 != 0 {
+        bitstream.push('0');
+    }
+    
+    // Encode each 13 bits as two base-95 characters (reversing bits, as in decoding)
     let base = 95u32;
     let offset = 32u32;
+    let mut body = String::new();
     
-    for chunk in clean_body.chars().collect::<Vec<char>>().chunks(2) {
-        let c1 = chunk[0] as u32;
-        let c2 = chunk[1] as u32;
+    for chunk in bitstream.as_bytes().chunks(13) {
+        let value = chunk
+            .iter()
+            .rev()
+            .fold(0u32, |value, &bit| (value << 1) | u32::from(bit == b'1'));
         
-        // Convert to offset values
-        let a = c1 - offset;
-        let b = c2 - offset;
+        body.push(char::from((value / base + offset) as u8));
+        body.push(char::from((value % base + offset) as u8));
+    }
+    
+    let mut output = String::from(ENCODED_HEADER);
+    
+    for line in body.as_bytes().chunks(ENCODED_LINE_LENGTH) {
+        // The body only contains ASCII characters
+        output.push_str(std::str::from_utf8(line).unwrap());
+        output.push('\n');
+    }
+    
+    Ok(output)
+}
+
+/// Replace every non-ASCII character in `content` by an escape that denotes the
+/// same character
+///
+/// Characters with a long name are written as `\[Name]`, other characters as
+/// `\:xxxx`, or as `\|xxxxxx` if they are outside the Basic Multilingual Plane.
+pub fn escape_non_ascii(content: &str) -> Cow<'_, str> {
+    if content.is_ascii() {
+        return Cow::Borrowed(content);
+    }
+    
+    let mut escaped = String::with_capacity(content.len() + 16);
+    
+    for ch in content.chars() {
+        if ch.is_ascii() {
+            escaped.push(ch);
+            continue;
+        }
         
-        if a >= base || b >= base {
-            return Err(ParseError::Encoding(EncodingError::DecodeError {
-                details: format!("Invalid character values in paclet body: {} {} (chars: {} {})", 
-                                a, b, c1 as u8 as char, c2 as u8 as char),
-            }));
+        match long_names::codepoint_to_longname(CodePoint::Char(ch)) {
+            Some(name) => escaped.push_str(&format!("\\[{}]", name)),
+            None if u32::from(ch) <= 0xFFFF => escaped.push_str(&format!("\\:{:04x}", u32::from(ch))),
+            None => escaped.push_str(&format!("\\|{:06x}", u32::from(ch))),
         }
+    }
+    
+    Cow::Owned(escaped)
+}
+
+/// Incremental paclet decoder
+///
+/// Reads a paclet-encoded file from the wrapped reader and yields the decoded
+/// Wolfram Language source, without holding either the encoded or the decoded
+/// content in memory. Decoding stops at the EOT character, like [`decode_paclet`].
+///
+/// ```
+/// use std::io::Read;
+/// use wolfram_parser::paclet::{self, DecodeReader};
+///
+/// let encoded = paclet::encode_paclet("f[x_] := x + 1").unwrap();
+///
+/// let mut decoded = String::new();
+/// DecodeReader::new(encoded.as_bytes())
+///     .unwrap()
+///     .read_to_string(&mut decoded)
+///     .unwrap();
+///
+/// assert_eq!(decoded, "f[x_] := x + 1");
+/// ```
+pub struct DecodeReader<R: Read> {
+    input: io::Bytes<io::BufReader<R>>,
+    buffer: BitBuffer,
+    done: bool,
+}
+
+impl<R: Read> DecodeReader<R> {
+    /// Create a decoder, reading and validating the paclet header from `inner`
+    pub fn new(inner: R) -> io::Result<Self> {
+        let mut input = io::BufReader::new(inner);
         
-        // Decode base-95 value and reverse bits (as per encoding algorithm)
-        let value = a * base + b;
-        let bits = format!("{:013b}", value);
-        let reversed_bits: String = bits.chars().rev().collect();
-        bitstream.push_str(&reversed_bits);
-    }
-    
-    // Decode bitstream using Huffman tree
-    let tree = build_huffman_tree();
-    let mut output = String::new();
-    let mut current = &tree;
-    
-    for bit in bitstream.chars() {
-        match bit {
-            '0' => {
-                if let Some(ref left) = current.left {
-                    current = left;
-                } else {
-                    // Hit a dead end, might be padding
-                    break;
-                }
+        let mut header = [0u8; 11];
+        input.read_exact(&mut header).map_err(|err| match err.kind() {
+            io::ErrorKind::UnexpectedEof => {
+                invalid_data("File too short to be a valid paclet".to_string())
             }
-            '1' => {
-                if let Some(ref right) = current.right {
-                    current = right;
-                } else {
-                    // Hit a dead end, might be padding
-                    break;
-                }
+            _ => err,
+        })?;
+        
+        let header = std::str::from_utf8(&header)
+            .ok()
+            .filter(|header| header.is_ascii())
+            .ok_or_else(|| invalid_data("Invalid paclet header".to_string()))?;
+        let paclet_header = detect_paclet_header(header)
+            .ok_or_else(|| invalid_data(format!("Invalid paclet header: {}", &header[..8])))?;
+        
+        if !paclet_header.is_supported() {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, format!(
+                "Packlet version {}{}", paclet_header.version, paclet_header.variant
+            )));
+        }
+        
+        if !header[8..].starts_with("mcm") {
+            return Err(invalid_data("Missing 'mcm' suffix in paclet header".to_string()));
+        }
+        
+        Ok(DecodeReader {
+            input: input.bytes(),
+            buffer: BitBuffer::default(),
+            done: false,
+        })
+    }
+    
+    /// Read the next non-newline byte of the encoded body
+    fn next_body_byte(&mut self) -> io::Result<Option<u8>> {
+        for byte in self.input.by_ref() {
+            match byte? {
+                b'\n' | b'\r' => continue,
+                byte => return Ok(Some(byte)),
             }
-            _ => {
-                return Err(ParseError::Encoding(EncodingError::DecodeError {
-                    details: format!("Invalid bit character in bitstream: {}", bit),
-                }));
+        }
+        
+        Ok(None)
+    }
+    
+    /// Decode the next character, or mark the decoder as done
+    fn decode_next(&mut self) -> io::Result<Option<u8>> {
+        let mut at_end = false;
+        
+        loop {
+            match HUFFMAN_DECODER.decode(&mut self.buffer, at_end) {
+                Decoded::Char(ch) => return Ok(Some(ch)),
+                Decoded::End => {
+                    self.done = true;
+                    return Ok(None);
+                }
+                Decoded::NeedBits => match (self.next_body_byte()?, self.next_body_byte()?) {
+                    (Some(c1), Some(c2)) => {
+                        let value = base95_value(c1, c2).map_err(|err| {
+                            io::Error::new(io::ErrorKind::InvalidData, ParseError::Encoding(err))
+                        })?;
+                        self.buffer.push_value(value);
+                    }
+                    (None, None) => at_end = true,
+                    _ => return Err(invalid_data("Packlet body length is not even".to_string())),
+                },
             }
         }
+    }
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut len = 0;
         
-        if let Some(ch) = current.value {
-            // Check for EOT character (ASCII 4) - end of transmission
-            if ch as u8 == 4 {
-                break;
+        while len < buf.len() && !self.done {
+            if let Some(ch) = self.decode_next()? {
+                buf[len] = ch;
+                len += 1;
             }
-            output.push(ch);
-            current = &tree;
         }
+        
+        Ok(len)
     }
-    
-    Ok(output)
+}
+
+fn invalid_data(details: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        ParseError::Encoding(EncodingError::DecodeError { details }),
+    )
 }
 
 /// Try to decode input if it's a paclet, otherwise return the original input
@@ -282,6 +689,81 @@ mod tests {
         assert!(!unsupported2.is_supported());
     }
 
+    #[test]
+    fn test_encode_paclet_round_trip() {
+        for content in ["", "f[x_] := x + 1", "(* comment *)\nBeginPackage[\"A`\"]\n\tg[\"~\"]\n"] {
+            let encoded = encode_paclet(content).unwrap();
+            
+            assert!(encoded.starts_with("(*!1N!*)mcm\n"));
+            assert!(encoded.lines().all(|line| line.len() <= 70));
+            assert_eq!(decode_paclet(&encoded).unwrap(), content);
+        }
+    }
+
+    #[test]
+    fn test_encode_paclet_non_ascii() {
+        let encoded = encode_paclet("\"\u{03b1}\u{00e9}\" + \u{1F600} (* \u{2192} *)").unwrap();
+        
+        assert_eq!(
+            decode_paclet(&encoded).unwrap(),
+            "\"\\[Alpha]\\[EAcute]\" + \\|01f600 (* \\[RightArrow] *)"
+        );
+        
+        assert_eq!(escape_non_ascii("f[x]"), Cow::Borrowed("f[x]"));
+    }
+
+    #[test]
+    fn test_decode_paclet_long_codes() {
+        // Includes control characters, which have the longest codes
+        let content: String = (0u8..128).filter(|&c| c != 4).map(char::from).collect();
+        let encoded = encode_paclet(&content).unwrap();
+        
+        assert_eq!(decode_paclet(&encoded).unwrap(), content);
+        
+        let mut decoded = String::new();
+        DecodeReader::new(encoded.as_bytes()).unwrap().read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, content);
+    }
+
+    #[test]
+    fn test_decode_paclet_invalid_value() {
+        // "~~" is 94 * 95 + 94, which does not fit in 13 bits
+        assert!(decode_paclet("(*!1N!*)mcm\n~~").is_err());
+    }
+
+    #[test]
+    fn test_decode_reader() {
+        let content = "(* comment *)\nBeginPackage[\"A`\"]\n".repeat(50);
+        let encoded = encode_paclet(&content).unwrap();
+        
+        let mut decoded = String::new();
+        DecodeReader::new(encoded.as_bytes()).unwrap().read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, content);
+        
+        // Read one byte at a time
+        let mut reader = DecodeReader::new(encoded.as_bytes()).unwrap();
+        let mut decoded = Vec::new();
+        let mut byte = [0u8; 1];
+        while reader.read(&mut byte).unwrap() == 1 {
+            decoded.push(byte[0]);
+        }
+        assert_eq!(decoded, content.as_bytes());
+    }
+
+    #[test]
+    fn test_decode_reader_invalid() {
+        assert!(DecodeReader::new("f[x]".as_bytes()).is_err());
+        assert!(DecodeReader::new("(*!2N!*)mcm\n".as_bytes()).is_err());
+        assert!(DecodeReader::new("(*!1N!*)abc\n".as_bytes()).is_err());
+        
+        let mut decoded = String::new();
+        let mut reader = DecodeReader::new("(*!1N!*)mcm\n\u{1}\u{1}".as_bytes()).unwrap();
+        assert!(reader.read_to_string(&mut decoded).is_err());
+        
+        let mut reader = DecodeReader::new("(*!1N!*)mcm\nabc".as_bytes()).unwrap();
+        assert!(reader.read_to_string(&mut decoded).is_err());
+    }
+
     #[test]
     fn test_maybe_decode_paclet_passthrough() {
         let regular_content = "f[x_] := x + 1";
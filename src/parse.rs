@@ -4,6 +4,10 @@
 //!
 //! * The enums of the [`operators`] module
 //! * The [`ParseBuilder`] trait, used to implement custom parsing output.
+//! * [`prefix_parselet_class()`] and [`infix_parselet_class()`], which report
+//!   the [`ParseletClass`] that parses each [`TokenKind`].
+//! * The [`spec`] module, which describes every operator the parser
+//!   recognizes
 //!
 //! ###### General Terminology
 //!
@@ -46,10 +50,18 @@
 pub(crate) mod parser_docs;
 
 pub mod operators;
+pub mod spec;
 
 pub(crate) mod parselet;
 pub(crate) mod token_parselets;
 
+pub use self::parselet::ParseletClass;
+
+mod profile;
+mod commas;
+pub(crate) mod recovery;
+pub(crate) mod boxes;
+
 
 #[cfg(test)]
 mod parse_tests {
@@ -57,28 +69,40 @@ mod parse_tests {
 }
 
 
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use smallvec::SmallVec;
 
 use crate::{
-    create_parse_result,
-    cst::TriviaSeq,
-    error_handling::{ParserError, perf_monitor},
-    feature, panic_if_aborted,
-    parse::parselet::PrefixToplevelCloserParselet,
+    cst::{Cst, CstSeq, TriviaSeq},
+    explain::PrecedenceStep,
+    feature,
+    issue::{CodeAction, Issue, IssueTag, Severity, SyntaxIssue},
+    notation::{Notation, Notations},
+    panic_if_aborted,
+    parse::{
+        parselet::{
+            InfixNotationParselet, InfixParseletOperator,
+            PrefixNotationParselet, PrefixToplevelCloserParselet,
+        },
+        spec::OperatorPosition,
+    },
     precedence::Precedence,
     quirks,
+    read::InputMark,
     tokenize::{
-        token_kind::Closer, tokenizer::Tokenizer_currentToken_stringifyAsFile,
-        TokenKind, TokenRef, TokenStr, Tokenizer,
+        token_kind::Closer,
+        tokenizer::{ReplayedToken, Tokenizer_currentToken_stringifyAsFile},
+        Token, TokenInput, TokenKind, TokenRef, TokenStr, TokenStreamError,
+        TokenString, Tokenizer,
     },
-    ParseOptions, ParseResult, QuirkSettings,
+    parse_cst::ParseCst,
+    DebugPhase, NodeSeq, ParseOptions, ParseResult, QuirkSettings,
+    ReparseHeuristics, ToplevelNewlineMode,
 };
 
-// Import types used only in doc comments in this module.
-#[allow(unused_imports)]
-use crate::cst::Cst;
+#[cfg(feature = "bumpalo")]
+use crate::{cst::arena::ArenaCst, parse_cst_arena::ParseArenaCst};
 
 use self::{
     operators::{
@@ -92,41 +116,375 @@ use self::{
 // API
 //======================================
 
+/// Parse a sequence of tokens into concrete syntax trees.
+///
+/// `tokens` must be the complete token sequence of some input, in order and
+/// including trivia, as produced by [`tokenize()`][crate::tokenize()] with the
+/// same `opts`. Tokens may come from any source, for example an embedder's own
+/// incrementally maintained token stream, or a token stream deserialized with
+/// [`NodeSeq::from_bytes()`][crate::NodeSeq::from_bytes()].
+///
+/// The parser consumes `tokens` as they are, without lexing their input
+/// again, so a token whose kind was changed is parsed as that kind.
+///
+/// # Context-dependent tokens
+///
+/// How some Wolfram Language input is split into tokens depends on parser
+/// state. For example, the `b` in `a::b` is lexed as a string by the parser,
+/// but as a symbol by [`tokenize()`][crate::tokenize()]. The parser lexes such
+/// input from the token inputs itself, and continues with the supplied token
+/// that starts where it stopped.
+///
+/// Whether a newline token is a [`ToplevelNewline`][TokenKind::ToplevelNewline]
+/// or an [`InternalNewline`][TokenKind::InternalNewline] also depends on the
+/// enclosing groups, and is decided by the parser.
+///
+/// Issues that the tokenizer reports while lexing, like unexpected
+/// characters, are not reported for supplied tokens. Error tokens, like
+/// unterminated strings, are parsed into error nodes as usual, but are not
+/// [reparsed][crate::ParseOptions::reparse_unterminated()], because
+/// [`tokenize()`][crate::tokenize()] has already done that.
+///
+/// # Errors
+///
+/// Returns [`TokenStreamError::Inconsistent`] if a token has no input, if
+/// `tokens` do not cover their input contiguously, or if their spans do not
+/// use the [`SourceConvention`][crate::source::SourceConvention] of `opts`.
+pub fn parse_tokens<I: TokenInput>(
+    tokens: &[Token<I>],
+    opts: &ParseOptions,
+) -> Result<ParseResult<CstSeq<TokenString>>, TokenStreamError> {
+    let convention =
+        std::mem::discriminant(&opts.src_convention.newSourceLocation());
+
+    let mut source: Vec<u8> = Vec::new();
+    let mut replayed = Vec::with_capacity(tokens.len());
+
+    for (index, token) in tokens.iter().enumerate() {
+        let input = token.input.as_bytes();
+
+        let previous = index.checked_sub(1).map(|index| &tokens[index]);
+
+        let reason = if input.is_empty() {
+            format!("{:?} token has no input", token.tok)
+        } else if std::mem::discriminant(&token.src.start()) != convention {
+            format!(
+                "span {:?} does not use the {:?} source convention",
+                token.src, opts.src_convention
+            )
+        } else if let Some(previous) =
+            previous.filter(|previous| previous.src.end() != token.src.start())
+        {
+            format!(
+                "expected span starting at {:?}, found {:?}",
+                previous.src.end(),
+                token.src
+            )
+        } else {
+            replayed.push(ReplayedToken {
+                offset: source.len(),
+                len: input.len(),
+                tok: token.tok,
+                src: token.src,
+            });
+
+            source.extend_from_slice(input);
+
+            continue;
+        };
+
+        return Err(TokenStreamError::Inconsistent { index, reason });
+    }
+
+    let input = source.as_slice();
+
+    let result = recovery::recover_cst_seq(input, opts, || {
+        let tokenizer = Tokenizer::replaying(input, opts, replayed);
+
+        let builder = ParseCst::new_builder();
+
+        let mut session =
+            ParserSession::with_tokenizer(tokenizer, builder, opts);
+
+        do_parse(&mut session);
+
+        // Unterminated tokens are kept as supplied, like every other token;
+        // tokens from tokenize() have already been reparsed.
+        let finish_opts = opts.clone().reparse_unterminated(None);
+
+        check_cst_seq(finish_parse(session, input, &finish_opts), opts)
+    });
+
+    Ok(result.map(CstSeq::into_owned))
+}
+
+/// Get the class of parselet that parses a token of kind `kind` when it
+/// begins an expression.
+///
+/// ```
+/// use wolfram_parser::{
+///     parse::{prefix_parselet_class, ParseletClass},
+///     tokenize::TokenKind,
+/// };
+///
+/// assert_eq!(
+///     prefix_parselet_class(TokenKind::Minus),
+///     ParseletClass::PrefixOperator
+/// );
+/// assert_eq!(
+///     prefix_parselet_class(TokenKind::OpenCurly),
+///     ParseletClass::Group
+/// );
+/// ```
+pub fn prefix_parselet_class(kind: TokenKind) -> ParseletClass {
+    token_parselets::prefix_parselet(kind).class()
+}
+
+/// Get the class of parselet that parses a token of kind `kind` when it
+/// follows an expression.
+///
+/// ```
+/// use wolfram_parser::{
+///     parse::{infix_parselet_class, ParseletClass},
+///     tokenize::TokenKind,
+/// };
+///
+/// assert_eq!(infix_parselet_class(TokenKind::Semi), ParseletClass::Semi);
+/// assert_eq!(
+///     infix_parselet_class(TokenKind::Symbol),
+///     ParseletClass::InfixImplicitTimes
+/// );
+/// ```
+pub fn infix_parselet_class(kind: TokenKind) -> ParseletClass {
+    token_parselets::infix_parselet(kind).class()
+}
+
+/// Parse concrete syntax, and check it against the
+/// [`GrammarProfile`][crate::GrammarProfile] in `opts`.
+///
+/// See [`RecoveryMode`][crate::RecoveryMode] for how parser failures are
+/// handled.
+pub(crate) fn parse_cst_seq<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+) -> ParseResult<CstSeq<TokenStr<'i>>> {
+    recovery::recover_cst_seq(input, opts, || parse_cst_seq_inner(input, opts))
+}
+
+fn parse_cst_seq_inner<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+) -> ParseResult<CstSeq<TokenStr<'i>>> {
+    let result = parse(input, ParseCst::new_builder(), opts);
+
+    let result = check_cst_seq(result, opts);
+
+    #[cfg(debug_assertions)]
+    if let Err(err) = result.syntax.validate_spans() {
+        panic!("parse_cst_seq: invalid spans: {err}");
+    }
+
+    result
+}
+
+/// Parse concrete syntax into `bump`, and check it in the same way as
+/// [`parse_cst_seq()`].
+#[cfg(feature = "bumpalo")]
+pub(crate) fn parse_cst_seq_in<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    bump: &'i bumpalo::Bump,
+) -> ParseResult<&'i [ArenaCst<'i>]> {
+    recovery::recover_cst_seq_in(input, opts, bump, || {
+        parse_cst_seq_in_inner(input, opts, bump)
+    })
+}
+
+#[cfg(feature = "bumpalo")]
+fn parse_cst_seq_in_inner<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    bump: &'i bumpalo::Bump,
+) -> ParseResult<&'i [ArenaCst<'i>]> {
+    let mut result = parse(input, ParseArenaCst::new(bump), opts);
+
+    // Grammar profiles other than the default are rarely used, so they are
+    // checked on a copy of the tree.
+    if opts.grammar_profile != crate::GrammarProfile::Full {
+        profile::check_profile(
+            &crate::cst::arena::to_cst_seq(result.syntax),
+            opts.grammar_profile,
+            &mut result.fatal_issues,
+        );
+    }
+
+    if opts.check_issues {
+        let issues = commas::check_commas_in(result.syntax, opts);
+
+        push_issues(&mut result, issues);
+    }
+
+    opts.debug_snapshot(DebugPhase::Cst, &result.syntax);
+
+    result
+}
+
+/// Parse concrete syntax for the first top-level expression in `input` at or
+/// after `start`, skipping any top-level trivia before it.
+///
+/// Returns the position after the expression, from which parsing can be
+/// resumed.
+pub(crate) fn parse_cst_first<'i>(
+    input: &'i [u8],
+    start: Option<InputMark>,
+    opts: &ParseOptions,
+) -> (ParseResult<Option<Cst<TokenStr<'i>>>>, InputMark) {
+    recovery::recover_cst_first(input, start, opts, || {
+        parse_cst_first_inner(input, start, opts)
+    })
+}
+
+fn parse_cst_first_inner<'i>(
+    input: &'i [u8],
+    start: Option<InputMark>,
+    opts: &ParseOptions,
+) -> (ParseResult<Option<Cst<TokenStr<'i>>>>, InputMark) {
+    let mut session = ParserSession::new(input, ParseCst::new_builder(), opts);
+
+    if let Some(start) = start {
+        session.tokenizer.seek(start);
+    }
+
+    do_parse_first(&mut session);
+
+    let end = session.tokenizer.mark();
+
+    let result = check_cst_seq(finish_parse(session, input, opts), opts);
+
+    let result = result.map(|NodeSeq(nodes)| {
+        debug_assert!(nodes.len() <= 1, "expected at most one node: {nodes:?}");
+
+        nodes.into_iter().next()
+    });
+
+    (result, end)
+}
+
+/// Check `result` against the [`GrammarProfile`][crate::GrammarProfile] and
+/// for comma issues.
+fn check_cst_seq<'i>(
+    mut result: ParseResult<CstSeq<TokenStr<'i>>>,
+    opts: &ParseOptions,
+) -> ParseResult<CstSeq<TokenStr<'i>>> {
+    profile::check_profile(
+        &result.syntax,
+        opts.grammar_profile,
+        &mut result.fatal_issues,
+    );
+
+    if opts.check_issues {
+        let issues = commas::check_commas(&result.syntax, opts);
+
+        push_issues(&mut result, issues);
+    }
+
+    opts.debug_snapshot(DebugPhase::Cst, &result.syntax);
+
+    result
+}
+
+/// Add `issues` to the fatal or non-fatal issues of `result`, depending on
+/// their severity.
+fn push_issues<N>(result: &mut ParseResult<N>, issues: Vec<Issue>) {
+    for issue in issues {
+        if issue.sev == Severity::Fatal {
+            result.fatal_issues.push(issue);
+        } else {
+            result.non_fatal_issues.push(issue);
+        }
+    }
+}
+
+/// Parse concrete syntax, returning only the syntax and issues.
+///
+/// Unlike [`parse_cst_seq()`], tracked source locations and directives are
+/// discarded instead of returned.
+pub(crate) fn parse_cst_seq_simple<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+) -> (CstSeq<TokenStr<'i>>, Vec<Issue>) {
+    recovery::recover_cst_seq_simple(input, opts, || {
+        parse_cst_seq_simple_inner(input, opts)
+    })
+}
+
+fn parse_cst_seq_simple_inner<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+) -> (CstSeq<TokenStr<'i>>, Vec<Issue>) {
+    let result = parse(input, ParseCst::new_builder(), opts);
+
+    let ParseResult {
+        syntax,
+        fatal_issues: mut issues,
+        non_fatal_issues,
+        ..
+    } = check_cst_seq(result, opts);
+
+    issues.extend(non_fatal_issues);
+
+    (syntax, issues)
+}
+
 /// Parse Wolfram Language input using the specified [`ParseBuilder`].
 pub(crate) fn parse<'i, B: ParseBuilder<'i> + 'i>(
     input: &'i [u8],
+    builder: B,
     opts: &ParseOptions,
 ) -> ParseResult<B::Output> {
-    let builder: B = B::new_builder();
+    let mut session = ParserSession::new(input, builder, opts);
 
-    let (builder, result): (B, ParseResult<()>) =
-        do_parse(input, builder, opts);
+    do_parse(&mut session);
 
-    let exprs = builder.finish(input, opts);
-
-    ParseResult {
-        syntax: exprs,
-        unsafe_character_encoding: result.unsafe_character_encoding,
-        fatal_issues: result.fatal_issues,
-        non_fatal_issues: result.non_fatal_issues,
-        tracked: result.tracked,
-    }
+    finish_parse(session, input, opts)
 }
 
-fn do_parse<'i, B: ParseBuilder<'i> + 'i>(
+/// Parse Wolfram Language input, recording each operator precedence decision
+/// made by the parser.
+///
+/// See [`explain()`][crate::explain::explain()].
+pub(crate) fn parse_with_precedence_trace<'i, B: ParseBuilder<'i> + 'i>(
     input: &'i [u8],
     builder: B,
     opts: &ParseOptions,
-) -> (B, ParseResult<()>) {
-    let mut session = ParserSession::new(&*input, builder, opts);
+) -> (ParseResult<B::Output>, Vec<PrecedenceStep>) {
+    let mut session = ParserSession::new(input, builder, opts);
 
-    quirks::set_quirks(session.quirk_settings);
+    session.precedence_trace = Some(Vec::new());
 
-    #[cfg(feature = "DIAGNOSTICS")]
-    {
-        DiagnosticsLog("enter parseExpressions");
-        DiagnosticsMarkTime();
-    }
+    do_parse(&mut session);
+
+    let trace = session.precedence_trace.take().unwrap_or_default();
+
+    (finish_parse(session, input, opts), trace)
+}
+
+fn finish_parse<'i, B: ParseBuilder<'i> + 'i>(
+    session: ParserSession<'i, B>,
+    input: &'i [u8],
+    opts: &ParseOptions,
+) -> ParseResult<B::Output> {
+    let ParserSession {
+        tokenizer, builder, ..
+    } = session;
+
+    let exprs = builder.finish(input, opts);
+
+    tokenizer.into_parse_result(exprs)
+}
+
+fn do_parse<'i, B: ParseBuilder<'i> + 'i>(session: &mut ParserSession<'i, B>) {
+    quirks::set_quirks(session.quirk_settings);
 
     //
     // Collect all expressions
@@ -155,8 +513,8 @@ fn do_parse<'i, B: ParseBuilder<'i> + 'i>(
         // special top-level handling of stray closers
         //
         if peek.tok.isCloser() {
-            let node = (PrefixToplevelCloserParselet {})
-                .parse_prefix(&mut session, peek);
+            let node =
+                (PrefixToplevelCloserParselet {}).parse_prefix(session, peek);
 
             session.builder.finish_top_level_expr(node);
 
@@ -171,14 +529,44 @@ fn do_parse<'i, B: ParseBuilder<'i> + 'i>(
 
         assert!(session.is_quiescent());
     } // while (true)
+}
 
-    #[cfg(feature = "DIAGNOSTICS")]
-    {
-        DiagnosticsLog("exit parseExpressions");
-        DiagnosticsLogTime();
-    }
+/// Like [`do_parse()`], but stop after the first top-level expression, and
+/// skip the top-level trivia before it.
+fn do_parse_first<'i, B: ParseBuilder<'i> + 'i>(
+    session: &mut ParserSession<'i, B>,
+) {
+    quirks::set_quirks(session.quirk_settings);
+
+    loop {
+        if feature::CHECK_ABORT && crate::abortQ() {
+            break;
+        }
 
-    return (session.builder, create_parse_result(&session.tokenizer, ()));
+        let peek: TokenRef = session.tokenizer.peek_token();
+
+        if peek.tok == TokenKind::EndOfFile {
+            break;
+        }
+
+        if peek.tok.isTrivia() {
+            peek.skip(&mut session.tokenizer);
+
+            continue;
+        }
+
+        let node = if peek.tok.isCloser() {
+            (PrefixToplevelCloserParselet {}).parse_prefix(session, peek)
+        } else {
+            session.parse_prefix(peek)
+        };
+
+        session.builder.finish_top_level_expr(node);
+
+        assert!(session.is_quiescent());
+
+        break;
+    }
 }
 
 
@@ -195,7 +583,23 @@ pub(crate) struct ParserSession<'i, B: ParseBuilder<'i> + 'i> {
 
     context_stack: SmallVec<[Context<B::ContextData>; 4]>,
 
+    /// See [`ParseOptions::implicit_times()`].
+    implicit_times: bool,
+
+    /// See [`ParseOptions::toplevel_newlines()`].
+    toplevel_newlines: ToplevelNewlineMode,
+
     quirk_settings: QuirkSettings,
+
+    /// See [`ParseOptions::reparse_unterminated()`].
+    reparse: Option<ReparseHeuristics>,
+
+    /// See [`ParseOptions::notations()`].
+    notations: Option<Arc<Notations>>,
+
+    /// If set, every precedence decision made in [`ParserSession::parse_climb()`]
+    /// is recorded here.
+    precedence_trace: Option<Vec<PrecedenceStep>>,
 }
 
 #[derive(Debug)]
@@ -464,8 +868,6 @@ where
     // Lifecycle
     //==================================
 
-    fn new_builder() -> Self;
-
     /// Complete the parse and return the parsed output.
     fn finish(self, input: &'i [u8], opts: &ParseOptions) -> Self::Output;
 
@@ -772,6 +1174,7 @@ where
         ctx_data: Self::ContextData,
         input: &'i str,
         tab_width: usize,
+        heuristics: ReparseHeuristics,
         op: GroupOperator,
         group_state: Self::GroupParseState,
         trailing_trivia: Self::TriviaHandle,
@@ -927,10 +1330,16 @@ pub(crate) enum SyntaxErrorData<N, TRV, STN> {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SyntaxErrorKind {
     ExpectedSymbol,
     ExpectedSet,
     ExpectedTilde,
+    /// Input that the parser failed on, with
+    /// [`RecoveryMode::Recover`][crate::RecoveryMode::Recover].
+    ///
+    /// The children of the node are the tokens of the input.
+    Unparsable,
 }
 
 #[derive(Debug)]
@@ -958,22 +1367,49 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
         input: &'i [u8],
         builder: B,
         opts: &ParseOptions,
+    ) -> ParserSession<'i, B> {
+        let tokenizer = Tokenizer::new(input, opts);
+
+        ParserSession::with_tokenizer(tokenizer, builder, opts)
+    }
+
+    fn with_tokenizer(
+        tokenizer: Tokenizer<'i>,
+        builder: B,
+        opts: &ParseOptions,
     ) -> ParserSession<'i, B> {
         let ParseOptions {
             first_line_behavior: _,
             src_convention: _,
             encoding_mode: _,
             tab_width: _,
+            tab_width_auto: _,
             check_issues: _,
             compute_oob: _,
+            implicit_times,
+            toplevel_newlines,
+            grammar_profile: _,
+            denied_issues: _,
+            max_line_length: _,
+            check_indentation: _,
+            reparse,
+            recovery: _,
+            deduplicate_issues: _,
+            ref notations,
+            debug_sink: _,
             quirk_settings,
         } = *opts;
 
         ParserSession {
-            tokenizer: Tokenizer::new(input, opts),
+            tokenizer,
             builder,
             context_stack: SmallVec::new(),
+            implicit_times,
+            toplevel_newlines,
             quirk_settings,
+            reparse,
+            notations: notations.clone(),
+            precedence_trace: None,
         }
     }
 
@@ -986,6 +1422,14 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
     /// with the [`TokenKind`] of `token`.
     // TODO(cleanup): Rename to avoid ambiguity with PrefixParselet::parse_prefix()?
     pub(crate) fn parse_prefix(&mut self, token: TokenRef<'i>) -> B::Node {
+        if let Some(precedence) =
+            self.notation_precedence(OperatorPosition::Prefix, token)
+        {
+            // MUSTTAIL
+            return PrefixNotationParselet::new(precedence)
+                .parse_prefix(self, token);
+        }
+
         B::with_prefix_parselet(token.tok, |parselet| {
             // MUSTTAIL
             parselet.parse_prefix(self, token)
@@ -1003,16 +1447,78 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
         trivia1: B::TriviaHandle,
         token: TokenRef<'i>,
     ) -> B::Node {
+        if let Some(precedence) =
+            self.notation_precedence(OperatorPosition::Infix, token)
+        {
+            // MUSTTAIL
+            return InfixNotationParselet::new(precedence)
+                .parse_infix(self, finished, trivia1, token);
+        }
+
         B::with_infix_parselet(token.tok, |parselet| {
             // MUSTTAIL
             parselet.parse_infix(self, finished, trivia1, token)
         })
     }
 
+    /// The precedence of `token` when it appears in infix position.
+    pub(crate) fn infix_precedence(
+        &self,
+        token: TokenRef<'i>,
+    ) -> Option<Precedence> {
+        if let Some(precedence) =
+            self.notation_precedence(OperatorPosition::Infix, token)
+        {
+            return Some(precedence);
+        }
+
+        B::with_infix_parselet(token.tok, |parselet| {
+            parselet.getPrecedence(self)
+        })
+    }
+
+    /// The operator of `token` when it appears in infix position.
+    ///
+    /// Used by parselets for operators with any number of operands to check
+    /// whether `token` continues the current operator.
+    pub(crate) fn infix_op(&self, token: TokenRef<'i>) -> InfixParseletOperator {
+        if self
+            .notation_precedence(OperatorPosition::Infix, token)
+            .is_some()
+        {
+            return InfixParseletOperator::Binary(
+                BinaryOperator::CodeParser_BinaryNotation,
+            );
+        }
+
+        B::with_infix_parselet(token.tok, |parselet| parselet.getOp())
+    }
+
+    /// The precedence of the user-defined notation for `token` when it
+    /// appears in `position`, if any.
+    fn notation_precedence(
+        &self,
+        position: OperatorPosition,
+        token: TokenRef<'i>,
+    ) -> Option<Precedence> {
+        let notations = self.notations.as_deref()?;
+
+        notations
+            .find(position, &token)
+            .map(Notation::parser_precedence)
+    }
+
     fn do_process_implicit_times(
         &mut self,
         token: TokenRef<'i>,
     ) -> TokenRef<'i> {
+        if self
+            .notation_precedence(OperatorPosition::Infix, token)
+            .is_some()
+        {
+            return token;
+        }
+
         B::with_infix_parselet(token.tok, |parselet| {
             parselet.process_implicit_times(self, token)
         })
@@ -1041,9 +1547,7 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
 
         token = self.do_process_implicit_times(token);
 
-        let TokenPrecedence = B::with_infix_parselet(token.tok, |parselet| {
-            parselet.getPrecedence(self)
-        });
+        let TokenPrecedence = self.infix_precedence(token);
 
         //
         // if (Ctxt.Prec > TokenPrecedence)
@@ -1052,7 +1556,21 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
         //   break;
         //
 
-        if Precedence::greater(self.top_precedence(), TokenPrecedence) {
+        let binds =
+            !Precedence::greater(self.top_precedence(), TokenPrecedence);
+
+        if let (Some(trace), Some(prec)) =
+            (&mut self.precedence_trace, TokenPrecedence)
+        {
+            trace.push(PrecedenceStep::new(
+                token,
+                prec,
+                self.context_stack.last().and_then(|ctxt| ctxt.prec),
+                binds,
+            ));
+        }
+
+        if !binds {
             self.trivia_reset(trivia1);
 
             return finished;
@@ -1110,6 +1628,34 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
         node
     }
 
+    /// Report an issue if `token` is an implicit multiplication and implicit
+    /// multiplication has been disabled.
+    ///
+    /// This must only be called once `token` is known to be consumed, because
+    /// [`do_process_implicit_times()`][Self::do_process_implicit_times] may
+    /// speculatively produce implicit Times tokens that are later discarded.
+    pub(crate) fn check_implicit_times(&mut self, token: TokenRef<'i>) {
+        if self.implicit_times || token.tok != TokenKind::Fake_ImplicitTimes {
+            return;
+        }
+
+        let issue = SyntaxIssue(
+            IssueTag::UnexpectedImplicitTimes,
+            "Implicit multiplication is disabled.".into(),
+            Severity::Fatal,
+            token.src,
+            1.0,
+            vec![CodeAction::insert_text(
+                "Insert ``*``".into(),
+                token.src,
+                "*".into(),
+            )],
+            vec![],
+        );
+
+        self.tokenizer.addIssue(issue);
+    }
+
     /// Consume the resettable trivia in `trivia` and advance the read cursor
     /// past `token`.
     fn commit_syntax_and_next(
@@ -1210,11 +1756,16 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
         (trivia, token)
     }
 
-    pub(crate) fn current_token_eat_trivia_but_not_toplevel_newlines(
+    /// Get the next non-trivia token after an operator whose last operand
+    /// is optional, like `;` or `;;`, eating trivia tokens.
+    ///
+    /// Top-level newlines are only eaten if an operand follows them and the
+    /// [`toplevel_newlines()`][ParseOptions::toplevel_newlines] mode is
+    /// [`ToplevelNewlineMode::ContinueIfOperatorPending`].
+    pub(crate) fn current_token_eat_trivia_after_operator(
         &mut self,
     ) -> (B::TriviaHandle, TokenRef<'i>) {
-        let (trivia, tok) =
-            self.current_token_eat_trivia_but_not_toplevel_newlines_into();
+        let (trivia, tok) = self.current_token_eat_trivia_after_operator_into();
 
         // Commit this trivia.
         let trivia = self.builder.push_trivia_seq(trivia);
@@ -1222,6 +1773,26 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
         (trivia, tok)
     }
 
+    pub(crate) fn current_token_eat_trivia_after_operator_into(
+        &mut self,
+    ) -> (B::ResettableTriviaHandle, TokenRef<'i>) {
+        if self.toplevel_newlines
+            == ToplevelNewlineMode::ContinueIfOperatorPending
+        {
+            let (trivia, tok) = self.current_token();
+
+            if tok.tok.isPossibleBeginning()
+                && !matches!(tok.tok, TokenKind::Semi | TokenKind::SemiSemi)
+            {
+                return (trivia, tok);
+            }
+
+            self.trivia_reset(trivia);
+        }
+
+        self.current_token_eat_trivia_but_not_toplevel_newlines_into()
+    }
+
     pub(crate) fn current_token_eat_trivia_but_not_toplevel_newlines_into(
         &mut self,
     ) -> (B::ResettableTriviaHandle, TokenRef<'i>) {
@@ -1262,11 +1833,9 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
 
     /// Helper method for safe context popping with data extraction
     fn safe_pop_context_data(&mut self, operation: &'static str) -> B::ContextData {
-        perf_monitor::increment_check();
         match self.context_stack.pop() {
             Some(ctx) => ctx.builder_data,
             None => {
-                perf_monitor::increment_error();
                 panic!("Empty context stack during {}: This indicates a parser bug", operation)
             }
         }
@@ -1493,6 +2062,14 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
 
         self.pop_group();
 
+        let Some(heuristics) = self.reparse else {
+            return self.builder.reduce_group_missing_closer(
+                ctx_data,
+                op,
+                group_state,
+            );
+        };
+
         // The input MUST be valid UTF-8, because we only reduce an *unterminated*
         // group node if we've read an EOF (which is how we know it must be
         // unterminated: we've read all the input).
@@ -1506,6 +2083,7 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
             ctx_data,
             input,
             tab_width,
+            heuristics,
             op,
             group_state,
             trailing_trivia,
@@ -1561,29 +2139,6 @@ impl<'i, B: ParseBuilder<'i> + 'i> ParserSession<'i, B> {
             .expect("top_context: no contexts set");
     }
 
-    /// Safe context stack operations
-    fn safe_pop_context(&mut self) -> Result<Context<B::ContextData>, ParserError> {
-        perf_monitor::increment_check();
-        self.context_stack.pop()
-            .ok_or_else(|| {
-                perf_monitor::increment_error();
-                ParserError::EmptyContextStack {
-                    operation: "safe_pop_context"
-                }
-            })
-    }
-
-    fn safe_top_context_mut(&mut self) -> Result<&mut Context<B::ContextData>, ParserError> {
-        perf_monitor::increment_check();
-        self.context_stack.last_mut()
-            .ok_or_else(|| {
-                perf_monitor::increment_error();
-                ParserError::EmptyContextStack {
-                    operation: "safe_top_context_mut"
-                }
-            })
-    }
-
     //==================================
     // Precedence management
     //==================================
@@ -0,0 +1,453 @@
+//! Parsing box expressions, like the contents of notebook cells.
+//!
+//! See [`parse_box_cst()`][crate::parse_box_cst].
+//!
+//! The strings in a `RowBox[{..}]` are parsed together as Wolfram Language
+//! input, with each string tokenized on its own. Other boxes in the row, like
+//! `SuperscriptBox[..]`, are parsed recursively and appear in the row as
+//! operands, in [`Cst::Box`] nodes. Every token gets the
+//! [`BoxPosition`] of the string it came from.
+//!
+//! The arguments of a box are parsed into its children, in order. Use
+//! [`BoxNode::as_script()`] to get the base and scripts of a box like
+//! `SubscriptBox[..]` or `UnderoverscriptBox[..]`.
+
+use std::ops::Range;
+
+use wolfram_expr::{Expr, ExprKind};
+
+use crate::{
+    cst::{
+        BinaryNode, BoxKind, BoxNode, CallHead, CallNode, CodeNode,
+        CompoundNode, Cst, CstSeq, GroupMissingCloserNode,
+        GroupMissingOpenerNode, GroupNode, InfixNode, OperatorNode,
+        PostfixNode, PrefixBinaryNode, PrefixNode, SyntaxErrorNode,
+        TernaryNode,
+    },
+    issue::Issue,
+    parse::SyntaxErrorKind,
+    source::{BoxPosition, LineMap, Location, Source, Span},
+    symbols as sym,
+    tokenize::{Token, TokenInput, TokenStr, TokenString},
+    EncodingMode, FirstLineBehavior, NodeSeq, ParseOptions,
+};
+
+/// Text standing in for a box that is not a string while a row is parsed.
+const PLACEHOLDER: &str = "CodeParser`Private`BoxPlaceholder";
+
+/// Parse the box expression `boxes` into concrete syntax.
+///
+/// Returns the top-level nodes, and the issues found in every row.
+pub(crate) fn parse_box_cst_seq(
+    boxes: &Expr,
+    opts: &ParseOptions,
+) -> (CstSeq<TokenString, Source>, Vec<Issue>) {
+    let mut parser = BoxParser {
+        opts: ParseOptions {
+            encoding_mode: EncodingMode::Box,
+            first_line_behavior: FirstLineBehavior::NotScript,
+            ..opts.clone()
+        },
+        issues: Vec::new(),
+    };
+
+    let nodes = match row_children(boxes) {
+        Some(children) => parser.row(&[], row_items(children, &[])),
+        None => vec![parser.node(boxes, vec![])],
+    };
+
+    (NodeSeq(nodes), parser.issues)
+}
+
+struct BoxParser {
+    opts: ParseOptions,
+    issues: Vec<Issue>,
+}
+
+/// The text of a row being parsed, and the boxes it was built from.
+struct Row<'e> {
+    /// Position of the box containing the row.
+    position: Vec<usize>,
+    map: LineMap,
+    segments: Vec<Segment<'e>>,
+}
+
+/// The part of the text of a [`Row`] that came from one box.
+struct Segment<'e> {
+    range: Range<usize>,
+    position: Vec<usize>,
+    /// The box, if it is not a string and [`PLACEHOLDER`] was used for it.
+    placeholder_for: Option<&'e Expr>,
+}
+
+impl BoxParser {
+    /// Parse the box `expr` at `position` into a single node.
+    fn node(
+        &mut self,
+        expr: &Expr,
+        position: Vec<usize>,
+    ) -> Cst<TokenString, Source> {
+        if let Some(children) = row_children(expr) {
+            let mut nodes = self.row(&position, row_items(children, &position));
+
+            if nodes.len() == 1 {
+                return nodes.pop().unwrap();
+            }
+
+            return Cst::Box(BoxNode {
+                kind: BoxKind::RowBox,
+                children: NodeSeq(nodes),
+                src: Source::Box(BoxPosition::At(position)),
+            });
+        }
+
+        if let ExprKind::String(_) = expr.kind() {
+            let mut nodes = self.row(&position, vec![(expr, position.clone())]);
+
+            return match nodes.len() {
+                0 => code_node(expr, position),
+                1 => nodes.pop().unwrap(),
+                _ => Cst::SyntaxError(SyntaxErrorNode {
+                    err: SyntaxErrorKind::Unparsable,
+                    children: NodeSeq(nodes),
+                }),
+            };
+        }
+
+        let Some((kind, args)) = box_call(expr) else {
+            return code_node(expr, position);
+        };
+
+        let children = args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                let mut arg_position = position.clone();
+                arg_position.push(index + 1);
+
+                if is_box(arg) {
+                    self.node(arg, arg_position)
+                } else {
+                    code_node(arg, arg_position)
+                }
+            })
+            .collect();
+
+        Cst::Box(BoxNode {
+            kind,
+            children: NodeSeq(children),
+            src: Source::Box(BoxPosition::At(position)),
+        })
+    }
+
+    /// Parse the boxes in a row, at the given positions, as a sequence of
+    /// nodes.
+    fn row(
+        &mut self,
+        position: &[usize],
+        items: Vec<(&Expr, Vec<usize>)>,
+    ) -> Vec<Cst<TokenString, Source>> {
+        let mut text = String::new();
+        let mut segments = Vec::new();
+
+        for (expr, position) in items {
+            let (item_text, placeholder_for) = match expr.kind() {
+                ExprKind::String(string) => (string.as_str(), None),
+                _ => (PLACEHOLDER, Some(expr)),
+            };
+
+            // Separate boxes so that each string is tokenized on its own.
+            if needs_separator(&text, item_text) {
+                text.push(' ');
+            }
+
+            let start = text.len();
+            text.push_str(item_text);
+
+            segments.push(Segment {
+                range: start..text.len(),
+                position,
+                placeholder_for,
+            });
+        }
+
+        let result = crate::parse_cst_seq(&text, &self.opts);
+
+        let row = Row {
+            position: position.to_vec(),
+            map: LineMap::new(&text, &self.opts),
+            segments,
+        };
+
+        for mut issue in result.issues().cloned() {
+            issue.src = row.source(issue.src);
+            issue.additional_sources = issue
+                .additional_sources
+                .into_iter()
+                .map(|src| row.source(src))
+                .collect();
+
+            self.issues.push(issue);
+        }
+
+        let NodeSeq(nodes) = self.seq(&row, result.syntax);
+
+        nodes
+    }
+
+    //==================================
+    // Converting parsed nodes
+    //==================================
+
+    fn seq(
+        &mut self,
+        row: &Row,
+        NodeSeq(nodes): CstSeq<TokenStr>,
+    ) -> CstSeq<TokenString, Source> {
+        NodeSeq(
+            nodes
+                .into_iter()
+                .filter_map(|node| self.convert(row, node))
+                .collect(),
+        )
+    }
+
+    fn op<O>(
+        &mut self,
+        row: &Row,
+        OperatorNode { op, children }: OperatorNode<TokenStr, Span, O>,
+    ) -> OperatorNode<TokenString, Source, O> {
+        OperatorNode {
+            op,
+            children: self.seq(row, children),
+        }
+    }
+
+    /// Give `node` box sources, replacing placeholders with the box they
+    /// stand for.
+    ///
+    /// Returns `None` for whitespace that was added to separate boxes.
+    fn convert(
+        &mut self,
+        row: &Row,
+        node: Cst<TokenStr>,
+    ) -> Option<Cst<TokenString, Source>> {
+        let node = match node {
+            Cst::Token(token) => return self.token(row, token),
+            Cst::Call(CallNode { head, body }) => Cst::Call(CallNode {
+                head: match head {
+                    CallHead::Concrete(head) => {
+                        CallHead::Concrete(self.seq(row, head))
+                    },
+                    CallHead::Aggregate(head) => {
+                        CallHead::Aggregate(Box::new(self.convert(row, *head)?))
+                    },
+                },
+                body: body.map_op(|op| self.op(row, op)),
+            }),
+            Cst::SyntaxError(SyntaxErrorNode { err, children }) => {
+                Cst::SyntaxError(SyntaxErrorNode {
+                    err,
+                    children: self.seq(row, children),
+                })
+            },
+            Cst::Prefix(PrefixNode(op)) => {
+                Cst::Prefix(PrefixNode(self.op(row, op)))
+            },
+            Cst::Infix(InfixNode(op)) => {
+                Cst::Infix(InfixNode(self.op(row, op)))
+            },
+            Cst::Postfix(PostfixNode(op)) => {
+                Cst::Postfix(PostfixNode(self.op(row, op)))
+            },
+            Cst::Binary(BinaryNode(op)) => {
+                Cst::Binary(BinaryNode(self.op(row, op)))
+            },
+            Cst::Ternary(TernaryNode(op)) => {
+                Cst::Ternary(TernaryNode(self.op(row, op)))
+            },
+            Cst::PrefixBinary(PrefixBinaryNode(op)) => {
+                Cst::PrefixBinary(PrefixBinaryNode(self.op(row, op)))
+            },
+            Cst::Compound(CompoundNode(op)) => {
+                Cst::Compound(CompoundNode(self.op(row, op)))
+            },
+            Cst::Group(GroupNode(op)) => {
+                Cst::Group(GroupNode(self.op(row, op)))
+            },
+            Cst::GroupMissingCloser(GroupMissingCloserNode(op)) => {
+                Cst::GroupMissingCloser(GroupMissingCloserNode(
+                    self.op(row, op),
+                ))
+            },
+            Cst::GroupMissingOpener(GroupMissingOpenerNode(op)) => {
+                Cst::GroupMissingOpener(GroupMissingOpenerNode(
+                    self.op(row, op),
+                ))
+            },
+            Cst::Box(_) | Cst::Code(_) => {
+                unreachable!("parsed text does not contain box or code nodes")
+            },
+        };
+
+        Some(node)
+    }
+
+    fn token(
+        &mut self,
+        row: &Row,
+        token: Token<TokenStr>,
+    ) -> Option<Cst<TokenString, Source>> {
+        let Token { tok, input, src } = token;
+
+        let range = row.offset(src.start())..row.offset(src.end());
+
+        let src = match row.segment_at(range.start) {
+            Some(segment) => {
+                if let Some(expr) = segment.placeholder_for {
+                    if range == segment.range {
+                        return Some(self.node(expr, segment.position.clone()));
+                    }
+                }
+
+                Source::Box(BoxPosition::At(segment.position.clone()))
+            },
+            // Whitespace added to separate two boxes.
+            None if tok.isTrivia() && !range.is_empty() => return None,
+            None => row.after(range.start),
+        };
+
+        Some(Cst::Token(Token {
+            tok,
+            input: input.into_owned(),
+            src,
+        }))
+    }
+}
+
+impl<'e> Row<'e> {
+    fn offset(&self, loc: Location) -> usize {
+        self.map
+            .offset(loc)
+            .expect("parsed location is inside of the row text")
+    }
+
+    fn segment_at(&self, offset: usize) -> Option<&Segment<'e>> {
+        self.segments
+            .iter()
+            .find(|segment| segment.range.contains(&offset))
+    }
+
+    /// Source of a zero-width token at `offset`, which is not inside of any
+    /// box.
+    fn after(&self, offset: usize) -> Source {
+        let position = match self
+            .segments
+            .iter()
+            .rev()
+            .find(|segment| segment.range.end <= offset)
+        {
+            Some(segment) => BoxPosition::After(segment.position.clone()),
+            None => BoxPosition::At(self.position.clone()),
+        };
+
+        Source::Box(position)
+    }
+
+    /// Convert `src`, a span in the row text, to a box source.
+    fn source(&self, src: Source) -> Source {
+        let Source::Span(span) = src else {
+            return src;
+        };
+
+        let offset = self.offset(span.start());
+
+        match self.segment_at(offset) {
+            Some(segment) => {
+                Source::Box(BoxPosition::At(segment.position.clone()))
+            },
+            None => self.after(offset),
+        }
+    }
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// The boxes in `expr`, if it is `RowBox[{..}]`.
+fn row_children(expr: &Expr) -> Option<&[Expr]> {
+    let (BoxKind::RowBox, [list]) = box_call(expr)? else {
+        return None;
+    };
+
+    let ExprKind::Normal(list) = list.kind() else {
+        return None;
+    };
+
+    if list.head().try_as_symbol()?.as_str() != "System`List" {
+        return None;
+    }
+
+    Some(list.elements())
+}
+
+/// The boxes in a row at `position`, paired with their positions.
+fn row_items<'e>(
+    children: &'e [Expr],
+    position: &[usize],
+) -> Vec<(&'e Expr, Vec<usize>)> {
+    children
+        .iter()
+        .enumerate()
+        .map(|(index, child)| {
+            let mut child_position = position.to_vec();
+            child_position.extend([1, index + 1]);
+
+            (child, child_position)
+        })
+        .collect()
+}
+
+/// The kind and arguments of `expr`, if it is a call to a `System` box
+/// symbol, like `SuperscriptBox[..]`.
+fn box_call(expr: &Expr) -> Option<(BoxKind, &[Expr])> {
+    let ExprKind::Normal(normal) = expr.kind() else {
+        return None;
+    };
+
+    let head = normal.head().try_as_symbol()?.as_symbol_ref();
+
+    if head.context().as_str() != "System`" {
+        return None;
+    }
+
+    let kind = BoxKind::from_symbol(head)?;
+
+    Some((kind, normal.elements()))
+}
+
+fn is_box(expr: &Expr) -> bool {
+    matches!(expr.kind(), ExprKind::String(_)) || box_call(expr).is_some()
+}
+
+/// `CodeNode[Null, expr, <|Source -> position|>]`, for an argument of a box
+/// that is not itself a box, like an option.
+fn code_node(expr: &Expr, position: Vec<usize>) -> Cst<TokenString, Source> {
+    Cst::Code(CodeNode {
+        first: Expr::from(sym::Null.to_symbol()),
+        second: expr.clone(),
+        src: Source::Box(BoxPosition::At(position)),
+    })
+}
+
+/// Whether a space must be added between `text` and `next` so that the last
+/// token of `text` does not run into the first token of `next`.
+fn needs_separator(text: &str, next: &str) -> bool {
+    let ends_with_space =
+        text.chars().next_back().map_or(true, char::is_whitespace);
+    let starts_with_space =
+        next.chars().next().map_or(true, char::is_whitespace);
+
+    !ends_with_space && !starts_with_space
+}
@@ -0,0 +1,201 @@
+//! Reporting empty arguments between commas, like in `f[a, ]` and `{1,,2}`.
+
+#[cfg(feature = "bumpalo")]
+use crate::cst::arena::{ArenaCst, ArenaNodeKind};
+use crate::{
+    cst::{Cst, CstSeq, InfixNode, OperatorNode},
+    issue::{Annotation, CodeAction, Issue, IssueTag, Severity, SyntaxIssue},
+    parse::operators::InfixOperator,
+    source::{Source, Span},
+    tokenize::{Token, TokenKind},
+    ParseOptions,
+};
+
+/// Report every implicit `Null` argument of a comma-separated sequence in
+/// `nodes`.
+///
+/// A missing last argument, like in `f[a, ]`, is reported as a
+/// [`TrailingComma`][IssueTag::TrailingComma]. Any other missing argument,
+/// like in `{1,,2}` or `f[, a]`, is reported as an
+/// [`EmptyArgument`][IssueTag::EmptyArgument].
+///
+/// Issues with tags that are denied by `opts` are reported as fatal.
+pub(crate) fn check_commas<I>(
+    nodes: &CstSeq<I, Span>,
+    opts: &ParseOptions,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    nodes.visit(&mut |node| {
+        if let Cst::Infix(InfixNode(OperatorNode {
+            op: InfixOperator::CodeParser_Comma,
+            children,
+        })) = node
+        {
+            check_comma_children(children, &mut issues);
+        }
+    });
+
+    for issue in &mut issues {
+        if opts.denied_issues.contains(&issue.tag) {
+            issue.sev = Severity::Fatal;
+        }
+    }
+
+    issues
+}
+
+fn check_comma_children<I>(
+    children: &CstSeq<I, Span>,
+    issues: &mut Vec<Issue>,
+) {
+    // Operands and commas alternate once trivia is skipped.
+    let items: Vec<CommaItem> = children
+        .iter()
+        .filter(|child| {
+            !matches!(child, Cst::Token(Token { tok, .. }) if tok.isTrivia())
+        })
+        .map(|child| CommaItem {
+            null: match child {
+                Cst::Token(Token { tok, src, .. }) if is_implicit_null(*tok) => {
+                    Some(*src)
+                },
+                _ => None,
+            },
+            src: child.get_source(),
+        })
+        .collect();
+
+    check_comma_items(&items, issues);
+}
+
+/// Report every implicit `Null` argument of a comma-separated sequence in
+/// the arena-allocated `nodes`.
+///
+/// See [`check_commas()`].
+#[cfg(feature = "bumpalo")]
+pub(crate) fn check_commas_in(
+    nodes: &[ArenaCst],
+    opts: &ParseOptions,
+) -> Vec<Issue> {
+    fn visit(node: &ArenaCst, issues: &mut Vec<Issue>) {
+        let ArenaCst::Node(node) = node else {
+            return;
+        };
+
+        if node.kind == ArenaNodeKind::Infix(InfixOperator::CodeParser_Comma) {
+            let items: Vec<CommaItem> = node
+                .children
+                .iter()
+                .filter(|child| {
+                    !matches!(child, ArenaCst::Token(token) if token.tok.isTrivia())
+                })
+                .map(|child| CommaItem {
+                    null: match child {
+                        ArenaCst::Token(token) if is_implicit_null(token.tok) => {
+                            Some(token.src)
+                        },
+                        _ => None,
+                    },
+                    src: child.get_source(),
+                })
+                .collect();
+
+            check_comma_items(&items, issues);
+        }
+
+        for child in node.children {
+            visit(child, issues);
+        }
+    }
+
+    let mut issues = Vec::new();
+
+    for node in nodes {
+        visit(node, &mut issues);
+    }
+
+    for issue in &mut issues {
+        if opts.denied_issues.contains(&issue.tag) {
+            issue.sev = Severity::Fatal;
+        }
+    }
+
+    issues
+}
+
+/// An operand or comma of a comma-separated sequence.
+struct CommaItem {
+    /// The source of the implicit `Null`, if this is one.
+    null: Option<Span>,
+    src: Span,
+}
+
+/// `items` are the operands and commas of a comma-separated sequence, which
+/// alternate.
+fn check_comma_items(items: &[CommaItem], issues: &mut Vec<Issue>) {
+    for (index, item) in items.iter().enumerate().step_by(2) {
+        let Some(null) = item.null else {
+            continue;
+        };
+
+        let prev_comma = index.checked_sub(1).map(|prev| items[prev].src);
+        let next_comma = items.get(index + 1).map(|next| next.src);
+
+        let insert_null = CodeAction::insert_text(
+            "Insert ``Null``".to_owned(),
+            null,
+            "Null".to_owned(),
+        );
+
+        let issue = match (prev_comma, next_comma) {
+            // Something like  f[a, ]
+            (Some(prev_comma), None) => SyntaxIssue(
+                IssueTag::TrailingComma,
+                "Extra ``,``.".to_owned(),
+                Severity::Warning,
+                prev_comma,
+                0.95,
+                vec![
+                    CodeAction::delete_text(
+                        "Delete ``,``".to_owned(),
+                        prev_comma,
+                    ),
+                    insert_null,
+                ],
+                vec![Annotation::explanation(
+                    "The argument after ``,`` is ``Null``.".to_owned(),
+                    Source::Span(null),
+                )],
+            ),
+            // Something like  {1,,2}  or  f[, a]
+            (prev_comma, Some(next_comma)) => {
+                let start = prev_comma.unwrap_or(null).start();
+
+                SyntaxIssue(
+                    IssueTag::EmptyArgument,
+                    "Empty argument.".to_owned(),
+                    Severity::Warning,
+                    Span::new(start, next_comma.end()),
+                    0.95,
+                    vec![insert_null],
+                    vec![Annotation::explanation(
+                        "An empty argument is ``Null``.".to_owned(),
+                        Source::Span(null),
+                    )],
+                )
+            },
+            (None, None) => continue,
+        };
+
+        issues.push(issue);
+    }
+}
+
+fn is_implicit_null(tok: TokenKind) -> bool {
+    matches!(
+        tok,
+        TokenKind::Error_InfixImplicitNull
+            | TokenKind::Error_PrefixImplicitNull
+    )
+}
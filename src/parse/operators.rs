@@ -37,6 +37,7 @@ pub trait Operator: Sized + 'static {
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InfixOperator {
     Times,
     CompoundExpression,
@@ -237,6 +238,7 @@ pub enum InfixOperator {
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PrefixOperator {
     /// Prefix '?' on its own line, currently created only during box parsing.
     Information,
@@ -273,10 +275,13 @@ pub enum PrefixOperator {
     DoubleContourIntegral,
     ClockwiseContourIntegral,
     CounterClockwiseContourIntegral,
+    /// A user-defined prefix operator. See [`crate::notation`].
+    CodeParser_PrefixNotation,
 }
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PostfixOperator {
     Function,
     Repeated,
@@ -295,6 +300,7 @@ pub enum PostfixOperator {
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinaryOperator {
     Pattern,
     Optional,
@@ -345,10 +351,13 @@ pub enum BinaryOperator {
     UpTee,
     DownTee,
     Application,
+    /// A user-defined infix operator. See [`crate::notation`].
+    CodeParser_BinaryNotation,
 }
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TernaryOperator {
     CodeParser_TernaryTilde,
     CodeParser_TernaryOptionalPattern,
@@ -360,6 +369,7 @@ pub enum TernaryOperator {
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PrefixBinaryOperator {
     Integrate,
     ContourIntegral,
@@ -370,6 +380,7 @@ pub enum PrefixBinaryOperator {
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CompoundOperator {
     Blank,
     BlankSequence,
@@ -385,6 +396,7 @@ pub enum CompoundOperator {
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GroupOperator {
     Token_Comment,
     /// Created when parsing boxes.
@@ -406,6 +418,7 @@ pub enum GroupOperator {
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CallOperator {
     CodeParser_GroupSquare,
     CodeParser_GroupDoubleBracket,
@@ -899,6 +912,7 @@ impl Operator for PrefixOperator {
             PrefixOperator::DoubleContourIntegral => sym::DoubleContourIntegral,
             PrefixOperator::ClockwiseContourIntegral => sym::ClockwiseContourIntegral,
             PrefixOperator::CounterClockwiseContourIntegral => sym::CounterClockwiseContourIntegral,
+            PrefixOperator::CodeParser_PrefixNotation => sym::CodeParser_PrefixNotation,
         }
     }
 
@@ -938,6 +952,7 @@ impl Operator for PrefixOperator {
             sym::DoubleContourIntegral => PrefixOperator::DoubleContourIntegral,
             sym::ClockwiseContourIntegral => PrefixOperator::ClockwiseContourIntegral,
             sym::CounterClockwiseContourIntegral => PrefixOperator::CounterClockwiseContourIntegral,
+            sym::CodeParser_PrefixNotation => PrefixOperator::CodeParser_PrefixNotation,
             _ => return None,
         };
 
@@ -1040,6 +1055,7 @@ impl Operator for BinaryOperator {
             BinaryOperator::UpTee => sym::UpTee,
             BinaryOperator::DownTee => sym::DownTee,
             BinaryOperator::Application => sym::Application,
+            BinaryOperator::CodeParser_BinaryNotation => sym::CodeParser_BinaryNotation,
         }
     }
 
@@ -1094,6 +1110,7 @@ impl Operator for BinaryOperator {
             sym::UpTee => BinaryOperator::UpTee,
             sym::DownTee => BinaryOperator::DownTee,
             sym::Application => BinaryOperator::Application,
+            sym::CodeParser_BinaryNotation => BinaryOperator::CodeParser_BinaryNotation,
             _ => return None,
         };
 
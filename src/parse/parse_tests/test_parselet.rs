@@ -1,7 +1,12 @@
 use crate::{
     cst::Cst,
-    parse::{ParseBuilder, ParserSession},
+    parse::{
+        infix_parselet_class, prefix_parselet_class,
+        spec::{operator_spec, operator_specs, OperatorPosition},
+        ParseletClass, ParserSession,
+    },
     parse_cst::ParseCst,
+    tokenize::TokenKind,
     ParseOptions,
 };
 
@@ -87,3 +92,90 @@ fn ParseletTest_Bug4() {
     assert_eq!(session.tokenizer.non_fatal_issues.len(), 0);
     assert_eq!(session.tokenizer.fatal_issues.len(), 0);
 }
+
+#[test]
+fn test_parselet_class() {
+    assert_eq!(
+        prefix_parselet_class(TokenKind::Symbol),
+        ParseletClass::Symbol
+    );
+    assert_eq!(
+        prefix_parselet_class(TokenKind::CloseSquare),
+        ParseletClass::PrefixCloser
+    );
+    assert_eq!(
+        prefix_parselet_class(TokenKind::LongName_Integral),
+        ParseletClass::Integral
+    );
+
+    assert_eq!(
+        infix_parselet_class(TokenKind::OpenSquare),
+        ParseletClass::Call
+    );
+    assert_eq!(infix_parselet_class(TokenKind::Equal), ParseletClass::Equal);
+    assert_eq!(
+        infix_parselet_class(TokenKind::Plus),
+        ParseletClass::InfixOperator
+    );
+
+    // Every token kind has a prefix and an infix parselet.
+    for kind in TokenKind::VARIANTS {
+        prefix_parselet_class(*kind);
+        infix_parselet_class(*kind);
+    }
+}
+
+#[test]
+fn test_operator_specs() {
+    let bang = operator_spec(TokenKind::Bang, OperatorPosition::Infix).unwrap();
+
+    assert_eq!(bang.symbol.as_str(), "System`Factorial");
+    assert_eq!(bang.class, ParseletClass::PostfixOperator);
+
+    let set = operator_spec(TokenKind::Equal, OperatorPosition::Infix).unwrap();
+
+    assert_eq!(set.symbol.as_str(), "System`Set");
+    assert_eq!(set.class, ParseletClass::Equal);
+
+    assert_eq!(
+        operator_spec(TokenKind::Symbol, OperatorPosition::Prefix),
+        None
+    );
+    assert_eq!(
+        operator_spec(TokenKind::Symbol, OperatorPosition::Infix),
+        None
+    );
+
+    let specs = operator_specs();
+
+    assert!(specs.contains(&bang));
+    assert!(specs.contains(&set));
+
+    for spec in specs {
+        let class = match spec.position {
+            OperatorPosition::Prefix => prefix_parselet_class(spec.token),
+            OperatorPosition::Infix => infix_parselet_class(spec.token),
+        };
+
+        assert_eq!(spec.class, class);
+    }
+}
+
+#[test]
+fn test_under_parselets_match_table() {
+    use crate::parse::token_parselets::{
+        prefix_parselet, under1Parselet, under2Parselet, under3Parselet,
+    };
+
+    // SymbolParselet parses  a_  with these instead of the table.
+    for (kind, under) in [
+        (TokenKind::Under, &under1Parselet),
+        (TokenKind::UnderUnder, &under2Parselet),
+        (TokenKind::UnderUnderUnder, &under3Parselet),
+    ] {
+        assert_eq!(
+            format!("{:?}", prefix_parselet(kind)),
+            format!("{:?}", under)
+        );
+    }
+}
@@ -9,7 +9,7 @@ use crate::{
     parse::{
         operators::{
             BinaryOperator, CompoundOperator, GroupOperator, InfixOperator,
-            PostfixOperator, PrefixBinaryOperator, PrefixOperator,
+            Operator, PostfixOperator, PrefixBinaryOperator, PrefixOperator,
             TernaryOperator,
         },
         token_parselets::{under1Parselet, under2Parselet, under3Parselet},
@@ -17,6 +17,7 @@ use crate::{
     },
     precedence::Precedence,
     source::*,
+    symbol::Symbol,
     tokenize::{
         token_kind::{Closer, GroupOpenerToCloser, TokenToCloser},
         tokenizer::Tokenizer_currentToken_stringifyAsTag,
@@ -27,7 +28,15 @@ use crate::{
 //
 /// Classes that derive from Parselet are responsible for parsing specific kinds of syntax
 //
-pub(crate) trait Parselet: std::fmt::Debug {}
+pub(crate) trait Parselet: std::fmt::Debug {
+    fn class(&self) -> ParseletClass;
+
+    /// The symbol of the operator parsed by this parselet, and its precedence
+    /// if it doesn't depend on the surrounding input.
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        None
+    }
+}
 
 //======================================
 // Parselet categories
@@ -104,56 +113,233 @@ impl From<BinaryOperator> for InfixParseletOperator {
 // Parselet types
 //======================================
 
+/// The kind of parselet that parses a [`TokenKind`].
+///
+/// See [`prefix_parselet_class()`][crate::parse::prefix_parselet_class] and
+/// [`infix_parselet_class()`][crate::parse::infix_parselet_class].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseletClass {
+    Leaf,
+    Call,
+    PrefixEndOfFile,
+    Under,
+    PrefixToplevelCloser,
+    BinaryOperator,
+    Group,
+    PrefixOperator,
+    InfixOperator,
+    PostfixOperator,
+    Equal,
+    ColonEqual,
+    UnderDot,
+    PrefixAssertFalse,
+    Symbol,
+    PrefixUnhandled,
+    InfixImplicitTimes,
+    Comma,
+    Tilde,
+    InfixAssertFalse,
+    InfixDifferentialD,
+    Times,
+    SlashColon,
+    GreaterGreater,
+    GreaterGreaterGreater,
+    Colon,
+    ColonColon,
+    PrefixCloser,
+    PrefixError,
+    PrefixComma,
+    Semi,
+    SemiSemi,
+    InfixToplevelNewline,
+    Hash,
+    HashHash,
+    Percent,
+    PrefixUnsupportedToken,
+    Integral,
+    LessLess,
+    /// A user-defined prefix operator. See [`crate::notation`].
+    PrefixNotation,
+    /// A user-defined infix operator. See [`crate::notation`].
+    InfixNotation,
+}
+
 macro_rules! impl_Parselet {
-    ($($name:ident),* $(,)?) => {
+    ($($name:ident => $class:ident),* $(,)?) => {
         $(
-            impl Parselet for $name {}
+            impl Parselet for $name {
+                fn class(&self) -> ParseletClass {
+                    ParseletClass::$class
+                }
+            }
         )*
     };
 }
 
 impl_Parselet!(
-    LeafParselet,
-    CallParselet,
-    PrefixEndOfFileParselet,
-    UnderParselet,
-    PrefixToplevelCloserParselet,
-    BinaryOperatorParselet,
-    GroupParselet,
-    PrefixOperatorParselet,
-    InfixOperatorParselet,
-    PostfixOperatorParselet,
-    EqualParselet,
-    ColonEqualParselet,
-    UnderDotParselet,
-    PrefixAssertFalseParselet,
-    SymbolParselet,
-    PrefixUnhandledParselet,
-    InfixImplicitTimesParselet,
-    CommaParselet,
-    TildeParselet,
-    InfixAssertFalseParselet,
-    InfixDifferentialDParselet,
-    TimesParselet,
-    SlashColonParselet,
-    GreaterGreaterParselet,
-    GreaterGreaterGreaterParselet,
-    ColonParselet,
-    ColonColonParselet,
-    PrefixCloserParselet,
-    PrefixErrorParselet,
-    PrefixCommaParselet,
-    SemiParselet,
-    SemiSemiParselet,
-    InfixToplevelNewlineParselet,
-    HashParselet,
-    HashHashParselet,
-    PercentParselet,
-    PrefixUnsupportedTokenParselet,
-    IntegralParselet,
-    LessLessParselet,
+    LeafParselet => Leaf,
+    PrefixEndOfFileParselet => PrefixEndOfFile,
+    UnderParselet => Under,
+    PrefixToplevelCloserParselet => PrefixToplevelCloser,
+    UnderDotParselet => UnderDot,
+    PrefixAssertFalseParselet => PrefixAssertFalse,
+    SymbolParselet => Symbol,
+    PrefixUnhandledParselet => PrefixUnhandled,
+    InfixImplicitTimesParselet => InfixImplicitTimes,
+    CommaParselet => Comma,
+    TildeParselet => Tilde,
+    InfixAssertFalseParselet => InfixAssertFalse,
+    InfixDifferentialDParselet => InfixDifferentialD,
+    SlashColonParselet => SlashColon,
+    GreaterGreaterParselet => GreaterGreater,
+    GreaterGreaterGreaterParselet => GreaterGreaterGreater,
+    ColonParselet => Colon,
+    ColonColonParselet => ColonColon,
+    PrefixCloserParselet => PrefixCloser,
+    PrefixErrorParselet => PrefixError,
+    PrefixCommaParselet => PrefixComma,
+    SemiParselet => Semi,
+    SemiSemiParselet => SemiSemi,
+    InfixToplevelNewlineParselet => InfixToplevelNewline,
+    HashParselet => Hash,
+    HashHashParselet => HashHash,
+    PercentParselet => Percent,
+    PrefixUnsupportedTokenParselet => PrefixUnsupportedToken,
+    LessLessParselet => LessLess,
 );
 
+// Parselets for a single operator.
+
+impl Parselet for CallParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::Call
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        self.GP
+            .Op
+            .try_to_call_operator()
+            .map(|op| (op.to_symbol(), Some(Precedence::CALL)))
+    }
+}
+
+impl Parselet for BinaryOperatorParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::BinaryOperator
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((self.Op.to_symbol(), Some(self.precedence)))
+    }
+}
+
+impl Parselet for GroupParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::Group
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((self.Op.to_symbol(), None))
+    }
+}
+
+impl Parselet for PrefixOperatorParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::PrefixOperator
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((self.Op.to_symbol(), Some(self.precedence)))
+    }
+}
+
+impl Parselet for InfixOperatorParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::InfixOperator
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((self.Op.to_symbol(), Some(self.precedence)))
+    }
+}
+
+impl Parselet for PostfixOperatorParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::PostfixOperator
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((self.Op.to_symbol(), Some(self.precedence)))
+    }
+}
+
+impl Parselet for EqualParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::Equal
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        self.op.operator()
+    }
+}
+
+impl Parselet for ColonEqualParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::ColonEqual
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        self.op.operator()
+    }
+}
+
+impl Parselet for TimesParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::Times
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((InfixOperator::Times.to_symbol(), Some(Precedence::STAR)))
+    }
+}
+
+impl Parselet for PrefixNotationParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::PrefixNotation
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((
+            PrefixOperator::CodeParser_PrefixNotation.to_symbol(),
+            Some(self.precedence),
+        ))
+    }
+}
+
+impl Parselet for InfixNotationParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::InfixNotation
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((
+            BinaryOperator::CodeParser_BinaryNotation.to_symbol(),
+            Some(self.precedence),
+        ))
+    }
+}
+
+impl Parselet for IntegralParselet {
+    fn class(&self) -> ParseletClass {
+        ParseletClass::Integral
+    }
+
+    fn operator(&self) -> Option<(Symbol, Option<Precedence>)> {
+        Some((self.Op1.to_symbol(), None))
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct CallParselet /*: public InfixParselet*/ {
     GP: GroupParselet,
@@ -255,6 +441,22 @@ pub(crate) struct PostfixOperatorParselet /* : InfixParselet */ {
 }
 
 
+//
+// User-defined notations are not in the parselet tables, and are created by
+// ParserSession when a token has a notation.
+//
+#[derive(Debug)]
+pub(crate) struct PrefixNotationParselet /* : PrefixParselet */ {
+    precedence: Precedence,
+}
+
+
+#[derive(Debug)]
+pub(crate) struct InfixNotationParselet /* : InfixParselet */ {
+    precedence: Precedence,
+}
+
+
 #[derive(Debug)]
 pub(crate) struct GroupParselet /* : PrefixParselet */ {
     Op: GroupOperator,
@@ -592,9 +794,7 @@ impl<'i, B: ParseBuilder<'i> + 'i> PrefixParselet<'i, B>
         // TODO(cleanup): This call does nothing? Add test and remove.
         let _ = session.tokenizer.peek_token();
 
-        let TokenPrecedence = B::with_infix_parselet(tok_in.tok, |parselet| {
-            parselet.getPrecedence(session)
-        });
+        let TokenPrecedence = session.infix_precedence(tok_in);
 
         //
         // if (Ctxt.prec > TokenPrecedence)
@@ -931,6 +1131,97 @@ impl<'i, B: ParseBuilder<'i> + 'i> InfixParselet<'i, B>
     }
 }
 
+//======================================
+// PrefixNotationParselet
+//======================================
+
+impl PrefixNotationParselet {
+    pub(crate) const fn new(precedence: Precedence) -> Self {
+        PrefixNotationParselet { precedence }
+    }
+}
+
+impl<'i, B: ParseBuilder<'i> + 'i> PrefixParselet<'i, B>
+    for PrefixNotationParselet
+{
+    fn parse_prefix(
+        &self,
+        session: &mut ParserSession<'i, B>,
+        tok_in: TokenRef<'i>,
+    ) -> B::Node {
+        panic_if_aborted!();
+
+        let tok_in = session.push_syntax_and_next(tok_in);
+
+        let _ = session.push_context(self.precedence);
+
+        let (trivia, tok) = session.current_token_eat_trivia();
+
+        let operand = session.parse_prefix(tok);
+
+        let node = session.reduce_prefix(
+            PrefixOperator::CodeParser_PrefixNotation,
+            tok_in,
+            trivia,
+            operand,
+        );
+
+        return session.parse_climb(node);
+    }
+}
+
+//======================================
+// InfixNotationParselet
+//======================================
+
+impl InfixNotationParselet {
+    pub(crate) const fn new(precedence: Precedence) -> Self {
+        InfixNotationParselet { precedence }
+    }
+}
+
+impl<'i, B: ParseBuilder<'i> + 'i> InfixParselet<'i, B>
+    for InfixNotationParselet
+{
+    fn parse_infix(
+        &self,
+        session: &mut ParserSession<'i, B>,
+        lhs_node: B::Node,
+        trivia1: B::TriviaHandle,
+        tok_in: TokenRef<'i>,
+    ) -> B::Node {
+        panic_if_aborted!();
+
+        let tok_in = session.push_syntax_and_next(tok_in);
+
+        let (trivia2, tok) = session.current_token_eat_trivia();
+
+        let rhs_node = session.parse_prefix(tok);
+
+        let node = session.reduce_binary(
+            BinaryOperator::CodeParser_BinaryNotation,
+            lhs_node,
+            trivia1,
+            tok_in,
+            trivia2,
+            rhs_node,
+        );
+
+        return session.parse_climb(node);
+    }
+
+    fn getPrecedence(
+        &self,
+        _session: &ParserSession<'i, B>,
+    ) -> Option<Precedence> {
+        Some(self.precedence)
+    }
+
+    fn getOp(&self) -> InfixParseletOperator {
+        BinaryOperator::CodeParser_BinaryNotation.into()
+    }
+}
+
 //======================================
 // InfixOperatorParselet
 //======================================
@@ -1015,8 +1306,7 @@ impl InfixOperatorParselet {
             //
             // if tok1.tok.infix_parselet().getOp() != self.getOp() {
 
-            let tok1_op =
-                B::with_infix_parselet(tok1.tok, |parselet| parselet.getOp());
+            let tok1_op = session.infix_op(tok1);
 
             if tok1_op != <Self as InfixParselet<'i, B>>::getOp(self) {
                 //
@@ -1948,10 +2238,10 @@ impl<'i, B: ParseBuilder<'i> + 'i> InfixParselet<'i, B> for SemiParselet {
         //
 
         //
-        // CompoundExpression should not cross toplevel newlines
+        // CompoundExpression should not cross toplevel newlines, unless
+        // continuing while an operator is pending
         //
-        let (trivia2, tok2) =
-            session.current_token_eat_trivia_but_not_toplevel_newlines();
+        let (trivia2, tok2) = session.current_token_eat_trivia_after_operator();
 
         if tok2.tok == TokenKind::Semi {
             //
@@ -2059,10 +2349,11 @@ impl SemiParselet {
             let (trivia1, tok1) = session.commit_syntax_and_next(trivia1, tok1);
 
             //
-            // CompoundExpression should not cross toplevel newlines
+            // CompoundExpression should not cross toplevel newlines, unless
+            // continuing while an operator is pending
             //
             let (trivia2, tok2) =
-                session.current_token_eat_trivia_but_not_toplevel_newlines();
+                session.current_token_eat_trivia_after_operator();
 
             if tok2.tok == TokenKind::Semi {
                 //
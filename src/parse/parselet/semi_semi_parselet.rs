@@ -86,10 +86,11 @@ impl SemiSemiParselet {
         panic_if_aborted!();
 
         //
-        // Span should not cross toplevel newlines
+        // Span should not cross toplevel newlines, unless continuing while an
+        // operator is pending
         //
         let (trivia2, SecondTok) =
-            session.current_token_eat_trivia_but_not_toplevel_newlines();
+            session.current_token_eat_trivia_after_operator();
 
         //
         // a;;
@@ -160,10 +161,11 @@ impl SemiSemiParselet {
         let trivia3 = B::empty_trivia();
 
         //
-        // Span should not cross toplevel newlines
+        // Span should not cross toplevel newlines, unless continuing while an
+        // operator is pending
         //
         let (trivia4, ThirdTok) =
-            session.current_token_eat_trivia_but_not_toplevel_newlines_into();
+            session.current_token_eat_trivia_after_operator_into();
 
         if !ThirdTok.tok.isPossibleBeginning()
             || ThirdTok.tok == TokenKind::SemiSemi
@@ -278,10 +280,11 @@ impl SemiSemiParselet {
         ThirdTok.skip(&mut session.tokenizer);
 
         //
-        // Span should not cross toplevel newlines
+        // Span should not cross toplevel newlines, unless continuing while an
+        // operator is pending
         //
         let (trivia4, FourthTok) =
-            session.current_token_eat_trivia_but_not_toplevel_newlines_into();
+            session.current_token_eat_trivia_after_operator_into();
 
         if !FourthTok.tok.isPossibleBeginning()
             || FourthTok.tok == TokenKind::SemiSemi
@@ -16,6 +16,8 @@ impl<'i, B: ParseBuilder<'i> + 'i> InfixParselet<'i, B> for TimesParselet {
     ) -> B::Node {
         panic_if_aborted!();
 
+        session.check_implicit_times(tok_in);
+
         let tok_in = session.push_syntax_and_next(tok_in);
 
         let mut infix_state =
@@ -85,8 +87,7 @@ impl TimesParselet {
             // and we want only a single Infix node created
             //
 
-            let tok1_op =
-                B::with_infix_parselet(tok1.tok, |parselet| parselet.getOp());
+            let tok1_op = session.infix_op(tok1);
 
             if tok1_op
                 != <TimesParselet as InfixParselet<B>>::getOp(&TimesParselet {})
@@ -103,6 +104,8 @@ impl TimesParselet {
                 return session.parse_climb(node);
             }
 
+            session.check_implicit_times(tok1);
+
             let (trivia1, tok1) = session.commit_syntax_and_next(trivia1, tok1);
 
             let (trivia2, Tok2) = session.current_token_eat_trivia();
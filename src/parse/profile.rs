@@ -0,0 +1,166 @@
+//! Checking parsed syntax against a restricted [`GrammarProfile`].
+
+use crate::{
+    cst::{
+        BinaryNode, Cst, CstSeq, GroupMissingCloserNode,
+        GroupMissingOpenerNode, GroupNode, InfixNode, OperatorNode, PrefixNode,
+    },
+    issue::{Issue, IssueTag, Severity, SyntaxIssue},
+    parse::operators::{
+        BinaryOperator, GroupOperator, InfixOperator, PrefixOperator,
+    },
+    source::Span,
+    tokenize::{Token, TokenKind},
+    GrammarProfile,
+};
+
+/// Report every top-level or nested expression in `nodes` that is not allowed
+/// by `profile`.
+///
+/// Only the outermost disallowed node is reported; its children are not
+/// checked.
+pub(crate) fn check_profile<I>(
+    nodes: &CstSeq<I, Span>,
+    profile: GrammarProfile,
+    issues: &mut Vec<Issue>,
+) {
+    match profile {
+        GrammarProfile::Full => (),
+        GrammarProfile::Data => {
+            for node in nodes.iter() {
+                check_data(node, issues);
+            }
+        },
+    }
+}
+
+//======================================
+// GrammarProfile::Data
+//======================================
+
+fn check_data<I>(node: &Cst<I, Span>, issues: &mut Vec<Issue>) {
+    let disallowed = match node {
+        Cst::Token(Token { tok, .. }) => {
+            if is_data_token(*tok) {
+                return;
+            }
+
+            format!("`{}`", tok.name())
+        },
+        Cst::Group(GroupNode(OperatorNode { op, children }))
+        | Cst::GroupMissingCloser(GroupMissingCloserNode(OperatorNode {
+            op,
+            children,
+        }))
+        | Cst::GroupMissingOpener(GroupMissingOpenerNode(OperatorNode {
+            op,
+            children,
+        })) => match op {
+            GroupOperator::List | GroupOperator::Association => {
+                return check_data_children(children, issues);
+            },
+            _ => format!("Group `{op:?}`"),
+        },
+        Cst::Infix(InfixNode(OperatorNode { op, children })) => match op {
+            InfixOperator::CodeParser_Comma => {
+                return check_data_children(children, issues);
+            },
+            _ => format!("Operator `{op:?}`"),
+        },
+        Cst::Binary(BinaryNode(OperatorNode { op, children })) => match op {
+            BinaryOperator::Rule | BinaryOperator::RuleDelayed => {
+                return check_data_children(children, issues);
+            },
+            _ => format!("Operator `{op:?}`"),
+        },
+        Cst::Prefix(PrefixNode(OperatorNode { op, children })) => match op {
+            // Signed numbers, e.g. `-1`
+            PrefixOperator::Minus | PrefixOperator::Plus
+                if is_signed_number(children) =>
+            {
+                return;
+            },
+            _ => format!("Operator `{op:?}`"),
+        },
+        // Already reported as a syntax error.
+        Cst::SyntaxError(_) => return,
+        Cst::Call(_) => "Function call".to_owned(),
+        Cst::Postfix(node) => format!("Operator `{:?}`", node.0.op),
+        Cst::Ternary(node) => format!("Operator `{:?}`", node.0.op),
+        Cst::PrefixBinary(node) => format!("Operator `{:?}`", node.0.op),
+        Cst::Compound(node) => format!("Operator `{:?}`", node.0.op),
+        Cst::Box(_) | Cst::Code(_) => "Box syntax".to_owned(),
+    };
+
+    issues.push(SyntaxIssue(
+        IssueTag::DisallowedSyntax,
+        format!("{disallowed} is not allowed in data-only input."),
+        Severity::Fatal,
+        node.get_source(),
+        1.0,
+        vec![],
+        vec![],
+    ));
+}
+
+fn check_data_children<I>(
+    children: &CstSeq<I, Span>,
+    issues: &mut Vec<Issue>,
+) {
+    for child in children.iter() {
+        check_data(child, issues);
+    }
+}
+
+/// Literals, trivia, and the syntax tokens that can appear as children of
+/// nodes allowed by [`GrammarProfile::Data`].
+fn is_data_token(tok: TokenKind) -> bool {
+    // Errors have already been reported.
+    if tok.isTrivia() || tok.isError() {
+        return true;
+    }
+
+    matches!(
+        tok,
+        TokenKind::Symbol
+            | TokenKind::String
+            | TokenKind::Integer
+            | TokenKind::Real
+            | TokenKind::Rational
+            // `{1,}`
+            | TokenKind::Fake_ImplicitNull
+            | TokenKind::Comma
+            | TokenKind::OpenCurly
+            | TokenKind::CloseCurly
+            | TokenKind::LessBar
+            | TokenKind::BarGreater
+            | TokenKind::LongName_LeftAssociation
+            | TokenKind::LongName_RightAssociation
+            | TokenKind::MinusGreater
+            | TokenKind::ColonGreater
+            | TokenKind::LongName_Rule
+            | TokenKind::LongName_RuleDelayed
+    )
+}
+
+/// `children` of a prefix `-` or `+` node is a sign followed by a number.
+fn is_signed_number<I>(children: &CstSeq<I, Span>) -> bool {
+    let mut tokens = children.iter().filter(|child| match child {
+        Cst::Token(Token { tok, .. }) => !tok.isTrivia(),
+        _ => true,
+    });
+
+    // Skip the operator token.
+    tokens.next();
+
+    matches!(
+        (tokens.next(), tokens.next()),
+        (
+            Some(Cst::Token(Token {
+                tok: TokenKind::Integer | TokenKind::Real | TokenKind::Rational,
+                ..
+            })),
+            None
+        )
+    )
+}
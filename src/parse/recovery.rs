@@ -0,0 +1,448 @@
+//! Recovering from input that the parser fails to parse.
+//!
+//! See [`RecoveryMode`].
+
+use std::{
+    any::Any,
+    collections::HashSet,
+    panic::{self, AssertUnwindSafe},
+};
+
+#[cfg(feature = "bumpalo")]
+use bumpalo::Bump;
+
+#[cfg(feature = "bumpalo")]
+use crate::cst::arena::{self, ArenaCst};
+use crate::{
+    cst::{Cst, CstSeq, SyntaxErrorNode},
+    issue::{Issue, IssueTag, Severity, SyntaxIssue},
+    parse::SyntaxErrorKind,
+    read::InputMark,
+    source::{LineMap, Span},
+    tokenize::{
+        token_kind::TryGroupOpenerToCloser,
+        tokenizer::{Tokenizer, TrackedSourceLocations},
+        Token, TokenKind, TokenStr,
+    },
+    NodeSeq, ParseOptions, ParseResult, RecoveryMode,
+};
+
+/// Call `parse`, reparsing `input` one top-level expression at a time if it
+/// panics and `opts` uses [`RecoveryMode::Recover`].
+///
+/// Each expression that fails to parse again is returned as an
+/// [`Unparsable`][SyntaxErrorKind::Unparsable] node, and parsing resumes at
+/// the next top-level newline after it.
+pub(crate) fn recover_cst_seq<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    parse: impl FnOnce() -> ParseResult<CstSeq<TokenStr<'i>>>,
+) -> ParseResult<CstSeq<TokenStr<'i>>> {
+    let msg = match catch(opts, parse) {
+        Ok(result) => return result,
+        Err(msg) => msg,
+    };
+
+    recover_exprs(input, opts, &msg)
+}
+
+/// Like [`recover_cst_seq()`], for parsing into the arena `bump`.
+#[cfg(feature = "bumpalo")]
+pub(crate) fn recover_cst_seq_in<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    bump: &'i Bump,
+    parse: impl FnOnce() -> ParseResult<&'i [ArenaCst<'i>]>,
+) -> ParseResult<&'i [ArenaCst<'i>]> {
+    let msg = match catch(opts, parse) {
+        Ok(result) => return result,
+        Err(msg) => msg,
+    };
+
+    recover_exprs(input, opts, &msg).map(|NodeSeq(nodes)| {
+        &*bump.alloc_slice_fill_iter(
+            nodes.iter().map(|node| ArenaCst::from_cst(bump, node)),
+        )
+    })
+}
+
+/// Like [`recover_cst_seq()`], for parsing functions that only return the
+/// syntax and issues.
+pub(crate) fn recover_cst_seq_simple<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    parse: impl FnOnce() -> (CstSeq<TokenStr<'i>>, Vec<Issue>),
+) -> (CstSeq<TokenStr<'i>>, Vec<Issue>) {
+    let msg = match catch(opts, parse) {
+        Ok(result) => return result,
+        Err(msg) => msg,
+    };
+
+    let ParseResult {
+        syntax,
+        fatal_issues: mut issues,
+        non_fatal_issues,
+        ..
+    } = recover_exprs(input, opts, &msg);
+
+    issues.extend(non_fatal_issues);
+
+    (syntax, issues)
+}
+
+/// Like [`recover_cst_seq()`], for parsing a single expression starting at
+/// `start`.
+///
+/// If `parse` panics, the tokens up to the next top-level newline are
+/// returned as a single [`Unparsable`][SyntaxErrorKind::Unparsable] node, and
+/// parsing resumes after them.
+pub(crate) fn recover_cst_first<'i>(
+    input: &'i [u8],
+    start: Option<InputMark>,
+    opts: &ParseOptions,
+    parse: impl FnOnce() -> (ParseResult<Option<Cst<TokenStr<'i>>>>, InputMark),
+) -> (ParseResult<Option<Cst<TokenStr<'i>>>>, InputMark) {
+    let msg = match catch(opts, parse) {
+        Ok(result) => return result,
+        Err(msg) => msg,
+    };
+
+    let (node, end) = unparsable_expr(input, start, opts);
+
+    let issue = failure_issue(&msg, &node);
+
+    let result = ParseResult {
+        syntax: Some(node),
+        unsafe_character_encoding: None,
+        fatal_issues: vec![issue],
+        non_fatal_issues: Vec::new(),
+        directives: Vec::new(),
+        tracked: empty_tracked(),
+    };
+
+    (result, end)
+}
+
+/// Get the single top-level node of `input`, for the parsing functions that
+/// return one node.
+///
+/// If there is not exactly one node, the input is returned as a single
+/// [`Unparsable`][SyntaxErrorKind::Unparsable] node, along with a fatal
+/// issue.
+pub(crate) fn single_cst<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    NodeSeq(nodes): CstSeq<TokenStr<'i>>,
+) -> (Cst<TokenStr<'i>>, Option<Issue>) {
+    let nodes = match <[_; 1]>::try_from(nodes) {
+        Ok([node]) => return (node, None),
+        Err(nodes) => nodes,
+    };
+
+    if nodes.is_empty() {
+        let (node, issue) = unparsable(input, opts, "Expected an expression.");
+
+        return (node, Some(issue));
+    }
+
+    let msg =
+        format!("Expected a single expression, found {} nodes.", nodes.len());
+
+    let node = Cst::SyntaxError(SyntaxErrorNode::new(
+        SyntaxErrorKind::Unparsable,
+        NodeSeq(nodes),
+    ));
+
+    let issue = failure_issue(&msg, &node);
+
+    (node, Some(issue))
+}
+
+/// Like [`single_cst()`], for nodes allocated in `bump`.
+#[cfg(feature = "bumpalo")]
+pub(crate) fn single_cst_in<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    bump: &'i Bump,
+    nodes: &'i [ArenaCst<'i>],
+) -> (ArenaCst<'i>, Option<Issue>) {
+    if let [node] = nodes {
+        return (*node, None);
+    }
+
+    let (node, issue) = single_cst(input, opts, arena::to_cst_seq(nodes));
+
+    (ArenaCst::from_cst(bump, &node), issue)
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// Parse `input` one top-level expression at a time, after parsing all of it
+/// at once failed with `msg`.
+///
+/// If every expression parses on its own, the failure depended on more than
+/// one of them, and all of `input` is returned as a single
+/// [`Unparsable`][SyntaxErrorKind::Unparsable] node instead.
+fn recover_exprs<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    msg: &str,
+) -> ParseResult<CstSeq<TokenStr<'i>>> {
+    let mut nodes = Vec::new();
+    let mut unsafe_character_encoding = None;
+    let mut fatal_issues = Vec::new();
+    let mut non_fatal_issues = Vec::new();
+    let mut directives = Vec::new();
+
+    let mut failed = false;
+    let mut start = None;
+
+    loop {
+        let (result, end) = recover_cst_first(input, start, opts, || {
+            super::parse_cst_first_inner(input, start, opts)
+        });
+
+        let Some(node) = result.syntax else {
+            break;
+        };
+
+        failed |= result
+            .fatal_issues
+            .iter()
+            .any(|issue| issue.tag == IssueTag::ParserFailure);
+
+        nodes.push(node);
+        unsafe_character_encoding =
+            unsafe_character_encoding.or(result.unsafe_character_encoding);
+        fatal_issues.extend(result.fatal_issues);
+        non_fatal_issues.extend(result.non_fatal_issues);
+        directives.extend(result.directives);
+
+        start = Some(end);
+    }
+
+    if !failed {
+        let (node, issue) = unparsable(input, opts, msg);
+
+        nodes = vec![node];
+        unsafe_character_encoding = None;
+        fatal_issues = vec![issue];
+        non_fatal_issues = Vec::new();
+        directives = Vec::new();
+    }
+
+    ParseResult {
+        syntax: NodeSeq(nodes),
+        unsafe_character_encoding,
+        fatal_issues,
+        non_fatal_issues,
+        directives,
+        tracked: empty_tracked(),
+    }
+}
+
+/// Construct an [`Unparsable`][SyntaxErrorKind::Unparsable] node containing
+/// the tokens of the expression at or after `start` that could not be parsed.
+///
+/// The expression is assumed to end at the first top-level newline that is
+/// outside of any brackets. Returns the position after the node.
+fn unparsable_expr<'i>(
+    input: &'i [u8],
+    start: Option<InputMark>,
+    opts: &ParseOptions,
+) -> (Cst<TokenStr<'i>>, InputMark) {
+    let span = input_span(input, opts);
+
+    let expr = panic::catch_unwind(AssertUnwindSafe(|| {
+        expr_tokens(input, start, opts)
+    }));
+
+    let (children, end) = match expr {
+        Ok((tokens, Some(end))) => {
+            (tokens.into_iter().map(Cst::Token).collect(), end)
+        },
+        // The rest of the input could not be tokenized, or has no tokens
+        // other than trivia.
+        _ => {
+            let (offset, start) = match start {
+                Some(start) => (start.offset(), start.src_loc),
+                None => (0, span.start()),
+            };
+
+            let token = Cst::Token(Token {
+                tok: TokenKind::Error_UnhandledCharacter,
+                input: TokenStr::new(&input[offset..]),
+                src: Span::new(start, span.end()),
+            });
+
+            (vec![token], InputMark::new(input.len(), span.end()))
+        },
+    };
+
+    let node = Cst::SyntaxError(SyntaxErrorNode::new(
+        SyntaxErrorKind::Unparsable,
+        NodeSeq(children),
+    ));
+
+    (node, end)
+}
+
+/// Get the tokens of the expression at or after `start`, up to the first
+/// top-level newline outside of any brackets, and the position after the
+/// last of them.
+///
+/// Trivia before and after the expression is not included.
+fn expr_tokens<'i>(
+    input: &'i [u8],
+    start: Option<InputMark>,
+    opts: &ParseOptions,
+) -> (Vec<Token<TokenStr<'i>>>, Option<InputMark>) {
+    let mut tokenizer = Tokenizer::new(input, opts);
+
+    if let Some(start) = start {
+        tokenizer.seek(start);
+    }
+
+    let mut tokens = Vec::new();
+    let mut len = 0;
+    let mut end = None;
+    let mut depth: usize = 0;
+
+    loop {
+        let token = tokenizer.peek_token();
+
+        match token.tok {
+            TokenKind::EndOfFile => break,
+            TokenKind::ToplevelNewline if depth == 0 && end.is_some() => break,
+            tok if tok.isTrivia() && tokens.is_empty() => {
+                token.skip(&mut tokenizer);
+
+                continue;
+            },
+            tok if TryGroupOpenerToCloser(tok).is_some() => depth += 1,
+            tok if tok.isCloser() => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+
+        tokens.push(token);
+        token.skip(&mut tokenizer);
+
+        if !token.tok.isTrivia() {
+            len = tokens.len();
+            end = Some(tokenizer.mark());
+        }
+    }
+
+    tokens.truncate(len);
+
+    (tokens, end)
+}
+
+/// Call `f`, catching any panic if `opts` uses [`RecoveryMode::Recover`].
+///
+/// Returns a description of the panic if one was caught. See
+/// [`RecoveryMode::Recover`] for the limits of catching panics.
+fn catch<T>(opts: &ParseOptions, f: impl FnOnce() -> T) -> Result<T, String> {
+    match opts.recovery {
+        RecoveryMode::Panic => Ok(f()),
+        RecoveryMode::Recover => panic::catch_unwind(AssertUnwindSafe(f))
+            .map_err(|payload| {
+                format!(
+                    "The parser failed on this input: {}",
+                    panic_message(&*payload)
+                )
+            }),
+    }
+}
+
+/// Construct an [`Unparsable`][SyntaxErrorKind::Unparsable] node containing
+/// the tokens of `input`, and a fatal issue with message `msg`.
+fn unparsable<'i>(
+    input: &'i [u8],
+    opts: &ParseOptions,
+    msg: &str,
+) -> (Cst<TokenStr<'i>>, Issue) {
+    // Don't report the tokens of the fallback to the debug sink.
+    let mut opts = opts.clone();
+    opts.debug_sink = None;
+
+    let tokens = panic::catch_unwind(AssertUnwindSafe(|| {
+        crate::tokenize_bytes(input, &opts)
+    }));
+
+    let children: Vec<Cst<TokenStr<'i>>> = match tokens {
+        Ok(Ok(NodeSeq(tokens))) if !tokens.is_empty() => {
+            tokens.into_iter().map(Cst::Token).collect()
+        },
+        // The input is empty, or could not be tokenized either.
+        _ => {
+            let tok = if input.is_empty() {
+                TokenKind::Error_ExpectedOperand
+            } else {
+                TokenKind::Error_UnhandledCharacter
+            };
+
+            vec![Cst::Token(Token {
+                tok,
+                input: TokenStr::new(input),
+                src: input_span(input, &opts),
+            })]
+        },
+    };
+
+    let node = Cst::SyntaxError(SyntaxErrorNode::new(
+        SyntaxErrorKind::Unparsable,
+        NodeSeq(children),
+    ));
+
+    let issue = failure_issue(msg, &node);
+
+    (node, issue)
+}
+
+fn failure_issue<I>(msg: &str, node: &Cst<I>) -> Issue {
+    SyntaxIssue(
+        IssueTag::ParserFailure,
+        msg.to_owned(),
+        Severity::Fatal,
+        node.get_source(),
+        1.0,
+        vec![],
+        vec![],
+    )
+}
+
+pub(crate) fn empty_tracked() -> TrackedSourceLocations {
+    TrackedSourceLocations {
+        simple_line_continuations: HashSet::new(),
+        complex_line_continuations: HashSet::new(),
+        embedded_newlines: HashSet::new(),
+        embedded_tabs: HashSet::new(),
+    }
+}
+
+/// Source span covering all of `input`.
+fn input_span(input: &[u8], opts: &ParseOptions) -> Span {
+    let input = String::from_utf8_lossy(input);
+
+    let map = LineMap::new(&input, opts);
+
+    let location = |offset| {
+        map.location(offset, opts.src_convention)
+            .expect("offset is a character boundary of the input")
+    };
+
+    Span::new(location(0), location(input.len()))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg
+    } else {
+        "unknown error"
+    }
+}
@@ -0,0 +1,109 @@
+//! The operators recognized by the parser.
+//!
+//! The operator syntax of the Wolfram Language is defined by which parselet
+//! handles each [`TokenKind`] (see [`ParseletClass`]), by the operator symbol
+//! that parselet produces, and by the precedence it parses with. Those
+//! associations are declared in one data file, `src/generated/parselets.txt`,
+//! from which the build script generates the parser's tables. This module
+//! exposes them as [`OperatorSpec`] values, read from the generated tables.
+//!
+//! Adding an operator means adding a row to `parselets.txt`. Only a new kind
+//! of token, operator symbol, or precedence also needs a [`TokenKind`] and
+//! lexing rule, a variant in `parse::operators`, or a value in
+//! `src/generated/precedence/values.rs`.
+//!
+//! ```
+//! use wolfram_parser::{
+//!     parse::spec::{operator_spec, OperatorPosition},
+//!     tokenize::TokenKind,
+//! };
+//!
+//! use OperatorPosition::{Infix, Prefix};
+//!
+//! let plus = operator_spec(TokenKind::Plus, Infix).unwrap();
+//! let times = operator_spec(TokenKind::Star, Infix).unwrap();
+//!
+//! assert_eq!(plus.symbol.as_str(), "System`Plus");
+//! assert!(plus.precedence.unwrap().level < times.precedence.unwrap().level);
+//!
+//! let minus = operator_spec(TokenKind::Minus, Prefix).unwrap();
+//!
+//! assert_eq!(minus.symbol.as_str(), "System`Minus");
+//! ```
+
+use wolfram_expr::symbol::SymbolRef;
+
+use crate::{
+    explain::OperatorPrecedence,
+    parse::{
+        parselet::ParseletClass,
+        token_parselets,
+    },
+    tokenize::TokenKind,
+};
+
+/// How a token that begins an operator is parsed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OperatorSpec {
+    /// The token that begins the operator.
+    pub token: TokenKind,
+    pub position: OperatorPosition,
+    /// The class of parselet that parses the operator.
+    pub class: ParseletClass,
+    /// The canonical symbol of the operator, like `` System`Plus `` for `+`.
+    pub symbol: SymbolRef<'static>,
+    /// The precedence of the operator, or `None` if it depends on the
+    /// surrounding input, or if the operator is a group like `{ .. }`.
+    pub precedence: Option<OperatorPrecedence>,
+}
+
+/// Where a token appears relative to the expression preceding it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OperatorPosition {
+    /// The token begins an expression, like `-` in `-a` or `{` in `{a}`.
+    Prefix,
+    /// The token follows an expression, like `+` in `a + b`, `!` in `a!`, or
+    /// `[` in `f[x]`.
+    Infix,
+}
+
+/// Get the operator that a token of kind `kind` begins when it appears in
+/// `position`, if any.
+pub fn operator_spec(
+    kind: TokenKind,
+    position: OperatorPosition,
+) -> Option<OperatorSpec> {
+    let (class, operator) = match position {
+        OperatorPosition::Prefix => {
+            let parselet = token_parselets::prefix_parselet(kind);
+            (parselet.class(), parselet.operator())
+        },
+        OperatorPosition::Infix => {
+            let parselet = token_parselets::infix_parselet(kind);
+            (parselet.class(), parselet.operator())
+        },
+    };
+
+    let (symbol, precedence) = operator?;
+
+    Some(OperatorSpec {
+        token: kind,
+        position,
+        class,
+        symbol,
+        precedence: precedence.map(OperatorPrecedence::from),
+    })
+}
+
+/// Get every operator recognized by the parser, ordered by token kind, with
+/// the prefix operator of a token before its infix operator.
+pub fn operator_specs() -> Vec<OperatorSpec> {
+    TokenKind::VARIANTS
+        .iter()
+        .flat_map(|&kind| {
+            [OperatorPosition::Prefix, OperatorPosition::Infix]
+                .into_iter()
+                .filter_map(move |position| operator_spec(kind, position))
+        })
+        .collect()
+}
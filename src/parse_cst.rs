@@ -14,15 +14,12 @@ use crate::{
             TernaryOperator,
         },
         parselet::{InfixParselet, PrefixParselet},
-        token_parselets::{
-            token_kind_to_infix_parselet, token_kind_to_prefix_parselet,
-        },
-        ColonLHS, ParseBuilder, SyntaxErrorData, SyntaxErrorKind, TriviaSeqRef,
-        UnderParseData,
+        token_parselets, ColonLHS, ParseBuilder, SyntaxErrorData,
+        SyntaxErrorKind, TriviaSeqRef, UnderParseData,
     },
     tokenize::{TokenKind, TokenRef, TokenStr},
     utils::debug_assert_matches,
-    NodeSeq, ParseOptions,
+    NodeSeq, ParseOptions, ReparseHeuristics,
 };
 
 #[derive(Debug)]
@@ -111,59 +108,18 @@ impl<'i> ParseBuilder<'i> for ParseCst<'i> {
     // Lifecycle
     //==================================
 
-    fn new_builder() -> Self {
-        ParseCst {
-            node_stack: Vec::new(),
-            finished: Vec::new(),
-        }
-    }
-
     fn with_prefix_parselet<R, F>(kind: TokenKind, callback: F) -> R
     where
         F: FnOnce(&dyn PrefixParselet<'i, Self>) -> R,
     {
-        const PREFIX_PARSELETS: [&dyn for<'ii> PrefixParselet<
-            'ii,
-            ParseCst<'ii>,
-        >; TokenKind::COUNT] = crate::utils::from_fn!(
-            [&'static dyn for<'ii> PrefixParselet<'ii, ParseCst<'ii>>, TokenKind::COUNT],
-            |index: usize| {
-                let kind = TokenKind::VARIANTS[index];
-
-                token_kind_to_prefix_parselet!(
-                    &dyn for<'ii> PrefixParselet<'ii, ParseCst<'ii>>;
-                    kind
-                )
-            }
-        );
-
-        let parselet = &*PREFIX_PARSELETS[usize::from(kind.id())];
-
-        callback(parselet)
+        callback(token_parselets::prefix_parselet(kind))
     }
 
     fn with_infix_parselet<R, F: FnOnce(&dyn InfixParselet<'i, Self>) -> R>(
         kind: TokenKind,
         callback: F,
     ) -> R {
-        const INFIX_PARSELETS: [&dyn for<'ii> InfixParselet<
-            'ii,
-            ParseCst<'ii>,
-        >; TokenKind::COUNT] = crate::utils::from_fn!(
-            [&'static dyn for<'ii> InfixParselet<'ii, ParseCst<'ii>>, TokenKind::COUNT],
-            |index: usize| {
-                let kind = TokenKind::VARIANTS[index];
-
-                token_kind_to_infix_parselet!(
-                    &dyn for<'ii> InfixParselet<'ii, ParseCst<'ii>>;
-                    kind
-                )
-            }
-        );
-
-        let parselet = &*INFIX_PARSELETS[usize::from(kind.id())];
-
-        callback(parselet)
+        callback(token_parselets::infix_parselet(kind))
     }
 
     fn finish(self, input: &'i [u8], opts: &ParseOptions) -> Self::Output {
@@ -179,11 +135,14 @@ impl<'i> ParseBuilder<'i> for ParseCst<'i> {
 
         let mut exprs = NodeSeq(finished);
 
-        if let Ok(input) = std::str::from_utf8(input) {
+        if let (Ok(input), Some(heuristics)) =
+            (std::str::from_utf8(input), opts.reparse)
+        {
             exprs = crate::error::reparse_unterminated(
                 exprs,
                 input,
-                crate::safe_convert!(opts.tab_width, usize, "tab_width conversion"),
+                usize::try_from(opts.tab_width_for(input.as_bytes())).unwrap(),
+                heuristics,
             );
         }
 
@@ -618,6 +577,7 @@ impl<'i> ParseBuilder<'i> for ParseCst<'i> {
         ctx_data: Self::ContextData,
         input: &'i str,
         tab_width: usize,
+        heuristics: ReparseHeuristics,
         op: GroupOperator,
         _state: Self::GroupParseState,
         _trailing_trivia: Self::TriviaHandle,
@@ -628,6 +588,7 @@ impl<'i> ParseBuilder<'i> for ParseCst<'i> {
             (op, children),
             input,
             tab_width,
+            heuristics,
         );
 
         self.push_node(Cst::GroupMissingCloser(node))
@@ -801,6 +762,13 @@ impl<'i> ParseBuilder<'i> for ParseCst<'i> {
 }
 
 impl<'i> ParseCst<'i> {
+    pub(crate) fn new_builder() -> Self {
+        ParseCst {
+            node_stack: Vec::new(),
+            finished: Vec::new(),
+        }
+    }
+
     fn push_node(&mut self, node: Cst<TokenStr<'i>>) {
         self.node_stack.push(node)
     }
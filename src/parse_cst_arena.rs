@@ -0,0 +1,730 @@
+use bumpalo::Bump;
+use smallvec::SmallVec;
+
+use crate::{
+    cst::{
+        arena::{self, ArenaCst, ArenaNode, ArenaNodeKind},
+        Cst, TriviaSeq,
+    },
+    parse::{
+        operators::{
+            BinaryOperator, CallOperator, CompoundOperator, GroupOperator,
+            InfixOperator, PostfixOperator, PrefixBinaryOperator,
+            PrefixOperator, TernaryOperator,
+        },
+        parselet::{InfixParselet, PrefixParselet},
+        token_parselets, ColonLHS, ParseBuilder, SyntaxErrorData,
+        SyntaxErrorKind, TriviaSeqRef, UnderParseData,
+    },
+    tokenize::{TokenKind, TokenRef},
+    utils::debug_assert_matches,
+    ParseOptions, ReparseHeuristics,
+};
+
+/// Builds [`ArenaCst`] nodes, in the same way that
+/// [`ParseCst`][crate::parse_cst::ParseCst] builds [`Cst`] nodes.
+///
+/// The children of each node are moved from `node_stack` into a single slice
+/// allocated in `bump` when the node is reduced.
+#[derive(Debug)]
+pub(crate) struct ParseArenaCst<'i> {
+    bump: &'i Bump,
+
+    node_stack: Vec<ArenaCst<'i>>,
+
+    finished: Vec<ArenaCst<'i>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Context {
+    /// Index in [`ParseArenaCst::node_stack`] at which nodes associated with
+    /// the current context begin.
+    start_index: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct InfixParseArenaCst {
+    op: InfixOperator,
+}
+
+#[derive(Debug)]
+pub(crate) struct InfixParseGroup {}
+
+impl<'i> ParseBuilder<'i> for ParseArenaCst<'i> {
+    type Node = ();
+    type SyntaxTokenNode = ();
+
+    type Output = &'i [ArenaCst<'i>];
+
+    type ContextData = Context;
+
+    type InfixParseState = InfixParseArenaCst;
+    type GroupParseState = InfixParseGroup;
+
+    //==================================
+    // Trivia handling
+    //==================================
+
+    type ResettableTriviaAccumulator = SmallVec<[TokenRef<'i>; 4]>;
+    type ResettableTriviaHandle = TriviaSeqRef<'i>;
+
+    type TriviaHandle = ();
+
+    fn resettable_trivia_begin(&mut self) -> Self::ResettableTriviaAccumulator {
+        SmallVec::new()
+    }
+
+    fn resettable_trivia_push(
+        &mut self,
+        state: &mut Self::ResettableTriviaAccumulator,
+        trivia: TokenRef<'i>,
+    ) {
+        state.push(trivia);
+    }
+
+    fn resettable_trivia_end(
+        &mut self,
+        state: Self::ResettableTriviaAccumulator,
+    ) -> Self::ResettableTriviaHandle {
+        TriviaSeq(state)
+    }
+
+    fn empty_trivia() -> Self::TriviaHandle {}
+
+    fn reset_trivia_seq(
+        &mut self,
+        trivia: Self::ResettableTriviaHandle,
+    ) -> Option<TokenRef<'i>> {
+        trivia.0.first().copied()
+    }
+
+    fn push_trivia_seq(
+        &mut self,
+        seq: Self::ResettableTriviaHandle,
+    ) -> Self::TriviaHandle {
+        let TriviaSeq(vec) = seq;
+
+        self.node_stack.extend(vec.into_iter().map(ArenaCst::Token));
+    }
+
+    //==================================
+    // Lifecycle
+    //==================================
+
+    fn with_prefix_parselet<R, F>(kind: TokenKind, callback: F) -> R
+    where
+        F: FnOnce(&dyn PrefixParselet<'i, Self>) -> R,
+    {
+        callback(token_parselets::arena_prefix_parselet(kind))
+    }
+
+    fn with_infix_parselet<R, F: FnOnce(&dyn InfixParselet<'i, Self>) -> R>(
+        kind: TokenKind,
+        callback: F,
+    ) -> R {
+        callback(token_parselets::arena_infix_parselet(kind))
+    }
+
+    fn finish(self, input: &'i [u8], opts: &ParseOptions) -> Self::Output {
+        let ParseArenaCst {
+            bump,
+            node_stack,
+            finished,
+        } = self;
+
+        debug_assert!(
+            node_stack.is_empty(),
+            "expected empty node stack, got: {node_stack:#?}"
+        );
+
+        let exprs: &[ArenaCst] = bump.alloc_slice_copy(&finished);
+
+        let (Ok(input), Some(heuristics)) =
+            (std::str::from_utf8(input), opts.reparse)
+        else {
+            return exprs;
+        };
+
+        // Unterminated tokens are rare, so instead of reparsing them in
+        // place, copy the few trees that contain them.
+        if !exprs.iter().any(has_unterminated_token) {
+            return exprs;
+        }
+
+        let exprs = crate::error::reparse_unterminated(
+            arena::to_cst_seq(exprs),
+            input,
+            usize::try_from(opts.tab_width_for(input.as_bytes())).unwrap(),
+            heuristics,
+        );
+
+        bump.alloc_slice_fill_iter(
+            exprs.iter().map(|cst| ArenaCst::from_cst(bump, cst)),
+        )
+    }
+
+    //==================================
+    // Context management
+    //==================================
+
+    fn begin_context<'s>(&'s mut self) -> Self::ContextData {
+        debug_assert!(!self.node_stack.is_empty(),);
+
+        Context {
+            start_index: self.node_stack.len() - 1,
+        }
+    }
+
+    fn is_quiescent(&self) -> bool {
+        true
+    }
+
+    //==================================
+    // Push
+    //==================================
+
+    fn push_leaf(&mut self, token: TokenRef<'i>) -> Self::Node {
+        debug_assert!(!token.tok.isTrivia());
+
+        self.push_node(ArenaCst::Token(token))
+    }
+
+    fn push_syntax(&mut self, token: TokenRef<'i>) -> Self::Node {
+        debug_assert!(!token.tok.isTrivia());
+
+        self.push_node(ArenaCst::Token(token))
+    }
+
+    fn push_compound_pattern_blank(
+        &mut self,
+        op: CompoundOperator,
+        symbol: TokenRef<'i>,
+        under: UnderParseData<'i>,
+    ) -> Self::Node {
+        debug_assert_eq!(symbol.tok, TokenKind::Symbol);
+
+        let under = self.under(under);
+
+        let node = self.alloc_node(
+            ArenaNodeKind::Compound(op),
+            &[ArenaCst::Token(symbol), under],
+        );
+
+        self.push_node(node)
+    }
+
+    fn push_compound_blank(&mut self, under: UnderParseData<'i>) -> Self::Node {
+        let node = self.under(under);
+
+        self.push_node(node)
+    }
+
+    fn push_compound_pattern_optional(
+        &mut self,
+        op: CompoundOperator,
+        symbol: TokenRef<'i>,
+        under_dot: TokenRef<'i>,
+    ) -> Self::Node {
+        debug_assert_eq!(symbol.tok, TokenKind::Symbol);
+        debug_assert_eq!(under_dot.tok, TokenKind::UnderDot);
+
+        self.push_compound(op, symbol, under_dot)
+    }
+
+    fn push_compound_slot(
+        &mut self,
+        op: CompoundOperator,
+        hash: TokenRef<'i>,
+        arg: TokenRef<'i>,
+    ) -> Self::Node {
+        debug_assert_matches!(
+            op,
+            CompoundOperator::Slot | CompoundOperator::SlotSequence
+        );
+        debug_assert_matches!(hash.tok, TokenKind::Hash | TokenKind::HashHash);
+        debug_assert_matches!(arg.tok, TokenKind::Integer | TokenKind::String);
+
+        self.push_compound(op, hash, arg)
+    }
+
+    fn push_compound_out(
+        &mut self,
+        op: CompoundOperator,
+        percent: TokenRef<'i>,
+        integer: TokenRef<'i>,
+    ) -> Self::Node {
+        debug_assert_eq!(op, CompoundOperator::Out);
+        debug_assert_eq!(percent.tok, TokenKind::Percent);
+        debug_assert_matches!(integer.tok, TokenKind::Integer);
+
+        self.push_compound(op, percent, integer)
+    }
+
+    //==================================
+    // Reduce
+    //==================================
+
+    fn reduce_prefix(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: PrefixOperator,
+        _op_token: Self::SyntaxTokenNode,
+        _trivia: Self::TriviaHandle,
+        _operand: Self::Node,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::Prefix(op))
+    }
+
+    fn reduce_prefix_get(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: PrefixOperator,
+        _tok1: Self::SyntaxTokenNode,
+        _trivia: Self::TriviaHandle,
+        _tok2: TokenRef<'i>,
+    ) -> Self::Node {
+        debug_assert_eq!(op, PrefixOperator::Get);
+
+        self.reduce_node(ctx_data, ArenaNodeKind::Prefix(op))
+    }
+
+    fn reduce_postfix(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: PostfixOperator,
+        _operand: Self::Node,
+        _trivia: Self::TriviaHandle,
+        _op_tok: Self::SyntaxTokenNode,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::Postfix(op))
+    }
+
+    fn reduce_binary(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: BinaryOperator,
+        _lhs_node: Self::Node,
+        _trivia1: Self::TriviaHandle,
+        _op_token: Self::SyntaxTokenNode,
+        _trivia2: Self::TriviaHandle,
+        _rhs_node: Self::Node,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::Binary(op))
+    }
+
+    fn reduce_binary_unset(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: BinaryOperator,
+        _lhs_node: Self::Node,
+        _trivia1: Self::TriviaHandle,
+        _op_token: Self::SyntaxTokenNode,
+        _trivia2: Self::TriviaHandle,
+        _dot_token: Self::SyntaxTokenNode,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::Binary(op))
+    }
+
+    fn reduce_ternary(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: TernaryOperator,
+        _lhs_node: Self::Node,
+        _trivia1: Self::TriviaHandle,
+        _first_op_token: Self::SyntaxTokenNode,
+        _trivia2: Self::TriviaHandle,
+        _middle_node: Self::Node,
+        _trivia3: Self::TriviaHandle,
+        _second_op_token: Self::SyntaxTokenNode,
+        _trivia4: Self::TriviaHandle,
+        _rhs_node: Self::Node,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::Ternary(op))
+    }
+
+    fn reduce_ternary_tag_unset(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: TernaryOperator,
+        _lhs_node: Self::Node,
+        _trivia1: Self::TriviaHandle,
+        _slash_colon_token: Self::SyntaxTokenNode,
+        _trivia2: Self::TriviaHandle,
+        _middle_node: Self::Node,
+        _trivia3: Self::TriviaHandle,
+        _equal_token: Self::SyntaxTokenNode,
+        _trivia4: Self::TriviaHandle,
+        _dot_token: Self::SyntaxTokenNode,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::Ternary(op))
+    }
+
+    fn reduce_prefix_binary(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: PrefixBinaryOperator,
+        _prefix_op_token: Self::SyntaxTokenNode,
+        _trivia1: Self::TriviaHandle,
+        _lhs_node: Self::Node,
+        _trivia2: Self::TriviaHandle,
+        _rhs_node: Self::Node,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::PrefixBinary(op))
+    }
+
+    //----------------------------------
+    // Infix parsing
+    //----------------------------------
+
+    fn begin_infix(
+        &mut self,
+        op: InfixOperator,
+        _first_node: Self::Node,
+    ) -> Self::InfixParseState {
+        InfixParseArenaCst { op }
+    }
+
+    fn infix_add(
+        &mut self,
+        _infix_state: &mut Self::InfixParseState,
+        _trivia1: Self::TriviaHandle,
+        _op_token: Self::SyntaxTokenNode,
+        _trivia2: Self::TriviaHandle,
+        _operand: Self::Node,
+    ) {
+        // Do nothing, because all the arguments should already have been
+        // added to `node_stack` when they were originally processed.
+    }
+
+    fn reduce_infix(
+        &mut self,
+        ctx_data: Self::ContextData,
+        state: Self::InfixParseState,
+    ) -> Self::Node {
+        let InfixParseArenaCst { op } = state;
+
+        self.reduce_node(ctx_data, ArenaNodeKind::Infix(op))
+    }
+
+    fn begin_group(
+        &mut self,
+        _opener_tok: Self::SyntaxTokenNode,
+    ) -> Self::GroupParseState {
+        InfixParseGroup {}
+    }
+
+    fn group_add(
+        &mut self,
+        _infix_state: &mut Self::GroupParseState,
+        _trivia: Self::TriviaHandle,
+        _operand: Self::Node,
+    ) {
+        // Do nothing, because all the arguments should already have been
+        // added to `node_stack` when they were originally processed.
+    }
+
+    fn reduce_group(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: GroupOperator,
+        _group_state: Self::GroupParseState,
+        _trailing_trivia: Self::TriviaHandle,
+        _closer_tok: Self::SyntaxTokenNode,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::Group(op))
+    }
+
+    fn reduce_call(
+        &mut self,
+        ctx_data: Self::ContextData,
+        _head: Self::Node,
+        _head_trivia: Self::TriviaHandle,
+        _body: Self::Node,
+    ) -> Self::Node {
+        let body = match self.pop_node() {
+            ArenaCst::Node(&ArenaNode {
+                kind: ArenaNodeKind::Group(op),
+                children,
+            }) => ArenaNode {
+                kind: ArenaNodeKind::CallBody(to_call_operator(op)),
+                children,
+            },
+            ArenaCst::Node(&ArenaNode {
+                kind: ArenaNodeKind::GroupMissingCloser(op),
+                children,
+            }) => ArenaNode {
+                kind: ArenaNodeKind::CallBodyMissingCloser(to_call_operator(
+                    op,
+                )),
+                children,
+            },
+            other => panic!(
+                "expected CallParselet body to reduce to a Group or GroupMissingCloser node; got: {:#?}",
+                other
+            ),
+        };
+
+        let body = ArenaCst::Node(self.bump.alloc(body));
+
+        // The body is reduced along with the nodes of the head.
+        self.push_node(body);
+
+        self.reduce_node(ctx_data, ArenaNodeKind::Call)
+    }
+
+    //----------------------------------
+    // Reduce errors
+    //----------------------------------
+
+    fn reduce_syntax_error(
+        &mut self,
+        ctx_data: Self::ContextData,
+        data: SyntaxErrorData<
+            Self::Node,
+            Self::TriviaHandle,
+            Self::SyntaxTokenNode,
+        >,
+    ) -> Self::Node {
+        let kind = match data {
+            SyntaxErrorData::ExpectedSymbol { .. } => {
+                SyntaxErrorKind::ExpectedSymbol
+            },
+            SyntaxErrorData::ExpectedSet => SyntaxErrorKind::ExpectedSet,
+            SyntaxErrorData::ExpectedTilde { .. } => {
+                SyntaxErrorKind::ExpectedTilde
+            },
+        };
+
+        self.reduce_node(ctx_data, ArenaNodeKind::SyntaxError(kind))
+    }
+
+    fn reduce_unterminated_group(
+        &mut self,
+        ctx_data: Self::ContextData,
+        input: &'i str,
+        tab_width: usize,
+        heuristics: ReparseHeuristics,
+        op: GroupOperator,
+        _state: Self::GroupParseState,
+        _trailing_trivia: Self::TriviaHandle,
+    ) -> Self::Node {
+        let children = self.reduce(ctx_data);
+
+        // Reparsing is done on a copy of the group, because it only happens
+        // for input that is missing a closer.
+        let node = crate::error::reparse_unterminated_group_node(
+            (op, arena::to_cst_seq(children)),
+            input,
+            tab_width,
+            heuristics,
+        );
+
+        let node =
+            ArenaCst::from_cst(self.bump, &Cst::GroupMissingCloser(node));
+
+        self.push_node(node)
+    }
+
+    fn reduce_group_missing_closer(
+        &mut self,
+        ctx_data: Self::ContextData,
+        op: GroupOperator,
+        _state: Self::GroupParseState,
+    ) -> Self::Node {
+        self.reduce_node(ctx_data, ArenaNodeKind::GroupMissingCloser(op))
+    }
+
+    //==================================
+    // Pop
+    //==================================
+
+    fn finish_top_level_trivia(&mut self, token: TokenRef<'i>) {
+        debug_assert!(token.tok.isTrivia());
+
+        self.finished.push(ArenaCst::Token(token));
+    }
+
+    fn finish_top_level_expr(&mut self, node: Self::Node) {
+        let () = node;
+
+        let node = self.pop_node();
+
+        self.finished.push(node);
+    }
+
+    //==================================
+    // Properties
+    //==================================
+
+    fn check_colon_lhs(&self, lhs: &Self::Node) -> ColonLHS {
+        let () = lhs;
+
+        match self.top_node().expect(
+            "unable to check colon LHS: no non-trivia token in top context",
+        ) {
+            //
+            // Something like  a:b:c
+            //                  ^ Pattern
+            //                    ^ Optional
+            //
+            ArenaCst::Node(ArenaNode {
+                kind: ArenaNodeKind::Binary(BinaryOperator::Pattern),
+                ..
+            }) => ColonLHS::Optional,
+
+            //
+            // Something like  a_:b
+            //                   ^ Optional
+            //
+            ArenaCst::Node(ArenaNode {
+                kind:
+                    ArenaNodeKind::Compound(
+                        CompoundOperator::CodeParser_PatternBlank
+                        | CompoundOperator::CodeParser_PatternBlankSequence
+                        | CompoundOperator::CodeParser_PatternBlankNullSequence
+                        | CompoundOperator::Blank
+                        | CompoundOperator::BlankSequence
+                        | CompoundOperator::BlankNullSequence,
+                    ),
+                ..
+            }) => ColonLHS::Optional,
+
+            ArenaCst::Token(tok) => match tok.tok {
+                //
+                // Something like  a:b
+                //                  ^ Pattern
+                //
+                TokenKind::Symbol => ColonLHS::Pattern,
+                //
+                // Something like  _:b
+                //                  ^ Optional
+                //
+                TokenKind::Under
+                | TokenKind::UnderUnder
+                | TokenKind::UnderUnderUnder => ColonLHS::Optional,
+                TokenKind::Colon => panic!("Fix at call site"),
+                // Allow errors to be on the LHS of `:`, because there is
+                // already an error.
+                tok if tok.isError() => ColonLHS::Pattern,
+                _ => ColonLHS::Error,
+            },
+
+            ArenaCst::Node(_) => ColonLHS::Error,
+        }
+    }
+
+    fn top_node_is_span(&self) -> bool {
+        let top_node = self.top_node().expect(
+            "unable to check colon LHS: no non-trivia token in top context",
+        );
+
+        debug_assert!(
+            !matches!(top_node, ArenaCst::Token(tok) if tok.tok.isTriviaButNotToplevelNewline())
+        );
+
+        matches!(
+            top_node,
+            ArenaCst::Node(ArenaNode {
+                kind: ArenaNodeKind::Binary(BinaryOperator::Span)
+                    | ArenaNodeKind::Ternary(TernaryOperator::Span),
+                ..
+            })
+        )
+    }
+}
+
+impl<'i> ParseArenaCst<'i> {
+    pub(crate) fn new(bump: &'i Bump) -> Self {
+        ParseArenaCst {
+            bump,
+            node_stack: Vec::new(),
+            finished: Vec::new(),
+        }
+    }
+
+    fn push_node(&mut self, node: ArenaCst<'i>) {
+        self.node_stack.push(node)
+    }
+
+    fn pop_node(&mut self) -> ArenaCst<'i> {
+        self.node_stack.pop().unwrap()
+    }
+
+    /// The top (last) node on the stack that is not trivia.
+    fn top_node(&self) -> Option<&ArenaCst<'i>> {
+        self.node_stack.iter().rev().find(
+            |node| !matches!(node, ArenaCst::Token(token) if token.tok.isTrivia()),
+        )
+    }
+
+    /// Move the nodes of the current context into the arena.
+    fn reduce(&mut self, ctx_data: Context) -> &'i [ArenaCst<'i>] {
+        let Context { start_index } = ctx_data;
+
+        self.bump
+            .alloc_slice_fill_iter(self.node_stack.drain(start_index..))
+    }
+
+    fn reduce_node(&mut self, ctx_data: Context, kind: ArenaNodeKind) {
+        let children = self.reduce(ctx_data);
+
+        let node =
+            ArenaCst::Node(self.bump.alloc(ArenaNode { kind, children }));
+
+        self.push_node(node)
+    }
+
+    fn alloc_node(
+        &self,
+        kind: ArenaNodeKind,
+        children: &[ArenaCst<'i>],
+    ) -> ArenaCst<'i> {
+        ArenaCst::Node(self.bump.alloc(ArenaNode {
+            kind,
+            children: self.bump.alloc_slice_copy(children),
+        }))
+    }
+
+    fn push_compound(
+        &mut self,
+        op: CompoundOperator,
+        first: TokenRef<'i>,
+        second: TokenRef<'i>,
+    ) {
+        let node = self.alloc_node(
+            ArenaNodeKind::Compound(op),
+            &[ArenaCst::Token(first), ArenaCst::Token(second)],
+        );
+
+        self.push_node(node)
+    }
+
+    fn under(&self, under: UnderParseData<'i>) -> ArenaCst<'i> {
+        match under {
+            UnderParseData::Under(under) => ArenaCst::Token(under),
+            UnderParseData::UnderSymbol { op, under, symbol } => self
+                .alloc_node(
+                    ArenaNodeKind::Compound(op),
+                    &[ArenaCst::Token(under), ArenaCst::Token(symbol)],
+                ),
+        }
+    }
+}
+
+fn to_call_operator(op: GroupOperator) -> CallOperator {
+    op.try_to_call_operator()
+        .expect("expected call group to be a valid CallOperator")
+}
+
+/// Whether `node` contains an unterminated token error, like an unterminated
+/// string.
+fn has_unterminated_token(node: &ArenaCst) -> bool {
+    match node {
+        ArenaCst::Token(token) => {
+            token.tok.isError() && token.tok.isUnterminated()
+        },
+        ArenaCst::Node(node) => {
+            node.children.iter().any(has_unterminated_token)
+        },
+    }
+}
@@ -28,6 +28,16 @@ impl Precedence {
         bits.get()
     }
 
+    /// The precedence level, ignoring associativity.
+    pub(crate) fn level(self) -> u8 {
+        self.bits() >> 1
+    }
+
+    /// Returns true if operators at this precedence group to the right.
+    pub(crate) fn is_right_associative(self) -> bool {
+        self.bits() & 0x1 == 0x1
+    }
+
     /// Returns true if `lhs` is greater then `rhs`.
     pub(crate) fn greater(
         lhs: Option<Precedence>,
@@ -0,0 +1,196 @@
+//! Parsing of multi-file projects.
+//!
+//! A [`ProjectParse`] holds the abstract syntax of every file in a project,
+//! keyed by file path. Files are parsed in parallel, and a single file can be
+//! reparsed after it changes without reparsing the rest of the project.
+//!
+//! Parse results are kept in memory only. To speed up cold starts, persist a
+//! [`SymbolIndex`][crate::index::SymbolIndex] with
+//! [`SymbolIndex::save()`][crate::index::SymbolIndex::save] instead, and
+//! reparse files as they are needed.
+//!
+//! ```
+//! use wolfram_parser::{project::ProjectParse, ParseOptions};
+//!
+//! let project = ProjectParse::from_sources(
+//!     vec![
+//!         ("A.wl".into(), "f[x_] := x + 1".to_owned()),
+//!         ("B.wl".into(), "f[2]".to_owned()),
+//!     ],
+//!     &ParseOptions::default(),
+//! );
+//!
+//! assert_eq!(project.len(), 2);
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ast::Ast, utils, EncodingError, NodeSeq, ParseError, ParseOptions,
+    ParseResult,
+};
+
+/// The parsed files of a project.
+pub struct ProjectParse {
+    files: BTreeMap<PathBuf, ProjectFile>,
+}
+
+/// A single parsed file in a [`ProjectParse`].
+pub struct ProjectFile {
+    pub path: PathBuf,
+    pub result: ParseResult<NodeSeq<Ast>>,
+
+    /// [`content_hash()`] of the source this file was parsed from.
+    pub content_hash: u64,
+}
+
+//======================================
+// Impls
+//======================================
+
+impl ProjectParse {
+    /// Construct an empty project.
+    pub fn new() -> Self {
+        ProjectParse {
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Read and parse the files at `paths` in parallel.
+    ///
+    /// Paclet-encoded files are decoded automatically, as with
+    /// [`parse_file_ast()`][crate::parse_file_ast].
+    pub fn parse_files<P: AsRef<Path> + Sync>(
+        paths: &[P],
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let results = utils::par_map(paths, |path| {
+            let path = path.as_ref();
+
+            read_source(path)
+                .map(|source| ProjectFile::parse(path, &source, opts))
+        });
+
+        let mut project = ProjectParse::new();
+
+        for result in results {
+            let file = result?;
+
+            project.insert(file);
+        }
+
+        Ok(project)
+    }
+
+    /// Parse in-memory sources in parallel.
+    pub fn from_sources<I>(sources: I, opts: &ParseOptions) -> Self
+    where
+        I: IntoIterator<Item = (PathBuf, String)>,
+    {
+        let sources: Vec<(PathBuf, String)> = sources.into_iter().collect();
+
+        let files = utils::par_map(&sources, |(path, source)| {
+            ProjectFile::parse(path, source, opts)
+        });
+
+        let mut project = ProjectParse::new();
+
+        for file in files {
+            project.insert(file);
+        }
+
+        project
+    }
+
+    /// Parse `source` as the new contents of the file at `path`, replacing
+    /// any previous parse of that file.
+    pub fn reparse_file(
+        &mut self,
+        path: impl Into<PathBuf>,
+        source: &str,
+        opts: &ParseOptions,
+    ) -> &ProjectFile {
+        let path = path.into();
+
+        let file = ProjectFile::parse(&path, source, opts);
+
+        self.files.insert(path.clone(), file);
+
+        &self.files[&path]
+    }
+
+    /// Remove the file at `path` from this project.
+    pub fn remove_file(&mut self, path: &Path) -> Option<ProjectFile> {
+        self.files.remove(path)
+    }
+
+    pub fn file(&self, path: &Path) -> Option<&ProjectFile> {
+        self.files.get(path)
+    }
+
+    /// Iterate over the files in this project, ordered by path.
+    pub fn files(&self) -> impl Iterator<Item = &ProjectFile> {
+        self.files.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn insert(&mut self, file: ProjectFile) {
+        self.files.insert(file.path.clone(), file);
+    }
+}
+
+impl Default for ProjectParse {
+    fn default() -> Self {
+        ProjectParse::new()
+    }
+}
+
+impl ProjectFile {
+    fn parse(path: &Path, source: &str, opts: &ParseOptions) -> Self {
+        ProjectFile {
+            path: path.to_owned(),
+            result: crate::parse_ast_seq(source, opts),
+            content_hash: content_hash(source),
+        }
+    }
+
+    /// The top-level expressions in this file.
+    pub fn exprs(&self) -> &[Ast] {
+        &self.result.syntax.0
+    }
+}
+
+/// Compute a hash of the contents of a source file.
+///
+/// The hash is stable across platforms and builds of this crate, so it can be
+/// persisted alongside data derived from the file to detect when that data is
+/// out of date.
+pub fn content_hash(source: &str) -> u64 {
+    // 64-bit FNV-1a
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    source.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+fn read_source(path: &Path) -> Result<String, ParseError> {
+    let bytes = crate::read_source_file(path)?;
+
+    String::from_utf8(bytes).map_err(|err| {
+        ParseError::Encoding(EncodingError::InvalidUtf8 {
+            position: err.utf8_error().valid_up_to(),
+        })
+    })
+}
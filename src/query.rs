@@ -0,0 +1,422 @@
+//! Incremental computation of per-file syntax and analyses.
+//!
+//! *Requires the `incremental` feature.*
+//!
+//! A [`Database`] stores the text of a set of files, and computes derived
+//! values — tokens, concrete syntax, abstract syntax, and user-defined
+//! analyses — as memoized [`Query`]s. Each query records which file texts
+//! and other queries it read while it was computed, so after a file is
+//! edited only the queries that (transitively) depend on that file are
+//! recomputed.
+//!
+//! If a recomputed query produces a value equal to its previous value, queries
+//! that depend on it are not recomputed ("early cutoff").
+//!
+//! ```
+//! use std::path::Path;
+//! use wolfram_parser::{
+//!     query::{AbstractSyntax, Database, Query},
+//!     ParseOptions,
+//! };
+//!
+//! /// Number of top-level expressions in a file.
+//! struct ExprCount;
+//!
+//! impl Query for ExprCount {
+//!     type Output = usize;
+//!
+//!     fn compute(db: &Database, path: &Path) -> usize {
+//!         db.get::<AbstractSyntax>(path).syntax.len()
+//!     }
+//! }
+//!
+//! let mut db = Database::new(ParseOptions::default());
+//!
+//! db.set_file_text("A.wl", "a; b\nc");
+//! assert_eq!(*db.get::<ExprCount>(Path::new("A.wl")), 2);
+//!
+//! db.set_file_text("A.wl", "a\nb\nc");
+//! assert_eq!(*db.get::<ExprCount>(Path::new("A.wl")), 3);
+//! ```
+//!
+//! # Built-in queries
+//!
+//! Query               | Output
+//! --------------------|----------------------------------------------
+//! [`Tokens`]          | [`NodeSeq<Token>`][crate::tokenize::Token]
+//! [`ConcreteSyntax`]  | [`Parsed<NodeSeq<Cst>>`][Parsed]
+//! [`AbstractSyntax`]  | [`Parsed<NodeSeq<Ast>>`][Parsed]
+
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    ast::Ast,
+    cst::{Cst, CstSeq},
+    directive::Directive,
+    issue::Issue,
+    parse::recovery::empty_tracked,
+    tokenize::{Token, TokenString},
+    NodeSeq, ParseOptions, ParseResult,
+};
+
+/// A memoized computation over a single file in a [`Database`].
+pub trait Query: 'static {
+    type Output: PartialEq + 'static;
+
+    /// Compute the value of this query for the file at `path`.
+    ///
+    /// Any file text or other query read through `db` during this
+    /// computation is recorded as a dependency.
+    fn compute(db: &Database, path: &Path) -> Self::Output;
+}
+
+/// Stores file texts and memoized [`Query`] results.
+pub struct Database {
+    opts: ParseOptions,
+
+    /// Incremented every time an input changes.
+    revision: u64,
+
+    inputs: HashMap<PathBuf, Input>,
+    memos: RefCell<HashMap<QueryKey, Memo>>,
+
+    /// Dependencies recorded by each query currently being computed, innermost
+    /// last.
+    active: RefCell<Vec<(QueryKey, Vec<Dependency>)>>,
+
+    /// Number of times [`Query::compute()`] has been called.
+    executions: Cell<u64>,
+}
+
+/// Syntax and the issues found while parsing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parsed<T> {
+    pub syntax: T,
+    pub fatal_issues: Vec<Issue>,
+    pub non_fatal_issues: Vec<Issue>,
+
+    /// Directives found in comments, in source order.
+    pub directives: Vec<Directive>,
+}
+
+/// Query for the tokens of a file.
+pub struct Tokens;
+
+/// Query for the concrete syntax of a file.
+pub struct ConcreteSyntax;
+
+/// Query for the abstract syntax of a file.
+///
+/// This is computed from [`ConcreteSyntax`], so the file is not parsed a
+/// second time.
+pub struct AbstractSyntax;
+
+struct Input {
+    /// `None` if the file has been removed.
+    text: Option<Rc<str>>,
+    changed_at: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct QueryKey {
+    query: TypeId,
+    path: PathBuf,
+}
+
+struct Memo {
+    value: Rc<dyn Any>,
+    /// The revision at which `value` last changed.
+    changed_at: u64,
+    /// The revision at which `dependencies` were last checked.
+    verified_at: u64,
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Clone)]
+enum Dependency {
+    Input(PathBuf),
+    Query {
+        key: QueryKey,
+        /// Bring the dependency up to date and return the revision at which
+        /// its value last changed.
+        refresh: fn(&Database, &Path) -> u64,
+    },
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Database {
+    pub fn new(opts: ParseOptions) -> Self {
+        Database {
+            opts,
+            revision: 0,
+            inputs: HashMap::new(),
+            memos: RefCell::new(HashMap::new()),
+            active: RefCell::new(Vec::new()),
+            executions: Cell::new(0),
+        }
+    }
+
+    /// The options used by the built-in parsing queries.
+    pub fn options(&self) -> &ParseOptions {
+        &self.opts
+    }
+
+    /// Set the text of the file at `path`.
+    ///
+    /// Setting a file to the text it already has does not invalidate any
+    /// queries.
+    pub fn set_file_text(&mut self, path: impl Into<PathBuf>, text: &str) {
+        let path = path.into();
+
+        if let Some(Input {
+            text: Some(current),
+            ..
+        }) = self.inputs.get(&path)
+        {
+            if **current == *text {
+                return;
+            }
+        }
+
+        self.set_input(path, Some(Rc::from(text)));
+    }
+
+    /// Remove the file at `path`.
+    pub fn remove_file(&mut self, path: &Path) {
+        if let Some(Input { text: Some(_), .. }) = self.inputs.get(path) {
+            self.set_input(path.to_owned(), None);
+        }
+    }
+
+    fn set_input(&mut self, path: PathBuf, text: Option<Rc<str>>) {
+        self.revision += 1;
+
+        self.inputs.insert(
+            path,
+            Input {
+                text,
+                changed_at: self.revision,
+            },
+        );
+    }
+
+    /// Get the text of the file at `path`, or `None` if it has not been set.
+    pub fn file_text(&self, path: &Path) -> Option<Rc<str>> {
+        self.record(Dependency::Input(path.to_owned()));
+
+        self.inputs.get(path)?.text.clone()
+    }
+
+    /// Paths of every file with text in this database.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.inputs
+            .iter()
+            .filter(|(_, input)| input.text.is_some())
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// Get the value of query `Q` for the file at `path`, computing it if it
+    /// is not memoized or may be out of date.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Q` depends on itself for the same file.
+    pub fn get<Q: Query>(&self, path: &Path) -> Rc<Q::Output> {
+        let key = QueryKey {
+            query: TypeId::of::<Q>(),
+            path: path.to_owned(),
+        };
+
+        self.record(Dependency::Query {
+            key: key.clone(),
+            refresh: refresh::<Q>,
+        });
+
+        refresh::<Q>(self, path);
+
+        let memos = self.memos.borrow();
+        let value = memos[&key].value.clone();
+
+        match value.downcast::<Q::Output>() {
+            Ok(value) => value,
+            Err(_) => unreachable!("memoized value has wrong type"),
+        }
+    }
+
+    /// Number of times any query has been computed (not counting memoized
+    /// results).
+    ///
+    /// This is useful for testing that an edit invalidates only the expected
+    /// queries.
+    pub fn executions(&self) -> u64 {
+        self.executions.get()
+    }
+
+    fn record(&self, dependency: Dependency) {
+        if let Some((_, dependencies)) = self.active.borrow_mut().last_mut() {
+            dependencies.push(dependency);
+        }
+    }
+
+    fn input_changed_at(&self, path: &Path) -> u64 {
+        self.inputs.get(path).map_or(0, |input| input.changed_at)
+    }
+}
+
+/// Ensure the memoized value of `Q` for `path` is up to date, and return the
+/// revision at which its value last changed.
+fn refresh<Q: Query>(db: &Database, path: &Path) -> u64 {
+    let key = QueryKey {
+        query: TypeId::of::<Q>(),
+        path: path.to_owned(),
+    };
+
+    // Check whether the existing memo (if any) is still valid.
+    let dependencies = match db.memos.borrow().get(&key) {
+        Some(memo) if memo.verified_at == db.revision => {
+            return memo.changed_at;
+        },
+        Some(memo) => Some((memo.verified_at, memo.dependencies.clone())),
+        None => None,
+    };
+
+    if let Some((verified_at, dependencies)) = dependencies {
+        let unchanged = dependencies.iter().all(|dependency| {
+            let changed_at = match dependency {
+                Dependency::Input(path) => db.input_changed_at(path),
+                Dependency::Query { key, refresh } => refresh(db, &key.path),
+            };
+
+            changed_at <= verified_at
+        });
+
+        if unchanged {
+            let mut memos = db.memos.borrow_mut();
+            let memo = memos.get_mut(&key).unwrap();
+            memo.verified_at = db.revision;
+            return memo.changed_at;
+        }
+    }
+
+    // Recompute
+    if db.active.borrow().iter().any(|(active, _)| *active == key) {
+        panic!(
+            "query cycle detected: {} depends on itself for {path:?}",
+            std::any::type_name::<Q>()
+        );
+    }
+
+    db.active.borrow_mut().push((key.clone(), Vec::new()));
+    db.executions.set(db.executions.get() + 1);
+
+    let value = Q::compute(db, path);
+
+    let (_, dependencies) = db.active.borrow_mut().pop().unwrap();
+
+    let mut memos = db.memos.borrow_mut();
+
+    // Early cutoff: if the value did not change, dependents don't need to be
+    // recomputed.
+    let changed_at = match memos.get(&key) {
+        Some(old) if old.value.downcast_ref::<Q::Output>() == Some(&value) => {
+            old.changed_at
+        },
+        _ => db.revision,
+    };
+
+    let value: Rc<dyn Any> = match memos.remove(&key) {
+        Some(old) if changed_at == old.changed_at => old.value,
+        _ => Rc::new(value),
+    };
+
+    memos.insert(
+        key,
+        Memo {
+            value,
+            changed_at,
+            verified_at: db.revision,
+            dependencies,
+        },
+    );
+
+    changed_at
+}
+
+impl Query for Tokens {
+    type Output = NodeSeq<Token<TokenString>>;
+
+    fn compute(db: &Database, path: &Path) -> Self::Output {
+        let text = db.file_text(path);
+
+        crate::tokenize(text.as_deref().unwrap_or(""), db.options())
+            .into_owned()
+    }
+}
+
+impl Query for ConcreteSyntax {
+    type Output = Parsed<NodeSeq<Cst<TokenString>>>;
+
+    fn compute(db: &Database, path: &Path) -> Self::Output {
+        let text = db.file_text(path);
+
+        let result =
+            crate::parse_cst_seq(text.as_deref().unwrap_or(""), db.options());
+
+        Parsed::from(result.map(CstSeq::into_owned))
+    }
+}
+
+impl Query for AbstractSyntax {
+    type Output = Parsed<NodeSeq<Ast>>;
+
+    fn compute(db: &Database, path: &Path) -> Self::Output {
+        let cst = db.get::<ConcreteSyntax>(path);
+
+        let Parsed {
+            syntax,
+            fatal_issues,
+            non_fatal_issues,
+            directives,
+        } = Parsed::clone(&cst);
+
+        let result = ParseResult {
+            syntax,
+            unsafe_character_encoding: None,
+            fatal_issues,
+            non_fatal_issues,
+            directives,
+            tracked: empty_tracked(),
+        };
+
+        Parsed::from(crate::abstract_cst_seq_result(result, db.options()))
+    }
+}
+
+impl<T> From<ParseResult<T>> for Parsed<T> {
+    fn from(result: ParseResult<T>) -> Self {
+        let ParseResult {
+            syntax,
+            unsafe_character_encoding: _,
+            fatal_issues,
+            non_fatal_issues,
+            directives,
+            tracked: _,
+        } = result;
+
+        Parsed {
+            syntax,
+            fatal_issues,
+            non_fatal_issues,
+            directives,
+        }
+    }
+}
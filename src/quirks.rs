@@ -49,6 +49,21 @@ pub struct QuirkSettings {
     ///
     /// `a @@@ b` parses as `MapApply[a, b]`
     pub old_at_at_at: bool,
+
+    /// "LegacyPrefixMinus" quirk
+    ///
+    /// Older kernels bound prefix `-` and `+` more tightly than `^` when the
+    /// operand of the prefix operator was a power:
+    ///
+    /// * `-x^2` was parsed as `Power[Times[-1, x], 2]`
+    /// * `-2^2` was parsed as `Power[-2, 2]`
+    /// * `+x^2` was parsed as `Power[Plus[x], 2]`
+    ///
+    /// Current kernels parse `-x^2` as `Times[-1, Power[x, 2]]`.
+    ///
+    /// Only a power that is the direct operand of the prefix operator is
+    /// affected; `-(x^2)` and `a - x^2` are parsed the same either way.
+    pub legacy_prefix_minus: bool,
 }
 
 pub enum Quirk {
@@ -92,6 +107,21 @@ pub enum Quirk {
     ///
     /// `a @@@ b` parses as `MapApply[a, b]`
     OldAtAtAt,
+
+    /// "LegacyPrefixMinus" quirk
+    ///
+    /// Older kernels bound prefix `-` and `+` more tightly than `^` when the
+    /// operand of the prefix operator was a power:
+    ///
+    /// * `-x^2` was parsed as `Power[Times[-1, x], 2]`
+    /// * `-2^2` was parsed as `Power[-2, 2]`
+    /// * `+x^2` was parsed as `Power[Plus[x], 2]`
+    ///
+    /// Current kernels parse `-x^2` as `Times[-1, Power[x, 2]]`.
+    ///
+    /// Only a power that is the direct operand of the prefix operator is
+    /// affected; `-(x^2)` and `a - x^2` are parsed the same either way.
+    LegacyPrefixMinus,
 }
 
 impl QuirkSettings {
@@ -100,6 +130,7 @@ impl QuirkSettings {
             infix_binary_at: true,
             flatten_times: false,
             old_at_at_at: false,
+            legacy_prefix_minus: false,
         }
     }
 
@@ -123,11 +154,24 @@ impl QuirkSettings {
             ..self
         }
     }
+
+    pub fn legacy_prefix_minus(self, value: bool) -> Self {
+        QuirkSettings {
+            legacy_prefix_minus: value,
+            ..self
+        }
+    }
 }
 
+/// Returns the quirk settings of the
+/// [global default options][crate::ParseOptions::set_global_default()], or
+/// [`QuirkSettings::const_default()`] if none have been set.
 impl Default for QuirkSettings {
     fn default() -> Self {
-        Self::const_default()
+        match crate::ParseOptions::global_default() {
+            Some(opts) => opts.quirk_settings,
+            None => Self::const_default(),
+        }
     }
 }
 
@@ -142,5 +186,6 @@ pub(crate) fn is_quirk_enabled(quirk: Quirk) -> bool {
         Quirk::InfixBinaryAt => settings.infix_binary_at,
         Quirk::FlattenTimes => settings.flatten_times,
         Quirk::OldAtAtAt => settings.old_at_at_at,
+        Quirk::LegacyPrefixMinus => settings.legacy_prefix_minus,
     }
 }
@@ -8,9 +8,11 @@ pub(crate) mod code_point;
 mod wl_character;
 
 
+use std::num::NonZeroU32;
+
 use crate::{
-    issue::{Issue, Severity},
-    source::{Buffer, Location, NextPolicy, SourceCharacter},
+    issue::{FormatIssue, Issue, IssueTag, Severity},
+    source::{Buffer, LineColumn, Location, NextPolicy, SourceCharacter, Span},
     EncodingMode, ParseOptions, UnsafeCharacterEncoding,
 };
 
@@ -47,6 +49,19 @@ pub(crate) struct Reader<'i> {
     pub(crate) fatal_issues: Vec<Issue>,
     pub(crate) non_fatal_issues: Vec<Issue>,
 
+    /// Issues with these tags are reported as fatal.
+    ///
+    /// See [`ParseOptions::deny()`].
+    denied_issues: Vec<IssueTag>,
+
+    /// See [`ParseOptions::max_line_length()`].
+    max_line_length: Option<u32>,
+    /// See [`ParseOptions::check_mixed_indentation()`].
+    check_indentation: bool,
+    /// The character used to indent the first indented line, either `b' '`
+    /// or `b'\t'`.
+    indentation: Option<u8>,
+
     pub(crate) unsafe_character_encoding_flag: Option<UnsafeCharacterEncoding>,
 }
 
@@ -78,9 +93,21 @@ impl<'i> Reader<'i> {
             first_line_behavior: _,
             src_convention,
             encoding_mode,
-            tab_width,
+            tab_width: _,
+            tab_width_auto: _,
             check_issues,
             compute_oob,
+            implicit_times: _,
+            toplevel_newlines: _,
+            grammar_profile: _,
+            ref denied_issues,
+            max_line_length,
+            check_indentation,
+            reparse: _,
+            recovery: _,
+            deduplicate_issues: _,
+            notations: _,
+            debug_sink: _,
             quirk_settings: _,
         } = *opts;
 
@@ -90,7 +117,7 @@ impl<'i> Reader<'i> {
             wasEOF: false,
             SrcLoc: src_convention.newSourceLocation(),
 
-            tab_width,
+            tab_width: opts.tab_width_for(input),
             check_issues,
             compute_oob,
             encoding_mode,
@@ -98,6 +125,12 @@ impl<'i> Reader<'i> {
             fatal_issues: Vec::new(),
             non_fatal_issues: Vec::new(),
 
+            denied_issues: denied_issues.clone(),
+
+            max_line_length,
+            check_indentation,
+            indentation: None,
+
             unsafe_character_encoding_flag: None,
         }
     }
@@ -244,7 +277,11 @@ impl<'i> Reader<'i> {
         self.unsafe_character_encoding_flag = Some(flag);
     }
 
-    pub(crate) fn addIssue(&mut self, issue: Issue) {
+    pub(crate) fn addIssue(&mut self, mut issue: Issue) {
+        if self.denied_issues.contains(&issue.tag) {
+            issue.sev = Severity::Fatal;
+        }
+
         if issue.sev == Severity::Fatal {
             //
             // There may be situations where many (1000+) fatal errors are generated.
@@ -276,6 +313,108 @@ impl<'i> Reader<'i> {
             }
         }
     }
+
+    /// Check the style of the line ending at byte offset `line_end` of the
+    /// input.
+    ///
+    /// Must be called while [`SrcLoc`][Reader::SrcLoc] is at the end of the
+    /// line, before it is advanced past the line terminator.
+    #[inline]
+    pub(crate) fn check_line_style(&mut self, line_end: usize) {
+        if !self.check_issues
+            || (self.max_line_length.is_none() && !self.check_indentation)
+        {
+            return;
+        }
+
+        let Location::LineColumn(LineColumn(line, column)) = self.SrcLoc else {
+            return;
+        };
+
+        if let Some(max) = self.max_line_length {
+            if column.get() - 1 > max {
+                let start = Location::LineColumn(LineColumn(
+                    line,
+                    NonZeroU32::new(max + 1).unwrap(),
+                ));
+
+                self.addIssue(FormatIssue(
+                    IssueTag::LineTooLong,
+                    format!("Line is longer than {max} characters."),
+                    Severity::Formatting,
+                    Span::new(start, self.SrcLoc),
+                    1.0,
+                    vec![],
+                    vec![],
+                ));
+            }
+        }
+
+        if self.check_indentation {
+            self.check_line_indentation(line, line_end);
+        }
+    }
+
+    fn check_line_indentation(&mut self, line: NonZeroU32, line_end: usize) {
+        let line_start = self.input[..line_end]
+            .iter()
+            .rposition(|&byte| byte == b'\n' || byte == b'\r')
+            .map_or(0, |index| index + 1);
+
+        let text = &self.input[line_start..line_end];
+
+        let Some(indent_len) =
+            text.iter().position(|&byte| byte != b' ' && byte != b'\t')
+        else {
+            // Only whitespace
+            return;
+        };
+
+        let indent = &text[..indent_len];
+
+        let Some(&first) = indent.first() else {
+            return;
+        };
+
+        let expected = *self.indentation.get_or_insert(first);
+
+        if indent.iter().all(|&byte| byte == expected) {
+            return;
+        }
+
+        let msg = if indent.iter().all(|&byte| byte == indent[0]) {
+            match expected {
+                b'\t' => "Indented with spaces, but earlier lines use tabs.",
+                _ => "Indented with tabs, but earlier lines use spaces.",
+            }
+        } else {
+            "Indentation mixes tabs and spaces."
+        };
+
+        // Width of the indentation in columns.
+        let width = indent.iter().fold(0, |column, &byte| match byte {
+            b'\t' => {
+                self.tab_width * (column / self.tab_width) + self.tab_width
+            },
+            _ => column + 1,
+        });
+
+        let start = Location::LineColumn(LineColumn(line, NonZeroU32::MIN));
+        let end = Location::LineColumn(LineColumn(
+            line,
+            NonZeroU32::new(width + 1).unwrap(),
+        ));
+
+        self.addIssue(FormatIssue(
+            IssueTag::MixedIndentation,
+            msg.into(),
+            Severity::Formatting,
+            Span::new(start, end),
+            1.0,
+            vec![],
+            vec![],
+        ));
+    }
 }
 
 impl InputMark {
@@ -288,4 +427,22 @@ impl InputMark {
             wasEOF: None,
         }
     }
+
+    /// Offset of the marked byte in the input.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The same position in the input after its first `len` bytes have been
+    /// removed.
+    ///
+    /// More input may be appended after the mark, so whether the end of the
+    /// input was reached is not kept.
+    pub(crate) fn without_prefix(self, len: usize) -> Self {
+        InputMark {
+            offset: self.offset - len,
+            src_loc: self.src_loc,
+            wasEOF: None,
+        }
+    }
 }
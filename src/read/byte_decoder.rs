@@ -89,6 +89,8 @@ pub(super) fn ByteDecoder_nextSourceCharacter(
 
         ByteBuffer_nextByte(session);
 
+        session.check_line_style(session.offset - 1);
+
         session.src().newline();
 
         return SourceCharacter::from_u8(firstByte);
@@ -128,11 +130,15 @@ fn ByteDecoder_nextSourceCharacter_uncommon(
 
                 ByteBuffer_nextByte(session);
 
+                session.check_line_style(session.offset - 2);
+
                 session.src().windowsNewline();
 
                 return SourceCharacter::from(CodePoint::CRLF);
             }
 
+            session.check_line_style(session.offset - 1);
+
             session.src().newline();
 
             if session.check_issues && policy.contains(ENABLE_CHARACTER_DECODING_ISSUES) {
@@ -758,6 +764,8 @@ fn ByteDecoder_nextSourceCharacter_uncommon(
 
             if session.wasEOF {
 
+                session.check_line_style(session.input.len());
+
                 //
                 // Do not increment Column
                 //
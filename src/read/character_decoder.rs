@@ -1,6 +1,6 @@
 use crate::{
     generated::long_names::CODEPOINT_TO_LONGNAME_MAP,
-    issue::{CodeAction, IssueTag, Severity, SyntaxIssue},
+    issue::{Annotation, CodeAction, IssueTag, Severity, SyntaxIssue},
     long_names::{self as LongNames, self},
     read::{
         code_point::{CodePoint::*, *},
@@ -12,7 +12,7 @@ use crate::{
         NextPolicyBits::{
             ENABLE_CHARACTER_DECODING_ISSUES, SCAN_FOR_UNRECOGNIZEDLONGNAMES,
         },
-        SourceCharacter, Span, STRING_OR_COMMENT,
+        Source, SourceCharacter, Span, STRING_OR_COMMENT,
     },
     utils::{self, from_fn},
 };
@@ -167,8 +167,11 @@ fn CharacterDecoder_handleStringMetaOpen(
             Src,
             0.95,
             vec![],
-            vec![format!(
-                "The kernel parses ``\"{graphicalStr}\"`` as an empty string."
+            vec![Annotation::explanation(
+                format!(
+                    "The kernel parses ``\"{graphicalStr}\"`` as an empty string."
+                ),
+                Source::Span(Src),
             )],
         );
 
@@ -210,8 +213,11 @@ fn CharacterDecoder_handleStringMetaClose(
             Src,
             0.95,
             vec![],
-            vec![format!(
-                "The kernel parses ``\"{graphicalStr}\"`` as an empty string."
+            vec![Annotation::explanation(
+                format!(
+                    "The kernel parses ``\"{graphicalStr}\"`` as an empty string."
+                ),
+                Source::Span(Src),
             )],
         );
 
@@ -484,8 +490,12 @@ fn CharacterDecoder_handleLongName(
                     ),
                     0.75,
                     Actions,
-                    vec![format!(
-                        "``{longNameStr}`` is not a valid long name."
+                    vec![Annotation::explanation(
+                        format!("``{longNameStr}`` is not a valid long name."),
+                        Source::Span(Span::new(
+                            currentUnrecognizedStartLoc,
+                            currentWLCharacterEndLoc,
+                        )),
                     )],
                 );
 
@@ -512,8 +522,12 @@ fn CharacterDecoder_handleLongName(
                     ),
                     1.0,
                     Actions,
-                    vec![format!(
-                        "``{longNameStr}`` is not a valid long name."
+                    vec![Annotation::explanation(
+                        format!("``{longNameStr}`` is not a valid long name."),
+                        Source::Span(Span::new(
+                            currentWLCharacterStartLoc,
+                            currentWLCharacterEndLoc,
+                        )),
                     )],
                 );
 
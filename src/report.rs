@@ -0,0 +1,309 @@
+//! Render issues as human-readable reports, with the source they refer to.
+//!
+//! ```
+//! use wolfram_parser::{parse_cst_seq, report::{self, Theme}, ParseOptions};
+//!
+//! let input = "f[1.2.3]";
+//! let opts = ParseOptions::default();
+//!
+//! let result = parse_cst_seq(input, &opts);
+//! let issues: Vec<_> = result.issues().cloned().collect();
+//!
+//! let report = report::render(input, &issues, &opts, &Theme::default());
+//!
+//! assert_eq!(
+//!     report,
+//!     "error[UnexpectedImplicitTimes]: Suspicious syntax.
+//!  --> 1:4
+//!   |
+//! 1 | f[1.2.3]
+//!   |    ^
+//! "
+//! );
+//! ```
+//!
+//! The look of a report is controlled by a [`Theme`], so that embedders can
+//! match the style of their own tools:
+//!
+//! * [`Theme::chars`] selects ASCII or Unicode characters for the rules
+//!   drawn around the source.
+//! * [`Theme::max_context_lines`] limits how many source lines are shown for
+//!   a single issue.
+//! * [`Theme::style`] is called with every piece of text that is written, and
+//!   can wrap it in e.g. terminal color codes, see [`Theme::ansi()`].
+
+use std::fmt::Write;
+
+use crate::{
+    issue::{Issue, Severity},
+    source::{LineColumn, Location, Source},
+    ParseOptions,
+};
+
+/// Controls how [`render()`] draws reports.
+pub struct Theme {
+    pub chars: Chars,
+    /// Maximum number of source lines shown for a single issue.
+    ///
+    /// If an issue spans more lines, the remaining lines are replaced by an
+    /// ellipsis.
+    pub max_context_lines: usize,
+    /// Applied to every piece of text written to a report, together with
+    /// what that text is.
+    pub style: Box<dyn Fn(Style, &str) -> String + Send + Sync>,
+}
+
+/// Characters used to draw the rules of a report.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Chars {
+    /// Points to the location of an issue, like `-->`.
+    pub arrow: &'static str,
+    /// Separates line numbers from source lines, like `|`.
+    pub gutter: &'static str,
+    /// Underlines the source of an issue, like `^`.
+    pub underline: char,
+    /// Replaces source lines that are not shown, like `...`.
+    pub ellipsis: &'static str,
+}
+
+/// The kind of text passed to [`Theme::style`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Style {
+    /// The severity and tag of an issue, like `error[SyntaxError]`.
+    Header(Severity),
+    /// The message of an issue.
+    Message,
+    /// The location of an issue, and the line numbers and rules around the
+    /// source.
+    Gutter,
+    /// A line of source.
+    Source,
+    /// The underline below the source of an issue.
+    Underline(Severity),
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Chars {
+    pub const ASCII: Chars = Chars {
+        arrow: "-->",
+        gutter: "|",
+        underline: '^',
+        ellipsis: "...",
+    };
+
+    pub const UNICODE: Chars = Chars {
+        arrow: "──▶",
+        gutter: "│",
+        underline: '━',
+        ellipsis: "⋮",
+    };
+}
+
+impl Theme {
+    /// A theme that writes plain text.
+    pub fn plain(chars: Chars) -> Self {
+        Theme {
+            chars,
+            max_context_lines: 5,
+            style: Box::new(|_, text| text.to_owned()),
+        }
+    }
+
+    /// A theme that colors text using ANSI terminal escape codes.
+    pub fn ansi(chars: Chars) -> Self {
+        Theme::plain(chars).with_style(|style, text| {
+            let code = match style {
+                Style::Header(sev) | Style::Underline(sev) => match sev {
+                    Severity::Fatal | Severity::Error => "1;31",
+                    Severity::Warning => "1;33",
+                    Severity::Remark => "1;36",
+                    Severity::Formatting => "1;34",
+                },
+                Style::Message => "1",
+                Style::Gutter => "34",
+                Style::Source => return text.to_owned(),
+            };
+
+            format!("\x1b[{code}m{text}\x1b[0m")
+        })
+    }
+
+    pub fn with_style<F>(self, style: F) -> Self
+    where
+        F: Fn(Style, &str) -> String + Send + Sync + 'static,
+    {
+        Theme {
+            style: Box::new(style),
+            ..self
+        }
+    }
+
+    pub fn max_context_lines(self, max_context_lines: usize) -> Self {
+        Theme {
+            max_context_lines,
+            ..self
+        }
+    }
+
+    fn paint(&self, style: Style, text: &str) -> String {
+        (self.style)(style, text)
+    }
+}
+
+/// A plain theme using ASCII characters.
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::plain(Chars::ASCII)
+    }
+}
+
+//======================================
+// Rendering
+//======================================
+
+/// Render a report of every issue in `issues`, which were found in `input`.
+///
+/// `opts` must be the options `input` was parsed with, so that source
+/// locations are interpreted the same way, e.g. with the same tab width.
+/// Issues without a line and column source are rendered without context.
+pub fn render(
+    input: &str,
+    issues: &[Issue],
+    opts: &ParseOptions,
+    theme: &Theme,
+) -> String {
+    let lines: Vec<&str> = input.lines().collect();
+
+    let tab_width = opts.tab_width_for(input.as_bytes());
+
+    // Width of the widest line number that could be shown.
+    let number_width = lines.len().max(1).to_string().len();
+
+    let mut out = String::new();
+
+    for issue in issues {
+        render_issue(&mut out, &lines, issue, tab_width, number_width, theme);
+    }
+
+    out
+}
+
+fn render_issue(
+    out: &mut String,
+    lines: &[&str],
+    issue: &Issue,
+    tab_width: u32,
+    number_width: usize,
+    theme: &Theme,
+) {
+    let Chars {
+        arrow,
+        gutter,
+        underline,
+        ellipsis,
+    } = theme.chars;
+
+    let header = format!(
+        "{}[{}]",
+        issue.sev.as_str().to_lowercase(),
+        issue.tag.as_str()
+    );
+
+    writeln!(
+        out,
+        "{}: {}",
+        theme.paint(Style::Header(issue.sev), &header),
+        theme.paint(Style::Message, &issue.msg)
+    )
+    .unwrap();
+
+    let (start, end) = match issue.src {
+        Source::Span(span) => match (span.start(), span.end()) {
+            (
+                Location::LineColumn(LineColumn(start_line, start_column)),
+                Location::LineColumn(LineColumn(end_line, end_column)),
+            ) => (
+                (start_line.get() as usize, start_column.get() as usize),
+                (end_line.get() as usize, end_column.get() as usize),
+            ),
+            _ => return,
+        },
+        _ => return,
+    };
+
+    let blank = " ".repeat(number_width + 1);
+    let rule = format!("{blank}{gutter}");
+
+    let location = format!("{}{arrow} {}:{}", &blank[1..], start.0, start.1);
+
+    writeln!(out, "{}", theme.paint(Style::Gutter, &location)).unwrap();
+    writeln!(out, "{}", theme.paint(Style::Gutter, &rule)).unwrap();
+
+    for (index, number) in (start.0..=end.0).enumerate() {
+        let Some(line) = lines.get(number - 1) else {
+            break;
+        };
+
+        if index == theme.max_context_lines {
+            let elided = format!("{blank}{ellipsis}");
+            writeln!(out, "{}", theme.paint(Style::Gutter, &elided)).unwrap();
+            break;
+        }
+
+        let line = expand_tabs(line, tab_width);
+        let width = line.chars().count();
+
+        let first = if number == start.0 { start.1 } else { 1 };
+        let last = if number == end.0 { end.1 } else { width + 1 };
+
+        // The end of a span is exclusive, but always underline at least one
+        // column, so that empty spans are visible.
+        let len = last.saturating_sub(first).max(1);
+
+        let number = format!("{number:>number_width$} {gutter}");
+
+        writeln!(
+            out,
+            "{} {}",
+            theme.paint(Style::Gutter, &number),
+            theme.paint(Style::Source, &line)
+        )
+        .unwrap();
+
+        let marks: String = std::iter::repeat(underline).take(len).collect();
+
+        writeln!(
+            out,
+            "{} {}{}",
+            theme.paint(Style::Gutter, &rule),
+            " ".repeat(first - 1),
+            theme.paint(Style::Underline(issue.sev), &marks)
+        )
+        .unwrap();
+    }
+}
+
+/// Replace tabs by spaces, so that columns of the result match source
+/// locations.
+fn expand_tabs(line: &str, tab_width: u32) -> String {
+    let tab_width = tab_width as usize;
+
+    let mut out = String::with_capacity(line.len());
+    let mut column = 0;
+
+    for c in line.chars() {
+        if c == '\t' {
+            let stop = tab_width * (column / tab_width) + tab_width;
+            out.extend(std::iter::repeat(' ').take(stop - column));
+            column = stop;
+        } else {
+            out.push(c);
+            column += 1;
+        }
+    }
+
+    out
+}
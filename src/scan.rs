@@ -0,0 +1,453 @@
+//! Cheap pre-scans of input that do not require a full parse.
+//!
+//! ```
+//! use wolfram_parser::scan::{self, Imbalance};
+//!
+//! assert!(scan::balance("f[{1, 2}]").is_balanced());
+//!
+//! // Still waiting for more input
+//! let balance = scan::balance("f[{1, 2}");
+//!
+//! assert_eq!(balance.depth, 1);
+//! assert!(matches!(
+//!     balance.first_imbalance,
+//!     Some(Imbalance::Unclosed { .. })
+//! ));
+//! ```
+
+use crate::{
+    feature,
+    issue::{Issue, IssueTag, Severity, SyntaxIssue},
+    parse::{
+        infix_parselet_class, prefix_parselet_class, spec::OperatorPosition,
+        ParseletClass,
+    },
+    read::InputMark,
+    source::{Location, Span},
+    tokenize::{
+        token_kind::{Closer, TokenToCloser, TryGroupOpenerToCloser},
+        TokenKind, TokenRef, Tokenizer,
+    },
+    ParseOptions, ToplevelNewlineMode,
+};
+
+/// Bracket balance of some input, as computed by [`balance()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balance {
+    /// Number of groups that are still open at the end of the input, or at
+    /// the first unmatched closer.
+    pub depth: usize,
+    /// Maximum group nesting depth reached.
+    pub max_depth: usize,
+    /// The first place the input is not balanced, if any.
+    pub first_imbalance: Option<Imbalance>,
+}
+
+/// A bracket, string, or comment imbalance found by [`balance()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Imbalance {
+    /// A closer that does not match the innermost open group, like the `}` in
+    /// `f[x}`.
+    ///
+    /// `opener` is the opener of the innermost open group, or `None` if no
+    /// group was open.
+    UnmatchedCloser { closer: Span, opener: Option<Span> },
+    /// An opener that is never closed, like the `[` in `f[x`.
+    ///
+    /// If several groups are unclosed, this is the outermost one.
+    Unclosed { opener: Span },
+    /// A string or comment that is not terminated before the end of the input,
+    /// like `"abc` or `(* abc`.
+    Unterminated { tok: TokenKind, src: Span },
+}
+
+/// Whether input is complete, as determined by [`is_input_complete()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Completeness {
+    /// The input can be parsed as-is.
+    Complete,
+    /// More input is needed.
+    Incomplete(Expecting),
+    /// The input can never be completed by adding more input, e.g. because it
+    /// contains an unmatched closer.
+    Invalid(Issue),
+}
+
+/// What an incomplete input is waiting for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expecting {
+    /// The closer of the innermost open group, e.g. `"]"`.
+    Closer { closer: &'static str, opener: Span },
+    /// The end of the unterminated string at this span.
+    StringTerminator(Span),
+    /// The end of the unterminated comment at this span.
+    CommentTerminator(Span),
+    /// An operand after the operator at this span, like the `+` in `a +`.
+    Operand(Span),
+}
+
+//======================================
+// API
+//======================================
+
+/// Compute the bracket balance of `input`, tokenizing but not parsing it.
+///
+/// Brackets inside strings and comments are ignored. Scanning stops at the
+/// first unmatched closer or unterminated string or comment.
+///
+/// This is useful for deciding whether input typed into a REPL is complete:
+///
+/// ```
+/// use wolfram_parser::scan;
+///
+/// assert_eq!(scan::balance("If[x,\n  (* ] *) \"]\"").depth, 1);
+/// assert!(scan::balance("If[x,\n  (* ] *) \"]\"]").is_balanced());
+/// ```
+///
+/// This does not detect all syntax errors; input can be balanced but still
+/// fail to parse.
+pub fn balance(input: &str) -> Balance {
+    let Scan {
+        groups,
+        max_depth,
+        first_imbalance,
+        pending: _,
+    } = scan(input, &ParseOptions::builtin_default());
+
+    Balance {
+        depth: groups.len(),
+        max_depth,
+        first_imbalance,
+    }
+}
+
+/// Determine whether `input` is complete, or whether more input is expected
+/// before it can be parsed, e.g. because a group or string is still open.
+///
+/// This is intended for terminal REPLs deciding whether to evaluate the
+/// current input or show a continuation prompt:
+///
+/// ```
+/// use wolfram_parser::scan::{is_input_complete, Completeness, Expecting};
+///
+/// assert_eq!(is_input_complete("f[x] + 1"), Completeness::Complete);
+///
+/// match is_input_complete("Module[{x},\n  x = {1, 2") {
+///     Completeness::Incomplete(Expecting::Closer { closer, .. }) => {
+///         assert_eq!(closer, "}")
+///     },
+///     other => panic!("unexpected: {other:?}"),
+/// }
+///
+/// assert!(matches!(
+///     is_input_complete("f[x}"),
+///     Completeness::Invalid(_)
+/// ));
+/// ```
+///
+/// Input that ends in an infix or prefix operator, like `a +` or `f @`, is
+/// waiting for the operator's operand:
+///
+/// ```
+/// use wolfram_parser::scan::{is_input_complete, Completeness, Expecting};
+///
+/// assert!(matches!(
+///     is_input_complete("x = a +\n  (* next line *)"),
+///     Completeness::Incomplete(Expecting::Operand(_))
+/// ));
+///
+/// // Postfix operators don't need another operand.
+/// assert_eq!(is_input_complete("x++; n!"), Completeness::Complete);
+/// ```
+///
+/// Like [`balance()`], only the tokenizer is used, so input that is complete
+/// may still contain syntax errors.
+pub fn is_input_complete(input: &str) -> Completeness {
+    let Scan {
+        groups,
+        max_depth: _,
+        first_imbalance,
+        pending,
+    } = scan(input, &ParseOptions::builtin_default());
+
+    match first_imbalance {
+        None => match pending {
+            Some(src) => Completeness::Incomplete(Expecting::Operand(src)),
+            None => Completeness::Complete,
+        },
+        Some(Imbalance::Unclosed { opener: _ }) => {
+            // Report the innermost group, which must be closed first.
+            let &(opener, closer) =
+                groups.last().expect("unclosed group without open groups");
+
+            Completeness::Incomplete(Expecting::Closer {
+                closer: closer.as_str(),
+                opener,
+            })
+        },
+        Some(Imbalance::Unterminated { tok, src }) => match tok {
+            TokenKind::Error_UnterminatedComment => {
+                Completeness::Incomplete(Expecting::CommentTerminator(src))
+            },
+            _ => Completeness::Incomplete(Expecting::StringTerminator(src)),
+        },
+        Some(Imbalance::UnmatchedCloser { closer, opener }) => {
+            let msg = match opener {
+                Some(_) => "Unexpected closer.",
+                None => "Unexpected closer with no open group.",
+            };
+
+            Completeness::Invalid(SyntaxIssue(
+                IssueTag::UnexpectedCloser,
+                msg.to_owned(),
+                Severity::Fatal,
+                closer,
+                1.0,
+                vec![],
+                vec![],
+            ))
+        },
+    }
+}
+
+//======================================
+// Impls
+//======================================
+
+impl Balance {
+    /// Whether every group was closed and no imbalance was found.
+    pub fn is_balanced(&self) -> bool {
+        self.depth == 0 && self.first_imbalance.is_none()
+    }
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// Progress of [`starts_with_expression()`] through an input that is still
+/// being read.
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct ExprScan {
+    /// Offset of the first token that has not been scanned.
+    offset: usize,
+    /// Number of groups open before that token.
+    depth: usize,
+    /// The last non-trivia token before that token.
+    last: Option<TokenKind>,
+    /// Whether `last` is an operator that is missing its operand.
+    pending: bool,
+}
+
+struct Scan {
+    /// Opener span and expected closer of each open group, outermost first.
+    groups: Vec<(Span, Closer)>,
+    max_depth: usize,
+    first_imbalance: Option<Imbalance>,
+    /// The last non-trivia token scanned, if it is an operator that is
+    /// missing its operand.
+    pending: Option<Span>,
+}
+
+fn scan(input: &str, opts: &ParseOptions) -> Scan {
+    let mut tokenizer = Tokenizer::new(input.as_bytes(), opts);
+
+    let mut groups: Vec<(Span, Closer)> = Vec::new();
+    let mut max_depth = 0;
+    let mut last = None;
+    let mut pending = None;
+
+    let first_imbalance = loop {
+        if feature::CHECK_ABORT && crate::abortQ() {
+            break None;
+        }
+
+        let tok = tokenizer.peek_token();
+
+        match tok.tok {
+            TokenKind::EndOfFile => {
+                break groups
+                    .first()
+                    .map(|&(opener, _)| Imbalance::Unclosed { opener });
+            },
+            kind if kind.isUnterminated() => {
+                break Some(Imbalance::Unterminated {
+                    tok: kind,
+                    src: tok.src,
+                });
+            },
+            kind if kind.isCloser() => {
+                let closer = TokenToCloser(kind);
+
+                match groups.last() {
+                    Some(&(_, expected)) if expected == closer => {
+                        groups.pop();
+                    },
+                    last => {
+                        break Some(Imbalance::UnmatchedCloser {
+                            closer: tok.src,
+                            opener: last.map(|&(opener, _)| opener),
+                        });
+                    },
+                }
+            },
+            kind => {
+                if let Some(closer) = TryGroupOpenerToCloser(kind) {
+                    groups.push((tok.src, closer));
+                    max_depth = max_depth.max(groups.len());
+                }
+            },
+        }
+
+        if !tok.tok.isTrivia() {
+            pending = needs_operand(&tok, last, opts).then_some(tok.src);
+            last = Some(tok.tok);
+        }
+
+        tok.skip(&mut tokenizer);
+    };
+
+    Scan {
+        groups,
+        max_depth,
+        first_imbalance,
+        pending,
+    }
+}
+
+/// Returns true if `input` starts with a top-level expression that is ended
+/// by a newline, or with input that can never be completed.
+///
+/// Used to decide how much of a stream to read before parsing its next
+/// top-level expression. `progress` is the progress of a previous call with a
+/// prefix of `input`, and is updated so that the next call doesn't scan the
+/// same input again.
+///
+/// `opts` must be the options `input` will be parsed with, so that operators
+/// are recognized the same way they are by the parser.
+pub(crate) fn starts_with_expression(
+    input: &[u8],
+    opts: &ParseOptions,
+    progress: &mut ExprScan,
+) -> bool {
+    let mut tokenizer = Tokenizer::new(input, opts);
+
+    tokenizer.seek(InputMark::new(progress.offset, Location::start()));
+
+    let ExprScan {
+        offset: _,
+        mut depth,
+        mut last,
+        mut pending,
+    } = *progress;
+
+    // The last token before the end of the input may continue in input that
+    // hasn't been read yet, so scanning is resumed from its start.
+    let mut before_prev = *progress;
+
+    loop {
+        if feature::CHECK_ABORT && crate::abortQ() {
+            return true;
+        }
+
+        let before = ExprScan {
+            offset: tokenizer.offset,
+            depth,
+            last,
+            pending,
+        };
+
+        let tok = tokenizer.peek_token();
+
+        if tok.tok == TokenKind::EndOfFile {
+            return false;
+        }
+
+        *progress = before_prev;
+        before_prev = before;
+
+        match tok.tok {
+            kind if kind.isUnterminated() => return false,
+            TokenKind::ToplevelNewline | TokenKind::InternalNewline
+                if depth == 0 =>
+            {
+                if last.is_some() && !pending {
+                    return true;
+                }
+            },
+            kind if kind.isCloser() => match depth.checked_sub(1) {
+                Some(outer) => depth = outer,
+                // An unmatched closer.
+                None => return true,
+            },
+            kind => {
+                if TryGroupOpenerToCloser(kind).is_some() {
+                    depth += 1;
+                }
+            },
+        }
+
+        if !tok.tok.isTrivia() {
+            pending = needs_operand(&tok, last, opts);
+            last = Some(tok.tok);
+        }
+
+        tok.skip(&mut tokenizer);
+    }
+}
+
+/// Returns true if `token` is an infix or prefix operator that is still
+/// missing its operand when it is the last non-trivia token of the input.
+/// `prev` is the non-trivia token before `token`, if any.
+///
+/// This is derived from the parselets that would parse `token`, so that it
+/// agrees with the parser. Tokens that can also be postfix operators, like
+/// `!` and `++`, or that can end an expression, like `;` in `a;`, don't need
+/// an operand.
+fn needs_operand(
+    token: &TokenRef,
+    prev: Option<TokenKind>,
+    opts: &ParseOptions,
+) -> bool {
+    if let Some(notations) = opts.notations.as_deref() {
+        if notations.find(OperatorPosition::Infix, token).is_some()
+            || notations.find(OperatorPosition::Prefix, token).is_some()
+        {
+            return true;
+        }
+    }
+
+    // The `.` in  a =.  is part of the operator, not an operand.
+    if token.tok == TokenKind::Dot && prev == Some(TokenKind::Equal) {
+        return false;
+    }
+
+    match infix_parselet_class(token.tok) {
+        ParseletClass::BinaryOperator
+        | ParseletClass::InfixOperator
+        | ParseletClass::Times
+        | ParseletClass::Equal
+        | ParseletClass::ColonEqual
+        | ParseletClass::Tilde
+        | ParseletClass::SlashColon
+        | ParseletClass::Colon
+        | ParseletClass::ColonColon
+        | ParseletClass::GreaterGreater
+        | ParseletClass::GreaterGreaterGreater => true,
+        // The last operand of  ;  and  ;;  is optional, but is still pending
+        // if the caller continues expressions after them.
+        ParseletClass::Semi | ParseletClass::SemiSemi => {
+            opts.toplevel_newlines
+                == ToplevelNewlineMode::ContinueIfOperatorPending
+        },
+        // Tokens that start a new operand, like  \[Not]  in  a \[Not]
+        ParseletClass::InfixImplicitTimes
+        | ParseletClass::InfixDifferentialD => matches!(
+            prefix_parselet_class(token.tok),
+            ParseletClass::PrefixOperator
+                | ParseletClass::Integral
+                | ParseletClass::LessLess
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,324 @@
+//! [`serde`] support for types that can't derive it.
+//!
+//! Most public syntax types derive `Serialize` and `Deserialize` when the
+//! `serde` feature is enabled. This module implements them for token input
+//! text and types that borrow static data, and provides
+//! `#[serde(with = "...")]` helpers for fields whose types are defined in
+//! other crates or borrow static data.
+//!
+//! Token input is serialized as a string, or as bytes if it is not valid
+//! UTF-8. Borrowed [`TokenStr`] input can only be serialized; deserialize
+//! trees with owned [`TokenString`] input instead, e.g. [`Cst<TokenString>`].
+//!
+//! [`Cst<TokenString>`]: crate::cst::Cst
+
+use std::fmt;
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    ast::MissingCloser,
+    source::{Location, Source, Span},
+    symbols as sym,
+    tokenize::{token_kind::Closer, TokenInput, TokenStr, TokenString},
+};
+
+//======================================
+// Token input
+//======================================
+
+impl<'i> Serialize for TokenStr<'i> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serialize_input(self.as_bytes(), serializer)
+    }
+}
+
+impl Serialize for TokenString {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serialize_input(self.as_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenString {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TokenStringVisitor)
+    }
+}
+
+fn serialize_input<S: Serializer>(
+    input: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    // Input is only invalid UTF-8 for Error_UnsafeCharacterEncoding tokens.
+    match std::str::from_utf8(input) {
+        Ok(string) => serializer.serialize_str(string),
+        Err(_) => serializer.serialize_bytes(input),
+    }
+}
+
+struct TokenStringVisitor;
+
+impl<'de> Visitor<'de> for TokenStringVisitor {
+    type Value = TokenString;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string, or a sequence of bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<TokenString, E> {
+        Ok(TokenString {
+            buf: value.as_bytes().to_vec(),
+        })
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<TokenString, E> {
+        Ok(TokenString {
+            buf: value.to_vec(),
+        })
+    }
+
+    // Formats without a bytes type, like JSON, write bytes as a sequence.
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<TokenString, A::Error> {
+        let mut buf = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        while let Some(byte) = seq.next_element()? {
+            buf.push(byte);
+        }
+
+        Ok(TokenString { buf })
+    }
+}
+
+//======================================
+// Span
+//======================================
+
+/// Unvalidated form of a deserialized [`Span`].
+#[derive(Deserialize)]
+pub(crate) struct SpanRepr {
+    start: Location,
+    end: Location,
+}
+
+impl TryFrom<SpanRepr> for Span {
+    type Error = String;
+
+    fn try_from(SpanRepr { start, end }: SpanRepr) -> Result<Span, String> {
+        let same_kind = matches!(
+            (start, end),
+            (Location::LineColumn(_), Location::LineColumn(_))
+                | (Location::CharacterIndex(_), Location::CharacterIndex(_))
+        );
+
+        if !same_kind || start > end {
+            return Err(format!("invalid span: start = {start}, end = {end}"));
+        }
+
+        Ok(Span::new(start, end))
+    }
+}
+
+//======================================
+// MissingCloser
+//======================================
+
+/// Unvalidated form of a deserialized [`MissingCloser`].
+#[derive(Deserialize)]
+#[serde(rename = "MissingCloser")]
+struct MissingCloserRepr {
+    opener: Source,
+    expected: String,
+}
+
+// Not derived, because a derived impl would only deserialize from input that
+// lives for `'static`, like the `&'static str` closer.
+impl<'de> Deserialize<'de> for MissingCloser {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let repr = MissingCloserRepr::deserialize(deserializer)?;
+
+        MissingCloser::try_from(repr).map_err(de::Error::custom)
+    }
+}
+
+impl TryFrom<MissingCloserRepr> for MissingCloser {
+    type Error = String;
+
+    fn try_from(
+        MissingCloserRepr { opener, expected }: MissingCloserRepr,
+    ) -> Result<MissingCloser, String> {
+        const CLOSERS: &[Closer] = &[
+            Closer::BarGreater,
+            Closer::CloseCurly,
+            Closer::CloseParen,
+            Closer::CloseSquare,
+            Closer::LongName_CloseCurlyDoubleQuote,
+            Closer::LongName_CloseCurlyQuote,
+            Closer::LongName_RightAngleBracket,
+            Closer::LongName_RightAssociation,
+            Closer::LongName_RightBracketingBar,
+            Closer::LongName_RightCeiling,
+            Closer::LongName_RightDoubleBracket,
+            Closer::LongName_RightDoubleBracketingBar,
+            Closer::LongName_RightFloor,
+        ];
+
+        let expected = CLOSERS
+            .iter()
+            .map(|closer| closer.as_str())
+            .find(|closer| *closer == expected)
+            .ok_or_else(|| format!("unknown closer: {expected}"))?;
+
+        Ok(MissingCloser { opener, expected })
+    }
+}
+
+//======================================
+// `with` helpers
+//======================================
+
+/// The symbols an [`Issue`][crate::issue::Issue] can be made with.
+pub(crate) mod symbol_ref {
+    use super::*;
+
+    use crate::symbol::Symbol;
+
+    const ISSUE_SYMBOLS: &[Symbol] = &[
+        sym::CodeParser_SyntaxIssue,
+        sym::CodeParser_FormatIssue,
+        sym::CodeParser_EncodingIssue,
+        sym::CodeParser_LintIssue,
+    ];
+
+    pub(crate) fn serialize<S: Serializer>(
+        symbol: &Symbol,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(symbol.as_str())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Symbol, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        ISSUE_SYMBOLS
+            .iter()
+            .find(|symbol| symbol.as_str() == name)
+            .copied()
+            .ok_or_else(|| {
+                de::Error::custom(format!("unknown issue symbol: {name}"))
+            })
+    }
+}
+
+/// A [`wolfram_expr::Symbol`], as its fully qualified name.
+pub(crate) mod symbol {
+    use super::*;
+
+    use wolfram_expr::Symbol;
+
+    pub(crate) fn serialize<S: Serializer>(
+        symbol: &Symbol,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(symbol.as_str())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Symbol, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        Symbol::try_new(&name).ok_or_else(|| {
+            de::Error::custom(format!("invalid symbol name: {name}"))
+        })
+    }
+}
+
+/// A [`wolfram_expr::Expr`], as a tree of its atoms and normal expressions.
+pub(crate) mod expr {
+    use super::*;
+
+    use wolfram_expr::{Expr, ExprKind, Symbol};
+
+    #[derive(Serialize, Deserialize)]
+    enum ExprRepr {
+        Integer(i64),
+        Real(f64),
+        String(String),
+        Symbol(String),
+        Normal {
+            head: Box<ExprRepr>,
+            elements: Vec<ExprRepr>,
+        },
+    }
+
+    pub(crate) fn serialize<S: Serializer>(
+        expr: &Expr,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        to_repr(expr).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Expr, D::Error> {
+        from_repr(ExprRepr::deserialize(deserializer)?)
+            .map_err(de::Error::custom)
+    }
+
+    fn to_repr(expr: &Expr) -> ExprRepr {
+        match expr.kind() {
+            ExprKind::Integer(int) => ExprRepr::Integer(*int),
+            ExprKind::Real(real) => ExprRepr::Real(real.into_inner()),
+            ExprKind::String(string) => ExprRepr::String(string.clone()),
+            ExprKind::Symbol(symbol) => {
+                ExprRepr::Symbol(symbol.as_str().to_owned())
+            },
+            ExprKind::Normal(normal) => ExprRepr::Normal {
+                head: Box::new(to_repr(normal.head())),
+                elements: normal.elements().iter().map(to_repr).collect(),
+            },
+        }
+    }
+
+    fn from_repr(repr: ExprRepr) -> Result<Expr, String> {
+        let expr = match repr {
+            ExprRepr::Integer(int) => Expr::from(int),
+            ExprRepr::Real(real) if real.is_nan() => {
+                return Err("invalid Real: NaN".to_owned())
+            },
+            ExprRepr::Real(real) => Expr::real(real),
+            ExprRepr::String(string) => Expr::string(string),
+            ExprRepr::Symbol(name) => match Symbol::try_new(&name) {
+                Some(symbol) => Expr::from(symbol),
+                None => return Err(format!("invalid symbol name: {name}")),
+            },
+            ExprRepr::Normal { head, elements } => Expr::normal(
+                from_repr(*head)?,
+                elements
+                    .into_iter()
+                    .map(from_repr)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+        };
+
+        Ok(expr)
+    }
+}
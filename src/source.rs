@@ -1,11 +1,17 @@
 //! Types representing locations in the input being processed.
 
+mod line_map;
+
 use std::{
     cmp::Ordering,
     fmt::{self, Debug, Display},
+    io,
     num::NonZeroU32,
+    ops::Range,
 };
 
+use thiserror::Error;
+
 use crate::{
     long_names::{self as LongNames, codepoint_has_longname},
     read::{
@@ -16,9 +22,13 @@ use crate::{
         Escape, WLCharacter,
     },
     tokenize::tokenizer::{ASCII_FORM_FEED, ASCII_VTAB},
-    utils::{most_slice, non_zero_u32_incr, CommaSeparated, CommaTerminated},
+    utils::{
+        self, most_slice, non_zero_u32_incr, CommaSeparated, CommaTerminated,
+    },
 };
 
+pub use self::line_map::{ColumnUnit, LineMap};
+
 //==========================================================
 // Slices of source code: Buffer and BufferAndLength
 //==========================================================
@@ -371,7 +381,8 @@ pub const DEFAULT_TAB_WIDTH: u32 = 4;
 // bool operator<=(SourceLocation a, SourceLocation b);
 
 /// Specifies a region of source code in an input string or box structure.
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Source {
     /// Text span.
     ///
@@ -399,7 +410,14 @@ pub enum Source {
 ///
 /// Match over [`Span::kind()`] to access the source span position information
 /// stored in a [`Span`] instance.
-#[derive(Copy, Clone, PartialEq, Hash)]
+///
+/// Which convention a [`Span`] uses depends on the options the input was
+/// parsed with. Code that should work with either convention can convert
+/// spans into an [`AnySpan`], and use a [`LineMap`] to convert them into the
+/// representation it needs, like a [`ByteSpan`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serde_impls::SpanRepr"))]
 pub struct Span {
     start: Location,
     end: Location,
@@ -409,6 +427,8 @@ const _: () = assert!(std::mem::size_of::<Span>() == 16);
 
 /// A location in the source that can be the start or end of a [`Span`].
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
 pub enum Location {
     LineColumn(LineColumn),
     CharacterIndex(u32),
@@ -424,19 +444,113 @@ const _: () = assert!(std::mem::size_of::<Location>() == 8);
 
 
 /// Location data from a [`Span`].
+///
+/// The variant depends on the [`SourceConvention`] the input was parsed
+/// with. Use [`AnySpan`] and a [`LineMap`] to work with spans independently
+/// of that convention.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum SpanKind {
     LineColumnSpan(LineColumnSpan),
-    CharacterSpan(CharacterSpan),
+    CharIndexSpan(CharIndexSpan),
 }
 
 /// A span of input by character start and end point.
 ///
 /// This range starts indexing at 1, and is exclusive.
 ///
-/// `CharacterSpan(start, end)`
+/// `CharIndexSpan(start, end)`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CharIndexSpan(pub u32, pub u32);
+
+#[deprecated(note = "renamed to `CharIndexSpan`")]
+pub type CharacterSpan = CharIndexSpan;
+
+/// A span of input by byte offset start and end point.
+///
+/// Unlike [`CharIndexSpan`], this range starts indexing at 0, like a Rust
+/// [`Range`], and the offsets can be used to slice the input.
+///
+/// The parser never produces byte spans; a [`LineMap`] converts to and from
+/// the spans it does produce.
+///
+/// ```
+/// use wolfram_parser::{macros::src, source::ByteSpan};
+///
+/// assert_eq!(src!(byte 2..4), ByteSpan { start: 2, end: 4 });
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ByteSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A span of input in any of the [`LineColumnSpan`], [`CharIndexSpan`], or
+/// [`ByteSpan`] representations.
+///
+/// Every [`Span`] can be converted into an [`AnySpan`], and
+/// [`LineMap::convert_span()`] can convert an [`AnySpan`] into any of the
+/// [`SpanType`] representations:
+///
+/// ```
+/// use wolfram_parser::{
+///     macros::src,
+///     source::{AnySpan, ByteSpan, CharIndexSpan, LineColumnSpan, LineMap},
+///     ParseOptions,
+/// };
+///
+/// let map = LineMap::new("a\nbc", &ParseOptions::default());
+///
+/// let span = AnySpan::from(src!(2:1-2:3));
+///
+/// assert_eq!(
+///     map.convert_span::<ByteSpan>(span),
+///     Some(ByteSpan { start: 2, end: 4 })
+/// );
+/// assert_eq!(map.convert_span::<CharIndexSpan>(span), Some(src!(3..5)));
+///
+/// // Back to where we started.
+/// assert_eq!(
+///     map.convert_span::<LineColumnSpan>(ByteSpan { start: 2, end: 4 }),
+///     Some(src!(2:1-2:3))
+/// );
+/// ```
 #[derive(Debug, Copy, Clone, PartialEq)]
-pub struct CharacterSpan(pub u32, pub u32);
+pub enum AnySpan {
+    LineColumn(LineColumnSpan),
+    CharIndex(CharIndexSpan),
+    Byte(ByteSpan),
+}
+
+/// One of the representations of an [`AnySpan`], which a [`LineMap`] can
+/// convert spans to.
+pub trait SpanType: Copy + Into<AnySpan> {
+    /// Get the span of `span` in this representation.
+    ///
+    /// Returns `None` if `span` is outside of the input of `map`, or does not
+    /// start and end on character boundaries.
+    fn from_byte_span(map: &LineMap, span: ByteSpan) -> Option<Self>;
+}
+
+/// Error returned when converting a [`Span`] or [`AnySpan`] into a
+/// representation other than the one it uses.
+///
+/// Use [`LineMap::convert_span()`] to convert between representations.
+#[derive(Error, Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SpanConversionError {
+    #[error("span {0} is not a line:column span")]
+    NotLineColumn(AnySpan),
+
+    #[error("span {0} is not a character index span")]
+    NotCharIndex(AnySpan),
+
+    #[error("span {0} is not a byte span")]
+    NotByte(AnySpan),
+
+    /// A [`Span`] cannot store byte offsets.
+    #[error("span {0} is a byte span")]
+    Byte(ByteSpan),
+}
 
 /// A position in the input specified by it's line and column.
 ///
@@ -456,6 +570,7 @@ pub struct CharacterSpan(pub u32, pub u32);
 /// assert!(src!(2:1) > src!(1:7));
 /// ```
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineColumn(
     /// The line.
     pub NonZeroU32,
@@ -477,6 +592,7 @@ const _: () = assert!(std::mem::size_of::<LineColumnSpan>() == 16);
 /// The [`src!`][crate::macros::src] can be used to conveniently construct
 /// [`BoxPosition`] values.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BoxPosition {
     /// `{1, 2, 3}`
     At(Vec<usize>),
@@ -519,9 +635,7 @@ impl Debug for Location {
 impl Display for Span {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.kind() {
-            SpanKind::CharacterSpan(CharacterSpan(start, end)) => {
-                write!(f, "{}..{}", start, end)
-            },
+            SpanKind::CharIndexSpan(span) => write!(f, "{span}"),
             SpanKind::LineColumnSpan(span) => {
                 write!(f, "{span}")
             },
@@ -543,6 +657,32 @@ impl Display for LineColumnSpan {
     }
 }
 
+impl Display for CharIndexSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let CharIndexSpan(start, end) = self;
+
+        write!(f, "{start}..{end}")
+    }
+}
+
+impl Display for ByteSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ByteSpan { start, end } = self;
+
+        write!(f, "byte {start}..{end}")
+    }
+}
+
+impl Display for AnySpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnySpan::LineColumn(span) => write!(f, "{span}"),
+            AnySpan::CharIndex(span) => write!(f, "{span}"),
+            AnySpan::Byte(span) => write!(f, "{span}"),
+        }
+    }
+}
+
 impl Display for LineColumn {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let LineColumn(line, column) = self;
@@ -626,6 +766,62 @@ impl Location {
         ))
     }
 
+    /// Write this location in a compact binary form.
+    ///
+    /// See [`Location::read_binary()`].
+    pub(crate) fn write_binary<W: io::Write>(
+        &self,
+        out: &mut W,
+    ) -> io::Result<()> {
+        match *self {
+            Location::LineColumn(LineColumn(line, column)) => {
+                out.write_all(&[0])?;
+                utils::write_varint(out, u64::from(line.get()))?;
+                utils::write_varint(out, u64::from(column.get()))
+            },
+            Location::CharacterIndex(index) => {
+                out.write_all(&[1])?;
+                utils::write_varint(out, u64::from(index))
+            },
+        }
+    }
+
+    /// Read a location written by [`Location::write_binary()`].
+    pub(crate) fn read_binary<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        fn read_u32<R: io::Read>(reader: &mut R) -> io::Result<u32> {
+            u32::try_from(utils::read_varint(reader)?)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        }
+
+        fn read_non_zero<R: io::Read>(
+            reader: &mut R,
+        ) -> io::Result<NonZeroU32> {
+            NonZeroU32::new(read_u32(reader)?).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "line and column must not be zero",
+                )
+            })
+        }
+
+        let mut kind = [0];
+        reader.read_exact(&mut kind)?;
+
+        match kind[0] {
+            0 => {
+                let line = read_non_zero(reader)?;
+                let column = read_non_zero(reader)?;
+
+                Ok(Location::LineColumn(LineColumn(line, column)))
+            },
+            1 => Ok(Location::CharacterIndex(read_u32(reader)?)),
+            other => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid location kind: {other}"),
+            )),
+        }
+    }
+
     /// Advance by a number of characters in the same line.
     pub fn advance(self, chars: u32) -> Self {
         match self {
@@ -707,32 +903,32 @@ impl Location {
 // }
 
 
-impl CharacterSpan {
+impl CharIndexSpan {
     /// Returns `true` if `other` is completely contained inside `self`.
     #[allow(dead_code)]
-    pub(crate) fn contains_span(&self, other: CharacterSpan) -> bool {
+    pub(crate) fn contains_span(&self, other: CharIndexSpan) -> bool {
         crate::utils::is_interval_member(self.tuple(), other.tuple())
     }
 
-    pub(crate) fn intersects(&self, other: CharacterSpan) -> bool {
+    pub(crate) fn intersects(&self, other: CharIndexSpan) -> bool {
         crate::utils::intersection(self.tuple(), other.tuple()).is_some()
     }
 
     pub(crate) fn tuple(self) -> (u32, u32) {
-        let CharacterSpan(start, end) = self;
+        let CharIndexSpan(start, end) = self;
 
         (start, end)
     }
 
     pub(crate) fn to_rust_range(&self) -> std::ops::Range<usize> {
-        let CharacterSpan(start, end) = *self;
+        let CharIndexSpan(start, end) = *self;
 
         debug_assert!(start > 0);
 
         let start = usize::try_from(start).unwrap();
         let end = usize::try_from(end).unwrap();
 
-        // -1 because CharacterSpan is 1-indexed (like WL)
+        // -1 because CharIndexSpan is 1-indexed (like WL)
         let start = start - 1;
         let end = end - 1;
 
@@ -830,13 +1026,13 @@ impl Span {
                 self_.overlaps(other)
             },
             (
-                SpanKind::CharacterSpan(self_),
-                SpanKind::CharacterSpan(other),
+                SpanKind::CharIndexSpan(self_),
+                SpanKind::CharIndexSpan(other),
             ) => {
                 self_.intersects(other)
             },
-            (SpanKind::LineColumnSpan(_), SpanKind::CharacterSpan(_))
-            | (SpanKind::CharacterSpan(_), SpanKind::LineColumnSpan(_)) => panic!(
+            (SpanKind::LineColumnSpan(_), SpanKind::CharIndexSpan(_))
+            | (SpanKind::CharIndexSpan(_), SpanKind::LineColumnSpan(_)) => panic!(
                 "Invalid combination of Span types: Span::overlaps({self}, {other})"
             )
         }
@@ -849,7 +1045,7 @@ impl Span {
             (
                 Location::CharacterIndex(start_char),
                 Location::CharacterIndex(end_char),
-            ) => SpanKind::CharacterSpan(CharacterSpan(start_char, end_char)),
+            ) => SpanKind::CharIndexSpan(CharIndexSpan(start_char, end_char)),
             (
                 Location::LineColumn(LineColumn(start_line, start_column)),
                 Location::LineColumn(LineColumn(end_line, end_column)),
@@ -860,6 +1056,35 @@ impl Span {
             (start, end) => panic!("invalid Location combination in Span: start = {start}, end = {end}"),
         }
     }
+
+    /// Get the [`SourceConvention`] used by the locations of this span.
+    pub fn convention(self) -> SourceConvention {
+        match self.kind() {
+            SpanKind::LineColumnSpan(_) => SourceConvention::LineColumn,
+            SpanKind::CharIndexSpan(_) => SourceConvention::CharacterIndex,
+        }
+    }
+}
+
+impl ByteSpan {
+    /// Get the number of bytes in this span.
+    pub fn len(self) -> usize {
+        let ByteSpan { start, end } = self;
+
+        end - start
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the byte offsets of this span as a [`Range`], for slicing the
+    /// input.
+    pub fn to_range(self) -> Range<usize> {
+        let ByteSpan { start, end } = self;
+
+        start..end
+    }
 }
 
 impl LineColumn {
@@ -1170,9 +1395,9 @@ impl From<LineColumnSpan> for Source {
     }
 }
 
-impl From<CharacterSpan> for Span {
-    fn from(value: CharacterSpan) -> Span {
-        let CharacterSpan(start, end) = value;
+impl From<CharIndexSpan> for Span {
+    fn from(value: CharIndexSpan) -> Span {
+        let CharIndexSpan(start, end) = value;
 
         Span {
             start: Location::CharacterIndex(start),
@@ -1187,6 +1412,102 @@ impl From<BoxPosition> for Source {
     }
 }
 
+impl From<Range<usize>> for ByteSpan {
+    fn from(range: Range<usize>) -> ByteSpan {
+        let Range { start, end } = range;
+
+        ByteSpan { start, end }
+    }
+}
+
+impl From<LineColumnSpan> for AnySpan {
+    fn from(span: LineColumnSpan) -> AnySpan {
+        AnySpan::LineColumn(span)
+    }
+}
+
+impl From<CharIndexSpan> for AnySpan {
+    fn from(span: CharIndexSpan) -> AnySpan {
+        AnySpan::CharIndex(span)
+    }
+}
+
+impl From<ByteSpan> for AnySpan {
+    fn from(span: ByteSpan) -> AnySpan {
+        AnySpan::Byte(span)
+    }
+}
+
+impl From<Span> for AnySpan {
+    fn from(span: Span) -> AnySpan {
+        match span.kind() {
+            SpanKind::LineColumnSpan(span) => AnySpan::LineColumn(span),
+            SpanKind::CharIndexSpan(span) => AnySpan::CharIndex(span),
+        }
+    }
+}
+
+impl TryFrom<AnySpan> for Span {
+    type Error = SpanConversionError;
+
+    fn try_from(span: AnySpan) -> Result<Span, SpanConversionError> {
+        match span {
+            AnySpan::LineColumn(span) => Ok(Span::from(span)),
+            AnySpan::CharIndex(span) => Ok(Span::from(span)),
+            AnySpan::Byte(span) => Err(SpanConversionError::Byte(span)),
+        }
+    }
+}
+
+impl TryFrom<AnySpan> for LineColumnSpan {
+    type Error = SpanConversionError;
+
+    fn try_from(span: AnySpan) -> Result<Self, SpanConversionError> {
+        match span {
+            AnySpan::LineColumn(span) => Ok(span),
+            _ => Err(SpanConversionError::NotLineColumn(span)),
+        }
+    }
+}
+
+impl TryFrom<AnySpan> for CharIndexSpan {
+    type Error = SpanConversionError;
+
+    fn try_from(span: AnySpan) -> Result<Self, SpanConversionError> {
+        match span {
+            AnySpan::CharIndex(span) => Ok(span),
+            _ => Err(SpanConversionError::NotCharIndex(span)),
+        }
+    }
+}
+
+impl TryFrom<AnySpan> for ByteSpan {
+    type Error = SpanConversionError;
+
+    fn try_from(span: AnySpan) -> Result<Self, SpanConversionError> {
+        match span {
+            AnySpan::Byte(span) => Ok(span),
+            _ => Err(SpanConversionError::NotByte(span)),
+        }
+    }
+}
+
+impl TryFrom<Span> for LineColumnSpan {
+    type Error = SpanConversionError;
+
+    fn try_from(span: Span) -> Result<Self, SpanConversionError> {
+        LineColumnSpan::try_from(AnySpan::from(span))
+    }
+}
+
+impl TryFrom<Span> for CharIndexSpan {
+    type Error = SpanConversionError;
+
+    fn try_from(span: Span) -> Result<Self, SpanConversionError> {
+        CharIndexSpan::try_from(AnySpan::from(span))
+    }
+}
+
 //======================================
 // Source types comparision impls
 //======================================
@@ -1541,7 +1862,7 @@ impl Display for SourceCharacter {
 
 #[test]
 fn test_character_span_intersects() {
-    let CS = CharacterSpan;
+    let CS = CharIndexSpan;
 
     assert!(CS(1, 2).intersects(CS(1, 2)));
     assert!(CS(1, 3).intersects(CS(2, 4)));
@@ -0,0 +1,534 @@
+use std::{num::NonZeroU32, ops::Range};
+
+use crate::{
+    source::{
+        AnySpan, ByteSpan, CharIndexSpan, LineColumn, LineColumnSpan, Location,
+        Span, SpanType,
+    },
+    ParseOptions, SourceConvention,
+};
+
+/// Map of the lines and characters of an input, for converting between
+/// source [`Location`]s and offsets in bytes, characters, or UTF-16 code
+/// units.
+///
+/// A `LineMap` does not borrow the input, and every conversion takes
+/// `O(log n)` time, however long the lines are: only the positions of
+/// newlines, tabs, and non-ASCII characters are stored.
+///
+/// Locations are computed the same way as by the parser, using the tab width
+/// of the options the input was parsed with.
+///
+/// ```
+/// use wolfram_parser::{
+///     macros::src,
+///     source::{ColumnUnit, LineMap, Location, SourceConvention},
+///     ParseOptions,
+/// };
+///
+/// let map = LineMap::new("x = \"\u{1F600}\"\ny", &ParseOptions::default());
+///
+/// // The byte offset of `y`.
+/// assert_eq!(map.offset(Location::LineColumn(src!(2:1))), Some(11));
+///
+/// let (utf8, char, utf16) =
+///     (ColumnUnit::Utf8, ColumnUnit::Char, ColumnUnit::Utf16);
+///
+/// assert_eq!(map.convert_offset(11, utf8, char), Some(8));
+/// assert_eq!(map.convert_offset(11, utf8, utf16), Some(9));
+///
+/// assert_eq!(
+///     map.location(11, SourceConvention::CharacterIndex),
+///     Some(Location::CharacterIndex(9))
+/// );
+///
+/// // Inside of the emoji.
+/// assert_eq!(map.convert_offset(6, utf8, char), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineMap {
+    /// Length of the input in bytes, characters, and UTF-16 code units.
+    len: Offsets,
+
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+    /// Byte offset of the end of each line, before its newline.
+    line_ends: Vec<usize>,
+
+    /// Every non-ASCII character, in input order.
+    wide_chars: Vec<WideChar>,
+    /// Every tab, in input order.
+    tabs: Vec<Tab>,
+}
+
+/// Unit that offsets are counted in by [`LineMap::convert_offset()`], and
+/// that columns are counted in by [`LineMap::line_column()`] and
+/// [`LineMap::offset_at()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColumnUnit {
+    /// Unicode scalar values ([`char`]s).
+    Char,
+    /// UTF-8 bytes.
+    Utf8,
+    /// UTF-16 code units, as used by e.g. the Language Server Protocol.
+    Utf16,
+}
+
+/// An offset in each [`ColumnUnit`].
+#[derive(Debug, Copy, Clone, Default)]
+struct Offsets {
+    utf8: usize,
+    char: usize,
+    utf16: usize,
+}
+
+/// A character that is more than one byte wide.
+#[derive(Debug, Copy, Clone)]
+struct WideChar {
+    /// Offsets of the start of the character.
+    start: Offsets,
+    len_utf8: u8,
+    len_utf16: u8,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Tab {
+    /// Byte offset of the tab.
+    offset: usize,
+    /// Column the tab starts at.
+    column: u32,
+    /// Column of the character following the tab.
+    next_column: u32,
+}
+
+impl LineMap {
+    /// Map the lines and characters of `input`.
+    ///
+    /// `opts` must be the options `input` was parsed with, so that columns
+    /// are computed with the same tab width.
+    pub fn new(input: &str, opts: &ParseOptions) -> Self {
+        let tab_width = opts.tab_width_for(input.as_bytes());
+
+        let mut line_starts = vec![0];
+        let mut line_ends = Vec::new();
+        let mut wide_chars = Vec::new();
+        let mut tabs = Vec::new();
+
+        let mut position = Offsets::default();
+        let mut column = 1;
+
+        let mut chars = input.char_indices().peekable();
+
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '\n' => {
+                    // `\r\n` is a single newline, which ends at the `\n`.
+                    let end = if input[..offset].ends_with('\r') {
+                        offset - 1
+                    } else {
+                        offset
+                    };
+
+                    line_starts.push(offset + 1);
+                    line_ends.push(end);
+                    column = 1;
+                },
+                '\r' if !matches!(chars.peek(), Some((_, '\n'))) => {
+                    line_starts.push(offset + 1);
+                    line_ends.push(offset);
+                    column = 1;
+                },
+                '\t' => {
+                    let next_column = next_tab_column(tab_width, column);
+
+                    tabs.push(Tab {
+                        offset,
+                        column,
+                        next_column,
+                    });
+                    column = next_column;
+                },
+                _ => column += 1,
+            }
+
+            if !c.is_ascii() {
+                wide_chars.push(WideChar {
+                    start: position,
+                    len_utf8: c.len_utf8() as u8,
+                    len_utf16: c.len_utf16() as u8,
+                });
+            }
+
+            position.utf8 += c.len_utf8();
+            position.char += 1;
+            position.utf16 += c.len_utf16();
+        }
+
+        line_ends.push(input.len());
+
+        LineMap {
+            len: position,
+            line_starts,
+            line_ends,
+            wide_chars,
+            tabs,
+        }
+    }
+
+    /// Get the number of lines in the input.
+    ///
+    /// This is one more than the number of newlines.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Get the length of the input, in `unit`s.
+    pub fn len(&self, unit: ColumnUnit) -> usize {
+        self.len.get(unit)
+    }
+
+    /// Convert `offset`, counted in `from` units from the start of the
+    /// input, to the same offset counted in `to` units.
+    ///
+    /// Returns `None` if `offset` is outside of the input, or is inside of a
+    /// character.
+    pub fn convert_offset(
+        &self,
+        offset: usize,
+        from: ColumnUnit,
+        to: ColumnUnit,
+    ) -> Option<usize> {
+        if offset > self.len.get(from) {
+            return None;
+        }
+
+        // The last wide character that starts before `offset`.
+        let before = self
+            .wide_chars
+            .partition_point(|wide| wide.start.get(from) < offset);
+
+        let (start, converted) = match before.checked_sub(1) {
+            Some(before) => {
+                let wide = &self.wide_chars[before];
+                let end = wide.end(from);
+
+                if offset < end {
+                    return None;
+                }
+
+                (end, wide.end(to))
+            },
+            None => (0, 0),
+        };
+
+        // Every character after the wide character is one unit wide.
+        Some(converted + (offset - start))
+    }
+
+    /// Get the byte offset of `loc`.
+    ///
+    /// A column inside the expansion of a tab is the offset of that tab.
+    /// Returns `None` if `loc` is outside of the input.
+    pub fn offset(&self, loc: Location) -> Option<usize> {
+        match loc {
+            Location::LineColumn(LineColumn(line, column)) => {
+                let line = line.get() as usize - 1;
+
+                let start = *self.line_starts.get(line)?;
+                let end = self.line_ends[line];
+
+                let tabs = self.tabs_between(start, end);
+
+                // The last tab that starts at or before `column`.
+                let before =
+                    tabs.partition_point(|tab| tab.column <= column.get());
+
+                let (start, start_column) = match before.checked_sub(1) {
+                    Some(before) => {
+                        let tab = &tabs[before];
+
+                        if column.get() < tab.next_column {
+                            return Some(tab.offset);
+                        }
+
+                        (tab.offset + 1, tab.next_column)
+                    },
+                    None => (start, 1),
+                };
+
+                // Every character after the tab is one column wide.
+                let chars = (column.get() - start_column) as usize;
+
+                let offset = self.convert_offset(
+                    self.char_offset(start)? + chars,
+                    ColumnUnit::Char,
+                    ColumnUnit::Utf8,
+                )?;
+
+                (offset <= end).then_some(offset)
+            },
+            Location::CharacterIndex(index) => self.convert_offset(
+                (index as usize).checked_sub(1)?,
+                ColumnUnit::Char,
+                ColumnUnit::Utf8,
+            ),
+        }
+    }
+
+    /// Get the [`Location`] of byte offset `offset`, using `convention`.
+    ///
+    /// Returns `None` if `offset` is outside of the input, or is not on a
+    /// character boundary.
+    pub fn location(
+        &self,
+        offset: usize,
+        convention: SourceConvention,
+    ) -> Option<Location> {
+        let char_offset = self.char_offset(offset)?;
+
+        let loc = match convention {
+            SourceConvention::LineColumn => {
+                let line =
+                    self.line_starts.partition_point(|&s| s <= offset) - 1;
+
+                let start = self.line_starts[line];
+
+                // The last tab on the line that is before `offset`.
+                let (start, start_column) =
+                    match self.tabs_between(start, offset).last() {
+                        Some(tab) => (tab.offset + 1, tab.next_column),
+                        None => (start, 1),
+                    };
+
+                let chars = char_offset - self.char_offset(start)?;
+
+                Location::LineColumn(LineColumn(
+                    NonZeroU32::new(line as u32 + 1).unwrap(),
+                    NonZeroU32::new(start_column + chars as u32).unwrap(),
+                ))
+            },
+            SourceConvention::CharacterIndex => {
+                Location::CharacterIndex(char_offset as u32 + 1)
+            },
+        };
+
+        Some(loc)
+    }
+
+    /// Get the line and column of byte offset `offset`, with the column
+    /// counted in `unit`s from the start of the line.
+    ///
+    /// Unlike [`LineMap::location()`], tabs are one unit wide.
+    ///
+    /// Returns `None` if `offset` is outside of the input, or is not on a
+    /// character boundary.
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     macros::src,
+    ///     source::{ColumnUnit, LineMap},
+    ///     ParseOptions,
+    /// };
+    ///
+    /// let map = LineMap::new("x\n\u{1F600} y", &ParseOptions::default());
+    ///
+    /// assert_eq!(map.line_column(7, ColumnUnit::Char), Some(src!(2:3)));
+    /// assert_eq!(map.line_column(7, ColumnUnit::Utf8), Some(src!(2:6)));
+    /// assert_eq!(map.line_column(7, ColumnUnit::Utf16), Some(src!(2:4)));
+    /// ```
+    pub fn line_column(
+        &self,
+        offset: usize,
+        unit: ColumnUnit,
+    ) -> Option<LineColumn> {
+        let units = self.convert_offset(offset, ColumnUnit::Utf8, unit)?;
+
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+
+        let start = self.convert_offset(
+            self.line_starts[line],
+            ColumnUnit::Utf8,
+            unit,
+        )?;
+
+        Some(LineColumn(
+            NonZeroU32::new(line as u32 + 1).unwrap(),
+            NonZeroU32::new((units - start) as u32 + 1).unwrap(),
+        ))
+    }
+
+    /// Get the byte offset of a line and column, with the column counted in
+    /// `unit`s from the start of the line.
+    ///
+    /// This is the inverse of [`LineMap::line_column()`]. Returns `None` if
+    /// the position is outside of the input, or is inside of a character.
+    pub fn offset_at(
+        &self,
+        pos: LineColumn,
+        unit: ColumnUnit,
+    ) -> Option<usize> {
+        let LineColumn(line, column) = pos;
+
+        let line = line.get() as usize - 1;
+
+        let start = *self.line_starts.get(line)?;
+        let end = self.line_ends[line];
+
+        let start = self.convert_offset(start, ColumnUnit::Utf8, unit)?;
+
+        let offset = self.convert_offset(
+            start + (column.get() as usize - 1),
+            unit,
+            ColumnUnit::Utf8,
+        )?;
+
+        (offset <= end).then_some(offset)
+    }
+
+    /// Get the byte offsets of `span`, which uses either
+    /// [`SourceConvention`].
+    ///
+    /// Returns `None` if `span` is outside of the input.
+    pub fn span_offsets(&self, span: Span) -> Option<Range<usize>> {
+        self.byte_span(span).map(ByteSpan::to_range)
+    }
+
+    /// Get the byte offsets of `span`, in any representation.
+    ///
+    /// Returns `None` if `span` is outside of the input, or is a
+    /// [`ByteSpan`] that does not start and end on character boundaries.
+    pub fn byte_span(&self, span: impl Into<AnySpan>) -> Option<ByteSpan> {
+        let (start, end) = match span.into() {
+            AnySpan::LineColumn(LineColumnSpan { start, end }) => (
+                self.offset(Location::LineColumn(start))?,
+                self.offset(Location::LineColumn(end))?,
+            ),
+            AnySpan::CharIndex(CharIndexSpan(start, end)) => (
+                self.offset(Location::CharacterIndex(start))?,
+                self.offset(Location::CharacterIndex(end))?,
+            ),
+            AnySpan::Byte(ByteSpan { start, end }) => {
+                // Both offsets must be character boundaries.
+                self.char_offset(start)?;
+                self.char_offset(end)?;
+
+                (start, end)
+            },
+        };
+
+        (start <= end).then_some(ByteSpan { start, end })
+    }
+
+    /// Convert `span`, in any representation, to the `T` representation.
+    ///
+    /// Returns `None` if `span` is outside of the input, or is a
+    /// [`ByteSpan`] that does not start and end on character boundaries.
+    ///
+    /// See [`AnySpan`] for an example.
+    pub fn convert_span<T: SpanType>(
+        &self,
+        span: impl Into<AnySpan>,
+    ) -> Option<T> {
+        T::from_byte_span(self, self.byte_span(span)?)
+    }
+
+    /// Get the [`Span`] of the byte offsets `offsets`, using `convention`.
+    ///
+    /// Returns `None` if either offset is outside of the input, or is not on
+    /// a character boundary.
+    pub fn span(
+        &self,
+        offsets: Range<usize>,
+        convention: SourceConvention,
+    ) -> Option<Span> {
+        Some(Span::new(
+            self.location(offsets.start, convention)?,
+            self.location(offsets.end, convention)?,
+        ))
+    }
+
+    /// Convert `span` to use `convention`.
+    ///
+    /// Returns `None` if `span` is outside of the input.
+    pub fn convert(
+        &self,
+        span: Span,
+        convention: SourceConvention,
+    ) -> Option<Span> {
+        self.span(self.span_offsets(span)?, convention)
+    }
+
+    //==================================
+    // Helpers
+    //==================================
+
+    /// Number of characters before byte offset `offset`.
+    fn char_offset(&self, offset: usize) -> Option<usize> {
+        self.convert_offset(offset, ColumnUnit::Utf8, ColumnUnit::Char)
+    }
+
+    /// The tabs between byte offsets `start` and `end`, excluding a tab at
+    /// `end`.
+    fn tabs_between(&self, start: usize, end: usize) -> &[Tab] {
+        let first = self.tabs.partition_point(|tab| tab.offset < start);
+        let last = self.tabs.partition_point(|tab| tab.offset < end);
+
+        &self.tabs[first..last]
+    }
+}
+
+impl SpanType for ByteSpan {
+    fn from_byte_span(map: &LineMap, span: ByteSpan) -> Option<Self> {
+        map.byte_span(span)
+    }
+}
+
+impl SpanType for LineColumnSpan {
+    fn from_byte_span(map: &LineMap, span: ByteSpan) -> Option<Self> {
+        let ByteSpan { start, end } = map.byte_span(span)?;
+
+        let span = map.span(start..end, SourceConvention::LineColumn)?;
+
+        LineColumnSpan::try_from(span).ok()
+    }
+}
+
+impl SpanType for CharIndexSpan {
+    fn from_byte_span(map: &LineMap, span: ByteSpan) -> Option<Self> {
+        let ByteSpan { start, end } = map.byte_span(span)?;
+
+        let span = map.span(start..end, SourceConvention::CharacterIndex)?;
+
+        CharIndexSpan::try_from(span).ok()
+    }
+}
+
+impl Offsets {
+    fn get(&self, unit: ColumnUnit) -> usize {
+        match unit {
+            ColumnUnit::Char => self.char,
+            ColumnUnit::Utf8 => self.utf8,
+            ColumnUnit::Utf16 => self.utf16,
+        }
+    }
+}
+
+impl WideChar {
+    /// Offset of the end of this character, in `unit`s.
+    fn end(&self, unit: ColumnUnit) -> usize {
+        let len = match unit {
+            ColumnUnit::Char => 1,
+            ColumnUnit::Utf8 => self.len_utf8,
+            ColumnUnit::Utf16 => self.len_utf16,
+        };
+
+        self.start.get(unit) + len as usize
+    }
+}
+
+/// Column following a tab at `column`, in the same way as the tokenizer.
+fn next_tab_column(tab_width: u32, column: u32) -> u32 {
+    let tab_stop = tab_width * ((column - 1) / tab_width) + 1;
+
+    tab_stop + tab_width
+}
@@ -95,6 +95,7 @@ crate::symbol::nested_symbol! {
 pub const CodeParser_AbstractSyntaxErrorNode: Symbol = unsafe { Symbol::unchecked_new("CodeParser`AbstractSyntaxErrorNode") };
 pub const CodeParser_BinaryAt: Symbol = unsafe { Symbol::unchecked_new("CodeParser`BinaryAt") };
 pub const CodeParser_BinaryNode: Symbol = unsafe { Symbol::unchecked_new("CodeParser`BinaryNode") };
+pub const CodeParser_BinaryNotation: Symbol = unsafe { Symbol::unchecked_new("CodeParser`BinaryNotation") };
 pub const CodeParser_BinarySlashSlash: Symbol = unsafe { Symbol::unchecked_new("CodeParser`BinarySlashSlash") };
 pub const CodeParser_BoxNode: Symbol = unsafe { Symbol::unchecked_new("CodeParser`BoxNode") };
 pub const CodeParser_CallMissingCloserNode: Symbol = unsafe { Symbol::unchecked_new("CodeParser`CallMissingCloserNode") };
@@ -124,6 +125,7 @@ pub const CodeParser_InternalInvalid: Symbol = unsafe { Symbol::unchecked_new("C
 pub const CodeParser_LeafNode: Symbol = unsafe { Symbol::unchecked_new("CodeParser`LeafNode") };
 pub const CodeParser_Library_LongNameSuggestion: Symbol = unsafe { Symbol::unchecked_new("CodeParser`Library`LongNameSuggestion") };
 pub const CodeParser_Library_SetConcreteParseProgress: Symbol = unsafe { Symbol::unchecked_new("CodeParser`Library`SetConcreteParseProgress") };
+pub const CodeParser_LintIssue: Symbol = unsafe { Symbol::unchecked_new("CodeParser`LintIssue") };
 pub const CodeParser_PatternBlank: Symbol = unsafe { Symbol::unchecked_new("CodeParser`PatternBlank") };
 pub const CodeParser_PatternBlankNullSequence: Symbol = unsafe { Symbol::unchecked_new("CodeParser`PatternBlankNullSequence") };
 pub const CodeParser_PatternBlankSequence: Symbol = unsafe { Symbol::unchecked_new("CodeParser`PatternBlankSequence") };
@@ -133,6 +135,7 @@ pub const CodeParser_PrefixBinaryNode: Symbol = unsafe { Symbol::unchecked_new("
 pub const CodeParser_PrefixLinearSyntaxBang: Symbol = unsafe { Symbol::unchecked_new("CodeParser`PrefixLinearSyntaxBang") };
 pub const CodeParser_PrefixNode: Symbol = unsafe { Symbol::unchecked_new("CodeParser`PrefixNode") };
 pub const CodeParser_PrefixNot2: Symbol = unsafe { Symbol::unchecked_new("CodeParser`PrefixNot2") };
+pub const CodeParser_PrefixNotation: Symbol = unsafe { Symbol::unchecked_new("CodeParser`PrefixNotation") };
 pub const CodeParser_ReplaceText: Symbol = unsafe { Symbol::unchecked_new("CodeParser`ReplaceText") };
 pub const CodeParser_Source: Symbol = unsafe { Symbol::unchecked_new("CodeParser`Source") };
 pub const CodeParser_SyntaxErrorNode: Symbol = unsafe { Symbol::unchecked_new("CodeParser`SyntaxErrorNode") };
@@ -144,6 +147,7 @@ pub const CodeParser_UnterminatedTokenErrorNeedsReparseNode: Symbol = unsafe { S
 pub const SyntaxError_ExpectedSet: Symbol = unsafe { Symbol::unchecked_new("SyntaxError`ExpectedSet") };
 pub const SyntaxError_ExpectedSymbol: Symbol = unsafe { Symbol::unchecked_new("SyntaxError`ExpectedSymbol") };
 pub const SyntaxError_ExpectedTilde: Symbol = unsafe { Symbol::unchecked_new("SyntaxError`ExpectedTilde") };
+pub const SyntaxError_Unparsable: Symbol = unsafe { Symbol::unchecked_new("SyntaxError`Unparsable") };
 pub const AddTo: Symbol = unsafe { Symbol::unchecked_new("System`AddTo") };
 pub const Alternatives: Symbol = unsafe { Symbol::unchecked_new("System`Alternatives") };
 pub const And: Symbol = unsafe { Symbol::unchecked_new("System`And") };
@@ -905,3 +909,377 @@ pub mod Token {
     pub const Unknown: Symbol = unsafe { Symbol::unchecked_new("Token`Unknown") };
     pub const Whitespace: Symbol = unsafe { Symbol::unchecked_new("Token`Whitespace") };
 }
+
+//======================================
+// Lookup
+//======================================
+
+/// Names of the `` System` `` symbols above, sorted.
+const SYSTEM_SYMBOL_NAMES: &[&str] = &[
+    "AddTo",
+    "After",
+    "All",
+    "Alternatives",
+    "And",
+    "AngleBracket",
+    "Application",
+    "Apply",
+    "ApplyTo",
+    "Association",
+    "Backslash",
+    "Because",
+    "Before",
+    "Blank",
+    "BlankNullSequence",
+    "BlankSequence",
+    "BracketingBar",
+    "ByteArray",
+    "Cap",
+    "CapitalDifferentialD",
+    "Ceiling",
+    "CenterDot",
+    "CircleDot",
+    "CircleMinus",
+    "CirclePlus",
+    "CircleTimes",
+    "ClockwiseContourIntegral",
+    "Colon",
+    "Composition",
+    "CompoundExpression",
+    "Condition",
+    "Conditioned",
+    "ConfidenceLevel",
+    "Congruent",
+    "Conjugate",
+    "ConjugateTranspose",
+    "ContinuedFractionK",
+    "ContourIntegral",
+    "Coproduct",
+    "CounterClockwiseContourIntegral",
+    "Cross",
+    "CubeRoot",
+    "Cup",
+    "CupCap",
+    "CurlyDoubleQuote",
+    "CurlyQuote",
+    "Decrement",
+    "Degree",
+    "Del",
+    "Derivative",
+    "Diamond",
+    "DifferentialD",
+    "DirectedEdge",
+    "Distributed",
+    "Divide",
+    "DivideBy",
+    "Divisible",
+    "Dot",
+    "DotEqual",
+    "DoubleBracketingBar",
+    "DoubleContourIntegral",
+    "DoubleDownArrow",
+    "DoubleLeftArrow",
+    "DoubleLeftRightArrow",
+    "DoubleLeftTee",
+    "DoubleLongLeftArrow",
+    "DoubleLongLeftRightArrow",
+    "DoubleLongRightArrow",
+    "DoubleRightArrow",
+    "DoubleRightTee",
+    "DoubleUpArrow",
+    "DoubleUpDownArrow",
+    "DoubleVerticalBar",
+    "DownArrow",
+    "DownArrowBar",
+    "DownArrowUpArrow",
+    "DownLeftRightVector",
+    "DownLeftTeeVector",
+    "DownLeftVector",
+    "DownLeftVectorBar",
+    "DownRightTeeVector",
+    "DownRightVector",
+    "DownRightVectorBar",
+    "DownTee",
+    "DownTeeArrow",
+    "E",
+    "Element",
+    "EndOfFile",
+    "Equal",
+    "EqualTilde",
+    "Equilibrium",
+    "Equivalent",
+    "EvaluatePacket",
+    "Exists",
+    "ExpectationE",
+    "Factorial",
+    "Factorial2",
+    "Floor",
+    "ForAll",
+    "Function",
+    "Get",
+    "Greater",
+    "GreaterEqual",
+    "GreaterEqualLess",
+    "GreaterFullEqual",
+    "GreaterGreater",
+    "GreaterLess",
+    "GreaterSlantEqual",
+    "GreaterTilde",
+    "HermitianConjugate",
+    "HumpDownHump",
+    "HumpEqual",
+    "I",
+    "Implies",
+    "Increment",
+    "Inequality",
+    "Infinity",
+    "Information",
+    "Integer",
+    "Integral",
+    "Integrate",
+    "Intersection",
+    "InvisiblePostfixScriptBase",
+    "InvisiblePrefixScriptBase",
+    "LeftArrow",
+    "LeftArrowBar",
+    "LeftArrowRightArrow",
+    "LeftDownTeeVector",
+    "LeftDownVector",
+    "LeftDownVectorBar",
+    "LeftRightArrow",
+    "LeftRightVector",
+    "LeftTee",
+    "LeftTeeArrow",
+    "LeftTeeVector",
+    "LeftTriangle",
+    "LeftTriangleBar",
+    "LeftTriangleEqual",
+    "LeftUpDownVector",
+    "LeftUpTeeVector",
+    "LeftUpVector",
+    "LeftUpVectorBar",
+    "LeftVector",
+    "LeftVectorBar",
+    "Less",
+    "LessEqual",
+    "LessEqualGreater",
+    "LessFullEqual",
+    "LessGreater",
+    "LessLess",
+    "LessSlantEqual",
+    "LessTilde",
+    "List",
+    "LongLeftArrow",
+    "LongLeftRightArrow",
+    "LongRightArrow",
+    "LowerLeftArrow",
+    "LowerRightArrow",
+    "Map",
+    "MapAll",
+    "MapApply",
+    "MessageName",
+    "Minus",
+    "MinusPlus",
+    "Missing",
+    "Nand",
+    "NestedGreaterGreater",
+    "NestedLessLess",
+    "NonCommutativeMultiply",
+    "Nor",
+    "Not",
+    "NotCongruent",
+    "NotCupCap",
+    "NotDoubleVerticalBar",
+    "NotElement",
+    "NotEqualTilde",
+    "NotExists",
+    "NotGreater",
+    "NotGreaterEqual",
+    "NotGreaterFullEqual",
+    "NotGreaterGreater",
+    "NotGreaterLess",
+    "NotGreaterSlantEqual",
+    "NotGreaterTilde",
+    "NotHumpDownHump",
+    "NotHumpEqual",
+    "NotLeftTriangle",
+    "NotLeftTriangleBar",
+    "NotLeftTriangleEqual",
+    "NotLess",
+    "NotLessEqual",
+    "NotLessFullEqual",
+    "NotLessGreater",
+    "NotLessLess",
+    "NotLessSlantEqual",
+    "NotLessTilde",
+    "NotNestedGreaterGreater",
+    "NotNestedLessLess",
+    "NotPrecedes",
+    "NotPrecedesEqual",
+    "NotPrecedesSlantEqual",
+    "NotPrecedesTilde",
+    "NotReverseElement",
+    "NotRightTriangle",
+    "NotRightTriangleBar",
+    "NotRightTriangleEqual",
+    "NotSquareSubset",
+    "NotSquareSubsetEqual",
+    "NotSquareSuperset",
+    "NotSquareSupersetEqual",
+    "NotSubset",
+    "NotSubsetEqual",
+    "NotSucceeds",
+    "NotSucceedsEqual",
+    "NotSucceedsSlantEqual",
+    "NotSucceedsTilde",
+    "NotSuperset",
+    "NotSupersetEqual",
+    "NotTilde",
+    "NotTildeEqual",
+    "NotTildeFullEqual",
+    "NotTildeTilde",
+    "NotVerticalBar",
+    "Nothing",
+    "Null",
+    "Optional",
+    "Or",
+    "Out",
+    "Part",
+    "Pattern",
+    "PatternTest",
+    "PermutationProduct",
+    "Perpendicular",
+    "Pi",
+    "Piecewise",
+    "Plus",
+    "PlusMinus",
+    "Power",
+    "PreDecrement",
+    "PreIncrement",
+    "Precedes",
+    "PrecedesEqual",
+    "PrecedesSlantEqual",
+    "PrecedesTilde",
+    "ProbabilityPr",
+    "Product",
+    "Proportion",
+    "Proportional",
+    "Put",
+    "PutAppend",
+    "Rational",
+    "Real",
+    "Repeated",
+    "RepeatedNull",
+    "ReplaceAll",
+    "ReplaceRepeated",
+    "ReverseElement",
+    "ReverseEquilibrium",
+    "ReverseUpEquilibrium",
+    "RightArrow",
+    "RightArrowBar",
+    "RightArrowLeftArrow",
+    "RightComposition",
+    "RightDownTeeVector",
+    "RightDownVector",
+    "RightDownVectorBar",
+    "RightTee",
+    "RightTeeArrow",
+    "RightTeeVector",
+    "RightTriangle",
+    "RightTriangleBar",
+    "RightTriangleEqual",
+    "RightUpDownVector",
+    "RightUpTeeVector",
+    "RightUpVector",
+    "RightUpVectorBar",
+    "RightVector",
+    "RightVectorBar",
+    "RoundImplies",
+    "Rule",
+    "RuleDelayed",
+    "SameQ",
+    "Set",
+    "SetDelayed",
+    "ShortDownArrow",
+    "ShortLeftArrow",
+    "ShortRightArrow",
+    "ShortUpArrow",
+    "Slot",
+    "SlotSequence",
+    "SmallCircle",
+    "Span",
+    "Sqrt",
+    "Square",
+    "SquareIntersection",
+    "SquareSubset",
+    "SquareSubsetEqual",
+    "SquareSuperset",
+    "SquareSupersetEqual",
+    "SquareUnion",
+    "Star",
+    "String",
+    "StringExpression",
+    "StringJoin",
+    "Subset",
+    "SubsetEqual",
+    "SubtractFrom",
+    "Succeeds",
+    "SucceedsEqual",
+    "SucceedsSlantEqual",
+    "SucceedsTilde",
+    "SuchThat",
+    "Sum",
+    "Superset",
+    "SupersetEqual",
+    "Symbol",
+    "TagSet",
+    "TagSetDelayed",
+    "TagUnset",
+    "TensorProduct",
+    "TensorWedge",
+    "Therefore",
+    "Tilde",
+    "TildeEqual",
+    "TildeFullEqual",
+    "TildeTilde",
+    "Times",
+    "TimesBy",
+    "Transpose",
+    "TwoWayRule",
+    "TypeSpecifier",
+    "UndirectedEdge",
+    "Unequal",
+    "Union",
+    "UnionPlus",
+    "UnsameQ",
+    "Unset",
+    "UpArrow",
+    "UpArrowBar",
+    "UpArrowDownArrow",
+    "UpDownArrow",
+    "UpEquilibrium",
+    "UpSet",
+    "UpSetDelayed",
+    "UpTee",
+    "UpTeeArrow",
+    "UpperLeftArrow",
+    "UpperRightArrow",
+    "VectorGreater",
+    "VectorGreaterEqual",
+    "VectorLess",
+    "VectorLessEqual",
+    "Vee",
+    "VerticalBar",
+    "VerticalSeparator",
+    "VerticalTilde",
+    "Wedge",
+    "Whitespace",
+    "Xnor",
+    "Xor",
+];
+
+/// Whether `name` is the name of a `` System` `` symbol used by CodeParser,
+/// like `"Plus"`.
+pub(crate) fn is_system_symbol(name: &str) -> bool {
+    SYSTEM_SYMBOL_NAMES.binary_search(&name).is_ok()
+}
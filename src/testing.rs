@@ -0,0 +1,265 @@
+//! Utilities for testing tools that rewrite Wolfram Language source.
+//!
+//! [`check_format_stability()`] checks that a formatting function is
+//! idempotent, and that it does not change the syntax of the code it formats.
+//! [`check_format_stability_dir()`] runs the same check over every source file
+//! in a directory, so that a formatter can be validated on an existing codebase
+//! before it is trusted to rewrite files in place.
+//!
+//! ```
+//! use wolfram_parser::{testing, ParseOptions};
+//!
+//! // A "formatter" that removes trailing whitespace.
+//! let format = |input: &str| -> String {
+//!     input.lines().map(str::trim_end).collect::<Vec<_>>().join("\n")
+//! };
+//!
+//! let result = testing::check_format_stability(
+//!     "f[x]   \ng[y]",
+//!     &ParseOptions::default(),
+//!     format,
+//! );
+//!
+//! assert_eq!(result, Ok(()));
+//! ```
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::{
+    cst::{CallHead, CallNode, Cst, CstSeq},
+    parse_cst_seq,
+    tokenize::{
+        normalize::{self, NormalizedToken},
+        TokenInput,
+    },
+    NodeSeq, ParseOptions,
+};
+
+/// File extensions of the source files checked by
+/// [`check_format_stability_dir()`].
+pub const SOURCE_EXTENSIONS: &[&str] = &["wl", "m", "wls", "wlt", "mt"];
+
+/// Why formatting some input was not stable, as returned by
+/// [`check_format_stability()`].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum StabilityError {
+    /// Formatting the formatted input changed it again.
+    #[error("formatting is not idempotent")]
+    NotIdempotent { once: String, twice: String },
+    /// The formatted input does not have the same syntax as the original
+    /// input.
+    #[error("formatting changed the syntax of the input")]
+    SyntaxChanged { formatted: String },
+}
+
+/// The results of [`check_format_stability_dir()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StabilityReport {
+    /// Paths of every file that was checked.
+    pub checked: Vec<PathBuf>,
+    /// Files for which formatting was not stable.
+    pub failures: Vec<(PathBuf, StabilityError)>,
+}
+
+/// Check that `format` is idempotent on `input`, and that it does not change
+/// the syntax of `input`.
+///
+/// The syntax is compared using [`same_syntax()`].
+pub fn check_format_stability<F: Fn(&str) -> String>(
+    input: &str,
+    opts: &ParseOptions,
+    format: F,
+) -> Result<(), StabilityError> {
+    let once = format(input);
+
+    if !same_syntax(input, &once, opts) {
+        return Err(StabilityError::SyntaxChanged { formatted: once });
+    }
+
+    let twice = format(&once);
+
+    if twice != once {
+        return Err(StabilityError::NotIdempotent { once, twice });
+    }
+
+    Ok(())
+}
+
+/// Run [`check_format_stability()`] on every source file in `dir` and its
+/// subdirectories.
+///
+/// Files are recognized by their extension, see [`SOURCE_EXTENSIONS`]. Files
+/// are checked in sorted path order.
+pub fn check_format_stability_dir<F: Fn(&str) -> String>(
+    dir: &Path,
+    opts: &ParseOptions,
+    format: F,
+) -> io::Result<StabilityReport> {
+    let mut paths = Vec::new();
+
+    collect_source_files(dir, &mut paths)?;
+
+    let mut failures = Vec::new();
+
+    for path in &paths {
+        let input = std::fs::read_to_string(path)?;
+
+        if let Err(err) = check_format_stability(&input, opts, &format) {
+            failures.push((path.clone(), err));
+        }
+    }
+
+    Ok(StabilityReport {
+        checked: paths,
+        failures,
+    })
+}
+
+/// Returns true if `a` and `b` parse to the same syntax.
+///
+/// Trivia, like whitespace and comments, is ignored, and tokens are compared
+/// using [`normalize()`][crate::tokenize::normalize::normalize()], so
+/// equivalent spellings like `->` and `\[Rule]` are considered the same.
+pub fn same_syntax(a: &str, b: &str, opts: &ParseOptions) -> bool {
+    let a = parse_cst_seq(a, opts).syntax;
+    let b = parse_cst_seq(b, opts).syntax;
+
+    syntax_shape(&a) == syntax_shape(&b)
+}
+
+//======================================
+// Helpers
+//======================================
+
+impl StabilityReport {
+    /// Returns true if formatting was stable for every file.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+pub(crate) fn collect_source_files(
+    dir: &Path,
+    paths: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_source_files(&path, paths)?;
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+        {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// A pre-order walk of a syntax tree, with trivia removed.
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Open(String),
+    Token(NormalizedToken),
+    Close,
+}
+
+fn syntax_shape<I: TokenInput, S>(nodes: &CstSeq<I, S>) -> Vec<Shape> {
+    let mut shape = Vec::new();
+
+    push_seq_shape(nodes, &mut shape);
+
+    shape
+}
+
+fn push_seq_shape<I: TokenInput, S>(
+    nodes: &CstSeq<I, S>,
+    shape: &mut Vec<Shape>,
+) {
+    let NodeSeq(nodes) = nodes;
+
+    for node in nodes {
+        push_shape(node, shape);
+    }
+}
+
+fn push_shape<I: TokenInput, S>(node: &Cst<I, S>, shape: &mut Vec<Shape>) {
+    let (label, children) = match node {
+        Cst::Token(token) => {
+            if !token.tok.isTrivia() {
+                shape.push(Shape::Token(normalize::normalize(token)));
+            }
+            return;
+        },
+        Cst::Call(CallNode { head, body }) => {
+            shape.push(Shape::Open(format!("Call({:?})", body.as_op().op)));
+
+            match head {
+                CallHead::Concrete(head) => push_seq_shape(head, shape),
+                CallHead::Aggregate(head) => push_shape(head, shape),
+            }
+
+            push_seq_shape(&body.as_op().children, shape);
+
+            shape.push(Shape::Close);
+            return;
+        },
+        Cst::SyntaxError(node) => {
+            (format!("SyntaxError({:?})", node.err), &node.children)
+        },
+        Cst::Prefix(node) => {
+            (format!("Prefix({:?})", node.0.op), &node.0.children)
+        },
+        Cst::Infix(node) => {
+            (format!("Infix({:?})", node.0.op), &node.0.children)
+        },
+        Cst::Postfix(node) => {
+            (format!("Postfix({:?})", node.0.op), &node.0.children)
+        },
+        Cst::Binary(node) => {
+            (format!("Binary({:?})", node.0.op), &node.0.children)
+        },
+        Cst::Ternary(node) => {
+            (format!("Ternary({:?})", node.0.op), &node.0.children)
+        },
+        Cst::PrefixBinary(node) => {
+            (format!("PrefixBinary({:?})", node.0.op), &node.0.children)
+        },
+        Cst::Compound(node) => {
+            (format!("Compound({:?})", node.0.op), &node.0.children)
+        },
+        Cst::Group(node) => {
+            (format!("Group({:?})", node.0.op), &node.0.children)
+        },
+        Cst::GroupMissingCloser(node) => (
+            format!("GroupMissingCloser({:?})", node.0.op),
+            &node.0.children,
+        ),
+        Cst::GroupMissingOpener(node) => (
+            format!("GroupMissingOpener({:?})", node.0.op),
+            &node.0.children,
+        ),
+        Cst::Box(node) => (format!("Box({:?})", node.kind), &node.children),
+        Cst::Code(_) => {
+            shape.push(Shape::Open("Code".to_owned()));
+            shape.push(Shape::Close);
+            return;
+        },
+    };
+
+    shape.push(Shape::Open(label));
+    push_seq_shape(children, shape);
+    shape.push(Shape::Close);
+}
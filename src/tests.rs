@@ -15,6 +15,44 @@ mod test_paclet_decoder;
 mod test_roundtrip_compatibility;
 mod test_file_parsing;
 mod test_sll_files;
+mod test_index;
+mod test_token_stream;
+mod test_data;
+mod test_scan;
+mod test_missing_closer;
+mod test_explain;
+mod test_normalize;
+mod test_format_stability;
+mod test_cli;
+mod test_paclet;
+mod test_whitespace;
+mod test_report;
+mod test_line_map;
+mod test_lint;
+mod test_docs;
+mod test_analysis;
+mod test_file_ops;
+mod test_display_tree;
+mod test_render;
+mod test_format;
+mod test_to_expr;
+mod test_cst_visit;
+mod test_ast_visit;
+mod test_recovery;
+mod test_notation;
+mod test_spans;
+mod test_parse_box;
+mod test_diagnostics;
+mod test_lsp;
+mod test_issue_dedup;
+#[cfg(feature = "incremental")]
+mod test_query;
+#[cfg(feature = "bench")]
+mod test_benchmarks;
+#[cfg(feature = "serde")]
+mod test_serde;
+#[cfg(feature = "bumpalo")]
+mod test_arena;
 
 use pretty_assertions::assert_eq;
 
@@ -700,6 +738,8 @@ fn test_abstract_parse() {
                     data: AstMetadata {
                         source: Source::Span(src!(1:1-1:2).into()),
                         issues: vec![],
+                        implicit: false,
+                        comparisons: None,
                     },
                 },
                 Ast::Leaf {
@@ -708,12 +748,16 @@ fn test_abstract_parse() {
                     data: AstMetadata {
                         source: Source::Span(src!(1:5-1:6).into()),
                         issues: vec![],
+                        implicit: false,
+                        comparisons: None,
                     },
                 },
             ],
             data: AstMetadata {
                 source: Source::Span(src!(1:1-1:6).into()),
                 issues: vec![],
+                implicit: false,
+                comparisons: None,
             },
         }
     )
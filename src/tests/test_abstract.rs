@@ -1,19 +1,29 @@
 use crate::{
-    abstract_cst::{abstract_cst, aggregate_cst},
-    ast::{Ast, AstMetadata},
+    abstract_cst::{
+        abstract_cst, abstract_cst_seq_with_map, abstract_cst_with_map,
+        aggregate_cst,
+    },
+    ast::{
+        Ast, AstMetadata, ComparisonChain, ComparisonOperator, Derivative,
+        UnhandledReason,
+    },
     cst::{
-        BinaryNode, CallBody, CallHead, CallNode, CompoundNode,
+        BinaryNode, BoxKind, BoxNode, CallBody, CallHead, CallNode,
+        CompoundNode,
         Cst::{self, Call, Compound, Group, Infix, Token},
-        GroupNode, InfixNode, OperatorNode, PrefixNode,
+        CstKind, GroupNode, InfixNode, OperatorNode, PrefixNode,
     },
-    issue::{Issue, IssueTag, Severity},
+    issue::{Issue, IssueTag, Severity, SyntaxErrors},
     macros::{leaf, src, token},
     parse::operators::{
         BinaryOperator, CallOperator, CompoundOperator, GroupOperator,
         InfixOperator, PrefixOperator,
     },
-    parse_cst, symbols as st,
+    parse_cst, parse_cst_seq,
+    source::{Source, Span},
+    symbols as st,
     tests::assert_src,
+    tokenize::TokenKind,
     NodeSeq, QuirkSettings,
 };
 
@@ -1381,6 +1391,94 @@ fn test_abstract_old_at_at_at_quirk() {
     );
 }
 
+/// TID:261016/1: LegacyPrefixMinus quirk cases
+#[test]
+fn test_abstract_legacy_prefix_minus_quirk() {
+    let agg = |input: &'static str| {
+        aggregate_cst(parse_cst(input, &Default::default()).syntax).unwrap()
+    };
+
+    let quirk =
+        |enabled: bool| QuirkSettings::default().legacy_prefix_minus(enabled);
+
+    //----------------------------------
+    // LegacyPrefixMinus Quirk: Disabled
+    //----------------------------------
+
+    assert_eq!(
+        abstract_cst(agg("-2^2"), quirk(false)),
+        Ast::Call {
+            head: Box::new(leaf!(Symbol, "Times", <||>)),
+            args: vec![
+                leaf!(Integer, "-1", <||>),
+                Ast::Call {
+                    head: Box::new(leaf!(Symbol, "Power", <||>)),
+                    args: vec![
+                        leaf!(Integer, "2", 1:2-3),
+                        leaf!(Integer, "2", 1:4-5),
+                    ],
+                    data: src!(1:2-5).into(),
+                },
+            ],
+            data: src!(1:1-5).into(),
+        }
+    );
+
+    //----------------------------------
+    // LegacyPrefixMinus Quirk: Enabled
+    //----------------------------------
+
+    assert_eq!(
+        abstract_cst(agg("-2^2"), quirk(true)),
+        Ast::Call {
+            head: Box::new(leaf!(Symbol, "Power", <||>)),
+            args: vec![leaf!(Integer, "-2", 1:1-3), leaf!(Integer, "2", 1:4-5),],
+            data: src!(1:1-5).into(),
+        }
+    );
+
+    assert_eq!(
+        abstract_cst(agg("-x^2"), quirk(true)),
+        Ast::Call {
+            head: Box::new(leaf!(Symbol, "Power", <||>)),
+            args: vec![
+                Ast::Call {
+                    head: Box::new(leaf!(Symbol, "Times", <||>)),
+                    args: vec![
+                        leaf!(Integer, "-1", <||>),
+                        leaf!(Symbol, "x", 1:2-3),
+                    ],
+                    data: src!(1:1-3).into(),
+                },
+                leaf!(Integer, "2", 1:4-5),
+            ],
+            data: src!(1:1-5).into(),
+        }
+    );
+
+    assert_eq!(
+        abstract_cst(agg("+x^2"), quirk(true)),
+        Ast::Call {
+            head: Box::new(leaf!(Symbol, "Power", <||>)),
+            args: vec![
+                Ast::Call {
+                    head: Box::new(leaf!(Symbol, "Plus", <||>)),
+                    args: vec![leaf!(Symbol, "x", 1:2-3)],
+                    data: src!(1:1-3).into(),
+                },
+                leaf!(Integer, "2", 1:4-5),
+            ],
+            data: src!(1:1-5).into(),
+        }
+    );
+
+    // Parenthesized powers are not affected.
+    assert_eq!(
+        abstract_cst(agg("-(x^2)"), quirk(true)),
+        abstract_cst(agg("-(x^2)"), quirk(false)),
+    );
+}
+
 #[test]
 fn test_abstract_plus() {
     // TID:231104/2: "+a + b - c \[ImplicitPlus] d" is a single Plus expression (?)
@@ -1448,6 +1546,153 @@ fn test_abstract_plus() {
     );
 }
 
+#[test]
+fn test_abstract_compound_expression_implicit_null() {
+    let abstract_ = |input: &str| {
+        let cst = parse_cst(input, &Default::default()).syntax;
+
+        abstract_cst(aggregate_cst(cst).unwrap(), QuirkSettings::default())
+    };
+
+    // A trailing semicolon is followed by an implicit Null, like in the
+    // kernel: `a; b;` is CompoundExpression[a, b, Null].
+    assert_eq!(
+        abstract_("a; b;"),
+        Ast::Call {
+            head: Box::new(leaf!(Symbol, "CompoundExpression", <||>)),
+            args: vec![
+                leaf!(Symbol, "a", 1:1-2),
+                leaf!(Symbol, "b", 1:4-5),
+                leaf!(
+                    Symbol,
+                    "Null",
+                    AstMetadata::implicit(Span::from(src!(1:6-6)))
+                ),
+            ],
+            data: src!(1:1-6).into(),
+        }
+    );
+
+    // An empty statement between semicolons is also an implicit Null.
+    let ast = abstract_("a; ; b");
+
+    assert_eq!(
+        ast,
+        Ast::Call {
+            head: Box::new(leaf!(Symbol, "CompoundExpression", <||>)),
+            args: vec![
+                leaf!(Symbol, "a", 1:1-2),
+                leaf!(
+                    Symbol,
+                    "Null",
+                    AstMetadata::implicit(Span::from(src!(1:4-4)))
+                ),
+                leaf!(Symbol, "b", 1:6-7),
+            ],
+            data: src!(1:1-7).into(),
+        }
+    );
+
+    // An explicit Null is not implicit.
+    let Ast::Call { args, .. } = abstract_("a; Null") else {
+        panic!("expected CompoundExpression call");
+    };
+
+    assert_eq!(
+        args.iter().map(Ast::is_implicit).collect::<Vec<_>>(),
+        [false, false]
+    );
+}
+
+#[test]
+fn test_abstract_comparison_chain() {
+    let abstract_ = |input: &str| {
+        let cst = parse_cst(input, &Default::default()).syntax;
+
+        abstract_cst(aggregate_cst(cst).unwrap(), QuirkSettings::default())
+    };
+
+    let comparison = |kind: TokenKind, src: Span| ComparisonOperator {
+        kind,
+        source: Source::Span(src),
+    };
+
+    // Mixed-direction chains are abstracted to Inequality[..], and keep the
+    // original operators.
+    let Ast::Call { head, args, data } = abstract_("a < b > c") else {
+        panic!("expected Inequality call");
+    };
+
+    assert_eq!(*head, leaf!(Symbol, "Inequality", <||>));
+    assert_eq!(args.len(), 5);
+    assert_eq!(
+        data.comparisons,
+        Some(Box::new(ComparisonChain {
+            operands: vec![
+                src!(1:1-2).into(),
+                src!(1:5-6).into(),
+                src!(1:9-10).into(),
+            ],
+            operators: vec![
+                comparison(TokenKind::Less, src!(1:3-4).into()),
+                comparison(TokenKind::Greater, src!(1:7-8).into()),
+            ],
+        }))
+    );
+
+    // Chains of the same operator are simplified, but the operators are
+    // still recorded.
+    let Ast::Call { head, data, .. } = abstract_("a <= b \\[LessEqual] c")
+    else {
+        panic!("expected LessEqual call");
+    };
+
+    assert_eq!(*head, leaf!(Symbol, "LessEqual", <||>));
+    assert_eq!(
+        data.comparisons.map(|chain| chain.operators),
+        Some(vec![
+            comparison(TokenKind::LessEqual, src!(1:3-5).into()),
+            comparison(TokenKind::LongName_LessEqual, src!(1:8-20).into()),
+        ])
+    );
+}
+
+#[test]
+fn test_abstract_derivative() {
+    let abstract_ = |input: &str| {
+        let cst = parse_cst(input, &Default::default()).syntax;
+
+        abstract_cst(aggregate_cst(cst).unwrap(), QuirkSettings::default())
+    };
+
+    let ast = abstract_("f''");
+
+    assert_eq!(
+        ast.derivative(),
+        Some(Derivative {
+            order: 2,
+            body: &leaf!(Symbol, "f", 1:1-2),
+            quotes: &Source::Span(src!(1:2-4).into()),
+        })
+    );
+
+    let ast = abstract_("g[x]'[y]");
+
+    let Ast::Call { head, .. } = &ast else {
+        panic!("expected call");
+    };
+
+    assert_eq!(head.derivative().map(|d| d.order), Some(1));
+    assert_eq!(
+        head.derivative().map(|d| d.quotes),
+        Some(&Source::Span(src!(1:5-6).into()))
+    );
+
+    // Explicit Derivative calls are not quote derivatives.
+    assert_eq!(abstract_("Derivative[1][f]").derivative(), None);
+    assert_eq!(abstract_("f[x]").derivative(), None);
+}
+
 #[test]
 fn test_abstract_box_sources() {
     //
@@ -1586,9 +1831,11 @@ fn test_abstract_call() {
                     src: src!(1:4-5).into(),
                     val: 1.0,
                     actions: vec![],
-                    additional_descriptions: vec![],
+                    annotations: vec![],
                     additional_sources: vec![src!(1:8-9).into()],
                 }],
+                implicit: false,
+                comparisons: None,
             },
         }
     );
@@ -1734,3 +1981,106 @@ fn test_abstract_call() {
         }
     );
 }
+
+#[test]
+fn test_abstract_source_map() {
+    let input = "a; (* comment *)\nf[x_, {1, 2}] := g[x]";
+
+    let cst = parse_cst_seq(input, &Default::default()).syntax;
+
+    let (ast, map) = abstract_cst_seq_with_map(cst, QuirkSettings::default());
+
+    let NodeSeq(statements) = &ast;
+    assert_eq!(statements.len(), 2);
+
+    let source = |ast: &Ast| map.cst(ast).map(|cst| cst.to_source());
+
+    // `a;` is abstracted to CompoundExpression[a, Null]; the trivia before
+    // the implicit Null is part of the concrete node.
+    let Some((_, [a, null])) = statements[0].as_symbol_call() else {
+        panic!("expected CompoundExpression: {:?}", statements[0])
+    };
+    assert_eq!(
+        source(&statements[0]).as_deref(),
+        Some("a; (* comment *)")
+    );
+    assert_eq!(source(a).as_deref(), Some("a"));
+    assert_eq!(source(null).as_deref(), Some(""));
+
+    let Some(("SetDelayed", [lhs, rhs])) = statements[1].as_symbol_call()
+    else {
+        panic!("expected SetDelayed: {:?}", statements[1])
+    };
+    assert_eq!(source(lhs).as_deref(), Some("f[x_, {1, 2}]"));
+    assert_eq!(source(rhs).as_deref(), Some("g[x]"));
+
+    let Ast::Call { head, args, .. } = lhs else {
+        panic!("expected call: {lhs:?}")
+    };
+    assert_eq!(source(head).as_deref(), Some("f"));
+    assert_eq!(source(&args[0]).as_deref(), Some("x_"));
+    assert_eq!(source(&args[1]).as_deref(), Some("{1, 2}"));
+
+    // Heads inserted by abstraction have no concrete node.
+    let Ast::Call { head, .. } = &args[1] else {
+        panic!("expected List call: {:?}", args[1])
+    };
+    assert_eq!(source(head), None);
+
+    // A single node keeps the trivia inside it.
+    let cst = parse_cst("f[ (* c *) x ]", &Default::default()).syntax;
+
+    let (ast, map) = abstract_cst_with_map(cst, QuirkSettings::default());
+
+    assert_eq!(
+        map.cst(&ast).map(|cst| cst.to_source()).as_deref(),
+        Some("f[ (* c *) x ]")
+    );
+}
+
+#[test]
+fn test_abstract_unhandled() {
+    // The file name of `>>` is always tokenized as a string.
+    let cst = Cst::Binary(BinaryNode(OperatorNode {
+        op: BinaryOperator::Put,
+        children: NodeSeq(vec![
+            Token(token!(Symbol, "a", 1:1-2)),
+            Token(token!(GreaterGreater, ">>", 1:3-5)),
+            Token(token!(Symbol, "b", 1:6-7)),
+        ]),
+    }));
+
+    let ast = abstract_cst(cst, QuirkSettings::default());
+
+    assert_eq!(
+        ast,
+        Ast::Unhandled {
+            cst_kind: CstKind::Binary(BinaryOperator::Put),
+            reason: UnhandledReason::UnexpectedChild,
+            data: AstMetadata::from(src!(1:1-7)),
+        }
+    );
+
+    // Unhandled syntax is not an error in the input.
+    assert!(!ast.is_syntax_error());
+    assert!(ast.syntax_errors().is_empty());
+
+    //==================================
+    // Boxes
+    //==================================
+
+    let cst = Cst::Box(BoxNode {
+        kind: BoxKind::RowBox,
+        children: NodeSeq(vec![Token(token!(Symbol, "a", 1:1-2))]),
+        src: Span::from(src!(1:1-2)),
+    });
+
+    assert_eq!(
+        abstract_cst(cst, QuirkSettings::default()),
+        Ast::Unhandled {
+            cst_kind: CstKind::Box(BoxKind::RowBox),
+            reason: UnhandledReason::UnsupportedBox,
+            data: AstMetadata::from(src!(1:1-2)),
+        }
+    );
+}
@@ -0,0 +1,393 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    analysis::{
+        self,
+        control_flow::{self, ArmCondition, BranchKind, ExitKind, FlowNode},
+        package::{self, PackageOutline},
+        scoping::{self, ScopeKind, SymbolTable},
+        whitespace::{self, WhitespaceEffect},
+        EditorData, FoldingKind, FoldingRange, OutlineKind, SemanticTokenKind,
+    },
+    issue::{Issue, SyntaxErrors},
+    macros::src,
+    parse_ast, parse_ast_seq, parse_cst_seq,
+    source::{Source, Span},
+    ParseOptions,
+};
+
+fn editor_data(input: &str) -> EditorData {
+    let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+
+    analysis::editor_data(&cst)
+}
+
+#[test]
+fn test_outline() {
+    let data = editor_data(
+        r#"f[x_] := x; g = 2;
+HoldPattern[h[x_]] /; x > 0 := x
+k[a][b] = 1
+t /: u[t] := 1
+f::usage = "f[x]"
+Print[f]
+"#,
+    );
+
+    let outline: Vec<(&str, OutlineKind)> = data
+        .outline
+        .iter()
+        .map(|item| (item.name.as_str(), item.kind))
+        .collect();
+
+    assert_eq!(
+        outline,
+        [
+            ("f", OutlineKind::Function),
+            ("g", OutlineKind::Variable),
+            ("h", OutlineKind::Function),
+            ("k", OutlineKind::Function),
+            ("t", OutlineKind::Function),
+        ]
+    );
+
+    assert_eq!(data.outline[0].span, Span::from(src!(1:1-1:11)));
+    assert_eq!(data.outline[0].name_span, Span::from(src!(1:1-1:2)));
+    assert_eq!(data.outline[2].name_span, Span::from(src!(2:13-2:14)));
+}
+
+#[test]
+fn test_semantic_tokens() {
+    let data = editor_data("(* c *) f[x_, y : _] + \"s\" 1.5");
+
+    let kinds: Vec<SemanticTokenKind> = data
+        .semantic_tokens
+        .iter()
+        .map(|token| token.kind)
+        .collect();
+
+    use SemanticTokenKind::*;
+
+    assert_eq!(
+        kinds,
+        [
+            Comment, Function, Operator, Parameter, Operator, Operator,
+            Parameter, Operator, Operator, Operator, Operator, String, Number,
+        ]
+    );
+
+    assert_eq!(data.semantic_tokens[1].span, Span::from(src!(1:9-1:10)));
+}
+
+#[test]
+fn test_folding_ranges() {
+    let data = editor_data("(* a\n b *)\nf[\n  {1, 2}\n]\n{1,\n 2}");
+
+    assert_eq!(
+        data.folding_ranges,
+        [
+            FoldingRange {
+                span: Span::from(src!(1:1-2:6)),
+                kind: FoldingKind::Comment,
+            },
+            FoldingRange {
+                span: Span::from(src!(3:2-5:2)),
+                kind: FoldingKind::Region,
+            },
+            FoldingRange {
+                span: Span::from(src!(6:1-7:4)),
+                kind: FoldingKind::Region,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_diagnostics() {
+    let input = "f[a, {b + ]\ng[";
+
+    let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+
+    let data = analysis::editor_data(&cst);
+
+    let msgs = |issues: &[Issue]| -> Vec<String> {
+        issues.iter().map(|issue| issue.msg.clone()).collect()
+    };
+
+    assert!(!data.diagnostics.is_empty());
+    assert_eq!(msgs(&data.diagnostics), msgs(&cst.syntax_errors()));
+}
+
+fn package_outline(input: &str) -> PackageOutline {
+    let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+
+    package::package_outline(&ast)
+}
+
+#[test]
+fn test_package_outline() {
+    let outline = package_outline(
+        r#"BeginPackage["Pkg`", {"Dep`"}];
+f::usage = "f[x] does " <> "something.";
+{g, h}
+Begin["`Private`"];
+g::usage = "g[x]";
+f[x_] := x
+Begin["Other`"]
+End[]
+End[];
+EndPackage[]
+Begin["`Unclosed`"]
+"#,
+    );
+
+    assert_eq!(outline.context.as_deref(), Some("Pkg`"));
+
+    let exports: Vec<_> = outline
+        .exported_symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.usage.as_deref()))
+        .collect();
+
+    assert_eq!(
+        exports,
+        [
+            ("f", Some("f[x] does something.")),
+            ("g", Some("g[x]")),
+            ("h", None),
+        ]
+    );
+
+    assert_eq!(
+        outline.exported_symbols[0].src,
+        Source::from(src!(2:1-2:40))
+    );
+
+    let sections: Vec<_> = outline
+        .sections
+        .iter()
+        .map(|section| (section.context.as_str(), section.end.is_some()))
+        .collect();
+
+    assert_eq!(
+        sections,
+        [
+            ("Pkg`Private`", true),
+            ("Other`", true),
+            ("Global`Unclosed`", false),
+        ]
+    );
+
+    assert_eq!(outline.sections[0].end, Some(Source::from(src!(9:1-9:6))));
+
+    // Not a package.
+    let outline = package_outline("f::usage = \"f\"\nf");
+
+    assert_eq!(outline, PackageOutline::default());
+}
+
+#[test]
+fn test_scoping() {
+    let input = "f[x_, x_, y_] := With[{x = x}, Function[{y}, x + y]] /; y > 0
+g[a_] := a /. {a -> 1, b_ :> a + b}
+h = Module[{m}, #m &]";
+
+    let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+
+    let table = scoping::symbol_table_seq(&ast);
+
+    let kinds: Vec<_> = table.scopes.iter().map(|scope| scope.kind).collect();
+
+    assert_eq!(
+        kinds,
+        [
+            ScopeKind::Pattern,
+            ScopeKind::With,
+            ScopeKind::Function,
+            ScopeKind::Pattern,
+            ScopeKind::Pattern,
+            ScopeKind::Module,
+        ]
+    );
+
+    assert_eq!(table.scopes[1].parent, Some(0));
+    assert_eq!(table.scopes[2].parent, Some(1));
+    assert_eq!(table.scopes[4].parent, Some(3));
+    assert_eq!(table.scopes[5].parent, None);
+
+    let vars: Vec<_> = table
+        .variables
+        .iter()
+        .map(|var| (var.name.as_str(), var.scope, var.usages.len()))
+        .collect();
+
+    assert_eq!(
+        vars,
+        [
+            // The second `x_`, the initial value of `x`, and `y > 0`.
+            ("x", 0, 2),
+            ("y", 0, 1),
+            ("x", 1, 1),
+            ("y", 2, 1),
+            // `a /. ..`, and `a -> 1`, which doesn't bind `a`.
+            ("a", 3, 3),
+            ("b", 4, 1),
+            ("m", 5, 0),
+        ]
+    );
+
+    assert_eq!(table.variables[0].definition, Source::from(src!(1:3-1:4)));
+    assert_eq!(table.variables[0].usages[0], Source::from(src!(1:7-1:8)));
+
+    let var = table.variable_at(&Source::from(src!(1:7-1:8))).unwrap();
+    assert_eq!(var.definition, table.variables[0].definition);
+
+    let unused: Vec<_> = table.unused().map(|var| var.name.as_str()).collect();
+    assert_eq!(unused, ["m"]);
+
+    // A rule without patterns, and a Function with slots, bind nothing.
+    let ast = parse_ast("x -> Function[x + 1]", &ParseOptions::default());
+    assert_eq!(scoping::symbol_table(&ast.syntax), SymbolTable::default());
+}
+
+#[test]
+fn test_control_flow() {
+    let input =
+        "f[x_] /; x > 0 := Switch[x, 1, Throw[x], _, If[x > 1, 2, 3, 4]]
+t /: u[t] := Module[{y}, If[g[x], Return[y]]]; v = 1
+Print[If[a, b]]";
+
+    let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+
+    let defs = control_flow::control_flow(&ast);
+
+    let names: Vec<_> = defs.iter().map(|def| def.name.as_deref()).collect();
+    assert_eq!(names, [Some("f"), Some("t"), Some("v")]);
+
+    let complexities: Vec<_> =
+        defs.iter().map(|def| def.complexity()).collect();
+    assert_eq!(complexities, [4, 2, 1]);
+
+    //
+    // Switch[x, 1, Throw[x], _, If[..]]
+    //
+
+    let [FlowNode::Branch(switch)] = defs[0].flow.as_slice() else {
+        panic!("{:?}", defs[0].flow)
+    };
+
+    assert_eq!(switch.kind, BranchKind::Switch);
+    assert_eq!(switch.subject, Some(Source::from(src!(1:26-1:27))));
+    assert_eq!(
+        switch.arms[0].condition,
+        ArmCondition::Matches(Source::from(src!(1:29-1:30)))
+    );
+    assert_eq!(switch.arms[0].body, Source::from(src!(1:32-1:40)));
+    assert_eq!(
+        switch.arms[0].flow,
+        [FlowNode::Exit(control_flow::Exit {
+            kind: ExitKind::Throw,
+            src: Source::from(src!(1:32-1:40)),
+        })]
+    );
+
+    let [FlowNode::Branch(if_)] = switch.arms[1].flow.as_slice() else {
+        panic!("{:?}", switch.arms[1].flow)
+    };
+
+    let conditions: Vec<_> =
+        if_.arms.iter().map(|arm| arm.condition.clone()).collect();
+    assert_eq!(
+        conditions,
+        [
+            ArmCondition::True(Source::from(src!(1:48-1:53))),
+            ArmCondition::Else,
+            ArmCondition::Neither,
+        ]
+    );
+
+    //
+    // Module[{y}, If[g[x], Return[y]]]
+    //
+
+    let [FlowNode::Branch(if_)] = defs[1].flow.as_slice() else {
+        panic!("{:?}", defs[1].flow)
+    };
+
+    assert_eq!(if_.kind, BranchKind::If);
+    assert_eq!(if_.subject, None);
+    assert!(matches!(
+        if_.arms[0].flow.as_slice(),
+        [FlowNode::Exit(control_flow::Exit {
+            kind: ExitKind::Return,
+            ..
+        })]
+    ));
+
+    assert!(defs[2].flow.is_empty());
+}
+
+#[test]
+fn test_significant_whitespace() {
+    use WhitespaceEffect::*;
+
+    let found = |input: &str| -> Vec<(WhitespaceEffect, Span)> {
+        let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+
+        whitespace::significant_whitespace(&cst)
+            .iter()
+            .map(|found| (found.effect, found.span))
+            .collect()
+    };
+
+    // `2x` is also `2*x`, so the whitespace in `2 x` doesn't matter.
+    assert_eq!(
+        found("a b + 2 x + x 2"),
+        [
+            (ImplicitTimes, Span::from(src!(1:2-1:3))),
+            (ImplicitTimes, Span::from(src!(1:14-1:15))),
+        ]
+    );
+
+    // Any line break inside of brackets that makes a multiplication.
+    assert_eq!(
+        found("{a\n(b), 1\n2}"),
+        [
+            (ImplicitTimes, Span::from(src!(1:3-2:1))),
+            (ImplicitTimes, Span::from(src!(2:7-3:1))),
+        ]
+    );
+
+    assert_eq!(found("x - -y"), [(Separation, Span::from(src!(1:4-1:5)))]);
+
+    assert_eq!(
+        found("{1 -2, x +y, 3 - 4, 5-6}"),
+        [
+            (PrefixSpacing, Span::from(src!(1:4-1:5))),
+            (PrefixSpacing, Span::from(src!(1:10-1:11))),
+        ]
+    );
+
+    // A line that starts with `;` already ends the previous expression, and
+    // lines that don't start with a sign are not reported.
+    assert_eq!(
+        found("total = a\n- b\nc;\n-d\ne"),
+        [(NewlineBreak, Span::from(src!(1:10-2:1)))]
+    );
+
+    assert_eq!(
+        found("x = .; f /: f[x] = .; y =."),
+        [
+            (UnsetSpacing, Span::from(src!(1:3-1:6))),
+            (UnsetSpacing, Span::from(src!(1:18-1:21))),
+        ]
+    );
+
+    let cst = parse_cst_seq("a b", &ParseOptions::default()).syntax;
+
+    assert_eq!(
+        whitespace::significant_whitespace(&cst)[0].explanation,
+        "`a` and `b` are multiplied. Without whitespace between them, `ab` \
+         would be read as one token."
+    );
+}
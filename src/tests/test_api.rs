@@ -1,9 +1,14 @@
 use crate::{
-    issue::{CodeAction, Issue, IssueTag, Severity},
-    macros::src,
+    ast::{Ast, AstMetadata, ChainLinkKind, SymbolParts},
+    directive::{Directive, DirectiveKind},
+    issue::{
+        Annotation, AnnotationKind, CodeAction, Issue, IssueTag, Severity,
+    },
+    macros::{leaf, src},
     parse_cst,
     source::{Source, Span},
-    symbols as sym, ParseOptions, SourceConvention, StringifyMode,
+    symbols as sym, DebugPhase, GrammarProfile, NodeSeq, ParseOptions,
+    SourceConvention, StringifyMode, ToplevelNewlineMode,
 };
 
 use pretty_assertions::assert_eq;
@@ -99,7 +104,7 @@ fn APITest_Crash4() {
                 Span::from(src!(1:3-3)),
                 " ".into(),
             )],
-            additional_descriptions: vec![],
+            annotations: vec![],
             additional_sources: vec![],
         }]
     );
@@ -170,3 +175,980 @@ fn APITest_Crash8() {
     assert_eq!(result.non_fatal_issues, Vec::new());
     assert_eq!(result.fatal_issues, Vec::new());
 }
+
+#[test]
+fn test_disable_implicit_times() {
+    let opts = ParseOptions::default().implicit_times(false);
+
+    let implicit_times_issue = |span: Span| Issue {
+        make_sym: sym::CodeParser_SyntaxIssue,
+        tag: IssueTag::UnexpectedImplicitTimes,
+        msg: "Implicit multiplication is disabled.".to_owned(),
+        sev: Severity::Fatal,
+        src: Source::Span(span),
+        val: 1.0,
+        actions: vec![CodeAction::insert_text(
+            "Insert ``*``".into(),
+            span,
+            "*".into(),
+        )],
+        annotations: vec![],
+        additional_sources: vec![],
+    };
+
+    // Enabled by default
+    let result = parse_cst("a b", &ParseOptions::default());
+    assert_eq!(result.fatal_issues, Vec::new());
+
+    let result = parse_cst("a b", &opts);
+    assert_eq!(
+        result.fatal_issues,
+        vec![implicit_times_issue(Span::from(src!(1:3-3)))]
+    );
+
+    // Every implicit multiplication is reported, including inside groups and
+    // across newlines.
+    let result = parse_cst("{a b c,\nd\ne}", &opts);
+    assert_eq!(
+        result.fatal_issues,
+        vec![
+            implicit_times_issue(Span::from(src!(1:4-4))),
+            implicit_times_issue(Span::from(src!(1:6-6))),
+            implicit_times_issue(Span::from(src!(3:1-1))),
+        ]
+    );
+
+    // Explicit multiplication and toplevel newlines are not affected.
+    let result = crate::parse_cst_seq("a * b \\[Times] c\nd", &opts);
+    assert_eq!(result.fatal_issues, Vec::new());
+}
+
+#[test]
+fn test_toplevel_newline_mode() {
+    let sources = |input: &str, opts: &ParseOptions| -> Vec<Source> {
+        let NodeSeq(nodes) = crate::parse_ast_seq(input, opts).syntax;
+
+        nodes.iter().map(|node| node.source().clone()).collect()
+    };
+
+    let repl = ParseOptions::default()
+        .toplevel_newlines(ToplevelNewlineMode::ContinueIfOperatorPending);
+
+    // A trailing `;` ends the expression by default.
+    assert_eq!(
+        sources("a;\nb", &ParseOptions::default()),
+        [Source::from(src!(1:1-1:3)), Source::from(src!(2:1-2:2))]
+    );
+
+    assert_eq!(sources("a;\nb", &repl), [Source::from(src!(1:1-2:2))]);
+
+    // Only the pending operator continues across newlines: `b;;` continues,
+    // but `c` is complete.
+    assert_eq!(
+        sources("a;\n\nb;;\nc\nd", &repl),
+        [Source::from(src!(1:1-4:2)), Source::from(src!(5:1-5:2))]
+    );
+
+    // An implicit operand, if nothing follows.
+    assert_eq!(sources("a;\n", &repl), [Source::from(src!(1:1-1:3))]);
+    assert_eq!(sources("a;;\n", &repl), [Source::from(src!(1:1-1:4))]);
+
+    // Not an operand.
+    assert_eq!(
+        sources("a;\n;", &repl),
+        [Source::from(src!(1:1-1:3)), Source::from(src!(2:1-2:2))]
+    );
+
+    // Complete expressions and other operators are not affected.
+    assert_eq!(
+        sources("a\nb", &repl),
+        [Source::from(src!(1:1-1:2)), Source::from(src!(2:1-2:2))]
+    );
+    assert_eq!(sources("a +\nb", &repl), [Source::from(src!(1:1-2:2))]);
+}
+
+#[test]
+fn test_data_grammar_profile() {
+    let opts = ParseOptions::default().grammar_profile(GrammarProfile::Data);
+
+    let disallowed = |msg: &str, span: Span| Issue {
+        make_sym: sym::CodeParser_SyntaxIssue,
+        tag: IssueTag::DisallowedSyntax,
+        msg: msg.to_owned(),
+        sev: Severity::Fatal,
+        src: Source::Span(span),
+        val: 1.0,
+        actions: vec![],
+        annotations: vec![],
+        additional_sources: vec![],
+    };
+
+    let result = crate::parse_cst_seq(
+        "{1, -2, 3.5`, \"s\", True, (* comment *) {}}\n\
+         <|\"a\" -> x, b \\[RuleDelayed] {c}|>",
+        &opts,
+    );
+    assert_eq!(result.fatal_issues, Vec::new());
+
+    let result = crate::parse_cst_seq("{f[x], 1 + 2, -x}\nRun[\"cmd\"]", &opts);
+    assert_eq!(
+        result.fatal_issues,
+        vec![
+            disallowed(
+                "Function call is not allowed in data-only input.",
+                Span::from(src!(1:2-6))
+            ),
+            disallowed(
+                "Operator `Plus` is not allowed in data-only input.",
+                Span::from(src!(1:8-13))
+            ),
+            disallowed(
+                "Operator `Minus` is not allowed in data-only input.",
+                Span::from(src!(1:15-17))
+            ),
+            disallowed(
+                "Function call is not allowed in data-only input.",
+                Span::from(src!(2:1-11))
+            ),
+        ]
+    );
+
+    // Not restricted by default
+    let result =
+        crate::parse_cst_seq("{f[x], 1 + 2}", &ParseOptions::default());
+    assert_eq!(result.fatal_issues, Vec::new());
+}
+
+#[test]
+fn test_primary_error() {
+    let result = parse_cst("f[x]", &ParseOptions::default());
+    assert_eq!(result.primary_error(), None);
+    assert_eq!(result.error_summary(), None);
+
+    let result = parse_cst("f[x, y +]", &ParseOptions::default());
+    assert_eq!(
+        result.primary_error_span(),
+        Some(Source::Span(Span::from(src!(1:9-9))))
+    );
+    assert_eq!(
+        result.error_summary().as_deref(),
+        Some("1:9: Expected an operand.")
+    );
+
+    // Errors that appear earlier are preferred.
+    let result = parse_cst(
+        "f[x y, z +]",
+        &ParseOptions::default().implicit_times(false),
+    );
+    assert_eq!(
+        result.error_summary().as_deref(),
+        Some("1:5: Implicit multiplication is disabled.")
+    );
+
+    let result = crate::parse_ast("{1, 2", &ParseOptions::default());
+    let error = result.primary_error().unwrap();
+    assert_eq!(error.tag, IssueTag::SyntaxError);
+    assert_eq!(error.src, Source::Span(Span::from(src!(1:1-6))));
+    assert_eq!(
+        result.error_summary().as_deref(),
+        Some("1:1: Missing closer.")
+    );
+}
+
+#[test]
+fn test_comment_directives() {
+    let result = crate::parse_cst_seq(
+        "(* wolfram-parse: indent=4, =x *)\n\
+         f[(*@ a @*) x, (* other *) y]\n\
+         (*@ b @*)",
+        &ParseOptions::default(),
+    );
+
+    assert_eq!(
+        result.directives,
+        vec![
+            Directive {
+                kind: DirectiveKind::Setting {
+                    key: "indent".to_owned(),
+                    value: Some("4".to_owned()),
+                },
+                src: Span::from(src!(1:1-34)),
+            },
+            Directive {
+                kind: DirectiveKind::Annotation("a".to_owned()),
+                src: Span::from(src!(2:3-12)),
+            },
+            Directive {
+                kind: DirectiveKind::Annotation("b".to_owned()),
+                src: Span::from(src!(3:1-10)),
+            },
+        ]
+    );
+
+    assert_eq!(
+        result.non_fatal_issues,
+        vec![Issue {
+            make_sym: sym::CodeParser_SyntaxIssue,
+            tag: IssueTag::InvalidDirective,
+            msg: "Invalid ``wolfram-parse`` setting: ``=x``.".to_owned(),
+            sev: Severity::Warning,
+            src: Source::Span(Span::from(src!(1:1-34))),
+            val: 0.95,
+            actions: vec![],
+            annotations: vec![],
+            additional_sources: vec![],
+        }]
+    );
+}
+
+#[test]
+fn test_call_chain() {
+    let ast =
+        crate::parse_ast("f[x][y, z][[2]]", &ParseOptions::default()).syntax;
+
+    let chain = ast.call_chain();
+
+    assert_eq!(chain.root.as_symbol(), Some("f"));
+
+    let links: Vec<_> = chain
+        .links
+        .iter()
+        .map(|link| (link.kind, link.args.len(), link.src.clone()))
+        .collect();
+
+    assert_eq!(
+        links,
+        vec![
+            (ChainLinkKind::Call, 1, Source::from(src!(1:1-5))),
+            (ChainLinkKind::Call, 2, Source::from(src!(1:1-11))),
+            (ChainLinkKind::Part, 1, Source::from(src!(1:1-16))),
+        ]
+    );
+
+    // Explicit `Part[..]` calls are not parts
+    let ast = crate::parse_ast("Part[x, 1]", &ParseOptions::default()).syntax;
+    let chain = ast.call_chain();
+    assert_eq!(chain.root.as_symbol(), Some("Part"));
+    assert_eq!(chain.links.len(), 1);
+    assert_eq!(chain.links[0].kind, ChainLinkKind::Call);
+
+    // Incomplete calls
+    let ast = crate::parse_ast("g[a][b", &ParseOptions::default()).syntax;
+    let chain = ast.call_chain();
+    assert_eq!(chain.root.as_symbol(), Some("g"));
+    assert_eq!(
+        chain
+            .links
+            .iter()
+            .map(|link| link.missing_closer)
+            .collect::<Vec<_>>(),
+        vec![false, true]
+    );
+
+    // Not a call
+    let ast = crate::parse_ast("a + b", &ParseOptions::default()).syntax;
+    let chain = ast.call_chain();
+    assert_eq!(chain.root, &ast);
+    assert!(chain.links.is_empty());
+}
+
+#[test]
+fn test_symbol_parts() {
+    let ast =
+        |input: &str| crate::parse_ast(input, &ParseOptions::default()).syntax;
+
+    assert_eq!(
+        ast("x").symbol_parts(),
+        Some(SymbolParts {
+            relative: false,
+            context: vec![],
+            name: "x",
+        })
+    );
+    assert_eq!(
+        ast("Global`x").symbol_parts(),
+        Some(SymbolParts {
+            relative: false,
+            context: vec!["Global"],
+            name: "x",
+        })
+    );
+    assert_eq!(
+        ast("`Private`Util`helper").symbol_parts(),
+        Some(SymbolParts {
+            relative: true,
+            context: vec!["Private", "Util"],
+            name: "helper",
+        })
+    );
+    assert_eq!(ast("f[x]").symbol_parts(), None);
+    assert_eq!(ast("\"x`y\"").symbol_parts(), None);
+
+    let context =
+        |input: &str| ast(input).symbol_parts().unwrap().context_str();
+
+    assert_eq!(context("x"), None);
+    assert_eq!(context("A`B`c").as_deref(), Some("A`B`"));
+    assert_eq!(context("`c").as_deref(), Some("`"));
+}
+
+#[test]
+fn test_parse_cst_simple() {
+    let profiles = [GrammarProfile::Full, GrammarProfile::Data];
+
+    for opts in profiles.map(|p| ParseOptions::default().grammar_profile(p)) {
+        for input in [
+            "a + b",
+            "{a -> f[x]}",
+            "1+\\[Alpha+2",
+            "\"a\\q\"",
+            "f[a, 1.2.3, ]",
+        ] {
+            let result = parse_cst(input, &opts);
+
+            let (cst, issues) = crate::parse_cst_simple(input, &opts);
+
+            assert_eq!(cst, result.syntax, "input: {input:?}");
+            assert_eq!(
+                issues,
+                [result.fatal_issues, result.non_fatal_issues].concat(),
+                "input: {input:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_parse_error_conversions() {
+    use crate::{ParseError, UnsafeCharacterEncoding};
+
+    fn single(input: &str) -> Result<crate::ast::Ast, ParseError> {
+        let seq = crate::parse_ast_seq(input, &ParseOptions::default())
+            .into_result()?;
+
+        Ok(seq.try_single()?)
+    }
+
+    assert!(single("f[x]").is_ok());
+
+    let err = single("\\[Foo]").unwrap_err();
+    assert!(matches!(err, ParseError::Issues(_)));
+    assert!(!err.issues().is_empty());
+
+    let err = single("a\nb").unwrap_err();
+    assert!(matches!(err, ParseError::NodeSeq(_)));
+    assert!(err.issues().is_empty());
+
+    let err = ParseError::from(UnsafeCharacterEncoding::StraySurrogate);
+    assert_eq!(
+        err.to_string(),
+        "Unsafe character encoding: stray surrogate"
+    );
+
+    fn expr(input: &str) -> Result<wolfram_expr::Expr, ParseError> {
+        Ok(single(input)?.to_expr()?)
+    }
+
+    assert!(expr("f[x]").is_ok());
+    assert!(matches!(
+        expr("99999999999999999999"),
+        Err(ParseError::ToExpr(_))
+    ));
+
+    let err = ParseError::from(crate::testing::StabilityError::SyntaxChanged {
+        formatted: String::new(),
+    });
+    assert_eq!(
+        err.to_string(),
+        "Format stability error: formatting changed the syntax of the input"
+    );
+}
+
+#[test]
+fn test_try_syntax() {
+    let severities = [
+        Severity::Formatting,
+        Severity::Remark,
+        Severity::Warning,
+        Severity::Error,
+        Severity::Fatal,
+    ];
+
+    for input in ["a + b", "f[", "a\tb", "1+\\[Alpha+2", "a /: b"] {
+        for max_severity in severities {
+            let result = parse_cst(input, &ParseOptions::default());
+
+            let expected: Vec<Issue> = result
+                .issues()
+                .filter(|issue| issue.sev > max_severity)
+                .cloned()
+                .collect();
+
+            match result.try_syntax(max_severity) {
+                Ok(_) => assert!(expected.is_empty(), "input: {input:?}"),
+                Err(err) => {
+                    assert!(matches!(err, crate::ParseError::Issues(_)));
+                    assert!(!err.issues().is_empty(), "input: {input:?}");
+                    assert_eq!(err.issues(), expected.as_slice(), "{input:?}");
+                },
+            }
+        }
+    }
+
+    assert!(parse_cst("\\[Foo]", &ParseOptions::default()).ok().is_none());
+    assert!(parse_cst("f[x]", &ParseOptions::default()).ok().is_some());
+}
+
+#[test]
+fn test_deny_issues() {
+    let input = "1.2.3";
+
+    let result = parse_cst(input, &ParseOptions::default());
+    assert!(result.is_ok());
+    assert!(result
+        .warnings()
+        .iter()
+        .any(|issue| issue.tag == IssueTag::UnexpectedImplicitTimes));
+
+    let opts =
+        ParseOptions::default().deny(&[IssueTag::UnexpectedImplicitTimes]);
+    let result = parse_cst(input, &opts);
+
+    assert!(!result.is_ok());
+    assert!(result.fatal_issues().iter().all(|issue| {
+        issue.tag == IssueTag::UnexpectedImplicitTimes
+            && issue.sev == Severity::Fatal
+    }));
+    assert!(result
+        .warnings()
+        .iter()
+        .all(|issue| issue.tag != IssueTag::UnexpectedImplicitTimes));
+
+    // Denying tags that are not reported has no effect.
+    let opts = ParseOptions::default().deny(&[IssueTag::UnrecognizedLongName]);
+    assert!(parse_cst(input, &opts).is_ok());
+}
+
+#[test]
+fn test_style_checks() {
+    fn style_issues(
+        input: &str,
+        opts: &ParseOptions,
+    ) -> Vec<(IssueTag, Source)> {
+        crate::parse_cst_seq(input, opts)
+            .issues()
+            .map(|issue| (issue.tag, issue.src.clone()))
+            .collect()
+    }
+
+    let input = "f[x]\ng[xxxxxxxxxx]\n";
+
+    assert_eq!(style_issues(input, &ParseOptions::default()), vec![]);
+
+    let opts = ParseOptions::default().max_line_length(Some(10));
+
+    assert_eq!(
+        style_issues(input, &opts),
+        vec![(IssueTag::LineTooLong, Source::from(src!(2:11-2:14)))]
+    );
+
+    // Tabs advance to the next tab stop, and the last line is checked even
+    // without a trailing newline.
+    assert_eq!(
+        style_issues("\tf[x, y]", &opts),
+        vec![(IssueTag::LineTooLong, Source::from(src!(1:11-1:12)))]
+    );
+
+    //
+    // Mixed indentation
+    //
+
+    let input = "f[\n\tx,\n  y,\n\t z,\n\n  \n\tw\n]";
+
+    assert_eq!(style_issues(input, &ParseOptions::default()), vec![]);
+
+    let opts = ParseOptions::default().check_mixed_indentation(true);
+
+    assert_eq!(
+        style_issues(input, &opts),
+        vec![
+            (IssueTag::MixedIndentation, Source::from(src!(3:1-3:3))),
+            (IssueTag::MixedIndentation, Source::from(src!(4:1-4:6))),
+        ]
+    );
+}
+
+#[test]
+fn test_comma_issues() {
+    fn comma_issues(input: &str) -> Vec<(IssueTag, Source, Vec<CodeAction>)> {
+        parse_cst(input, &ParseOptions::default())
+            .issues()
+            .map(|issue| (issue.tag, issue.src.clone(), issue.actions.clone()))
+            .collect()
+    }
+
+    assert_eq!(
+        comma_issues("f[a, ]"),
+        vec![(
+            IssueTag::TrailingComma,
+            Source::from(src!(1:4-1:5)),
+            vec![
+                CodeAction::delete_text(
+                    "Delete ``,``".to_owned(),
+                    Span::from(src!(1:4-1:5))
+                ),
+                CodeAction::insert_text(
+                    "Insert ``Null``".to_owned(),
+                    Span::from(src!(1:6-1:6)),
+                    "Null".to_owned()
+                ),
+            ]
+        )]
+    );
+
+    assert_eq!(
+        comma_issues("{1,,2}"),
+        vec![(
+            IssueTag::EmptyArgument,
+            Source::from(src!(1:3-1:5)),
+            vec![CodeAction::insert_text(
+                "Insert ``Null``".to_owned(),
+                Span::from(src!(1:4-1:4)),
+                "Null".to_owned()
+            )]
+        )]
+    );
+
+    // A leading empty argument has no comma before it.
+    assert_eq!(
+        comma_issues("f[,a]")
+            .into_iter()
+            .map(|(tag, src, _)| (tag, src))
+            .collect::<Vec<_>>(),
+        vec![(IssueTag::EmptyArgument, Source::from(src!(1:3-1:4)))]
+    );
+
+    assert_eq!(comma_issues("f[a, b]"), vec![]);
+
+    let result = parse_cst("f[a, ]", &ParseOptions::default());
+    let issue = result.issues().next().unwrap();
+
+    assert_eq!(
+        issue.annotations,
+        vec![Annotation::explanation(
+            "The argument after ``,`` is ``Null``.".to_owned(),
+            Source::from(src!(1:6-1:6))
+        )]
+    );
+    assert_eq!(issue.annotations[0].kind, AnnotationKind::Explanation);
+
+    // Comma issues are not fatal unless denied.
+    assert!(parse_cst("f[a,]", &ParseOptions::default()).is_ok());
+
+    let opts = ParseOptions::default().deny(&[IssueTag::TrailingComma]);
+
+    assert!(!parse_cst("f[a,]", &opts).is_ok());
+    assert!(parse_cst("{1,,2}", &opts).is_ok());
+}
+
+#[test]
+fn test_tab_width_auto() {
+    let last_token_src = |input: &str, opts: &ParseOptions| {
+        crate::tokenize(input, opts).0.last().unwrap().src
+    };
+
+    let input = "(* wolfram-parse: tab-width=2 *)\n\tx";
+
+    // The directive is ignored unless requested.
+    assert_eq!(
+        last_token_src(input, &ParseOptions::default()),
+        Span::from(src!(2:5-2:6))
+    );
+    assert_eq!(
+        last_token_src(input, &ParseOptions::default().tab_width_auto()),
+        Span::from(src!(2:3-2:4))
+    );
+
+    // Without a directive, the configured tab width is used.
+    assert_eq!(
+        last_token_src(
+            "(* no directive *)\n\tx",
+            &ParseOptions::default().tab_width(8).tab_width_auto()
+        ),
+        Span::from(src!(2:9-2:10))
+    );
+
+    // Spans of the abstract syntax use the same tab width.
+    let ast = crate::parse_ast(
+        "(* wolfram-parse: tab_width=8 *)\n\tx",
+        &ParseOptions::default().tab_width_auto(),
+    );
+
+    assert_eq!(ast.syntax.source(), &Source::from(src!(2:9-2:10)));
+}
+
+#[test]
+fn test_summarize_issues() {
+    use crate::{
+        issue::{summarize, IssueSummary, Region},
+        source::LineColumn,
+    };
+    use std::num::NonZeroU32;
+
+    const ONE: NonZeroU32 = NonZeroU32::MIN;
+
+    let issue = |tag: IssueTag, sev: Severity, line: u32| {
+        Issue::syntax(
+            tag,
+            String::new(),
+            sev,
+            Source::from(crate::source::LineColumnSpan {
+                start: LineColumn(NonZeroU32::new(line).unwrap(), ONE),
+                end: LineColumn(NonZeroU32::new(line).unwrap(), ONE),
+            }),
+            1.0,
+        )
+    };
+
+    let issues = [
+        issue(IssueTag::SyntaxError, Severity::Fatal, 1),
+        issue(IssueTag::UnexpectedDot, Severity::Warning, 150),
+        issue(IssueTag::UnexpectedDot, Severity::Warning, 160),
+        issue(IssueTag::Ambiguous, Severity::Remark, 2),
+    ];
+
+    let summary = summarize(&issues);
+
+    assert_eq!(
+        summary,
+        IssueSummary {
+            total: 4,
+            by_tag: vec![
+                (IssueTag::UnexpectedDot, 2),
+                (IssueTag::Ambiguous, 1),
+                (IssueTag::SyntaxError, 1),
+            ],
+            by_severity: vec![
+                (Severity::Warning, 2),
+                (Severity::Remark, 1),
+                (Severity::Fatal, 1),
+            ],
+            by_region: vec![
+                (
+                    Region {
+                        start_line: 1,
+                        end_line: 100
+                    },
+                    2
+                ),
+                (
+                    Region {
+                        start_line: 101,
+                        end_line: 200
+                    },
+                    2
+                ),
+            ],
+        }
+    );
+
+    assert_eq!(summary.count(Severity::Warning), 2);
+    assert_eq!(summary.count(Severity::Error), 0);
+
+    assert_eq!(
+        summary.to_string(),
+        "4 issues (1 Fatal, 2 Warning, 1 Remark); most common: \
+         UnexpectedDot (2), Ambiguous (1), SyntaxError (1)"
+    );
+
+    assert_eq!(summarize(&[]).to_string(), "0 issues");
+}
+
+#[test]
+fn test_truncate() {
+    let ast =
+        crate::parse_ast("f[{1, 2, 3}, g[h[x]], y]", &ParseOptions::default())
+            .syntax;
+
+    let elided = |subtrees, nodes, data: AstMetadata| Ast::Elided {
+        subtrees,
+        nodes,
+        data,
+    };
+
+    // Breadth-first: f[...], f, {...}, and g[...] are kept.
+    assert_eq!(
+        ast.truncate(4),
+        Ast::Call {
+            head: Box::new(leaf!(Symbol, "f", 1:1-2)),
+            args: vec![
+                Ast::Call {
+                    head: Box::new(elided(1, 1, AstMetadata::empty())),
+                    args: vec![elided(3, 3, src!(1:4-11).into())],
+                    data: src!(1:3-12).into(),
+                },
+                Ast::Call {
+                    head: Box::new(elided(1, 1, src!(1:14-15).into())),
+                    args: vec![elided(1, 3, src!(1:16-20).into())],
+                    data: src!(1:14-21).into(),
+                },
+                elided(1, 1, src!(1:23-24).into()),
+            ],
+            data: src!(1:1-25).into(),
+        }
+    );
+
+    // The root is always kept.
+    let Ast::Call { head, args, .. } = ast.truncate(0) else {
+        panic!("expected call");
+    };
+    assert_eq!(*head, elided(1, 1, src!(1:1-2).into()));
+    assert_eq!(args, vec![elided(3, 11, src!(1:3-24).into())]);
+
+    // Nothing is elided from a tree within the limit.
+    assert_eq!(ast.truncate(100), ast);
+}
+
+#[test]
+fn test_debug_sink() {
+    use std::sync::{Arc, Mutex};
+
+    let snapshots = Arc::new(Mutex::new(Vec::new()));
+
+    let opts = ParseOptions::default().debug_sink({
+        let snapshots = Arc::clone(&snapshots);
+        move |phase, snapshot| {
+            let snapshot = format!("{snapshot:?}");
+            snapshots.lock().unwrap().push((phase, snapshot))
+        }
+    });
+
+    crate::tokenize("a", &opts);
+    parse_cst("a", &opts);
+    crate::parse_cst_simple("a", &opts);
+
+    let snapshots = std::mem::take(&mut *snapshots.lock().unwrap());
+
+    let phases: Vec<DebugPhase> =
+        snapshots.iter().map(|(phase, _)| *phase).collect();
+
+    assert_eq!(
+        phases,
+        [DebugPhase::Tokens, DebugPhase::Cst, DebugPhase::Cst]
+    );
+
+    assert!(snapshots[0].1.contains("Symbol"));
+    assert!(snapshots[1].1.starts_with("NodeSeq(vec!["));
+
+    // The sink itself can't be formatted, only whether there is one.
+    let opts = format!("{:?}", ParseOptions::default());
+    assert!(opts.contains("debug_sink: false"));
+}
+
+#[test]
+fn test_parse_reader() {
+    use std::io::Read;
+
+    let input =
+        "#!/usr/bin/env wolframscript\nf[x]\n(* comment *)\ng[y,\n  z]\n\nh[";
+
+    // Split the input so that it is read in more than one piece.
+    let (first, second) = input.as_bytes().split_at(7);
+    let reader = first.chain(second);
+
+    let result =
+        crate::parse_reader_cst(reader, &ParseOptions::default()).unwrap();
+
+    let expected: Vec<_> =
+        crate::parse_cst_expr_iter(input, &ParseOptions::default()).collect();
+
+    assert_eq!(
+        result.syntax.0,
+        expected
+            .iter()
+            .map(|result| result.syntax.clone().into_owned())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        result.fatal_issues,
+        expected
+            .iter()
+            .flat_map(|result| result.fatal_issues.clone())
+            .collect::<Vec<_>>()
+    );
+
+    let result =
+        crate::parse_reader_ast(input.as_bytes(), &ParseOptions::default())
+            .unwrap();
+
+    assert_eq!(
+        result.syntax,
+        crate::parse_ast_seq(input, &ParseOptions::default()).syntax
+    );
+
+    // Unterminated groups and tokens are reparsed using only the input that
+    // is still buffered.
+    for opts in [
+        ParseOptions::default(),
+        ParseOptions::default()
+            .source_convention(crate::source::SourceConvention::CharacterIndex),
+    ] {
+        for input in ["a\nb\nf[x,\n  y\n\ng", "a\nb = \"xy\n\nz"] {
+            let result = crate::parse_reader_cst(input.as_bytes(), &opts)
+                .unwrap()
+                .syntax;
+
+            let expected: Vec<_> = crate::parse_cst_expr_iter(input, &opts)
+                .map(|result| result.syntax.into_owned())
+                .collect();
+
+            assert_eq!(result.0, expected, "input: {input:?}");
+        }
+    }
+}
+
+#[test]
+fn test_parse_reader_pending_operator() {
+    use crate::{
+        explain::{Associativity, OperatorPrecedence},
+        notation::Notations,
+        parse::spec::OperatorPosition,
+    };
+
+    let mut notations = Notations::new();
+
+    notations
+        .register(
+            "\\[Wolf]",
+            OperatorPosition::Infix,
+            OperatorPrecedence {
+                level: 20,
+                associativity: Associativity::NonRight,
+            },
+            "Pack`Howl",
+        )
+        .unwrap();
+
+    let check = |input: &str, opts: &ParseOptions| {
+        let result = crate::parse_reader_cst(input.as_bytes(), opts).unwrap();
+
+        let expected: Vec<_> = crate::parse_cst_expr_iter(input, opts)
+            .map(|result| result.syntax.into_owned())
+            .collect();
+
+        assert_eq!(result.syntax.0, expected, "input: {input:?}");
+    };
+
+    let opts = ParseOptions::default();
+
+    // Lines that end in an operator are continued on the next line.
+    check("a +\n b\nc", &opts);
+    check("a .\n b\nc", &opts);
+    check("a \\[Rule]\n b\nc", &opts);
+    check("a \\[And] \\[Not]\n b\nc", &opts);
+    check("f @\n\n (* x *)\n x", &opts);
+    check("a =.\nb", &opts);
+    check("x = 1;\nx + 1", &opts);
+
+    // The options the input is parsed with decide which operators are
+    // pending.
+    check(
+        "x = 1;\nx + 1",
+        &ParseOptions::default()
+            .toplevel_newlines(ToplevelNewlineMode::ContinueIfOperatorPending),
+    );
+    check("a \\[Wolf]\n b\nc", &opts.notations(notations));
+}
+
+#[test]
+fn test_parse_reader_incremental() {
+    use std::io::{self, Read};
+
+    /// A stream whose first reads succeed, and later reads fail.
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("connection reset"))
+        }
+    }
+
+    let reader = &b"a = 1\nf[x,\n  y]\n"[..];
+    let reader = reader.chain(Failing);
+
+    let mut exprs =
+        crate::parse_reader_expr_iter(reader, &ParseOptions::default());
+
+    // Expressions are parsed before the rest of the stream is read.
+    assert_eq!(exprs.next().unwrap().unwrap().syntax.to_source(), "a = 1");
+    assert_eq!(
+        exprs.next().unwrap().unwrap().syntax.to_source(),
+        "f[x,\n  y]"
+    );
+
+    assert!(matches!(exprs.next(), Some(Err(crate::ParseError::Io(_)))));
+    assert!(exprs.next().is_none());
+
+    let reader = &b"a = 1\n"[..];
+    let reader = reader.chain(Failing);
+
+    assert!(matches!(
+        crate::parse_reader_cst(reader, &ParseOptions::default()),
+        Err(crate::ParseError::Io(_))
+    ));
+}
+
+#[test]
+fn test_joined_string_value() {
+    let ast =
+        |input: &str| crate::parse_ast(input, &ParseOptions::default()).syntax;
+
+    let joined = |input: &str| ast(input).as_joined_string_value();
+
+    assert_eq!(joined(r#""abc""#).as_deref(), Some("abc"));
+    assert_eq!(joined(r#""a" <> "b" <> "c""#).as_deref(), Some("abc"));
+    assert_eq!(joined(r#"("a" <> "b") <> "c""#).as_deref(), Some("abc"));
+    assert_eq!(joined(r#""a\tb" <> "\"c\"""#).as_deref(), Some("a\tb\"c\""));
+    assert_eq!(joined(r#"StringJoin["a", "b"]"#).as_deref(), Some("ab"));
+
+    // Not every part is a literal.
+    assert_eq!(joined(r#""a" <> x"#), None);
+    assert_eq!(joined(r#""a" <> ToString[1]"#), None);
+    assert_eq!(joined("x"), None);
+
+    let ast = ast(r#""a" <> x <> "c""#);
+    let parts = ast.string_join_parts().unwrap();
+
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[1].as_symbol(), Some("x"));
+    assert_eq!(
+        crate::parse_ast("f[x]", &ParseOptions::default())
+            .syntax
+            .string_join_parts(),
+        None
+    );
+}
+
+#[test]
+fn test_capabilities() {
+    let caps = crate::capabilities();
+
+    assert!(caps.compute_source);
+    assert_eq!(caps.string_interning, cfg!(feature = "string-interning"));
+    assert_eq!(caps.check_abort, cfg!(feature = "CHECK_ABORT"));
+    assert_eq!(caps.use_mathlink, cfg!(feature = "USE_MATHLINK"));
+    assert_eq!(caps.serde, cfg!(feature = "serde"));
+    assert_eq!(caps.bumpalo, cfg!(feature = "bumpalo"));
+
+    assert_eq!(
+        caps.can_abort(),
+        cfg!(all(feature = "CHECK_ABORT", feature = "USE_MATHLINK"))
+    );
+
+    assert_eq!(
+        caps.enabled_features().contains(&"string-interning"),
+        caps.string_interning
+    );
+    assert_eq!(caps.enabled_features().contains(&"bumpalo"), caps.bumpalo);
+    assert!(!caps.enabled_features().contains(&"COMPUTE_SOURCE"));
+}
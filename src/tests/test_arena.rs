@@ -0,0 +1,132 @@
+use bumpalo::Bump;
+
+use crate::{
+    cst::arena::{self, ArenaCst, ArenaNodeKind},
+    parse::operators::CallOperator,
+    parse_cst, parse_cst_in, parse_cst_seq, parse_cst_seq_in, DebugPhase,
+    GrammarProfile, ParseOptions, RecoveryMode,
+};
+
+use pretty_assertions::assert_eq;
+
+
+/// Check that parsing `input` into an arena gives the same trees and issues
+/// as parsing it normally.
+fn assert_same_as_heap(input: &str, opts: &ParseOptions) {
+    let bump = Bump::new();
+
+    let result = parse_cst_seq_in(input, opts, &bump);
+    let expected = parse_cst_seq(input, opts);
+
+    assert_eq!(
+        arena::to_cst_seq(result.syntax),
+        expected.syntax,
+        "input: {input:?}"
+    );
+    assert_eq!(
+        result.issues().collect::<Vec<_>>(),
+        expected.issues().collect::<Vec<_>>(),
+        "input: {input:?}"
+    );
+}
+
+#[test]
+fn test_arena_matches_heap() {
+    let opts = ParseOptions::default();
+
+    for input in [
+        "",
+        "a + b * c",
+        "f[x_, y_:1] := x + y; g[[1]]",
+        "(* comment *) {1, 2, <|a -> b|>}",
+        "a /: b[a] =. ; x:_Integer",
+        "#2 + ##3 & /@ %5",
+        "\\[Integral] f \\[DifferentialD] x",
+        "a ~f~ b ;; c ;; d",
+        "f[a, ]\n{1,,2}",
+        "a::b::c::d",
+        "f[{1, 2\n\ng[x]",
+        "x = \"abc\ny = 2",
+        "a +",
+        "1 2 3\n-4",
+    ] {
+        assert_same_as_heap(input, &opts);
+    }
+}
+
+#[test]
+fn test_arena_options() {
+    assert_same_as_heap(
+        "f[x] + {1, 2}",
+        &ParseOptions::default().grammar_profile(GrammarProfile::Data),
+    );
+
+    assert_same_as_heap(
+        "f[{1, 2\n\ng[x]",
+        &ParseOptions::default().reparse_unterminated(None),
+    );
+
+    assert_same_as_heap(
+        "x = \"abc\ny = 2",
+        &ParseOptions::default().reparse_unterminated(None),
+    );
+}
+
+#[test]
+fn test_arena_call() {
+    let bump = Bump::new();
+
+    let result = parse_cst_in("f [x]", &ParseOptions::default(), &bump);
+
+    let ArenaCst::Node(call) = result.syntax else {
+        panic!("expected a node: {:?}", result.syntax);
+    };
+
+    assert_eq!(call.kind, ArenaNodeKind::Call);
+
+    // `f`, the whitespace, and the body.
+    let [_, _, ArenaCst::Node(body)] = call.children else {
+        panic!("unexpected call children: {:?}", call.children);
+    };
+
+    assert_eq!(
+        body.kind,
+        ArenaNodeKind::CallBody(CallOperator::CodeParser_GroupSquare)
+    );
+
+    assert_eq!(
+        result.syntax.to_cst(),
+        parse_cst("f [x]", &ParseOptions::default()).syntax
+    );
+}
+
+#[test]
+fn test_arena_recovery() {
+    let opts = ParseOptions::default().recovery(RecoveryMode::Recover);
+
+    for input in ["", "a\nb"] {
+        let bump = Bump::new();
+
+        let result = parse_cst_in(input, &opts, &bump);
+        let expected = parse_cst(input, &opts);
+
+        assert_eq!(result.syntax.to_cst(), expected.syntax);
+        assert_eq!(result.fatal_issues, expected.fatal_issues);
+    }
+}
+
+#[test]
+fn test_arena_recovery_each_expression() {
+    // Panic while parsing any concrete syntax containing `boom`.
+    let opts = ParseOptions::default()
+        .recovery(RecoveryMode::Recover)
+        .debug_sink(|phase, snapshot| {
+            if phase == DebugPhase::Cst
+                && format!("{snapshot:?}").contains("boom")
+            {
+                panic!("boom");
+            }
+        });
+
+    assert_same_as_heap("a\nf[boom]\nc", &opts);
+}
@@ -0,0 +1,106 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    ast::{
+        fold::AstFold,
+        visit::{walk_ast, AstVisitor},
+        Ast, AstMetadata,
+    },
+    parse_ast,
+    tokenize::{TokenKind, TokenString},
+    ParseOptions,
+};
+
+
+#[derive(Default)]
+struct Collect {
+    events: Vec<String>,
+}
+
+impl AstVisitor for Collect {
+    fn visit_leaf(&mut self, node: &Ast) {
+        let Ast::Leaf { input, .. } = node else {
+            panic!("expected leaf: {node:?}")
+        };
+
+        self.events.push(input.to_str().to_owned());
+    }
+
+    fn visit_call(&mut self, node: &Ast) {
+        let Ast::Call { args, .. } = node else {
+            panic!("expected call: {node:?}")
+        };
+
+        self.events.push(format!("Call({})", args.len()));
+    }
+
+    fn visit_call_missing_closer(&mut self, _node: &Ast) {
+        self.events.push("CallMissingCloser".to_owned());
+    }
+}
+
+#[test]
+fn test_ast_visitor() {
+    let ast = parse_ast("f[a + 1, g[b]]", &ParseOptions::default()).syntax;
+
+    let mut collect = Collect::default();
+    walk_ast(&mut collect, &ast);
+
+    assert_eq!(
+        collect.events,
+        ["Call(2)", "f", "Call(2)", "Plus", "a", "1", "Call(1)", "g", "b"]
+    );
+
+    let ast = parse_ast("f[x", &ParseOptions::default()).syntax;
+
+    let mut collect = Collect::default();
+    walk_ast(&mut collect, &ast);
+
+    assert_eq!(collect.events, ["CallMissingCloser", "f", "x"]);
+}
+
+#[test]
+fn test_ast_fold() {
+    /// Rename `x` to `y`, keeping the source of the original symbol.
+    struct Rename;
+
+    impl AstFold for Rename {
+        fn fold_leaf(
+            &mut self,
+            kind: TokenKind,
+            input: TokenString,
+            data: AstMetadata,
+        ) -> Ast {
+            let input = match (kind, input.to_str()) {
+                (TokenKind::Symbol, "x") => TokenString::new("y"),
+                _ => input,
+            };
+
+            Ast::Leaf { kind, input, data }
+        }
+    }
+
+    let ast = parse_ast("f[x, {x, z}]", &ParseOptions::default()).syntax;
+
+    let folded = Rename.fold_ast(ast.clone());
+
+    let expected = parse_ast("f[y, {y, z}]", &ParseOptions::default()).syntax;
+
+    assert_eq!(folded.to_expr().unwrap(), expected.to_expr().unwrap());
+
+    // Metadata is kept, including on renamed nodes.
+    assert_eq!(folded.source(), ast.source());
+
+    let (_, args) = folded.as_symbol_call().unwrap();
+    let (_, orig_args) = ast.as_symbol_call().unwrap();
+
+    assert_eq!(args[0].as_symbol(), Some("y"));
+    assert_eq!(args[0].source(), orig_args[0].source());
+
+    // The default fold leaves the tree unchanged.
+    struct Identity;
+
+    impl AstFold for Identity {}
+
+    assert_eq!(Identity.fold_ast(ast.clone()), ast);
+}
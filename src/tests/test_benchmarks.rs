@@ -0,0 +1,45 @@
+use pretty_assertions::assert_eq;
+
+use crate::benchmarks::{self, Phase};
+
+#[test]
+fn test_run() {
+    let dir = std::env::temp_dir().join(format!(
+        "wolfram-parser-test-benchmarks-{}",
+        std::process::id()
+    ));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+
+    std::fs::write(dir.join("A.wl"), "f[x_] := x + 1").unwrap();
+    std::fs::write(dir.join("sub/B.m"), "{1, \"a\", g[2]}").unwrap();
+    std::fs::write(dir.join("notes.txt"), "not benchmarked").unwrap();
+
+    let report = benchmarks::run(&dir).unwrap();
+
+    assert_eq!(report.files, vec![dir.join("A.wl"), dir.join("sub/B.m")]);
+    assert_eq!(report.bytes, 28);
+
+    for phase in [&report.tokenize, &report.parse, &report.abstract_] {
+        assert_eq!(phase.bytes, 28);
+        assert!(phase.mb_per_sec() > 0.0);
+        // The test harness does not use `CountingAllocator`.
+        assert_eq!(phase.allocations, None);
+    }
+
+    assert!(report.to_string().starts_with("2 files, 28 bytes\n"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_phase_mb_per_sec() {
+    let phase = Phase {
+        bytes: 3_000_000,
+        elapsed: std::time::Duration::from_millis(500),
+        allocations: None,
+    };
+
+    assert_eq!(phase.mb_per_sec(), 6.0);
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+
+use crate::{
+    cli::{Envelope, FileResult},
+    parse_cst, ParseOptions,
+};
+
+#[test]
+fn test_envelope_to_json() {
+    let mut envelope = Envelope::new("paclet", "decode");
+    envelope.option("output", Some("out.m"));
+    envelope.option("verbose", false);
+    envelope.option("range", None::<String>);
+
+    let mut result = FileResult::new(Some("in.m".into()), Some("f[x]".into()));
+    result.elapsed = Duration::from_millis(2);
+
+    envelope.results.push(result);
+    envelope.elapsed = Duration::from_millis(3);
+
+    assert_eq!(
+        envelope.to_json(),
+        format!(
+            r#"{{"tool":"paclet","version":"{}","command":"decode","options":{{"output":"out.m","verbose":false,"range":null}},"success":true,"results":[{{"file":"in.m","success":true,"output":"f[x]","issues":[],"timeMs":2}}],"summary":{{"total":0,"bySeverity":[],"byTag":[],"byRegion":[]}},"timeMs":3}}"#,
+            env!("CARGO_PKG_VERSION")
+        )
+    );
+}
+
+#[test]
+fn test_envelope_failure() {
+    let mut envelope = Envelope::new("paclet", "check");
+    envelope.results.push(FileResult::new(None, None));
+    envelope
+        .results
+        .push(FileResult::error(None, "not a paclet".into()));
+
+    assert!(!envelope.success());
+
+    let json = serde_json::from_str::<Value>(&envelope.to_json()).unwrap();
+
+    assert_eq!(json.get("success"), Some(&Value::Bool(false)));
+
+    let results = json.get("results").unwrap().as_array().unwrap();
+
+    assert_eq!(results[0].get("success"), Some(&Value::Bool(true)));
+    assert_eq!(results[0].get("error"), None);
+    assert_eq!(results[1].get("success"), Some(&Value::Bool(false)));
+    assert_eq!(
+        results[1].get("error").and_then(Value::as_str),
+        Some("not a paclet")
+    );
+}
+
+#[test]
+fn test_envelope_issues() {
+    let input = "1+\\[Alpha+2";
+    let parsed = parse_cst(input, &ParseOptions::default());
+
+    let mut result = FileResult::new(None, None);
+    result.issues = parsed.issues().cloned().collect();
+
+    let mut envelope = Envelope::new("wolfram-parser", "cst");
+    envelope.results.push(result);
+
+    let json = serde_json::from_str::<Value>(&envelope.to_json()).unwrap();
+
+    let issues = json.get("results").unwrap().as_array().unwrap()[0]
+        .get("issues")
+        .unwrap()
+        .as_array()
+        .unwrap();
+
+    assert_eq!(issues.len(), parsed.issues().count());
+    assert!(!issues.is_empty());
+
+    for (json, issue) in issues.iter().zip(parsed.issues()) {
+        assert_eq!(
+            json.get("tag").and_then(Value::as_str),
+            Some(issue.tag.as_str())
+        );
+        assert_eq!(
+            json.get("severity").and_then(Value::as_str),
+            Some(issue.sev.as_str())
+        );
+        assert_eq!(
+            json.get("message").and_then(Value::as_str),
+            Some(&*issue.msg)
+        );
+
+        let source = json.get("source").unwrap();
+        assert!(source.get("start").and_then(Value::as_array).is_some());
+        assert!(source.get("end").and_then(Value::as_array).is_some());
+
+        let annotations = json.get("annotations").and_then(Value::as_array);
+        assert_eq!(annotations.map(|a| a.len()), Some(issue.annotations.len()));
+    }
+    let summary = json.get("summary").unwrap();
+
+    assert_eq!(
+        summary.get("total"),
+        Some(&Value::from(parsed.issues().count()))
+    );
+    assert_eq!(
+        summary.get("byTag").and_then(Value::as_array).map(Vec::len),
+        Some(envelope.summary().by_tag.len())
+    );
+}
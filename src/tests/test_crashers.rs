@@ -116,7 +116,7 @@ fn CrashTest_Crash2() {
             src: Source::Span(Span::from(src!(1:1-7))),
             val: 0.8,
             actions: vec![],
-            additional_descriptions: vec![],
+            annotations: vec![],
             additional_sources: vec![],
         }]
     );
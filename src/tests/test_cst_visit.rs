@@ -0,0 +1,159 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cst::{
+        visit::{walk_cst_seq, walk_cst_seq_mut, CstVisitor, CstVisitorMut},
+        CallNode, Cst, GroupNode, InfixNode,
+    },
+    parse_cst_seq,
+    source::Span,
+    tokenize::{Token, TokenInput, TokenKind, TokenString},
+    ParseOptions,
+};
+
+
+#[derive(Default)]
+struct Collect {
+    events: Vec<String>,
+}
+
+impl<I: TokenInput, S> CstVisitor<I, S> for Collect {
+    fn visit_token(&mut self, token: &Token<I, S>) {
+        if !token.tok.isTrivia() {
+            self.events.push(token.input.as_str().to_owned());
+        }
+    }
+
+    fn visit_call(&mut self, _node: &CallNode<I, S>) {
+        self.events.push("Call".to_owned());
+    }
+
+    fn visit_infix(&mut self, node: &InfixNode<I, S>) {
+        self.events.push(format!("Infix({:?})", node.0.op));
+    }
+
+    fn visit_group(&mut self, _node: &GroupNode<I, S>) {
+        self.events.push("Group".to_owned());
+    }
+}
+
+#[test]
+fn test_cst_visitor() {
+    let cst = parse_cst_seq("f[a + b, {c}]", &ParseOptions::default()).syntax;
+
+    let mut collect = Collect::default();
+    walk_cst_seq(&mut collect, &cst);
+
+    assert_eq!(
+        collect.events,
+        [
+            "Call",
+            "f",
+            "[",
+            "Infix(CodeParser_Comma)",
+            "Infix(Plus)",
+            "a",
+            "+",
+            "b",
+            ",",
+            "Group",
+            "{",
+            "c",
+            "}",
+            "]",
+        ]
+    );
+
+    // Every node is walked, in the same order as `Cst::visit()`.
+    let mut count = 0;
+    cst.visit(&mut |_| count += 1);
+
+    struct CountAll(usize);
+
+    impl<I, S> CstVisitor<I, S> for CountAll {
+        fn visit_token(&mut self, _: &Token<I, S>) {
+            self.0 += 1;
+        }
+
+        fn visit_call(&mut self, _: &CallNode<I, S>) {
+            self.0 += 1;
+        }
+
+        fn visit_infix(&mut self, _: &InfixNode<I, S>) {
+            self.0 += 1;
+        }
+
+        fn visit_group(&mut self, _: &GroupNode<I, S>) {
+            self.0 += 1;
+        }
+    }
+
+    let mut count_all = CountAll(0);
+    walk_cst_seq(&mut count_all, &cst);
+
+    assert_eq!(count_all.0, count);
+}
+
+#[test]
+fn test_cst_visitor_mut() {
+    struct Rename;
+
+    impl CstVisitorMut<TokenString, Span> for Rename {
+        fn visit_token_mut(&mut self, token: &mut Token<TokenString, Span>) {
+            if token.tok == TokenKind::Symbol {
+                let name = format!("{}1", token.input.as_str());
+                token.input = TokenString::from_string(name);
+            }
+        }
+    }
+
+    let mut cst = parse_cst_seq("f[x, g[y]]", &ParseOptions::default())
+        .syntax
+        .into_owned();
+
+    walk_cst_seq_mut(&mut Rename, &mut cst);
+
+    let mut symbols = Vec::new();
+    cst.visit(&mut |node| {
+        if let Cst::Token(token) = node {
+            if token.tok == TokenKind::Symbol {
+                symbols.push(token.input.as_str().to_owned());
+            }
+        }
+    });
+
+    assert_eq!(symbols, ["f1", "x1", "g1", "y1"]);
+}
+
+#[test]
+fn test_cst_metrics() {
+    let opts = ParseOptions::default();
+
+    let cst = parse_cst_seq("", &opts).syntax;
+
+    assert_eq!(cst.node_count(), 0);
+    assert_eq!(cst.token_count(), 0);
+    assert_eq!(cst.max_depth(), 0);
+
+    let cst = parse_cst_seq("a", &opts).syntax;
+
+    assert_eq!(cst.node_count(), 1);
+    assert_eq!(cst.token_count(), 1);
+    assert_eq!(cst.max_depth(), 1);
+
+    let cst = parse_cst_seq("f[g[x]]+1\n{y}", &opts).syntax;
+
+    let mut count = 0;
+    let mut tokens = 0;
+    cst.visit(&mut |node| {
+        count += 1;
+        if let Cst::Token(_) = node {
+            tokens += 1;
+        }
+    });
+
+    assert_eq!(cst.node_count(), count);
+    assert_eq!(cst.token_count(), tokens);
+    // Plus -> Call f -> Call g -> x
+    assert_eq!(cst.max_depth(), 4);
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use pretty_assertions::assert_eq;
+
+use crate::data::{
+    parse_association, parse_literal, LiteralError, LiteralValue,
+};
+
+#[test]
+fn test_parse_association() {
+    let map = parse_association(
+        r#"<|
+    "string" -> "a\"b\n",
+    "integer" -> -12,
+    "real" -> 1.5`10*^2,
+    "plus" -> +3,
+    "bool" -> True,
+    "null" -> Null,
+    "symbol" -> Automatic,
+    "list" -> {1, {2.5}, "x"},
+    "nested" :> <|"a" -> <||>|>,
+    "integer" -> 7
+|>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        map,
+        BTreeMap::from([
+            (
+                "string".to_owned(),
+                LiteralValue::String("a\"b\n".to_owned())
+            ),
+            // Last duplicate key wins.
+            ("integer".to_owned(), LiteralValue::Integer(7)),
+            ("real".to_owned(), LiteralValue::Real(150.0)),
+            ("plus".to_owned(), LiteralValue::Integer(3)),
+            ("bool".to_owned(), LiteralValue::Boolean(true)),
+            ("null".to_owned(), LiteralValue::Null),
+            (
+                "symbol".to_owned(),
+                LiteralValue::Symbol("Automatic".to_owned())
+            ),
+            (
+                "list".to_owned(),
+                LiteralValue::List(vec![
+                    LiteralValue::Integer(1),
+                    LiteralValue::List(vec![LiteralValue::Real(2.5)]),
+                    LiteralValue::String("x".to_owned()),
+                ])
+            ),
+            (
+                "nested".to_owned(),
+                LiteralValue::Association(BTreeMap::from([(
+                    "a".to_owned(),
+                    LiteralValue::Association(BTreeMap::new())
+                )]))
+            ),
+        ])
+    );
+}
+
+#[test]
+fn test_parse_literal() {
+    assert_eq!(
+        parse_literal("{1, , 16^^ff}").unwrap(),
+        LiteralValue::List(vec![
+            LiteralValue::Integer(1),
+            LiteralValue::Null,
+            LiteralValue::Integer(255),
+        ])
+    );
+
+    // The exponent is in the base of the digits
+    assert_eq!(
+        parse_literal("12*^3").unwrap(),
+        LiteralValue::Integer(12000)
+    );
+    assert_eq!(
+        parse_literal("16^^FF*^2").unwrap(),
+        LiteralValue::Integer(65280)
+    );
+    assert_eq!(
+        parse_literal("2^^101*^3").unwrap(),
+        LiteralValue::Integer(40)
+    );
+}
+
+#[test]
+fn test_parse_association_errors() {
+    // Disallowed syntax
+    assert!(matches!(
+        parse_association(r#"<|"a" -> f[x]|>"#),
+        Err(LiteralError::Syntax(_))
+    ));
+
+    // Syntax error
+    assert!(matches!(
+        parse_association(r#"<|"a" -> |>"#),
+        Err(LiteralError::Syntax(_))
+    ));
+
+    assert!(matches!(
+        parse_association("<|\"a\" -> 1|>\n<|\"b\" -> 2|>"),
+        Err(LiteralError::ExpectedSingleExpression(2))
+    ));
+
+    assert!(matches!(
+        parse_association("{1, 2}"),
+        Err(LiteralError::ExpectedAssociation(_))
+    ));
+
+    assert!(matches!(
+        parse_association("<|a -> 1|>"),
+        Err(LiteralError::NonStringKey(_))
+    ));
+
+    // Integer out of range
+    assert!(matches!(
+        parse_association(r#"<|"a" -> 123456789012345678901234567890|>"#),
+        Err(LiteralError::Unsupported { input, .. })
+            if input == "123456789012345678901234567890"
+    ));
+}
@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    diagnostics::{self, Counter, Diagnostics},
+    parse_cst, ParseOptions,
+};
+
+fn parse_counts(input: &str) -> Diagnostics {
+    let (_, counts) =
+        diagnostics::collect(|| parse_cst(input, &ParseOptions::default()));
+
+    counts
+}
+
+#[test]
+fn test_collect() {
+    let counts = parse_counts("f[x] + (a - b) + \"\\n\"");
+
+    assert_eq!(counts.get(Counter::Node_CallNodeCount), 1);
+    assert_eq!(counts.get(Counter::Node_TernaryNodeCount), 0);
+
+    assert!(counts.get(Counter::Node_GroupNodeCount) > 0);
+    assert!(counts.get(Counter::Tokenizer_SymbolCount) > 0);
+    assert!(counts.get(Counter::CharacterDecoder_StringMetaLineFeedCount) > 0);
+    assert!(counts.iter().all(|(_, count)| count > 0));
+
+    // Each call starts from zero, so the same input gives the same counts.
+    assert_eq!(parse_counts("f[x] + (a - b) + \"\\n\""), counts);
+}
+
+#[test]
+fn test_collect_nested() {
+    let (inner, outer) = diagnostics::collect(|| {
+        let _ = parse_cst("f[x]", &ParseOptions::default());
+
+        let (_, inner) = diagnostics::collect(|| {
+            parse_cst("g[y]", &ParseOptions::default())
+        });
+
+        inner
+    });
+
+    assert_eq!(inner.get(Counter::Node_CallNodeCount), 1);
+    assert_eq!(outer.get(Counter::Node_CallNodeCount), 2);
+
+    let mut total = parse_counts("f[x]");
+    total += &parse_counts("g[y]");
+
+    assert_eq!(total, outer);
+}
+
+#[test]
+fn test_collect_threads() {
+    let expected = parse_counts("f[x]");
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| parse_counts("f[x]")))
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+
+    // Work that isn't inside collect() isn't counted.
+    let (_, counts) = diagnostics::collect(|| {
+        std::thread::spawn(|| parse_cst("f[x]", &ParseOptions::default()))
+            .join()
+            .unwrap();
+    });
+
+    assert_eq!(counts, Diagnostics::default());
+}
@@ -0,0 +1,61 @@
+use crate::{parse_ast, parse_ast_seq, parse_cst, ParseOptions};
+
+use pretty_assertions::assert_eq;
+
+
+#[test]
+fn test_display_tree() {
+    let opts = ParseOptions::default();
+
+    assert_eq!(
+        parse_ast("{a, f[b]}", &opts)
+            .syntax
+            .display_tree()
+            .to_string(),
+        "\
+Call 1:1-10
+├── Leaf Symbol \"List\" <unknown>
+├── Leaf Symbol \"a\" 1:2-3
+└── Call 1:5-9
+    ├── Leaf Symbol \"f\" 1:5-6
+    └── Leaf Symbol \"b\" 1:7-8
+"
+    );
+
+    assert_eq!(
+        parse_ast("{a, f[b]}", &opts)
+            .syntax
+            .truncate(3)
+            .display_tree()
+            .to_string(),
+        "\
+Call 1:1-10
+├── Leaf Symbol \"List\" <unknown>
+├── Leaf Symbol \"a\" 1:2-3
+└── Elided 1 subtree, 3 nodes 1:5-9
+"
+    );
+
+    // Sequences draw each tree in turn.
+    assert_eq!(
+        parse_ast_seq("a\nb", &opts)
+            .syntax
+            .display_tree()
+            .to_string(),
+        "\
+Leaf Symbol \"a\" 1:1-2
+Leaf Symbol \"b\" 2:1-2
+"
+    );
+}
+
+#[test]
+fn test_display_tree_ansi() {
+    let cst = parse_cst("x", &ParseOptions::default()).syntax;
+
+    assert_eq!(
+        cst.display_tree().ansi().to_string(),
+        "\x1b[1;34mToken\x1b[0m \x1b[36mSymbol\x1b[0m \x1b[32m\"x\"\x1b[0m \
+         \x1b[2m1:1-2\x1b[0m\n"
+    );
+}
@@ -0,0 +1,127 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    docs::{self, DocComment},
+    macros::src,
+    parse_cst_seq,
+    source::Span,
+    ParseOptions,
+};
+
+fn doc_comments(input: &str) -> Vec<DocComment> {
+    let opts = ParseOptions::default();
+
+    let cst = parse_cst_seq(input, &opts).syntax;
+
+    docs::doc_comments(&cst, &opts)
+}
+
+/// The symbol, text, and section titles of each definition in `input`.
+fn summary(input: &str) -> Vec<(String, String, Vec<String>)> {
+    doc_comments(input)
+        .into_iter()
+        .map(|doc| {
+            let sections = doc
+                .sections
+                .iter()
+                .map(|section| section.title.clone().unwrap_or_default())
+                .collect();
+
+            (doc.symbol.clone(), doc.text(), sections)
+        })
+        .collect()
+}
+
+fn entry(
+    symbol: &str,
+    text: &str,
+    sections: &[&str],
+) -> (String, String, Vec<String>) {
+    (
+        symbol.to_owned(),
+        text.to_owned(),
+        sections.iter().map(|s| (*s).to_owned()).collect(),
+    )
+}
+
+#[test]
+fn test_doc_comments() {
+    let input = r#"(* Adds one. *)
+f[x_] := x + 1
+
+(* Not attached: g is not a definition. *)
+g[1]
+h = 2
+
+(* Usage *)
+f::usage = "f[x] adds one"; Options[f] = {};
+(*first*) (*second*)
+HoldPattern[p[x_]] /; x > 0 := x
+q /: r[q] := 1
+"#;
+
+    assert_eq!(
+        summary(input),
+        vec![
+            entry("f", "Adds one.", &[]),
+            entry("h", "", &[]),
+            entry("f", "Usage", &[]),
+            entry("f", "Usage", &[]),
+            entry("p", "first\nsecond", &[]),
+            entry("q", "", &[]),
+        ]
+    );
+
+    let docs = doc_comments(input);
+
+    assert_eq!(docs[0].span, Span::from(src!(2:1-2:15)));
+    assert_eq!(docs[0].comments[0].span, Span::from(src!(1:1-1:16)));
+    assert_eq!(docs[0].comments[0].style, None);
+}
+
+#[test]
+fn test_doc_comment_sections() {
+    let input = r#"(* ::Package:: *)
+
+(* ::Title:: *)
+(*Geometry*)
+
+(* ::Section:: *)
+(*Circles*)
+
+(* ::Text:: *)
+(*The area of a circle.*)
+area[r_] := Pi r^2
+
+(* ::Subsection:: *)
+(*Helpers*)
+diameter[r_] := 2 r
+
+(* ::Section:: *)
+(*Squares*)
+
+side[a_] := Sqrt[a]
+"#;
+
+    assert_eq!(
+        summary(input),
+        vec![
+            entry("area", "The area of a circle.", &["Geometry", "Circles"]),
+            entry("diameter", "Helpers", &["Geometry", "Circles", "Helpers"]),
+            entry("side", "", &["Geometry", "Squares"]),
+        ]
+    );
+
+    let docs = doc_comments(input);
+
+    // Markers are part of the block, along with the comments after them.
+    let styles: Vec<Option<&str>> = docs[1]
+        .comments
+        .iter()
+        .map(|comment| comment.style.as_deref())
+        .collect();
+
+    assert_eq!(styles, [Some("Subsection"), None]);
+    assert_eq!(docs[1].sections[2].style, "Subsection");
+    assert_eq!(docs[1].sections[2].span, Span::from(src!(13:1-13:21)));
+}
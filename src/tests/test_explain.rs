@@ -0,0 +1,86 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    explain::{self, Associativity, OperatorPrecedence},
+    tokenize::TokenKind,
+    ParseOptions,
+};
+
+fn grouping(input: &str) -> String {
+    explain::explain(input, &ParseOptions::default()).grouping
+}
+
+#[test]
+fn test_explain_grouping() {
+    assert_eq!(grouping("a && b || c ~f~ d"), "((a && b) || (c ~ f ~ d))");
+    assert_eq!(grouping("a + b * c"), "(a + (b * c))");
+    assert_eq!(grouping("a^b^c"), "(a ^ (b ^ c))");
+    assert_eq!(grouping("-a^2"), "(-(a ^ 2))");
+    assert_eq!(grouping("a b + c"), "((a b) + c)");
+    assert_eq!(grouping("f[a + b, c]"), "f[(a + b), c]");
+    assert_eq!(grouping("(a + b) c"), "(((a + b)) c)");
+    assert_eq!(grouping("a = b; c"), "((a = b) ; c)");
+    assert_eq!(grouping("a\nb + c"), "a\n(b + c)");
+}
+
+#[test]
+fn test_explain_steps() {
+    let explanation = explain::explain("a && b || c", &ParseOptions::default());
+
+    let steps: Vec<_> = explanation
+        .steps
+        .iter()
+        .map(|step| {
+            (
+                step.tok,
+                step.precedence.level,
+                step.context.map(|prec| prec.level),
+                step.binds,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        steps,
+        [
+            (TokenKind::AmpAmp, 31, None, true),
+            (TokenKind::BarBar, 29, Some(31), false),
+            (TokenKind::BarBar, 29, None, true),
+        ]
+    );
+
+    assert_eq!(
+        explanation.to_string(),
+        "\
+((a && b) || c)
+
+1:3-5: `&&` (precedence 31) binds: no enclosing operator
+1:8-10: `||` (precedence 29) yields: enclosing operator has higher precedence 31
+1:8-10: `||` (precedence 29) binds: no enclosing operator
+"
+    );
+}
+
+#[test]
+fn test_explain_associativity() {
+    let explanation = explain::explain("a^b^c", &ParseOptions::default());
+
+    let [first, second] = explanation.steps.as_slice() else {
+        panic!("unexpected steps: {:#?}", explanation.steps)
+    };
+
+    assert_eq!(
+        first.precedence,
+        OperatorPrecedence {
+            level: 75,
+            associativity: Associativity::Right,
+        }
+    );
+    assert_eq!(first.context, None);
+    assert!(first.binds);
+
+    // The second `^` has the same precedence as the first, but groups to the
+    // right, so it binds.
+    assert_eq!(second.context, Some(first.precedence));
+    assert!(second.binds);
+}
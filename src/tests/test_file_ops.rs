@@ -0,0 +1,92 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    file_ops::{self, FileOperationKind, OperationSyntax, Target},
+    macros::src,
+    parse_ast_seq,
+    source::{Source, Span},
+    ParseOptions,
+};
+
+fn targets(input: &str) -> Vec<(FileOperationKind, Target, OperationSyntax)> {
+    let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+
+    file_ops::file_operations(&ast)
+        .into_iter()
+        .map(|op| (op.kind, op.target, op.syntax))
+        .collect()
+}
+
+fn path(path: &str) -> Target {
+    Target::Path(path.to_owned())
+}
+
+fn context(context: &str) -> Target {
+    Target::Context(context.to_owned())
+}
+
+#[test]
+fn test_file_operations() {
+    use FileOperationKind::*;
+    use OperationSyntax::*;
+
+    assert_eq!(
+        targets(
+            r#"
+Get["a.wl"]; Get["Pkg`"]
+<< "b.wl"
+<< Pkg`Sub`
+Needs["Pkg`", "pkg/init.m"]
+x >> out.m
+Put[x, y, "put.m"]
+x >>> log.txt
+PutAppend[x, "append.txt"]
+Import[File["in.csv"], "CSV"]
+f[] := Export["out/" <> "x.png", img]
+System`Get["qualified.wl"]
+"#
+        ),
+        [
+            (Get, path("a.wl"), Call),
+            (Get, context("Pkg`"), Call),
+            (Get, path("b.wl"), Operator),
+            (Get, context("Pkg`Sub`"), Operator),
+            (Needs, context("Pkg`"), Call),
+            (Needs, path("pkg/init.m"), Call),
+            (Put, path("out.m"), Operator),
+            (Put, path("put.m"), Call),
+            (PutAppend, path("log.txt"), Operator),
+            (PutAppend, path("append.txt"), Call),
+            (Import, path("in.csv"), Call),
+            (Export, path("out/x.png"), Call),
+            (Get, path("qualified.wl"), Call),
+        ]
+    );
+
+    // Targets that aren't literal strings are skipped.
+    assert_eq!(
+        targets(
+            r#"
+Get[file]
+Import[dir <> "x.csv"]
+Needs[ctx]
+Export[]
+Pkg`Get["shadowed.wl"]
+"#
+        ),
+        []
+    );
+}
+
+#[test]
+fn test_file_operation_sources() {
+    let ast =
+        parse_ast_seq("Import[\"a.csv\"]", &ParseOptions::default()).syntax;
+
+    let [op] = file_ops::file_operations(&ast).try_into().unwrap();
+
+    assert_eq!(op.src, Source::Span(Span::from(src!(1:1-1:16))));
+    assert_eq!(op.target_src, Source::Span(Span::from(src!(1:8-1:15))));
+    assert!(!op.kind.is_write());
+    assert_eq!(op.target.as_str(), "a.csv");
+}
@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use crate::{parse_file_ast, parse_file_cst, tokenize_file, ParseOptions};
+    use crate::{
+        parse_file_ast, parse_file_cst, tokenize::TokenInput, tokenize_file,
+        ParseOptions,
+    };
     use std::fs;
 
     #[test]
@@ -31,29 +34,31 @@ mod tests {
 
     #[test]
     fn test_parse_paclet_file() {
-        // Test parsing a paclet-encoded file if it exists
-        let paclet_file = "/Users/David.Girardo/Documents/wolfram-parser-rust/paclet-decode/QunatityUnits.m";
-        
-        if fs::metadata(paclet_file).is_ok() {
-            let opts = ParseOptions::default();
-            
-            // Test CST parsing - should automatically decode the paclet
-            let cst_result = parse_file_cst(paclet_file, &opts).expect("Failed to parse CST from paclet file");
-            assert!(!cst_result.syntax.0.is_empty(), "Should have parsed some CST nodes from paclet");
-            
-            // Test AST parsing
-            let ast_result = parse_file_ast(paclet_file, &opts).expect("Failed to parse AST from paclet file");
-            assert!(!ast_result.syntax.0.is_empty(), "Should have parsed some AST nodes from paclet");
-            
-            // Test tokenization
-            let tokens = tokenize_file(paclet_file, &opts).expect("Failed to tokenize paclet file");
-            assert!(!tokens.0.is_empty(), "Should have tokenized some tokens from paclet");
-            
-            println!("✅ Successfully parsed paclet file with {} CST nodes, {} AST nodes, {} tokens", 
-                     cst_result.syntax.0.len(), ast_result.syntax.0.len(), tokens.0.len());
-        } else {
-            println!("ℹ️  Packlet test file not found, skipping paclet test");
-        }
+        // Encode a file as a paclet, which should be decoded automatically when parsed
+        let test_content = "BeginPackage[\"Units`\"]\nf[x_] := x + 1\nEndPackage[]";
+        let paclet_file = "/tmp/test_paclet.m";
+
+        let encoded = crate::paclet::encode_paclet(test_content).expect("Failed to encode paclet");
+        fs::write(paclet_file, encoded).expect("Failed to write test file");
+
+        let opts = ParseOptions::default();
+
+        // Test CST parsing
+        let cst_result = parse_file_cst(paclet_file, &opts).expect("Failed to parse CST from paclet file");
+        let string_cst = crate::parse_cst_seq(test_content, &opts);
+        assert_eq!(cst_result.syntax, string_cst.syntax.into_owned());
+
+        // Test AST parsing
+        let ast_result = parse_file_ast(paclet_file, &opts).expect("Failed to parse AST from paclet file");
+        let string_ast = crate::parse_ast_seq(test_content, &opts);
+        assert_eq!(ast_result.syntax, string_ast.syntax);
+
+        // Test tokenization
+        let tokens = tokenize_file(paclet_file, &opts).expect("Failed to tokenize paclet file");
+        let string_tokens = crate::tokenize(test_content, &opts);
+        assert_eq!(tokens.0.len(), string_tokens.0.len(), "Token count should match");
+
+        fs::remove_file(paclet_file).ok();
     }
     
     #[test]
@@ -86,4 +91,42 @@ mod tests {
         
         println!("✅ File parsing produces equivalent results to string parsing");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_file_bom_and_shebang() {
+        let opts = ParseOptions::default();
+
+        // UTF-8 byte order mark
+        let temp_file = "/tmp/test_bom.wl";
+        fs::write(temp_file, b"\xEF\xBB\xBFf[x]").expect("Failed to write test file");
+
+        let tokens = tokenize_file(temp_file, &opts).expect("Failed to tokenize file");
+        assert_eq!(tokens.0.len(), 4);
+        assert_eq!(tokens.0[0].input.as_str(), "f");
+        assert!(tokens.0[0].src.start() == crate::source::Location::start());
+
+        fs::remove_file(temp_file).ok();
+
+        // UTF-16 with a little-endian byte order mark
+        let temp_file = "/tmp/test_utf16.wl";
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend("a + b".encode_utf16().flat_map(u16::to_le_bytes));
+        fs::write(temp_file, bytes).expect("Failed to write test file");
+
+        let file_cst = parse_file_cst(temp_file, &opts).expect("Failed to parse CST from file");
+        let string_cst = crate::parse_cst_seq("a + b", &opts);
+        assert_eq!(file_cst.syntax, string_cst.syntax.into_owned());
+
+        fs::remove_file(temp_file).ok();
+
+        // Shebang in a script
+        let temp_file = "/tmp/test_shebang.wls";
+        fs::write(temp_file, "#!/usr/bin/env wolframscript\nPrint[1]").expect("Failed to write test file");
+
+        let ast_result = parse_file_ast(temp_file, &opts).expect("Failed to parse AST from file");
+        assert_eq!(ast_result.syntax.0.len(), 1);
+        assert!(ast_result.fatal_issues.is_empty());
+
+        fs::remove_file(temp_file).ok();
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{
+    format::{self, FormatOptions, OperatorSpacing},
+    testing, ParseOptions,
+};
+
+use pretty_assertions::assert_eq;
+
+
+fn fmt(input: &str, opts: &FormatOptions) -> String {
+    format::format(input, &ParseOptions::default(), opts)
+}
+
+fn standard(input: &str) -> String {
+    fmt(input, &FormatOptions::default())
+}
+
+fn with_spacing(input: &str, operator_spacing: OperatorSpacing) -> String {
+    fmt(
+        input,
+        &FormatOptions {
+            operator_spacing,
+            ..FormatOptions::default()
+        },
+    )
+}
+
+fn with_width(input: &str, max_line_length: usize) -> String {
+    fmt(
+        input,
+        &FormatOptions {
+            max_line_length,
+            ..FormatOptions::default()
+        },
+    )
+}
+
+#[test]
+fn test_format_spacing() {
+    assert_eq!(standard(""), "");
+    assert_eq!(standard("f[x_]:=x+1"), "f[x_] := x + 1\n");
+    assert_eq!(standard("a=b;c=d"), "a = b; c = d\n");
+    assert_eq!(standard("f [ x ,y ]"), "f[x, y]\n");
+    assert_eq!(standard("x_?IntegerQ"), "x_?IntegerQ\n");
+    assert_eq!(standard("a^b"), "a^b\n");
+    assert_eq!(standard("a[[ 1 ;; 2 ]]"), "a[[1;;2]]\n");
+    assert_eq!(standard("a;"), "a;\n");
+    assert_eq!(standard("2x"), "2 x\n");
+    assert_eq!(standard("#+1&"), "# + 1 &\n");
+    assert_eq!(standard("a - -b"), "a - -b\n");
+    assert_eq!(standard("a::b"), "a::b\n");
+
+    assert_eq!(
+        with_spacing("f[x, y] + 1", OperatorSpacing::Compact),
+        "f[x,y]+1\n"
+    );
+    assert_eq!(with_spacing("a  b", OperatorSpacing::Compact), "a b\n");
+    assert_eq!(with_spacing("x_ + y", OperatorSpacing::Compact), "x_+y\n");
+    // Joining these would make `--`.
+    assert_eq!(with_spacing("a - -b", OperatorSpacing::Compact), "a- -b\n");
+    assert_eq!(
+        with_spacing("a+b  -  c", OperatorSpacing::Preserve),
+        "a+b - c\n"
+    );
+}
+
+#[test]
+fn test_format_comments_and_newlines() {
+    assert_eq!(
+        standard("(* header *)\n\n\n\nf[x]\ng[y] (* trailing *)\n\n"),
+        "(* header *)\n\nf[x]\ng[y] (* trailing *)\n"
+    );
+
+    assert_eq!(standard("f[x (* c *),y]"), "f[x (* c *), y]\n");
+    assert_eq!(standard("a(* c *)+b"), "a (* c *) + b\n");
+}
+
+#[test]
+fn test_format_line_breaking() {
+    assert_eq!(
+        with_width("{alpha, beta, gamma, delta}", 20),
+        "{\n    alpha,\n    beta,\n    gamma,\n    delta\n}\n"
+    );
+
+    assert_eq!(
+        with_width("f[{1, 2}, g[x]]", 12),
+        "f[\n    {1, 2},\n    g[x]\n]\n"
+    );
+
+    let opts = FormatOptions {
+        indent_width: 2,
+        max_line_length: 20,
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        fmt("Module[{x = 1}, x = x + 1; Print[x]; x]", &opts),
+        "Module[\n  {x = 1},\n  x = x + 1;\n    Print[x];\n    x\n]\n"
+    );
+
+    // Lines are never broken outside of brackets.
+    assert_eq!(
+        with_width("alpha + beta + gamma", 10),
+        "alpha + beta + gamma\n"
+    );
+}
+
+#[test]
+fn test_format_errors_unchanged() {
+    assert_eq!(standard("f[x,  y"), "f[x,  y\n");
+    assert_eq!(standard("a  +  \\[Alpha"), "a  +  \\[Alpha\n");
+}
+
+#[test]
+fn test_format_align_rules() {
+    let opts = FormatOptions {
+        max_line_length: 30,
+        align_rules: true,
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        fmt("<|\"name\" -> \"server\", \"port\" -> 8080, \"timeout\" :> t|>", &opts),
+        "<|\n    \"name\"    -> \"server\",\n    \"port\"    -> 8080,\n    \"timeout\" :> t\n|>\n"
+    );
+
+    // Rules are only padded if the brackets are broken.
+    assert_eq!(fmt("{a -> 1, bb -> 2}", &opts), "{a -> 1, bb -> 2}\n");
+
+    // Lists that contain anything but rules are not aligned.
+    assert_eq!(
+        fmt("{alpha -> 1, b -> 2, somethingElse}", &opts),
+        "{\n    alpha -> 1,\n    b -> 2,\n    somethingElse\n}\n"
+    );
+
+    assert_eq!(
+        fmt(
+            "{alpha->1,b->2,gamma->3,d->4}",
+            &FormatOptions {
+                max_line_length: 20,
+                operator_spacing: OperatorSpacing::Compact,
+                align_rules: true,
+                ..FormatOptions::default()
+            }
+        ),
+        "{\n    alpha ->1,\n    b     ->2,\n    gamma ->3,\n    d     ->4\n}\n"
+    );
+}
+
+#[test]
+fn test_format_align_definitions() {
+    let opts = FormatOptions {
+        align_definitions: true,
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        fmt("f[x_]:=x^2\ng[x_, y_] := x + y;\nh = 1", &opts),
+        "f[x_]     := x^2\ng[x_, y_] := x + y;\nh         = 1\n"
+    );
+
+    // Blank lines and comments separate runs of definitions.
+    assert_eq!(
+        fmt("a = 1\nbbb = 2\n\ncc = 3\n(* c *)\nd = 4\neeee = 5", &opts),
+        "a   = 1\nbbb = 2\n\ncc = 3\n(* c *)\nd    = 4\neeee = 5\n"
+    );
+
+    // A single definition is not padded.
+    assert_eq!(fmt("a = 1\nf[b]", &opts), "a = 1\nf[b]\n");
+
+    // Definitions are not aligned unless the option is set.
+    assert_eq!(standard("a = 1\nbbb = 2"), "a = 1\nbbb = 2\n");
+}
+
+#[test]
+fn test_format_reflow_comments() {
+    let opts = FormatOptions {
+        max_line_length: 40,
+        reflow_comments: true,
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        fmt(
+            "(* This comment is long enough that it has to be wrapped over several lines. *)",
+            &opts
+        ),
+        "(* This comment is long enough that it\n   has to be wrapped over several\n   lines. *)\n"
+    );
+
+    assert_eq!(
+        fmt(
+            "(*\n  First paragraph, which is too long to fit on one line.\n\n  Short paragraph\n  kept as is.\n\n      code[example] + which + is + indented + more\n*)\nf[x]",
+            &opts
+        ),
+        "(*\n  First paragraph, which is too long to\n  fit on one line.\n\n  Short paragraph\n  kept as is.\n\n      code[example] + which + is + indented + more\n*)\nf[x]\n"
+    );
+
+    for comment in [
+        // Package metadata
+        "(* :Title: A title that is much longer than forty characters *)",
+        // Directives
+        "(* wolfram-parse: max-line-length=100, tab-width=4, other-setting *)",
+        "(*@ an annotation that is much longer than forty characters @*)",
+        // Nested comments
+        "(* a comment that contains (* another *) comment and is long *)",
+    ] {
+        assert_eq!(fmt(comment, &opts), format!("{comment}\n"));
+    }
+
+    // Comments after code are not reflowed, though the code before them may
+    // still be broken over several lines.
+    assert_eq!(
+        fmt("f[x] (* a trailing comment that is longer than forty characters *)", &opts),
+        "f[\n    x\n] (* a trailing comment that is longer than forty characters *)\n"
+    );
+}
+
+#[test]
+fn test_format_stability() {
+    let corpus = [
+        "f[x_] := x + 1",
+        "a=b;c=d",
+        "{1,2,{3,4}}",
+        "f[[1, 2]]",
+        "<|a -> 1, b :> 2|>",
+        "x_?IntegerQ",
+        "f[x_:0]",
+        "a /: b[a] = c",
+        "a ~f~ b",
+        "#+1&",
+        "a;;b;;c",
+        "2x y",
+        "a - -b",
+        "a . b",
+        "x_ . y",
+        "f@x",
+        "f @@ {a}",
+        "a // f",
+        "a /. b -> c",
+        "(a + b) c",
+        "f[a, (* c *) b]",
+        "(* header *)\nf[x]\n\n\n\ng[y]",
+        "Module[{x = 1, y = 2}, x + y; x * y; longFunctionName[x, y]]",
+        "a!",
+        "f'[x]",
+        "!a && !b",
+        "a^b^c",
+        "x == -1",
+        "\"string\" <> \"s2\"",
+        "a::b",
+        "% 2",
+        "# 1",
+        "a _",
+        "1. 5",
+        "a; ; b",
+        "f[x_]:=x^2\ng[x_, y_] := x + y;\nh = 1",
+        "<|\"name\" -> \"server\", \"port\"->8080, \"timeout\" :> t|>",
+        "{a -> {b -> 1, cc -> 2}, ddd -> 3}",
+        "(* This comment is long enough that it has to be wrapped. *)\nf[x]",
+    ];
+
+    let parse_opts = ParseOptions::default();
+
+    for operator_spacing in [
+        OperatorSpacing::Standard,
+        OperatorSpacing::Compact,
+        OperatorSpacing::Preserve,
+    ] {
+        for max_line_length in [80, 20, 1] {
+            for optional in [false, true] {
+                let opts = FormatOptions {
+                    indent_width: 4,
+                    max_line_length,
+                    operator_spacing,
+                    align_rules: optional,
+                    align_definitions: optional,
+                    reflow_comments: optional,
+                };
+
+                for input in corpus {
+                    let result = testing::check_format_stability(
+                        input,
+                        &parse_opts,
+                        |input| format::format(input, &parse_opts, &opts),
+                    );
+
+                    assert_eq!(result, Ok(()), "{input:?} with {opts:?}");
+                }
+            }
+        }
+    }
+}
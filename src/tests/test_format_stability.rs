@@ -0,0 +1,113 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    testing::{self, StabilityError},
+    ParseOptions,
+};
+
+/// Collapse runs of spaces into a single space.
+fn collapse_spaces(input: &str) -> String {
+    let mut out = String::new();
+
+    for c in input.chars() {
+        if !(c == ' ' && out.ends_with(' ')) {
+            out.push(c);
+        }
+    }
+
+    out
+}
+
+#[test]
+fn test_same_syntax() {
+    let opts = ParseOptions::default();
+
+    assert!(testing::same_syntax("a+b", "a  +  b", &opts));
+    assert!(testing::same_syntax("f[x] (* c *)", "f[ x ]", &opts));
+    assert!(testing::same_syntax("a -> b", "a \\[Rule] b", &opts));
+    assert!(testing::same_syntax("a\nb", "a\n\n\nb", &opts));
+
+    assert!(!testing::same_syntax("a + b", "a - b", &opts));
+    assert!(!testing::same_syntax("a\nb", "a b", &opts));
+    assert!(!testing::same_syntax("f[x]", "f[[x]]", &opts));
+    assert!(!testing::same_syntax("(a + b) c", "a + b c", &opts));
+}
+
+#[test]
+fn test_check_format_stability() {
+    let opts = ParseOptions::default();
+
+    assert_eq!(
+        testing::check_format_stability("f[x,    y]", &opts, collapse_spaces),
+        Ok(())
+    );
+
+    // Not idempotent
+    assert_eq!(
+        testing::check_format_stability("f[x]", &opts, |input| {
+            format!("{input} ")
+        }),
+        Err(StabilityError::NotIdempotent {
+            once: "f[x] ".to_owned(),
+            twice: "f[x]  ".to_owned(),
+        })
+    );
+
+    // Changes the syntax
+    assert_eq!(
+        testing::check_format_stability("a + b", &opts, |input| {
+            input.replace('+', "-")
+        }),
+        Err(StabilityError::SyntaxChanged {
+            formatted: "a - b".to_owned(),
+        })
+    );
+}
+
+#[test]
+fn test_check_format_stability_dir() {
+    let dir = std::env::temp_dir().join(format!(
+        "wolfram-parser-test-format-stability-{}",
+        std::process::id()
+    ));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+
+    std::fs::write(dir.join("A.wl"), "f[x_]  :=  x").unwrap();
+    std::fs::write(dir.join("sub/B.m"), "a  +  b").unwrap();
+    std::fs::write(dir.join("sub/C.wl"), "a  -   b").unwrap();
+    std::fs::write(dir.join("notes.txt"), "not  checked").unwrap();
+
+    // Stable
+    let report = testing::check_format_stability_dir(
+        &dir,
+        &ParseOptions::default(),
+        collapse_spaces,
+    )
+    .unwrap();
+
+    assert!(report.is_ok());
+    assert_eq!(
+        report.checked,
+        vec![dir.join("A.wl"), dir.join("sub/B.m"), dir.join("sub/C.wl")]
+    );
+
+    // Changes the syntax of files containing `-`
+    let report = testing::check_format_stability_dir(
+        &dir,
+        &ParseOptions::default(),
+        |input| input.replace('-', "+"),
+    )
+    .unwrap();
+
+    let failed: Vec<_> = report
+        .failures
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    assert_eq!(failed, vec![dir.join("sub/C.wl")]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,294 @@
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    index::{Occurrence, OccurrenceKind, RenameEdit, SymbolIndex},
+    macros::src,
+    project::ProjectParse,
+    source::Span,
+    ParseOptions,
+};
+
+const PACKAGE: &str = r#"BeginPackage["Pkg`"]
+f::usage = "f[x]"
+Begin["`Private`"]
+f[x_] := g[x]
+g[y_] := y + 1
+End[]
+EndPackage[]"#;
+
+fn project(files: &[(&str, &str)]) -> ProjectParse {
+    ProjectParse::from_sources(
+        files
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), source.to_string())),
+        &ParseOptions::default(),
+    )
+}
+
+fn occ(path: &str, span: Span, kind: OccurrenceKind) -> Occurrence {
+    Occurrence {
+        path: PathBuf::from(path),
+        span,
+        kind,
+    }
+}
+
+#[test]
+fn test_package_exports() {
+    let project = project(&[("Pkg.wl", PACKAGE)]);
+
+    let index = SymbolIndex::build(&project);
+
+    let f = index.get("Pkg`f").unwrap();
+
+    assert_eq!(f.exporting_context.as_deref(), Some("Pkg`"));
+    assert_eq!(
+        f.definitions,
+        vec![
+            occ("Pkg.wl", src!(2:1-2:2).into(), OccurrenceKind::Definition),
+            occ("Pkg.wl", src!(4:1-4:2).into(), OccurrenceKind::Definition),
+        ]
+    );
+    assert_eq!(f.uses, vec![]);
+
+    let g = index.get("Pkg`Private`g").unwrap();
+
+    assert_eq!(g.exporting_context, None);
+    assert_eq!(
+        g.definitions,
+        vec![occ(
+            "Pkg.wl",
+            src!(5:1-5:2).into(),
+            OccurrenceKind::Definition
+        )]
+    );
+    assert_eq!(
+        g.uses,
+        vec![occ("Pkg.wl", src!(4:10-4:11).into(), OccurrenceKind::Use)]
+    );
+
+    // Pattern names are not indexed as uses of the symbol.
+    assert_eq!(
+        index.get("Pkg`Private`x").unwrap().uses,
+        vec![occ("Pkg.wl", src!(4:12-4:13).into(), OccurrenceKind::Use)]
+    );
+}
+
+#[test]
+fn test_cross_file_uses() {
+    let project = project(&[
+        ("Pkg.wl", PACKAGE),
+        ("Main.wl", "Needs[\"Pkg`\"]\nf[1]\nPkg`Private`g[2]"),
+    ]);
+
+    let index = SymbolIndex::build(&project);
+
+    assert_eq!(
+        index.get("Pkg`f").unwrap().uses,
+        vec![occ("Main.wl", src!(2:1-2:2).into(), OccurrenceKind::Use)]
+    );
+    assert_eq!(
+        index.get("Pkg`Private`g").unwrap().uses,
+        vec![
+            occ("Main.wl", src!(3:1-3:14).into(), OccurrenceKind::Use),
+            occ("Pkg.wl", src!(4:10-4:11).into(), OccurrenceKind::Use),
+        ]
+    );
+
+    assert_eq!(
+        index.qualified_name_at(Path::new("Main.wl"), src!(2:1-2:2).into()),
+        Some("Pkg`f")
+    );
+}
+
+#[test]
+fn test_global_definitions() {
+    let project = project(&[("A.wl", "x = 1; {y, z} = {2, 3}\nh /: k[h] = 4")]);
+
+    let index = SymbolIndex::build(&project);
+
+    for name in ["Global`x", "Global`y", "Global`z", "Global`h"] {
+        assert_eq!(
+            index.get(name).unwrap().definitions.len(),
+            1,
+            "definitions of {name}"
+        );
+    }
+
+    assert_eq!(index.get("Global`h").unwrap().uses.len(), 1);
+}
+
+#[test]
+fn test_incremental_update() {
+    let mut project =
+        project(&[("Pkg.wl", PACKAGE), ("Main.wl", "Needs[\"Pkg`\"]\nf[1]")]);
+
+    let mut index = SymbolIndex::build(&project);
+
+    assert_eq!(index.get("Pkg`f").unwrap().uses.len(), 1);
+
+    let file = project.reparse_file(
+        "Main.wl",
+        "Needs[\"Pkg`\"]\nf[1] + f[2]",
+        &ParseOptions::default(),
+    );
+    index.update_file(file);
+
+    assert_eq!(index.get("Pkg`f").unwrap().uses.len(), 2);
+
+    // Removing the export changes how uses in other files resolve.
+    let file = project.reparse_file(
+        "Pkg.wl",
+        "BeginPackage[\"Pkg`\"]\nEndPackage[]",
+        &ParseOptions::default(),
+    );
+    index.update_file(file);
+
+    assert!(index.get("Pkg`f").is_none());
+    assert_eq!(index.get("Global`f").unwrap().uses.len(), 2);
+
+    index.remove_file(Path::new("Main.wl"));
+
+    assert!(index.get("Global`f").is_none());
+}
+
+#[test]
+fn test_definition_of() {
+    let project = project(&[
+        ("Pkg.wl", PACKAGE),
+        (
+            "Other.wl",
+            "BeginPackage[\"Other`\", {\"Pkg`\"}]\nf[g]\nEndPackage[]",
+        ),
+    ]);
+
+    let index = SymbolIndex::build(&project);
+
+    // Use of an exported symbol through the BeginPackage needs list
+    let occ = index
+        .occurrence_at(Path::new("Other.wl"), src!(2:1).into())
+        .unwrap();
+
+    assert_eq!(
+        index.definition_of(occ),
+        vec![
+            (PathBuf::from("Pkg.wl"), src!(2:1-2:2).into()),
+            (PathBuf::from("Pkg.wl"), src!(4:1-4:2).into()),
+        ]
+    );
+
+    // Private package symbols are not visible outside the package
+    let occ = index
+        .occurrence_at(Path::new("Other.wl"), src!(2:3).into())
+        .unwrap();
+
+    assert_eq!(index.definition_of(occ), vec![]);
+
+    // Symbols resolved from another package are not re-exported
+    assert_eq!(index.get("Other`f"), None);
+
+    // Use of a private symbol inside the package
+    let occ = index
+        .occurrence_at(Path::new("Pkg.wl"), src!(4:10).into())
+        .unwrap();
+
+    assert_eq!(
+        index.definition_of(occ),
+        vec![(PathBuf::from("Pkg.wl"), src!(5:1-5:2).into())]
+    );
+}
+
+#[test]
+fn test_rename() {
+    let package = r#"BeginPackage["Pkg`"]
+f::usage = "f[x]"
+`Private`g
+Begin["`Private`"]
+f[x_] := g[x]
+g[y_] := y + 1
+End[]
+EndPackage[]"#;
+
+    let project = project(&[
+        ("Pkg.wl", package),
+        ("Main.wl", "Needs[\"Pkg`\"]\nPkg`f[1] + f[2]"),
+    ]);
+
+    let index = SymbolIndex::build(&project);
+
+    let edit = |path: &str, span: Span, new_text: &str| RenameEdit {
+        path: PathBuf::from(path),
+        span,
+        new_text: new_text.to_owned(),
+    };
+
+    // Short and fully qualified uses of an exported symbol
+    assert_eq!(
+        index.rename("Pkg`f", "h"),
+        vec![
+            edit("Main.wl", src!(2:5-2:6).into(), "h"),
+            edit("Main.wl", src!(2:12-2:13).into(), "h"),
+            edit("Pkg.wl", src!(2:1-2:2).into(), "h"),
+            edit("Pkg.wl", src!(5:1-5:2).into(), "h"),
+        ]
+    );
+
+    // Uses relative to the package context
+    assert_eq!(
+        index.rename("Pkg`Private`g", "helper"),
+        vec![
+            edit("Pkg.wl", src!(3:10-3:11).into(), "helper"),
+            edit("Pkg.wl", src!(5:10-5:11).into(), "helper"),
+            edit("Pkg.wl", src!(6:1-6:2).into(), "helper"),
+        ]
+    );
+
+    assert_eq!(index.rename("Global`missing", "x"), vec![]);
+}
+
+#[test]
+fn test_save_and_load() {
+    let project =
+        project(&[("Pkg.wl", PACKAGE), ("Main.wl", "Needs[\"Pkg`\"]\nf[1]")]);
+
+    let index = SymbolIndex::build(&project);
+
+    let mut bytes = Vec::new();
+    index.save(&mut bytes).unwrap();
+
+    let loaded = SymbolIndex::load(bytes.as_slice()).unwrap();
+
+    let mut names: Vec<&str> = index.symbols().map(|(name, _)| name).collect();
+    names.sort();
+
+    for name in names {
+        assert_eq!(loaded.get(name), index.get(name), "entry for {name}");
+    }
+    assert_eq!(loaded.symbols().count(), index.symbols().count());
+
+    // Saving is deterministic
+    let mut resaved = Vec::new();
+    loaded.save(&mut resaved).unwrap();
+    assert_eq!(resaved, bytes);
+
+    assert!(!loaded.is_stale(Path::new("Pkg.wl"), PACKAGE));
+    assert!(loaded.is_stale(Path::new("Pkg.wl"), "f[x_] := x"));
+    assert!(loaded.is_stale(Path::new("New.wl"), ""));
+
+    let err = SymbolIndex::load(&b"not an index"[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // A string length larger than the input is an error, not an allocation
+    // of that size.
+    let mut corrupt = b"WLIX".to_vec();
+    corrupt.extend_from_slice(&1u32.to_le_bytes());
+    corrupt.push(1);
+    corrupt.extend_from_slice(&[0xff; 7]);
+    corrupt.push(0x7f);
+    corrupt.extend_from_slice(b"abc");
+
+    let err = SymbolIndex::load(corrupt.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
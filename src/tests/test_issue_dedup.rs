@@ -0,0 +1,197 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    issue::{
+        self, Annotation, AnnotationKind, CodeAction, Issue, IssueTag, Severity,
+    },
+    macros::src,
+    parse_cst,
+    source::{LineColumnSpan, Source, Span},
+    ParseOptions, RecoveryMode,
+};
+
+
+fn issue(tag: IssueTag, msg: &str, sev: Severity, src: Source) -> Issue {
+    Issue::syntax(tag, msg.to_owned(), sev, src, 1.0)
+}
+
+fn span(span: LineColumnSpan) -> Source {
+    Source::Span(Span::from(span))
+}
+
+#[test]
+fn test_deduplicate_errors() {
+    let missing = issue(
+        IssueTag::SyntaxError,
+        "Missing closer.",
+        Severity::Fatal,
+        span(src!(1:1-1:4)),
+    );
+
+    let other = issue(
+        IssueTag::SyntaxError,
+        "Expected an operand.",
+        Severity::Fatal,
+        span(src!(1:6-1:6)),
+    );
+
+    let deduplicated = issue::deduplicate([
+        missing.clone(),
+        other.clone(),
+        // Reported again by a later stage.
+        missing.clone(),
+        issue(
+            IssueTag::Ambiguous,
+            "Unexpected parse.",
+            Severity::Error,
+            span(src!(1:1-1:4)),
+        ),
+    ]);
+
+    assert_eq!(
+        deduplicated,
+        [
+            missing.with_annotations(vec![Annotation::note(
+                "Unexpected parse.".to_owned(),
+                span(src!(1:1-1:4)),
+            )]),
+            other,
+        ]
+    );
+}
+
+#[test]
+fn test_deduplicate_primary() {
+    let src = span(src!(2:1-2:5));
+
+    let warning = issue(
+        IssueTag::Ambiguous,
+        "Warning.",
+        Severity::Warning,
+        src.clone(),
+    );
+
+    // The most severe error is kept, even if it was reported last.
+    let deduplicated = issue::deduplicate([
+        issue(
+            IssueTag::SyntaxError,
+            "Error.",
+            Severity::Error,
+            src.clone(),
+        ),
+        warning.clone(),
+        issue(
+            IssueTag::ParserFailure,
+            "Fatal.",
+            Severity::Fatal,
+            src.clone(),
+        ),
+    ]);
+
+    assert_eq!(deduplicated.len(), 2);
+    assert_eq!(deduplicated[0].msg, "Fatal.");
+    assert_eq!(
+        deduplicated[0].annotations,
+        [Annotation::note("Error.".to_owned(), src.clone())]
+    );
+
+    // Warnings are only collapsed into warnings with the same tag.
+    assert_eq!(deduplicated[1], warning);
+
+    // Of equally severe issues, the most confident is kept.
+    let mut confident = issue(
+        IssueTag::SyntaxError,
+        "Confident.",
+        Severity::Fatal,
+        src.clone(),
+    );
+    confident.val = 2.0;
+
+    let deduplicated = issue::deduplicate([
+        issue(
+            IssueTag::SyntaxError,
+            "First.",
+            Severity::Fatal,
+            src.clone(),
+        ),
+        confident,
+    ]);
+
+    assert_eq!(deduplicated.len(), 1);
+    assert_eq!(deduplicated[0].msg, "Confident.");
+}
+
+#[test]
+fn test_deduplicate_merges_actions() {
+    let src = src!(1:1-1:3);
+
+    let mut with_action = issue(
+        IssueTag::UnexpectedCharacter,
+        "Unexpected character.",
+        Severity::Error,
+        span(src),
+    );
+    with_action.actions.push(CodeAction::delete_text(
+        "Delete".to_owned(),
+        Span::from(src),
+    ));
+
+    let deduplicated = issue::deduplicate([
+        issue(
+            IssueTag::SyntaxError,
+            "Syntax error.",
+            Severity::Fatal,
+            span(src),
+        ),
+        with_action.clone(),
+    ]);
+
+    assert_eq!(deduplicated.len(), 1);
+    assert_eq!(deduplicated[0].tag, IssueTag::SyntaxError);
+    assert_eq!(deduplicated[0].actions, with_action.actions);
+    assert_eq!(deduplicated[0].annotations[0].kind, AnnotationKind::Note);
+}
+
+#[test]
+fn test_deduplicate_unknown_source() {
+    let unknown = issue(
+        IssueTag::SyntaxError,
+        "Error.",
+        Severity::Fatal,
+        Source::Unknown,
+    );
+
+    let deduplicated = issue::deduplicate([unknown.clone(), unknown.clone()]);
+
+    assert_eq!(deduplicated, [unknown.clone(), unknown]);
+}
+
+#[test]
+fn test_diagnostics() {
+    let opts = ParseOptions::default().recovery(RecoveryMode::Recover);
+
+    let result = parse_cst("", &opts);
+
+    let diagnostics = result.diagnostics(&opts);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].tag, IssueTag::ParserFailure);
+    assert!(diagnostics[0]
+        .annotations
+        .iter()
+        .all(|annotation| annotation.kind == AnnotationKind::Note));
+
+    let all = result.diagnostics(&opts.clone().deduplicate_issues(false));
+
+    assert_eq!(all.len(), diagnostics[0].annotations.len() + 1);
+
+    // Different problems are still reported separately.
+    let opts = ParseOptions::default();
+
+    let diagnostics = parse_cst("f[a +", &opts).diagnostics(&opts);
+
+    let msgs: Vec<&str> =
+        diagnostics.iter().map(|issue| issue.msg.as_str()).collect();
+
+    assert_eq!(msgs, ["Missing closer.", "Expected an operand."]);
+}
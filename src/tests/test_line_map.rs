@@ -0,0 +1,343 @@
+use std::num::NonZeroU32;
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    macros::src,
+    parse_cst,
+    source::{
+        AnySpan, ByteSpan, CharIndexSpan, ColumnUnit, LineColumn,
+        LineColumnSpan, LineMap, Location, Span, SpanConversionError,
+    },
+    NodeSeq, ParseOptions, SourceConvention,
+};
+
+const INPUTS: &[&str] = &[
+    "f[x_] :=\n\tModule[{y},\t(* tab *)\r\n\t\ty = x;\r  \n\t\ty\n\t]",
+    "\"\u{03B1}\tb\" <> \"\u{1F600}\"\n\n",
+    "a\r\n\tb\u{2022}\rc\n",
+    "\u{1F600}\t\u{03B1}\t\tx",
+    "",
+];
+
+/// Spans of every token of `input`, tokenized using `opts`.
+fn token_spans(input: &str, opts: &ParseOptions) -> Vec<Span> {
+    let NodeSeq(tokens) = crate::tokenize(input, opts);
+
+    tokens.iter().map(|token| token.src).collect()
+}
+
+#[test]
+fn test_line_map_convert() {
+    let opts = ParseOptions::default();
+
+    let map = LineMap::new("a\r\n\tb\u{2022}\rc\n", &opts);
+
+    assert_eq!(map.line_count(), 4);
+
+    assert_eq!(
+        map.convert(
+            Span::from(src!(2:5-2:6)),
+            SourceConvention::CharacterIndex
+        ),
+        Some(Span::from_character_span(5, 6))
+    );
+    assert_eq!(
+        map.convert(
+            Span::from_character_span(5, 8),
+            SourceConvention::LineColumn
+        ),
+        Some(Span::from(src!(2:5-3:1)))
+    );
+
+    // Columns inside the expansion of a tab are the offset of the tab.
+    assert_eq!(map.offset(Location::LineColumn(src!(2:3))), Some(3));
+
+    // Locations outside of the input.
+    assert_eq!(map.offset(Location::LineColumn(src!(2:8))), None);
+    assert_eq!(map.offset(Location::LineColumn(src!(5:1))), None);
+    assert_eq!(map.offset(Location::CharacterIndex(13)), None);
+    assert_eq!(map.location(100, SourceConvention::LineColumn), None);
+}
+
+#[test]
+fn test_line_map_matches_tokenizer() {
+    for input in INPUTS {
+        let line_column = ParseOptions::default().tab_width(2);
+        let character_index = ParseOptions::default()
+            .tab_width(2)
+            .source_convention(SourceConvention::CharacterIndex);
+
+        let map = LineMap::new(input, &line_column);
+
+        let converted: Vec<Option<Span>> = token_spans(input, &line_column)
+            .into_iter()
+            .map(|span| map.convert(span, SourceConvention::CharacterIndex))
+            .collect();
+
+        let expected: Vec<Option<Span>> = token_spans(input, &character_index)
+            .into_iter()
+            .map(Some)
+            .collect();
+
+        assert_eq!(converted, expected, "input: {input:?}");
+
+        let converted: Vec<Option<Span>> = token_spans(input, &character_index)
+            .into_iter()
+            .map(|span| map.convert(span, SourceConvention::LineColumn))
+            .collect();
+
+        let expected: Vec<Option<Span>> = token_spans(input, &line_column)
+            .into_iter()
+            .map(Some)
+            .collect();
+
+        assert_eq!(converted, expected, "input: {input:?}");
+    }
+}
+
+#[test]
+fn test_line_map_round_trip() {
+    for input in INPUTS {
+        let opts = ParseOptions::default().tab_width(2);
+
+        let map = LineMap::new(input, &opts);
+
+        for offset in 0..=input.len() + 1 {
+            // The `\n` of `\r\n` is not the start of a line or column.
+            if input
+                .get(..=offset)
+                .is_some_and(|before| before.ends_with("\r\n"))
+            {
+                continue;
+            }
+
+            for convention in [
+                SourceConvention::LineColumn,
+                SourceConvention::CharacterIndex,
+            ] {
+                let loc = map.location(offset, convention);
+
+                assert_eq!(
+                    loc.is_some(),
+                    input.is_char_boundary(offset),
+                    "input: {input:?}, offset: {offset}"
+                );
+
+                if let Some(loc) = loc {
+                    assert_eq!(map.offset(loc), Some(offset), "{loc:?}");
+                }
+            }
+        }
+
+        // Every column of every line, including columns inside of tabs and
+        // past the end of the line.
+        for line in 1..=map.line_count() as u32 + 1 {
+            for column in 1..=input.len() as u32 + 2 {
+                let loc = Location::LineColumn(LineColumn(
+                    NonZeroU32::new(line).unwrap(),
+                    NonZeroU32::new(column).unwrap(),
+                ));
+
+                let Some(offset) = map.offset(loc) else {
+                    continue;
+                };
+
+                // Columns inside of a tab are the offset of the tab.
+                let Some(Location::LineColumn(found)) =
+                    map.location(offset, SourceConvention::LineColumn)
+                else {
+                    panic!("input: {input:?}, location: {loc:?}");
+                };
+
+                assert_eq!(found.0.get(), line, "input: {input:?}");
+                assert!(found.1.get() <= column, "input: {input:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_line_map_convert_offset() {
+    use ColumnUnit::*;
+
+    let input = "\u{03B1}\t\u{1F600}b\r\nc";
+
+    let map = LineMap::new(input, &ParseOptions::default());
+
+    assert_eq!(map.len(Utf8), 11);
+    assert_eq!(map.len(Char), 7);
+    assert_eq!(map.len(Utf16), 8);
+
+    // Offsets of `b`.
+    assert_eq!(map.convert_offset(7, Utf8, Char), Some(3));
+    assert_eq!(map.convert_offset(7, Utf8, Utf16), Some(4));
+    assert_eq!(map.convert_offset(3, Char, Utf16), Some(4));
+    assert_eq!(map.convert_offset(4, Utf16, Utf8), Some(7));
+
+    // Inside of the emoji, which is 2 UTF-16 units and 4 UTF-8 bytes.
+    assert_eq!(map.convert_offset(3, Utf16, Char), None);
+    assert_eq!(map.convert_offset(4, Utf8, Utf16), None);
+
+    // Past the end of the input.
+    assert_eq!(map.convert_offset(11, Utf8, Utf16), Some(8));
+    assert_eq!(map.convert_offset(12, Utf8, Utf16), None);
+    assert_eq!(map.convert_offset(8, Char, Utf8), None);
+
+    for (char_offset, (offset, c)) in input.char_indices().enumerate() {
+        let utf16 = input[..offset].encode_utf16().count();
+
+        assert_eq!(map.convert_offset(offset, Utf8, Char), Some(char_offset));
+        assert_eq!(map.convert_offset(utf16, Utf16, Utf8), Some(offset));
+        assert_eq!(
+            map.convert_offset(char_offset, Char, Utf16),
+            Some(utf16),
+            "{c:?}"
+        );
+    }
+}
+
+#[test]
+fn test_line_map_column_units() {
+    let input = "\u{03B1}\t\u{1F600}b\r\nc";
+
+    let map = LineMap::new(input, &ParseOptions::default());
+
+    // Offset of `b`.
+    let b = input.find('b').unwrap();
+
+    assert_eq!(map.line_column(b, ColumnUnit::Char), Some(src!(1:4)));
+    assert_eq!(map.line_column(b, ColumnUnit::Utf8), Some(src!(1:8)));
+    assert_eq!(map.line_column(b, ColumnUnit::Utf16), Some(src!(1:5)));
+
+    for unit in [ColumnUnit::Char, ColumnUnit::Utf8, ColumnUnit::Utf16] {
+        for (offset, _) in input.char_indices().chain([(input.len(), ' ')]) {
+            // The `\n` of `\r\n` is not the start of a line or column.
+            if offset == input.find('\n').unwrap() {
+                continue;
+            }
+
+            let pos = map.line_column(offset, unit).unwrap();
+
+            assert_eq!(map.offset_at(pos, unit), Some(offset), "{unit:?}");
+        }
+    }
+
+    // Inside of the emoji, which is 2 UTF-16 units and 4 UTF-8 bytes.
+    assert_eq!(map.offset_at(src!(1:4), ColumnUnit::Utf16), None);
+    assert_eq!(map.offset_at(src!(1:5), ColumnUnit::Utf8), None);
+    assert_eq!(map.line_column(b - 1, ColumnUnit::Char), None);
+
+    // Past the end of a line.
+    assert_eq!(map.offset_at(src!(1:6), ColumnUnit::Char), None);
+    assert_eq!(
+        map.offset_at(src!(2:2), ColumnUnit::Char),
+        Some(input.len())
+    );
+    assert_eq!(map.offset_at(src!(3:1), ColumnUnit::Char), None);
+}
+
+#[test]
+fn test_line_map_spans() {
+    let map = LineMap::new("f[x]\n\tg[y]", &ParseOptions::default());
+
+    // `y`, after a tab that is 4 columns wide.
+    let span = Span::from(src!(2:7-2:8));
+
+    assert_eq!(map.span_offsets(span), Some(8..9));
+    assert_eq!(
+        map.span_offsets(Span::from_character_span(9, 10)),
+        Some(8..9)
+    );
+
+    assert_eq!(map.span(8..9, SourceConvention::LineColumn), Some(span));
+    assert_eq!(
+        map.convert(span, SourceConvention::CharacterIndex),
+        Some(Span::from_character_span(9, 10))
+    );
+
+    assert_eq!(map.span_offsets(Span::from(src!(2:7-3:1))), None);
+    assert_eq!(map.span(8..20, SourceConvention::LineColumn), None);
+}
+
+#[test]
+fn test_line_map_convert_span() {
+    let input = "\u{03B1} +\n\t\u{1F600}";
+
+    let map = LineMap::new(input, &ParseOptions::default());
+
+    // The emoji.
+    let bytes = ByteSpan { start: 6, end: 10 };
+    let line_column: LineColumnSpan = src!(2:5-2:6);
+    let char_index: CharIndexSpan = src!(6..7);
+
+    assert_eq!(&input[bytes.to_range()], "\u{1F600}");
+
+    for span in [
+        AnySpan::from(bytes),
+        AnySpan::from(line_column),
+        AnySpan::from(char_index),
+    ] {
+        assert_eq!(map.byte_span(span), Some(bytes), "{span}");
+        assert_eq!(map.convert_span(span), Some(bytes));
+        assert_eq!(map.convert_span(span), Some(line_column));
+        assert_eq!(map.convert_span(span), Some(char_index));
+    }
+
+    // Inside of the emoji.
+    assert_eq!(map.byte_span(ByteSpan { start: 7, end: 10 }), None);
+    assert_eq!(
+        map.convert_span::<CharIndexSpan>(ByteSpan::from(6..9)),
+        None
+    );
+
+    // Past the end of the input.
+    assert_eq!(map.byte_span(ByteSpan { start: 10, end: 11 }), None);
+    assert_eq!(map.convert_span::<ByteSpan>(src!(3:1-3:2)), None);
+
+    // Start after end.
+    assert_eq!(map.byte_span(ByteSpan { start: 4, end: 0 }), None);
+}
+
+#[test]
+fn test_span_conventions() {
+    let input = "a +\n b";
+
+    let line_column = parse_cst(input, &ParseOptions::default()).syntax;
+    let char_index = parse_cst(
+        input,
+        &ParseOptions::default()
+            .source_convention(SourceConvention::CharacterIndex),
+    )
+    .syntax;
+
+    let (line_column, char_index) =
+        (line_column.get_source(), char_index.get_source());
+
+    assert_eq!(line_column.convention(), SourceConvention::LineColumn);
+    assert_eq!(char_index.convention(), SourceConvention::CharacterIndex);
+
+    // The same span, whichever convention the input was parsed with.
+    let map = LineMap::new(input, &ParseOptions::default());
+
+    assert_eq!(map.byte_span(line_column), Some(ByteSpan::from(0..6)));
+    assert_eq!(map.byte_span(line_column), map.byte_span(char_index));
+
+    let expected: LineColumnSpan = src!(1:1-2:3);
+
+    assert_eq!(LineColumnSpan::try_from(line_column), Ok(expected));
+    assert_eq!(
+        CharIndexSpan::try_from(line_column),
+        Err(SpanConversionError::NotCharIndex(AnySpan::from(expected)))
+    );
+    assert_eq!(CharIndexSpan::try_from(char_index), Ok(src!(1..7)));
+
+    assert_eq!(
+        Span::try_from(AnySpan::from(ByteSpan::from(0..6))),
+        Err(SpanConversionError::Byte(ByteSpan::from(0..6)))
+    );
+    assert_eq!(
+        Span::try_from(AnySpan::from(char_index)),
+        Ok(Span::from(src!(1..7)))
+    );
+}
@@ -0,0 +1,484 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    issue::{CodeAction, CodeActionKind, IssueTag, Severity},
+    lint::{
+        self, DynamicCodeOptions, DynamicCodeRule, LintConfig, LintRule,
+        SideEffectOptions,
+    },
+    macros::src,
+    parse_ast_seq, parse_cst_seq,
+    source::{Source, Span},
+    ParseOptions,
+};
+
+fn side_effects(
+    input: &str,
+    opts: &SideEffectOptions,
+) -> Vec<(IssueTag, String, Source)> {
+    let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+
+    lint::top_level_side_effects(&ast, opts)
+        .into_iter()
+        .map(|issue| (issue.tag, issue.msg, issue.src))
+        .collect()
+}
+
+#[test]
+fn test_top_level_side_effects() {
+    let opts = SideEffectOptions::default();
+
+    // Definitions, declarations, and context management are allowed.
+    assert_eq!(
+        side_effects(
+            r#"BeginPackage["Pkg`", {"Other`"}]
+f::usage = "f[x]"
+g
+Begin["`Private`"]
+Needs["Util`"]
+SetAttributes[f, HoldAll]
+f[x_] := x; g[y_] = y;
+f /: h[f] := 1
+Protect[f]
+End[]
+EndPackage[]"#,
+            &opts
+        ),
+        vec![]
+    );
+
+    assert_eq!(
+        side_effects("f[x_] := x\nPrint[f[1]]\n1 + 2", &opts),
+        vec![
+            (
+                IssueTag::TopLevelSideEffect,
+                "``Print`` is called when the file is loaded.".to_owned(),
+                Source::Span(Span::from(src!(2:1-2:12)))
+            ),
+            (
+                IssueTag::TopLevelSideEffect,
+                "``Plus`` is called when the file is loaded.".to_owned(),
+                Source::Span(Span::from(src!(3:1-3:6)))
+            ),
+        ]
+    );
+
+    // Statements of a compound expression are checked individually.
+    assert_eq!(
+        side_effects("x = 1; Print[x]; y = 2", &opts),
+        vec![(
+            IssueTag::TopLevelSideEffect,
+            "``Print`` is called when the file is loaded.".to_owned(),
+            Source::Span(Span::from(src!(1:8-1:16)))
+        )]
+    );
+
+    // Qualified heads are not builtins.
+    assert_eq!(
+        side_effects("Pkg`Set[x, 1]", &opts),
+        vec![(
+            IssueTag::TopLevelSideEffect,
+            "``Pkg`Set`` is called when the file is loaded.".to_owned(),
+            Source::Span(Span::from(src!(1:1-1:14)))
+        )]
+    );
+
+    assert_eq!(side_effects("System`SetDelayed[f[x_], x]", &opts), vec![]);
+}
+
+#[test]
+fn test_top_level_side_effects_options() {
+    let input = "Print[1]\nPkg`init[]";
+
+    let opts = SideEffectOptions::default().allow("Pkg`init");
+
+    assert_eq!(
+        side_effects(input, &opts),
+        vec![(
+            IssueTag::TopLevelSideEffect,
+            "``Print`` is called when the file is loaded.".to_owned(),
+            Source::Span(Span::from(src!(1:1-1:9)))
+        )]
+    );
+
+    let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+
+    let issues = lint::top_level_side_effects(
+        &ast,
+        &opts.allow("Print").severity(Severity::Remark),
+    );
+    assert_eq!(issues, vec![]);
+
+    let issues = lint::top_level_side_effects(
+        &ast,
+        &SideEffectOptions::default().severity(Severity::Remark),
+    );
+    assert_eq!(
+        issues.iter().map(|issue| issue.sev).collect::<Vec<_>>(),
+        vec![Severity::Remark, Severity::Remark]
+    );
+}
+
+fn dynamic_code(
+    input: &str,
+    opts: &DynamicCodeOptions,
+) -> Vec<(IssueTag, Severity, Source)> {
+    let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+
+    lint::dynamic_code_execution(&ast, opts)
+        .into_iter()
+        .map(|issue| (issue.tag, issue.sev, issue.src))
+        .collect()
+}
+
+#[test]
+fn test_dynamic_code_execution() {
+    let opts = DynamicCodeOptions::default();
+
+    // Literal arguments are allowed.
+    assert_eq!(
+        dynamic_code(
+            r#"ToExpression["1 + 1"]
+Symbol["Pkg`" <> "x"]
+Get["init.wl"]
+Get[File["init.wl"]]
+<< init.wl
+Pkg`Get[path]"#,
+            &opts
+        ),
+        vec![]
+    );
+
+    // Calls are found anywhere in the expression.
+    assert_eq!(
+        dynamic_code(
+            r#"f[s_] := ToExpression[s]
+g[n_] := {Symbol[n]}
+Get[dir <> "x.m"]"#,
+            &opts
+        ),
+        vec![
+            (
+                IssueTag::DynamicToExpression,
+                Severity::Error,
+                Source::Span(Span::from(src!(1:10-1:25)))
+            ),
+            (
+                IssueTag::DynamicSymbol,
+                Severity::Warning,
+                Source::Span(Span::from(src!(2:11-2:20)))
+            ),
+            (
+                IssueTag::DynamicGet,
+                Severity::Warning,
+                Source::Span(Span::from(src!(3:1-3:18)))
+            ),
+        ]
+    );
+
+    assert_eq!(
+        dynamic_code("s // ToExpression", &opts),
+        vec![(
+            IssueTag::DynamicToExpression,
+            Severity::Error,
+            Source::Span(Span::from(src!(1:1-1:18)))
+        )]
+    );
+}
+
+#[test]
+fn test_dynamic_code_execution_options() {
+    let input = "ToExpression[s]; Symbol[n]; Get[p]";
+
+    let opts = DynamicCodeOptions::default()
+        .severity(DynamicCodeRule::ToExpression, Severity::Remark)
+        .severity(DynamicCodeRule::Get, Severity::Error)
+        .disable(DynamicCodeRule::Symbol);
+
+    assert_eq!(
+        dynamic_code(input, &opts)
+            .into_iter()
+            .map(|(tag, sev, _)| (tag, sev))
+            .collect::<Vec<_>>(),
+        vec![
+            (IssueTag::DynamicToExpression, Severity::Remark),
+            (IssueTag::DynamicGet, Severity::Error),
+        ]
+    );
+
+    // A disabled rule can be enabled again by setting its severity.
+    let opts = opts.severity(DynamicCodeRule::Symbol, Severity::Warning);
+
+    assert_eq!(dynamic_code(input, &opts).len(), 3);
+}
+
+fn lints(input: &str, config: &LintConfig) -> Vec<(IssueTag, Source)> {
+    let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+
+    lint::lint(&cst, config)
+        .into_iter()
+        .map(|issue| (issue.tag, issue.src))
+        .collect()
+}
+
+#[test]
+fn test_lint() {
+    let config = LintConfig::default();
+
+    assert_eq!(
+        lints(
+            "f[x_] := x^2
+g[x_] = 1
+Unprotect[N]; N[x_] := 1
+Module[{Pi = 1, y}, {Pi,
+y}]
+Pkg`Sin = 1",
+            &config
+        ),
+        vec![]
+    );
+
+    let input = "h[x_] = x + 1
+System`Sin[x_] := 0
+Pi /: f[Pi] = 1
+Block[{}, {a
+b}]";
+
+    assert_eq!(
+        lints(input, &config),
+        vec![
+            (
+                IssueTag::SuspiciousSet,
+                Source::Span(Span::from(src!(1:1-1:14)))
+            ),
+            (
+                IssueTag::ProtectedAssignment,
+                Source::Span(Span::from(src!(2:1-2:20)))
+            ),
+            (
+                IssueTag::ProtectedAssignment,
+                Source::Span(Span::from(src!(3:1-3:16)))
+            ),
+            (
+                IssueTag::EmptyScopeVariables,
+                Source::Span(Span::from(src!(4:1-5:4)))
+            ),
+            (
+                IssueTag::ImplicitTimesAcrossLines,
+                Source::Span(Span::from(src!(5:1-5:2)))
+            ),
+        ]
+    );
+
+    //
+    // Code actions
+    //
+
+    let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+
+    let actions: Vec<Vec<CodeAction>> = lint::lint(&cst, &config)
+        .into_iter()
+        .map(|issue| issue.actions)
+        .collect();
+
+    assert_eq!(
+        actions,
+        vec![
+            vec![CodeAction::replace_text(
+                "Replace ``=`` with ``:=``".to_owned(),
+                Span::from(src!(1:7-1:8)),
+                ":=".to_owned(),
+            )],
+            vec![],
+            vec![],
+            vec![CodeAction::replace_text(
+                "Replace ``Block`` with its body".to_owned(),
+                Span::from(src!(4:1-5:4)),
+                "{a\nb}".to_owned(),
+            )],
+            vec![CodeAction::insert_text(
+                "Insert ``*``".to_owned(),
+                Span::from(src!(5:1-5:1)),
+                "*".to_owned(),
+            )],
+        ]
+    );
+
+    //
+    // Abstract syntax
+    //
+
+    // Implicit multiplications can't be found, and no actions are offered.
+    let ast = parse_ast_seq(input, &ParseOptions::default()).syntax;
+
+    let issues: Vec<_> = lint::lint(&ast, &config)
+        .into_iter()
+        .map(|issue| (issue.tag, issue.actions.len()))
+        .collect();
+
+    assert_eq!(
+        issues,
+        vec![
+            (IssueTag::SuspiciousSet, 0),
+            (IssueTag::ProtectedAssignment, 0),
+            (IssueTag::ProtectedAssignment, 0),
+            (IssueTag::EmptyScopeVariables, 0),
+        ]
+    );
+}
+
+#[test]
+fn test_lint_config() {
+    let input = "f[x_] = x; E = 1; With[{}, 1]";
+
+    let config = LintConfig::default()
+        .severity(LintRule::EmptyScopeVariables, Severity::Warning)
+        .disable(LintRule::SuspiciousSet)
+        .protect("f");
+
+    let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+
+    let issues: Vec<_> = lint::lint(&cst, &config)
+        .into_iter()
+        .map(|issue| (issue.tag, issue.sev))
+        .collect();
+
+    assert_eq!(
+        issues,
+        vec![
+            (IssueTag::ProtectedAssignment, Severity::Error),
+            (IssueTag::ProtectedAssignment, Severity::Error),
+            (IssueTag::EmptyScopeVariables, Severity::Warning),
+        ]
+    );
+}
+
+#[test]
+fn test_lint_constant_conditions() {
+    let input = r#"a = If[True, 1, 2]
+b = If[!(1 < 2), x]
+c = Which[False, 1, y, 2, True, 3, z, 4]
+d = {1 == 1, "a" =!= "b", x == 1, 1 == 1.0, 1 === 1.0}
+e = Which[x, 1, True, 2]"#;
+
+    let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+
+    let issues: Vec<_> = lint::lint(&cst, &LintConfig::default())
+        .into_iter()
+        .map(|issue| (issue.tag, issue.msg, issue.src, issue.actions))
+        .collect();
+
+    let condition = |msg: &str, src: Span, action: CodeAction| {
+        (
+            IssueTag::ConstantCondition,
+            msg.to_owned(),
+            Source::Span(src),
+            vec![action],
+        )
+    };
+
+    let comparison = |value: &str, src: Span| {
+        (
+            IssueTag::ConstantComparison,
+            format!("Comparison is always ``{value}``."),
+            Source::Span(src),
+            vec![CodeAction::replace_text(
+                format!("Replace with ``{value}``"),
+                src,
+                value.to_owned(),
+            )],
+        )
+    };
+
+    assert_eq!(
+        issues,
+        vec![
+            condition(
+                "Condition of ``If`` is always ``True``.",
+                Span::from(src!(1:8-1:12)),
+                CodeAction::replace_text(
+                    "Replace ``If`` with its ``True`` branch".to_owned(),
+                    Span::from(src!(1:5-1:19)),
+                    "1".to_owned(),
+                ),
+            ),
+            condition(
+                "Condition of ``If`` is always ``False``.",
+                Span::from(src!(2:8-2:16)),
+                CodeAction::replace_text(
+                    "Replace ``If`` with its ``False`` branch".to_owned(),
+                    Span::from(src!(2:5-2:20)),
+                    "Null".to_owned(),
+                ),
+            ),
+            condition(
+                "Condition of ``Which`` arm is always ``False``.",
+                Span::from(src!(3:11-3:16)),
+                CodeAction::delete_text(
+                    "Remove this arm".to_owned(),
+                    Span::from(src!(3:11-3:21)),
+                ),
+            ),
+            condition(
+                "Condition of ``Which`` arm is always ``True``.",
+                Span::from(src!(3:27-3:31)),
+                CodeAction::delete_text(
+                    "Remove the arms after this arm".to_owned(),
+                    Span::from(src!(3:34-3:40)),
+                ),
+            ),
+            comparison("True", Span::from(src!(4:6-4:12))),
+            comparison("True", Span::from(src!(4:14-4:25))),
+            comparison("True", Span::from(src!(4:35-4:43))),
+            comparison("False", Span::from(src!(4:45-4:54))),
+        ]
+    );
+
+    // Removing the last arm also removes the comma before it.
+    let issues = lint::lint(
+        &parse_cst_seq("Which[x, 1, False, 2]", &ParseOptions::default())
+            .syntax,
+        &LintConfig::default(),
+    );
+
+    assert_eq!(
+        issues[0].actions,
+        vec![CodeAction::delete_text(
+            "Remove this arm".to_owned(),
+            Span::from(src!(1:11-1:21)),
+        )]
+    );
+}
+
+#[test]
+fn test_lint_constant_if_parentheses() {
+    let replacement = |input: &str| -> String {
+        let cst = parse_cst_seq(input, &ParseOptions::default()).syntax;
+
+        let issue = lint::lint(&cst, &LintConfig::default())
+            .into_iter()
+            .find(|issue| issue.tag == IssueTag::ConstantCondition)
+            .unwrap();
+
+        match &issue.actions[..] {
+            [CodeAction {
+                kind: CodeActionKind::ReplaceText { replacement_text },
+                ..
+            }] => replacement_text.clone(),
+            actions => panic!("unexpected actions: {actions:?}"),
+        }
+    };
+
+    assert_eq!(replacement("x = If[True, a; b, c]"), "(a; b)");
+    assert_eq!(replacement("If[True, a + b, c]*2"), "(a + b)");
+    assert_eq!(replacement("a - If[True, b - c, d]"), "(b - c)");
+
+    // The branch binds tighter than its context.
+    assert_eq!(replacement("If[True, a*b, c] + 1"), "a*b");
+    assert_eq!(replacement("x = If[True, f[a], c]"), "f[a]");
+
+    // The If is not an operand.
+    assert_eq!(replacement("If[True, a; b, c]"), "a; b");
+    assert_eq!(replacement("f[If[True, a + b, c]]"), "a + b");
+    assert_eq!(replacement("(If[True, a + b, c])*2"), "a + b");
+}
@@ -0,0 +1,170 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    issue::{CodeAction, Issue, IssueTag, Severity},
+    lsp::{self, Diagnostic, DiagnosticSeverity, Fix, Position, Range},
+    macros::src,
+    source::{BoxPosition, LineMap, Location, Source, Span},
+    symbols as sym, ParseOptions, SourceConvention,
+};
+
+fn pos(line: u32, character: u32) -> Position {
+    Position { line, character }
+}
+
+fn issue(sev: Severity, src: Source, actions: Vec<CodeAction>) -> Issue {
+    Issue {
+        make_sym: sym::CodeParser_SyntaxIssue,
+        tag: IssueTag::UnexpectedCharacter,
+        msg: "Unexpected character.".to_owned(),
+        sev,
+        src,
+        val: 1.0,
+        actions,
+        annotations: vec![],
+        additional_sources: vec![],
+    }
+}
+
+#[test]
+fn test_lsp_position() {
+    let opts = ParseOptions::default();
+
+    // Offsets: \t is 3, b is 4, the emoji is 5-8, and c is 9.
+    let map = LineMap::new("a\r\n\tb\u{1F600}c\n", &opts);
+
+    assert_eq!(Position::from_offset(&map, 0), Some(pos(0, 0)));
+    assert_eq!(Position::from_offset(&map, 9), Some(pos(1, 4)));
+    assert_eq!(Position::from_offset(&map, 11), Some(pos(2, 0)));
+
+    // Not on a character boundary, and outside of the input.
+    assert_eq!(Position::from_offset(&map, 6), None);
+    assert_eq!(Position::from_offset(&map, 12), None);
+
+    // Tabs are one column wide, unlike in source locations.
+    assert_eq!(
+        Position::from_location(&map, Location::LineColumn(src!(2:7))),
+        Some(pos(1, 4))
+    );
+    assert_eq!(
+        Position::from_location(&map, Location::CharacterIndex(7)),
+        Some(pos(1, 4))
+    );
+
+    assert_eq!(pos(1, 4).to_offset(&map), Some(9));
+    assert_eq!(
+        pos(1, 4).to_location(&map, SourceConvention::LineColumn),
+        Some(Location::LineColumn(src!(2:7)))
+    );
+    assert_eq!(pos(2, 0).to_offset(&map), Some(11));
+
+    // Inside of the emoji, past the end of a line, and past the last line.
+    assert_eq!(pos(1, 3).to_offset(&map), None);
+    assert_eq!(pos(0, 2).to_offset(&map), None);
+    assert_eq!(pos(3, 0).to_offset(&map), None);
+
+    let range = Range {
+        start: pos(1, 1),
+        end: pos(1, 5),
+    };
+
+    assert_eq!(
+        Range::from_span(&map, Span::from(src!(2:5-2:8))),
+        Some(range)
+    );
+    assert_eq!(range.to_offsets(&map), Some(4..10));
+    assert_eq!(
+        range.to_span(&map, SourceConvention::CharacterIndex),
+        Some(Span::from_character_span(5, 8))
+    );
+}
+
+#[test]
+fn test_lsp_diagnostics() {
+    let input = "\u{00E9}x\n\u{1F600} = y";
+    let opts = ParseOptions::default();
+
+    let issues = [
+        issue(
+            Severity::Warning,
+            Source::Span(Span::from(src!(2:3-2:4))),
+            vec![
+                CodeAction::replace_text(
+                    "Replace".into(),
+                    Span::from(src!(2:3-2:4)),
+                    "==".into(),
+                ),
+                CodeAction::insert_text(
+                    "Insert".into(),
+                    Span::from(src!(2:3-2:4)),
+                    ":".into(),
+                ),
+                CodeAction::delete_text(
+                    "Delete".into(),
+                    Span::from(src!(1:1-2:1)),
+                ),
+                // Outside of the input.
+                CodeAction::delete_text(
+                    "Delete".into(),
+                    Span::from(src!(3:1-3:2)),
+                ),
+            ],
+        ),
+        // Not a span of the input.
+        issue(
+            Severity::Fatal,
+            Source::Box(BoxPosition::At(vec![1])),
+            vec![],
+        ),
+        issue(
+            Severity::Formatting,
+            Source::Span(Span::from(src!(1:2-1:3))),
+            vec![],
+        ),
+    ];
+
+    let diagnostic = |range: Range, severity, fixes| Diagnostic {
+        range,
+        severity,
+        message: "Unexpected character.".to_owned(),
+        code: "UnexpectedCharacter".to_owned(),
+        fixes,
+    };
+
+    let fix = |title: &str, start, end, new_text: &str| Fix {
+        title: title.to_owned(),
+        range: Range { start, end },
+        new_text: new_text.to_owned(),
+    };
+
+    assert_eq!(
+        lsp::diagnostics(input, &issues, &opts),
+        [
+            diagnostic(
+                Range {
+                    start: pos(1, 3),
+                    end: pos(1, 4),
+                },
+                DiagnosticSeverity::Warning,
+                vec![
+                    fix("Replace", pos(1, 3), pos(1, 4), "=="),
+                    fix("Insert", pos(1, 3), pos(1, 3), ":"),
+                    fix("Delete", pos(0, 0), pos(1, 0), ""),
+                ]
+            ),
+            diagnostic(
+                Range {
+                    start: pos(0, 1),
+                    end: pos(0, 2),
+                },
+                DiagnosticSeverity::Hint,
+                vec![]
+            ),
+        ]
+    );
+
+    assert_eq!(
+        DiagnosticSeverity::from(Severity::Remark),
+        DiagnosticSeverity::Information
+    );
+}
@@ -0,0 +1,204 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    ast::{Ast, MissingCloser},
+    cst::{CallBody, CallNode, Cst},
+    macros::src,
+    parse::operators::{CallOperator, GroupOperator},
+    parse_ast, parse_cst,
+    source::{LineColumnSpan, Source, Span},
+    ParseOptions,
+};
+
+/// Check that `input`, which contains a single group missing its closer,
+/// parses to a `GroupMissingCloser` node in both the CST and AST.
+#[track_caller]
+fn assert_group_missing_closer(
+    input: &str,
+    kind: GroupOperator,
+    opener: LineColumnSpan,
+    expected: &'static str,
+) {
+    let cst = parse_cst(input, &ParseOptions::default()).syntax;
+
+    let Cst::GroupMissingCloser(group) = &cst else {
+        panic!("expected GroupMissingCloser for {input:?}, got: {cst:#?}")
+    };
+
+    assert_eq!(group.0.op, kind, "input: {input:?}");
+    assert_eq!(
+        group.opener().map(|tok| tok.src),
+        Some(Span::from(opener)),
+        "input: {input:?}"
+    );
+    assert_eq!(group.expected_closer(), Some(expected), "input: {input:?}");
+
+    let ast = parse_ast(input, &ParseOptions::default()).syntax;
+
+    let Ast::GroupMissingCloser {
+        kind: ast_kind,
+        closer,
+        ..
+    } = &ast
+    else {
+        panic!("expected GroupMissingCloser for {input:?}, got: {ast:#?}")
+    };
+
+    assert_eq!(*ast_kind, kind, "input: {input:?}");
+    assert_eq!(
+        *closer,
+        MissingCloser {
+            opener: Source::from(opener),
+            expected,
+        },
+        "input: {input:?}"
+    );
+}
+
+/// Like [`assert_group_missing_closer()`], but for a call like `f[x`.
+#[track_caller]
+fn assert_call_missing_closer(
+    input: &str,
+    kind: CallOperator,
+    opener: LineColumnSpan,
+    expected: &'static str,
+) {
+    let cst = parse_cst(input, &ParseOptions::default()).syntax;
+
+    let Cst::Call(CallNode {
+        head: _,
+        body: CallBody::GroupMissingCloser(group),
+    }) = &cst
+    else {
+        panic!("expected call missing closer for {input:?}, got: {cst:#?}")
+    };
+
+    assert_eq!(group.0.op, kind, "input: {input:?}");
+    assert_eq!(
+        group.opener().map(|tok| tok.src),
+        Some(Span::from(opener)),
+        "input: {input:?}"
+    );
+    assert_eq!(group.expected_closer(), Some(expected), "input: {input:?}");
+
+    let ast = parse_ast(input, &ParseOptions::default()).syntax;
+
+    assert!(
+        matches!(ast, Ast::CallMissingCloser { .. }),
+        "expected CallMissingCloser for {input:?}, got: {ast:#?}"
+    );
+    assert_eq!(
+        ast.missing_closer(),
+        Some(&MissingCloser {
+            opener: Source::from(opener),
+            expected,
+        }),
+        "input: {input:?}"
+    );
+}
+
+#[test]
+fn test_group_missing_closer_all_kinds() {
+    use GroupOperator as G;
+
+    #[rustfmt::skip]
+    let cases: [(&str, GroupOperator, LineColumnSpan, &str); 14] = [
+        ("(a", G::CodeParser_GroupParen, src!(1:1-2), ")"),
+        ("[a", G::CodeParser_GroupSquare, src!(1:1-2), "]"),
+        ("{a", G::List, src!(1:1-2), "}"),
+        ("<|a", G::Association, src!(1:1-3), "|>"),
+        ("::[a", G::CodeParser_GroupTypeSpecifier, src!(1:1-4), "]"),
+        ("\\[LeftAngleBracket]a", G::AngleBracket, src!(1:1-20), "\\[RightAngleBracket]"),
+        ("\\[LeftCeiling]a", G::Ceiling, src!(1:1-15), "\\[RightCeiling]"),
+        ("\\[LeftFloor]a", G::Floor, src!(1:1-13), "\\[RightFloor]"),
+        ("\\[LeftDoubleBracket]a", G::CodeParser_GroupDoubleBracket, src!(1:1-21), "\\[RightDoubleBracket]"),
+        ("\\[LeftBracketingBar]a", G::BracketingBar, src!(1:1-21), "\\[RightBracketingBar]"),
+        ("\\[LeftDoubleBracketingBar]a", G::DoubleBracketingBar, src!(1:1-27), "\\[RightDoubleBracketingBar]"),
+        ("\\[LeftAssociation]a", G::Association, src!(1:1-19), "\\[RightAssociation]"),
+        ("\\[OpenCurlyQuote]a", G::CurlyQuote, src!(1:1-18), "\\[CloseCurlyQuote]"),
+        ("\\[OpenCurlyDoubleQuote]a", G::CurlyDoubleQuote, src!(1:1-24), "\\[CloseCurlyDoubleQuote]"),
+    ];
+
+    for (input, kind, opener, expected) in cases {
+        assert_group_missing_closer(input, kind, opener, expected);
+    }
+}
+
+#[test]
+fn test_call_missing_closer_all_kinds() {
+    assert_call_missing_closer(
+        "f[a",
+        CallOperator::CodeParser_GroupSquare,
+        src!(1:2-3),
+        "]",
+    );
+    assert_call_missing_closer(
+        "f::[a",
+        CallOperator::CodeParser_GroupTypeSpecifier,
+        src!(1:2-5),
+        "]",
+    );
+    assert_call_missing_closer(
+        "f\\[LeftDoubleBracket]a",
+        CallOperator::CodeParser_GroupDoubleBracket,
+        src!(1:2-22),
+        "\\[RightDoubleBracket]",
+    );
+}
+
+#[test]
+fn test_group_missing_closer_before_other_closer() {
+    // The `(` is closed implicitly by the `}` of the enclosing group.
+    let ast = parse_ast("{(a}", &ParseOptions::default()).syntax;
+
+    let Some(("List", [group])) = ast.as_symbol_call() else {
+        panic!("unexpected AST: {ast:#?}")
+    };
+
+    assert_eq!(
+        group.missing_closer(),
+        Some(&MissingCloser {
+            opener: src!(1:2-3).into(),
+            expected: ")",
+        })
+    );
+
+    // Nested groups that are all missing their closers
+    let ast = parse_ast("f[{a, g[b", &ParseOptions::default()).syntax;
+
+    assert_eq!(
+        ast.missing_closer(),
+        Some(&MissingCloser {
+            opener: src!(1:2-3).into(),
+            expected: "]",
+        })
+    );
+}
+
+#[test]
+fn test_group_missing_closer_reparse() {
+    let input = "{x,\ny = 1";
+
+    // By default, the group ends before the line that looks like a new
+    // statement.
+    let cst = parse_cst(input, &ParseOptions::default()).syntax;
+
+    let Cst::GroupMissingCloser(group) = &cst else {
+        panic!("unexpected CST: {cst:#?}")
+    };
+
+    assert_eq!(group.expected_closer(), Some("}"));
+    assert_ne!(cst.get_source(), Span::from(src!(1:1-2:6)));
+
+    // Without reparsing, the group extends to the end of the input.
+    let opts = ParseOptions::default().reparse_unterminated(None);
+
+    let cst = parse_cst(input, &opts).syntax;
+
+    let Cst::GroupMissingCloser(group) = &cst else {
+        panic!("unexpected CST: {cst:#?}")
+    };
+
+    assert_eq!(group.expected_closer(), Some("}"));
+    assert_eq!(cst.get_source(), Span::from(src!(1:1-2:6)));
+}
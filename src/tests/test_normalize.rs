@@ -0,0 +1,84 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    tokenize,
+    tokenize::normalize::{self, normalize_text, NormalizedToken},
+    tokenize::TokenKind,
+    ParseOptions,
+};
+
+#[track_caller]
+fn assert_equivalent(a: &str, b: &str, expected: bool) {
+    let opts = ParseOptions::default();
+
+    let a_tokens = tokenize(a, &opts);
+    let b_tokens = tokenize(b, &opts);
+
+    let equivalent = a_tokens.len() == b_tokens.len()
+        && a_tokens
+            .iter()
+            .zip(b_tokens.iter())
+            .all(|(a, b)| normalize::equivalent(a, b));
+
+    assert_eq!(equivalent, expected, "{a:?} vs {b:?}");
+}
+
+#[test]
+fn test_normalize_operators() {
+    assert_equivalent("a -> b", "a \\[Rule] b", true);
+    assert_equivalent("a :> b", "a \\[RuleDelayed] b", true);
+    assert_equivalent("a != b", "a \\[NotEqual] b", true);
+    assert_equivalent("a == b", "a \\[Equal] b", true);
+    assert_equivalent("a && b || !c", "a \\[And] b \\[Or] \\[Not]c", true);
+    assert_equivalent(
+        "<|a -> 1|>",
+        "\\[LeftAssociation]a -> 1\\[RightAssociation]",
+        true,
+    );
+
+    // The named character written directly
+    assert_equivalent("a -> b", "a \u{F522} b", true);
+
+    assert_equivalent("a -> b", "a :> b", false);
+    assert_equivalent("a == b", "a === b", false);
+
+    let tokens = tokenize("\\[Rule]", &ParseOptions::default());
+
+    assert_eq!(
+        normalize::normalize(&tokens[0]),
+        NormalizedToken {
+            tok: TokenKind::MinusGreater,
+            text: "->".into(),
+        }
+    );
+}
+
+#[test]
+fn test_normalize_escapes() {
+    assert_equivalent("α", "\\[Alpha]", true);
+    assert_equivalent("α", "\\:03b1", true);
+    assert_equivalent("α", "\\|0003b1", true);
+    assert_equivalent("a", "\\.61", true);
+    assert_equivalent("\"αβ\"", "\"\\[Alpha]\\:03b2\"", true);
+    assert_equivalent("a \\[Element] b", "a \u{2208} b", true);
+
+    assert_equivalent("α", "β", false);
+}
+
+#[test]
+fn test_normalize_text() {
+    assert_eq!(normalize_text("abc"), "abc");
+    assert_eq!(normalize_text("x\\[Alpha]y"), "xαy");
+    assert_eq!(normalize_text("\\:03b1\\.62"), "αb");
+
+    // Escaped backslashes are not the start of an escape
+    assert_eq!(normalize_text("\"\\\\[Alpha]\""), "\"\\\\[Alpha]\"");
+
+    // Escapes for `"` and `\` are left as-is
+    assert_eq!(normalize_text("\"\\:0022\""), "\"\\:0022\"");
+
+    // Invalid escapes are left as-is
+    assert_eq!(normalize_text("\\[NotAName]\\:zz"), "\\[NotAName]\\:zz");
+    assert_eq!(normalize_text("\\:+041\\.+4"), "\\:+041\\.+4");
+    assert_eq!(normalize_text("\"\\n\""), "\"\\n\"");
+}
@@ -0,0 +1,231 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    ast::Ast,
+    cst::{BinaryNode, Cst, OperatorNode, PrefixNode},
+    explain::{Associativity, OperatorPrecedence},
+    notation::{NotationError, Notations},
+    parse::{
+        operators::{BinaryOperator, PrefixOperator},
+        spec::OperatorPosition::{self, Infix, Prefix},
+    },
+    parse_ast, parse_cst, ParseOptions,
+};
+
+fn prec(level: u8, associativity: Associativity) -> OperatorPrecedence {
+    OperatorPrecedence {
+        level,
+        associativity,
+    }
+}
+
+fn notations(
+    list: &[(&str, OperatorPosition, OperatorPrecedence, &str)],
+) -> ParseOptions {
+    let mut notations = Notations::new();
+
+    for &(text, position, precedence, symbol) in list {
+        notations
+            .register(text, position, precedence, symbol)
+            .expect("valid notation");
+    }
+
+    ParseOptions::default().notations(notations)
+}
+
+/// Render `ast` in compact `head[args]` form.
+fn render(ast: &Ast) -> String {
+    if let Some(symbol) = ast.as_symbol() {
+        return symbol.to_owned();
+    }
+
+    let (head, args) = ast
+        .as_symbol_call()
+        .unwrap_or_else(|| panic!("unexpected node: {ast:?}"));
+
+    let args: Vec<String> = args.iter().map(render).collect();
+
+    format!("{head}[{}]", args.join(", "))
+}
+
+fn parse(input: &str, opts: &ParseOptions) -> String {
+    render(&parse_ast(input, opts).syntax)
+}
+
+#[test]
+fn test_infix_notation_precedence() {
+    use Associativity::NonRight;
+
+    // Looser than Plus.
+    let opts = notations(&[(r"\[Wolf]", Infix, prec(20, NonRight), "P`Howl")]);
+
+    assert_eq!(parse(r"a \[Wolf] b + c", &opts), "P`Howl[a, Plus[b, c]]");
+    assert_eq!(parse(r"a + b \[Wolf] c", &opts), "P`Howl[Plus[a, b], c]");
+
+    // Between Plus and Times.
+    let opts = notations(&[(r"\[Wolf]", Infix, prec(50, NonRight), "P`Howl")]);
+
+    assert_eq!(parse(r"a \[Wolf] b + c", &opts), "Plus[P`Howl[a, b], c]");
+    assert_eq!(
+        parse(r"a \[Wolf] b * c", &opts),
+        "P`Howl[a, Times[b, c]]"
+    );
+}
+
+#[test]
+fn test_infix_notation_associativity() {
+    let opts = notations(&[(
+        "dot",
+        Infix,
+        prec(50, Associativity::NonRight),
+        "P`Dot",
+    )]);
+
+    assert_eq!(parse("a dot b dot c", &opts), "P`Dot[P`Dot[a, b], c]");
+
+    let opts =
+        notations(&[("dot", Infix, prec(50, Associativity::Right), "P`Dot")]);
+
+    assert_eq!(parse("a dot b dot c", &opts), "P`Dot[a, P`Dot[b, c]]");
+
+    // Other symbols are still implicit Times.
+    assert_eq!(parse("a dots b", &opts), "Times[a, dots, b]");
+}
+
+#[test]
+fn test_infix_notation_overrides_operator() {
+    // Without a notation, ** is a flat infix operator.
+    assert_eq!(
+        parse("a ** b ** c", &ParseOptions::default()),
+        "NonCommutativeMultiply[a, b, c]"
+    );
+
+    let opts = notations(&[(
+        "**",
+        Infix,
+        prec(50, Associativity::NonRight),
+        "P`Star",
+    )]);
+
+    assert_eq!(parse("a ** b ** c", &opts), "P`Star[P`Star[a, b], c]");
+
+    let cst = parse_cst("a ** b", &opts).syntax;
+
+    assert!(matches!(
+        cst,
+        Cst::Binary(BinaryNode(OperatorNode {
+            op: BinaryOperator::CodeParser_BinaryNotation,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn test_prefix_notation() {
+    let opts = notations(&[(
+        r"\[Wolf]",
+        Prefix,
+        prec(50, Associativity::NonRight),
+        "P`Howl",
+    )]);
+
+    assert_eq!(parse(r"\[Wolf] x", &opts), "P`Howl[x]");
+    assert_eq!(parse(r"\[Wolf] x + y", &opts), "Plus[P`Howl[x], y]");
+    assert_eq!(parse(r"\[Wolf] x y", &opts), "P`Howl[Times[x, y]]");
+
+    // The prefix notation doesn't apply in infix position.
+    assert_eq!(parse(r"a \[Wolf] x", &opts), "Times[a, P`Howl[x]]");
+
+    let cst = parse_cst(r"\[Wolf] x", &opts).syntax;
+
+    assert!(matches!(
+        cst,
+        Cst::Prefix(PrefixNode(OperatorNode {
+            op: PrefixOperator::CodeParser_PrefixNotation,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn test_prefix_and_infix_notation() {
+    let opts = notations(&[
+        (r"\[Wolf]", Prefix, prec(60, Associativity::NonRight), "P`Pre"),
+        (r"\[Wolf]", Infix, prec(20, Associativity::NonRight), "P`In"),
+    ]);
+
+    assert_eq!(
+        parse(r"\[Wolf] a \[Wolf] \[Wolf] b", &opts),
+        "P`In[P`Pre[a], P`Pre[b]]"
+    );
+}
+
+#[test]
+fn test_register_replaces_notation() {
+    let mut notations = Notations::new();
+
+    let prec = prec(50, Associativity::NonRight);
+
+    notations.register("dot", Infix, prec, "P`Old").unwrap();
+    notations.register("dot", Prefix, prec, "P`Prefix").unwrap();
+    notations.register("dot", Infix, prec, "P`New").unwrap();
+
+    let symbols: Vec<&str> = notations
+        .iter()
+        .map(|notation| notation.symbol.as_str())
+        .collect();
+
+    assert_eq!(symbols, ["P`Prefix", "P`New"]);
+
+    let opts = ParseOptions::default().notations(notations);
+
+    assert_eq!(parse("a dot b", &opts), "P`New[a, b]");
+}
+
+#[test]
+fn test_register_errors() {
+    let mut notations = Notations::new();
+
+    let prec = prec(50, Associativity::NonRight);
+
+    for text in ["", "a b", "=", ";", "(", "_", "1"] {
+        assert_eq!(
+            notations.register(text, Infix, prec, "P`Op"),
+            Err(NotationError::InvalidToken(text.to_owned())),
+            "{text:?}"
+        );
+    }
+
+    assert_eq!(
+        notations.register(
+            "dot",
+            Infix,
+            OperatorPrecedence {
+                level: 0,
+                associativity: Associativity::NonRight
+            },
+            "P`Op"
+        ),
+        Err(NotationError::InvalidPrecedence(0))
+    );
+
+    assert_eq!(
+        notations.register(
+            "dot",
+            Infix,
+            OperatorPrecedence {
+                level: 128,
+                associativity: Associativity::NonRight
+            },
+            "P`Op"
+        ),
+        Err(NotationError::InvalidPrecedence(128))
+    );
+
+    assert_eq!(
+        notations.register("dot", Infix, prec, "not a symbol"),
+        Err(NotationError::InvalidSymbol("not a symbol".to_owned()))
+    );
+
+    assert!(notations.is_empty());
+}
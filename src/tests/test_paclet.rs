@@ -0,0 +1,211 @@
+use std::io::Read;
+
+use proptest::prelude::*;
+
+use crate::{
+    macros::src,
+    paclet::{self, DecodeReader},
+    source::Span,
+    tokenize::normalize::normalize_text,
+    ParseOptions,
+};
+
+/// ASCII strings that can be encoded, i.e. that do not contain EOT (ASCII 4).
+fn encodable() -> impl Strategy<Value = String> {
+    proptest::collection::vec(
+        (0u8..128)
+            .prop_filter("EOT", |&c| c != 4)
+            .prop_map(char::from),
+        0..500,
+    )
+    .prop_map(|chars| chars.into_iter().collect())
+}
+
+fn read_all(input: &[u8]) -> std::io::Result<String> {
+    let mut decoded = String::new();
+    DecodeReader::new(input)?.read_to_string(&mut decoded)?;
+    Ok(decoded)
+}
+
+proptest! {
+    #[test]
+    fn test_paclet_round_trip(content in encodable()) {
+        let encoded = paclet::encode_paclet(&content).unwrap();
+
+        prop_assert!(paclet::detect_paclet_header(&encoded).is_some());
+        prop_assert_eq!(&paclet::decode_paclet(&encoded).unwrap(), &content);
+        prop_assert_eq!(
+            &paclet::maybe_decode_paclet(&encoded).unwrap(),
+            &content
+        );
+        prop_assert_eq!(&read_all(encoded.as_bytes()).unwrap(), &content);
+    }
+
+    #[test]
+    fn test_paclet_encode_eot(prefix in encodable(), suffix in encodable()) {
+        let content = format!("{prefix}\u{4}{suffix}");
+
+        prop_assert!(paclet::encode_paclet(&content).is_err());
+    }
+
+    /// Non-ASCII characters are encoded as escapes that denote the same
+    /// characters.
+    #[test]
+    fn test_paclet_encode_non_ascii(content in "[^\\\\\u{4}]*") {
+        let encoded = paclet::encode_paclet(&content).unwrap();
+        let decoded = paclet::decode_paclet(&encoded).unwrap();
+
+        prop_assert!(decoded.is_ascii());
+        prop_assert_eq!(&decoded, &paclet::escape_non_ascii(&content));
+        prop_assert_eq!(normalize_text(&decoded), normalize_text(&content));
+    }
+
+    /// Decoding arbitrary input returns an error or some output, but does not
+    /// panic.
+    ///
+    /// `DecodeReader` stops at the EOT character without reading the rest of
+    /// the body, so it can succeed where `decode_paclet()` rejects the body.
+    #[test]
+    fn test_paclet_decode_arbitrary(body in ".*") {
+        let content = format!("(*!1N!*)mcm\n{body}");
+
+        let decoded = paclet::decode_paclet(&content);
+        let read = read_all(content.as_bytes());
+
+        if let (Ok(decoded), Ok(read)) = (decoded, read) {
+            prop_assert_eq!(decoded, read);
+        }
+
+        let _ = paclet::decode_paclet(&body);
+        let _ = paclet::maybe_decode_paclet(&body);
+        let _ = read_all(body.as_bytes());
+    }
+
+    /// Corrupting a single byte of the encoded body does not cause a panic.
+    #[test]
+    fn test_paclet_decode_corrupted(
+        content in encodable(),
+        index in any::<prop::sample::Index>(),
+        byte in any::<u8>(),
+    ) {
+        let mut encoded = paclet::encode_paclet(&content).unwrap().into_bytes();
+        let index = index.index(encoded.len());
+        encoded[index] = byte;
+
+        let _ = read_all(&encoded);
+
+        if let Ok(encoded) = String::from_utf8(encoded) {
+            let _ = paclet::decode_paclet(&encoded);
+        }
+    }
+
+    /// Truncating the encoded body does not cause a panic, and decodes a
+    /// prefix of the content if it succeeds.
+    #[test]
+    fn test_paclet_decode_truncated(
+        content in encodable(),
+        index in any::<prop::sample::Index>(),
+    ) {
+        let encoded = paclet::encode_paclet(&content).unwrap();
+        let truncated = &encoded[..index.index(encoded.len())];
+
+        if let Ok(decoded) = paclet::decode_paclet(truncated) {
+            prop_assert!(content.starts_with(&decoded));
+        }
+    }
+
+    /// The encoded range of a decoded character covers exactly the pairs of
+    /// the body that hold its bits: decoding up to the start of the range
+    /// does not decode the character, and decoding up to the end does.
+    #[test]
+    fn test_paclet_source_map(
+        content in encodable(),
+        index in any::<prop::sample::Index>(),
+    ) {
+        prop_assume!(!content.is_empty());
+
+        let encoded = paclet::encode_paclet(&content).unwrap();
+
+        let (decoded, source_map) = paclet::decode_paclet_with_source_map(
+            &encoded,
+            &ParseOptions::default(),
+        )
+        .unwrap();
+
+        prop_assert_eq!(&decoded, &content);
+
+        let offset = index.index(decoded.len());
+        let range = source_map.encoded_range(offset..offset + 1).unwrap();
+
+        let before = paclet::decode_paclet(&encoded[..range.start]).unwrap();
+        let through = paclet::decode_paclet(&encoded[..range.end]).unwrap();
+
+        prop_assert!(before.len() <= offset);
+        prop_assert!(through.len() > offset);
+    }
+}
+
+#[test]
+fn test_paclet_decode_invalid_headers() {
+    for input in [
+        "",
+        "(*!",
+        "(*!1N!*)",
+        "(*!1N!*)xyz\nab",
+        "(*!2N!*)mcm\nab",
+        "(*!α!*)mcm\nab",
+        "(*!1αN!*)mcm\nab",
+        "(*!€!*)mcm\nab",
+    ] {
+        assert!(paclet::decode_paclet(input).is_err(), "input: {input:?}");
+        assert!(read_all(input.as_bytes()).is_err(), "input: {input:?}");
+    }
+}
+
+#[test]
+fn test_paclet_decode_invalid_body() {
+    for body in ["a", "ab\nc", "\u{1}\u{1}", "~~", "αβ"] {
+        let content = format!("(*!1N!*)mcm\n{body}");
+
+        assert!(paclet::decode_paclet(&content).is_err(), "body: {body:?}");
+        assert!(read_all(content.as_bytes()).is_err(), "body: {body:?}");
+    }
+}
+
+#[test]
+fn test_paclet_source_map_spans() {
+    let content = "f[x_] := x + 1\n".repeat(20);
+    let encoded = paclet::encode_paclet(&content).unwrap();
+
+    let (decoded, source_map) = paclet::decode_paclet_with_source_map(
+        &encoded,
+        &ParseOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(decoded, content);
+
+    // The body starts on the line after the header.
+    assert_eq!(
+        source_map.encoded_span(Span::from(src!(1:1-1:15))),
+        Some(Span::from(src!(2:1-2:13)))
+    );
+    assert_eq!(
+        source_map.encoded_span(Span::from(src!(20:1-20:15))),
+        Some(Span::from(src!(5:29-5:43)))
+    );
+    assert_eq!(
+        source_map.encoded_span(Span::from_character_span(1, 2)),
+        Some(Span::from_character_span(13, 15))
+    );
+
+    // The end of the decoded source.
+    let len = decoded.len();
+    let last = source_map.encoded_range(len - 1..len).unwrap();
+
+    assert_eq!(source_map.encoded_range(len..len), Some(last.end..last.end));
+
+    // Outside of the decoded source.
+    assert_eq!(source_map.encoded_range(0..len + 1), None);
+    assert_eq!(source_map.encoded_span(Span::from(src!(22:1-22:2))), None);
+}
@@ -0,0 +1,273 @@
+use pretty_assertions::assert_eq;
+use wolfram_expr::{Expr, Symbol};
+
+use crate::{
+    cst::{
+        BoxKind, BoxNode, CallHead, CallNode, CodeNode, Cst, InfixNode,
+        OperatorNode,
+    },
+    macros::src,
+    parse::operators::InfixOperator,
+    parse_box_cst,
+    source::{BoxPosition, Source},
+    tokenize::{Token, TokenKind, TokenString},
+    ContainerBody, ContainerKind, NodeSeq, ParseOptions,
+};
+
+fn call(head: &str, args: Vec<Expr>) -> Expr {
+    Expr::normal(Symbol::new(&format!("System`{head}")), args)
+}
+
+fn row(boxes: Vec<Expr>) -> Expr {
+    call("RowBox", vec![call("List", boxes)])
+}
+
+fn s(string: &str) -> Expr {
+    Expr::string(string)
+}
+
+fn parse(boxes: &Expr) -> Vec<Cst<TokenString, Source>> {
+    let container = parse_box_cst(boxes, &ParseOptions::default());
+
+    assert_eq!(container.kind, ContainerKind::Box);
+
+    let ContainerBody::Nodes(NodeSeq(nodes)) = container.body else {
+        panic!("missing container body");
+    };
+
+    nodes
+}
+
+fn token(
+    tok: TokenKind,
+    input: &str,
+    src: BoxPosition,
+) -> Cst<TokenString, Source> {
+    Cst::Token(Token {
+        tok,
+        input: TokenString::from_string(input.to_owned()),
+        src: Source::Box(src),
+    })
+}
+
+#[test]
+fn test_parse_box_row() {
+    assert_eq!(
+        parse(&row(vec![s("a"), s("+"), s("b")])),
+        vec![Cst::Infix(InfixNode(OperatorNode {
+            op: InfixOperator::Plus,
+            children: NodeSeq(vec![
+                token(TokenKind::Symbol, "a", src!({1, 1})),
+                token(TokenKind::Plus, "+", src!({1, 2})),
+                token(TokenKind::Symbol, "b", src!({1, 3})),
+            ]),
+        }))]
+    );
+
+    // Each string is tokenized on its own.
+    let [Cst::Infix(InfixNode(OperatorNode { op, children }))] =
+        <[_; 1]>::try_from(parse(&row(vec![s("a"), s("b")]))).unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(op, InfixOperator::Times);
+    assert_eq!(children.0[0], token(TokenKind::Symbol, "a", src!({1, 1})));
+    assert_eq!(children.0[2], token(TokenKind::Symbol, "b", src!({1, 2})));
+
+    // Whitespace strings are kept.
+    let [Cst::Infix(InfixNode(OperatorNode { op, children }))] =
+        <[_; 1]>::try_from(parse(&row(vec![s("a"), s(" "), s("b")]))).unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(op, InfixOperator::Times);
+    assert_eq!(
+        children.0[1],
+        token(TokenKind::Whitespace, " ", src!({1, 2}))
+    );
+}
+
+#[test]
+fn test_parse_box_nested_boxes() {
+    let nodes = parse(&row(vec![
+        s("f"),
+        s("["),
+        call("SuperscriptBox", vec![s("x"), s("2")]),
+        s("]"),
+    ]));
+
+    let [Cst::Call(CallNode { head, body })] = nodes.as_slice() else {
+        panic!("{nodes:?}")
+    };
+
+    let CallHead::Concrete(head) = head else {
+        panic!("{head:?}")
+    };
+
+    assert_eq!(head.0[0].get_source(), Source::from(src!({1, 1})));
+
+    let NodeSeq(args) = &body.as_op().children;
+
+    assert_eq!(
+        args[1],
+        Cst::Box(BoxNode {
+            kind: BoxKind::SuperscriptBox,
+            children: NodeSeq(vec![
+                token(TokenKind::Symbol, "x", src!({1, 3, 1})),
+                token(TokenKind::Integer, "2", src!({1, 3, 2})),
+            ]),
+            src: Source::from(src!({1, 3})),
+        })
+    );
+}
+
+#[test]
+fn test_parse_box_options() {
+    let option = call(
+        "Rule",
+        vec![
+            Expr::from(Symbol::new("System`MultilineFunction")),
+            Expr::from(Symbol::new("System`None")),
+        ],
+    );
+
+    let nodes =
+        parse(&call("SubscriptBox", vec![s("x"), s("1"), option.clone()]));
+
+    let [Cst::Box(BoxNode {
+        kind,
+        children,
+        src,
+    })] = nodes.as_slice()
+    else {
+        panic!("{nodes:?}")
+    };
+
+    assert_eq!(*kind, BoxKind::SubscriptBox);
+    assert_eq!(*src, Source::from(src!({})));
+    assert_eq!(
+        children.0[2],
+        Cst::Code(CodeNode {
+            first: Expr::from(Symbol::new("System`Null")),
+            second: option,
+            src: Source::from(src!({ 3 })),
+        })
+    );
+}
+
+#[test]
+fn test_parse_box_missing_operand() {
+    let container =
+        parse_box_cst(&row(vec![s("1"), s("+")]), &ParseOptions::default());
+
+    let ContainerBody::Nodes(NodeSeq(nodes)) = container.body else {
+        panic!()
+    };
+
+    // The missing operand comes after the `+`.
+    let [Cst::Infix(InfixNode(OperatorNode { children, .. }))] =
+        nodes.as_slice()
+    else {
+        panic!("{nodes:?}")
+    };
+
+    assert_eq!(
+        children.0[2],
+        token(TokenKind::Error_ExpectedOperand, "", src!(After[{1, 2}]))
+    );
+}
+
+#[test]
+fn test_parse_box_scripts() {
+    let nodes = parse(&call(
+        "UnderoverscriptBox",
+        vec![s("x"), row(vec![s("i"), s("="), s("1")]), s("n")],
+    ));
+
+    let [Cst::Box(node)] = nodes.as_slice() else {
+        panic!("{nodes:?}")
+    };
+
+    assert_eq!(node.kind, BoxKind::UnderoverscriptBox);
+
+    let script = node.as_script().unwrap();
+
+    assert_eq!(*script.base, token(TokenKind::Symbol, "x", src!({ 1 })));
+    assert!(
+        matches!(script.underscript, Some(Cst::Binary(_))),
+        "{script:?}"
+    );
+    assert_eq!(
+        script.overscript,
+        Some(&token(TokenKind::Symbol, "n", src!({ 3 })))
+    );
+    assert_eq!(script.subscript, None);
+    assert_eq!(script.superscript, None);
+    assert_eq!(script.derivative_order(), None);
+
+    // Options are not scripts.
+    let option = call(
+        "Rule",
+        vec![
+            Expr::from(Symbol::new("System`MultilineFunction")),
+            Expr::from(Symbol::new("System`None")),
+        ],
+    );
+
+    let nodes = parse(&call("SuperscriptBox", vec![s("x"), option]));
+
+    let [Cst::Box(node)] = nodes.as_slice() else {
+        panic!("{nodes:?}")
+    };
+
+    assert!(matches!(
+        node.as_script().unwrap().superscript,
+        Some(Cst::Code(_))
+    ));
+
+    let nodes = parse(&call("SubsuperscriptBox", vec![s("x"), s("i")]));
+
+    let [Cst::Box(node)] = nodes.as_slice() else {
+        panic!("{nodes:?}")
+    };
+
+    // Missing the superscript.
+    assert_eq!(node.as_script(), None);
+}
+
+#[test]
+fn test_parse_box_derivative() {
+    // f^(2), the second derivative of `f`.
+    let nodes = parse(&call(
+        "SuperscriptBox",
+        vec![
+            s("f"),
+            call(
+                "TagBox",
+                vec![
+                    row(vec![s("("), s("2"), s(")")]),
+                    Expr::from(Symbol::new("System`Derivative")),
+                ],
+            ),
+        ],
+    ));
+
+    let [Cst::Box(node)] = nodes.as_slice() else {
+        panic!("{nodes:?}")
+    };
+
+    let order = node.as_script().unwrap().derivative_order().unwrap();
+
+    assert!(matches!(order, Cst::Group(_)), "{order:?}");
+
+    // f^2
+    let nodes = parse(&call("SuperscriptBox", vec![s("f"), s("2")]));
+
+    let [Cst::Box(node)] = nodes.as_slice() else {
+        panic!("{nodes:?}")
+    };
+
+    assert_eq!(node.as_script().unwrap().derivative_order(), None);
+}
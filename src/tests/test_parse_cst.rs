@@ -8,8 +8,8 @@ use crate::{
             Prefix, PrefixBinary, SyntaxError, Ternary, Token,
         },
         GroupMissingCloserNode, GroupNode, InfixNode, OperatorNode,
-        PostfixNode, PrefixBinaryNode, PrefixNode, SyntaxErrorNode,
-        TernaryNode,
+        PostfixNode, PrefixBinaryNode, PrefixNode, SpanOperands,
+        SyntaxErrorNode, TernaryNode, TernaryOperands,
     },
     macros::{leaf, src, token},
     parse::{
@@ -22,10 +22,10 @@ use crate::{
         },
         SyntaxErrorKind,
     },
-    parse_ast, parse_cst, parse_cst_seq,
+    parse_ast, parse_cst, parse_cst_expr_iter, parse_cst_first, parse_cst_seq,
     tests::assert_src,
     tokenize::{TokenKind, TokenString},
-    NodeSeq, QuirkSettings,
+    NodeSeq, ParseOptions, QuirkSettings,
 };
 
 use pretty_assertions::assert_eq;
@@ -171,6 +171,140 @@ fn ternary_tag_unset() {
     )
 }
 
+#[test]
+fn test_ternary_operands() {
+    let cst = parse_cst("a /: b = c", &Default::default()).syntax;
+
+    let Ternary(node) = &cst else { panic!() };
+
+    assert_eq!(
+        node.operands(),
+        TernaryOperands {
+            lhs: &Token(token!(Symbol, "a", 1:1-2)),
+            mid: &Token(token!(Symbol, "b", 1:6-7)),
+            rhs: Some(&Token(token!(Symbol, "c", 1:10-11))),
+        }
+    );
+
+    let cst = parse_cst("a ~f~ b", &Default::default()).syntax;
+
+    let Ternary(node) = &cst else { panic!() };
+
+    assert_eq!(
+        node.operands(),
+        TernaryOperands {
+            lhs: &Token(token!(Symbol, "a", 1:1-2)),
+            mid: &Token(token!(Symbol, "f", 1:4-5)),
+            rhs: Some(&Token(token!(Symbol, "b", 1:7-8))),
+        }
+    );
+
+    // TagUnset has no right-hand side
+    let cst = parse_cst("a /: b = .", &Default::default()).syntax;
+
+    let Ternary(node) = &cst else { panic!() };
+
+    assert_eq!(
+        node.operands(),
+        TernaryOperands {
+            lhs: &Token(token!(Symbol, "a", 1:1-2)),
+            mid: &Token(token!(Symbol, "b", 1:6-7)),
+            rhs: None,
+        }
+    );
+}
+
+#[test]
+fn test_infix_operands() {
+    let cst = parse_cst("a + b - c", &Default::default()).syntax;
+
+    let Infix(node) = &cst else { panic!() };
+
+    assert_eq!(
+        node.operands().collect::<Vec<_>>(),
+        vec![
+            &Token(token!(Symbol, "a", 1:1-2)),
+            &Token(token!(Symbol, "b", 1:5-6)),
+            &Token(token!(Symbol, "c", 1:9-10)),
+        ]
+    );
+
+    assert_eq!(
+        node.operator_tokens().collect::<Vec<_>>(),
+        vec![
+            (
+                &token!(Plus, "+", 1:3-4),
+                &Token(token!(Symbol, "b", 1:5-6))
+            ),
+            (
+                &token!(Minus, "-", 1:7-8),
+                &Token(token!(Symbol, "c", 1:9-10))
+            ),
+        ]
+    );
+
+    // Implicit operators and operands are fake tokens
+    let cst = parse_cst("a b;", &Default::default()).syntax;
+
+    let Infix(node) = &cst else { panic!() };
+
+    let [Infix(times), Token(null)] = node.operands().collect::<Vec<_>>()[..]
+    else {
+        panic!()
+    };
+
+    assert_eq!(null.tok, TokenKind::Fake_ImplicitNull);
+
+    assert_eq!(
+        times
+            .operator_tokens()
+            .map(|(token, _)| token.tok)
+            .collect::<Vec<_>>(),
+        [TokenKind::Fake_ImplicitTimes]
+    );
+}
+
+#[test]
+fn test_span_operands() {
+    assert_eq!(
+        parse_cst("a ;; b ;; c", &Default::default())
+            .syntax
+            .span_operands(),
+        Some(SpanOperands {
+            lhs: &Token(token!(Symbol, "a", 1:1-2)),
+            rhs: &Token(token!(Symbol, "b", 1:6-7)),
+            step: Some(&Token(token!(Symbol, "c", 1:11-12))),
+        })
+    );
+
+    assert_eq!(
+        parse_cst("a;;", &Default::default()).syntax.span_operands(),
+        Some(SpanOperands {
+            lhs: &Token(token!(Symbol, "a", 1:1-2)),
+            rhs: &Token(token!(Fake_ImplicitAll, "", 1:4-4)),
+            step: None,
+        })
+    );
+
+    assert_eq!(
+        parse_cst(";;;;2", &Default::default())
+            .syntax
+            .span_operands(),
+        Some(SpanOperands {
+            lhs: &Token(token!(Fake_ImplicitOne, "", 1:1-1)),
+            rhs: &Token(token!(Fake_ImplicitAll, "", 1:3-3)),
+            step: Some(&Token(token!(Integer, "2", 1:5-6))),
+        })
+    );
+
+    assert_eq!(
+        parse_cst("a; b", &Default::default())
+            .syntax
+            .span_operands(),
+        None
+    );
+}
+
 #[test]
 fn test_prefix_binary_integral() {
     //==================================
@@ -3024,3 +3158,101 @@ fn test_regressions() {
         })
     );
 }
+
+#[test]
+fn test_parse_cst_first() {
+    let opts = ParseOptions::default();
+
+    for input in [
+        "",
+        "  \n",
+        "a",
+        "a\nb",
+        "a; b\n\n(* c *)\nf[x,\n  y] + 1\n",
+        "#!shebang\na\nb",
+        "f[a\nb",
+        "a ]] b",
+    ] {
+        let NodeSeq(expected) = parse_cst_seq(input, &opts).syntax;
+
+        let expected: Vec<_> = expected
+            .into_iter()
+            .filter(|node| {
+                !matches!(node, Token(token) if token.tok.isTrivia())
+            })
+            .collect();
+
+        let mut actual = Vec::new();
+
+        let (mut result, mut rest) = parse_cst_first(input, &opts);
+
+        while let Some(node) = result.syntax {
+            actual.push(node);
+
+            (result, rest) = rest.parse_cst_next(&opts);
+        }
+
+        assert_eq!(actual, expected, "input: {input:?}");
+        assert!(rest.is_empty(), "input: {input:?}");
+    }
+}
+
+#[test]
+fn test_parse_cst_first_issues() {
+    let opts = ParseOptions::default();
+
+    let (first, rest) = parse_cst_first("1+\\[Alpha+2\nc", &opts);
+
+    assert!(first.issues().next().is_some());
+    assert_eq!(rest.as_bytes(), b"\nc");
+    assert_eq!(rest.location(), src!(1:12).into());
+
+    let (second, rest) = rest.parse_cst_next(&opts);
+
+    assert!(second.syntax.is_some());
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn test_parse_cst_expr_iter() {
+    let opts = ParseOptions::default();
+
+    for input in [
+        "",
+        "  \n",
+        "a",
+        "a; b\n\n(* c *)\nf[x,\n  y] + 1\n",
+        "a ]] b",
+    ] {
+        let mut expected = Vec::new();
+
+        let (mut result, mut rest) = parse_cst_first(input, &opts);
+
+        while let Some(node) = result.syntax {
+            expected.push(node);
+
+            (result, rest) = rest.parse_cst_next(&opts);
+        }
+
+        let actual: Vec<_> = parse_cst_expr_iter(input, &opts)
+            .map(|result| result.syntax)
+            .collect();
+
+        assert_eq!(actual, expected, "input: {input:?}");
+    }
+
+    // Expressions are parsed as the iterator is advanced.
+    let mut exprs = parse_cst_expr_iter("a\n1+\\[Alpha+2\n", &opts);
+
+    let first = exprs.next().unwrap();
+
+    assert!(first.issues().next().is_none());
+    assert_eq!(first.syntax.to_source(), "a");
+
+    let second = exprs.next().unwrap();
+
+    assert!(second.issues().next().is_some());
+    assert_eq!(second.syntax.to_source(), "1+\\[Alpha+2");
+    assert!(exprs.next().is_none());
+    assert!(exprs.next().is_none());
+}
@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    query::{AbstractSyntax, ConcreteSyntax, Database, Parsed, Query, Tokens},
+    ParseOptions,
+};
+
+/// Number of top-level expressions in a file.
+struct ExprCount;
+
+impl Query for ExprCount {
+    type Output = usize;
+
+    fn compute(db: &Database, path: &Path) -> usize {
+        db.get::<AbstractSyntax>(path).syntax.len()
+    }
+}
+
+/// Depends only on `ExprCount`, to test early cutoff.
+struct IsEmpty;
+
+impl Query for IsEmpty {
+    type Output = bool;
+
+    fn compute(db: &Database, path: &Path) -> bool {
+        *db.get::<ExprCount>(path) == 0
+    }
+}
+
+#[test]
+fn test_memoization() {
+    let a = Path::new("A.wl");
+
+    let mut db = Database::new(ParseOptions::default());
+    db.set_file_text(a, "f[x]\ng[y]");
+
+    assert_eq!(*db.get::<ExprCount>(a), 2);
+    // ExprCount, AbstractSyntax, ConcreteSyntax
+    assert_eq!(db.executions(), 3);
+
+    // Memoized
+    assert_eq!(*db.get::<ExprCount>(a), 2);
+    // The top-level newline is also a concrete syntax node
+    assert_eq!(db.get::<ConcreteSyntax>(a).syntax.len(), 3);
+    assert_eq!(db.executions(), 3);
+
+    // Setting identical text does not invalidate anything
+    db.set_file_text(a, "f[x]\ng[y]");
+    assert_eq!(*db.get::<ExprCount>(a), 2);
+    assert_eq!(db.executions(), 3);
+
+    // Tokens are independent of the parse queries
+    assert!(!db.get::<Tokens>(a).is_empty());
+    assert_eq!(db.executions(), 4);
+}
+
+#[test]
+fn test_invalidation_is_per_file() {
+    let a = Path::new("A.wl");
+    let b = Path::new("B.wl");
+
+    let mut db = Database::new(ParseOptions::default());
+    db.set_file_text(a, "1");
+    db.set_file_text(b, "2\n3");
+
+    assert_eq!(*db.get::<ExprCount>(a), 1);
+    assert_eq!(*db.get::<ExprCount>(b), 2);
+    assert_eq!(db.executions(), 6);
+
+    db.set_file_text(a, "1\n2\n3");
+
+    assert_eq!(*db.get::<ExprCount>(a), 3);
+    assert_eq!(*db.get::<ExprCount>(b), 2);
+    assert_eq!(db.executions(), 9);
+
+    db.remove_file(a);
+
+    assert_eq!(*db.get::<ExprCount>(a), 0);
+    assert_eq!(db.paths().collect::<Vec<_>>(), vec![b]);
+}
+
+#[test]
+fn test_early_cutoff() {
+    let a = Path::new("A.wl");
+
+    let mut db = Database::new(ParseOptions::default());
+    db.set_file_text(a, "x\ny");
+
+    assert!(!*db.get::<IsEmpty>(a));
+    assert_eq!(db.executions(), 4);
+
+    // The syntax changes, but the expression count does not, so IsEmpty is
+    // not recomputed.
+    db.set_file_text(a, "z\nw");
+
+    assert!(!*db.get::<IsEmpty>(a));
+    // ConcreteSyntax, AbstractSyntax, ExprCount
+    assert_eq!(db.executions(), 7);
+}
+
+#[test]
+fn test_abstract_syntax_matches_parse_ast_seq() {
+    let a = Path::new("A.wl");
+    let input = "(* wolfram-parse: indent=4 *)\nf[x,]\na b (*@ c @*)";
+
+    let mut db = Database::new(ParseOptions::default());
+    db.set_file_text(a, input);
+
+    let result = crate::parse_ast_seq(input, &ParseOptions::default());
+
+    assert_eq!(*db.get::<AbstractSyntax>(a), Parsed::from(result));
+}
@@ -0,0 +1,242 @@
+use proptest::prelude::*;
+
+use crate::{
+    cst::{Cst, SyntaxErrorNode},
+    issue::IssueTag,
+    macros::token,
+    parse::SyntaxErrorKind,
+    parse_ast, parse_bytes_cst, parse_cst, parse_cst_seq, parse_cst_simple,
+    tokenize::TokenStr,
+    DebugPhase, NodeSeq, ParseOptions, ParseResult, RecoveryMode,
+};
+
+use pretty_assertions::assert_eq;
+
+
+fn recovering() -> ParseOptions {
+    ParseOptions::default().recovery(RecoveryMode::Recover)
+}
+
+/// Recovering options whose debug sink panics while parsing any concrete
+/// syntax containing the symbol `boom`.
+fn recovering_with_boom() -> ParseOptions {
+    recovering().debug_sink(|phase, snapshot| {
+        if phase == DebugPhase::Cst && format!("{snapshot:?}").contains("boom")
+        {
+            panic!("boom");
+        }
+    })
+}
+
+fn issue_tags(result: &ParseResult<Cst<TokenStr>>) -> Vec<IssueTag> {
+    result.issues().map(|issue| issue.tag).collect()
+}
+
+#[test]
+fn test_recover_empty_input() {
+    let result = parse_cst("", &recovering());
+
+    assert_eq!(
+        result.syntax,
+        Cst::SyntaxError(SyntaxErrorNode {
+            err: SyntaxErrorKind::Unparsable,
+            children: NodeSeq(vec![Cst::Token(token!(
+                Error_ExpectedOperand,
+                "",
+                1:1-1
+            ))]),
+        })
+    );
+
+    assert_eq!(issue_tags(&result), [IssueTag::ParserFailure]);
+
+    let (cst, issues) = parse_cst_simple("", &recovering());
+
+    assert_eq!(cst, result.syntax);
+    assert_eq!(issues[0].tag, IssueTag::ParserFailure);
+}
+
+#[test]
+fn test_recover_multiple_expressions() {
+    let result = parse_cst("a\nb", &recovering());
+
+    let Cst::SyntaxError(SyntaxErrorNode {
+        err: SyntaxErrorKind::Unparsable,
+        ..
+    }) = &result.syntax
+    else {
+        panic!("expected Unparsable node, got: {:?}", result.syntax)
+    };
+
+    assert_eq!(result.syntax.to_source(), "a\nb");
+    assert_eq!(issue_tags(&result), [IssueTag::ParserFailure]);
+
+    let (cst, issues) = parse_cst_simple("a\nb", &recovering());
+
+    assert_eq!(cst, result.syntax);
+    assert_eq!(issues[0].tag, IssueTag::ParserFailure);
+}
+
+#[test]
+#[should_panic(
+    expected = "parse_cst_simple: expected one Cst in input, found 0"
+)]
+fn test_parse_cst_simple_empty_input_panics() {
+    let _ = parse_cst_simple("", &ParseOptions::default());
+}
+
+#[test]
+fn test_recover_each_expression() {
+    let input = "a\nf[boom,\n  x]\n(* c *)\nc";
+
+    let result = parse_cst_seq(input, &recovering_with_boom());
+
+    let NodeSeq(nodes) = &result.syntax;
+
+    let sources: Vec<String> =
+        nodes.iter().map(|node| node.to_source()).collect();
+
+    assert_eq!(sources, ["a", "f[boom,\n  x]", "c"]);
+
+    let Cst::SyntaxError(SyntaxErrorNode {
+        err: SyntaxErrorKind::Unparsable,
+        ..
+    }) = &nodes[1]
+    else {
+        panic!("expected Unparsable node, got: {:?}", nodes[1])
+    };
+
+    let issues: Vec<_> = result.issues().collect();
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].tag, IssueTag::ParserFailure);
+    assert_eq!(issues[0].msg, "The parser failed on this input: boom");
+
+    assert!(result.syntax.validate_spans().is_ok());
+
+    let (cst, issues) = parse_cst_simple("boom + 1", &recovering_with_boom());
+
+    assert_eq!(cst.to_source(), "boom + 1");
+    assert_eq!(issues[0].tag, IssueTag::ParserFailure);
+}
+
+#[test]
+fn test_recover_ast() {
+    let result = parse_ast("a\n", &recovering());
+
+    assert_eq!(
+        result.syntax,
+        parse_ast("a\n", &ParseOptions::default()).syntax
+    );
+    assert_eq!(result.issues().count(), 0);
+
+    for input in ["", "(* c *)", "a\nb", "boom[x]"] {
+        let result = parse_ast(input, &recovering_with_boom());
+
+        let tags: Vec<IssueTag> =
+            result.issues().map(|issue| issue.tag).collect();
+
+        assert_eq!(tags, [IssueTag::ParserFailure], "input: {input:?}");
+    }
+}
+
+#[test]
+fn test_recover_valid_input_unchanged() {
+    for input in ["f[x_] := x + 1", "{1, 2", "a ~f", "1::*\\\r\n"] {
+        let expected = parse_cst(input, &ParseOptions::default());
+        let result = parse_cst(input, &recovering());
+
+        assert_eq!(result.syntax, expected.syntax);
+        assert_eq!(result.fatal_issues, expected.fatal_issues);
+        assert_eq!(result.non_fatal_issues, expected.non_fatal_issues);
+    }
+}
+
+/// Strings made of fragments of Wolfram Language syntax, which are more
+/// likely than arbitrary strings to reach deep into the parser.
+fn source() -> impl Strategy<Value = String> {
+    let fragment = prop_oneof![
+        Just("a"),
+        Just("1"),
+        Just("2.5`3"),
+        Just("^^"),
+        Just(" "),
+        Just("\n"),
+        Just("\r"),
+        Just("\\\n"),
+        Just("\\\r\n"),
+        Just("["),
+        Just("]"),
+        Just("[["),
+        Just("]]"),
+        Just("{"),
+        Just("}"),
+        Just("<|"),
+        Just("|>"),
+        Just("("),
+        Just(")"),
+        Just(","),
+        Just("+"),
+        Just("-"),
+        Just("*"),
+        Just(":"),
+        Just("::"),
+        Just("="),
+        Just(":="),
+        Just("=."),
+        Just("/:"),
+        Just("~"),
+        Just(";"),
+        Just(";;"),
+        Just("_"),
+        Just("_."),
+        Just("#"),
+        Just("%"),
+        Just("&"),
+        Just("'"),
+        Just("@"),
+        Just("/."),
+        Just("<<"),
+        Just(">>"),
+        Just("?"),
+        Just("!"),
+        Just("(*"),
+        Just("*)"),
+        Just("\""),
+        Just("\\"),
+        Just("\\[Alpha]"),
+        Just("\\[Integral]"),
+        Just("\\[DifferentialD]"),
+        Just("\\:03b1"),
+        Just("\\!"),
+        Just("\u{00A0}"),
+    ];
+
+    proptest::collection::vec(fragment, 0..40)
+        .prop_map(|fragments| fragments.concat())
+}
+
+proptest! {
+    #[test]
+    fn test_recover_never_panics(input in source()) {
+        let result = parse_cst(&input, &recovering());
+
+        if let Cst::SyntaxError(SyntaxErrorNode {
+            err: SyntaxErrorKind::Unparsable,
+            ..
+        }) = result.syntax
+        {
+            prop_assert!(issue_tags(&result).contains(&IssueTag::ParserFailure));
+        }
+
+        let _ = parse_cst_seq(&input, &recovering());
+        let _ = parse_cst_simple(&input, &recovering());
+    }
+
+    #[test]
+    fn test_recover_never_panics_bytes(
+        input in proptest::collection::vec(any::<u8>(), 0..64)
+    ) {
+        let _ = parse_bytes_cst(&input, &recovering());
+    }
+}
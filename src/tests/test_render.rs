@@ -0,0 +1,113 @@
+use proptest::prelude::*;
+
+use crate::{parse_cst_seq, ParseOptions};
+
+use pretty_assertions::assert_eq;
+
+
+/// Inputs covering every kind of trivia and node.
+const CORPUS: &[&str] = &[
+    "",
+    "f[x_] := x + 1",
+    "  a  +  b  \n",
+    "a\n\n\nb\r\nc\rd",
+    "f[x] (* comment *) g[y]",
+    "(* nested (* comment *) *)",
+    "f[a, , b,]",
+    "{1, 2",
+    "f[x",
+    "a /: b = c",
+    "a ~f~ b",
+    "x;;y;;z",
+    ";;",
+    "a; b;",
+    "2 x y",
+    "x_.",
+    "#abc + ##2 + %3",
+    "-a!",
+    "\\[Integral] f \\[DifferentialD] x",
+    "a \\[Rule] b",
+    "\"a string with \\\"escapes\\\"\"",
+    "abc\\\ndef",
+    "\"abc\\\n   def\"",
+    "a +\\\n  b",
+    "f @@ {1, 2} // g",
+    "1.2.3",
+    "a::b::c",
+    "<|a -> 1|>",
+    "\t\tf[\tx\t]",
+    "a &&& b",
+    "}",
+];
+
+#[test]
+fn test_to_source() {
+    let opts = ParseOptions::default();
+
+    for input in CORPUS {
+        let cst = parse_cst_seq(input, &opts).syntax;
+
+        assert_eq!(cst.to_source(), *input);
+        assert_eq!(cst.to_source_bytes(), input.as_bytes());
+
+        // Each top-level node renders as its own part of the input.
+        let parts: String = cst.0.iter().map(|node| node.to_source()).collect();
+        assert_eq!(parts, *input);
+    }
+}
+
+/// Strings made of fragments of Wolfram Language syntax.
+fn source() -> impl Strategy<Value = String> {
+    let fragment = prop_oneof![
+        Just("a"),
+        Just("f"),
+        Just("1"),
+        Just("2.5"),
+        Just(" "),
+        Just("\t"),
+        Just("\n"),
+        Just("\\\n"),
+        Just("["),
+        Just("]"),
+        Just("{"),
+        Just("}"),
+        Just("("),
+        Just(")"),
+        Just(","),
+        Just("+"),
+        Just("-"),
+        Just("*"),
+        Just("="),
+        Just(":="),
+        Just(";"),
+        Just("_"),
+        Just("#"),
+        Just("&"),
+        Just("@"),
+        Just("/."),
+        Just("(*"),
+        Just("*)"),
+        Just("\""),
+        Just("\\[Alpha]"),
+    ];
+
+    proptest::collection::vec(fragment, 0..40)
+        .prop_map(|fragments| fragments.concat())
+}
+
+proptest! {
+    #[test]
+    fn test_to_source_round_trip(input in source()) {
+        // A line continuation at the very end of the input, and the
+        // whitespace after it, are part of the end of file token, which is
+        // not kept.
+        prop_assume!(!input.trim_end_matches([' ', '\t']).ends_with("\\\n"));
+
+        // Reparsing drops the lines after an unterminated group or token.
+        let opts = ParseOptions::default().reparse_unterminated(None);
+
+        let cst = parse_cst_seq(&input, &opts).syntax;
+
+        prop_assert_eq!(cst.to_source(), input);
+    }
+}
@@ -0,0 +1,110 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    issue::{Issue, IssueTag, Severity},
+    macros::src,
+    report::{self, Chars, Style, Theme},
+    source::{Source, Span},
+    symbols as sym, ParseOptions,
+};
+
+fn issue(sev: Severity, src: Source) -> Issue {
+    Issue {
+        make_sym: sym::CodeParser_SyntaxIssue,
+        tag: IssueTag::SyntaxError,
+        msg: "Bad.".to_owned(),
+        sev,
+        src,
+        val: 1.0,
+        actions: vec![],
+        annotations: vec![],
+        additional_sources: vec![],
+    }
+}
+
+#[test]
+fn test_render() {
+    let opts = ParseOptions::default();
+
+    // Columns of the underline account for tabs.
+    assert_eq!(
+        report::render(
+            "ab\tcd",
+            &[issue(
+                Severity::Warning,
+                Source::Span(Span::from(src!(1:5-1:7)))
+            )],
+            &opts,
+            &Theme::default()
+        ),
+        "warning[SyntaxError]: Bad.\n --> 1:5\n  |\n1 | ab  cd\n  |     ^^\n"
+    );
+
+    assert_eq!(
+        report::render(
+            "ab\tcd",
+            &[issue(Severity::Warning, Source::Span(Span::from(src!(1:9-1:11))))],
+            &ParseOptions::default().tab_width(8),
+            &Theme::default()
+        ),
+        "warning[SyntaxError]: Bad.\n --> 1:9\n  |\n1 | ab      cd\n  |         ^^\n"
+    );
+
+    // Issues without a line and column source have no context.
+    assert_eq!(
+        report::render(
+            "a",
+            &[issue(Severity::Remark, Source::Unknown)],
+            &opts,
+            &Theme::default()
+        ),
+        "remark[SyntaxError]: Bad.\n"
+    );
+}
+
+#[test]
+fn test_render_max_context_lines() {
+    let opts = ParseOptions::default();
+
+    let issues = [issue(
+        Severity::Error,
+        Source::Span(Span::from(src!(1:1-4:2))),
+    )];
+
+    assert_eq!(
+        report::render(
+            "a\nb\nc\nd",
+            &issues,
+            &opts,
+            &Theme::default().max_context_lines(2)
+        ),
+        "error[SyntaxError]: Bad.\n --> 1:1\n  |\n1 | a\n  | ^\n2 | b\n  | ^\n  ...\n"
+    );
+
+    assert_eq!(
+        report::render("a\nb\nc\nd", &issues, &opts, &Theme::default()),
+        "error[SyntaxError]: Bad.\n --> 1:1\n  |\n1 | a\n  | ^\n2 | b\n  | ^\n3 | c\n  | ^\n4 | d\n  | ^\n"
+    );
+}
+
+#[test]
+fn test_render_theme() {
+    let opts = ParseOptions::default();
+
+    let theme =
+        Theme::plain(Chars::UNICODE).with_style(|style, text| match style {
+            Style::Header(sev) => format!("<{}:{text}>", sev.as_str()),
+            Style::Underline(_) => format!("<{text}>"),
+            Style::Message | Style::Gutter | Style::Source => text.to_owned(),
+        });
+
+    assert_eq!(
+        report::render(
+            "f[x y]",
+            &[issue(Severity::Fatal, Source::Span(Span::from(src!(1:3-1:6))))],
+            &opts,
+            &theme
+        ),
+        "<Fatal:fatal[SyntaxError]>: Bad.\n ──▶ 1:3\n  │\n1 │ f[x y]\n  │   <━━━>\n"
+    );
+}
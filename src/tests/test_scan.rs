@@ -0,0 +1,171 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    issue::{IssueTag, Severity},
+    macros::src,
+    scan::{
+        balance, is_input_complete, Balance, Completeness, Expecting, Imbalance,
+    },
+    tokenize::TokenKind,
+};
+
+#[test]
+fn test_balance() {
+    assert_eq!(
+        balance("f[{1, <|\"a\" -> (2)|>}]"),
+        Balance {
+            depth: 0,
+            max_depth: 4,
+            first_imbalance: None,
+        }
+    );
+
+    // Brackets in strings and comments are ignored
+    assert!(balance("f[\"]\" (* ] *)]").is_balanced());
+
+    assert!(balance("").is_balanced());
+}
+
+#[test]
+fn test_balance_unclosed() {
+    assert_eq!(
+        balance("f[{1,\n2"),
+        Balance {
+            depth: 2,
+            max_depth: 2,
+            first_imbalance: Some(Imbalance::Unclosed {
+                opener: src!(1:2-1:3).into(),
+            }),
+        }
+    );
+}
+
+#[test]
+fn test_balance_unmatched_closer() {
+    assert_eq!(
+        balance("f[x} + 1"),
+        Balance {
+            depth: 1,
+            max_depth: 1,
+            first_imbalance: Some(Imbalance::UnmatchedCloser {
+                closer: src!(1:4-1:5).into(),
+                opener: Some(src!(1:2-1:3).into()),
+            }),
+        }
+    );
+
+    assert_eq!(
+        balance("x]").first_imbalance,
+        Some(Imbalance::UnmatchedCloser {
+            closer: src!(1:2-1:3).into(),
+            opener: None,
+        })
+    );
+}
+
+#[test]
+fn test_balance_unterminated() {
+    assert_eq!(
+        balance("f[\"abc").first_imbalance,
+        Some(Imbalance::Unterminated {
+            tok: TokenKind::Error_UnterminatedString,
+            src: src!(1:3-1:7).into(),
+        })
+    );
+
+    assert!(matches!(
+        balance("f[x] (* abc").first_imbalance,
+        Some(Imbalance::Unterminated {
+            tok: TokenKind::Error_UnterminatedComment,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_is_input_complete() {
+    assert_eq!(is_input_complete(""), Completeness::Complete);
+    assert_eq!(is_input_complete("a = {1, 2}"), Completeness::Complete);
+
+    assert_eq!(
+        is_input_complete("f[<|\"a\" -> (1"),
+        Completeness::Incomplete(Expecting::Closer {
+            closer: ")",
+            opener: src!(1:12-1:13).into(),
+        })
+    );
+
+    assert_eq!(
+        is_input_complete("\\[LeftAssociation]1"),
+        Completeness::Incomplete(Expecting::Closer {
+            closer: "\\[RightAssociation]",
+            opener: src!(1:1-1:19).into(),
+        })
+    );
+
+    assert_eq!(
+        is_input_complete("Print[\"abc\ndef"),
+        Completeness::Incomplete(Expecting::StringTerminator(
+            src!(1:7-2:4).into()
+        ))
+    );
+
+    assert_eq!(
+        is_input_complete("f[x] (* abc"),
+        Completeness::Incomplete(Expecting::CommentTerminator(
+            src!(1:6-1:12).into()
+        ))
+    );
+
+    assert_eq!(
+        is_input_complete("a +"),
+        Completeness::Incomplete(Expecting::Operand(src!(1:3-1:4).into()))
+    );
+    assert_eq!(
+        is_input_complete("f[x_] :=\n  (* body *)"),
+        Completeness::Incomplete(Expecting::Operand(src!(1:7-1:9).into()))
+    );
+    assert!(matches!(
+        is_input_complete("f @"),
+        Completeness::Incomplete(Expecting::Operand(_))
+    ));
+    assert_eq!(is_input_complete("a;"), Completeness::Complete);
+    assert_eq!(is_input_complete("a!"), Completeness::Complete);
+    assert_eq!(is_input_complete("#&"), Completeness::Complete);
+    assert_eq!(is_input_complete("a;;"), Completeness::Complete);
+
+    // Operators that are only spelled as long names, and `.`
+    assert!(matches!(
+        is_input_complete("a ."),
+        Completeness::Incomplete(Expecting::Operand(_))
+    ));
+    assert_eq!(
+        is_input_complete("a \\[Rule]"),
+        Completeness::Incomplete(Expecting::Operand(src!(1:3-1:10).into()))
+    );
+    assert!(matches!(
+        is_input_complete("a \\[Element]"),
+        Completeness::Incomplete(Expecting::Operand(_))
+    ));
+    assert!(matches!(
+        is_input_complete("\\[Not]"),
+        Completeness::Incomplete(Expecting::Operand(_))
+    ));
+    assert!(matches!(
+        is_input_complete("a \\[And] \\[Not]"),
+        Completeness::Incomplete(Expecting::Operand(_))
+    ));
+    assert_eq!(is_input_complete("a \\[Transpose]"), Completeness::Complete);
+
+    // The `.` of  a =.  is not an operator
+    assert_eq!(is_input_complete("a =."), Completeness::Complete);
+    assert_eq!(is_input_complete("a = ."), Completeness::Complete);
+
+    let Completeness::Invalid(issue) = is_input_complete("{1, 2]") else {
+        panic!("expected invalid input");
+    };
+
+    assert_eq!(issue.tag, IssueTag::UnexpectedCloser);
+    assert_eq!(issue.sev, Severity::Fatal);
+    assert_eq!(issue.src, src!(1:6-1:7).into());
+}
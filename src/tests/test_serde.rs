@@ -0,0 +1,102 @@
+use crate::{
+    ast::Ast,
+    cst::Cst,
+    lsp::{Diagnostic, DiagnosticSeverity, Fix, Position, Range},
+    macros::src,
+    parse_ast_seq, parse_cst,
+    source::Span,
+    tokenize::{TokenKind, TokenString},
+    NodeSeq, ParseOptions,
+};
+
+use pretty_assertions::assert_eq;
+
+
+#[test]
+fn test_serde_cst_roundtrip() {
+    let opts = ParseOptions::default();
+
+    for input in ["f[x_, y] := x + y", "{1, 2", "a /: b = c", "\"abc\\n\""] {
+        let cst = parse_cst(input, &opts).syntax.into_owned();
+
+        let json = serde_json::to_string(&cst).unwrap();
+
+        let back: Cst<TokenString> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(back, cst, "input: {input}");
+    }
+}
+
+#[test]
+fn test_serde_ast_roundtrip() {
+    let result = parse_ast_seq("f[x,]\n{1, 2", &ParseOptions::default());
+
+    let json = serde_json::to_string(&result.syntax).unwrap();
+
+    let back: NodeSeq<Ast> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(back, result.syntax);
+}
+
+#[test]
+fn test_serde_format() {
+    assert_eq!(
+        serde_json::to_string(&Span::from(src!(1:2-3:4))).unwrap(),
+        r#"{"start":[1,2],"end":[3,4]}"#
+    );
+
+    assert_eq!(
+        serde_json::to_string(&TokenKind::OpenSquare).unwrap(),
+        r#""OpenSquare""#
+    );
+
+    // Spans with mixed or reversed locations are rejected.
+    assert!(serde_json::from_str::<Span>(r#"{"start":[1,2],"end":5}"#).is_err());
+    assert!(serde_json::from_str::<Span>(r#"{"start":4,"end":2}"#).is_err());
+}
+
+#[test]
+fn test_serde_lsp_format() {
+    let diagnostic = Diagnostic {
+        range: Range {
+            start: Position {
+                line: 0,
+                character: 2,
+            },
+            end: Position {
+                line: 0,
+                character: 3,
+            },
+        },
+        severity: DiagnosticSeverity::Warning,
+        message: "msg".to_owned(),
+        code: "UnexpectedCharacter".to_owned(),
+        fixes: vec![Fix {
+            title: "Insert".to_owned(),
+            range: Range {
+                start: Position {
+                    line: 0,
+                    character: 2,
+                },
+                end: Position {
+                    line: 0,
+                    character: 2,
+                },
+            },
+            new_text: "*".to_owned(),
+        }],
+    };
+
+    let json = serde_json::to_string(&diagnostic).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"range":{"start":{"line":0,"character":2},"end":{"line":0,"character":3}},"severity":2,"message":"msg","code":"UnexpectedCharacter","fixes":[{"title":"Insert","range":{"start":{"line":0,"character":2},"end":{"line":0,"character":2}},"newText":"*"}]}"#
+    );
+
+    let back: Diagnostic = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(back, diagnostic);
+
+    assert!(serde_json::from_str::<DiagnosticSeverity>("5").is_err());
+}
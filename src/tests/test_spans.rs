@@ -0,0 +1,107 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cst::{spans::SpanError, Cst, InfixNode, OperatorNode},
+    macros::{src, token},
+    parse::operators::InfixOperator,
+    parse_cst_seq,
+    source::Span,
+    NodeSeq, ParseOptions,
+};
+
+#[test]
+fn test_parsed_spans_are_valid() {
+    let opts = ParseOptions::default().reparse_unterminated(None);
+
+    for input in [
+        "",
+        "a",
+        "  a + b  ",
+        "f[x, y](*comment*)\n",
+        "a b c",
+        "a;\n b;",
+        "a;;b;;",
+        "{1, 2",
+        "f[1, 2",
+        "\"abc",
+        "(* abc",
+        "x_ :> y\n\n z",
+        "a\\\n+b",
+        "a ~f~ b // g @@ h",
+    ] {
+        let cst = parse_cst_seq(input, &opts).syntax;
+
+        assert_eq!(cst.validate_spans(), Ok(()), "{input:?}");
+        assert_eq!(cst.validate_coverage(input, &opts), Ok(()), "{input:?}");
+    }
+}
+
+#[test]
+fn test_out_of_order_spans() {
+    let node: Cst<_> = Cst::Infix(InfixNode(OperatorNode {
+        op: InfixOperator::Plus,
+        children: NodeSeq(vec![
+            Cst::Token(token!(Symbol, "a", 1:1-2)),
+            Cst::Token(token!(Plus, "+", 1:3-4)),
+            Cst::Token(token!(Symbol, "b", 1:2-3)),
+        ]),
+    }));
+
+    assert_eq!(
+        node.validate_spans(),
+        Err(SpanError::OutOfOrder {
+            first: Span::from(src!(1:3-4)),
+            second: Span::from(src!(1:2-3)),
+        })
+    );
+
+    // Overlapping nodes.
+    let seq = NodeSeq(vec![
+        Cst::Token(token!(Symbol, "ab", 1:1-3)),
+        Cst::Token(token!(Symbol, "b", 1:2-3)),
+    ]);
+
+    assert_eq!(
+        seq.validate_spans(),
+        Err(SpanError::OutOfOrder {
+            first: Span::from(src!(1:1-3)),
+            second: Span::from(src!(1:2-3)),
+        })
+    );
+}
+
+#[test]
+fn test_coverage_gaps() {
+    let opts = ParseOptions::default();
+
+    let seq = NodeSeq(vec![
+        Cst::Token(token!(Symbol, "a", 1:1-2)),
+        Cst::Token(token!(Symbol, "b", 1:3-4)),
+    ]);
+
+    assert_eq!(seq.validate_spans(), Ok(()));
+    assert_eq!(
+        seq.validate_coverage("a b", &opts),
+        Err(SpanError::Gap {
+            end: src!(1:2).into(),
+            start: src!(1:3).into(),
+        })
+    );
+
+    let seq = NodeSeq(vec![
+        Cst::Token(token!(Symbol, "a", 1:1-2)),
+        Cst::Token(token!(Whitespace, " ", 1:2-3)),
+        Cst::Token(token!(Symbol, "b", 1:3-4)),
+    ]);
+
+    assert_eq!(seq.validate_coverage("a b", &opts), Ok(()));
+
+    // The input is longer than the tokens.
+    assert_eq!(
+        seq.validate_coverage("a b c", &opts),
+        Err(SpanError::Gap {
+            end: src!(1:4).into(),
+            start: src!(1:6).into(),
+        })
+    );
+}
@@ -0,0 +1,144 @@
+use pretty_assertions::assert_eq;
+
+use wolfram_expr::{Expr, Symbol};
+
+use crate::{
+    ast::{expr::ToExprError, Ast},
+    parse_ast, ParseOptions,
+};
+
+fn to_expr(input: &str) -> Result<Expr, ToExprError> {
+    parse_ast(input, &ParseOptions::default()).syntax.to_expr()
+}
+
+fn sym(name: &str) -> Expr {
+    Expr::from(Symbol::new(name))
+}
+
+fn call(head: &str, args: Vec<Expr>) -> Expr {
+    Expr::normal(Symbol::new(head), args)
+}
+
+#[test]
+fn test_to_expr() {
+    assert_eq!(
+        to_expr("a + b c"),
+        Ok(call(
+            "System`Plus",
+            vec![
+                sym("Global`a"),
+                call("System`Times", vec![sym("Global`b"), sym("Global`c")])
+            ]
+        ))
+    );
+
+    assert_eq!(
+        to_expr(r#"{-1, 1.5*^3, "a\"b\n", A`b, `c}"#),
+        Ok(call(
+            "System`List",
+            vec![
+                Expr::from(-1_i64),
+                Expr::real(1500.0),
+                Expr::string("a\"b\n"),
+                sym("A`b"),
+                sym("Global`c"),
+            ]
+        ))
+    );
+
+    assert_eq!(
+        to_expr("{12*^3, 16^^FF*^2}"),
+        Ok(call(
+            "System`List",
+            vec![Expr::from(12000_i64), Expr::from(65280_i64)]
+        ))
+    );
+
+    assert_eq!(
+        to_expr("f[x_] := x"),
+        Ok(call(
+            "System`SetDelayed",
+            vec![
+                call(
+                    "Global`f",
+                    vec![call(
+                        "System`Pattern",
+                        vec![sym("Global`x"), call("System`Blank", vec![])]
+                    )]
+                ),
+                sym("Global`x"),
+            ]
+        ))
+    );
+}
+
+#[test]
+fn test_to_expr_with() {
+    let ast = parse_ast("f[x]", &ParseOptions::default()).syntax;
+
+    let expr =
+        ast.to_expr_with(&|name: &str| Symbol::try_new(&format!("Pkg`{name}")));
+
+    assert_eq!(expr, Ok(call("Pkg`f", vec![sym("Pkg`x")])));
+
+    let expr = ast.to_expr_with(&|_: &str| None);
+
+    assert!(matches!(expr, Err(ToExprError::UnresolvedSymbol { .. })));
+}
+
+#[test]
+fn test_to_expr_errors() {
+    assert!(matches!(to_expr("f[x"), Err(ToExprError::SyntaxError(_))));
+    assert!(matches!(to_expr("a +"), Err(ToExprError::SyntaxError(_))));
+
+    assert!(matches!(
+        to_expr("99999999999999999999"),
+        Err(ToExprError::UnsupportedLiteral { .. })
+    ));
+}
+
+#[test]
+fn test_from_expr() {
+    let expr = call(
+        "System`List",
+        vec![
+            Expr::from(-3_i64),
+            Expr::real(1e300),
+            Expr::real(0.25),
+            Expr::string("say \"hi\"\\\n"),
+            sym("Global`x"),
+            sym("System`Sin"),
+            call("System`Plus", vec![sym("A`b")]),
+        ],
+    );
+
+    let ast = Ast::from_expr(&expr).unwrap();
+
+    let (head, args) = ast.as_symbol_call().unwrap();
+
+    assert_eq!(head, "List");
+
+    let inputs: Vec<_> = args
+        .iter()
+        .filter_map(|arg| match arg {
+            Ast::Leaf { input, .. } => Some(input.to_str()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        inputs,
+        [
+            "-3",
+            "1.*^300",
+            "0.25",
+            r#""say \"hi\"\\\n""#,
+            "Global`x",
+            "System`Sin"
+        ]
+    );
+
+    assert_eq!(ast.to_expr(), Ok(expr));
+
+    assert_eq!(Ast::from_expr(&Expr::real(f64::INFINITY)), None);
+}
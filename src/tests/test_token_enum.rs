@@ -40,3 +40,14 @@ fn TokenEnumTest_Empty() {
 
     assert!(TokenKind::Error_ExpectedOperand.isEmpty());
 }
+
+#[test]
+fn TokenEnumTest_Spelling() {
+    assert_eq!(TokenKind::Plus.spelling(), Some("+"));
+
+    assert_eq!(TokenKind::SlashColon.spelling(), Some("/:"));
+
+    assert_eq!(TokenKind::Symbol.spelling(), None);
+
+    assert_eq!(TokenKind::LongName_Times.spelling(), None);
+}
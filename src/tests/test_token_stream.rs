@@ -0,0 +1,196 @@
+use pretty_assertions::assert_eq;
+
+#[cfg(feature = "serde")]
+use crate::macros::src;
+use crate::{
+    parse::parse_tokens,
+    parse_cst_seq, parse_from_tokens,
+    source::SourceConvention,
+    tokenize,
+    tokenize::{Token, TokenKind, TokenStreamError, TokenString},
+    NodeSeq, ParseOptions,
+};
+
+const INPUT: &str = "f[x_] := x^2 (* square *)\n\ng[\"a\\nb\"] ;; 1.5`";
+
+fn owned_tokens(
+    input: &str,
+    opts: &ParseOptions,
+) -> NodeSeq<Token<TokenString>> {
+    tokenize(input, opts).into_owned()
+}
+
+#[test]
+fn test_binary_roundtrip() {
+    let tokens = owned_tokens(INPUT, &ParseOptions::default());
+
+    let bytes = tokens.to_bytes();
+
+    assert_eq!(NodeSeq::from_bytes(&bytes).unwrap(), tokens);
+
+    //
+    // Character index spans
+    //
+
+    let opts = ParseOptions::default()
+        .source_convention(SourceConvention::CharacterIndex);
+
+    let tokens = owned_tokens(INPUT, &opts);
+
+    assert_eq!(NodeSeq::from_bytes(&tokens.to_bytes()).unwrap(), tokens);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_roundtrip() {
+    let tokens = owned_tokens("a+\"\\\"\"", &ParseOptions::default());
+
+    let json = tokens.to_json();
+
+    assert_eq!(
+        json,
+        r#"[{"tok":"Symbol","input":"a","src":{"start":[1,1],"end":[1,2]}},{"tok":"Plus","input":"+","src":{"start":[1,2],"end":[1,3]}},{"tok":"String","input":"\"\\\"\"","src":{"start":[1,3],"end":[1,7]}}]"#
+    );
+
+    assert_eq!(NodeSeq::from_json(&json).unwrap(), tokens);
+
+    let tokens = owned_tokens(INPUT, &ParseOptions::default());
+
+    assert_eq!(NodeSeq::from_json(&tokens.to_json()).unwrap(), tokens);
+
+    // Non-UTF-8 input
+    let tokens = NodeSeq(vec![Token {
+        tok: TokenKind::Symbol,
+        input: TokenString {
+            buf: vec![b'a', 0xff],
+        },
+        src: src!(1:1-3).into(),
+    }]);
+
+    let json = tokens.to_json();
+    assert!(json.contains(r#""input":[97,255]"#));
+    assert_eq!(NodeSeq::from_json(&json).unwrap(), tokens);
+}
+
+#[test]
+fn test_invalid_token_streams() {
+    assert!(matches!(
+        NodeSeq::from_bytes(b"XXXX\x01\x00\x00\x00\x00"),
+        Err(TokenStreamError::InvalidMagic)
+    ));
+
+    assert!(matches!(
+        NodeSeq::from_bytes(b"WLTK\x07\x00\x00\x00\x00"),
+        Err(TokenStreamError::UnsupportedVersion(7))
+    ));
+
+    // Truncated
+    let bytes = owned_tokens("a + b", &ParseOptions::default()).to_bytes();
+    assert!(matches!(
+        NodeSeq::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(TokenStreamError::InvalidData(_))
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_invalid_json_token_streams() {
+    assert!(matches!(
+        NodeSeq::from_json(
+            r#"[{"tok":"NotAKind","input":"a","src":{"start":1,"end":2}}]"#
+        ),
+        Err(TokenStreamError::InvalidData(_))
+    ));
+
+    // Mixed span conventions
+    assert!(matches!(
+        NodeSeq::from_json(
+            r#"[{"tok":"Symbol","input":"a","src":{"start":[1,1],"end":2}}]"#
+        ),
+        Err(TokenStreamError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_parse_from_tokens() {
+    let opts = ParseOptions::default();
+
+    let tokens = owned_tokens(INPUT, &opts);
+    let tokens = NodeSeq::from_bytes(&tokens.to_bytes()).unwrap();
+
+    let result = parse_from_tokens(&tokens, &opts).unwrap();
+    let expected = parse_cst_seq(INPUT, &opts);
+
+    assert_eq!(result.syntax, expected.syntax.into_owned());
+    assert_eq!(result.fatal_issues, expected.fatal_issues);
+    assert_eq!(result.non_fatal_issues, expected.non_fatal_issues);
+}
+
+#[test]
+fn test_parse_from_inconsistent_tokens() {
+    let opts = ParseOptions::default();
+
+    let NodeSeq(mut tokens) = owned_tokens("a + b", &opts);
+
+    // Remove a token, leaving a gap in the spans.
+    tokens.remove(1);
+
+    assert!(matches!(
+        parse_from_tokens(&NodeSeq(tokens), &opts),
+        Err(TokenStreamError::Inconsistent { index: 1, .. })
+    ));
+
+    // Spans computed with a different source convention.
+    let tokens = owned_tokens(
+        "a + b",
+        &ParseOptions::default()
+            .source_convention(SourceConvention::CharacterIndex),
+    );
+
+    assert!(matches!(
+        parse_from_tokens(&tokens, &opts),
+        Err(TokenStreamError::Inconsistent { index: 0, .. })
+    ));
+}
+
+#[test]
+fn test_parse_tokens() {
+    let opts = ParseOptions::default();
+
+    // Borrowed tokens, including input that the parser lexes differently
+    // than tokenize() does (`b` after `::`).
+    for input in [
+        "a::b",
+        "x >> file.txt",
+        "{1,\n2}\n3",
+        "f[x] := \"unterminated\nstring",
+        "(* unterminated\ncomment",
+        INPUT,
+    ] {
+        let NodeSeq(tokens) = tokenize(input, &opts);
+
+        let result = parse_tokens(&tokens, &opts).unwrap();
+        let expected = parse_cst_seq(input, &opts);
+
+        assert_eq!(result.syntax, expected.syntax.into_owned());
+    }
+
+    // Modified tokens are parsed as supplied, without lexing their input again.
+    let NodeSeq(mut tokens) = owned_tokens("a + b", &opts);
+    tokens[2].tok = TokenKind::Minus;
+    tokens[2].input = TokenString::new("-");
+
+    assert_eq!(
+        parse_tokens(&tokens, &opts).unwrap().syntax,
+        parse_cst_seq("a - b", &opts).syntax.into_owned()
+    );
+
+    // Token without input
+    let NodeSeq(mut tokens) = owned_tokens("a + b", &opts);
+    tokens[2].input = TokenString::new("");
+
+    assert!(matches!(
+        parse_tokens(&tokens, &opts),
+        Err(TokenStreamError::Inconsistent { index: 2, .. })
+    ));
+}
@@ -1,8 +1,11 @@
 use crate::{
     macros::{src, token},
-    source::{NextPolicyBits::RETURN_TOPLEVELNEWLINE, TOPLEVEL},
+    source::{
+        CharIndexSpan, LineMap, NextPolicyBits::RETURN_TOPLEVELNEWLINE,
+        SourceConvention, TOPLEVEL,
+    },
     tests::tokens,
-    tokenize::Tokenizer,
+    tokenize::{self, Token, TokenCounts, TokenKind, Tokenizer},
     tokenize_bytes, NodeSeq, ParseOptions,
 };
 
@@ -490,3 +493,75 @@ fn test_tokenize_jpeg_string() {
         ])
     )
 }
+
+#[test]
+fn test_count() {
+    let opts = ParseOptions::default();
+
+    let input = "f[x_] := x + 1 (* c *)\n\"abc\" <> 1.2.3\n\"unterminated";
+
+    let counts = tokenize::count(input, &opts);
+
+    // The counts match the tokens returned by tokenize().
+    let NodeSeq(tokens) = crate::tokenize(input, &opts);
+
+    for (kind, count) in counts.iter() {
+        assert_eq!(
+            tokens.iter().filter(|token| token.tok == kind).count(),
+            count,
+            "kind: {kind:?}"
+        );
+    }
+
+    assert_eq!(counts.total(), tokens.len());
+    assert_eq!(counts.get(TokenKind::Symbol), 3);
+    assert_eq!(counts.get(TokenKind::Comment), 1);
+    assert_eq!(counts.get(TokenKind::ColonEqual), 1);
+    assert_eq!(counts.get(TokenKind::Error_UnterminatedString), 1);
+    assert_eq!(counts.errors(), 1);
+    assert_eq!(counts.get(TokenKind::OpenCurly), 0);
+
+    // Counts of several inputs can be totaled.
+    let mut total = TokenCounts::default();
+    total += &counts;
+    total += &tokenize::count("g", &opts);
+
+    assert_eq!(total.get(TokenKind::Symbol), 4);
+    assert_eq!(total.total(), counts.total() + 1);
+}
+
+#[test]
+fn test_token_macro_forms() {
+    let input = "a\\[Times]b";
+
+    let opts = ParseOptions::default()
+        .source_convention(SourceConvention::CharacterIndex);
+
+    let map = LineMap::new(input, &opts);
+
+    let NodeSeq(tokens) = crate::tokenize(input, &opts);
+
+    let byte_tokens: Vec<_> = tokens
+        .iter()
+        .map(|token| Token {
+            tok: token.tok,
+            input: token.input,
+            src: map.byte_span(token.src).unwrap(),
+        })
+        .collect();
+
+    assert_eq!(
+        byte_tokens,
+        [
+            token!(Symbol, "a", byte 0..1),
+            token!(LongName[Times], byte 1..9),
+            token!(Symbol, "b", byte 9..10),
+        ]
+    );
+
+    assert_eq!(token!(LongName[Times], 2..10), tokens[1]);
+    assert_eq!(
+        map.convert_span::<CharIndexSpan>(src!(byte 1..9)),
+        Some(src!(2..10))
+    );
+}
@@ -0,0 +1,88 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    edit::{apply_edits, TextEdit},
+    macros::src,
+    source::Span,
+    testing,
+    whitespace::normalize_whitespace,
+    ParseOptions,
+};
+
+fn normalize(input: &str, opts: &ParseOptions) -> String {
+    apply_edits(input, &normalize_whitespace(input, opts))
+}
+
+#[test]
+fn test_normalize_whitespace() {
+    let opts = ParseOptions::default();
+
+    assert_eq!(normalize("", &opts), "");
+    assert_eq!(normalize("f[x]\n", &opts), "f[x]\n");
+    assert_eq!(normalize("f[x]", &opts), "f[x]\n");
+    assert_eq!(normalize("f[x]  \t\ng[y] ", &opts), "f[x]\ng[y]\n");
+    assert_eq!(normalize("a\r\n  \r\nb\r\n", &opts), "a\r\n\r\nb\r\n");
+
+    // Tabs are expanded up to the next tab stop.
+    assert_eq!(normalize("\ta", &opts), "    a\n");
+    assert_eq!(normalize("ab\tc", &opts), "ab  c\n");
+    assert_eq!(normalize("a \t b", &opts), "a    b\n");
+    assert_eq!(normalize("\ta", &opts.tab_width(2)), "  a\n");
+
+    // Tabs and trailing whitespace in strings are left as-is.
+    assert_eq!(
+        normalize("\"a\tb  \n\tc\"\t", &ParseOptions::default()),
+        "\"a\tb  \n\tc\"\n"
+    );
+
+    // Tabs and trailing whitespace in comments are changed.
+    assert_eq!(
+        normalize("(* a\tb  \n\tc *)", &ParseOptions::default()),
+        "(* a    b\n    c *)\n"
+    );
+}
+
+#[test]
+fn test_normalize_whitespace_edits() {
+    let opts = ParseOptions::default();
+
+    assert_eq!(
+        normalize_whitespace("f[\n\tx ]  ", &opts),
+        vec![
+            TextEdit {
+                range: 3..4,
+                span: Span::from(src!(2:1-2:5)),
+                new_text: "    ".to_owned(),
+            },
+            TextEdit {
+                range: 7..9,
+                span: Span::from(src!(2:8-2:10)),
+                new_text: String::new(),
+            },
+            TextEdit {
+                range: 9..9,
+                span: Span::from(src!(2:10-2:10)),
+                new_text: "\n".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_normalize_whitespace_is_stable() {
+    let opts = ParseOptions::default();
+
+    for input in [
+        "f[x_] :=\n\tModule[{y},\t(* tab *)\n\t\ty = x;  \n\t\ty\n\t]",
+        "\"tab\there\"\t\n{1,\t2}",
+        "a \\\n\tb",
+    ] {
+        assert_eq!(
+            testing::check_format_stability(input, &opts, |input| {
+                normalize(input, &opts)
+            }),
+            Ok(()),
+            "input: {input:?}"
+        );
+    }
+}
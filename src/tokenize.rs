@@ -1,8 +1,13 @@
+mod count;
+pub mod normalize;
+mod serialize;
 mod token;
 pub(crate) mod token_kind;
 pub(crate) mod tokenizer;
 
 pub use self::{
+    count::{count, TokenCounts},
+    serialize::TokenStreamError,
     token::{Token, TokenStr, TokenString},
     token_kind::TokenKind,
 };
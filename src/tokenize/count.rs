@@ -0,0 +1,122 @@
+use std::fmt;
+
+use crate::{
+    feature,
+    tokenize::{TokenKind, Tokenizer},
+    ParseOptions,
+};
+
+/// Number of tokens of each [`TokenKind`] in some input, as returned by
+/// [`count()`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct TokenCounts {
+    /// Indexed by the id of each kind.
+    counts: Box<[usize; TokenKind::COUNT]>,
+}
+
+/// Count the tokens in `input`, by kind.
+///
+/// This tokenizes `input` in the same way as [`tokenize()`][crate::tokenize],
+/// but without collecting the tokens, so uses a small, constant amount of
+/// memory regardless of the length of `input`.
+///
+/// ```
+/// use wolfram_parser::{tokenize::{self, TokenKind}, ParseOptions};
+///
+/// let counts = tokenize::count("f[x] + f[y]", &ParseOptions::default());
+///
+/// assert_eq!(counts.get(TokenKind::Symbol), 4);
+/// assert_eq!(counts.get(TokenKind::Plus), 1);
+/// assert_eq!(counts.total(), 11);
+/// ```
+pub fn count(input: &str, opts: &ParseOptions) -> TokenCounts {
+    let mut tokenizer = Tokenizer::new(input.as_bytes(), opts);
+
+    let mut counts = TokenCounts::default();
+
+    loop {
+        if feature::CHECK_ABORT && crate::abortQ() {
+            break;
+        }
+
+        let tok = tokenizer.peek_token();
+
+        if tok.tok == TokenKind::EndOfFile {
+            break;
+        }
+
+        counts.counts[usize::from(tok.tok.id())] += 1;
+
+        tok.skip(&mut tokenizer);
+    }
+
+    counts
+}
+
+//======================================
+// Impls
+//======================================
+
+impl TokenCounts {
+    /// Number of tokens of kind `kind`.
+    pub fn get(&self, kind: TokenKind) -> usize {
+        self.counts[usize::from(kind.id())]
+    }
+
+    /// Total number of tokens, of every kind.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Number of trivia tokens, like whitespace and comments.
+    pub fn trivia(&self) -> usize {
+        self.iter()
+            .filter(|(kind, _)| kind.isTrivia())
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Number of error tokens, like unterminated strings.
+    pub fn errors(&self) -> usize {
+        self.iter()
+            .filter(|(kind, _)| kind.isError())
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Iterate over every kind of token that occurred, with its count, in
+    /// [`TokenKind`] order.
+    pub fn iter(&self) -> impl Iterator<Item = (TokenKind, usize)> + '_ {
+        TokenKind::VARIANTS
+            .iter()
+            .zip(self.counts.iter())
+            .filter(|(_, count)| **count > 0)
+            .map(|(kind, count)| (*kind, *count))
+    }
+}
+
+/// No tokens.
+impl Default for TokenCounts {
+    fn default() -> Self {
+        TokenCounts {
+            counts: Box::new([0; TokenKind::COUNT]),
+        }
+    }
+}
+
+/// Add the counts of `other`, e.g. to total the counts of several files.
+impl std::ops::AddAssign<&TokenCounts> for TokenCounts {
+    fn add_assign(&mut self, other: &TokenCounts) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other;
+        }
+    }
+}
+
+impl fmt::Debug for TokenCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(kind, count)| (kind.name(), count)))
+            .finish()
+    }
+}
@@ -0,0 +1,165 @@
+//! Canonical identities for tokens with several equivalent spellings.
+//!
+//! Wolfram Language often has more than one way to write the same token:
+//!
+//! * an ASCII operator and a named character operator, like `->` and
+//!   `\[Rule]`
+//! * a character written directly and written as an escape, like `α`,
+//!   `\[Alpha]`, and `\:03b1`
+//!
+//! [`normalize()`] maps every spelling of a token to the same
+//! [`NormalizedToken`], so that code that compares tokens can treat
+//! equivalent spellings as equal.
+//!
+//! ```
+//! use wolfram_parser::{tokenize, tokenize::normalize, ParseOptions};
+//!
+//! let opts = ParseOptions::default();
+//!
+//! let ascii = tokenize("a -> α", &opts);
+//! let named = tokenize("a \\[Rule] \\[Alpha]", &opts);
+//!
+//! assert!(ascii
+//!     .iter()
+//!     .zip(named.iter())
+//!     .all(|(a, b)| normalize::equivalent(a, b)));
+//! ```
+
+use std::borrow::Cow;
+
+use crate::{
+    long_names,
+    tokenize::{Token, TokenInput, TokenKind},
+};
+
+/// The canonical identity of a token, as computed by [`normalize()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizedToken {
+    pub tok: TokenKind,
+    pub text: Cow<'static, str>,
+}
+
+/// Named character operators that are equivalent to an ASCII operator, and
+/// the canonical spelling of that operator.
+#[rustfmt::skip]
+const EQUIVALENT_OPERATORS: &[(TokenKind, TokenKind, &str)] = &[
+    (TokenKind::LongName_Rule, TokenKind::MinusGreater, "->"),
+    (TokenKind::LongName_RuleDelayed, TokenKind::ColonGreater, ":>"),
+    (TokenKind::LongName_TwoWayRule, TokenKind::LessMinusGreater, "<->"),
+    (TokenKind::LongName_Function, TokenKind::BarMinusGreater, "|->"),
+    (TokenKind::LongName_Equal, TokenKind::EqualEqual, "=="),
+    (TokenKind::LongName_LongEqual, TokenKind::EqualEqual, "=="),
+    (TokenKind::LongName_NotEqual, TokenKind::BangEqual, "!="),
+    (TokenKind::LongName_LessEqual, TokenKind::LessEqual, "<="),
+    (TokenKind::LongName_GreaterEqual, TokenKind::GreaterEqual, ">="),
+    (TokenKind::LongName_And, TokenKind::AmpAmp, "&&"),
+    (TokenKind::LongName_Or, TokenKind::BarBar, "||"),
+    (TokenKind::LongName_Not, TokenKind::Bang, "!"),
+    (TokenKind::LongName_Times, TokenKind::Star, "*"),
+    (TokenKind::LongName_LeftAssociation, TokenKind::LessBar, "<|"),
+    (TokenKind::LongName_RightAssociation, TokenKind::BarGreater, "|>"),
+];
+
+/// Get the canonical identity of `token`.
+///
+/// Named character operators with an ASCII equivalent are mapped to the
+/// ASCII operator. In all other tokens, `\[Name]`, `\:xxxx`, `\.xx`, and
+/// `\|xxxxxx` character escapes are replaced by the character they denote.
+pub fn normalize<I: TokenInput, S>(token: &Token<I, S>) -> NormalizedToken {
+    if let Some(&(_, tok, text)) = EQUIVALENT_OPERATORS
+        .iter()
+        .find(|(long_name, _, _)| *long_name == token.tok)
+    {
+        return NormalizedToken {
+            tok,
+            text: Cow::Borrowed(text),
+        };
+    }
+
+    NormalizedToken {
+        tok: token.tok,
+        text: normalize_text(&String::from_utf8_lossy(token.input.as_bytes()))
+            .into_owned()
+            .into(),
+    }
+}
+
+/// Returns true if `a` and `b` are different spellings of the same token.
+pub fn equivalent<I: TokenInput, J: TokenInput, S, T>(
+    a: &Token<I, S>,
+    b: &Token<J, T>,
+) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// Replace the character escapes in `text` by the characters they denote.
+///
+/// Escapes that denote `"` or `\` are left as-is, because replacing them would
+/// change the meaning of a string literal.
+pub fn normalize_text(text: &str) -> Cow<'_, str> {
+    if !text.contains('\\') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+
+    let mut rest = text;
+
+    while let Some(index) = rest.find('\\') {
+        out.push_str(&rest[..index]);
+
+        rest = &rest[index..];
+
+        match unescape_char(rest) {
+            Some((c, len)) if c != '"' && c != '\\' => {
+                out.push(c);
+                rest = &rest[len..];
+            },
+            _ => {
+                // Copy the backslash and the character it escapes, so that
+                // e.g. `\\[Alpha]` is not treated as containing `\[Alpha]`.
+                let len =
+                    rest[1..].chars().next().map_or(1, |c| 1 + c.len_utf8());
+
+                out.push_str(&rest[..len]);
+                rest = &rest[len..];
+            },
+        }
+    }
+
+    out.push_str(rest);
+
+    Cow::Owned(out)
+}
+
+/// Parse a character escape at the start of `text`, returning the character
+/// and the length of the escape in bytes.
+fn unescape_char(text: &str) -> Option<(char, usize)> {
+    let body = text.strip_prefix('\\')?;
+
+    let (len, digits) = match body.as_bytes().first()? {
+        b'[' => {
+            let end = body.find(']')?;
+
+            let c =
+                long_names::longname_to_codepoint(&body[1..end])?.as_char()?;
+
+            return Some((c, 1 + end + 1));
+        },
+        b':' => (4, &body[1..]),
+        b'.' => (2, &body[1..]),
+        b'|' => (6, &body[1..]),
+        _ => return None,
+    };
+
+    let digits = digits.get(..len)?;
+
+    // from_str_radix() would also accept a leading `+`.
+    if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let c = char::from_u32(u32::from_str_radix(digits, 16).ok()?)?;
+
+    Some((c, 2 + len))
+}
@@ -0,0 +1,235 @@
+//! Binary and JSON serialization of token streams.
+//!
+//! Serialized tokens keep their exact input and [`Span`], so a token stream can
+//! be produced once by [`tokenize()`][crate::tokenize()], stored or sent
+//! elsewhere, and later parsed with
+//! [`parse_from_tokens()`][crate::parse_from_tokens()].
+//!
+//! # Binary format
+//!
+//! Integers are unsigned LEB128 varints unless noted otherwise.
+//!
+//! ```text
+//! stream    := MAGIC version:u32-le count token*
+//! token     := kind:u16-le len input-bytes span
+//! span      := location location
+//! location  := 0 line column | 1 index
+//! ```
+//!
+//! `kind` is the value of [`TokenKind::bits()`].
+//!
+//! # JSON format
+//!
+//! With the `serde` feature enabled, tokens can also be written as JSON, using
+//! the `serde` representation of [`Token`]: an array with one object per
+//! token.
+//!
+//! ```json
+//! [
+//!     {"tok":"Symbol","input":"f","src":{"start":[1,1],"end":[1,2]}},
+//!     {"tok":"OpenSquare","input":"[","src":{"start":[1,2],"end":[1,3]}}
+//! ]
+//! ```
+//!
+//! `tok` is the [`TokenKind::name()`] of the token. Line and column locations
+//! are written as `[line, column]` arrays, and character index locations as
+//! integers. Token input that is not valid UTF-8 is written as an array of
+//! byte values instead of a string.
+
+use std::io::{self, Read};
+
+use thiserror::Error;
+
+use crate::{
+    source::{Location, Span},
+    tokenize::{Token, TokenKind, TokenString},
+    utils, NodeSeq,
+};
+
+const MAGIC: &[u8; 4] = b"WLTK";
+const VERSION: u32 = 1;
+
+/// Error returned when deserializing or replaying a token stream fails.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum TokenStreamError {
+    #[error("not a serialized token stream")]
+    InvalidMagic,
+
+    #[error("unsupported token stream format version: {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("unknown token kind: {0}")]
+    UnknownTokenKind(String),
+
+    #[error("invalid token stream data: {0}")]
+    InvalidData(String),
+
+    /// The tokens do not cover their input contiguously, e.g. because tokens
+    /// were removed or reordered, or have spans computed with different
+    /// [`ParseOptions`][crate::ParseOptions].
+    #[error(
+        "token {index} is inconsistent with the token stream input: {reason}"
+    )]
+    Inconsistent { index: usize, reason: String },
+}
+
+impl NodeSeq<Token<TokenString>> {
+    /// Serialize these tokens in a compact binary format.
+    ///
+    /// See [`NodeSeq::from_bytes()`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let NodeSeq(tokens) = self;
+
+        let mut out = Vec::new();
+
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+
+        // Writing to a Vec cannot fail.
+        let write = |out: &mut Vec<u8>| -> io::Result<()> {
+            utils::write_varint(out, tokens.len() as u64)?;
+
+            for Token { tok, input, src } in tokens {
+                out.extend_from_slice(&tok.bits().to_le_bytes());
+
+                utils::write_varint(out, input.buf.len() as u64)?;
+                out.extend_from_slice(&input.buf);
+
+                src.start().write_binary(out)?;
+                src.end().write_binary(out)?;
+            }
+
+            Ok(())
+        };
+
+        write(&mut out).expect("writing token stream to Vec failed");
+
+        out
+    }
+
+    /// Deserialize tokens written by [`NodeSeq::to_bytes()`].
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, TokenStreamError> {
+        let reader = &mut bytes;
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic).map_err(invalid_data)?;
+
+        if magic != *MAGIC {
+            return Err(TokenStreamError::InvalidMagic);
+        }
+
+        let mut version = [0; 4];
+        reader.read_exact(&mut version).map_err(invalid_data)?;
+        let version = u32::from_le_bytes(version);
+
+        if version != VERSION {
+            return Err(TokenStreamError::UnsupportedVersion(version));
+        }
+
+        let count = read_len(reader)?;
+
+        // Don't trust `count` for the initial allocation.
+        let mut tokens = Vec::with_capacity(count.min(reader.len()));
+
+        for _ in 0..count {
+            let mut kind = [0; 2];
+            reader.read_exact(&mut kind).map_err(invalid_data)?;
+            let kind = u16::from_le_bytes(kind);
+
+            let tok = TokenKind::from_bits(kind).ok_or_else(|| {
+                TokenStreamError::UnknownTokenKind(format!("{kind:#x}"))
+            })?;
+
+            let len = read_len(reader)?;
+            if len > reader.len() {
+                return Err(TokenStreamError::InvalidData(
+                    "unexpected end of token stream".to_owned(),
+                ));
+            }
+            let (input, rest) = reader.split_at(len);
+            *reader = rest;
+
+            let start = Location::read_binary(reader).map_err(invalid_data)?;
+            let end = Location::read_binary(reader).map_err(invalid_data)?;
+
+            tokens.push(Token {
+                tok,
+                input: TokenString {
+                    buf: input.to_vec(),
+                },
+                src: span(start, end)?,
+            });
+        }
+
+        if !reader.is_empty() {
+            return Err(TokenStreamError::InvalidData(
+                "unexpected trailing bytes".to_owned(),
+            ));
+        }
+
+        Ok(NodeSeq(tokens))
+    }
+
+    /// Serialize these tokens as JSON.
+    ///
+    /// Only available with the `serde` feature enabled. See
+    /// [`NodeSeq::from_json()`].
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("tokens serialize as JSON")
+    }
+
+    /// Deserialize tokens written by [`NodeSeq::to_json()`].
+    ///
+    /// Only available with the `serde` feature enabled.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, TokenStreamError> {
+        serde_json::from_str(json)
+            .map_err(|err| TokenStreamError::InvalidData(err.to_string()))
+    }
+}
+
+//======================================
+// Helpers
+//======================================
+
+/// Construct a span, checking that `start` and `end` use the same convention
+/// and are in order.
+fn span(start: Location, end: Location) -> Result<Span, TokenStreamError> {
+    let ordered = match (start, end) {
+        (Location::LineColumn(start), Location::LineColumn(end)) => {
+            start <= end
+        },
+        (Location::CharacterIndex(start), Location::CharacterIndex(end)) => {
+            start <= end
+        },
+        _ => false,
+    };
+
+    if !ordered {
+        return Err(TokenStreamError::InvalidData(format!(
+            "invalid token span: {start:?} to {end:?}"
+        )));
+    }
+
+    Ok(Span::new(start, end))
+}
+
+fn read_len(reader: &mut &[u8]) -> Result<usize, TokenStreamError> {
+    let len = utils::read_varint(reader).map_err(invalid_data)?;
+
+    usize::try_from(len)
+        .map_err(|err| TokenStreamError::InvalidData(err.to_string()))
+}
+
+fn invalid_data(err: io::Error) -> TokenStreamError {
+    let message = match err.kind() {
+        io::ErrorKind::UnexpectedEof => {
+            "unexpected end of token stream".to_owned()
+        },
+        _ => err.to_string(),
+    };
+
+    TokenStreamError::InvalidData(message)
+}
@@ -3,6 +3,7 @@ use std::fmt::{self, Debug, Display};
 use crate::{
     source::{BoxPosition, BufferAndLength, Source, Span},
     tokenize::{TokenKind, Tokenizer},
+    NodeSeq,
 };
 
 #[cfg(feature = "string-interning")]
@@ -26,6 +27,7 @@ pub(crate) type TokenRef<'i> = Token<TokenStr<'i>>;
 /// `\[Alpha]bc` | [`Symbol`][TokenKind::Symbol]               | Letterlike named character
 /// `\[Rule]`    | [`LongName_Rule`][TokenKind::LongName_Rule] | Operator named character
 #[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token<I = TokenString, S = Span> {
     pub tok: TokenKind,
 
@@ -186,7 +188,7 @@ impl TokenString {
         }
     }
 
-    pub(crate) fn from_string(string: String) -> Self {
+    pub fn from_string(string: String) -> Self {
         TokenString {
             buf: string.into_bytes(),
         }
@@ -342,7 +344,7 @@ impl<'i> TokenRef<'i> {
                     // Spanning multiple lines is too complicated to care about
                     //
                     match src.kind() {
-                        SpanKind::CharacterSpan(_) => {
+                        SpanKind::CharIndexSpan(_) => {
                             //
                             // SourceConvention of "SourceCharacterIndex"
                             // so nothing to do
@@ -479,7 +481,9 @@ impl<'i> TokenRef<'i> {
 }
 
 impl<I: TokenInput, S> Token<I, S> {
-    pub(crate) fn into_owned_input(self) -> Token<TokenString, S> {
+    /// Copy the input of this token into an owned [`TokenString`], so that
+    /// the token no longer borrows the input it was tokenized from.
+    pub fn into_owned(self) -> Token<TokenString, S> {
         let Token { tok, src, input } = self;
 
         Token {
@@ -488,6 +492,34 @@ impl<I: TokenInput, S> Token<I, S> {
             input: input.into_owned(),
         }
     }
+
+    #[deprecated(note = "renamed to `into_owned()`")]
+    pub fn into_owned_input(self) -> Token<TokenString, S> {
+        self.into_owned()
+    }
+}
+
+impl<I: TokenInput, S> NodeSeq<Token<I, S>> {
+    /// Copy the input of each token into an owned [`TokenString`].
+    ///
+    /// ```
+    /// use wolfram_parser::{
+    ///     tokenize, tokenize::{Token, TokenString}, NodeSeq, ParseOptions,
+    /// };
+    ///
+    /// let tokens: NodeSeq<Token<TokenString>> = {
+    ///     let input = String::from("f[x]");
+    ///
+    ///     tokenize(&input, &ParseOptions::default()).into_owned()
+    /// };
+    ///
+    /// assert_eq!(tokens.0.len(), 4);
+    /// ```
+    pub fn into_owned(self) -> NodeSeq<Token<TokenString, S>> {
+        let NodeSeq(tokens) = self;
+
+        NodeSeq(tokens.into_iter().map(Token::into_owned).collect())
+    }
 }
 
 impl<'i> TokenRef<'i> {
@@ -261,6 +261,7 @@ macro_rules! token_kind {
         /// Complete enumeration of all tokens in Wolfram Language
         #[allow(non_camel_case_types)]
         #[derive(Debug, Copy, Clone, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         #[repr(u16)]
         pub enum TokenKind {
             $(
@@ -276,6 +277,26 @@ macro_rules! token_kind {
 
             pub const COUNT: usize = TokenKind::VARIANTS.len();
 
+            /// The name of this variant, e.g. `"Symbol"`.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(
+                        TokenKind::$variant => stringify!($variant),
+                    )*
+                }
+            }
+
+            /// Get the variant named `name`, the inverse of
+            /// [`TokenKind::name()`].
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(
+                        stringify!($variant) => Some(TokenKind::$variant),
+                    )*
+                    _ => None
+                }
+            }
+
             pub fn from_symbol(symbol: SymbolRef) -> Option<Self> {
                 match symbol {
                     $(
@@ -454,7 +475,7 @@ token_kind! {
     /** `/;` */ SlashSemi                    = 83,
     /** `/.` */ SlashDot                     = 84,
     /** `//` */ SlashSlash                   = 85,
-    /** `/;` */ SlashColon                   = 86,
+    /** `/:` */ SlashColon                   = 86,
     /** `/=` */ SlashEqual                   = 87,
     /// `/*`
     SlashStar                                = 88,
@@ -874,6 +895,15 @@ impl TokenKind {
         return bits;
     }
 
+    /// Get the variant whose [`bits()`][TokenKind::bits] are `bits`.
+    pub fn from_bits(bits: u16) -> Option<Self> {
+        // VARIANTS is ordered by id (see test_token_kinds_are_sorted()).
+        TokenKind::VARIANTS
+            .get(usize::from(bits & 0x1ff))
+            .copied()
+            .filter(|variant| variant.bits() == bits)
+    }
+
     // TODO: This is only used with TOKEN_COUNT -- remove this?
     pub(crate) const fn id(self) -> u16 {
         let value: u16 = self as u16;
@@ -928,6 +958,100 @@ impl TokenKind {
     pub const fn isEmpty(self) -> bool {
         return self.bits() & Group2::MASK == Group2::Empty as u16;
     }
+
+    /// The ASCII spelling of this token, for tokens that have exactly one,
+    /// e.g. `"+"` for [`TokenKind::Plus`].
+    ///
+    /// Tokens with a spelling can still be written with escapes or line
+    /// continuations, like `\:002b` or `:\<newline>=`.
+    #[rustfmt::skip]
+    pub(crate) const fn spelling(self) -> Option<&'static str> {
+        let spelling = match self {
+            TokenKind::Dot => ".",
+            TokenKind::Colon => ":",
+            TokenKind::OpenParen => "(",
+            TokenKind::CloseParen => ")",
+            TokenKind::OpenSquare => "[",
+            TokenKind::CloseSquare => "]",
+            TokenKind::Comma => ",",
+            TokenKind::OpenCurly => "{",
+            TokenKind::CloseCurly => "}",
+            TokenKind::Equal => "=",
+            TokenKind::Bang => "!",
+            TokenKind::Under => "_",
+            TokenKind::Less => "<",
+            TokenKind::Greater => ">",
+            TokenKind::Minus => "-",
+            TokenKind::Bar => "|",
+            TokenKind::Semi => ";",
+            TokenKind::Hash => "#",
+            TokenKind::Amp => "&",
+            TokenKind::Slash => "/",
+            TokenKind::At => "@",
+            TokenKind::Plus => "+",
+            TokenKind::Tilde => "~",
+            TokenKind::Star => "*",
+            TokenKind::Caret => "^",
+            TokenKind::SingleQuote => "'",
+            TokenKind::Percent => "%",
+            TokenKind::Question => "?",
+            TokenKind::DotDot => "..",
+            TokenKind::ColonColon => "::",
+            TokenKind::ColonEqual => ":=",
+            TokenKind::ColonGreater => ":>",
+            TokenKind::EqualEqual => "==",
+            TokenKind::UnderUnder => "__",
+            TokenKind::UnderDot => "_.",
+            TokenKind::LessBar => "<|",
+            TokenKind::LessLess => "<<",
+            TokenKind::LessGreater => "<>",
+            TokenKind::LessEqual => "<=",
+            TokenKind::GreaterGreater => ">>",
+            TokenKind::GreaterEqual => ">=",
+            TokenKind::MinusGreater => "->",
+            TokenKind::MinusMinus => "--",
+            TokenKind::MinusEqual => "-=",
+            TokenKind::BarBar => "||",
+            TokenKind::BarGreater => "|>",
+            TokenKind::SemiSemi => ";;",
+            TokenKind::AmpAmp => "&&",
+            TokenKind::SlashAt => "/@",
+            TokenKind::SlashSemi => "/;",
+            TokenKind::SlashDot => "/.",
+            TokenKind::SlashSlash => "//",
+            TokenKind::SlashColon => "/:",
+            TokenKind::SlashEqual => "/=",
+            TokenKind::SlashStar => "/*",
+            TokenKind::AtAt => "@@",
+            TokenKind::AtStar => "@*",
+            TokenKind::PlusPlus => "++",
+            TokenKind::PlusEqual => "+=",
+            TokenKind::TildeTilde => "~~",
+            TokenKind::StarEqual => "*=",
+            TokenKind::StarStar => "**",
+            TokenKind::CaretEqual => "^=",
+            TokenKind::HashHash => "##",
+            TokenKind::BangEqual => "!=",
+            TokenKind::BangBang => "!!",
+            TokenKind::QuestionQuestion => "??",
+            TokenKind::DotDotDot => "...",
+            TokenKind::EqualEqualEqual => "===",
+            TokenKind::EqualBangEqual => "=!=",
+            TokenKind::UnderUnderUnder => "___",
+            TokenKind::SlashSlashDot => "//.",
+            TokenKind::AtAtAt => "@@@",
+            TokenKind::LessMinusGreater => "<->",
+            TokenKind::SlashSlashAt => "//@",
+            TokenKind::CaretColonEqual => "^:=",
+            TokenKind::GreaterGreaterGreater => ">>>",
+            TokenKind::BarMinusGreater => "|->",
+            TokenKind::SlashSlashEqual => "//=",
+            TokenKind::ColonColonOpenSquare => "::[",
+            _ => return None,
+        };
+
+        Some(spelling)
+    }
 }
 
 
@@ -1000,6 +1124,30 @@ pub(crate) const fn GroupOpenerToCloser(token: TokenKind) -> Closer {
     }
 }
 
+/// Like [`GroupOpenerToCloser()`], but returns `None` if `token` does not
+/// open a group.
+pub(crate) const fn TryGroupOpenerToCloser(token: TokenKind) -> Option<Closer> {
+    match token {
+        TokenKind::OpenSquare
+        | TokenKind::OpenCurly
+        | TokenKind::OpenParen
+        | TokenKind::LessBar
+        | TokenKind::ColonColonOpenSquare
+        | TokenKind::LongName_LeftAngleBracket
+        | TokenKind::LongName_LeftAssociation
+        | TokenKind::LongName_LeftBracketingBar
+        | TokenKind::LongName_LeftCeiling
+        | TokenKind::LongName_LeftDoubleBracket
+        | TokenKind::LongName_LeftDoubleBracketingBar
+        | TokenKind::LongName_LeftFloor
+        | TokenKind::LongName_OpenCurlyDoubleQuote
+        | TokenKind::LongName_OpenCurlyQuote => {
+            Some(GroupOpenerToCloser(token))
+        },
+        _ => None,
+    }
+}
+
 #[rustfmt::skip]
 pub(crate) fn TokenToCloser(token: TokenKind) -> Closer {
     match token {
@@ -1020,6 +1168,29 @@ pub(crate) fn TokenToCloser(token: TokenKind) -> Closer {
     }
 }
 
+impl Closer {
+    /// Get the source text of this closer, e.g. `"]"`.
+    #[rustfmt::skip]
+    pub(crate) const fn as_str(self) -> &'static str {
+        match self {
+            Closer::BarGreater => "|>",
+            Closer::CloseCurly => "}",
+            Closer::CloseParen => ")",
+            Closer::CloseSquare => "]",
+            Closer::LongName_CloseCurlyDoubleQuote => "\\[CloseCurlyDoubleQuote]",
+            Closer::LongName_CloseCurlyQuote => "\\[CloseCurlyQuote]",
+            Closer::LongName_RightAngleBracket => "\\[RightAngleBracket]",
+            Closer::LongName_RightAssociation => "\\[RightAssociation]",
+            Closer::LongName_RightBracketingBar => "\\[RightBracketingBar]",
+            Closer::LongName_RightCeiling => "\\[RightCeiling]",
+            Closer::LongName_RightDoubleBracket => "\\[RightDoubleBracket]",
+            Closer::LongName_RightDoubleBracketingBar => "\\[RightDoubleBracketingBar]",
+            Closer::LongName_RightFloor => "\\[RightFloor]",
+            Closer::AssertFalse => panic!("unexpected Closer::AssertFalse"),
+        }
+    }
+}
+
 //======================================
 // Verify some TokenKind properties
 //======================================
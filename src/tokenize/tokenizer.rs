@@ -5,9 +5,11 @@ use std::{collections::HashSet, os::raw::c_int};
 use smallvec::SmallVec;
 
 use crate::{
+    directive::{self, Directive, InvalidSetting},
     feature,
-    issue::{CodeAction, FormatIssue, IssueTag, Severity, SyntaxIssue},
-    safe_expect,
+    issue::{
+        Annotation, CodeAction, FormatIssue, IssueTag, Severity, SyntaxIssue,
+    },
     read::{
         code_point::{
             CodePoint::{Char, *},
@@ -16,13 +18,13 @@ use crate::{
         Escape, InputMark, Reader, WLCharacter,
     },
     source::{
-        Buffer, BufferAndLength, Location, NextPolicy, SourceCharacter, Span,
-        INSIDE_SLOT, INSIDE_STRINGIFY_AS_FILE, INSIDE_STRINGIFY_AS_TAG,
+        Buffer, BufferAndLength, Location, NextPolicy, Source, SourceCharacter,
+        Span, INSIDE_SLOT, INSIDE_STRINGIFY_AS_FILE, INSIDE_STRINGIFY_AS_TAG,
         TOPLEVEL,
     },
     tokenize::{token_kind::Closer, Token, TokenKind, TokenRef},
     utils::{self, from_fn},
-    FirstLineBehavior, ParseOptions,
+    FirstLineBehavior, ParseOptions, ParseResult,
 };
 
 #[cfg(feature = "string-interning")]
@@ -43,10 +45,29 @@ pub(crate) struct Tokenizer<'i> {
 
     pub(crate) tracked: TrackedSourceLocations,
 
+    /// Directives found in comments, in source order.
+    pub(crate) directives: Vec<Directive>,
+
+    /// Tokens supplied by the caller, sorted by offset, that are returned
+    /// instead of lexing the input where they start.
+    replayed: Vec<ReplayedToken>,
+
     #[cfg(feature = "string-interning")]
     interner: LocalInterner,
 }
 
+/// A token that was lexed elsewhere, replayed by [`Tokenizer_nextToken()`]
+/// when the input cursor is at `offset`.
+///
+/// See [`parse_tokens()`][crate::parse::parse_tokens()].
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ReplayedToken {
+    pub(crate) offset: usize,
+    pub(crate) len: usize,
+    pub(crate) tok: TokenKind,
+    pub(crate) src: Span,
+}
+
 #[doc(hidden)]
 #[derive(Debug, Clone)]
 pub struct TrackedSourceLocations {
@@ -131,6 +152,22 @@ impl UnsafeCharacterEncoding {
     }
 }
 
+impl std::fmt::Display for UnsafeCharacterEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message = match self {
+            UnsafeCharacterEncoding::IncompleteUTF8Sequence => {
+                "incomplete UTF-8 sequence"
+            },
+            UnsafeCharacterEncoding::StraySurrogate => "stray surrogate",
+            UnsafeCharacterEncoding::BOM => "byte order mark",
+        };
+
+        write!(f, "{message}")
+    }
+}
+
+impl std::error::Error for UnsafeCharacterEncoding {}
+
 impl<'i> std::ops::Deref for Tokenizer<'i> {
     type Target = Reader<'i>;
 
@@ -147,13 +184,42 @@ impl<'i> std::ops::DerefMut for Tokenizer<'i> {
 
 impl<'i> Tokenizer<'i> {
     pub(crate) fn new(input: &'i [u8], opts: &ParseOptions) -> Self {
+        Tokenizer::replaying(input, opts, Vec::new())
+    }
+
+    /// Construct a tokenizer that returns the tokens in `replayed` instead of
+    /// lexing the input they cover.
+    ///
+    /// Input that does not start at the offset of a replayed token, like the
+    /// input that the parser lexes with a different policy, is lexed as usual.
+    pub(crate) fn replaying(
+        input: &'i [u8],
+        opts: &ParseOptions,
+        replayed: Vec<ReplayedToken>,
+    ) -> Self {
+        debug_assert!(replayed
+            .windows(2)
+            .all(|pair| pair[0].offset + pair[0].len <= pair[1].offset));
+
         let ParseOptions {
             first_line_behavior,
             src_convention: _,
             encoding_mode: _,
             tab_width: _,
+            tab_width_auto: _,
             check_issues: _,
             compute_oob: _,
+            implicit_times: _,
+            toplevel_newlines: _,
+            grammar_profile: _,
+            denied_issues: _,
+            max_line_length: _,
+            check_indentation: _,
+            reparse: _,
+            recovery: _,
+            deduplicate_issues: _,
+            notations: _,
+            debug_sink: _,
             quirk_settings: _,
         } = *opts;
 
@@ -171,6 +237,10 @@ impl<'i> Tokenizer<'i> {
                 embedded_tabs: HashSet::new(),
             },
 
+            directives: Vec::new(),
+
+            replayed,
+
             #[cfg(feature = "string-interning")]
             interner: LocalInterner::new(),
         };
@@ -180,6 +250,33 @@ impl<'i> Tokenizer<'i> {
         tokenizer
     }
 
+    /// Consume this tokenizer, moving the issues, directives, and tracked
+    /// source locations it collected into a [`ParseResult`] for `syntax`.
+    pub(crate) fn into_parse_result<N>(self, syntax: N) -> ParseResult<N> {
+        let Tokenizer {
+            reader,
+            tracked,
+            directives,
+            ..
+        } = self;
+
+        let Reader {
+            fatal_issues,
+            non_fatal_issues,
+            unsafe_character_encoding_flag,
+            ..
+        } = reader;
+
+        ParseResult {
+            syntax,
+            unsafe_character_encoding: unsafe_character_encoding_flag,
+            fatal_issues,
+            non_fatal_issues,
+            directives,
+            tracked,
+        }
+    }
+
     fn handle_first_line(&mut self) {
         match self.first_line_behavior {
             FirstLineBehavior::NotScript => {
@@ -363,6 +460,52 @@ impl<'i> Tokenizer<'i> {
         Tokenizer_nextToken(self, crate::source::TOPLEVEL)
     }
 
+    /// Returns the replayed token that starts at the current offset, if any,
+    /// and advances past it.
+    fn next_replayed_token(
+        &mut self,
+        policy: NextPolicy,
+    ) -> Option<TokenRef<'i>> {
+        if self.replayed.is_empty() {
+            return None;
+        }
+
+        let index = self
+            .replayed
+            .binary_search_by_key(&self.offset, |token| token.offset)
+            .ok()?;
+
+        let ReplayedToken {
+            offset,
+            len,
+            mut tok,
+            src,
+        } = self.replayed[index];
+
+        // Whether a newline is significant depends on the enclosing groups,
+        // which only the parser knows.
+        if matches!(
+            tok,
+            TokenKind::ToplevelNewline | TokenKind::InternalNewline
+        ) {
+            tok = TokenKind::newline_with_policy(policy);
+        }
+
+        let buf =
+            BufferAndLength::from_buffer_with_len(self.buffer_at(offset), len);
+
+        let token = Token::new2(tok, buf, src);
+
+        self.offset = offset + len;
+        self.SrcLoc = src.end();
+
+        if tok == TokenKind::Comment {
+            self.addCommentDirectives(token);
+        }
+
+        Some(token)
+    }
+
     //==================================
     // Create tokens
     //==================================
@@ -467,6 +610,47 @@ impl<'i> Tokenizer<'i> {
     fn addEmbeddedTab(&mut self, loc: Location) {
         self.tracked.embedded_tabs.insert(loc);
     }
+
+    //==================================
+    // Directives
+    //==================================
+
+    fn addCommentDirectives(&mut self, comment: TokenRef<'i>) {
+        //
+        // The same comment may be tokenized more than once, so skip comments
+        // that start before the end of the last directive that was found.
+        //
+        if let Some(last) = self.directives.last() {
+            if comment.src.start() < last.src.end() {
+                return;
+            }
+        }
+
+        let Ok(text) = std::str::from_utf8(comment.input.buf.as_bytes()) else {
+            return;
+        };
+
+        let invalid =
+            directive::parse_comment(text, comment.src, &mut self.directives);
+
+        if !self.check_issues {
+            return;
+        }
+
+        for InvalidSetting { entry } in invalid {
+            let I = SyntaxIssue(
+                IssueTag::InvalidDirective,
+                format!("Invalid ``wolfram-parse`` setting: ``{entry}``."),
+                Severity::Warning,
+                comment.src,
+                0.95,
+                vec![],
+                vec![],
+            );
+
+            self.addIssue(I);
+        }
+    }
 }
 
 impl TrackedSourceLocations {
@@ -571,6 +755,10 @@ fn Tokenizer_nextToken<'i>(
     session: &mut Tokenizer<'i>,
     policy: NextPolicy,
 ) -> TokenRef<'i> {
+    if let Some(token) = session.next_replayed_token(policy) {
+        return token;
+    }
+
     let token_start = &TokenStart {
         buf: session.buffer(),
         loc: session.SrcLoc,
@@ -1225,7 +1413,12 @@ fn Tokenizer_handleComment<'i>(
                     depth = depth - 1;
 
                     if depth == 0 {
-                        return session.token(TokenKind::Comment, token_start);
+                        let comment =
+                            session.token(TokenKind::Comment, token_start);
+
+                        session.addCommentDirectives(comment);
+
+                        return comment;
                     }
 
                     c = session.next_source_char(policy);
@@ -1649,7 +1842,7 @@ fn Tokenizer_handleString<'i>(
         //
 
         if terminated {
-            session.offset = safe_expect!(quot_offset, "quot_offset should be set when terminated=true") + 1;
+            session.offset = quot_offset.expect("quot_offset should be set when terminated=true") + 1;
 
             return session.token(TokenKind::String, token_start);
         } else {
@@ -2071,7 +2264,7 @@ fn Tokenizer_handleNumber<'i>(
                     caret_1_mark = Some(session.mark());
 
                     assert!(utils::ifASCIIWLCharacter(
-                        safe_expect!(caret1Buf, "caret1Buf should be set")[0],
+                        caret1Buf.expect("caret1Buf should be set")[0],
                         b'^'
                     ));
                 } else if c.to_point() == '*' {
@@ -2136,7 +2329,7 @@ fn Tokenizer_handleNumber<'i>(
 
             Tokenizer_nextWLCharacter(session, token_start, policy);
 
-            if nonZeroStartBuf == safe_expect!(caret1Buf, "caret1Buf should be set in base parsing") {
+            if nonZeroStartBuf == caret1Buf.expect("caret1Buf should be set in base parsing") {
                 //
                 // Something like  0^^2
                 //
@@ -2146,7 +2339,7 @@ fn Tokenizer_handleNumber<'i>(
                 // PRE_COMMIT: Compute string length differently
                 let baseStrLen = BufferAndLength::between(
                     nonZeroStartBuf,
-                    safe_expect!(caret1Buf, "caret1Buf should be set in base parsing"),
+                    caret1Buf.expect("caret1Buf should be set in base parsing"),
                 )
                 .buf
                 .len();
@@ -2484,6 +2677,8 @@ fn Tokenizer_handleNumber<'i>(
                                 // do not warn about 1.2``+3 for now
                                 //
                             } else {
+                                let src = Span::at(sign_mark.expect("sign_mark should be set when sign found").src_loc);
+
                                 let I = SyntaxIssue(
                                     IssueTag::UnexpectedSign,
                                     format!(
@@ -2491,10 +2686,14 @@ fn Tokenizer_handleNumber<'i>(
                                         signBuf.as_str()
                                     ),
                                     Severity::Warning,
-                                    Span::at(safe_expect!(sign_mark, "sign_mark should be set when sign found").src_loc),
+                                    src,
                                     0.95,
                                     vec![],
-                                    vec!["This is usually unintentional.".into()],
+                                    vec![Annotation::explanation(
+                                        "This is usually unintentional."
+                                            .to_owned(),
+                                        Source::Span(src),
+                                    )],
                                 );
 
                                 session.addIssue(I);
@@ -2514,6 +2713,8 @@ fn Tokenizer_handleNumber<'i>(
                                 // do not warn about 1.2``+.3 for now
                                 //
                             } else {
+                                let src = Span::at(sign_mark.expect("sign_mark should be set when sign found").src_loc);
+
                                 let I = SyntaxIssue(
                                     IssueTag::UnexpectedSign,
                                     format!(
@@ -2521,10 +2722,14 @@ fn Tokenizer_handleNumber<'i>(
                                         signBuf.as_str()
                                     ),
                                     Severity::Warning,
-                                    Span::at(safe_expect!(sign_mark, "sign_mark should be set when sign found").src_loc),
+                                    src,
                                     0.95,
                                     vec![],
-                                    vec!["This is usually unintentional.".into()],
+                                    vec![Annotation::explanation(
+                                        "This is usually unintentional."
+                                            .to_owned(),
+                                        Source::Span(src),
+                                    )],
                                 );
 
                                 session.addIssue(I);
@@ -2551,7 +2756,7 @@ fn Tokenizer_handleNumber<'i>(
                         //
                         // Must now do surgery and back up
                         //
-                        Tokenizer_backupAndWarn(session, safe_expect!(sign_mark, "sign_mark should be set when sign found"));
+                        Tokenizer_backupAndWarn(session, sign_mark.expect("sign_mark should be set when sign found"));
 
                         //
                         // Success!
@@ -2739,7 +2944,7 @@ fn Tokenizer_handleNumber<'i>(
 
                             Tokenizer_backupAndWarn(
                                 session,
-                                safe_expect!(sign_mark, "sign_mark should be set when sign found"),
+                                sign_mark.expect("sign_mark should be set when sign found"),
                             );
 
                             //
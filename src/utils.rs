@@ -408,6 +408,91 @@ pub(crate) fn is_sorted_by<T, B: Ord, F: Fn(&T) -> B>(
     slice.windows(2).all(|elem| by(&elem[0]) <= by(&elem[1]))
 }
 
+/// Apply `func` to every element of `items` using scoped worker threads,
+/// returning the results in the same order as `items`.
+pub(crate) fn par_map<T, R, F>(items: &[T], func: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(items.len());
+
+    if threads <= 1 {
+        return items.iter().map(func).collect();
+    }
+
+    let chunk_size = (items.len() + threads - 1) / threads;
+
+    let func = &func;
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || chunk.iter().map(func).collect::<Vec<R>>())
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| match handle.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    })
+}
+
+/// Write `value` as an unsigned LEB128 variable-length integer.
+pub(crate) fn write_varint<W: std::io::Write>(
+    out: &mut W,
+    mut value: u64,
+) -> std::io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            return out.write_all(&[byte]);
+        }
+
+        out.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Read an unsigned LEB128 variable-length integer written by
+/// [`write_varint()`].
+pub(crate) fn read_varint<R: std::io::Read>(
+    reader: &mut R,
+) -> std::io::Result<u64> {
+    let mut value: u64 = 0;
+    let mut shift = 0;
+
+    loop {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        let byte = byte[0];
+
+        if shift >= 64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "varint is too long",
+            ));
+        }
+
+        value |= u64::from(byte & 0x7f) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
 /// Alternative to [`std::array::from_fn`] that works in `const` contexts.
 ///
 /// See also: <https://doc.rust-lang.org/stable/std/mem/union.MaybeUninit.html#initializing-an-array-element-by-element>
@@ -755,28 +840,3 @@ fn test_intersection() {
     assert_eq!(intersection((3, 4), (1, 2)), None);
     assert_eq!(intersection((3, 4), (1, 3)), Some((3, 3)));
 }
-
-//======================================
-// Copy to clipboard
-//======================================
-
-#[cfg(test)]
-pub(crate) fn copy_to_clipboard(content: &str) {
-    if cfg!(target_os = "macos") {
-        use std::io::Write;
-        use std::process::{Command, Stdio};
-
-        let mut child = Command::new("pbcopy")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .spawn()
-            .unwrap();
-
-        let child_stdin = child.stdin.as_mut().unwrap();
-        child_stdin.write_all(content.as_bytes()).unwrap();
-
-        let output = child.wait_with_output().unwrap();
-
-        eprintln!("copied to clipboard with result: {output:?}");
-    }
-}
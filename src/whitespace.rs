@@ -0,0 +1,222 @@
+//! Normalize the whitespace of Wolfram Language source.
+//!
+//! [`normalize_whitespace()`] computes the [`TextEdit`]s that:
+//!
+//! * replace tabs with spaces, up to the same tab stops used to compute
+//!   source spans (see [`ParseOptions::tab_width()`])
+//! * remove trailing whitespace from every line
+//! * add a final newline, if the input does not end with one
+//!
+//! Only whitespace between tokens and inside comments is changed. Tabs and
+//! trailing whitespace inside strings are part of the value of the string, so
+//! are left as-is.
+//!
+//! ```
+//! use wolfram_parser::{edit, whitespace, ParseOptions};
+//!
+//! let input = "f[\n\tx, \"a\tb\"\t\n]";
+//!
+//! let edits = whitespace::normalize_whitespace(input, &ParseOptions::default());
+//!
+//! assert_eq!(edit::apply_edits(input, &edits), "f[\n    x, \"a\tb\"\n]\n");
+//! ```
+
+use std::num::NonZeroU32;
+
+use crate::{
+    edit::TextEdit,
+    source::{LineColumn, Location, Span},
+    tokenize::{Token, TokenInput, TokenKind, TokenStr},
+    NodeSeq, ParseOptions,
+};
+
+/// Compute the edits that normalize the whitespace of `input`.
+///
+/// The edits are sorted and do not overlap, so they can be applied with
+/// [`apply_edits()`][crate::edit::apply_edits]. Spans of the edits use
+/// [`SourceConvention::LineColumn`][crate::SourceConvention::LineColumn],
+/// regardless of the source convention of `opts`.
+pub fn normalize_whitespace(input: &str, opts: &ParseOptions) -> Vec<TextEdit> {
+    let tab_width = opts.tab_width_for(input.as_bytes());
+
+    let opts = ParseOptions::builtin_default().tab_width(tab_width);
+
+    let NodeSeq(tokens) = crate::tokenize(input, &opts);
+
+    let mut normalizer = Normalizer {
+        input,
+        tab_width,
+        offset: 0,
+        loc: Location::start(),
+        prev: None,
+        run: None,
+        edits: Vec::new(),
+    };
+
+    for token in &tokens {
+        normalizer.token(token);
+    }
+
+    normalizer.end_run(RunEnd::Line);
+
+    if !input.is_empty() && !input.ends_with(['\n', '\r']) {
+        normalizer.edits.push(TextEdit {
+            range: input.len()..input.len(),
+            span: Span::new(normalizer.loc, normalizer.loc),
+            new_text: "\n".to_owned(),
+        });
+    }
+
+    normalizer.edits
+}
+
+//======================================
+// Helpers
+//======================================
+
+struct Normalizer<'i> {
+    input: &'i str,
+    tab_width: u32,
+
+    /// Byte offset of the next character.
+    offset: usize,
+    /// Location of the next character.
+    loc: Location,
+    prev: Option<char>,
+
+    /// Byte offset and location of the start of the current run of spaces and
+    /// tabs.
+    run: Option<(usize, Location)>,
+
+    edits: Vec<TextEdit>,
+}
+
+enum RunEnd {
+    /// The run is followed by more text on the same line.
+    Text,
+    /// The run is followed by a newline or the end of the input.
+    Line,
+}
+
+impl<'i> Normalizer<'i> {
+    fn token(&mut self, token: &Token<TokenStr<'i>>) {
+        let bytes = token.input.as_bytes();
+
+        let start = bytes.as_ptr() as usize - self.input.as_ptr() as usize;
+        let text = &self.input[start..start + bytes.len()];
+
+        if start != self.offset {
+            // Skipped input, like a line continuation, ends the current run.
+            self.end_run(RunEnd::Text);
+            self.offset = start;
+        }
+
+        // Resynchronize with the tokenizer, so that locations are always
+        // computed the same way as source spans.
+        self.loc = token.src.start();
+
+        if !is_editable(token.tok, text) {
+            self.end_run(RunEnd::Text);
+
+            self.offset += text.len();
+            self.loc = token.src.end();
+            self.prev = text.chars().last();
+
+            return;
+        }
+
+        for c in text.chars() {
+            match c {
+                ' ' | '\t' => {
+                    if self.run.is_none() {
+                        self.run = Some((self.offset, self.loc));
+                    }
+                },
+                '\n' | '\r' => self.end_run(RunEnd::Line),
+                _ => self.end_run(RunEnd::Text),
+            }
+
+            self.advance(c);
+        }
+    }
+
+    fn end_run(&mut self, end: RunEnd) {
+        let Some((start, start_loc)) = self.run.take() else {
+            return;
+        };
+
+        let range = start..self.offset;
+        let span = Span::new(start_loc, self.loc);
+
+        match end {
+            RunEnd::Line => self.edits.push(TextEdit {
+                range,
+                span,
+                new_text: String::new(),
+            }),
+            RunEnd::Text => {
+                if !self.input[range.clone()].contains('\t') {
+                    return;
+                }
+
+                let width = column(self.loc) - column(start_loc);
+
+                self.edits.push(TextEdit {
+                    range,
+                    span,
+                    new_text: " ".repeat(width as usize),
+                })
+            },
+        }
+    }
+
+    /// Advance past `c`, in the same way as the tokenizer.
+    fn advance(&mut self, c: char) {
+        self.offset += c.len_utf8();
+
+        let Location::LineColumn(LineColumn(line, column)) = &mut self.loc
+        else {
+            unreachable!("normalize_whitespace: expected line-column location")
+        };
+
+        match c {
+            // `\r\n` is a single newline.
+            '\n' if self.prev == Some('\r') => (),
+            '\n' | '\r' => {
+                *line = line.saturating_add(1);
+                *column = NonZeroU32::MIN;
+            },
+            '\t' => {
+                let tab_stop =
+                    self.tab_width * ((column.get() - 1) / self.tab_width) + 1;
+
+                *column = NonZeroU32::new(tab_stop + self.tab_width).unwrap();
+            },
+            _ => *column = column.saturating_add(1),
+        }
+
+        self.prev = Some(c);
+    }
+}
+
+/// Whether whitespace inside a token of kind `tok` can be changed without
+/// changing the meaning of the input.
+fn is_editable(tok: TokenKind, text: &str) -> bool {
+    match tok {
+        TokenKind::Comment
+        | TokenKind::InternalNewline
+        | TokenKind::ToplevelNewline => true,
+        // Exclude e.g. non-ASCII whitespace characters.
+        TokenKind::Whitespace => text.bytes().all(|b| b == b' ' || b == b'\t'),
+        _ => false,
+    }
+}
+
+fn column(loc: Location) -> u32 {
+    match loc {
+        Location::LineColumn(LineColumn(_, column)) => column.get(),
+        Location::CharacterIndex(_) => {
+            unreachable!("normalize_whitespace: expected line-column location")
+        },
+    }
+}
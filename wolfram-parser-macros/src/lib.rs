@@ -0,0 +1,300 @@
+//! Compile-time checked Wolfram Language expressions.
+//!
+//! The [`wl!`] macro parses Wolfram Language input when the Rust code using
+//! it is compiled, and expands to code that builds the equivalent
+//! [`Expr`](wolfram_expr::Expr). Rust code that generates Wolfram Language
+//! code gets its syntax checked by the compiler, instead of failing when the
+//! generated code is evaluated:
+//!
+//! ```
+//! use wolfram_expr::{Expr, Symbol};
+//! use wolfram_parser_macros::wl;
+//!
+//! let n = 3_i64;
+//!
+//! let expr = wl!(f[#n, 2 + 2]);
+//!
+//! let plus = Expr::normal(Symbol::new("System`Plus"), vec![
+//!     Expr::from(2_i64),
+//!     Expr::from(2_i64),
+//! ]);
+//!
+//! assert_eq!(
+//!     expr,
+//!     Expr::normal(Symbol::new("Global`f"), vec![Expr::from(3_i64), plus])
+//! );
+//! ```
+//!
+//! Input that is not valid Wolfram Language is a compile error:
+//!
+//! ```compile_fail
+//! use wolfram_parser_macros::wl;
+//!
+//! let expr = wl!(f[x] +);
+//! ```
+//!
+//! The generated code refers to the `wolfram_expr` crate, which must be a
+//! dependency of the crate using [`wl!`].
+
+use std::fmt::Write;
+
+use proc_macro2::{Delimiter, Ident, Spacing, Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::LitStr;
+use wolfram_expr::{Expr, ExprKind};
+use wolfram_parser::{parse_ast, ParseOptions};
+
+#[cfg(test)]
+mod tests;
+
+/// Context of the placeholder symbols that splices are replaced with while
+/// parsing.
+const SPLICE_CONTEXT: &str = "WolframParserMacros`";
+
+/// Build an [`Expr`](wolfram_expr::Expr) from Wolfram Language input that is
+/// parsed at compile time.
+///
+/// The input can be written directly, like `wl!(f[x_] := x + 1)`, or as a
+/// string literal, like `wl!("f'[x]")`. The string form accepts any input,
+/// including syntax that Rust can't tokenize, like `f'[x]` or ``Pkg`f``.
+///
+/// Symbols are put in a context as described in
+/// [`Ast::to_expr()`](wolfram_parser::ast::Ast::to_expr): `Plus` is
+/// ``System`Plus``, and `f` is ``Global`f``.
+///
+/// # Splices
+///
+/// In the direct form, `#name` is replaced by the value of the Rust variable
+/// `name`, which can be any value that implements
+/// `Into<`[`Expr`](wolfram_expr::Expr)`>`, like another `Expr`, a
+/// [`Symbol`](wolfram_expr::Symbol), or an `i64`:
+///
+/// ```
+/// use wolfram_expr::{Expr, Symbol};
+/// use wolfram_parser_macros::wl;
+///
+/// let body = wl!(x^2);
+/// let name = Symbol::new("Global`square");
+///
+/// let def = wl!(#name[x_] := #body);
+///
+/// assert_eq!(def, wl!(square[x_] := x^2));
+/// ```
+///
+/// Because `#` introduces a splice, named slots like `#name` must be written
+/// in the string form. Numbered slots, like `#1` or `##`, can be written
+/// directly.
+///
+/// # Limitations
+///
+/// The direct form is limited to input that Rust can tokenize the same way:
+///
+/// * `//` starts a Rust comment, so postfix application like `x // f` must
+///   be written in the string form, as must context marks like ``Pkg`f``
+/// * Rust literals other than strings and decimal numbers, like `1e3` or
+///   `'a'`, are a compile error
+/// * spaces between tokens are not preserved, so input whose meaning depends
+///   on them, like `x_ .`, should be written in the string form
+#[proc_macro]
+pub fn wl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let (source, splices) = wl_source(input)?;
+
+    let result = parse_ast(&source, &ParseOptions::default());
+
+    let error = |msg: String| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("invalid Wolfram Language input: {msg}"),
+        )
+    };
+
+    if let Some(issue) = result.fatal_issues.first() {
+        return Err(error(issue.msg.clone()));
+    }
+
+    let expr = result
+        .syntax
+        .to_expr()
+        .map_err(|err| error(err.to_string()))?;
+
+    Ok(expr_tokens(&expr, &splices))
+}
+
+//======================================
+// Input
+//======================================
+
+/// The Wolfram Language source of the macro input, and the splices it
+/// contains.
+///
+/// Each splice `#name` is replaced by a placeholder symbol in
+/// [`SPLICE_CONTEXT`], named after its index in the returned splices.
+fn wl_source(input: TokenStream) -> syn::Result<(String, Vec<Ident>)> {
+    if let Ok(literal) = syn::parse2::<LitStr>(input.clone()) {
+        return Ok((literal.value(), Vec::new()));
+    }
+
+    let mut source = String::new();
+    let mut splices = Vec::new();
+
+    write_tokens(input, &mut source, &mut splices)?;
+
+    Ok((source, splices))
+}
+
+fn write_tokens(
+    input: TokenStream,
+    source: &mut String,
+    splices: &mut Vec<Ident>,
+) -> syn::Result<()> {
+    let mut tokens = input.into_iter().peekable();
+
+    // Whether a space separates the previous token from the next one.
+    let mut space = false;
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+
+                // `f[x]` and `f[[1]]` are written without spaces.
+                if space && group.delimiter() != Delimiter::Bracket {
+                    source.push(' ');
+                }
+
+                source.push_str(open);
+                write_tokens(group.stream(), source, splices)?;
+                source.push_str(close);
+
+                space = true;
+            },
+            TokenTree::Punct(punct)
+                if punct.as_char() == '#'
+                    && matches!(tokens.peek(), Some(TokenTree::Ident(_))) =>
+            {
+                let Some(TokenTree::Ident(name)) = tokens.next() else {
+                    unreachable!()
+                };
+
+                if space {
+                    source.push(' ');
+                }
+
+                write!(source, "{SPLICE_CONTEXT}splice{}", splices.len())
+                    .unwrap();
+                splices.push(name);
+
+                space = true;
+            },
+            TokenTree::Punct(punct) => {
+                if space {
+                    source.push(' ');
+                }
+
+                source.push(punct.as_char());
+
+                // Operators like `:=` and slots like `#1` are written
+                // without spaces.
+                space =
+                    punct.spacing() == Spacing::Alone && punct.as_char() != '#';
+            },
+            TokenTree::Ident(ident) => {
+                if space {
+                    source.push(' ');
+                }
+
+                write!(source, "{ident}").unwrap();
+
+                space = true;
+            },
+            TokenTree::Literal(literal) => {
+                let repr = literal.to_string();
+
+                let is_supported = repr.starts_with('"')
+                    || repr.chars().all(|c| c.is_ascii_digit() || c == '.');
+
+                if !is_supported {
+                    return Err(syn::Error::new(
+                        literal.span(),
+                        "unsupported literal; write the input as a string, \
+                         like `wl!(\"...\")`",
+                    ));
+                }
+
+                if space {
+                    source.push(' ');
+                }
+
+                source.push_str(&repr);
+
+                space = true;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+//======================================
+// Output
+//======================================
+
+/// Rust code that builds `expr`, with placeholder symbols replaced by the
+/// corresponding `splices`.
+fn expr_tokens(expr: &Expr, splices: &[Ident]) -> TokenStream {
+    match expr.kind() {
+        ExprKind::Integer(int) => {
+            quote! { ::wolfram_expr::Expr::from(#int) }
+        },
+        ExprKind::Real(real) => {
+            let real = real.into_inner();
+            quote! { ::wolfram_expr::Expr::real(#real) }
+        },
+        ExprKind::String(string) => {
+            quote! { ::wolfram_expr::Expr::string(#string) }
+        },
+        ExprKind::Symbol(symbol) => match splice_index(symbol.as_str()) {
+            Some(index) => {
+                let name = &splices[index];
+                quote! { ::wolfram_expr::Expr::from(#name) }
+            },
+            None => {
+                let name = symbol.as_str();
+                quote! {
+                    ::wolfram_expr::Expr::from(::wolfram_expr::Symbol::new(#name))
+                }
+            },
+        },
+        ExprKind::Normal(normal) => {
+            let head = expr_tokens(normal.head(), splices);
+
+            let elements = normal
+                .elements()
+                .iter()
+                .map(|element| expr_tokens(element, splices));
+
+            quote! {
+                ::wolfram_expr::Expr::normal(#head, ::std::vec![#(#elements),*])
+            }
+        },
+    }
+}
+
+/// The index of the splice that the symbol `name` is a placeholder for.
+fn splice_index(name: &str) -> Option<usize> {
+    name.strip_prefix(SPLICE_CONTEXT)?
+        .strip_prefix("splice")?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,62 @@
+use pretty_assertions::assert_eq;
+
+use proc_macro2::TokenStream;
+
+use crate::{expand, wl_source};
+
+fn tokens(input: &str) -> TokenStream {
+    input.parse().expect("valid Rust tokens")
+}
+
+/// The Wolfram Language source of `input`, and the names of its splices.
+fn source(input: &str) -> (String, Vec<String>) {
+    let (source, splices) = wl_source(tokens(input)).unwrap();
+
+    (source, splices.iter().map(ToString::to_string).collect())
+}
+
+fn error(input: &str) -> String {
+    expand(tokens(input)).unwrap_err().to_string()
+}
+
+#[test]
+fn test_wl_source() {
+    assert_eq!(source("f[x_, 2 + 2]"), ("f[x_ , 2 + 2]".to_owned(), vec![]));
+
+    assert_eq!(
+        source("f[x_] := x; g[[1]]"),
+        ("f[x_] := x ; g[[1]]".to_owned(), vec![])
+    );
+
+    assert_eq!(
+        source("{#1 + ##2 &, -> }"),
+        ("{#1 + ##2 & , ->}".to_owned(), vec![])
+    );
+
+    assert_eq!(
+        source("#f[#x] + #y"),
+        (
+            "WolframParserMacros`splice0[WolframParserMacros`splice1] + \
+             WolframParserMacros`splice2"
+                .to_owned(),
+            vec!["f".to_owned(), "x".to_owned(), "y".to_owned()]
+        )
+    );
+
+    // The string form is used as written.
+    assert_eq!(
+        source(r#""f'[x] (* c *) #name &""#),
+        ("f'[x] (* c *) #name &".to_owned(), vec![])
+    );
+}
+
+#[test]
+fn test_wl_errors() {
+    assert_eq!(
+        error("1e3 + x"),
+        "unsupported literal; write the input as a string, like `wl!(\"...\")`"
+    );
+
+    assert!(error("f[x] +").starts_with("invalid Wolfram Language input: "));
+    assert!(error("\"f[x\"").starts_with("invalid Wolfram Language input: "));
+}
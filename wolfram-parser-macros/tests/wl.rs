@@ -0,0 +1,48 @@
+use pretty_assertions::assert_eq;
+
+use wolfram_expr::{Expr, Symbol};
+use wolfram_parser::{parse_ast, ParseOptions};
+use wolfram_parser_macros::wl;
+
+/// The expression read from `input` at run time.
+fn parse(input: &str) -> Expr {
+    parse_ast(input, &ParseOptions::default())
+        .syntax
+        .to_expr()
+        .unwrap()
+}
+
+#[test]
+fn test_wl() {
+    assert_eq!(wl!(f[x_] := x + 1), parse("f[x_] := x + 1"));
+    assert_eq!(wl!(a; b; {1, 2.5, "s"}), parse(r#"a; b; {1, 2.5, "s"}"#));
+    assert_eq!(wl!(Length[m[[1, 2]]]), parse("Length[m[[1, 2]]]"));
+    assert_eq!(wl!(x /. a -> b), parse("x /. a -> b"));
+    assert_eq!(wl!(#1 + ##2 &), parse("#1 + ##2 &"));
+    assert_eq!(wl!(f @@ {a, b}), parse("f @@ {a, b}"));
+
+    assert_eq!(wl!("f'[x] (* comment *)"), parse("f'[x]"));
+    assert_eq!(wl!("#name &"), parse("#name &"));
+    assert_eq!(wl!("Pkg`f[x] // N"), parse("Pkg`f[x] // N"));
+}
+
+#[test]
+fn test_wl_splices() {
+    let x = 3_i64;
+    let f = Symbol::new("Global`g");
+    let body = wl!(Sin[y]);
+
+    assert_eq!(
+        wl!(#f[y_] := #body + #x),
+        Expr::normal(
+            Symbol::new("System`SetDelayed"),
+            vec![
+                Expr::normal(Symbol::new("Global`g"), vec![parse("y_")]),
+                Expr::normal(
+                    Symbol::new("System`Plus"),
+                    vec![parse("Sin[y]"), Expr::from(3_i64),]
+                ),
+            ]
+        )
+    );
+}